@@ -1,10 +1,75 @@
 //! EVM gas calculation utilities.
 
+/// `const` Option `?`.
+macro_rules! tri {
+    ($e:expr) => {
+        match $e {
+            Some(v) => v,
+            None => return None,
+        }
+    };
+}
+
 mod calc;
 mod constants;
+mod schedule;
 
 pub use calc::*;
 pub use constants::*;
+pub use schedule::{GasSchedule, SharedGasSchedule};
+
+/// Gas spent by an execution, before the refund is subtracted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpentGas(pub u64);
+
+impl SpentGas {
+    /// Returns the spent gas.
+    #[inline]
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the spent gas after subtracting the final refund.
+    ///
+    /// The final refund is capped to a fraction of the spent gas, so it can't exceed it.
+    #[inline]
+    pub fn net_of(self, refund: RefundGas) -> u64 {
+        let refund = refund.final_value();
+        debug_assert!(
+            refund <= self.0,
+            "refund {refund} exceeds spent gas {}",
+            self.0
+        );
+        self.0.saturating_sub(refund)
+    }
+}
+
+/// Refund counter of an execution.
+///
+/// The counter of a frame can be negative, e.g. when a slot cleared by a parent frame is set
+/// again, but the final refund of a transaction never is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RefundGas(pub i64);
+
+impl RefundGas {
+    /// Returns the refund counter.
+    #[inline]
+    pub const fn get(self) -> i64 {
+        self.0
+    }
+
+    /// Returns the refund counter as the final refund of a transaction.
+    ///
+    /// A negative counter is a bug in the refund accounting, it is clamped to zero in release
+    /// builds.
+    #[inline]
+    pub fn final_value(self) -> u64 {
+        debug_assert!(self.0 >= 0, "negative final refund {}", self.0);
+        self.0.max(0) as u64
+    }
+}
 
 /// Represents the state of gas during execution.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
@@ -66,6 +131,31 @@ impl Gas {
         self.limit - self.remaining
     }
 
+    /// Returns the total amount of gas spent, see [`SpentGas`].
+    #[inline]
+    pub const fn spent_gas(&self) -> SpentGas {
+        SpentGas(self.spent())
+    }
+
+    /// Returns the refund counter, see [`RefundGas`].
+    #[inline]
+    pub const fn refund_gas(&self) -> RefundGas {
+        RefundGas(self.refunded)
+    }
+
+    /// Returns the gas used by a finished transaction, the spent gas minus the final refund.
+    #[inline]
+    pub fn used(&self) -> u64 {
+        self.spent_gas().net_of(self.refund_gas())
+    }
+
+    /// Returns the gas reimbursed to the payer of a finished transaction, the remaining gas
+    /// plus the final refund.
+    #[inline]
+    pub fn reimbursed(&self) -> u64 {
+        self.remaining + self.refund_gas().final_value()
+    }
+
     /// Returns the amount of gas remaining.
     #[inline]
     pub const fn remaining(&self) -> u64 {
@@ -78,9 +168,16 @@ impl Gas {
     }
 
     /// Erases a gas cost from the totals.
+    ///
+    /// The returned gas can't exceed the spent gas.
     #[inline]
     pub fn erase_cost(&mut self, returned: u64) {
-        self.remaining += returned;
+        debug_assert!(
+            returned <= self.spent(),
+            "returned gas {returned} exceeds spent gas {}",
+            self.spent()
+        );
+        self.remaining = self.remaining.saturating_add(returned).min(self.limit);
     }
 
     /// Spends all remaining gas.
@@ -95,7 +192,11 @@ impl Gas {
     /// at the end of transact.
     #[inline]
     pub fn record_refund(&mut self, refund: i64) {
-        self.refunded += refund;
+        debug_assert!(
+            self.refunded.checked_add(refund).is_some(),
+            "refund counter overflow"
+        );
+        self.refunded = self.refunded.saturating_add(refund);
     }
 
     /// Set a refund value for final refund.
@@ -105,8 +206,19 @@ impl Gas {
     /// Related to EIP-3529: Reduction in refunds
     #[inline]
     pub fn set_final_refund(&mut self, is_london: bool) {
+        self.refunded = self
+            .refund_gas()
+            .final_value()
+            .min(self.max_refund(is_london)) as i64;
+    }
+
+    /// Returns the maximum refund value, the Nth part (depending of fork) of gas spend.
+    ///
+    /// Related to EIP-3529: Reduction in refunds
+    #[inline]
+    pub const fn max_refund(&self, is_london: bool) -> u64 {
         let max_refund_quotient = if is_london { 5 } else { 2 };
-        self.refunded = (self.refunded() as u64).min(self.spent() / max_refund_quotient) as i64;
+        self.spent() / max_refund_quotient
     }
 
     /// Set a refund value. This overrides the current refund value.
@@ -129,3 +241,63 @@ impl Gas {
         success
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::Rng;
+
+    #[test]
+    fn accounting_never_negative() {
+        let mut rng = Rng::default();
+        for _ in 0..1000 {
+            let mut gas = Gas::new(rng.next(1_000_000) + 1);
+            // Refunds of a frame can be negative, but are covered by earlier positive refunds.
+            let mut positive = 0;
+            for _ in 0..32 {
+                match rng.next(3) {
+                    0 => {
+                        let cost = rng.next(gas.limit());
+                        let before = gas;
+                        if !gas.record_cost(cost) {
+                            assert_eq!(gas, before);
+                        }
+                    }
+                    1 => gas.erase_cost(rng.next(gas.spent() + 1)),
+                    _ => {
+                        let refund = rng.next(10_000) as i64;
+                        if rng.next(2) == 0 {
+                            positive += refund;
+                            gas.record_refund(refund);
+                        } else {
+                            let refund = refund.min(positive);
+                            positive -= refund;
+                            gas.record_refund(-refund);
+                        }
+                    }
+                }
+                assert!(gas.remaining() <= gas.limit());
+                assert!(gas.refunded() >= 0);
+            }
+            for is_london in [false, true] {
+                let mut gas = gas;
+                gas.set_final_refund(is_london);
+                assert!(gas.refunded() >= 0);
+                assert_eq!(gas.used() + gas.reimbursed(), gas.limit());
+            }
+        }
+    }
+
+    #[test]
+    fn typed_units() {
+        let mut gas = Gas::new(100);
+        assert!(gas.record_cost(60));
+        gas.record_refund(50);
+        assert_eq!(gas.spent_gas(), SpentGas(60));
+        assert_eq!(gas.refund_gas(), RefundGas(50));
+        gas.set_final_refund(true);
+        assert_eq!(gas.refunded(), 12);
+        assert_eq!(gas.used(), 48);
+        assert_eq!(gas.reimbursed(), 52);
+    }
+}
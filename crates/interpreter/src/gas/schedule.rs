@@ -0,0 +1,721 @@
+use super::{calc::log2floor, constants::*};
+use crate::{
+    num_words,
+    primitives::{charges_new_account, AccessListItem, Eip, SpecId, Transaction, U256},
+    SelfDestructResult,
+};
+use core::{num::NonZeroU64, ops::Deref};
+use std::sync::Arc;
+
+/// Constant gas costs charged by the interpreter.
+///
+/// The interpreter reads the costs from its [`gas_schedule`](crate::Interpreter::gas_schedule)
+/// instead of the constants of this module, so chains with different pricing can change a cost
+/// without forking the instructions. [`GasSchedule::for_spec`] returns the costs of a hardfork.
+///
+/// Hardforks that changed how a cost is computed rather than its value, e.g. the warm and cold
+/// accesses of EIP-2929, are still selected by the [`SpecId`] passed to the cost functions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GasSchedule {
+    /// Base tier, e.g. `ADDRESS` or `POP`.
+    pub base: u64,
+    /// Very low tier, e.g. `ADD` or `PUSH1`.
+    pub verylow: u64,
+    /// Low tier, e.g. `MUL`.
+    pub low: u64,
+    /// Mid tier, e.g. `ADDMOD` or `JUMP`.
+    pub mid: u64,
+    /// High tier, `JUMPI`.
+    pub high: u64,
+    /// `JUMPDEST`.
+    pub jumpdest: u64,
+    /// `RJUMPI` and `RJUMPV`.
+    pub condition_jump: u64,
+    /// `RETF`.
+    pub retf: u64,
+    /// `DATALOAD`.
+    pub data_load: u64,
+    /// `BLOCKHASH`.
+    pub blockhash: u64,
+    /// `EXP` with a zero exponent.
+    pub exp: u64,
+    /// `EXP` per byte of the exponent.
+    pub exp_byte: u64,
+    /// `KECCAK256` of empty data.
+    pub keccak256: u64,
+    /// Per word hashed by `KECCAK256` and `CREATE2`.
+    pub keccak256_word: u64,
+    /// Per word copied by the `*COPY` opcodes.
+    pub copy: u64,
+    /// `LOG*` without topics and data.
+    pub log: u64,
+    /// Per byte of `LOG*` data.
+    pub log_data: u64,
+    /// Per `LOG*` topic.
+    pub log_topic: u64,
+    /// Linear coefficient of the memory expansion, per word.
+    pub memory: u64,
+    /// Divisor of the quadratic part of the memory expansion.
+    pub memory_quad_divisor: NonZeroU64,
+    /// `BALANCE` before Berlin.
+    pub balance: u64,
+    /// `EXTCODESIZE` before Berlin.
+    pub extcodesize: u64,
+    /// `EXTCODEHASH` before Berlin.
+    pub extcodehash: u64,
+    /// `EXTCODECOPY` without data before Berlin.
+    pub extcodecopy: u64,
+    /// Account access of the `*CALL` opcodes before Berlin.
+    pub call: u64,
+    /// `SLOAD` before Berlin.
+    pub sload: u64,
+    /// Access of a warm account or slot from Berlin, and `TLOAD` and `TSTORE`.
+    pub warm_storage_read: u64,
+    /// Access of a cold account from Berlin.
+    pub cold_account_access: u64,
+    /// Access of a cold slot from Berlin.
+    pub cold_sload: u64,
+    /// `SSTORE` of a non-zero value to a zero slot.
+    pub sstore_set: u64,
+    /// `SSTORE` changing a non-zero slot.
+    pub sstore_reset: u64,
+    /// Refund of an `SSTORE` clearing a slot.
+    pub sstore_clears_refund: i64,
+    /// `SELFDESTRUCT`.
+    pub selfdestruct: u64,
+    /// Refund of a `SELFDESTRUCT` before London.
+    pub selfdestruct_refund: i64,
+    /// Value transfer of the `*CALL` opcodes.
+    pub call_value: u64,
+    /// Account creation by a call or `SELFDESTRUCT`.
+    pub new_account: u64,
+    /// Gas given to the callee of a call transferring value.
+    pub call_stipend: u64,
+    /// Least gas available to the callee of an `EXT*CALL`.
+    pub min_callee_gas: u64,
+    /// `CREATE` and `CREATE2`.
+    pub create: u64,
+    /// `EOFCREATE`.
+    pub eof_create: u64,
+    /// Per word of initcode, from Shanghai.
+    pub initcode_word: u64,
+    /// Per byte of code deposited by a contract creation.
+    pub code_deposit: u64,
+    /// Base cost of a transaction.
+    pub tx_base: u64,
+    /// Base cost of a transaction creating a contract.
+    pub tx_create: u64,
+    /// Per zero byte of transaction data.
+    pub tx_data_zero: u64,
+    /// Per non-zero byte of transaction data.
+    pub tx_data_non_zero: u64,
+    /// Per address of the transaction access list, from Berlin.
+    pub access_list_address: u64,
+    /// Per storage key of the transaction access list, from Berlin.
+    pub access_list_storage_key: u64,
+    /// Per authorization of the transaction, from Prague.
+    pub per_auth_base: u64,
+}
+
+impl Default for GasSchedule {
+    fn default() -> Self {
+        Self::for_spec(SpecId::LATEST)
+    }
+}
+
+impl GasSchedule {
+    /// Returns the costs of the `spec_id` hardfork.
+    pub const fn for_spec(spec_id: SpecId) -> Self {
+        let tangerine = spec_id.is_enabled_in(SpecId::TANGERINE);
+        let istanbul = spec_id.is_enabled_in(SpecId::ISTANBUL);
+        Self {
+            base: BASE,
+            verylow: VERYLOW,
+            low: LOW,
+            mid: MID,
+            high: HIGH,
+            jumpdest: JUMPDEST,
+            condition_jump: CONDITION_JUMP_GAS,
+            retf: RETF_GAS,
+            data_load: DATA_LOAD_GAS,
+            blockhash: BLOCKHASH,
+            exp: EXP,
+            // EIP-160: EXP cost increase
+            exp_byte: if spec_id.is_enabled_in(SpecId::SPURIOUS_DRAGON) {
+                50
+            } else {
+                10
+            },
+            keccak256: KECCAK256,
+            keccak256_word: KECCAK256WORD,
+            copy: COPY,
+            log: LOG,
+            log_data: LOGDATA,
+            log_topic: LOGTOPIC,
+            memory: MEMORY,
+            memory_quad_divisor: match NonZeroU64::new(512) {
+                Some(divisor) => divisor,
+                None => unreachable!(),
+            },
+            // EIP-150: Gas cost changes for IO-heavy operations
+            // EIP-1884: Repricing for trie-size-dependent opcodes
+            balance: if istanbul {
+                700
+            } else if tangerine {
+                400
+            } else {
+                20
+            },
+            extcodesize: if tangerine { 700 } else { 20 },
+            extcodehash: if istanbul { 700 } else { 400 },
+            extcodecopy: if tangerine { 700 } else { 20 },
+            call: if tangerine { 700 } else { 40 },
+            sload: if istanbul {
+                INSTANBUL_SLOAD_GAS
+            } else if tangerine {
+                200
+            } else {
+                50
+            },
+            warm_storage_read: WARM_STORAGE_READ_COST,
+            cold_account_access: COLD_ACCOUNT_ACCESS_COST,
+            cold_sload: COLD_SLOAD_COST,
+            sstore_set: SSTORE_SET,
+            sstore_reset: SSTORE_RESET,
+            // EIP-3529: Reduction in refunds
+            sstore_clears_refund: if spec_id.is_enabled_in(SpecId::LONDON) {
+                (SSTORE_RESET - COLD_SLOAD_COST + ACCESS_LIST_STORAGE_KEY) as i64
+            } else {
+                REFUND_SSTORE_CLEARS
+            },
+            selfdestruct: if tangerine { 5000 } else { 0 },
+            selfdestruct_refund: SELFDESTRUCT,
+            call_value: CALLVALUE,
+            new_account: NEWACCOUNT,
+            call_stipend: CALL_STIPEND,
+            min_callee_gas: MIN_CALLEE_GAS,
+            create: CREATE,
+            eof_create: EOF_CREATE_GAS,
+            initcode_word: INITCODE_WORD_COST,
+            code_deposit: CODEDEPOSIT,
+            tx_base: 21000,
+            // EIP-2: Homestead Hard-fork Changes
+            tx_create: if spec_id.is_enabled_in(SpecId::HOMESTEAD) {
+                53000
+            } else {
+                21000
+            },
+            tx_data_zero: TRANSACTION_ZERO_DATA,
+            // EIP-2028: Transaction data gas cost reduction
+            tx_data_non_zero: if istanbul {
+                TRANSACTION_NON_ZERO_DATA_INIT
+            } else {
+                TRANSACTION_NON_ZERO_DATA_FRONTIER
+            },
+            access_list_address: ACCESS_LIST_ADDRESS,
+            access_list_storage_key: ACCESS_LIST_STORAGE_KEY,
+            per_auth_base: PER_AUTH_BASE_COST,
+        }
+    }
+
+    /// Returns the costs of the `spec_id` hardfork, shared by every caller.
+    ///
+    /// Equal to [`Self::for_spec`], which only changes at the hardforks listed here.
+    pub const fn for_spec_static(spec_id: SpecId) -> &'static Self {
+        static FRONTIER: GasSchedule = GasSchedule::for_spec(SpecId::FRONTIER);
+        static HOMESTEAD: GasSchedule = GasSchedule::for_spec(SpecId::HOMESTEAD);
+        static TANGERINE: GasSchedule = GasSchedule::for_spec(SpecId::TANGERINE);
+        static SPURIOUS_DRAGON: GasSchedule = GasSchedule::for_spec(SpecId::SPURIOUS_DRAGON);
+        static ISTANBUL: GasSchedule = GasSchedule::for_spec(SpecId::ISTANBUL);
+        static LONDON: GasSchedule = GasSchedule::for_spec(SpecId::LONDON);
+        if spec_id.is_enabled_in(SpecId::LONDON) {
+            &LONDON
+        } else if spec_id.is_enabled_in(SpecId::ISTANBUL) {
+            &ISTANBUL
+        } else if spec_id.is_enabled_in(SpecId::SPURIOUS_DRAGON) {
+            &SPURIOUS_DRAGON
+        } else if spec_id.is_enabled_in(SpecId::TANGERINE) {
+            &TANGERINE
+        } else if spec_id.is_enabled_in(SpecId::HOMESTEAD) {
+            &HOMESTEAD
+        } else {
+            &FRONTIER
+        }
+    }
+
+    /// Initial gas that is deducted for transaction to be included, see
+    /// [`validate_initial_tx_gas`](super::validate_initial_tx_gas).
+    pub fn initial_tx_gas(
+        &self,
+        spec_id: SpecId,
+        input: &[u8],
+        is_create: bool,
+        access_list: &[AccessListItem],
+        authorization_list_num: u64,
+    ) -> u64 {
+        let mut initial_gas = 0;
+        let zero_data_len = input.iter().filter(|v| **v == 0).count() as u64;
+        let non_zero_data_len = input.len() as u64 - zero_data_len;
+
+        // initdate stipend
+        initial_gas += zero_data_len * self.tx_data_zero;
+        initial_gas += non_zero_data_len * self.tx_data_non_zero;
+
+        // get number of access list account and storages.
+        if spec_id.is_enabled_in(SpecId::BERLIN) {
+            let accessed_slots: usize =
+                access_list.iter().map(|item| item.storage_keys.len()).sum();
+            initial_gas += access_list.len() as u64 * self.access_list_address;
+            initial_gas += accessed_slots as u64 * self.access_list_storage_key;
+        }
+
+        // base stipend
+        initial_gas += if is_create {
+            self.tx_create
+        } else {
+            self.tx_base
+        };
+
+        // EIP-3860: Limit and meter initcode
+        // Init code stipend for bytecode analysis
+        if spec_id.is_enabled_in(SpecId::SHANGHAI) && is_create {
+            initial_gas = initial_gas.saturating_add(self.initcode_cost(input.len() as u64))
+        }
+
+        //   EIP-7702
+        if Eip::Eip7702.is_active_in(spec_id) {
+            initial_gas += authorization_list_num * self.per_auth_base;
+        }
+
+        initial_gas
+    }
+
+    /// Initial gas of the given [`Transaction`], see [`Self::initial_tx_gas`].
+    #[inline]
+    pub fn transaction_initial_gas<T: Transaction + ?Sized>(&self, spec_id: SpecId, tx: &T) -> u64 {
+        self.initial_tx_gas(
+            spec_id,
+            tx.data(),
+            tx.is_create(),
+            tx.access_list(),
+            tx.authorization_list()
+                .map(|list| list.len() as u64)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// `EXP` opcode cost calculation.
+    #[inline]
+    pub fn exp_cost(&self, power: U256) -> Option<u64> {
+        if power.is_zero() {
+            Some(self.exp)
+        } else {
+            let gas = U256::from(self.exp).checked_add(
+                U256::from(self.exp_byte).checked_mul(U256::from(log2floor(power) / 8 + 1))?,
+            )?;
+
+            u64::try_from(gas).ok()
+        }
+    }
+
+    /// `*COPY` opcodes cost calculation.
+    #[inline]
+    pub const fn verylowcopy_cost(&self, len: u64) -> Option<u64> {
+        self.verylow
+            .checked_add(tri!(self.copy.checked_mul(num_words(len))))
+    }
+
+    /// `BALANCE` opcode cost calculation.
+    #[inline]
+    pub const fn balance_cost(&self, spec_id: SpecId, is_cold: bool) -> u64 {
+        self.account_access_cost(spec_id, self.balance, is_cold)
+    }
+
+    /// `EXTCODESIZE` opcode cost calculation.
+    #[inline]
+    pub const fn extcodesize_cost(&self, spec_id: SpecId, is_cold: bool) -> u64 {
+        self.account_access_cost(spec_id, self.extcodesize, is_cold)
+    }
+
+    /// `EXTCODEHASH` opcode cost calculation.
+    #[inline]
+    pub const fn extcodehash_cost(&self, spec_id: SpecId, is_cold: bool) -> u64 {
+        self.account_access_cost(spec_id, self.extcodehash, is_cold)
+    }
+
+    /// `EXTCODECOPY` opcode cost calculation.
+    #[inline]
+    pub const fn extcodecopy_cost(&self, spec_id: SpecId, len: u64, is_cold: bool) -> Option<u64> {
+        let base_gas = self.account_access_cost(spec_id, self.extcodecopy, is_cold);
+        base_gas.checked_add(tri!(self.copy.checked_mul(num_words(len))))
+    }
+
+    /// `LOG` opcode cost calculation.
+    #[inline]
+    pub const fn log_cost(&self, n: u8, len: u64) -> Option<u64> {
+        tri!(self.log.checked_add(tri!(self.log_data.checked_mul(len))))
+            .checked_add(self.log_topic * n as u64)
+    }
+
+    /// `KECCAK256` opcode cost calculation.
+    #[inline]
+    pub const fn keccak256_cost(&self, len: u64) -> Option<u64> {
+        self.keccak256
+            .checked_add(tri!(self.keccak256_word.checked_mul(num_words(len))))
+    }
+
+    /// `CREATE2` opcode cost calculation.
+    #[inline]
+    pub const fn create2_cost(&self, len: u64) -> Option<u64> {
+        self.create
+            .checked_add(tri!(self.keccak256_word.checked_mul(num_words(len))))
+    }
+
+    /// EIP-3860: Limit and meter initcode
+    ///
+    /// Saturates at `u64::MAX`, which no gas limit can cover.
+    #[inline]
+    pub const fn initcode_cost(&self, len: u64) -> u64 {
+        self.initcode_word.saturating_mul(num_words(len))
+    }
+
+    /// `SLOAD` opcode cost calculation.
+    #[inline]
+    pub const fn sload_cost(&self, spec_id: SpecId, is_cold: bool) -> u64 {
+        if spec_id.is_enabled_in(SpecId::BERLIN) {
+            if is_cold {
+                self.cold_sload
+            } else {
+                self.warm_storage_read
+            }
+        } else {
+            self.sload
+        }
+    }
+
+    /// `SSTORE` opcode cost calculation.
+    #[inline]
+    pub fn sstore_cost(
+        &self,
+        spec_id: SpecId,
+        original: U256,
+        current: U256,
+        new: U256,
+        gas: u64,
+        is_cold: bool,
+    ) -> Option<u64> {
+        // EIP-1706 Disable SSTORE with gasleft lower than call stipend
+        if spec_id.is_enabled_in(SpecId::ISTANBUL) && gas <= self.call_stipend {
+            return None;
+        }
+
+        if spec_id.is_enabled_in(SpecId::BERLIN) {
+            // Berlin specification logic
+            let mut gas_cost = self.istanbul_sstore_cost(
+                self.warm_storage_read,
+                self.sstore_reset.saturating_sub(self.cold_sload),
+                original,
+                current,
+                new,
+            );
+
+            if is_cold {
+                gas_cost += self.cold_sload;
+            }
+            Some(gas_cost)
+        } else if spec_id.is_enabled_in(SpecId::ISTANBUL) {
+            // Istanbul logic
+            Some(self.istanbul_sstore_cost(self.sload, self.sstore_reset, original, current, new))
+        } else {
+            // Frontier logic
+            Some(self.frontier_sstore_cost(current, new))
+        }
+    }
+
+    /// EIP-2200: Structured Definitions for Net Gas Metering
+    #[inline]
+    fn istanbul_sstore_cost(
+        &self,
+        sload_gas: u64,
+        sstore_reset_gas: u64,
+        original: U256,
+        current: U256,
+        new: U256,
+    ) -> u64 {
+        if new == current {
+            sload_gas
+        } else if original == current && original.is_zero() {
+            self.sstore_set
+        } else if original == current {
+            sstore_reset_gas
+        } else {
+            sload_gas
+        }
+    }
+
+    /// Frontier sstore cost just had two cases set and reset values.
+    #[inline]
+    fn frontier_sstore_cost(&self, current: U256, new: U256) -> u64 {
+        if current.is_zero() && !new.is_zero() {
+            self.sstore_set
+        } else {
+            self.sstore_reset
+        }
+    }
+
+    /// `SSTORE` opcode refund calculation.
+    #[allow(clippy::collapsible_else_if)]
+    #[inline]
+    pub fn sstore_refund(&self, spec_id: SpecId, original: U256, current: U256, new: U256) -> i64 {
+        let sstore_clears_schedule = self.sstore_clears_refund;
+        if spec_id.is_enabled_in(SpecId::ISTANBUL) {
+            if current == new {
+                0
+            } else {
+                if original == current && new.is_zero() {
+                    sstore_clears_schedule
+                } else {
+                    let mut refund = 0;
+
+                    if !original.is_zero() {
+                        if current.is_zero() {
+                            refund -= sstore_clears_schedule;
+                        } else if new.is_zero() {
+                            refund += sstore_clears_schedule;
+                        }
+                    }
+
+                    if original == new {
+                        let (gas_sstore_reset, gas_sload) = if spec_id.is_enabled_in(SpecId::BERLIN)
+                        {
+                            (
+                                self.sstore_reset.saturating_sub(self.cold_sload),
+                                self.warm_storage_read,
+                            )
+                        } else {
+                            (self.sstore_reset, self.sload)
+                        };
+                        if original.is_zero() {
+                            refund += self.sstore_set.saturating_sub(gas_sload) as i64;
+                        } else {
+                            refund += gas_sstore_reset.saturating_sub(gas_sload) as i64;
+                        }
+                    }
+
+                    refund
+                }
+            }
+        } else {
+            if !current.is_zero() && new.is_zero() {
+                sstore_clears_schedule
+            } else {
+                0
+            }
+        }
+    }
+
+    /// `SELFDESTRUCT` opcode cost calculation.
+    #[inline]
+    pub const fn selfdestruct_cost(&self, spec_id: SpecId, res: SelfDestructResult) -> u64 {
+        // EIP-161: State trie clearing (invariant-preserving alternative)
+        let should_charge_topup = charges_new_account(spec_id, res.had_value, !res.target_exists);
+
+        // EIP-150: Gas cost changes for IO-heavy operations
+        let selfdestruct_gas_topup =
+            if spec_id.is_enabled_in(SpecId::TANGERINE) && should_charge_topup {
+                self.new_account
+            } else {
+                0
+            };
+
+        let mut gas = self.selfdestruct + selfdestruct_gas_topup;
+        if spec_id.is_enabled_in(SpecId::BERLIN) && res.is_cold {
+            gas += self.cold_account_access
+        }
+        gas
+    }
+
+    /// Calculate call gas cost for the call instruction, see [`call_cost`](super::call_cost).
+    #[inline]
+    pub const fn call_cost(
+        &self,
+        spec_id: SpecId,
+        transfers_value: bool,
+        is_cold: bool,
+        new_account_accounting: bool,
+    ) -> u64 {
+        // Account access.
+        let mut gas = self.account_access_cost(spec_id, self.call, is_cold);
+
+        // transfer value cost
+        if transfers_value {
+            gas += self.call_value;
+        }
+
+        // new account cost
+        // EIP-161: State trie clearing (invariant-preserving alternative)
+        if charges_new_account(spec_id, transfers_value, new_account_accounting) {
+            gas += self.new_account;
+        }
+
+        gas
+    }
+
+    /// Cost of an account access, `cost` before Berlin, warm or cold from Berlin.
+    #[inline]
+    pub const fn account_access_cost(&self, spec_id: SpecId, cost: u64, is_cold: bool) -> u64 {
+        if spec_id.is_enabled_in(SpecId::BERLIN) {
+            self.warm_cold_cost(is_cold)
+        } else {
+            cost
+        }
+    }
+
+    /// Berlin warm and cold storage access cost for account access.
+    #[inline]
+    pub const fn warm_cold_cost(&self, is_cold: bool) -> u64 {
+        if is_cold {
+            self.cold_account_access
+        } else {
+            self.warm_storage_read
+        }
+    }
+
+    /// Memory expansion cost calculation for a given number of words.
+    #[inline]
+    pub const fn memory_gas(&self, num_words: u64) -> u64 {
+        self.memory
+            .saturating_mul(num_words)
+            .saturating_add(num_words.saturating_mul(num_words) / self.memory_quad_divisor.get())
+    }
+}
+
+/// A [`GasSchedule`] shared by the interpreters that charge it instead of copied into each of
+/// them.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SharedGasSchedule {
+    /// Costs of a hardfork, see [`GasSchedule::for_spec_static`].
+    StaticRef(&'static GasSchedule),
+    /// Custom costs.
+    Shared(Arc<GasSchedule>),
+}
+
+impl SharedGasSchedule {
+    /// Returns the costs of the `spec_id` hardfork.
+    #[inline]
+    pub fn for_spec(spec_id: SpecId) -> Self {
+        Self::StaticRef(GasSchedule::for_spec_static(spec_id))
+    }
+}
+
+impl Default for SharedGasSchedule {
+    #[inline]
+    fn default() -> Self {
+        Self::for_spec(SpecId::LATEST)
+    }
+}
+
+impl From<GasSchedule> for SharedGasSchedule {
+    #[inline]
+    fn from(gas_schedule: GasSchedule) -> Self {
+        Self::Shared(Arc::new(gas_schedule))
+    }
+}
+
+impl Deref for SharedGasSchedule {
+    type Target = GasSchedule;
+
+    #[inline]
+    fn deref(&self) -> &GasSchedule {
+        match self {
+            Self::StaticRef(gas_schedule) => gas_schedule,
+            Self::Shared(gas_schedule) => gas_schedule,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        opcode::make_runtime_instruction_table, primitives::Bytecode, DummyHost, Gas, Interpreter,
+    };
+
+    #[test]
+    fn custom_schedule() {
+        // PUSH1 1, PUSH1 2, ADD, SLOAD, STOP
+        let code = Bytecode::new_raw([0x60, 0x01, 0x60, 0x02, 0x01, 0x54, 0x00].into());
+        let run = |schedule: Option<GasSchedule>| {
+            let mut interp = Interpreter::new_bytecode(code.clone()).with_spec_id(SpecId::CANCUN);
+            if let Some(schedule) = schedule {
+                interp = interp.with_gas_schedule(schedule);
+            }
+            interp.gas = Gas::new(100_000);
+            let table = make_runtime_instruction_table::<DummyHost>();
+            let mut host = DummyHost::default();
+            interp.run(Default::default(), &table, &mut host);
+            interp.gas.spent()
+        };
+
+        // The slot is cold on first access.
+        assert_eq!(run(None), 3 * 3 + 2100);
+        let schedule = GasSchedule {
+            verylow: 1,
+            cold_sload: 1000,
+            ..GasSchedule::for_spec(SpecId::CANCUN)
+        };
+        assert_eq!(run(Some(schedule)), 3 + 1000);
+    }
+
+    #[test]
+    fn static_schedules() {
+        for spec_id in (0..=u8::MAX).filter_map(SpecId::try_from_u8) {
+            assert_eq!(
+                *GasSchedule::for_spec_static(spec_id),
+                GasSchedule::for_spec(spec_id),
+                "{spec_id:?}"
+            );
+        }
+        let custom = GasSchedule {
+            base: 1,
+            ..Default::default()
+        };
+        let shared = SharedGasSchedule::from(custom);
+        let interp = Interpreter::default().with_gas_schedule(shared.clone());
+        assert!(matches!(
+            (&interp.gas_schedule, &shared),
+            (SharedGasSchedule::Shared(a), SharedGasSchedule::Shared(b)) if Arc::ptr_eq(a, b)
+        ));
+    }
+
+    #[test]
+    fn saturating_costs() {
+        let schedule = GasSchedule {
+            initcode_word: u64::MAX,
+            warm_storage_read: u64::MAX,
+            ..GasSchedule::for_spec(SpecId::CANCUN)
+        };
+        assert_eq!(schedule.initcode_cost(64), u64::MAX);
+        let (zero, one) = (U256::ZERO, U256::from(1));
+        assert_eq!(schedule.sstore_refund(SpecId::CANCUN, zero, one, zero), 0);
+        assert_eq!(
+            schedule.sstore_refund(SpecId::CANCUN, one, zero, one),
+            -4800
+        );
+    }
+
+    #[test]
+    fn memory_expansion() {
+        // PUSH1 0, MLOAD, STOP
+        let code = Bytecode::new_raw([0x60, 0x00, 0x51, 0x00].into());
+        let mut interp = Interpreter::new_bytecode(code).with_gas_schedule(GasSchedule {
+            memory: 10,
+            ..Default::default()
+        });
+        interp.gas = Gas::new(100_000);
+        let table = make_runtime_instruction_table::<DummyHost>();
+        interp.run(Default::default(), &table, &mut DummyHost::default());
+        assert_eq!(interp.gas.spent(), 3 + 3 + 10);
+    }
+}
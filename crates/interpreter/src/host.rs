@@ -1,7 +1,11 @@
 use crate::primitives::{Address, Bytes, Env, Log, B256, U256};
 
 mod dummy;
+mod static_host;
 pub use dummy::DummyHost;
+pub use static_host::{
+    StaticAccount, StaticHost, StaticState, StaticStateError, StaticStateWriter,
+};
 
 /// EVM context host.
 pub trait Host {
@@ -40,13 +44,26 @@ pub trait Host {
     fn tload(&mut self, address: Address, index: U256) -> U256;
 
     /// Set the transient storage value of `address` at `index`.
-    fn tstore(&mut self, address: Address, index: U256, value: U256);
+    ///
+    /// Returns `None` if the execution can not continue, e.g. the host ran past one of its limits.
+    fn tstore(&mut self, address: Address, index: U256, value: U256) -> Option<()>;
 
     /// Emit a log owned by `address` with given `LogData`.
-    fn log(&mut self, log: Log);
+    ///
+    /// Returns `None` if the execution can not continue, e.g. the host ran past one of its limits.
+    fn log(&mut self, log: Log) -> Option<()>;
 
     /// Mark `address` to be deleted, with funds transferred to `target`.
     fn selfdestruct(&mut self, address: Address, target: Address) -> Option<SelfDestructResult>;
+
+    /// Record the `input` of a KECCAK256 instruction that produced `hash`.
+    ///
+    /// Called only if preimage recording is enabled in [`CfgEnv`](crate::primitives::CfgEnv).
+    /// Does nothing by default.
+    #[inline]
+    fn record_keccak_preimage(&mut self, input: &[u8], hash: B256) {
+        let _ = (input, hash);
+    }
 }
 
 /// Represents the result of an `sstore` operation.
@@ -91,6 +108,7 @@ mod tests {
     #[test]
     fn object_safety() {
         assert_host::<DummyHost>();
+        assert_host::<StaticHost<'_>>();
         assert_host::<dyn Host>();
     }
 }
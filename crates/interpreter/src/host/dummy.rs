@@ -100,13 +100,15 @@ impl Host for DummyHost {
     }
 
     #[inline]
-    fn tstore(&mut self, _address: Address, index: U256, value: U256) {
+    fn tstore(&mut self, _address: Address, index: U256, value: U256) -> Option<()> {
         self.transient_storage.insert(index, value);
+        Some(())
     }
 
     #[inline]
-    fn log(&mut self, log: Log) {
-        self.log.push(log)
+    fn log(&mut self, log: Log) -> Option<()> {
+        self.log.push(log);
+        Some(())
     }
 
     #[inline]
@@ -0,0 +1,496 @@
+use crate::{
+    primitives::{keccak256, Address, Bytes, Env, HashMap, HashSet, Log, B256, KECCAK_EMPTY, U256},
+    Host, LoadAccountResult, SStoreResult, SelfDestructResult,
+};
+use core::fmt;
+use std::vec::Vec;
+
+/// Error of a malformed [`StaticState`] blob.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StaticStateError {
+    /// The blob ended in the middle of an item.
+    UnexpectedEnd,
+    /// The blob has bytes after its last account.
+    TrailingBytes,
+    /// The account is encoded twice.
+    DuplicateAccount(Address),
+    /// The storage keys of the account are not in strictly ascending order.
+    UnsortedStorage(Address),
+}
+
+impl fmt::Display for StaticStateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd => f.write_str("unexpected end of state blob"),
+            Self::TrailingBytes => f.write_str("trailing bytes after state blob"),
+            Self::DuplicateAccount(address) => write!(f, "duplicate account {address}"),
+            Self::UnsortedStorage(address) => write!(f, "unsorted storage of account {address}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StaticStateError {}
+
+/// Account of a [`StaticState`], borrowing its code and storage from the blob.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StaticAccount<'a> {
+    pub balance: U256,
+    pub nonce: u64,
+    pub code: &'a [u8],
+    /// Storage slots, 32 bytes key and 32 bytes value each, sorted by key.
+    storage: &'a [u8],
+}
+
+impl StaticAccount<'_> {
+    /// Returns `true` if the account has no balance, nonce or code.
+    pub fn is_empty(&self) -> bool {
+        self.balance.is_zero() && self.nonce == 0 && self.code.is_empty()
+    }
+
+    /// Returns the value of the storage slot, zero if it is not in the blob.
+    pub fn storage(&self, key: U256) -> U256 {
+        let key = key.to_be_bytes::<32>();
+        let slots = self.storage.len() / 64;
+        let (mut low, mut high) = (0, slots);
+        while low < high {
+            let mid = (low + high) / 2;
+            let slot = &self.storage[mid * 64..mid * 64 + 64];
+            match slot[..32].cmp(&key[..]) {
+                core::cmp::Ordering::Less => low = mid + 1,
+                core::cmp::Ordering::Greater => high = mid,
+                core::cmp::Ordering::Equal => return U256::from_be_slice(&slot[32..]),
+            }
+        }
+        U256::ZERO
+    }
+
+    /// Returns the number of storage slots in the blob.
+    pub fn storage_len(&self) -> usize {
+        self.storage.len() / 64
+    }
+}
+
+/// Read-only pre-state of an execution, decoded without copying from a serialized blob.
+///
+/// The blob is made of big-endian integers:
+///
+/// ```text
+/// blob    = count:u32 (number:u64 hash:[32])* count:u32 account*
+/// account = address:[20] balance:[32] nonce:u64 code_len:u32 code:[code_len]
+///           count:u32 (key:[32] value:[32])*
+/// ```
+///
+/// Storage keys are in strictly ascending order. Accounts and storage slots missing from the
+/// blob are empty, block hashes missing from it are an error of the host.
+///
+/// See [`StaticStateWriter`] to encode it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StaticState<'a> {
+    block_hashes: HashMap<u64, B256>,
+    accounts: HashMap<Address, StaticAccount<'a>>,
+}
+
+impl<'a> StaticState<'a> {
+    /// Decodes the state blob.
+    pub fn decode(blob: &'a [u8]) -> Result<Self, StaticStateError> {
+        let mut reader = Reader(blob);
+        let mut state = Self::default();
+        for _ in 0..reader.u32()? {
+            let number = reader.u64()?;
+            state
+                .block_hashes
+                .insert(number, B256::from_slice(reader.take(32)?));
+        }
+        for _ in 0..reader.u32()? {
+            let address = Address::from_slice(reader.take(20)?);
+            let balance = U256::from_be_slice(reader.take(32)?);
+            let nonce = reader.u64()?;
+            let code_len = reader.u32()? as usize;
+            let code = reader.take(code_len)?;
+            let slots = reader.u32()? as usize;
+            let storage = reader.take(
+                slots
+                    .checked_mul(64)
+                    .ok_or(StaticStateError::UnexpectedEnd)?,
+            )?;
+            let sorted = storage
+                .chunks_exact(64)
+                .zip(storage.chunks_exact(64).skip(1))
+                .all(|(a, b)| a[..32] < b[..32]);
+            if !sorted {
+                return Err(StaticStateError::UnsortedStorage(address));
+            }
+            let account = StaticAccount {
+                balance,
+                nonce,
+                code,
+                storage,
+            };
+            if state.accounts.insert(address, account).is_some() {
+                return Err(StaticStateError::DuplicateAccount(address));
+            }
+        }
+        if !reader.0.is_empty() {
+            return Err(StaticStateError::TrailingBytes);
+        }
+        Ok(state)
+    }
+
+    /// Returns the account, `None` if it is not in the blob.
+    pub fn account(&self, address: &Address) -> Option<&StaticAccount<'a>> {
+        self.accounts.get(address)
+    }
+
+    /// Returns the hash of the block, `None` if it is not in the blob.
+    pub fn block_hash(&self, number: u64) -> Option<B256> {
+        self.block_hashes.get(&number).copied()
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], StaticStateError> {
+        if self.0.len() < len {
+            return Err(StaticStateError::UnexpectedEnd);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32, StaticStateError> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, StaticStateError> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
+
+/// Encodes a [`StaticState`] blob.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StaticStateWriter {
+    block_hashes: Vec<(u64, B256)>,
+    accounts: Vec<u8>,
+    account_count: u32,
+}
+
+impl StaticStateWriter {
+    /// Creates an empty writer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the hash of a block.
+    pub fn block_hash(&mut self, number: u64, hash: B256) -> &mut Self {
+        self.block_hashes.push((number, hash));
+        self
+    }
+
+    /// Adds an account with its storage slots, in any order. A key set twice keeps its first
+    /// value.
+    pub fn account(
+        &mut self,
+        address: Address,
+        balance: U256,
+        nonce: u64,
+        code: &[u8],
+        storage: impl IntoIterator<Item = (U256, U256)>,
+    ) -> &mut Self {
+        let mut storage = storage.into_iter().collect::<Vec<_>>();
+        storage.sort_by_key(|(key, _)| *key);
+        storage.dedup_by_key(|(key, _)| *key);
+
+        self.accounts.extend_from_slice(address.as_slice());
+        self.accounts
+            .extend_from_slice(&balance.to_be_bytes::<32>());
+        self.accounts.extend_from_slice(&nonce.to_be_bytes());
+        self.accounts
+            .extend_from_slice(&(code.len() as u32).to_be_bytes());
+        self.accounts.extend_from_slice(code);
+        self.accounts
+            .extend_from_slice(&(storage.len() as u32).to_be_bytes());
+        for (key, value) in storage {
+            self.accounts.extend_from_slice(&key.to_be_bytes::<32>());
+            self.accounts.extend_from_slice(&value.to_be_bytes::<32>());
+        }
+        self.account_count += 1;
+        self
+    }
+
+    /// Returns the encoded blob.
+    pub fn finish(&self) -> Vec<u8> {
+        let mut blob = Vec::with_capacity(8 + self.block_hashes.len() * 40 + self.accounts.len());
+        blob.extend_from_slice(&(self.block_hashes.len() as u32).to_be_bytes());
+        for (number, hash) in &self.block_hashes {
+            blob.extend_from_slice(&number.to_be_bytes());
+            blob.extend_from_slice(hash.as_slice());
+        }
+        blob.extend_from_slice(&self.account_count.to_be_bytes());
+        blob.extend_from_slice(&self.accounts);
+        blob
+    }
+}
+
+/// [Host] serving accounts, storage and code from a read-only [`StaticState`].
+///
+/// Meant for `no_std` environments, e.g. zkVMs, executing a frame with the [`Interpreter`]
+/// directly: there is no journal and no database. Storage and transient storage writes are
+/// kept in memory on top of the blob, and accounts and slots are warm once accessed. Balances
+/// are never changed, a `SELFDESTRUCT` is only recorded in `selfdestructs`.
+///
+/// [`Interpreter`]: crate::Interpreter
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StaticHost<'a> {
+    pub env: Env,
+    pub state: StaticState<'a>,
+    /// Storage written by the execution.
+    pub storage: HashMap<(Address, U256), U256>,
+    pub transient_storage: HashMap<(Address, U256), U256>,
+    pub logs: Vec<Log>,
+    /// Self-destructed accounts, in order.
+    pub selfdestructs: Vec<(Address, Address)>,
+    /// Warm accounts.
+    pub warm_accounts: HashSet<Address>,
+    /// Warm storage slots.
+    pub warm_storage: HashSet<(Address, U256)>,
+}
+
+impl<'a> StaticHost<'a> {
+    /// Creates a host over the state with the given [`Env`].
+    ///
+    /// The caller, the target and the access list of the transaction are warm.
+    pub fn new(env: Env, state: StaticState<'a>) -> Self {
+        let mut host = Self {
+            state,
+            ..Default::default()
+        };
+        host.warm_accounts.insert(env.tx.caller);
+        if let Some(target) = env.tx.transact_to.to() {
+            host.warm_accounts.insert(*target);
+        }
+        for item in env.tx.access_list.iter() {
+            host.warm_accounts.insert(item.address);
+            host.warm_storage.extend(
+                item.storage_keys
+                    .iter()
+                    .map(|key| (item.address, U256::from_be_bytes(key.0))),
+            );
+        }
+        host.env = env;
+        host
+    }
+
+    /// Decodes the state blob and creates a host over it, see [`Self::new`].
+    pub fn decode(env: Env, blob: &'a [u8]) -> Result<Self, StaticStateError> {
+        StaticState::decode(blob).map(|state| Self::new(env, state))
+    }
+
+    /// Marks the account warm and returns `true` if it was cold.
+    #[inline]
+    fn warm_account(&mut self, address: Address) -> bool {
+        self.warm_accounts.insert(address)
+    }
+
+    #[inline]
+    fn present_value(&self, address: Address, index: U256) -> U256 {
+        match self.storage.get(&(address, index)) {
+            Some(value) => *value,
+            None => self.original_value(address, index),
+        }
+    }
+
+    #[inline]
+    fn original_value(&self, address: Address, index: U256) -> U256 {
+        self.state
+            .account(&address)
+            .map(|account| account.storage(index))
+            .unwrap_or_default()
+    }
+}
+
+impl Host for StaticHost<'_> {
+    #[inline]
+    fn env(&self) -> &Env {
+        &self.env
+    }
+
+    #[inline]
+    fn env_mut(&mut self) -> &mut Env {
+        &mut self.env
+    }
+
+    #[inline]
+    fn load_account(&mut self, address: Address) -> Option<LoadAccountResult> {
+        let is_cold = self.warm_account(address);
+        let is_empty = match self.state.account(&address) {
+            Some(account) => account.is_empty(),
+            None => true,
+        };
+        Some(LoadAccountResult { is_cold, is_empty })
+    }
+
+    #[inline]
+    fn block_hash(&mut self, number: u64) -> Option<B256> {
+        self.state.block_hash(number)
+    }
+
+    #[inline]
+    fn balance(&mut self, address: Address) -> Option<(U256, bool)> {
+        let is_cold = self.warm_account(address);
+        let balance = self.state.account(&address).map(|a| a.balance);
+        Some((balance.unwrap_or_default(), is_cold))
+    }
+
+    #[inline]
+    fn code(&mut self, address: Address) -> Option<(Bytes, bool)> {
+        let is_cold = self.warm_account(address);
+        let code = self.state.account(&address).map(|a| a.code);
+        Some((Bytes::copy_from_slice(code.unwrap_or_default()), is_cold))
+    }
+
+    #[inline]
+    fn code_hash(&mut self, address: Address) -> Option<(B256, bool)> {
+        let is_cold = self.warm_account(address);
+        let hash = match self.state.account(&address) {
+            None => B256::ZERO,
+            Some(account) if account.is_empty() => B256::ZERO,
+            Some(account) if account.code.is_empty() => KECCAK_EMPTY,
+            Some(account) => keccak256(account.code),
+        };
+        Some((hash, is_cold))
+    }
+
+    #[inline]
+    fn sload(&mut self, address: Address, index: U256) -> Option<(U256, bool)> {
+        let is_cold = self.warm_storage.insert((address, index));
+        Some((self.present_value(address, index), is_cold))
+    }
+
+    #[inline]
+    fn sstore(&mut self, address: Address, index: U256, value: U256) -> Option<SStoreResult> {
+        let is_cold = self.warm_storage.insert((address, index));
+        let original_value = self.original_value(address, index);
+        let present_value = self
+            .storage
+            .insert((address, index), value)
+            .unwrap_or(original_value);
+        Some(SStoreResult {
+            original_value,
+            present_value,
+            new_value: value,
+            is_cold,
+        })
+    }
+
+    #[inline]
+    fn tload(&mut self, address: Address, index: U256) -> U256 {
+        self.transient_storage
+            .get(&(address, index))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    #[inline]
+    fn tstore(&mut self, address: Address, index: U256, value: U256) -> Option<()> {
+        self.transient_storage.insert((address, index), value);
+        Some(())
+    }
+
+    #[inline]
+    fn log(&mut self, log: Log) -> Option<()> {
+        self.logs.push(log);
+        Some(())
+    }
+
+    #[inline]
+    fn selfdestruct(&mut self, address: Address, target: Address) -> Option<SelfDestructResult> {
+        let is_cold = self.warm_account(target);
+        let previously_destroyed = self.selfdestructs.iter().any(|(a, _)| *a == address);
+        self.selfdestructs.push((address, target));
+        let had_value = self
+            .state
+            .account(&address)
+            .is_some_and(|a| !a.balance.is_zero());
+        let target_exists = self.state.account(&target).is_some_and(|a| !a.is_empty());
+        Some(SelfDestructResult {
+            had_value,
+            target_exists,
+            is_cold,
+            previously_destroyed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        analysis::to_analysed,
+        opcode::make_instruction_table,
+        primitives::{Bytecode, CancunSpec, SpecId, TxKind},
+        Contract, Interpreter, InterpreterAction, SharedMemory,
+    };
+
+    #[test]
+    fn executes_over_blob() {
+        let contract = Address::with_last_byte(0x10);
+        let other = Address::with_last_byte(0x20);
+        // SSTORE(0, SLOAD(1) + BALANCE(0x20)) STOP
+        let code = [
+            0x60, 0x01, 0x54, 0x60, 0x20, 0x31, 0x01, 0x60, 0x00, 0x55, 0x00,
+        ];
+        let blob = StaticStateWriter::new()
+            .block_hash(1, B256::with_last_byte(1))
+            .account(
+                contract,
+                U256::ZERO,
+                1,
+                &code,
+                [
+                    (U256::from(2), U256::from(3)),
+                    (U256::from(1), U256::from(5)),
+                ],
+            )
+            .account(other, U256::from(7), 0, &[], [])
+            .finish();
+
+        let mut env = Env::default();
+        env.tx.transact_to = TxKind::Call(contract);
+        let mut host = StaticHost::decode(env, &blob).unwrap();
+
+        let mut interpreter = Interpreter::new(
+            Contract {
+                bytecode: to_analysed(Bytecode::new_raw(Bytes::copy_from_slice(&code))),
+                target_address: contract,
+                bytecode_address: Some(contract),
+                ..Default::default()
+            },
+            100_000,
+            false,
+        )
+        .with_spec_id(SpecId::CANCUN);
+        let table = make_instruction_table::<StaticHost<'_>, CancunSpec>();
+        let action = interpreter.run(SharedMemory::new(), &table, &mut host);
+        assert!(matches!(action, InterpreterAction::Return { .. }));
+        assert_eq!(
+            host.sload(contract, U256::ZERO),
+            Some((U256::from(12), false))
+        );
+        // Cold SLOAD, BALANCE and SSTORE setting a zero slot.
+        assert_eq!(
+            interpreter.gas.spent(),
+            3 + 2100 + 3 + 2600 + 3 + 3 + 2100 + 20000
+        );
+        assert_eq!(host.code_hash(other), Some((KECCAK_EMPTY, false)));
+        assert_eq!(host.block_hash(1), Some(B256::with_last_byte(1)));
+        assert_eq!(host.block_hash(2), None);
+
+        assert_eq!(
+            StaticState::decode(&blob[..blob.len() - 1]),
+            Err(StaticStateError::UnexpectedEnd)
+        );
+    }
+}
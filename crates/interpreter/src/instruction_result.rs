@@ -90,6 +90,12 @@ pub enum InstructionResult {
     EofAuxDataTooSmall,
     /// `EXT*CALL` target address needs to be padded with 0s.
     InvalidEXTCALLTarget,
+    /// EOF contract is calling opcode that is enabled only in legacy bytecode.
+    LegacyOpcodeDisabledInEOF,
+    /// `RETURNDATACOPY` read past the end of the return data buffer.
+    ReturnDataOutOfBounds,
+    /// The journal grew past the limit set in `CfgEnv::journal_limit`.
+    JournalLimitExceeded,
 }
 
 impl From<SuccessReason> for InstructionResult {
@@ -135,6 +141,9 @@ impl From<HaltReason> for InstructionResult {
             HaltReason::EofAuxDataTooSmall => Self::EofAuxDataTooSmall,
             HaltReason::EOFFunctionStackOverflow => Self::EOFFunctionStackOverflow,
             HaltReason::InvalidEXTCALLTarget => Self::InvalidEXTCALLTarget,
+            HaltReason::MemoryLimitExceeded => Self::MemoryLimitOOG,
+            HaltReason::ReturnDataOutOfBounds => Self::ReturnDataOutOfBounds,
+            HaltReason::JournalLimitExceeded => Self::JournalLimitExceeded,
             #[cfg(feature = "optimism")]
             HaltReason::FailedDeposit => Self::FatalExternalError,
         }
@@ -195,6 +204,9 @@ macro_rules! return_error {
             | InstructionResult::EofAuxDataTooSmall
             | InstructionResult::EofAuxDataOverflow
             | InstructionResult::InvalidEXTCALLTarget
+            | InstructionResult::LegacyOpcodeDisabledInEOF
+            | InstructionResult::ReturnDataOutOfBounds
+            | InstructionResult::JournalLimitExceeded
     };
 }
 
@@ -291,9 +303,7 @@ impl From<InstructionResult> for SuccessOrHalt {
             InstructionResult::CallTooDeep => Self::Halt(HaltReason::CallTooDeep), // not gonna happen for first call
             InstructionResult::OutOfFunds => Self::Halt(HaltReason::OutOfFunds), // Check for first call is done separately.
             InstructionResult::OutOfGas => Self::Halt(HaltReason::OutOfGas(OutOfGasError::Basic)),
-            InstructionResult::MemoryLimitOOG => {
-                Self::Halt(HaltReason::OutOfGas(OutOfGasError::MemoryLimit))
-            }
+            InstructionResult::MemoryLimitOOG => Self::Halt(HaltReason::MemoryLimitExceeded),
             InstructionResult::MemoryOOG => Self::Halt(HaltReason::OutOfGas(OutOfGasError::Memory)),
             InstructionResult::PrecompileOOG => {
                 Self::Halt(HaltReason::OutOfGas(OutOfGasError::Precompile))
@@ -316,6 +326,10 @@ impl From<InstructionResult> for SuccessOrHalt {
             InstructionResult::StackUnderflow => Self::Halt(HaltReason::StackUnderflow),
             InstructionResult::StackOverflow => Self::Halt(HaltReason::StackOverflow),
             InstructionResult::OutOfOffset => Self::Halt(HaltReason::OutOfOffset),
+            InstructionResult::ReturnDataOutOfBounds => {
+                Self::Halt(HaltReason::ReturnDataOutOfBounds)
+            }
+            InstructionResult::JournalLimitExceeded => Self::Halt(HaltReason::JournalLimitExceeded),
             InstructionResult::CreateCollision => Self::Halt(HaltReason::CreateCollision),
             InstructionResult::OverflowPayment => Self::Halt(HaltReason::OverflowPayment), // Check for first call is done separately.
             InstructionResult::PrecompileError => Self::Halt(HaltReason::PrecompileError),
@@ -330,7 +344,10 @@ impl From<InstructionResult> for SuccessOrHalt {
             // TODO (EOF) add proper Revert subtype.
             InstructionResult::InvalidEOFInitCode => Self::Revert,
             InstructionResult::FatalExternalError => Self::FatalExternalError,
-            InstructionResult::EOFOpcodeDisabledInLegacy => Self::Halt(HaltReason::OpcodeNotFound),
+            InstructionResult::EOFOpcodeDisabledInLegacy
+            | InstructionResult::LegacyOpcodeDisabledInEOF => {
+                Self::Halt(HaltReason::OpcodeNotFound)
+            }
             InstructionResult::EOFFunctionStackOverflow => {
                 Self::Halt(HaltReason::EOFFunctionStackOverflow)
             }
@@ -347,7 +364,7 @@ impl From<InstructionResult> for SuccessOrHalt {
 
 #[cfg(test)]
 mod tests {
-    use crate::InstructionResult;
+    use crate::{primitives::HaltReason, InstructionResult, SuccessOrHalt};
 
     #[test]
     fn all_results_are_covered() {
@@ -409,6 +426,8 @@ mod tests {
             InstructionResult::CreateContractStartingWithEF,
             InstructionResult::CreateInitCodeSizeLimit,
             InstructionResult::FatalExternalError,
+            InstructionResult::ReturnDataOutOfBounds,
+            InstructionResult::JournalLimitExceeded,
         ];
 
         for result in error_results {
@@ -417,4 +436,144 @@ mod tests {
             assert!(result.is_error());
         }
     }
+
+    /// Every variant, the match in `all_variants_are_listed` fails to compile when one is added.
+    const ALL: [InstructionResult; 43] = [
+        InstructionResult::Continue,
+        InstructionResult::Stop,
+        InstructionResult::Return,
+        InstructionResult::SelfDestruct,
+        InstructionResult::ReturnContract,
+        InstructionResult::Revert,
+        InstructionResult::CallTooDeep,
+        InstructionResult::OutOfFunds,
+        InstructionResult::CreateInitCodeStartingEF00,
+        InstructionResult::InvalidEOFInitCode,
+        InstructionResult::InvalidExtDelegateCallTarget,
+        InstructionResult::CallOrCreate,
+        InstructionResult::OutOfGas,
+        InstructionResult::MemoryOOG,
+        InstructionResult::MemoryLimitOOG,
+        InstructionResult::PrecompileOOG,
+        InstructionResult::InvalidOperandOOG,
+        InstructionResult::OpcodeNotFound,
+        InstructionResult::CallNotAllowedInsideStatic,
+        InstructionResult::StateChangeDuringStaticCall,
+        InstructionResult::InvalidFEOpcode,
+        InstructionResult::InvalidJump,
+        InstructionResult::NotActivated,
+        InstructionResult::StackUnderflow,
+        InstructionResult::StackOverflow,
+        InstructionResult::OutOfOffset,
+        InstructionResult::CreateCollision,
+        InstructionResult::OverflowPayment,
+        InstructionResult::PrecompileError,
+        InstructionResult::NonceOverflow,
+        InstructionResult::CreateContractSizeLimit,
+        InstructionResult::CreateContractStartingWithEF,
+        InstructionResult::CreateInitCodeSizeLimit,
+        InstructionResult::FatalExternalError,
+        InstructionResult::ReturnContractInNotInitEOF,
+        InstructionResult::EOFOpcodeDisabledInLegacy,
+        InstructionResult::EOFFunctionStackOverflow,
+        InstructionResult::EofAuxDataOverflow,
+        InstructionResult::EofAuxDataTooSmall,
+        InstructionResult::InvalidEXTCALLTarget,
+        InstructionResult::LegacyOpcodeDisabledInEOF,
+        InstructionResult::ReturnDataOutOfBounds,
+        InstructionResult::JournalLimitExceeded,
+    ];
+
+    #[test]
+    fn all_variants_are_listed() {
+        for result in ALL {
+            match result {
+                InstructionResult::Continue
+                | InstructionResult::Stop
+                | InstructionResult::Return
+                | InstructionResult::SelfDestruct
+                | InstructionResult::ReturnContract
+                | InstructionResult::Revert
+                | InstructionResult::CallTooDeep
+                | InstructionResult::OutOfFunds
+                | InstructionResult::CreateInitCodeStartingEF00
+                | InstructionResult::InvalidEOFInitCode
+                | InstructionResult::InvalidExtDelegateCallTarget
+                | InstructionResult::CallOrCreate
+                | InstructionResult::OutOfGas
+                | InstructionResult::MemoryOOG
+                | InstructionResult::MemoryLimitOOG
+                | InstructionResult::PrecompileOOG
+                | InstructionResult::InvalidOperandOOG
+                | InstructionResult::OpcodeNotFound
+                | InstructionResult::CallNotAllowedInsideStatic
+                | InstructionResult::StateChangeDuringStaticCall
+                | InstructionResult::InvalidFEOpcode
+                | InstructionResult::InvalidJump
+                | InstructionResult::NotActivated
+                | InstructionResult::StackUnderflow
+                | InstructionResult::StackOverflow
+                | InstructionResult::OutOfOffset
+                | InstructionResult::CreateCollision
+                | InstructionResult::OverflowPayment
+                | InstructionResult::PrecompileError
+                | InstructionResult::NonceOverflow
+                | InstructionResult::CreateContractSizeLimit
+                | InstructionResult::CreateContractStartingWithEF
+                | InstructionResult::CreateInitCodeSizeLimit
+                | InstructionResult::FatalExternalError
+                | InstructionResult::ReturnContractInNotInitEOF
+                | InstructionResult::EOFOpcodeDisabledInLegacy
+                | InstructionResult::EOFFunctionStackOverflow
+                | InstructionResult::EofAuxDataOverflow
+                | InstructionResult::EofAuxDataTooSmall
+                | InstructionResult::InvalidEXTCALLTarget
+                | InstructionResult::LegacyOpcodeDisabledInEOF
+                | InstructionResult::ReturnDataOutOfBounds
+                | InstructionResult::JournalLimitExceeded => {}
+            }
+        }
+    }
+
+    #[test]
+    fn success_or_halt_is_complete() {
+        for result in ALL {
+            let converted = SuccessOrHalt::from(result);
+            let internal = matches!(
+                converted,
+                SuccessOrHalt::Internal(_) | SuccessOrHalt::FatalExternalError
+            );
+            assert_eq!(
+                converted.is_success(),
+                result.is_ok() && !internal,
+                "{result:?}"
+            );
+            if let Some(halt) = converted.to_halt() {
+                assert!(result.is_error() || result.is_revert(), "{result:?}");
+                // Halts convert back to a failed result.
+                let back = InstructionResult::from(halt);
+                assert!(back.is_error() || back.is_revert(), "{result:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn resource_exhaustion_halts() {
+        let halts = [
+            (InstructionResult::OutOfGas, true),
+            (InstructionResult::MemoryLimitOOG, true),
+            (InstructionResult::ReturnDataOutOfBounds, true),
+            (InstructionResult::CallTooDeep, true),
+            (InstructionResult::InvalidJump, false),
+        ];
+        for (result, exhaustion) in halts {
+            let halt = SuccessOrHalt::from(result).to_halt().unwrap();
+            assert_eq!(halt.is_resource_exhaustion(), exhaustion);
+            assert_eq!(InstructionResult::from(halt), result);
+        }
+        assert_eq!(
+            SuccessOrHalt::from(InstructionResult::MemoryLimitOOG),
+            SuccessOrHalt::Halt(HaltReason::MemoryLimitExceeded)
+        );
+    }
 }
@@ -1,30 +1,29 @@
 use super::i256::{i256_div, i256_mod};
 use crate::{
-    gas,
-    primitives::{Spec, U256},
+    primitives::{SpecSelector, U256},
     Host, Interpreter,
 };
 
 pub fn add<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, op1, op2);
     *op2 = op1.wrapping_add(*op2);
 }
 
 pub fn mul<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::LOW);
+    gas!(interpreter, interpreter.gas_schedule.low);
     pop_top!(interpreter, op1, op2);
     *op2 = op1.wrapping_mul(*op2);
 }
 
 pub fn sub<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, op1, op2);
     *op2 = op1.wrapping_sub(*op2);
 }
 
 pub fn div<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::LOW);
+    gas!(interpreter, interpreter.gas_schedule.low);
     pop_top!(interpreter, op1, op2);
     if !op2.is_zero() {
         *op2 = op1.wrapping_div(*op2);
@@ -32,13 +31,13 @@ pub fn div<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
 }
 
 pub fn sdiv<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::LOW);
+    gas!(interpreter, interpreter.gas_schedule.low);
     pop_top!(interpreter, op1, op2);
     *op2 = i256_div(op1, *op2);
 }
 
 pub fn rem<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::LOW);
+    gas!(interpreter, interpreter.gas_schedule.low);
     pop_top!(interpreter, op1, op2);
     if !op2.is_zero() {
         *op2 = op1.wrapping_rem(*op2);
@@ -46,26 +45,26 @@ pub fn rem<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
 }
 
 pub fn smod<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::LOW);
+    gas!(interpreter, interpreter.gas_schedule.low);
     pop_top!(interpreter, op1, op2);
     *op2 = i256_mod(op1, *op2)
 }
 
 pub fn addmod<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::MID);
+    gas!(interpreter, interpreter.gas_schedule.mid);
     pop_top!(interpreter, op1, op2, op3);
     *op3 = op1.add_mod(op2, *op3)
 }
 
 pub fn mulmod<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::MID);
+    gas!(interpreter, interpreter.gas_schedule.mid);
     pop_top!(interpreter, op1, op2, op3);
     *op3 = op1.mul_mod(op2, *op3)
 }
 
-pub fn exp<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, _host: &mut H) {
+pub fn exp<H: Host + ?Sized, SPEC: SpecSelector>(interpreter: &mut Interpreter, _host: &mut H) {
     pop_top!(interpreter, op1, op2);
-    gas_or_fail!(interpreter, gas::exp_cost(SPEC::SPEC_ID, *op2));
+    gas_or_fail!(interpreter, interpreter.gas_schedule.exp_cost(*op2));
     *op2 = op1.pow(*op2);
 }
 
@@ -85,7 +84,7 @@ pub fn exp<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, _host: &
 /// `b == 0` then the yellow paper says the output should start with all zeros, then end with
 /// bits from `b`; this is equal to `y & mask` where `&` is bitwise `AND`.
 pub fn signextend<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::LOW);
+    gas!(interpreter, interpreter.gas_schedule.low);
     pop_top!(interpreter, ext, x);
     // For 31 we also don't need to do anything.
     if ext < U256::from(31) {
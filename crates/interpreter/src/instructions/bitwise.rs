@@ -1,73 +1,72 @@
 use super::i256::i256_cmp;
 use crate::{
-    gas,
-    primitives::{Spec, U256},
+    primitives::{SpecSelector, U256},
     Host, Interpreter,
 };
 use core::cmp::Ordering;
 
 pub fn lt<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, op1, op2);
     *op2 = U256::from(op1 < *op2);
 }
 
 pub fn gt<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, op1, op2);
     *op2 = U256::from(op1 > *op2);
 }
 
 pub fn slt<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, op1, op2);
     *op2 = U256::from(i256_cmp(&op1, op2) == Ordering::Less);
 }
 
 pub fn sgt<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, op1, op2);
     *op2 = U256::from(i256_cmp(&op1, op2) == Ordering::Greater);
 }
 
 pub fn eq<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, op1, op2);
     *op2 = U256::from(op1 == *op2);
 }
 
 pub fn iszero<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, op1);
     *op1 = U256::from(op1.is_zero());
 }
 
 pub fn bitand<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, op1, op2);
     *op2 = op1 & *op2;
 }
 
 pub fn bitor<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, op1, op2);
     *op2 = op1 | *op2;
 }
 
 pub fn bitxor<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, op1, op2);
     *op2 = op1 ^ *op2;
 }
 
 pub fn not<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, op1);
     *op1 = !*op1;
 }
 
 pub fn byte<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, op1, op2);
 
     let o1 = as_usize_saturated!(op1);
@@ -80,9 +79,9 @@ pub fn byte<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
 }
 
 /// EIP-145: Bitwise shifting instructions in EVM
-pub fn shl<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, _host: &mut H) {
+pub fn shl<H: Host + ?Sized, SPEC: SpecSelector>(interpreter: &mut Interpreter, _host: &mut H) {
     check!(interpreter, CONSTANTINOPLE);
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, op1, op2);
     let shift = as_usize_saturated!(op1);
     *op2 = if shift < 256 {
@@ -93,9 +92,9 @@ pub fn shl<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, _host: &
 }
 
 /// EIP-145: Bitwise shifting instructions in EVM
-pub fn shr<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, _host: &mut H) {
+pub fn shr<H: Host + ?Sized, SPEC: SpecSelector>(interpreter: &mut Interpreter, _host: &mut H) {
     check!(interpreter, CONSTANTINOPLE);
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, op1, op2);
     let shift = as_usize_saturated!(op1);
     *op2 = if shift < 256 {
@@ -106,9 +105,9 @@ pub fn shr<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, _host: &
 }
 
 /// EIP-145: Bitwise shifting instructions in EVM
-pub fn sar<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, _host: &mut H) {
+pub fn sar<H: Host + ?Sized, SPEC: SpecSelector>(interpreter: &mut Interpreter, _host: &mut H) {
     check!(interpreter, CONSTANTINOPLE);
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, op1, op2);
 
     let shift = as_usize_saturated!(op1);
@@ -3,10 +3,10 @@ mod call_helpers;
 pub use call_helpers::{calc_call_gas, get_memory_input_and_out_ranges, resize_memory};
 
 use crate::{
-    gas::{self, cost_per_word, EOF_CREATE_GAS, KECCAK256WORD, MIN_CALLEE_GAS},
+    gas::cost_per_word,
     interpreter::Interpreter,
     primitives::{
-        eof::EofHeader, keccak256, Address, BerlinSpec, Bytes, Eof, Spec, SpecId::*, B256, U256,
+        eof::EofHeader, keccak256, Address, Bytes, Eof, SpecId::*, SpecSelector, B256, U256,
     },
     CallInputs, CallScheme, CallValue, CreateInputs, CreateScheme, EOFCreateInputs, Host,
     InstructionResult, InterpreterAction, InterpreterResult, LoadAccountResult, MAX_INITCODE_SIZE,
@@ -18,7 +18,7 @@ use std::boxed::Box;
 pub fn eofcreate<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
     require_eof!(interpreter);
     require_non_staticcall!(interpreter);
-    gas!(interpreter, EOF_CREATE_GAS);
+    gas!(interpreter, interpreter.gas_schedule.eof_create);
     let initcontainer_index = unsafe { *interpreter.instruction_pointer };
     pop!(interpreter, value, salt, data_offset, data_size);
 
@@ -56,7 +56,10 @@ pub fn eofcreate<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H)
     // deduct gas for hash that is needed to calculate address.
     gas_or_fail!(
         interpreter,
-        cost_per_word(sub_container.len() as u64, KECCAK256WORD)
+        cost_per_word(
+            sub_container.len() as u64,
+            interpreter.gas_schedule.keccak256_word
+        )
     );
 
     let created_address = interpreter
@@ -136,11 +139,7 @@ pub fn return_contract<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &
     let result = InstructionResult::ReturnContract;
     interpreter.instruction_result = result;
     interpreter.next_action = crate::InterpreterAction::Return {
-        result: InterpreterResult {
-            output,
-            gas: interpreter.gas,
-            result,
-        },
+        result: InterpreterResult::new(result, output, interpreter.gas),
     };
 }
 
@@ -171,8 +170,8 @@ pub fn extcall_gas_calc<H: Host + ?Sized>(
         return None;
     };
 
-    let call_cost = gas::call_cost(
-        BerlinSpec::SPEC_ID,
+    let call_cost = interpreter.gas_schedule.call_cost(
+        BERLIN,
         transfers_value,
         load_result.is_cold,
         load_result.is_empty,
@@ -189,7 +188,7 @@ pub fn extcall_gas_calc<H: Host + ?Sized>(
     // applied uniformly for all introduced EXT*CALL instructions.
     //
     // If Gas available to callee is less than MIN_CALLEE_GAS trigger light failure (Same as Revert).
-    if gas_limit < MIN_CALLEE_GAS {
+    if gas_limit < interpreter.gas_schedule.min_callee_gas {
         // Push 1 to stack to indicate that call light failed.
         // It is safe to ignore stack overflow error as we already popped multiple values from stack.
         let _ = interpreter.stack_mut().push(U256::from(1));
@@ -218,7 +217,7 @@ pub fn pop_extcall_target_address(interpreter: &mut Interpreter) -> Option<Addre
     Some(Address::from_word(target_address))
 }
 
-pub fn extcall<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn extcall<H: Host + ?Sized, SPEC: SpecSelector>(interpreter: &mut Interpreter, host: &mut H) {
     require_eof!(interpreter);
 
     // pop target address
@@ -254,13 +253,17 @@ pub fn extcall<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host
             scheme: CallScheme::ExtCall,
             is_static: interpreter.is_static,
             is_eof: true,
+            caller_is_eof: true,
             return_memory_offset: 0..0,
         }),
     };
     interpreter.instruction_result = InstructionResult::CallOrCreate;
 }
 
-pub fn extdelegatecall<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn extdelegatecall<H: Host + ?Sized, SPEC: SpecSelector>(
+    interpreter: &mut Interpreter,
+    host: &mut H,
+) {
     require_eof!(interpreter);
 
     // pop target address
@@ -289,6 +292,7 @@ pub fn extdelegatecall<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpret
             scheme: CallScheme::ExtDelegateCall,
             is_static: interpreter.is_static,
             is_eof: true,
+            caller_is_eof: true,
             return_memory_offset: 0..0,
         }),
     };
@@ -324,13 +328,14 @@ pub fn extstaticcall<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut
             scheme: CallScheme::ExtStaticCall,
             is_static: true,
             is_eof: true,
+            caller_is_eof: true,
             return_memory_offset: 0..0,
         }),
     };
     interpreter.instruction_result = InstructionResult::CallOrCreate;
 }
 
-pub fn create<const IS_CREATE2: bool, H: Host + ?Sized, SPEC: Spec>(
+pub fn create<const IS_CREATE2: bool, H: Host + ?Sized, SPEC: SpecSelector>(
     interpreter: &mut Interpreter,
     host: &mut H,
 ) {
@@ -347,7 +352,7 @@ pub fn create<const IS_CREATE2: bool, H: Host + ?Sized, SPEC: Spec>(
     let mut code = Bytes::new();
     if len != 0 {
         // EIP-3860: Limit and meter initcode
-        if SPEC::enabled(SHANGHAI) {
+        if spec_id!(interpreter).is_enabled_in(SHANGHAI) {
             // Limit is set as double of max contract bytecode size
             let max_initcode_size = host
                 .env()
@@ -359,7 +364,10 @@ pub fn create<const IS_CREATE2: bool, H: Host + ?Sized, SPEC: Spec>(
                 interpreter.instruction_result = InstructionResult::CreateInitCodeSizeLimit;
                 return;
             }
-            gas!(interpreter, gas::initcode_cost(len as u64));
+            gas!(
+                interpreter,
+                interpreter.gas_schedule.initcode_cost(len as u64)
+            );
         }
 
         let code_offset = as_usize_or_fail!(interpreter, code_offset);
@@ -371,17 +379,22 @@ pub fn create<const IS_CREATE2: bool, H: Host + ?Sized, SPEC: Spec>(
     let scheme = if IS_CREATE2 {
         pop!(interpreter, salt);
         // SAFETY: len is reasonable in size as gas for it is already deducted.
-        gas_or_fail!(interpreter, gas::create2_cost(len.try_into().unwrap()));
+        gas_or_fail!(
+            interpreter,
+            interpreter
+                .gas_schedule
+                .create2_cost(len.try_into().unwrap())
+        );
         CreateScheme::Create2 { salt }
     } else {
-        gas!(interpreter, gas::CREATE);
+        gas!(interpreter, interpreter.gas_schedule.create);
         CreateScheme::Create
     };
 
     let mut gas_limit = interpreter.gas().remaining();
 
     // EIP-150: Gas cost changes for IO-heavy operations
-    if SPEC::enabled(TANGERINE) {
+    if spec_id!(interpreter).is_enabled_in(TANGERINE) {
         // take remaining gas and deduce l64 part of it.
         gas_limit -= gas_limit / 64
     }
@@ -395,12 +408,13 @@ pub fn create<const IS_CREATE2: bool, H: Host + ?Sized, SPEC: Spec>(
             value,
             init_code: code,
             gas_limit,
+            caller_is_eof: interpreter.is_eof,
         }),
     };
     interpreter.instruction_result = InstructionResult::CallOrCreate;
 }
 
-pub fn call<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn call<H: Host + ?Sized, SPEC: SpecSelector>(interpreter: &mut Interpreter, host: &mut H) {
     pop!(interpreter, local_gas_limit);
     pop_address!(interpreter, to);
     // max gas limit is not possible in real ethereum situation.
@@ -435,7 +449,7 @@ pub fn call<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &
 
     // add call stipend if there is value to be transferred.
     if has_transfer {
-        gas_limit = gas_limit.saturating_add(gas::CALL_STIPEND);
+        gas_limit = gas_limit.saturating_add(interpreter.gas_schedule.call_stipend);
     }
 
     // Call host to interact with target contract
@@ -450,13 +464,17 @@ pub fn call<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &
             scheme: CallScheme::Call,
             is_static: interpreter.is_static,
             is_eof: false,
+            caller_is_eof: interpreter.is_eof,
             return_memory_offset,
         }),
     };
     interpreter.instruction_result = InstructionResult::CallOrCreate;
 }
 
-pub fn call_code<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn call_code<H: Host + ?Sized, SPEC: SpecSelector>(
+    interpreter: &mut Interpreter,
+    host: &mut H,
+) {
     pop!(interpreter, local_gas_limit);
     pop_address!(interpreter, to);
     // max gas limit is not possible in real ethereum situation.
@@ -486,7 +504,7 @@ pub fn call_code<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, ho
 
     // add call stipend if there is value to be transferred.
     if !value.is_zero() {
-        gas_limit = gas_limit.saturating_add(gas::CALL_STIPEND);
+        gas_limit = gas_limit.saturating_add(interpreter.gas_schedule.call_stipend);
     }
 
     // Call host to interact with target contract
@@ -501,13 +519,17 @@ pub fn call_code<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, ho
             scheme: CallScheme::CallCode,
             is_static: interpreter.is_static,
             is_eof: false,
+            caller_is_eof: interpreter.is_eof,
             return_memory_offset,
         }),
     };
     interpreter.instruction_result = InstructionResult::CallOrCreate;
 }
 
-pub fn delegate_call<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn delegate_call<H: Host + ?Sized, SPEC: SpecSelector>(
+    interpreter: &mut Interpreter,
+    host: &mut H,
+) {
     check!(interpreter, HOMESTEAD);
     pop!(interpreter, local_gas_limit);
     pop_address!(interpreter, to);
@@ -542,13 +564,17 @@ pub fn delegate_call<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter
             scheme: CallScheme::DelegateCall,
             is_static: interpreter.is_static,
             is_eof: false,
+            caller_is_eof: interpreter.is_eof,
             return_memory_offset,
         }),
     };
     interpreter.instruction_result = InstructionResult::CallOrCreate;
 }
 
-pub fn static_call<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn static_call<H: Host + ?Sized, SPEC: SpecSelector>(
+    interpreter: &mut Interpreter,
+    host: &mut H,
+) {
     check!(interpreter, BYZANTIUM);
     pop!(interpreter, local_gas_limit);
     pop_address!(interpreter, to);
@@ -583,6 +609,7 @@ pub fn static_call<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter,
             scheme: CallScheme::StaticCall,
             is_static: true,
             is_eof: false,
+            caller_is_eof: interpreter.is_eof,
             return_memory_offset,
         }),
     };
@@ -1,7 +1,6 @@
 use crate::{
-    gas,
     interpreter::Interpreter,
-    primitives::{Bytes, Spec, SpecId::*, U256},
+    primitives::{Bytes, SpecId::*, SpecSelector, U256},
 };
 use core::{cmp::min, ops::Range};
 
@@ -42,19 +41,24 @@ pub fn resize_memory(
 }
 
 #[inline]
-pub fn calc_call_gas<SPEC: Spec>(
+pub fn calc_call_gas<SPEC: SpecSelector>(
     interpreter: &mut Interpreter,
     is_cold: bool,
     has_transfer: bool,
     new_account_accounting: bool,
     local_gas_limit: u64,
 ) -> Option<u64> {
-    let call_cost = gas::call_cost(SPEC::SPEC_ID, has_transfer, is_cold, new_account_accounting);
+    let call_cost = interpreter.gas_schedule.call_cost(
+        spec_id!(interpreter),
+        has_transfer,
+        is_cold,
+        new_account_accounting,
+    );
 
     gas!(interpreter, call_cost, None);
 
     // EIP-150: Gas cost changes for IO-heavy operations
-    let gas_limit = if SPEC::enabled(TANGERINE) {
+    let gas_limit = if spec_id!(interpreter).is_enabled_in(TANGERINE) {
         // take l64 part of gas_limit
         min(
             interpreter.gas().remaining_63_of_64_parts(),
@@ -1,13 +1,12 @@
 use super::utility::{read_i16, read_u16};
 use crate::{
-    gas,
-    primitives::{Bytes, Spec, U256},
+    primitives::{Bytes, SpecSelector, U256},
     Host, InstructionResult, Interpreter, InterpreterResult,
 };
 
 pub fn rjump<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
     require_eof!(interpreter);
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     let offset = unsafe { read_i16(interpreter.instruction_pointer) } as isize;
     // In spec it is +3 but pointer is already incremented in
     // `Interpreter::step` so for revm is +2.
@@ -16,7 +15,7 @@ pub fn rjump<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
 
 pub fn rjumpi<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
     require_eof!(interpreter);
-    gas!(interpreter, gas::CONDITION_JUMP_GAS);
+    gas!(interpreter, interpreter.gas_schedule.condition_jump);
     pop!(interpreter, condition);
     // In spec it is +3 but pointer is already incremented in
     // `Interpreter::step` so for revm is +2.
@@ -30,7 +29,7 @@ pub fn rjumpi<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
 
 pub fn rjumpv<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
     require_eof!(interpreter);
-    gas!(interpreter, gas::CONDITION_JUMP_GAS);
+    gas!(interpreter, interpreter.gas_schedule.condition_jump);
     pop!(interpreter, case);
     let case = as_isize_saturated!(case);
 
@@ -54,13 +53,13 @@ pub fn rjumpv<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
 }
 
 pub fn jump<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::MID);
+    gas!(interpreter, interpreter.gas_schedule.mid);
     pop!(interpreter, target);
     jump_inner(interpreter, target);
 }
 
 pub fn jumpi<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::HIGH);
+    gas!(interpreter, interpreter.gas_schedule.high);
     pop!(interpreter, target, cond);
     if !cond.is_zero() {
         jump_inner(interpreter, target);
@@ -79,12 +78,12 @@ fn jump_inner(interpreter: &mut Interpreter, target: U256) {
 }
 
 pub fn jumpdest_or_nop<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::JUMPDEST);
+    gas!(interpreter, interpreter.gas_schedule.jumpdest);
 }
 
 pub fn callf<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
     require_eof!(interpreter);
-    gas!(interpreter, gas::LOW);
+    gas!(interpreter, interpreter.gas_schedule.low);
 
     let idx = unsafe { read_u16(interpreter.instruction_pointer) } as usize;
 
@@ -116,7 +115,7 @@ pub fn callf<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
 
 pub fn retf<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
     require_eof!(interpreter);
-    gas!(interpreter, gas::RETF_GAS);
+    gas!(interpreter, interpreter.gas_schedule.retf);
 
     let Some(fframe) = interpreter.function_stack.pop() else {
         panic!("Expected function frame")
@@ -127,7 +126,7 @@ pub fn retf<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
 
 pub fn jumpf<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
     require_eof!(interpreter);
-    gas!(interpreter, gas::LOW);
+    gas!(interpreter, interpreter.gas_schedule.low);
 
     let idx = unsafe { read_u16(interpreter.instruction_pointer) } as usize;
 
@@ -148,7 +147,7 @@ pub fn jumpf<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
 }
 
 pub fn pc<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     // - 1 because we have already advanced the instruction pointer in `Interpreter::step`
     push!(interpreter, U256::from(interpreter.program_counter() - 1));
 }
@@ -160,20 +159,16 @@ fn return_inner(interpreter: &mut Interpreter, instruction_result: InstructionRe
     pop!(interpreter, offset, len);
     let len = as_usize_or_fail!(interpreter, len);
     // important: offset must be ignored if len is zeros
-    let mut output = Bytes::default();
     if len != 0 {
         let offset = as_usize_or_fail!(interpreter, offset);
         resize_memory!(interpreter, offset, len);
 
-        output = interpreter.shared_memory.slice(offset, len).to_vec().into()
+        // the output is taken from the memory when the interpreter stops.
+        interpreter.return_range = Some(offset..offset + len);
     }
     interpreter.instruction_result = instruction_result;
     interpreter.next_action = crate::InterpreterAction::Return {
-        result: InterpreterResult {
-            output,
-            gas: interpreter.gas,
-            result: instruction_result,
-        },
+        result: InterpreterResult::new(instruction_result, Bytes::new(), interpreter.gas),
     };
 }
 
@@ -182,7 +177,7 @@ pub fn ret<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
 }
 
 /// EIP-140: REVERT instruction
-pub fn revert<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, _host: &mut H) {
+pub fn revert<H: Host + ?Sized, SPEC: SpecSelector>(interpreter: &mut Interpreter, _host: &mut H) {
     check!(interpreter, BYZANTIUM);
     return_inner(interpreter, InstructionResult::Revert);
 }
@@ -1,14 +1,11 @@
 use crate::{
-    gas::{cost_per_word, BASE, DATA_LOAD_GAS, VERYLOW},
-    instructions::utility::read_u16,
-    interpreter::Interpreter,
-    primitives::U256,
-    Host,
+    gas::cost_per_word, instructions::utility::read_u16, interpreter::Interpreter,
+    primitives::U256, Host,
 };
 
 pub fn data_load<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
     require_eof!(interpreter);
-    gas!(interpreter, DATA_LOAD_GAS);
+    gas!(interpreter, interpreter.gas_schedule.data_load);
     pop_top!(interpreter, offset);
 
     let offset_usize = as_usize_saturated!(offset);
@@ -28,7 +25,7 @@ pub fn data_load<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H)
 
 pub fn data_loadn<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
     require_eof!(interpreter);
-    gas!(interpreter, VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     let offset = unsafe { read_u16(interpreter.instruction_pointer) } as usize;
 
     let slice = interpreter
@@ -49,7 +46,7 @@ pub fn data_loadn<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H
 
 pub fn data_size<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
     require_eof!(interpreter);
-    gas!(interpreter, BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     let data_size = interpreter.eof().expect("eof").header.data_size;
 
     push!(interpreter, U256::from(data_size));
@@ -57,7 +54,7 @@ pub fn data_size<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H)
 
 pub fn data_copy<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
     require_eof!(interpreter);
-    gas!(interpreter, VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop!(interpreter, mem_offset, offset, size);
 
     // sizes more than u64::MAX will spend all the gas in memory resize.
@@ -70,7 +67,10 @@ pub fn data_copy<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H)
     let mem_offset = as_usize_or_fail!(interpreter, mem_offset);
     resize_memory!(interpreter, mem_offset, size);
 
-    gas_or_fail!(interpreter, cost_per_word(size as u64, VERYLOW));
+    gas_or_fail!(
+        interpreter,
+        cost_per_word(size as u64, interpreter.gas_schedule.copy)
+    );
 
     let offset = as_usize_saturated!(offset);
     let data = interpreter.contract.bytecode.eof().expect("eof").data();
@@ -1,13 +1,12 @@
 use crate::{
-    gas::{self, warm_cold_cost},
     interpreter::Interpreter,
-    primitives::{Bytes, Log, LogData, Spec, SpecId::*, B256, U256},
+    primitives::{Bytes, Log, LogData, SpecId::*, SpecSelector, B256, U256},
     Host, InstructionResult, SStoreResult,
 };
 use core::cmp::min;
 use std::vec::Vec;
 
-pub fn balance<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn balance<H: Host + ?Sized, SPEC: SpecSelector>(interpreter: &mut Interpreter, host: &mut H) {
     pop_address!(interpreter, address);
     let Some((balance, is_cold)) = host.balance(address) else {
         interpreter.instruction_result = InstructionResult::FatalExternalError;
@@ -15,24 +14,20 @@ pub fn balance<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host
     };
     gas!(
         interpreter,
-        if SPEC::enabled(BERLIN) {
-            warm_cold_cost(is_cold)
-        } else if SPEC::enabled(ISTANBUL) {
-            // EIP-1884: Repricing for trie-size-dependent opcodes
-            700
-        } else if SPEC::enabled(TANGERINE) {
-            400
-        } else {
-            20
-        }
+        interpreter
+            .gas_schedule
+            .balance_cost(spec_id!(interpreter), is_cold)
     );
     push!(interpreter, balance);
 }
 
 /// EIP-1884: Repricing for trie-size-dependent opcodes
-pub fn selfbalance<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn selfbalance<H: Host + ?Sized, SPEC: SpecSelector>(
+    interpreter: &mut Interpreter,
+    host: &mut H,
+) {
     check!(interpreter, ISTANBUL);
-    gas!(interpreter, gas::LOW);
+    gas!(interpreter, interpreter.gas_schedule.low);
     let Some((balance, _)) = host.balance(interpreter.contract.target_address) else {
         interpreter.instruction_result = InstructionResult::FatalExternalError;
         return;
@@ -40,42 +35,49 @@ pub fn selfbalance<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter,
     push!(interpreter, balance);
 }
 
-pub fn extcodesize<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn extcodesize<H: Host + ?Sized, SPEC: SpecSelector>(
+    interpreter: &mut Interpreter,
+    host: &mut H,
+) {
     pop_address!(interpreter, address);
     let Some((code, is_cold)) = host.code(address) else {
         interpreter.instruction_result = InstructionResult::FatalExternalError;
         return;
     };
-    if SPEC::enabled(BERLIN) {
-        gas!(interpreter, warm_cold_cost(is_cold));
-    } else if SPEC::enabled(TANGERINE) {
-        gas!(interpreter, 700);
-    } else {
-        gas!(interpreter, 20);
-    }
+    gas!(
+        interpreter,
+        interpreter
+            .gas_schedule
+            .extcodesize_cost(spec_id!(interpreter), is_cold)
+    );
 
     push!(interpreter, U256::from(code.len()));
 }
 
 /// EIP-1052: EXTCODEHASH opcode
-pub fn extcodehash<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn extcodehash<H: Host + ?Sized, SPEC: SpecSelector>(
+    interpreter: &mut Interpreter,
+    host: &mut H,
+) {
     check!(interpreter, CONSTANTINOPLE);
     pop_address!(interpreter, address);
     let Some((code_hash, is_cold)) = host.code_hash(address) else {
         interpreter.instruction_result = InstructionResult::FatalExternalError;
         return;
     };
-    if SPEC::enabled(BERLIN) {
-        gas!(interpreter, warm_cold_cost(is_cold));
-    } else if SPEC::enabled(ISTANBUL) {
-        gas!(interpreter, 700);
-    } else {
-        gas!(interpreter, 400);
-    }
+    gas!(
+        interpreter,
+        interpreter
+            .gas_schedule
+            .extcodehash_cost(spec_id!(interpreter), is_cold)
+    );
     push_b256!(interpreter, code_hash);
 }
 
-pub fn extcodecopy<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn extcodecopy<H: Host + ?Sized, SPEC: SpecSelector>(
+    interpreter: &mut Interpreter,
+    host: &mut H,
+) {
     pop_address!(interpreter, address);
     pop!(interpreter, memory_offset, code_offset, len_u256);
 
@@ -87,7 +89,9 @@ pub fn extcodecopy<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter,
     let len = as_usize_or_fail!(interpreter, len_u256);
     gas_or_fail!(
         interpreter,
-        gas::extcodecopy_cost(SPEC::SPEC_ID, len as u64, is_cold)
+        interpreter
+            .gas_schedule
+            .extcodecopy_cost(spec_id!(interpreter), len as u64, is_cold)
     );
     if len == 0 {
         return;
@@ -102,29 +106,47 @@ pub fn extcodecopy<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter,
         .set_data(memory_offset, code_offset, len, &code);
 }
 
-pub fn blockhash<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
-    gas!(interpreter, gas::BLOCKHASH);
+pub fn blockhash<H: Host + ?Sized, SPEC: SpecSelector>(
+    interpreter: &mut Interpreter,
+    host: &mut H,
+) {
+    gas!(interpreter, interpreter.gas_schedule.blockhash);
     pop_top!(interpreter, number);
 
-    let number_u64 = as_u64_saturated!(number);
-    let Some(hash) = host.block_hash(number_u64) else {
+    let requested_number = as_u64_saturated!(number);
+    let block_number = as_u64_saturated!(host.env().block.number);
+
+    // Only the hashes of the blocks in the window before the current block are available,
+    // the current block and future blocks are zero.
+    let diff = block_number.saturating_sub(requested_number);
+    if requested_number >= block_number || diff > host.env().cfg.block_hash_window() {
+        *number = U256::ZERO;
+        return;
+    }
+
+    let Some(hash) = host.block_hash(requested_number) else {
         interpreter.instruction_result = InstructionResult::FatalExternalError;
         return;
     };
     *number = U256::from_be_bytes(hash.0);
 }
 
-pub fn sload<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn sload<H: Host + ?Sized, SPEC: SpecSelector>(interpreter: &mut Interpreter, host: &mut H) {
     pop_top!(interpreter, index);
     let Some((value, is_cold)) = host.sload(interpreter.contract.target_address, *index) else {
         interpreter.instruction_result = InstructionResult::FatalExternalError;
         return;
     };
-    gas!(interpreter, gas::sload_cost(SPEC::SPEC_ID, is_cold));
+    gas!(
+        interpreter,
+        interpreter
+            .gas_schedule
+            .sload_cost(spec_id!(interpreter), is_cold)
+    );
     *index = value;
 }
 
-pub fn sstore<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn sstore<H: Host + ?Sized, SPEC: SpecSelector>(interpreter: &mut Interpreter, host: &mut H) {
     require_non_staticcall!(interpreter);
 
     pop!(interpreter, index, value);
@@ -140,31 +162,45 @@ pub fn sstore<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host:
     };
     gas_or_fail!(interpreter, {
         let remaining_gas = interpreter.gas.remaining();
-        gas::sstore_cost(SPEC::SPEC_ID, original, old, new, remaining_gas, is_cold)
+        interpreter.gas_schedule.sstore_cost(
+            spec_id!(interpreter),
+            original,
+            old,
+            new,
+            remaining_gas,
+            is_cold,
+        )
     });
     refund!(
         interpreter,
-        gas::sstore_refund(SPEC::SPEC_ID, original, old, new)
+        interpreter
+            .gas_schedule
+            .sstore_refund(spec_id!(interpreter), original, old, new)
     );
 }
 
 /// EIP-1153: Transient storage opcodes
 /// Store value to transient storage
-pub fn tstore<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn tstore<H: Host + ?Sized, SPEC: SpecSelector>(interpreter: &mut Interpreter, host: &mut H) {
     check!(interpreter, CANCUN);
     require_non_staticcall!(interpreter);
-    gas!(interpreter, gas::WARM_STORAGE_READ_COST);
+    gas!(interpreter, interpreter.gas_schedule.warm_storage_read);
 
     pop!(interpreter, index, value);
 
-    host.tstore(interpreter.contract.target_address, index, value);
+    if host
+        .tstore(interpreter.contract.target_address, index, value)
+        .is_none()
+    {
+        interpreter.instruction_result = InstructionResult::FatalExternalError;
+    }
 }
 
 /// EIP-1153: Transient storage opcodes
 /// Load value from transient storage
-pub fn tload<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn tload<H: Host + ?Sized, SPEC: SpecSelector>(interpreter: &mut Interpreter, host: &mut H) {
     check!(interpreter, CANCUN);
-    gas!(interpreter, gas::WARM_STORAGE_READ_COST);
+    gas!(interpreter, interpreter.gas_schedule.warm_storage_read);
 
     pop_top!(interpreter, index);
 
@@ -176,7 +212,10 @@ pub fn log<const N: usize, H: Host + ?Sized>(interpreter: &mut Interpreter, host
 
     pop!(interpreter, offset, len);
     let len = as_usize_or_fail!(interpreter, len);
-    gas_or_fail!(interpreter, gas::log_cost(N as u8, len as u64));
+    gas_or_fail!(
+        interpreter,
+        interpreter.gas_schedule.log_cost(N as u8, len as u64)
+    );
     let data = if len == 0 {
         Bytes::new()
     } else {
@@ -201,10 +240,15 @@ pub fn log<const N: usize, H: Host + ?Sized>(interpreter: &mut Interpreter, host
         data: LogData::new(topics, data).expect("LogData should have <=4 topics"),
     };
 
-    host.log(log);
+    if host.log(log).is_none() {
+        interpreter.instruction_result = InstructionResult::FatalExternalError;
+    }
 }
 
-pub fn selfdestruct<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn selfdestruct<H: Host + ?Sized, SPEC: SpecSelector>(
+    interpreter: &mut Interpreter,
+    host: &mut H,
+) {
     require_non_staticcall!(interpreter);
     pop_address!(interpreter, target);
 
@@ -214,10 +258,15 @@ pub fn selfdestruct<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter,
     };
 
     // EIP-3529: Reduction in refunds
-    if !SPEC::enabled(LONDON) && !res.previously_destroyed {
-        refund!(interpreter, gas::SELFDESTRUCT)
+    if !spec_id!(interpreter).is_enabled_in(LONDON) && !res.previously_destroyed {
+        refund!(interpreter, interpreter.gas_schedule.selfdestruct_refund)
     }
-    gas!(interpreter, gas::selfdestruct_cost(SPEC::SPEC_ID, res));
+    gas!(
+        interpreter,
+        interpreter
+            .gas_schedule
+            .selfdestruct_cost(spec_id!(interpreter), res)
+    );
 
     interpreter.instruction_result = InstructionResult::SelfDestruct;
 }
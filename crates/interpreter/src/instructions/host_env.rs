@@ -1,66 +1,73 @@
 use crate::{
-    gas,
-    primitives::{Spec, SpecId::*, U256},
+    primitives::{SpecId::*, SpecSelector, U256},
     Host, Interpreter,
 };
 
 /// EIP-1344: ChainID opcode
-pub fn chainid<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn chainid<H: Host + ?Sized, SPEC: SpecSelector>(interpreter: &mut Interpreter, host: &mut H) {
     check!(interpreter, ISTANBUL);
-    gas!(interpreter, gas::BASE);
-    push!(interpreter, U256::from(host.env().cfg.chain_id));
+    gas!(interpreter, interpreter.gas_schedule.base);
+    push!(interpreter, U256::from(host.env().cfg.execution_chain_id()));
 }
 
 pub fn coinbase<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut H) {
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     push_b256!(interpreter, host.env().block.coinbase.into_word());
 }
 
 pub fn timestamp<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut H) {
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     push!(interpreter, host.env().block.timestamp);
 }
 
 pub fn block_number<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut H) {
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     push!(interpreter, host.env().block.number);
 }
 
-pub fn difficulty<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
-    gas!(interpreter, gas::BASE);
-    if SPEC::enabled(MERGE) {
-        push_b256!(interpreter, host.env().block.prevrandao.unwrap());
-    } else {
-        push!(interpreter, host.env().block.difficulty);
-    }
+pub fn difficulty<H: Host + ?Sized, SPEC: SpecSelector>(
+    interpreter: &mut Interpreter,
+    host: &mut H,
+) {
+    gas!(interpreter, interpreter.gas_schedule.base);
+    // `prevrandao` is checked to be set for the merge in `Env::validate_block_env`.
+    let value = host
+        .env()
+        .block
+        .difficulty_opcode_value(spec_id!(interpreter))
+        .unwrap();
+    push!(interpreter, value);
 }
 
 pub fn gaslimit<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut H) {
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     push!(interpreter, host.env().block.gas_limit);
 }
 
 pub fn gasprice<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut H) {
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     push!(interpreter, host.env().effective_gas_price());
 }
 
 /// EIP-3198: BASEFEE opcode
-pub fn basefee<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn basefee<H: Host + ?Sized, SPEC: SpecSelector>(interpreter: &mut Interpreter, host: &mut H) {
     check!(interpreter, LONDON);
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     push!(interpreter, host.env().block.basefee);
 }
 
 pub fn origin<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut H) {
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     push_b256!(interpreter, host.env().tx.caller.into_word());
 }
 
 // EIP-4844: Shard Blob Transactions
-pub fn blob_hash<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn blob_hash<H: Host + ?Sized, SPEC: SpecSelector>(
+    interpreter: &mut Interpreter,
+    host: &mut H,
+) {
     check!(interpreter, CANCUN);
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, index);
     let i = as_usize_saturated!(index);
     *index = match host.env().tx.blob_hashes.get(i) {
@@ -70,9 +77,12 @@ pub fn blob_hash<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, ho
 }
 
 /// EIP-7516: BLOBBASEFEE opcode
-pub fn blob_basefee<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn blob_basefee<H: Host + ?Sized, SPEC: SpecSelector>(
+    interpreter: &mut Interpreter,
+    host: &mut H,
+) {
     check!(interpreter, CANCUN);
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     push!(
         interpreter,
         U256::from(host.env().block.get_blob_gasprice().unwrap_or_default())
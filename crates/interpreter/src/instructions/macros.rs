@@ -33,14 +33,22 @@ macro_rules! require_init_eof {
     };
 }
 
+/// Returns the spec ID selected by `SPEC` for the running interpreter.
+///
+/// For a [`Spec`](crate::primitives::Spec) this is a constant, for
+/// [`RuntimeSpec`](crate::primitives::RuntimeSpec) it is read from the interpreter.
+#[macro_export]
+macro_rules! spec_id {
+    ($interp:expr) => {
+        <SPEC as $crate::primitives::SpecSelector>::resolve($interp.spec_id)
+    };
+}
+
 /// Check if the `SPEC` is enabled, and fail the instruction if it is not.
 #[macro_export]
 macro_rules! check {
     ($interp:expr, $min:ident) => {
-        if const {
-            !<SPEC as $crate::primitives::Spec>::SPEC_ID
-                .is_enabled_in($crate::primitives::SpecId::$min)
-        } {
+        if !$crate::spec_id!($interp).is_enabled_in($crate::primitives::SpecId::$min) {
             $interp.instruction_result = $crate::InstructionResult::NotActivated;
             return;
         }
@@ -103,6 +111,7 @@ macro_rules! resize_memory {
             if !$crate::interpreter::resize_memory(
                 &mut $interp.shared_memory,
                 &mut $interp.gas,
+                &$interp.gas_schedule,
                 new_size,
             ) {
                 $interp.instruction_result = $crate::InstructionResult::MemoryOOG;
@@ -127,21 +136,21 @@ macro_rules! pop_address {
 #[macro_export]
 macro_rules! pop_address_ret {
     ($interp:expr, $x1:ident, $ret:expr) => {
-        if $interp.stack.len() < 1 {
+        if !$interp.contract.is_stack_verified() && $interp.stack.len() < 1 {
             $interp.instruction_result = $crate::InstructionResult::StackUnderflow;
             return $ret;
         }
-        // SAFETY: Length is checked above.
+        // SAFETY: Length is checked above or statically verified.
         let $x1 = $crate::primitives::Address::from_word($crate::primitives::B256::from(unsafe {
             $interp.stack.pop_unsafe()
         }));
     };
     ($interp:expr, $x1:ident, $x2:ident, $ret:expr) => {
-        if $interp.stack.len() < 2 {
+        if !$interp.contract.is_stack_verified() && $interp.stack.len() < 2 {
             $interp.instruction_result = $crate::InstructionResult::StackUnderflow;
             return $ret;
         }
-        // SAFETY: Length is checked above.
+        // SAFETY: Length is checked above or statically verified.
         let $x1 = $crate::primitives::Address::from_word($crate::primitives::B256::from(unsafe {
             $interp.stack.pop_unsafe()
         }));
@@ -176,43 +185,43 @@ macro_rules! pop {
 #[macro_export]
 macro_rules! pop_ret {
     ($interp:expr, $x1:ident, $ret:expr) => {
-        if $interp.stack.len() < 1 {
+        if !$interp.contract.is_stack_verified() && $interp.stack.len() < 1 {
             $interp.instruction_result = $crate::InstructionResult::StackUnderflow;
             return $ret;
         }
-        // SAFETY: Length is checked above.
+        // SAFETY: Length is checked above or statically verified.
         let $x1 = unsafe { $interp.stack.pop_unsafe() };
     };
     ($interp:expr, $x1:ident, $x2:ident, $ret:expr) => {
-        if $interp.stack.len() < 2 {
+        if !$interp.contract.is_stack_verified() && $interp.stack.len() < 2 {
             $interp.instruction_result = $crate::InstructionResult::StackUnderflow;
             return $ret;
         }
-        // SAFETY: Length is checked above.
+        // SAFETY: Length is checked above or statically verified.
         let ($x1, $x2) = unsafe { $interp.stack.pop2_unsafe() };
     };
     ($interp:expr, $x1:ident, $x2:ident, $x3:ident, $ret:expr) => {
-        if $interp.stack.len() < 3 {
+        if !$interp.contract.is_stack_verified() && $interp.stack.len() < 3 {
             $interp.instruction_result = $crate::InstructionResult::StackUnderflow;
             return $ret;
         }
-        // SAFETY: Length is checked above.
+        // SAFETY: Length is checked above or statically verified.
         let ($x1, $x2, $x3) = unsafe { $interp.stack.pop3_unsafe() };
     };
     ($interp:expr, $x1:ident, $x2:ident, $x3:ident, $x4:ident, $ret:expr) => {
-        if $interp.stack.len() < 4 {
+        if !$interp.contract.is_stack_verified() && $interp.stack.len() < 4 {
             $interp.instruction_result = $crate::InstructionResult::StackUnderflow;
             return $ret;
         }
-        // SAFETY: Length is checked above.
+        // SAFETY: Length is checked above or statically verified.
         let ($x1, $x2, $x3, $x4) = unsafe { $interp.stack.pop4_unsafe() };
     };
     ($interp:expr, $x1:ident, $x2:ident, $x3:ident, $x4:ident, $x5:ident, $ret:expr) => {
-        if $interp.stack.len() < 5 {
+        if !$interp.contract.is_stack_verified() && $interp.stack.len() < 5 {
             $interp.instruction_result = $crate::InstructionResult::StackUnderflow;
             return $ret;
         }
-        // SAFETY: Length is checked above.
+        // SAFETY: Length is checked above or statically verified.
         let ($x1, $x2, $x3, $x4, $x5) = unsafe { $interp.stack.pop5_unsafe() };
     };
 }
@@ -222,27 +231,27 @@ macro_rules! pop_ret {
 #[macro_export]
 macro_rules! pop_top {
     ($interp:expr, $x1:ident) => {
-        if $interp.stack.len() < 1 {
+        if !$interp.contract.is_stack_verified() && $interp.stack.len() < 1 {
             $interp.instruction_result = $crate::InstructionResult::StackUnderflow;
             return;
         }
-        // SAFETY: Length is checked above.
+        // SAFETY: Length is checked above or statically verified.
         let $x1 = unsafe { $interp.stack.top_unsafe() };
     };
     ($interp:expr, $x1:ident, $x2:ident) => {
-        if $interp.stack.len() < 2 {
+        if !$interp.contract.is_stack_verified() && $interp.stack.len() < 2 {
             $interp.instruction_result = $crate::InstructionResult::StackUnderflow;
             return;
         }
-        // SAFETY: Length is checked above.
+        // SAFETY: Length is checked above or statically verified.
         let ($x1, $x2) = unsafe { $interp.stack.pop_top_unsafe() };
     };
     ($interp:expr, $x1:ident, $x2:ident, $x3:ident) => {
-        if $interp.stack.len() < 3 {
+        if !$interp.contract.is_stack_verified() && $interp.stack.len() < 3 {
             $interp.instruction_result = $crate::InstructionResult::StackUnderflow;
             return;
         }
-        // SAFETY: Length is checked above.
+        // SAFETY: Length is checked above or statically verified.
         let ($x1, $x2, $x3) = unsafe { $interp.stack.pop2_top_unsafe() };
     };
 }
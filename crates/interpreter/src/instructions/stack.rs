@@ -1,11 +1,10 @@
 use crate::{
-    gas,
-    primitives::{Spec, U256},
+    primitives::{SpecSelector, U256},
     Host, Interpreter,
 };
 
 pub fn pop<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     if let Err(result) = interpreter.stack.pop() {
         interpreter.instruction_result = result;
     }
@@ -14,16 +13,16 @@ pub fn pop<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
 /// EIP-3855: PUSH0 instruction
 ///
 /// Introduce a new instruction which pushes the constant value 0 onto the stack.
-pub fn push0<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, _host: &mut H) {
+pub fn push0<H: Host + ?Sized, SPEC: SpecSelector>(interpreter: &mut Interpreter, _host: &mut H) {
     check!(interpreter, SHANGHAI);
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     if let Err(result) = interpreter.stack.push(U256::ZERO) {
         interpreter.instruction_result = result;
     }
 }
 
 pub fn push<const N: usize, H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     // SAFETY: In analysis we append trailing bytes to the bytecode so that this is safe to do
     // without bounds checking.
     let ip = interpreter.instruction_pointer;
@@ -38,14 +37,14 @@ pub fn push<const N: usize, H: Host + ?Sized>(interpreter: &mut Interpreter, _ho
 }
 
 pub fn dup<const N: usize, H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     if let Err(result) = interpreter.stack.dup(N) {
         interpreter.instruction_result = result;
     }
 }
 
 pub fn swap<const N: usize, H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     if let Err(result) = interpreter.stack.swap(N) {
         interpreter.instruction_result = result;
     }
@@ -53,7 +52,7 @@ pub fn swap<const N: usize, H: Host + ?Sized>(interpreter: &mut Interpreter, _ho
 
 pub fn dupn<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
     require_eof!(interpreter);
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     let imm = unsafe { *interpreter.instruction_pointer };
     if let Err(result) = interpreter.stack.dup(imm as usize + 1) {
         interpreter.instruction_result = result;
@@ -63,7 +62,7 @@ pub fn dupn<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
 
 pub fn swapn<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
     require_eof!(interpreter);
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     let imm = unsafe { *interpreter.instruction_pointer };
     if let Err(result) = interpreter.stack.swap(imm as usize + 1) {
         interpreter.instruction_result = result;
@@ -73,7 +72,7 @@ pub fn swapn<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
 
 pub fn exchange<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
     require_eof!(interpreter);
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     let imm = unsafe { *interpreter.instruction_pointer };
     let n = (imm >> 4) + 1;
     let m = (imm & 0x0F) + 1;
@@ -1,36 +1,43 @@
 use crate::{
-    gas,
-    primitives::{Spec, B256, KECCAK_EMPTY, U256},
+    primitives::{SpecSelector, B256, KECCAK_EMPTY, U256},
     Host, InstructionResult, Interpreter,
 };
 use core::ptr;
 
-pub fn keccak256<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
+pub fn keccak256<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut H) {
     pop_top!(interpreter, offset, len_ptr);
     let len = as_usize_or_fail!(interpreter, len_ptr);
-    gas_or_fail!(interpreter, gas::keccak256_cost(len as u64));
+    gas_or_fail!(
+        interpreter,
+        interpreter.gas_schedule.keccak256_cost(len as u64)
+    );
     let hash = if len == 0 {
         KECCAK_EMPTY
     } else {
         let from = as_usize_or_fail!(interpreter, offset);
         resize_memory!(interpreter, from, len);
-        crate::primitives::keccak256(interpreter.shared_memory.slice(from, len))
+        let input = interpreter.shared_memory.slice(from, len);
+        let hash = crate::primitives::keccak256(input);
+        if host.env().cfg.is_keccak_preimage_recording_enabled() {
+            host.record_keccak_preimage(input, hash);
+        }
+        hash
     };
     *len_ptr = hash.into();
 }
 
 pub fn address<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     push_b256!(interpreter, interpreter.contract.target_address.into_word());
 }
 
 pub fn caller<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     push_b256!(interpreter, interpreter.contract.caller.into_word());
 }
 
 pub fn codesize<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     // Inform the optimizer that the bytecode cannot be EOF to remove a bounds check.
     assume!(!interpreter.contract.bytecode.is_eof());
     push!(interpreter, U256::from(interpreter.contract.bytecode.len()));
@@ -39,7 +46,10 @@ pub fn codesize<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H)
 pub fn codecopy<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
     pop!(interpreter, memory_offset, code_offset, len);
     let len = as_usize_or_fail!(interpreter, len);
-    gas_or_fail!(interpreter, gas::verylowcopy_cost(len as u64));
+    gas_or_fail!(
+        interpreter,
+        interpreter.gas_schedule.verylowcopy_cost(len as u64)
+    );
     if len == 0 {
         return;
     }
@@ -59,7 +69,7 @@ pub fn codecopy<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H)
 }
 
 pub fn calldataload<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, offset_ptr);
     let mut word = B256::ZERO;
     let offset = as_usize_saturated!(offset_ptr);
@@ -82,19 +92,22 @@ pub fn calldataload<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut
 }
 
 pub fn calldatasize<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     push!(interpreter, U256::from(interpreter.contract.input.len()));
 }
 
 pub fn callvalue<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     push!(interpreter, interpreter.contract.call_value);
 }
 
 pub fn calldatacopy<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
     pop!(interpreter, memory_offset, data_offset, len);
     let len = as_usize_or_fail!(interpreter, len);
-    gas_or_fail!(interpreter, gas::verylowcopy_cost(len as u64));
+    gas_or_fail!(
+        interpreter,
+        interpreter.gas_schedule.verylowcopy_cost(len as u64)
+    );
     if len == 0 {
         return;
     }
@@ -112,9 +125,12 @@ pub fn calldatacopy<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut
 }
 
 /// EIP-211: New opcodes: RETURNDATASIZE and RETURNDATACOPY
-pub fn returndatasize<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, _host: &mut H) {
+pub fn returndatasize<H: Host + ?Sized, SPEC: SpecSelector>(
+    interpreter: &mut Interpreter,
+    _host: &mut H,
+) {
     check!(interpreter, BYZANTIUM);
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     push!(
         interpreter,
         U256::from(interpreter.return_data_buffer.len())
@@ -122,12 +138,18 @@ pub fn returndatasize<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interprete
 }
 
 /// EIP-211: New opcodes: RETURNDATASIZE and RETURNDATACOPY
-pub fn returndatacopy<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, _host: &mut H) {
+pub fn returndatacopy<H: Host + ?Sized, SPEC: SpecSelector>(
+    interpreter: &mut Interpreter,
+    _host: &mut H,
+) {
     check!(interpreter, BYZANTIUM);
     pop!(interpreter, memory_offset, offset, len);
 
     let len = as_usize_or_fail!(interpreter, len);
-    gas_or_fail!(interpreter, gas::verylowcopy_cost(len as u64));
+    gas_or_fail!(
+        interpreter,
+        interpreter.gas_schedule.verylowcopy_cost(len as u64)
+    );
 
     let data_offset = as_usize_saturated!(offset);
     let data_end = data_offset.saturating_add(len);
@@ -135,7 +157,7 @@ pub fn returndatacopy<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interprete
     // Old legacy behavior is to panic if data_end is out of scope of return buffer.
     // This behavior is changed in EOF.
     if data_end > interpreter.return_data_buffer.len() && !interpreter.is_eof {
-        interpreter.instruction_result = InstructionResult::OutOfOffset;
+        interpreter.instruction_result = InstructionResult::ReturnDataOutOfBounds;
         return;
     }
 
@@ -160,7 +182,7 @@ pub fn returndatacopy<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interprete
 /// Part of EOF `<https://eips.ethereum.org/EIPS/eip-7069>`.
 pub fn returndataload<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
     require_eof!(interpreter);
-    gas!(interpreter, gas::VERYLOW);
+    gas!(interpreter, interpreter.gas_schedule.verylow);
     pop_top!(interpreter, offset);
     let offset_usize = as_usize_saturated!(offset);
 
@@ -180,7 +202,7 @@ pub fn returndataload<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &m
 }
 
 pub fn gas<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
-    gas!(interpreter, gas::BASE);
+    gas!(interpreter, interpreter.gas_schedule.base);
     push!(interpreter, U256::from(interpreter.gas.remaining()));
 }
 
@@ -1,21 +1,33 @@
 pub mod analysis;
 mod contract;
+#[cfg(feature = "execution_timeout")]
+mod deadline;
+#[cfg(feature = "gas_assert")]
+mod gas_assert;
 #[cfg(feature = "serde")]
 pub mod serde;
 mod shared_memory;
 mod stack;
+mod tracer;
 
 pub use contract::Contract;
+#[cfg(feature = "execution_timeout")]
+pub use deadline::Deadline;
 pub use shared_memory::{num_words, SharedMemory, EMPTY_SHARED_MEMORY};
 pub use stack::{Stack, STACK_LIMIT};
+pub use tracer::{TraceStep, Tracer};
 
 use crate::{
-    gas, primitives::Bytes, push, push_b256, return_ok, return_revert, CallOutcome, CreateOutcome,
-    FunctionStack, Gas, Host, InstructionResult, InterpreterAction,
+    gas::{GasSchedule, SharedGasSchedule},
+    opcode::{InstructionEntry, InstructionTables},
+    primitives::Bytes,
+    push, push_b256, return_ok, return_revert, CallOutcome, CreateOutcome, FunctionStack, Gas,
+    Host, InstructionResult, InterpreterAction,
 };
-use core::cmp::min;
-use revm_primitives::{Bytecode, Eof, U256};
+use core::{cmp::min, ops::Range};
+use revm_primitives::{Bytecode, Eof, SpecId, U256};
 use std::borrow::ToOwned;
+use std::collections::VecDeque;
 use std::sync::Arc;
 
 /// EVM bytecode interpreter.
@@ -60,6 +72,45 @@ pub struct Interpreter {
     /// Set inside CALL or CREATE instructions and RETURN or REVERT instructions. Additionally those instructions will set
     /// InstructionResult to CallOrCreate/Return/Revert so we know the reason.
     pub next_action: InterpreterAction,
+    /// Actions queued behind `next_action`.
+    ///
+    /// Custom instructions can enqueue more than one action with [`Interpreter::push_action`].
+    /// Only `next_action` is returned from [`Interpreter::run`], the remaining actions are
+    /// returned one by one from the following `run` calls, before any new instruction is executed.
+    pub action_queue: VecDeque<InterpreterAction>,
+    /// Memory region returned by `RETURN` or `REVERT`.
+    ///
+    /// The output is taken from the memory once the interpreter stops, after the tracers and
+    /// inspectors have seen the memory of the last step.
+    pub(crate) return_range: Option<Range<usize>>,
+    /// Specification of the running EVM.
+    ///
+    /// Read by instructions of tables made with
+    /// [`make_runtime_instruction_table`](crate::opcode::make_runtime_instruction_table).
+    /// Defaults to [`SpecId::LATEST`].
+    pub spec_id: SpecId,
+    /// Gas costs charged by the instructions.
+    ///
+    /// Set to the costs of the spec by [`with_spec_id`](Self::with_spec_id), chains with
+    /// different pricing override it with [`with_gas_schedule`](Self::with_gas_schedule). The
+    /// schedule is shared with the other frames, not copied into each of them.
+    pub gas_schedule: SharedGasSchedule,
+    /// Counters of the executed opcodes, updated by the interpreter loop when set.
+    #[cfg(feature = "opcode_stats")]
+    pub opcode_counts: Option<crate::primitives::OpcodeCounts>,
+    /// Gas spent by each opcode, updated by the interpreter loop when set.
+    ///
+    /// Instructions that call or create are charged without the gas limit of the subcall.
+    #[cfg(feature = "gas_profile")]
+    pub gas_usage: Option<crate::primitives::OpcodeGasUsage>,
+    /// Gas spent by the finished subcalls, merged from the profiles of their results.
+    #[cfg(feature = "gas_profile")]
+    pub gas_profile: Option<crate::primitives::GasProfile>,
+    /// Wall-clock deadline of the execution.
+    ///
+    /// Once passed, the interpreter stops with [`InstructionResult::FatalExternalError`].
+    #[cfg(feature = "execution_timeout")]
+    pub deadline: Option<Deadline>,
 }
 
 impl Default for Interpreter {
@@ -90,9 +141,36 @@ impl Interpreter {
             shared_memory: EMPTY_SHARED_MEMORY,
             stack: Stack::new(),
             next_action: InterpreterAction::None,
+            action_queue: VecDeque::new(),
+            return_range: None,
+            spec_id: SpecId::LATEST,
+            gas_schedule: SharedGasSchedule::for_spec(SpecId::LATEST),
+            #[cfg(feature = "opcode_stats")]
+            opcode_counts: None,
+            #[cfg(feature = "gas_profile")]
+            gas_usage: None,
+            #[cfg(feature = "gas_profile")]
+            gas_profile: None,
+            #[cfg(feature = "execution_timeout")]
+            deadline: None,
         }
     }
 
+    /// Sets the specification of the running EVM and its gas costs.
+    #[inline]
+    pub fn with_spec_id(mut self, spec_id: SpecId) -> Self {
+        self.spec_id = spec_id;
+        self.gas_schedule = SharedGasSchedule::for_spec(spec_id);
+        self
+    }
+
+    /// Sets the gas costs charged by the instructions.
+    #[inline]
+    pub fn with_gas_schedule(mut self, gas_schedule: impl Into<SharedGasSchedule>) -> Self {
+        self.gas_schedule = gas_schedule.into();
+        self
+    }
+
     /// Set is_eof_init to true, this is used to enable `RETURNCONTRACT` opcode.
     #[inline]
     pub fn set_is_eof_init(&mut self) {
@@ -162,6 +240,8 @@ impl Interpreter {
     /// - May alter `instruction_result` in case of external errors.
     pub fn insert_create_outcome(&mut self, create_outcome: CreateOutcome) {
         self.instruction_result = InstructionResult::Continue;
+        #[cfg(feature = "gas_profile")]
+        self.merge_gas_profile(&create_outcome.result);
 
         let instruction_result = create_outcome.instruction_result();
         self.return_data_buffer = if instruction_result.is_revert() {
@@ -192,8 +272,15 @@ impl Interpreter {
         }
     }
 
+    /// Inserts the output of an `EOFCREATE` into the interpreter.
+    ///
+    /// Same as [`Interpreter::insert_create_outcome`], except that only a `ReturnContract`
+    /// result pushes the created address and only a `Revert` keeps the output in the
+    /// `return_data_buffer`.
     pub fn insert_eofcreate_outcome(&mut self, create_outcome: CreateOutcome) {
         self.instruction_result = InstructionResult::Continue;
+        #[cfg(feature = "gas_profile")]
+        self.merge_gas_profile(&create_outcome.result);
         let instruction_result = create_outcome.instruction_result();
 
         self.return_data_buffer = if *instruction_result == InstructionResult::Revert {
@@ -248,12 +335,19 @@ impl Interpreter {
     /// - `return_revert!()`: Handles a revert by only updating the gas usage and shared memory.
     /// - `InstructionResult::FatalExternalError`: Sets the instruction result to a fatal external error.
     /// - Any other result: No specific action is taken.
+    ///
+    /// Together with [`Interpreter::insert_create_outcome`] and
+    /// [`Interpreter::insert_eofcreate_outcome`], this resumes an interpreter that returned
+    /// an [`InterpreterAction`] for a sub-call, whoever executed it. The interpreter continues
+    /// with the next instruction on the following [`Interpreter::run`].
     pub fn insert_call_outcome(
         &mut self,
         shared_memory: &mut SharedMemory,
         call_outcome: CallOutcome,
     ) {
         self.instruction_result = InstructionResult::Continue;
+        #[cfg(feature = "gas_profile")]
+        self.merge_gas_profile(&call_outcome.result);
 
         let out_offset = call_outcome.memory_start();
         let out_len = call_outcome.memory_length();
@@ -349,7 +443,7 @@ impl Interpreter {
     #[inline]
     pub(crate) fn step<FN, H: Host + ?Sized>(&mut self, instruction_table: &[FN; 256], host: &mut H)
     where
-        FN: Fn(&mut Interpreter, &mut H),
+        FN: InstructionEntry<H>,
     {
         // Get current opcode.
         let opcode = unsafe { *self.instruction_pointer };
@@ -359,8 +453,81 @@ impl Interpreter {
         // it will do noop and just stop execution of this contract
         self.instruction_pointer = unsafe { self.instruction_pointer.offset(1) };
 
+        #[cfg(feature = "opcode_stats")]
+        if let Some(counts) = &mut self.opcode_counts {
+            counts.increment(opcode);
+        }
+
+        #[cfg(feature = "gas_profile")]
+        let remaining = self.gas.remaining();
+        #[cfg(feature = "gas_assert")]
+        let gas_check = gas_assert::GasCheck::new(self, opcode);
+
         // execute instruction.
-        (instruction_table[opcode as usize])(self, host)
+        instruction_table[opcode as usize].execute(self, host);
+
+        #[cfg(feature = "gas_profile")]
+        self.record_gas_usage(opcode, remaining);
+        #[cfg(feature = "gas_assert")]
+        gas_check.verify(self);
+    }
+
+    /// Charges the gas spent by the instruction to its opcode, without the gas given to the
+    /// subcall it made, if any.
+    #[cfg(feature = "gas_profile")]
+    #[inline]
+    fn record_gas_usage(&mut self, opcode: u8, remaining: u64) {
+        let Some(usage) = &mut self.gas_usage else {
+            return;
+        };
+        let subcall_gas = match &self.next_action {
+            // The stipend of a value transfer is given to the subcall on top of its cost.
+            InterpreterAction::Call { inputs } if inputs.transfers_value() => {
+                inputs.gas_limit - self.gas_schedule.call_stipend
+            }
+            InterpreterAction::Call { inputs } => inputs.gas_limit,
+            InterpreterAction::Create { inputs } => inputs.gas_limit,
+            InterpreterAction::EOFCreate { inputs } => inputs.gas_limit,
+            _ => 0,
+        };
+        let spent = remaining.saturating_sub(self.gas.remaining());
+        usage.record(opcode, spent.saturating_sub(subcall_gas));
+    }
+
+    /// Adds the gas profile of a finished subcall to the profile of the frame.
+    #[cfg(feature = "gas_profile")]
+    #[inline]
+    fn merge_gas_profile(&mut self, result: &InterpreterResult) {
+        if let Some(profile) = &result.gas_profile {
+            self.gas_profile
+                .get_or_insert_with(Default::default)
+                .merge(profile);
+        }
+    }
+
+    /// Schedules an action for the EVM.
+    ///
+    /// The first action is stored in `next_action`, any further action is appended to the
+    /// action queue and returned by subsequent [`Interpreter::run`] calls in FIFO order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an action is pushed after an [`InterpreterAction::Return`], which ends the frame.
+    #[inline]
+    pub fn push_action(&mut self, action: InterpreterAction) {
+        let last = self.action_queue.back().unwrap_or(&self.next_action);
+        assert!(!last.is_return(), "action pushed after the frame returned");
+        if self.next_action.is_none() {
+            self.next_action = action;
+        } else {
+            self.action_queue.push_back(action);
+        }
+    }
+
+    /// Returns `true` if there are actions waiting in the action queue.
+    #[inline]
+    pub fn has_queued_actions(&self) -> bool {
+        !self.action_queue.is_empty()
     }
 
     /// Take memory and replace it with empty memory.
@@ -376,27 +543,105 @@ impl Interpreter {
         host: &mut H,
     ) -> InterpreterAction
     where
-        FN: Fn(&mut Interpreter, &mut H),
+        FN: InstructionEntry<H>,
     {
-        self.next_action = InterpreterAction::None;
-        self.shared_memory = shared_memory;
+        if let Some(action) = self.start(shared_memory) {
+            return action;
+        }
+
         // main loop
         while self.instruction_result == InstructionResult::Continue {
             self.step(instruction_table, host);
+            self.check_deadline();
+        }
+
+        self.take_action()
+    }
+
+    /// Executes the interpreter until it returns or stops, calling the tracer around every
+    /// instruction.
+    ///
+    /// Same as [`Interpreter::run`] otherwise.
+    pub fn run_with_tracer<FN, H: Host + ?Sized, T: Tracer<H>>(
+        &mut self,
+        shared_memory: SharedMemory,
+        instruction_table: &[FN; 256],
+        host: &mut H,
+        mut tracer: T,
+    ) -> InterpreterAction
+    where
+        FN: InstructionEntry<H>,
+    {
+        if let Some(action) = self.start(shared_memory) {
+            return action;
+        }
+
+        while self.instruction_result == InstructionResult::Continue {
+            let pc = self.program_counter();
+            let opcode = self.current_opcode();
+            tracer.step(&TraceStep::new(self, pc, opcode), host);
+            self.step(instruction_table, host);
+            tracer.step_end(&TraceStep::new(self, pc, opcode), host);
+            self.check_deadline();
+        }
+
+        self.take_action()
+    }
+
+    /// Sets the shared memory before running and returns the first queued action, if any.
+    #[inline]
+    fn start(&mut self, shared_memory: SharedMemory) -> Option<InterpreterAction> {
+        self.next_action = InterpreterAction::None;
+        self.shared_memory = shared_memory;
+
+        // Drain queued actions before executing any new instruction.
+        self.action_queue.pop_front()
+    }
+
+    #[inline]
+    fn check_deadline(&mut self) {
+        #[cfg(feature = "execution_timeout")]
+        if self.deadline.as_mut().is_some_and(Deadline::tick)
+            && self.instruction_result == InstructionResult::Continue
+        {
+            self.instruction_result = InstructionResult::FatalExternalError;
         }
+    }
 
+    /// Returns the action of the stopped interpreter.
+    #[inline]
+    fn take_action(&mut self) -> InterpreterAction {
+        if let Some(range) = self.return_range.take() {
+            if let InterpreterAction::Return { result } = &mut self.next_action {
+                result.output = self
+                    .shared_memory
+                    .take_slice_bytes(range.start, range.len());
+            }
+        }
         // Return next action if it is some.
         if self.next_action.is_some() {
             return core::mem::take(&mut self.next_action);
         }
         // If not, return action without output as it is a halt.
         InterpreterAction::Return {
-            result: InterpreterResult {
-                result: self.instruction_result,
-                // return empty bytecode
-                output: Bytes::new(),
-                gas: self.gas,
-            },
+            result: InterpreterResult::new(self.instruction_result, Bytes::new(), self.gas),
+        }
+    }
+
+    /// Executes the interpreter with the given instruction tables until it returns or stops.
+    ///
+    /// Same as [`Interpreter::run`], for any variant of [`InstructionTables`]. Embedders that
+    /// use the interpreter without the revm handlers can pass a different table on every call.
+    pub fn run_with_tables<H: Host + ?Sized>(
+        &mut self,
+        shared_memory: SharedMemory,
+        instruction_tables: &InstructionTables<'_, H>,
+        host: &mut H,
+    ) -> InterpreterAction {
+        match instruction_tables {
+            InstructionTables::Plain(table) => self.run(shared_memory, table, host),
+            InstructionTables::Hooked(table) => self.run(shared_memory, table, host),
+            InstructionTables::Boxed(table) => self.run(shared_memory, table, host),
         }
     }
 
@@ -404,7 +649,12 @@ impl Interpreter {
     #[inline]
     #[must_use]
     pub fn resize_memory(&mut self, new_size: usize) -> bool {
-        resize_memory(&mut self.shared_memory, &mut self.gas, new_size)
+        resize_memory(
+            &mut self.shared_memory,
+            &mut self.gas,
+            &self.gas_schedule,
+            new_size,
+        )
     }
 }
 
@@ -418,6 +668,11 @@ pub struct InterpreterResult {
     pub output: Bytes,
     /// The gas usage information.
     pub gas: Gas,
+    /// Gas spent by the frame and its subcalls.
+    ///
+    /// Only set when the frame was executed with [`Interpreter::gas_usage`] set.
+    #[cfg(feature = "gas_profile")]
+    pub gas_profile: Option<crate::primitives::GasProfile>,
 }
 
 impl InterpreterResult {
@@ -427,6 +682,8 @@ impl InterpreterResult {
             result,
             output,
             gas,
+            #[cfg(feature = "gas_profile")]
+            gas_profile: None,
         }
     }
 
@@ -453,10 +710,15 @@ impl InterpreterResult {
 #[inline(never)]
 #[cold]
 #[must_use]
-pub fn resize_memory(memory: &mut SharedMemory, gas: &mut Gas, new_size: usize) -> bool {
+pub fn resize_memory(
+    memory: &mut SharedMemory,
+    gas: &mut Gas,
+    schedule: &GasSchedule,
+    new_size: usize,
+) -> bool {
     let new_words = num_words(new_size as u64);
-    let new_cost = gas::memory_gas(new_words);
-    let current_cost = memory.current_expansion_cost();
+    let new_cost = schedule.memory_gas(new_words);
+    let current_cost = schedule.memory_gas(num_words(memory.len() as u64));
     let cost = new_cost - current_cost;
     let success = gas.record_cost(cost);
     if success {
@@ -468,8 +730,214 @@ pub fn resize_memory(memory: &mut SharedMemory, gas: &mut Gas, new_size: usize)
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{opcode::InstructionTable, DummyHost};
-    use revm_primitives::CancunSpec;
+    use crate::{opcode::InstructionTable, CallInputs, DummyHost};
+    use revm_primitives::{Address, CancunSpec};
+
+    fn call_action(interp: &Interpreter) -> InterpreterAction {
+        let inputs = CallInputs::builder()
+            .caller(interp.contract.target_address)
+            .to(Address::with_last_byte(1))
+            .gas(interp.gas.remaining())
+            .build()
+            .unwrap();
+        InterpreterAction::Call {
+            inputs: Box::new(inputs),
+        }
+    }
+
+    fn return_action(interp: &Interpreter) -> InterpreterAction {
+        InterpreterAction::Return {
+            result: InterpreterResult::new(
+                InstructionResult::Return,
+                Bytes::from_static(&[1]),
+                interp.gas,
+            ),
+        }
+    }
+
+    #[test]
+    fn action_queue() {
+        fn call_then_return(interp: &mut Interpreter, _host: &mut DummyHost) {
+            interp.instruction_result = InstructionResult::CallOrCreate;
+            interp.push_action(call_action(interp));
+            interp.push_action(return_action(interp));
+        }
+
+        let mut table = crate::opcode::make_instruction_table::<DummyHost, CancunSpec>();
+        table[crate::opcode::STOP as usize] = call_then_return;
+
+        let mut interp = Interpreter::new(Contract::default(), u64::MAX, false);
+        let mut host = DummyHost::default();
+
+        let action = interp.run(EMPTY_SHARED_MEMORY, &table, &mut host);
+        assert!(action.is_call());
+        assert!(interp.has_queued_actions());
+
+        let memory = interp.take_memory();
+        let action = interp.run(memory, &table, &mut host);
+        assert!(matches!(action, InterpreterAction::Return { result } if result.output[..] == [1]));
+        assert!(!interp.has_queued_actions());
+    }
+
+    #[test]
+    #[should_panic(expected = "action pushed after the frame returned")]
+    fn action_after_return() {
+        let mut interp = Interpreter::new(Contract::default(), u64::MAX, false);
+        interp.push_action(return_action(&interp));
+        interp.push_action(call_action(&interp));
+    }
+
+    #[test]
+    fn run_with_tables() {
+        fn return_one(interp: &mut Interpreter, _host: &mut DummyHost) {
+            interp.instruction_result = InstructionResult::Return;
+            interp.next_action = InterpreterAction::Return {
+                result: InterpreterResult::new(
+                    InstructionResult::Return,
+                    Bytes::from_static(&[1]),
+                    interp.gas,
+                ),
+            };
+        }
+
+        let mut host = DummyHost::default();
+        let mut tables = InstructionTables::<DummyHost>::new_plain::<CancunSpec>();
+        let mut interp = Interpreter::new(Contract::default(), u64::MAX, false);
+        let action = interp.run_with_tables(EMPTY_SHARED_MEMORY, &tables, &mut host);
+        assert!(
+            matches!(action, InterpreterAction::Return { result } if result.result == InstructionResult::Stop)
+        );
+
+        // Swap the STOP instruction without rebuilding the interpreter.
+        tables.insert_boxed(crate::opcode::STOP, Box::new(return_one));
+        let mut interp = Interpreter::new(Contract::default(), u64::MAX, false);
+        let action = interp.run_with_tables(EMPTY_SHARED_MEMORY, &tables, &mut host);
+        assert!(matches!(action, InterpreterAction::Return { result } if result.output[..] == [1]));
+    }
+
+    #[test]
+    fn hooked_instructions() {
+        fn spend_seven(interp: &mut Interpreter, _host: &mut DummyHost) {
+            assert!(interp.gas.record_cost(7));
+        }
+        fn spend_three(interp: &mut Interpreter, _host: &mut DummyHost) {
+            assert!(interp.gas.record_cost(3));
+        }
+        fn spent(tables: &InstructionTables<'_, DummyHost>) -> u64 {
+            let mut interp = Interpreter::new(Contract::default(), 100, false);
+            match interp.run_with_tables(EMPTY_SHARED_MEMORY, tables, &mut DummyHost::default()) {
+                InterpreterAction::Return { result } => result.gas.spent(),
+                action => panic!("unexpected action {action:?}"),
+            }
+        }
+
+        let mut tables = InstructionTables::<DummyHost>::new_plain::<CancunSpec>();
+        tables.insert_hooks(crate::opcode::STOP, Some(spend_seven), Some(spend_three));
+        assert!(matches!(tables, InstructionTables::Hooked(_)));
+        assert_eq!(spent(&tables), 10);
+
+        // The hooks are kept when the table is boxed.
+        tables.to_boxed();
+        assert_eq!(spent(&tables), 10);
+        tables.insert_hooks(crate::opcode::STOP, Some(spend_three), None);
+        assert_eq!(spent(&tables), 13);
+    }
+
+    #[test]
+    fn runtime_spec() {
+        let table = crate::opcode::make_runtime_instruction_table::<DummyHost>();
+        let mut host = DummyHost::default();
+        // PUSH0, introduced in Shanghai.
+        let bytecode = Bytecode::LegacyRaw(Bytes::from_static(&[crate::opcode::PUSH0]));
+
+        let mut interp = Interpreter::new_bytecode(bytecode.clone()).with_spec_id(SpecId::MERGE);
+        interp.gas = Gas::new(10000);
+        interp.step(&table, &mut host);
+        assert_eq!(interp.instruction_result, InstructionResult::NotActivated);
+
+        let mut interp = Interpreter::new_bytecode(bytecode).with_spec_id(SpecId::SHANGHAI);
+        interp.gas = Gas::new(10000);
+        interp.step(&table, &mut host);
+        assert_eq!(interp.instruction_result, InstructionResult::Continue);
+        assert_eq!(interp.stack.data(), &[U256::ZERO]);
+    }
+
+    #[test]
+    fn run_with_tracer() {
+        #[derive(Default)]
+        struct Steps(Vec<(usize, u8, usize, u64)>);
+
+        impl Tracer<DummyHost> for Steps {
+            fn step(&mut self, step: &TraceStep<'_>, _host: &mut DummyHost) {
+                self.0
+                    .push((step.pc, step.opcode, step.stack.len(), step.gas.spent()));
+            }
+
+            fn step_end(&mut self, step: &TraceStep<'_>, _host: &mut DummyHost) {
+                let (pc, opcode, ..) = *self.0.last().unwrap();
+                assert_eq!((step.pc, step.opcode), (pc, opcode));
+            }
+        }
+
+        let table = crate::opcode::make_instruction_table::<DummyHost, CancunSpec>();
+        // PUSH1 1 PUSH1 2 ADD STOP
+        let bytecode = Bytecode::LegacyRaw(Bytes::from_static(&[0x60, 1, 0x60, 2, 0x01, 0x00]));
+        let mut interp = Interpreter::new_bytecode(bytecode);
+        interp.gas = Gas::new(10000);
+
+        let mut steps = Steps::default();
+        let action = interp.run_with_tracer(
+            EMPTY_SHARED_MEMORY,
+            &table,
+            &mut DummyHost::default(),
+            &mut steps,
+        );
+        assert!(
+            matches!(action, InterpreterAction::Return { result } if result.result == InstructionResult::Stop)
+        );
+        assert_eq!(
+            steps.0,
+            [
+                (0, 0x60, 0, 0),
+                (2, 0x60, 1, 3),
+                (4, 0x01, 2, 6),
+                (5, 0x00, 1, 9)
+            ]
+        );
+    }
+
+    #[test]
+    fn return_keeps_memory_until_stop() {
+        struct ReturnMemory(Vec<u8>);
+
+        impl Tracer<DummyHost> for ReturnMemory {
+            fn step_end(&mut self, step: &TraceStep<'_>, _host: &mut DummyHost) {
+                if step.opcode == crate::opcode::RETURN {
+                    self.0 = step.memory.to_vec();
+                }
+            }
+        }
+
+        let table = crate::opcode::make_instruction_table::<DummyHost, CancunSpec>();
+        // PUSH1 0x2a PUSH1 0 MSTORE PUSH1 0x20 PUSH1 0 RETURN
+        let bytecode = Bytecode::LegacyRaw(Bytes::from_static(&[
+            0x60, 0x2a, 0x60, 0, 0x52, 0x60, 0x20, 0x60, 0, 0xf3,
+        ]));
+        let mut interp = Interpreter::new_bytecode(bytecode);
+        interp.gas = Gas::new(10000);
+        let mut memory = SharedMemory::new();
+        memory.new_context();
+
+        let mut tracer = ReturnMemory(Vec::new());
+        let action = interp.run_with_tracer(memory, &table, &mut DummyHost::default(), &mut tracer);
+        let InterpreterAction::Return { result } = action else {
+            panic!("expected a return");
+        };
+        assert_eq!(U256::from_be_slice(&result.output), U256::from(0x2a));
+        assert_eq!(U256::from_be_slice(&tracer.0), U256::from(0x2a));
+        // The output took the buffer of the root context.
+        assert!(interp.shared_memory.is_empty());
+    }
 
     #[test]
     fn object_safety() {
@@ -1,3 +1,7 @@
+mod legacy_report;
+
+pub use legacy_report::LegacyAnalysisReport;
+
 use revm_primitives::MAX_INITCODE_SIZE;
 
 use crate::{
@@ -65,6 +69,28 @@ fn analyze(code: &[u8]) -> JumpTable {
     JumpTable(Arc::new(jumps))
 }
 
+/// Analyzes legacy bytecode to build its jump map and a [`LegacyAnalysisReport`] of structural
+/// issues: unreachable code, dead jump destinations and stack underflows.
+///
+/// `code` is the original bytecode, without the padding added by [`to_analysed`]. The report is
+/// best effort and is not needed for execution.
+pub fn analyze_with_report(code: &[u8]) -> (JumpTable, LegacyAnalysisReport) {
+    let jump_table = analyze(code);
+    let report = legacy_report::report(code, &jump_table);
+    (jump_table, report)
+}
+
+/// Statically verifies that legacy bytecode can't underflow or overflow the stack.
+///
+/// Every reachable basic block must be entered with a single known stack height, so verification
+/// fails as soon as a reachable jump doesn't have its destination pushed right before it. Contracts
+/// that fail it are executed with the usual dynamic checks.
+///
+/// `code` is the original bytecode and `jump_table` its jump map.
+pub fn verify_legacy_stack(code: &[u8], jump_table: &JumpTable) -> bool {
+    legacy_report::verify_stack(code, jump_table)
+}
+
 /// Decodes `raw` into an [`Eof`] container and validates it.
 pub fn validate_raw_eof(raw: Bytes) -> Result<Eof, EofError> {
     validate_raw_eof_inner(raw, Some(CodeType::ReturnContract))
@@ -820,7 +846,7 @@ pub fn validate_eof_code(
 #[cfg(test)]
 mod test {
     use super::*;
-    use revm_primitives::hex;
+    use revm_primitives::{eof::EofBuilder, hex};
 
     #[test]
     fn test1() {
@@ -942,4 +968,28 @@ mod test {
             ))
         );
     }
+
+    #[test]
+    fn built_container() {
+        let runtime = EofBuilder::new()
+            // CALLF 1, POP, STOP
+            .code_section(TypesSection::new(0, 0x80, 1), hex!("e300015000"))
+            // PUSH0, RETF
+            .code_section(TypesSection::new(0, 1, 1), hex!("5fe4"))
+            .build()
+            .unwrap();
+        assert_eq!(
+            validate_eof_inner(&runtime, Some(CodeType::ReturnOrStop)),
+            Ok(())
+        );
+
+        let initcode = EofBuilder::new()
+            // PUSH0, PUSH0, RETURNCONTRACT 0
+            .code_section(TypesSection::new(0, 0x80, 2), hex!("5f5fee00"))
+            .container(runtime.raw.clone())
+            .build()
+            .unwrap();
+        assert_eq!(Eof::decode(initcode.raw.clone()).as_ref(), Ok(&initcode));
+        assert_eq!(validate_eof(&initcode), Ok(()));
+    }
 }
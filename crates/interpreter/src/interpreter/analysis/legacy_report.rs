@@ -0,0 +1,359 @@
+//! Best effort structural checks of legacy bytecode.
+
+use crate::{opcode, primitives::legacy::JumpTable, OPCODE_INFO_JUMPTABLE, STACK_LIMIT};
+use core::ops::Range;
+use std::{collections::BTreeSet, vec, vec::Vec};
+
+/// Maximum number of distinct entry stack heights tracked per basic block.
+const MAX_HEIGHTS_PER_BLOCK: usize = 16;
+
+/// Structural findings of [`analyze_with_report`](super::analyze_with_report).
+///
+/// Jumps whose destination is pushed right before them are followed exactly. Other jumps are
+/// assumed to reach every `JUMPDEST` whose position is pushed somewhere in the code, which is
+/// how compilers implement returns from internal functions.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LegacyAnalysisReport {
+    /// Ranges of instructions that can't be reached from the start of the code.
+    ///
+    /// Data appended to the code, like the solc metadata, is reported here as well.
+    pub unreachable: Vec<Range<usize>>,
+    /// `JUMPDEST`s that no jump can reach because their position is never pushed.
+    pub dead_jumpdests: Vec<usize>,
+    /// Jumps to a pushed destination that is not a valid `JUMPDEST`.
+    pub invalid_jumps: Vec<usize>,
+    /// Instructions that underflow the stack on a path from the start of the code.
+    ///
+    /// Only paths made of fall throughs and jumps to pushed destinations are followed.
+    pub stack_underflows: Vec<usize>,
+    /// Instructions that overflow the stack on a path from the start of the code.
+    pub stack_overflows: Vec<usize>,
+}
+
+impl LegacyAnalysisReport {
+    /// Returns `true` if nothing was found.
+    pub fn is_empty(&self) -> bool {
+        self.unreachable.is_empty()
+            && self.dead_jumpdests.is_empty()
+            && self.invalid_jumps.is_empty()
+            && self.stack_underflows.is_empty()
+            && self.stack_overflows.is_empty()
+    }
+}
+
+/// Decoded instruction.
+struct Instruction {
+    pc: usize,
+    opcode: u8,
+    /// Value of the push instruction, if it fits in an `usize`.
+    push: Option<usize>,
+}
+
+/// Basic block, as a range of instruction indices.
+struct Block {
+    instructions: Range<usize>,
+    /// Whether the execution can continue with the next block.
+    falls_through: bool,
+    /// Jump at the end of the block, with its pushed destination.
+    jump: Option<Option<usize>>,
+}
+
+pub(super) fn report(code: &[u8], jump_table: &JumpTable) -> LegacyAnalysisReport {
+    let instructions = decode(code);
+    let blocks = split_blocks(&instructions);
+    let block_at = |pc: usize| {
+        blocks
+            .binary_search_by_key(&pc, |block| instructions[block.instructions.start].pc)
+            .ok()
+    };
+    let is_jumpdest = |pc: usize| pc < code.len() && jump_table.is_valid(pc);
+
+    let mut report = LegacyAnalysisReport::default();
+
+    let pushed: BTreeSet<usize> = instructions.iter().filter_map(|i| i.push).collect();
+    let address_taken: Vec<usize> = pushed
+        .iter()
+        .copied()
+        .filter(|&pc| is_jumpdest(pc))
+        .filter_map(block_at)
+        .collect();
+    report.dead_jumpdests = instructions
+        .iter()
+        .filter(|i| i.opcode == opcode::JUMPDEST && !pushed.contains(&i.pc))
+        .map(|i| i.pc)
+        .collect();
+
+    // Reachability and stack heights, on entry of each block.
+    let mut reachable = vec![false; blocks.len()];
+    let mut heights = vec![BTreeSet::new(); blocks.len()];
+    let mut underflows = BTreeSet::new();
+    let mut overflows = BTreeSet::new();
+    let mut invalid_jumps = BTreeSet::new();
+    let mut worklist = Vec::new();
+    if !blocks.is_empty() {
+        reachable[0] = true;
+        heights[0].insert(0);
+        worklist.push((0, Some(0)));
+    }
+    while let Some((index, height)) = worklist.pop() {
+        let block = &blocks[index];
+        let mut height = height;
+        for instruction in &instructions[block.instructions.clone()] {
+            let Some(h) = height else { break };
+            let (inputs, outputs) = stack_io(instruction.opcode);
+            if h < inputs {
+                underflows.insert(instruction.pc);
+                height = None;
+            } else if h - inputs + outputs > STACK_LIMIT {
+                overflows.insert(instruction.pc);
+                height = None;
+            } else {
+                height = Some(h - inputs + outputs);
+            }
+        }
+        // Successors reached with a known height.
+        let mut successors = Vec::new();
+        if block.falls_through && index + 1 < blocks.len() {
+            successors.push(index + 1);
+        }
+        match block.jump {
+            Some(Some(target)) if is_jumpdest(target) => successors.extend(block_at(target)),
+            Some(Some(_)) => {
+                invalid_jumps.insert(instructions[block.instructions.end - 1].pc);
+            }
+            Some(None) => {
+                // Heights are not propagated through dynamic jumps.
+                for &target in &address_taken {
+                    if !reachable[target] {
+                        reachable[target] = true;
+                        worklist.push((target, None));
+                    }
+                }
+            }
+            None => {}
+        }
+        for successor in successors {
+            let first_visit = !reachable[successor];
+            reachable[successor] = true;
+            let known = match height {
+                Some(h) if heights[successor].len() < MAX_HEIGHTS_PER_BLOCK => {
+                    heights[successor].insert(h)
+                }
+                _ => false,
+            };
+            if known || first_visit {
+                worklist.push((successor, height.filter(|_| known)));
+            }
+        }
+    }
+    report.stack_underflows = underflows.into_iter().collect();
+    report.stack_overflows = overflows.into_iter().collect();
+    report.invalid_jumps = invalid_jumps.into_iter().collect();
+
+    // Merge adjacent unreachable blocks into ranges.
+    let end_pc = |block: &Block| {
+        instructions
+            .get(block.instructions.end)
+            .map_or(code.len(), |i| i.pc)
+    };
+    for (block, _) in blocks.iter().zip(&reachable).filter(|(_, r)| !**r) {
+        let range = instructions[block.instructions.start].pc..end_pc(block);
+        match report.unreachable.last_mut() {
+            Some(last) if last.end == range.start => last.end = range.end,
+            _ => report.unreachable.push(range),
+        }
+    }
+    report
+}
+
+pub(super) fn verify_stack(code: &[u8], jump_table: &JumpTable) -> bool {
+    let instructions = decode(code);
+    let blocks = split_blocks(&instructions);
+    let block_at = |pc: usize| {
+        blocks
+            .binary_search_by_key(&pc, |block| instructions[block.instructions.start].pc)
+            .ok()
+    };
+    if blocks.is_empty() {
+        return true;
+    }
+
+    let mut entry_heights = vec![None; blocks.len()];
+    entry_heights[0] = Some(0);
+    let mut worklist = vec![0];
+    while let Some(index) = worklist.pop() {
+        let block = &blocks[index];
+        let Some(mut height) = entry_heights[index] else {
+            continue;
+        };
+        for instruction in &instructions[block.instructions.clone()] {
+            let (inputs, outputs) = stack_io(instruction.opcode);
+            if height < inputs || height - inputs + outputs > STACK_LIMIT {
+                return false;
+            }
+            height = height - inputs + outputs;
+        }
+
+        let mut successors = [None, None];
+        if block.falls_through && index + 1 < blocks.len() {
+            successors[0] = Some(index + 1);
+        }
+        match block.jump {
+            Some(Some(target)) if target < code.len() && jump_table.is_valid(target) => {
+                match block_at(target) {
+                    Some(target) => successors[1] = Some(target),
+                    None => return false,
+                }
+            }
+            // Halts with `InvalidJump`.
+            Some(Some(_)) => {}
+            Some(None) => return false,
+            None => {}
+        }
+        for successor in successors.into_iter().flatten() {
+            match entry_heights[successor] {
+                None => {
+                    entry_heights[successor] = Some(height);
+                    worklist.push(successor);
+                }
+                Some(entry) if entry == height => {}
+                Some(_) => return false,
+            }
+        }
+    }
+    true
+}
+
+fn decode(code: &[u8]) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        let opcode = code[pc];
+        let push_size = opcode.wrapping_sub(opcode::PUSH0);
+        let (push, size) = if push_size <= 32 {
+            let size = push_size as usize;
+            // Missing immediate bytes at the end of the code are zeros.
+            let value = (0..size).try_fold(0usize, |value, i| {
+                let byte = code.get(pc + 1 + i).copied().unwrap_or_default();
+                value.checked_mul(256)?.checked_add(byte as usize)
+            });
+            (value, size)
+        } else {
+            (None, 0)
+        };
+        instructions.push(Instruction { pc, opcode, push });
+        pc += 1 + size;
+    }
+    instructions
+}
+
+fn split_blocks(instructions: &[Instruction]) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    for (index, instruction) in instructions.iter().enumerate() {
+        let next_is_jumpdest = instructions
+            .get(index + 1)
+            .is_some_and(|next| next.opcode == opcode::JUMPDEST);
+        let is_jump = matches!(instruction.opcode, opcode::JUMP | opcode::JUMPI);
+        let halts = halts(instruction.opcode);
+        if !(is_jump || halts || next_is_jumpdest || index + 1 == instructions.len()) {
+            continue;
+        }
+        let jump = is_jump.then(|| {
+            index
+                .checked_sub(1)
+                .filter(|&previous| previous >= start)
+                .and_then(|previous| instructions[previous].push)
+        });
+        blocks.push(Block {
+            instructions: start..index + 1,
+            falls_through: !halts && instruction.opcode != opcode::JUMP,
+            jump,
+        });
+        start = index + 1;
+    }
+    blocks
+}
+
+/// Returns `true` if the opcode ends the execution in legacy bytecode.
+///
+/// Undefined opcodes and opcodes only valid in EOF halt.
+fn halts(opcode: u8) -> bool {
+    use opcode::*;
+    OPCODE_INFO_JUMPTABLE[opcode as usize].is_none()
+        || matches!(
+            opcode,
+            STOP | RETURN
+                | REVERT
+                | INVALID
+                | SELFDESTRUCT
+                | DATALOAD..=DATACOPY
+                | RJUMP..=EXCHANGE
+                | EOFCREATE
+                | RETURNCONTRACT
+                | RETURNDATALOAD..=EXTDELEGATECALL
+                | EXTSTATICCALL
+        )
+}
+
+fn stack_io(opcode: u8) -> (usize, usize) {
+    OPCODE_INFO_JUMPTABLE[opcode as usize].map_or((0, 0), |info| {
+        (info.inputs() as usize, info.outputs() as usize)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{analyze_with_report, verify_legacy_stack};
+    use super::*;
+
+    #[test]
+    fn legacy_report() {
+        let code = [
+            opcode::PUSH1,
+            0x06,
+            opcode::JUMP,
+            opcode::ADD,      // unreachable
+            opcode::JUMPDEST, // dead
+            opcode::STOP,
+            opcode::JUMPDEST, // 6
+            opcode::PUSH1,
+            0x06,
+            opcode::JUMPI, // underflows
+            opcode::PUSH1,
+            0x03,
+            opcode::JUMP, // invalid jump
+        ];
+        let (jump_table, report) = analyze_with_report(&code);
+        assert!(jump_table.is_valid(6));
+        assert_eq!(report.unreachable, [3..6]);
+        assert_eq!(report.dead_jumpdests, [4]);
+        assert_eq!(report.stack_underflows, [9]);
+        assert_eq!(report.invalid_jumps, [12]);
+        assert!(report.stack_overflows.is_empty());
+
+        // PUSH1 0 PUSH1 0 RETURN
+        let (_, report) = analyze_with_report(&[0x60, 0x00, 0x60, 0x00, 0xf3]);
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn verify_stack() {
+        let verify = |code: &[u8]| {
+            let (jump_table, _) = analyze_with_report(code);
+            verify_legacy_stack(code, &jump_table)
+        };
+        // PUSH1 0 PUSH1 0 RETURN
+        assert!(verify(&[0x60, 0x00, 0x60, 0x00, 0xf3]));
+        // Loop that keeps the stack height: PUSH1 1 JUMPDEST PUSH1 2 JUMP
+        assert!(verify(&[0x60, 0x01, 0x5b, 0x60, 0x02, 0x56]));
+        // Loop that grows the stack: JUMPDEST PUSH1 1 PUSH1 0 JUMP
+        assert!(!verify(&[0x5b, 0x60, 0x01, 0x60, 0x00, 0x56]));
+        // Underflow: PUSH1 1 ADD
+        assert!(!verify(&[0x60, 0x01, 0x01]));
+        // Dynamic jump: PUSH1 0 CALLDATALOAD JUMP
+        assert!(!verify(&[0x60, 0x00, 0x35, 0x56]));
+        // Underflow after a halt is not reachable: STOP ADD
+        assert!(verify(&[0x00, 0x01]));
+    }
+}
@@ -26,6 +26,11 @@ pub struct Contract {
     pub caller: Address,
     /// Value send to contract from transaction or from CALL opcodes.
     pub call_value: U256,
+    /// Whether the stack bounds of the bytecode were statically verified, see
+    /// [`Contract::verify_stack`].
+    #[cfg(feature = "legacy_stack_verification")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stack_verified: bool,
 }
 
 impl Contract {
@@ -50,9 +55,42 @@ impl Contract {
             bytecode_address,
             caller,
             call_value,
+            #[cfg(feature = "legacy_stack_verification")]
+            stack_verified: false,
         }
     }
 
+    /// Statically verifies the stack bounds of legacy bytecode, see
+    /// [`verify_legacy_stack`](crate::analysis::verify_legacy_stack).
+    ///
+    /// Once verified, the stack underflow checks of the instructions are skipped for this
+    /// contract. Returns `false`, and keeps the checks, if verification fails or for EOF.
+    #[cfg(feature = "legacy_stack_verification")]
+    pub fn verify_stack(&mut self) -> bool {
+        self.stack_verified = match &self.bytecode {
+            Bytecode::LegacyAnalyzed(bytecode) => crate::analysis::verify_legacy_stack(
+                bytecode.original_byte_slice(),
+                bytecode.jump_table(),
+            ),
+            _ => false,
+        };
+        self.stack_verified
+    }
+
+    /// Returns `true` if the stack bounds of the bytecode were statically verified.
+    #[cfg(feature = "legacy_stack_verification")]
+    #[inline]
+    pub fn is_stack_verified(&self) -> bool {
+        self.stack_verified
+    }
+
+    /// Returns `true` if the stack bounds of the bytecode were statically verified.
+    #[cfg(not(feature = "legacy_stack_verification"))]
+    #[inline]
+    pub fn is_stack_verified(&self) -> bool {
+        false
+    }
+
     /// Creates a new contract from the given [`Env`].
     #[inline]
     pub fn new_env(env: &Env, bytecode: Bytecode, hash: Option<B256>) -> Self {
@@ -94,6 +132,36 @@ impl Contract {
         )
     }
 
+    /// Returns the address of the executing account, returned by `ADDRESS`.
+    ///
+    /// Storage, balance and logs of the frame belong to this account.
+    #[inline]
+    pub fn target_address(&self) -> Address {
+        self.target_address
+    }
+
+    /// Returns the address of the account the executed code was loaded from.
+    ///
+    /// It differs from [`Contract::target_address`] in DELEGATECALL and CALLCODE frames. For
+    /// contract creation it is the address of the created account.
+    #[inline]
+    pub fn code_address(&self) -> Address {
+        self.bytecode_address.unwrap_or(self.target_address)
+    }
+
+    /// Returns `true` if the frame executes code of another account, as in DELEGATECALL and
+    /// CALLCODE frames.
+    #[inline]
+    pub fn is_delegated(&self) -> bool {
+        self.code_address() != self.target_address
+    }
+
+    /// Returns the caller of the frame, returned by `CALLER`.
+    #[inline]
+    pub fn caller(&self) -> Address {
+        self.caller
+    }
+
     /// Returns whether the given position is a valid jump destination.
     #[inline]
     pub fn is_valid_jump(&self, pos: usize) -> bool {
@@ -0,0 +1,48 @@
+use std::time::Instant;
+
+/// Wall-clock deadline of an execution, checked by the interpreter loop.
+///
+/// The clock is read once every [`Deadline::CHECK_INTERVAL`] instructions, so the execution can
+/// run slightly past the deadline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Deadline {
+    at: Instant,
+    countdown: u32,
+}
+
+impl Deadline {
+    /// Number of instructions executed between two reads of the clock.
+    pub const CHECK_INTERVAL: u32 = 1024;
+
+    /// Creates a deadline at the given instant.
+    #[inline]
+    pub fn new(at: Instant) -> Self {
+        Self {
+            at,
+            countdown: Self::CHECK_INTERVAL,
+        }
+    }
+
+    /// Returns the instant of the deadline.
+    #[inline]
+    pub fn at(&self) -> Instant {
+        self.at
+    }
+
+    /// Returns `true` if the deadline has passed.
+    #[inline]
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.at
+    }
+
+    /// Counts an executed instruction and returns `true` if the deadline has passed.
+    #[inline]
+    pub(crate) fn tick(&mut self) -> bool {
+        self.countdown -= 1;
+        if self.countdown != 0 {
+            return false;
+        }
+        self.countdown = Self::CHECK_INTERVAL;
+        self.is_expired()
+    }
+}
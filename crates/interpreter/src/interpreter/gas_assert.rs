@@ -0,0 +1,313 @@
+//! Cross-check of the gas charged by the instructions, enabled by the `gas_assert` feature.
+//!
+//! Every step recomputes the gas of the executed opcode from the stack and memory it started
+//! with, and panics if the instruction charged a different amount. The reference costs are
+//! written out opcode by opcode, with prices of their own instead of the
+//! [`GasSchedule`](crate::gas::GasSchedule) and cost functions the instructions use, so a
+//! repricing that only reaches one of the two is caught by the tests.
+//!
+//! Opcodes whose cost depends on the host, e.g. `SLOAD` or `CALL`, steps that stop with an
+//! error and interpreters running a custom gas schedule are not checked. Instruction tables that
+//! replace an opcode with an instruction of a different cost must not be used with the feature.
+
+use super::Interpreter;
+use crate::{
+    gas::SharedGasSchedule,
+    opcode::{self, OpCode},
+    primitives::{SpecId, U256},
+};
+
+/// Prices of the checked opcodes in a hardfork, kept apart from
+/// [`GasSchedule::for_spec`](crate::gas::GasSchedule::for_spec).
+struct Prices {
+    base: u64,
+    verylow: u64,
+    low: u64,
+    mid: u64,
+    high: u64,
+    jumpdest: u64,
+    condition_jump: u64,
+    retf: u64,
+    data_load: u64,
+    blockhash: u64,
+    warm_storage_read: u64,
+    exp: u64,
+    exp_byte: u64,
+    keccak256: u64,
+    keccak256_word: u64,
+    copy: u64,
+    log: u64,
+    log_topic: u64,
+    log_data: u64,
+    memory: u64,
+    memory_quad_divisor: u64,
+}
+
+impl Prices {
+    /// Returns the prices of the `spec_id` hardfork.
+    const fn for_spec(spec_id: SpecId) -> Self {
+        Self {
+            base: 2,
+            verylow: 3,
+            low: 5,
+            mid: 8,
+            high: 10,
+            jumpdest: 1,
+            condition_jump: 4,
+            retf: 3,
+            data_load: 4,
+            blockhash: 20,
+            warm_storage_read: 100,
+            exp: 10,
+            // EIP-160: EXP cost increase
+            exp_byte: if spec_id.is_enabled_in(SpecId::SPURIOUS_DRAGON) {
+                50
+            } else {
+                10
+            },
+            keccak256: 30,
+            keccak256_word: 6,
+            copy: 3,
+            log: 375,
+            log_topic: 375,
+            log_data: 8,
+            memory: 3,
+            memory_quad_divisor: 512,
+        }
+    }
+}
+
+/// State of the interpreter before a step, see [`GasCheck::verify`].
+pub(crate) struct GasCheck {
+    pc: usize,
+    opcode: u8,
+    remaining: u64,
+    memory_len: usize,
+    inputs: [U256; 3],
+}
+
+impl GasCheck {
+    /// Records the state before the step executing `opcode`.
+    #[inline]
+    pub(crate) fn new(interpreter: &Interpreter, opcode: u8) -> Self {
+        let mut inputs = [U256::ZERO; 3];
+        for (i, input) in inputs.iter_mut().enumerate() {
+            *input = interpreter.stack.peek(i).unwrap_or_default();
+        }
+        Self {
+            pc: interpreter.program_counter(),
+            opcode,
+            remaining: interpreter.gas.remaining(),
+            memory_len: interpreter.shared_memory.len(),
+            inputs,
+        }
+    }
+
+    /// Panics if the step charged a different gas than the reference cost of its opcode.
+    #[inline]
+    pub(crate) fn verify(self, interpreter: &Interpreter) {
+        let result = interpreter.instruction_result;
+        if !(result.is_ok() || result.is_revert()) {
+            return;
+        }
+        if let SharedGasSchedule::Shared(_) = interpreter.gas_schedule {
+            return;
+        }
+        let Some(expected) = self.reference_cost(&Prices::for_spec(interpreter.spec_id)) else {
+            return;
+        };
+        let charged = self.remaining - interpreter.gas.remaining();
+        assert_eq!(
+            charged,
+            expected,
+            "gas mismatch for {} at pc {} in {:?}: charged {charged}, reference {expected}, \
+             inputs {:?}, memory {} bytes",
+            OpCode::name_by_op(self.opcode),
+            self.pc,
+            interpreter.spec_id,
+            self.inputs,
+            self.memory_len,
+        );
+    }
+
+    /// Returns the reference cost of the step, `None` if it is not checked.
+    fn reference_cost(&self, s: &Prices) -> Option<u64> {
+        let [a, b, c] = self.inputs;
+        let cost = match self.opcode {
+            opcode::STOP | opcode::INVALID => 0,
+
+            opcode::ADDRESS
+            | opcode::ORIGIN
+            | opcode::CALLER
+            | opcode::CALLVALUE
+            | opcode::CALLDATASIZE
+            | opcode::CODESIZE
+            | opcode::GASPRICE
+            | opcode::RETURNDATASIZE
+            | opcode::COINBASE
+            | opcode::TIMESTAMP
+            | opcode::NUMBER
+            | opcode::DIFFICULTY
+            | opcode::GASLIMIT
+            | opcode::CHAINID
+            | opcode::BASEFEE
+            | opcode::BLOBBASEFEE
+            | opcode::POP
+            | opcode::PC
+            | opcode::MSIZE
+            | opcode::GAS
+            | opcode::PUSH0
+            | opcode::DATASIZE
+            | opcode::RJUMP => s.base,
+
+            opcode::ADD
+            | opcode::SUB
+            | opcode::NOT
+            | opcode::LT
+            | opcode::GT
+            | opcode::SLT
+            | opcode::SGT
+            | opcode::EQ
+            | opcode::ISZERO
+            | opcode::AND
+            | opcode::OR
+            | opcode::XOR
+            | opcode::BYTE
+            | opcode::SHL
+            | opcode::SHR
+            | opcode::SAR
+            | opcode::CALLDATALOAD
+            | opcode::BLOBHASH
+            | opcode::DUPN
+            | opcode::SWAPN
+            | opcode::EXCHANGE
+            | opcode::DATALOADN
+            | opcode::RETURNDATALOAD
+            | opcode::PUSH1..=opcode::PUSH32
+            | opcode::DUP1..=opcode::DUP16
+            | opcode::SWAP1..=opcode::SWAP16 => s.verylow,
+
+            opcode::MUL
+            | opcode::DIV
+            | opcode::SDIV
+            | opcode::MOD
+            | opcode::SMOD
+            | opcode::SIGNEXTEND
+            | opcode::SELFBALANCE
+            | opcode::CALLF
+            | opcode::JUMPF => s.low,
+
+            opcode::ADDMOD | opcode::MULMOD | opcode::JUMP => s.mid,
+            opcode::JUMPI => s.high,
+            opcode::JUMPDEST => s.jumpdest,
+            opcode::RJUMPI | opcode::RJUMPV => s.condition_jump,
+            opcode::RETF => s.retf,
+            opcode::DATALOAD => s.data_load,
+            opcode::BLOCKHASH => s.blockhash,
+            opcode::TLOAD | opcode::TSTORE => s.warm_storage_read,
+
+            opcode::MLOAD | opcode::MSTORE => s.verylow + self.memory_cost(s, a, U256::from(32))?,
+            opcode::MSTORE8 => s.verylow + self.memory_cost(s, a, U256::from(1))?,
+            opcode::EXP => {
+                let bytes = (b.bit_len() as u64).div_ceil(8);
+                s.exp + s.exp_byte * bytes
+            }
+            opcode::KECCAK256 => {
+                s.keccak256 + s.keccak256_word * words(b)? + self.memory_cost(s, a, b)?
+            }
+            opcode::CALLDATACOPY | opcode::CODECOPY | opcode::RETURNDATACOPY | opcode::DATACOPY => {
+                s.verylow + s.copy * words(c)? + self.memory_cost(s, a, c)?
+            }
+            opcode::MCOPY => s.verylow + s.copy * words(c)? + self.memory_cost(s, a.max(b), c)?,
+            opcode::LOG0..=opcode::LOG4 => {
+                let topics = (self.opcode - opcode::LOG0) as u64;
+                s.log
+                    + s.log_topic * topics
+                    + s.log_data * u64::try_from(b).ok()?
+                    + self.memory_cost(s, a, b)?
+            }
+            opcode::RETURN | opcode::REVERT => self.memory_cost(s, a, b)?,
+
+            _ => return None,
+        };
+        Some(cost)
+    }
+
+    /// Cost of expanding the memory to cover `len` bytes at `offset`.
+    fn memory_cost(&self, s: &Prices, offset: U256, len: U256) -> Option<u64> {
+        if len.is_zero() {
+            return Some(0);
+        }
+        let end = offset.checked_add(len)?;
+        let old_words = (self.memory_len as u64).div_ceil(32);
+        let new_words = u64::try_from(end).ok()?.div_ceil(32).max(old_words);
+        let total = |words: u64| {
+            let words = words as u128;
+            words * s.memory as u128 + words * words / s.memory_quad_divisor as u128
+        };
+        u64::try_from(total(new_words) - total(old_words)).ok()
+    }
+}
+
+/// Number of words of `len` bytes.
+fn words(len: U256) -> Option<u64> {
+    Some(u64::try_from(len).ok()?.div_ceil(32))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        opcode::{make_instruction_table, make_runtime_instruction_table},
+        primitives::{Bytecode, Bytes, CancunSpec, SpecId},
+        DummyHost, Gas, Interpreter,
+    };
+
+    fn run(code: &'static [u8], spec_id: SpecId) -> Interpreter {
+        let mut interp = Interpreter::new_bytecode(Bytecode::LegacyRaw(Bytes::from_static(code)))
+            .with_spec_id(spec_id);
+        interp.gas = Gas::new(1_000_000);
+        let table = make_runtime_instruction_table::<DummyHost>();
+        interp.run(Default::default(), &table, &mut DummyHost::default());
+        interp
+    }
+
+    #[test]
+    fn dynamic_costs_match() {
+        // PUSH1 0xff PUSH2 0x0100 EXP POP
+        // PUSH1 0x40 PUSH1 0x10 KECCAK256 POP
+        // PUSH1 0x20 PUSH1 0 PUSH2 0x0200 CALLDATACOPY
+        // PUSH1 0x40 PUSH1 0 LOG0 PUSH1 0x60 PUSH1 0 RETURN
+        let code = &[
+            0x60, 0xff, 0x61, 0x01, 0x00, 0x0a, 0x50, 0x60, 0x40, 0x60, 0x10, 0x20, 0x50, 0x60,
+            0x20, 0x60, 0x00, 0x61, 0x02, 0x00, 0x37, 0x60, 0x40, 0x60, 0x00, 0xa0, 0x60, 0x60,
+            0x60, 0x00, 0xf3,
+        ];
+        for spec_id in [SpecId::FRONTIER, SpecId::BERLIN, SpecId::CANCUN] {
+            let interp = run(code, spec_id);
+            assert!(interp.instruction_result.is_ok());
+        }
+
+        // PUSH1 0x20 PUSH1 0x10 PUSH1 0x30 MCOPY PUSH1 0x20 PUSH2 0x0100 TSTORE STOP
+        let code = &[
+            0x60, 0x20, 0x60, 0x10, 0x60, 0x30, 0x5e, 0x60, 0x20, 0x61, 0x01, 0x00, 0x5d, 0x00,
+        ];
+        let interp = run(code, SpecId::CANCUN);
+        assert!(interp.instruction_result.is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "gas mismatch for ADD")]
+    fn mismatch_panics() {
+        fn cheap_add(interp: &mut Interpreter, _host: &mut DummyHost) {
+            interp.instruction_result = crate::InstructionResult::Continue;
+            let _ = interp.stack.pop();
+        }
+        let mut table = make_instruction_table::<DummyHost, CancunSpec>();
+        table[crate::opcode::ADD as usize] = cheap_add;
+        // PUSH1 1 PUSH1 2 ADD
+        let bytecode = Bytecode::LegacyRaw(Bytes::from_static(&[0x60, 1, 0x60, 2, 0x01]));
+        let mut interp = Interpreter::new_bytecode(bytecode);
+        interp.gas = Gas::new(1_000);
+        interp.run(Default::default(), &table, &mut DummyHost::default());
+    }
+}
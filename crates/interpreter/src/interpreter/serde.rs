@@ -1,9 +1,12 @@
 use super::Interpreter;
 use crate::{
+    gas::{GasSchedule, SharedGasSchedule},
     Contract, FunctionStack, Gas, InstructionResult, InterpreterAction, SharedMemory, Stack,
 };
-use revm_primitives::Bytes;
+use core::ops::Range;
+use revm_primitives::{Bytes, SpecId};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::VecDeque;
 
 #[derive(Serialize)]
 struct InterpreterSerde<'a> {
@@ -21,6 +24,10 @@ struct InterpreterSerde<'a> {
     return_data_buffer: &'a Bytes,
     is_static: bool,
     next_action: &'a InterpreterAction,
+    action_queue: &'a VecDeque<InterpreterAction>,
+    return_range: &'a Option<Range<usize>>,
+    spec_id: SpecId,
+    gas_schedule: Option<&'a GasSchedule>,
 }
 
 #[derive(Deserialize)]
@@ -39,6 +46,14 @@ struct InterpreterDe {
     return_data_buffer: Bytes,
     is_static: bool,
     next_action: InterpreterAction,
+    #[serde(default)]
+    action_queue: VecDeque<InterpreterAction>,
+    #[serde(default)]
+    return_range: Option<Range<usize>>,
+    #[serde(default)]
+    spec_id: SpecId,
+    #[serde(default)]
+    gas_schedule: Option<GasSchedule>,
 }
 
 impl Serialize for Interpreter {
@@ -60,6 +75,10 @@ impl Serialize for Interpreter {
             return_data_buffer: &self.return_data_buffer,
             is_static: self.is_static,
             next_action: &self.next_action,
+            action_queue: &self.action_queue,
+            return_range: &self.return_range,
+            spec_id: self.spec_id,
+            gas_schedule: Some(&*self.gas_schedule),
         }
         .serialize(serializer)
     }
@@ -84,6 +103,10 @@ impl<'de> Deserialize<'de> for Interpreter {
             return_data_buffer,
             is_static,
             next_action,
+            action_queue,
+            return_range,
+            spec_id,
+            gas_schedule,
         } = InterpreterDe::deserialize(deserializer)?;
 
         // Reconstruct the instruction pointer from usize
@@ -108,6 +131,24 @@ impl<'de> Deserialize<'de> for Interpreter {
             return_data_buffer,
             is_static,
             next_action,
+            action_queue,
+            return_range,
+            spec_id,
+            // The costs of the hardfork are shared, see `SharedGasSchedule::StaticRef`.
+            gas_schedule: match gas_schedule {
+                Some(gas_schedule) if gas_schedule != *GasSchedule::for_spec_static(spec_id) => {
+                    gas_schedule.into()
+                }
+                _ => SharedGasSchedule::for_spec(spec_id),
+            },
+            #[cfg(feature = "opcode_stats")]
+            opcode_counts: None,
+            #[cfg(feature = "gas_profile")]
+            gas_usage: None,
+            #[cfg(feature = "gas_profile")]
+            gas_profile: None,
+            #[cfg(feature = "execution_timeout")]
+            deadline: None,
         })
     }
 }
@@ -122,5 +163,14 @@ mod tests {
         let serialized = bincode::serialize(&interp).unwrap();
         let de: Interpreter = bincode::deserialize(&serialized).unwrap();
         assert_eq!(interp.program_counter(), de.program_counter());
+        assert!(matches!(de.gas_schedule, SharedGasSchedule::StaticRef(_)));
+
+        let interp = interp.with_gas_schedule(GasSchedule {
+            base: 1,
+            ..Default::default()
+        });
+        let serialized = bincode::serialize(&interp).unwrap();
+        let de: Interpreter = bincode::deserialize(&serialized).unwrap();
+        assert_eq!(de.gas_schedule, interp.gas_schedule);
     }
 }
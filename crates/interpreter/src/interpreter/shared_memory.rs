@@ -1,5 +1,5 @@
 use core::{cmp::min, fmt, ops::Range};
-use revm_primitives::{B256, U256};
+use revm_primitives::{Bytes, B256, U256};
 use std::vec::Vec;
 
 /// A sequential memory shared between calls, which uses
@@ -111,6 +111,13 @@ impl SharedMemory {
         }
     }
 
+    /// Returns the number of contexts sharing the memory, i.e. the call depth when every call
+    /// frame creates a new context.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.checkpoints.len()
+    }
+
     /// Returns the length of the current memory range.
     #[inline]
     pub fn len(&self) -> usize {
@@ -160,6 +167,32 @@ impl SharedMemory {
         }
     }
 
+    /// Returns the memory region at the given offset as [`Bytes`].
+    ///
+    /// When the current context is the root context and the region covers at least half of the
+    /// buffer, the buffer is moved into the returned `Bytes` without copying and the memory is
+    /// left empty. The buffer is then freed once the last reference to the output is dropped.
+    ///
+    /// In every other case the region is copied and the memory is left untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics on out of bounds.
+    #[inline]
+    #[cfg_attr(debug_assertions, track_caller)]
+    pub fn take_slice_bytes(&mut self, offset: usize, size: usize) -> Bytes {
+        let is_root = self.checkpoints.len() <= 1 && self.last_checkpoint == 0;
+        if !is_root || size < self.buffer.len() / 2 {
+            return Bytes::copy_from_slice(self.slice(offset, size));
+        }
+        let end = offset + size;
+        if end > self.buffer.len() {
+            debug_unreachable!("slice OOB: {offset}..{end}; len: {}", self.len());
+        }
+        let buffer = core::mem::take(&mut self.buffer);
+        Bytes::from(buffer).slice(offset..end)
+    }
+
     /// Returns a byte slice of the memory region at the given offset.
     ///
     /// # Panics
@@ -269,13 +302,11 @@ impl SharedMemory {
         let data_len = data_end - data_offset;
         debug_assert!(data_offset < data.len() && data_end <= data.len());
         let data = unsafe { data.get_unchecked(data_offset..data_end) };
-        self.slice_mut(memory_offset, data_len)
-            .copy_from_slice(data);
-
+        // single bounds check for the whole destination, then memcpy and zero the rest.
+        let (copied, rest) = self.slice_mut(memory_offset, len).split_at_mut(data_len);
+        copied.copy_from_slice(data);
         // nullify rest of memory slots
-        // SAFETY: Memory is assumed to be valid, and it is commented where this assumption is made.
-        self.slice_mut(memory_offset + data_len, len - data_len)
-            .fill(0);
+        rest.fill(0);
     }
 
     /// Copies elements from one part of the memory to another part of itself.
@@ -286,6 +317,7 @@ impl SharedMemory {
     #[inline]
     #[cfg_attr(debug_assertions, track_caller)]
     pub fn copy(&mut self, dst: usize, src: usize, len: usize) {
+        // `copy_within` is a memmove, the ranges are allowed to overlap.
         self.context_memory_mut().copy_within(src..src + len, dst);
     }
 
@@ -332,6 +364,32 @@ mod tests {
         assert_eq!(num_words(u64::MAX), u64::MAX / 32);
     }
 
+    #[test]
+    fn take_slice_bytes() {
+        let mut shared_memory = SharedMemory::new();
+        shared_memory.new_context();
+        shared_memory.resize(64);
+        shared_memory.set(0, &[1; 64]);
+
+        // nested context copies the region.
+        shared_memory.new_context();
+        shared_memory.resize(32);
+        shared_memory.set(0, &[2; 32]);
+        assert_eq!(shared_memory.take_slice_bytes(0, 32)[..], [2; 32]);
+        assert_eq!(shared_memory.len(), 32);
+        shared_memory.free_context();
+
+        // small region of the root context is copied.
+        assert_eq!(shared_memory.take_slice_bytes(0, 4)[..], [1; 4]);
+        assert_eq!(shared_memory.len(), 64);
+
+        // big region of the root context takes the buffer.
+        let output = shared_memory.take_slice_bytes(16, 48);
+        assert_eq!(output[..], [1; 48]);
+        assert!(shared_memory.is_empty());
+        shared_memory.free_context();
+    }
+
     #[test]
     fn new_free_context() {
         let mut shared_memory = SharedMemory::new();
@@ -0,0 +1,65 @@
+use super::{Interpreter, Stack};
+use crate::{Gas, InstructionResult};
+
+/// State of the interpreter around the execution of one instruction, passed to a [`Tracer`].
+#[derive(Clone, Copy, Debug)]
+pub struct TraceStep<'a> {
+    /// Program counter of the instruction.
+    pub pc: usize,
+    /// Opcode of the instruction.
+    pub opcode: u8,
+    /// Gas of the frame.
+    pub gas: &'a Gas,
+    /// Stack of the frame.
+    pub stack: &'a Stack,
+    /// Memory of the frame.
+    pub memory: &'a [u8],
+    /// Call depth, see [`SharedMemory::depth`](super::SharedMemory::depth).
+    pub depth: usize,
+    /// Result of the frame. `Continue` unless the frame stopped.
+    pub result: InstructionResult,
+}
+
+impl<'a> TraceStep<'a> {
+    pub(crate) fn new(interp: &'a Interpreter, pc: usize, opcode: u8) -> Self {
+        Self {
+            pc,
+            opcode,
+            gas: &interp.gas,
+            stack: &interp.stack,
+            memory: interp.shared_memory.context_memory(),
+            depth: interp.shared_memory.depth(),
+            result: interp.instruction_result,
+        }
+    }
+}
+
+/// Opcode-level tracer called by [`Interpreter::run_with_tracer`].
+///
+/// Unlike an inspector, it does not need the instruction table to be wrapped, which makes it
+/// usable by embedders running the interpreter without the revm handlers.
+pub trait Tracer<H: ?Sized> {
+    /// Called before the instruction is executed.
+    #[inline]
+    fn step(&mut self, step: &TraceStep<'_>, host: &mut H) {
+        let _ = (step, host);
+    }
+
+    /// Called after the instruction is executed, with the same `pc` and `opcode`.
+    #[inline]
+    fn step_end(&mut self, step: &TraceStep<'_>, host: &mut H) {
+        let _ = (step, host);
+    }
+}
+
+impl<H: ?Sized, T: Tracer<H> + ?Sized> Tracer<H> for &mut T {
+    #[inline]
+    fn step(&mut self, step: &TraceStep<'_>, host: &mut H) {
+        (**self).step(step, host)
+    }
+
+    #[inline]
+    fn step_end(&mut self, step: &TraceStep<'_>, host: &mut H) {
+        (**self).step_end(step, host)
+    }
+}
@@ -1,18 +1,52 @@
 mod call_inputs;
 mod call_outcome;
+mod child_effects;
 mod create_inputs;
 mod create_outcome;
 mod eof_create_inputs;
 
-pub use call_inputs::{CallInputs, CallScheme, CallValue};
+pub use call_inputs::{CallInputs, CallInputsBuilder, CallScheme, CallValue};
 pub use call_outcome::CallOutcome;
-pub use create_inputs::{CreateInputs, CreateScheme};
+pub use child_effects::{ChildEffects, GasBreakdown};
+pub use create_inputs::{create2_address, CreateInputs, CreateInputsBuilder, CreateScheme};
 pub use create_outcome::CreateOutcome;
 pub use eof_create_inputs::{EOFCreateInputs, EOFCreateKind};
 
 use crate::InterpreterResult;
+use core::fmt;
 use std::boxed::Box;
 
+/// Error returned by [`CallInputsBuilder::build`] and [`CreateInputsBuilder::build`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputsBuilderError {
+    /// The caller was not set.
+    MissingCaller,
+    /// The called account was not set.
+    MissingTarget,
+    /// The gas limit was not set.
+    MissingGasLimit,
+    /// A static call, or a call made in a static context, transfers value.
+    ValueInStaticCall,
+    /// The target address doesn't match the call scheme, e.g. a `CALLCODE` whose target is not
+    /// the caller.
+    TargetMismatch,
+}
+
+impl fmt::Display for InputsBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::MissingCaller => "missing caller",
+            Self::MissingTarget => "missing call target",
+            Self::MissingGasLimit => "missing gas limit",
+            Self::ValueInStaticCall => "value transfer in static call",
+            Self::TargetMismatch => "target address does not match the call scheme",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InputsBuilderError {}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InterpreterAction {
@@ -1,3 +1,4 @@
+use super::InputsBuilderError;
 use crate::primitives::{Address, Bytes, TxEnv, TxKind, U256};
 use core::ops::Range;
 use std::boxed::Box;
@@ -38,8 +39,13 @@ pub struct CallInputs {
     pub scheme: CallScheme,
     /// Whether the call is a static call, or is initiated inside a static call.
     pub is_static: bool,
-    /// Whether the call is initiated from EOF bytecode.
+    /// Whether the call is an EOF call, see [`CallScheme::is_ext`].
     pub is_eof: bool,
+    /// Whether the caller executes EOF bytecode.
+    ///
+    /// EOF bytecode can only call with the EOF call schemes, other schemes are rejected when the
+    /// frame is created.
+    pub caller_is_eof: bool,
 }
 
 impl CallInputs {
@@ -60,6 +66,7 @@ impl CallInputs {
             scheme: CallScheme::Call,
             is_static: false,
             is_eof: false,
+            caller_is_eof: false,
             return_memory_offset: 0..0,
         })
     }
@@ -71,6 +78,12 @@ impl CallInputs {
         Self::new(tx_env, gas_limit).map(Box::new)
     }
 
+    /// Returns a builder of call inputs that checks their consistency, see [`CallInputsBuilder`].
+    #[inline]
+    pub fn builder() -> CallInputsBuilder {
+        CallInputsBuilder::default()
+    }
+
     /// Returns `true` if the call will transfer a non-zero value.
     #[inline]
     pub fn transfers_value(&self) -> bool {
@@ -118,6 +131,141 @@ impl CallInputs {
     }
 }
 
+/// Builder of [`CallInputs`].
+///
+/// Addresses and the kind of value are derived from the [`CallScheme`], [`CallScheme::Call`] by
+/// default:
+/// - `CALL`, `STATICCALL` and their EOF variants execute and modify the account set with
+///   [`to`](Self::to).
+/// - `CALLCODE` executes the code of `to` on the account of the caller.
+/// - `DELEGATECALL` and `EXTDELEGATECALL` execute the code of `to` on the account set with
+///   [`target_address`](Self::target_address), and their value is only apparent.
+///
+/// EOF schemes don't write their output to memory, and static schemes are always static.
+#[derive(Clone, Debug, Default)]
+pub struct CallInputsBuilder {
+    scheme: Option<CallScheme>,
+    caller: Option<Address>,
+    to: Option<Address>,
+    target_address: Option<Address>,
+    value: U256,
+    input: Bytes,
+    gas_limit: Option<u64>,
+    is_static: bool,
+    return_memory_offset: Range<usize>,
+}
+
+impl CallInputsBuilder {
+    /// Sets the call scheme.
+    pub fn scheme(mut self, scheme: CallScheme) -> Self {
+        self.scheme = Some(scheme);
+        self
+    }
+
+    /// Sets the caller.
+    pub fn caller(mut self, caller: Address) -> Self {
+        self.caller = Some(caller);
+        self
+    }
+
+    /// Sets the account whose bytecode is executed.
+    pub fn to(mut self, to: Address) -> Self {
+        self.to = Some(to);
+        self
+    }
+
+    /// Sets the account whose storage is modified.
+    ///
+    /// Required for delegate calls. Other schemes derive it and only check that it matches.
+    pub fn target_address(mut self, target_address: Address) -> Self {
+        self.target_address = Some(target_address);
+        self
+    }
+
+    /// Sets the call value.
+    pub fn value(mut self, value: U256) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Sets the call data.
+    pub fn input(mut self, input: Bytes) -> Self {
+        self.input = input;
+        self
+    }
+
+    /// Sets the gas limit.
+    pub fn gas(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Sets whether the call is made in a static context.
+    pub fn is_static(mut self, is_static: bool) -> Self {
+        self.is_static = is_static;
+        self
+    }
+
+    /// Sets the memory range the output is written to.
+    pub fn return_memory_offset(mut self, return_memory_offset: Range<usize>) -> Self {
+        self.return_memory_offset = return_memory_offset;
+        self
+    }
+
+    /// Builds the call inputs.
+    pub fn build(self) -> Result<CallInputs, InputsBuilderError> {
+        let scheme = self.scheme.unwrap_or(CallScheme::Call);
+        let caller = self.caller.ok_or(InputsBuilderError::MissingCaller)?;
+        let to = self.to.ok_or(InputsBuilderError::MissingTarget)?;
+        let gas_limit = self.gas_limit.ok_or(InputsBuilderError::MissingGasLimit)?;
+
+        let (target_address, value) = match scheme {
+            CallScheme::Call
+            | CallScheme::ExtCall
+            | CallScheme::StaticCall
+            | CallScheme::ExtStaticCall => (to, CallValue::Transfer(self.value)),
+            CallScheme::CallCode => (caller, CallValue::Transfer(self.value)),
+            CallScheme::DelegateCall | CallScheme::ExtDelegateCall => (
+                self.target_address
+                    .ok_or(InputsBuilderError::MissingTarget)?,
+                CallValue::Apparent(self.value),
+            ),
+        };
+        if self
+            .target_address
+            .is_some_and(|address| address != target_address)
+        {
+            return Err(InputsBuilderError::TargetMismatch);
+        }
+
+        let is_static =
+            self.is_static || matches!(scheme, CallScheme::StaticCall | CallScheme::ExtStaticCall);
+        // `CALLCODE` transfers the value to the caller itself, which is allowed in static calls.
+        if is_static && scheme != CallScheme::CallCode && value.transfer() > Some(U256::ZERO) {
+            return Err(InputsBuilderError::ValueInStaticCall);
+        }
+
+        let is_eof = scheme.is_ext();
+        Ok(CallInputs {
+            input: self.input,
+            return_memory_offset: if is_eof {
+                0..0
+            } else {
+                self.return_memory_offset
+            },
+            gas_limit,
+            bytecode_address: to,
+            target_address,
+            caller,
+            value,
+            scheme,
+            is_static,
+            is_eof,
+            caller_is_eof: is_eof,
+        })
+    }
+}
+
 /// Call scheme.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -211,3 +359,68 @@ impl CallValue {
         matches!(self, Self::Apparent(_))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_call_inputs() {
+        let caller = Address::with_last_byte(1);
+        let to = Address::with_last_byte(2);
+        let value = U256::from(10);
+
+        let inputs = CallInputs::builder()
+            .caller(caller)
+            .to(to)
+            .value(value)
+            .gas(100_000)
+            .build()
+            .unwrap();
+        assert_eq!(inputs.target_address, to);
+        assert_eq!(inputs.transfer_value(), Some(value));
+        assert!(!inputs.is_static && !inputs.is_eof);
+
+        let call_code = CallInputs::builder()
+            .scheme(CallScheme::CallCode)
+            .caller(caller)
+            .to(to)
+            .gas(100_000);
+        assert_eq!(call_code.clone().build().unwrap().target_address, caller);
+        assert_eq!(
+            call_code.target_address(to).build(),
+            Err(InputsBuilderError::TargetMismatch)
+        );
+
+        let delegate = CallInputs::builder()
+            .scheme(CallScheme::DelegateCall)
+            .caller(caller)
+            .to(to)
+            .value(value)
+            .gas(100_000);
+        assert_eq!(
+            delegate.clone().build(),
+            Err(InputsBuilderError::MissingTarget)
+        );
+        let inputs = delegate
+            .target_address(Address::with_last_byte(3))
+            .is_static(true)
+            .build()
+            .unwrap();
+        assert_eq!(inputs.apparent_value(), Some(value));
+
+        let static_call = CallInputs::builder()
+            .scheme(CallScheme::StaticCall)
+            .caller(caller)
+            .to(to)
+            .value(value);
+        assert_eq!(
+            static_call.clone().build(),
+            Err(InputsBuilderError::MissingGasLimit)
+        );
+        assert_eq!(
+            static_call.gas(100_000).build(),
+            Err(InputsBuilderError::ValueInStaticCall)
+        );
+    }
+}
@@ -1,6 +1,7 @@
-use crate::{Gas, InstructionResult, InterpreterResult};
+use super::child_effects::propagated_logs;
+use crate::{ChildEffects, Gas, GasBreakdown, InstructionResult, InterpreterResult};
 use core::ops::Range;
-use revm_primitives::Bytes;
+use revm_primitives::{Bytes, Log};
 
 /// Represents the outcome of a call operation in a virtual machine.
 ///
@@ -11,11 +12,17 @@ use revm_primitives::Bytes;
 ///
 /// * `result` - The result of the interpreter's execution, including output data and gas usage.
 /// * `memory_offset` - The range in memory where the output data is located.
+/// * `is_static` - Whether the call was executed in a static context.
+/// * `effects` - Logs of the call, set by hosts that don't use the revm journal.
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CallOutcome {
     pub result: InterpreterResult,
     pub memory_offset: Range<usize>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub is_static: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub effects: Option<ChildEffects>,
 }
 
 impl CallOutcome {
@@ -31,9 +38,33 @@ impl CallOutcome {
         Self {
             result,
             memory_offset,
+            is_static: false,
+            effects: None,
         }
     }
 
+    /// Sets the effects of the call.
+    pub fn with_effects(mut self, effects: ChildEffects) -> Self {
+        self.effects = Some(effects);
+        self
+    }
+
+    /// Returns the logs the caller keeps, empty if the call did not succeed or has no effects.
+    pub fn propagated_logs(&self) -> &[Log] {
+        propagated_logs(self.result.result, self.effects.as_ref())
+    }
+
+    /// Returns the gas breakdown of the call as seen by the caller.
+    pub fn gas_breakdown(&self) -> GasBreakdown {
+        GasBreakdown::new(self.result.result, &self.result.gas)
+    }
+
+    /// Sets whether the call was executed in a static context.
+    pub fn with_static(mut self, is_static: bool) -> Self {
+        self.is_static = is_static;
+        self
+    }
+
     /// Returns a reference to the instruction result.
     ///
     /// Provides access to the result of the executed instruction.
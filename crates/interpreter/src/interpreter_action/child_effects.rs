@@ -0,0 +1,103 @@
+use crate::{return_ok, return_revert, Gas, InstructionResult};
+use revm_primitives::Log;
+use std::vec::Vec;
+
+/// Effects of a sub-execution that the revm handlers keep in the journal.
+///
+/// Hosts that use the interpreter without the revm handlers can attach them to
+/// [`CallOutcome`](crate::CallOutcome) and [`CreateOutcome`](crate::CreateOutcome), so that the
+/// parent frame can propagate them without a journal.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChildEffects {
+    /// Logs emitted by the sub-execution and its children, in emission order.
+    pub logs: Vec<Log>,
+}
+
+/// Gas of a sub-execution, as seen by its parent frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GasBreakdown {
+    /// Gas limit of the sub-execution.
+    pub limit: u64,
+    /// Gas spent by the sub-execution, which is not returned to the parent.
+    pub spent: u64,
+    /// Change of the refund counter of the parent, zero unless the sub-execution succeeded.
+    pub refund_change: i64,
+}
+
+impl GasBreakdown {
+    /// Returns the gas breakdown of a sub-execution that finished with the given result.
+    pub fn new(result: InstructionResult, gas: &Gas) -> Self {
+        // Halts consume all the gas, reverts return the remaining gas without the refund.
+        let (spent, refund_change) = match result {
+            return_ok!() => (gas.spent(), gas.refunded()),
+            return_revert!() => (gas.spent(), 0),
+            _ => (gas.limit(), 0),
+        };
+        Self {
+            limit: gas.limit(),
+            spent,
+            refund_change,
+        }
+    }
+}
+
+/// Returns the logs of a sub-execution that the parent keeps, reverted sub-executions don't
+/// emit logs.
+pub(super) fn propagated_logs<'a>(
+    result: InstructionResult,
+    effects: Option<&'a ChildEffects>,
+) -> &'a [Log] {
+    match (result, effects) {
+        (return_ok!(), Some(effects)) => &effects.logs,
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CallOutcome, ChildEffects, Gas, InstructionResult, InterpreterResult};
+    use revm_primitives::{Address, Bytes, Log};
+
+    fn outcome(result: InstructionResult) -> CallOutcome {
+        let mut gas = Gas::new(100);
+        assert!(gas.record_cost(30));
+        gas.record_refund(10);
+        CallOutcome::new(InterpreterResult::new(result, Bytes::new(), gas), 0..0).with_effects(
+            ChildEffects {
+                logs: vec![Log::new_unchecked(Address::ZERO, vec![], Bytes::new())],
+            },
+        )
+    }
+
+    #[test]
+    fn propagation() {
+        let ok = outcome(InstructionResult::Return);
+        assert_eq!(ok.propagated_logs().len(), 1);
+        assert_eq!(
+            (ok.gas_breakdown().spent, ok.gas_breakdown().refund_change),
+            (30, 10)
+        );
+
+        let revert = outcome(InstructionResult::Revert);
+        assert!(revert.propagated_logs().is_empty());
+        assert_eq!(
+            (
+                revert.gas_breakdown().spent,
+                revert.gas_breakdown().refund_change
+            ),
+            (30, 0)
+        );
+
+        let halt = outcome(InstructionResult::OutOfGas);
+        assert!(halt.propagated_logs().is_empty());
+        assert_eq!(
+            (
+                halt.gas_breakdown().spent,
+                halt.gas_breakdown().refund_change
+            ),
+            (100, 0)
+        );
+    }
+}
@@ -1,7 +1,16 @@
+use super::InputsBuilderError;
 pub use crate::primitives::CreateScheme;
-use crate::primitives::{Address, Bytes, TxEnv, TxKind, U256};
+use crate::primitives::{keccak256, Address, Bytes, TxEnv, TxKind, B256, U256};
 use std::boxed::Box;
 
+/// Returns the address of a contract created with `CREATE2` by `caller`.
+///
+/// This is the derivation used by the EVM when executing `CREATE2`.
+#[inline]
+pub fn create2_address(caller: Address, salt: U256, init_code_hash: B256) -> Address {
+    caller.create2(salt.to_be_bytes(), init_code_hash)
+}
+
 /// Inputs for a create call.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -16,6 +25,8 @@ pub struct CreateInputs {
     pub init_code: Bytes,
     /// The gas limit of the call.
     pub gas_limit: u64,
+    /// Whether the caller executes EOF bytecode, which can only create with `EOFCREATE`.
+    pub caller_is_eof: bool,
 }
 
 impl CreateInputs {
@@ -31,6 +42,7 @@ impl CreateInputs {
             value: tx_env.value,
             init_code: tx_env.data.clone(),
             gas_limit,
+            caller_is_eof: false,
         })
     }
 
@@ -39,13 +51,79 @@ impl CreateInputs {
         Self::new(tx_env, gas_limit).map(Box::new)
     }
 
+    /// Returns a builder of create inputs, see [`CreateInputsBuilder`].
+    #[inline]
+    pub fn builder() -> CreateInputsBuilder {
+        CreateInputsBuilder::default()
+    }
+
     /// Returns the address that this create call will create.
     pub fn created_address(&self, nonce: u64) -> Address {
         match self.scheme {
             CreateScheme::Create => self.caller.create(nonce),
-            CreateScheme::Create2 { salt } => self
-                .caller
-                .create2_from_code(salt.to_be_bytes(), &self.init_code),
+            CreateScheme::Create2 { salt } => {
+                create2_address(self.caller, salt, keccak256(&self.init_code))
+            }
         }
     }
 }
+
+/// Builder of [`CreateInputs`].
+///
+/// Creates with [`CreateScheme::Create`], or with [`CreateScheme::Create2`] once a salt is set.
+#[derive(Clone, Debug, Default)]
+pub struct CreateInputsBuilder {
+    caller: Option<Address>,
+    salt: Option<U256>,
+    value: U256,
+    init_code: Bytes,
+    gas_limit: Option<u64>,
+}
+
+impl CreateInputsBuilder {
+    /// Sets the caller.
+    pub fn caller(mut self, caller: Address) -> Self {
+        self.caller = Some(caller);
+        self
+    }
+
+    /// Sets the `CREATE2` salt.
+    pub fn salt(mut self, salt: U256) -> Self {
+        self.salt = Some(salt);
+        self
+    }
+
+    /// Sets the value transferred to the created account.
+    pub fn value(mut self, value: U256) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Sets the init code.
+    pub fn init_code(mut self, init_code: Bytes) -> Self {
+        self.init_code = init_code;
+        self
+    }
+
+    /// Sets the gas limit.
+    pub fn gas(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Builds the create inputs.
+    pub fn build(self) -> Result<CreateInputs, InputsBuilderError> {
+        let caller = self.caller.ok_or(InputsBuilderError::MissingCaller)?;
+        let gas_limit = self.gas_limit.ok_or(InputsBuilderError::MissingGasLimit)?;
+        Ok(CreateInputs {
+            caller,
+            scheme: self
+                .salt
+                .map_or(CreateScheme::Create, |salt| CreateScheme::Create2 { salt }),
+            value: self.value,
+            init_code: self.init_code,
+            gas_limit,
+            caller_is_eof: false,
+        })
+    }
+}
@@ -1,5 +1,6 @@
-use crate::{Gas, InstructionResult, InterpreterResult};
-use revm_primitives::{Address, Bytes};
+use super::child_effects::propagated_logs;
+use crate::{ChildEffects, Gas, GasBreakdown, InstructionResult, InterpreterResult};
+use revm_primitives::{Address, Bytes, Log};
 
 /// Represents the outcome of a create operation in an interpreter.
 ///
@@ -12,6 +13,9 @@ pub struct CreateOutcome {
     pub result: InterpreterResult,
     // An optional address associated with the create operation.
     pub address: Option<Address>,
+    // Logs of the create operation, set by hosts that don't use the revm journal.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub effects: Option<ChildEffects>,
 }
 
 impl CreateOutcome {
@@ -26,7 +30,28 @@ impl CreateOutcome {
     ///
     /// A new `CreateOutcome` instance.
     pub fn new(result: InterpreterResult, address: Option<Address>) -> Self {
-        Self { result, address }
+        Self {
+            result,
+            address,
+            effects: None,
+        }
+    }
+
+    /// Sets the effects of the create operation.
+    pub fn with_effects(mut self, effects: ChildEffects) -> Self {
+        self.effects = Some(effects);
+        self
+    }
+
+    /// Returns the logs the creator keeps, empty if the creation did not succeed or has no
+    /// effects.
+    pub fn propagated_logs(&self) -> &[Log] {
+        propagated_logs(self.result.result, self.effects.as_ref())
+    }
+
+    /// Returns the gas breakdown of the create operation as seen by the creator.
+    pub fn gas_breakdown(&self) -> GasBreakdown {
+        GasBreakdown::new(self.result.result, &self.result.gas)
     }
 
     /// Retrieves a reference to the `InstructionResult` from the `InterpreterResult`.
@@ -25,19 +25,28 @@ pub mod instructions;
 pub mod interpreter;
 pub mod interpreter_action;
 pub mod opcode;
+#[cfg(any(test, feature = "test-utils"))]
+#[doc(hidden)]
+pub mod test_utils;
 
 // Reexport primary types.
 pub use function_stack::{FunctionReturnFrame, FunctionStack};
-pub use gas::Gas;
-pub use host::{DummyHost, Host, LoadAccountResult, SStoreResult, SelfDestructResult};
+pub use gas::{Gas, RefundGas, SpentGas};
+pub use host::{
+    DummyHost, Host, LoadAccountResult, SStoreResult, SelfDestructResult, StaticAccount,
+    StaticHost, StaticState, StaticStateError, StaticStateWriter,
+};
 pub use instruction_result::*;
+#[cfg(feature = "execution_timeout")]
+pub use interpreter::Deadline;
 pub use interpreter::{
-    analysis, num_words, Contract, Interpreter, InterpreterResult, SharedMemory, Stack,
-    EMPTY_SHARED_MEMORY, STACK_LIMIT,
+    analysis, num_words, Contract, Interpreter, InterpreterResult, SharedMemory, Stack, TraceStep,
+    Tracer, EMPTY_SHARED_MEMORY, STACK_LIMIT,
 };
 pub use interpreter_action::{
-    CallInputs, CallOutcome, CallScheme, CallValue, CreateInputs, CreateOutcome, CreateScheme,
-    EOFCreateInputs, EOFCreateKind, InterpreterAction,
+    create2_address, CallInputs, CallInputsBuilder, CallOutcome, CallScheme, CallValue,
+    ChildEffects, CreateInputs, CreateInputsBuilder, CreateOutcome, CreateScheme, EOFCreateInputs,
+    EOFCreateKind, GasBreakdown, InputsBuilderError, InterpreterAction,
 };
 pub use opcode::{Instruction, OpCode, OPCODE_INFO_JUMPTABLE};
 pub use primitives::{MAX_CODE_SIZE, MAX_INITCODE_SIZE};
@@ -2,14 +2,18 @@
 
 pub mod eof_printer;
 
+mod gas_table;
+pub use gas_table::{opcode_gas, opcode_gas_table, DynamicGas, OpCodeGas};
+
 mod tables;
 pub use tables::{
-    make_boxed_instruction_table, make_instruction_table, update_boxed_instruction,
-    BoxedInstruction, BoxedInstructionTable, DynInstruction, Instruction, InstructionTable,
-    InstructionTables,
+    make_boxed_hooked_instruction_table, make_boxed_instruction_table, make_instruction_table,
+    make_runtime_instruction_table, update_boxed_instruction, BoxedInstruction,
+    BoxedInstructionTable, DynInstruction, HookedInstruction, HookedInstructionTable, Instruction,
+    InstructionEntry, InstructionTable, InstructionTables,
 };
 
-use crate::{instructions::*, primitives::Spec, Host};
+use crate::{instructions::*, primitives::SpecSelector, Host};
 use core::{fmt, ptr::NonNull};
 
 /// An error indicating that an opcode is invalid.
@@ -417,7 +421,7 @@ macro_rules! opcodes {
         static NAME_TO_OPCODE: phf::Map<&'static str, OpCode> = stringify_with_cb! { phf_map_cb; $($name)* };
 
         /// Returns the instruction function for the given opcode and spec.
-        pub const fn instruction<H: Host + ?Sized, SPEC: Spec>(opcode: u8) -> Instruction<H> {
+        pub const fn instruction<H: Host + ?Sized, SPEC: SpecSelector>(opcode: u8) -> Instruction<H> {
             match opcode {
                 $($name => $f,)*
                 _ => control::unknown,
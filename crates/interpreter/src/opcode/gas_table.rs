@@ -0,0 +1,306 @@
+//! Machine-readable opcode gas cost table, see [`opcode_gas_table`].
+//!
+//! The table is derived from the constants and cost functions of the hardforks, the costs of
+//! [`GasSchedule::for_spec`](crate::gas::GasSchedule::for_spec). It does not follow a custom
+//! [`GasSchedule`](crate::gas::GasSchedule) set on the interpreter, whose costs can differ.
+
+use super::*;
+use crate::{gas, primitives::SpecId, SelfDestructResult};
+use std::vec::Vec;
+
+/// Formula of the dynamic part of an opcode gas cost, charged on top of its static gas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DynamicGas {
+    /// Memory expansion, see [`gas::memory_gas`].
+    MemoryExpansion,
+    /// Per byte of the exponent, see [`gas::exp_cost`].
+    ExpByte,
+    /// Per word hashed and memory expansion, see [`gas::keccak256_cost`].
+    Keccak256Word,
+    /// Per word copied and memory expansion, see [`gas::verylowcopy_cost`].
+    CopyWord,
+    /// Cold account surcharge, see [`gas::warm_cold_cost`].
+    ColdAccountAccess,
+    /// Cold account surcharge, per word copied and memory expansion, see
+    /// [`gas::extcodecopy_cost`].
+    ExtCodeCopy,
+    /// Cold slot surcharge, see [`gas::sload_cost`].
+    ColdSload,
+    /// Net gas metering of the slot transition, see [`gas::sstore_cost`].
+    Sstore,
+    /// Per byte of data and memory expansion, see [`gas::log_cost`].
+    LogData,
+    /// Per word of initcode, memory expansion and gas forwarded to the frame, see
+    /// [`gas::initcode_cost`].
+    Create,
+    /// [`Create`](Self::Create) and per word hashed for the address, see [`gas::create2_cost`].
+    Create2,
+    /// Per word of the initcontainer hashed, memory expansion and gas forwarded to the frame.
+    EofCreate,
+    /// Account access, value transfer, new account, memory expansion and gas forwarded to the
+    /// frame, see [`gas::call_cost`].
+    Call,
+    /// Cold beneficiary and new account, see [`gas::selfdestruct_cost`].
+    Selfdestruct,
+}
+
+impl DynamicGas {
+    /// Returns the stable identifier of the formula.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::MemoryExpansion => "memory_expansion",
+            Self::ExpByte => "exp_byte",
+            Self::Keccak256Word => "keccak256_word",
+            Self::CopyWord => "copy_word",
+            Self::ColdAccountAccess => "cold_account_access",
+            Self::ExtCodeCopy => "ext_code_copy",
+            Self::ColdSload => "cold_sload",
+            Self::Sstore => "sstore",
+            Self::LogData => "log_data",
+            Self::Create => "create",
+            Self::Create2 => "create2",
+            Self::EofCreate => "eof_create",
+            Self::Call => "call",
+            Self::Selfdestruct => "selfdestruct",
+        }
+    }
+}
+
+impl fmt::Display for DynamicGas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Gas cost of an opcode in a spec, see [`opcode_gas_table`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct OpCodeGas {
+    /// The opcode.
+    pub opcode: u8,
+    /// The opcode name.
+    pub name: &'static str,
+    /// Gas charged on every execution, with warm accesses.
+    pub static_gas: u64,
+    /// Formula of the gas charged on top of the static gas, if any.
+    pub dynamic_gas: Option<DynamicGas>,
+    /// Spec that introduced the opcode.
+    pub since: SpecId,
+}
+
+impl OpCode {
+    /// Returns the spec that introduced the opcode.
+    pub const fn since(self) -> SpecId {
+        match self.0 {
+            DELEGATECALL => SpecId::HOMESTEAD,
+            REVERT | RETURNDATASIZE | RETURNDATACOPY | STATICCALL => SpecId::BYZANTIUM,
+            SHL | SHR | SAR | EXTCODEHASH => SpecId::CONSTANTINOPLE,
+            CREATE2 => SpecId::PETERSBURG,
+            CHAINID | SELFBALANCE => SpecId::ISTANBUL,
+            BASEFEE => SpecId::LONDON,
+            PUSH0 => SpecId::SHANGHAI,
+            TLOAD | TSTORE | MCOPY | BLOBHASH | BLOBBASEFEE => SpecId::CANCUN,
+            DATALOAD | DATALOADN | DATASIZE | DATACOPY | RJUMP | RJUMPI | RJUMPV | CALLF | RETF
+            | JUMPF | DUPN | SWAPN | EXCHANGE | EOFCREATE | RETURNCONTRACT | RETURNDATALOAD
+            | EXTCALL | EXTDELEGATECALL | EXTSTATICCALL => SpecId::PRAGUE_EOF,
+            _ => SpecId::FRONTIER,
+        }
+    }
+}
+
+/// Returns the gas cost of `opcode` in `spec_id`.
+///
+/// Accesses are priced as warm, the cold surcharge is part of the dynamic gas.
+pub fn opcode_gas(opcode: OpCode, spec_id: SpecId) -> OpCodeGas {
+    let (static_gas, dynamic_gas) = opcode_cost(opcode.get(), spec_id);
+    OpCodeGas {
+        opcode: opcode.get(),
+        name: opcode.as_str(),
+        static_gas,
+        dynamic_gas,
+        since: opcode.since(),
+    }
+}
+
+/// Returns the gas costs of the opcodes enabled in `spec_id`, sorted by opcode.
+pub fn opcode_gas_table(spec_id: SpecId) -> Vec<OpCodeGas> {
+    (0..=u8::MAX)
+        .filter_map(OpCode::new)
+        .filter(|opcode| spec_id.is_enabled_in(opcode.since()))
+        .map(|opcode| opcode_gas(opcode, spec_id))
+        .collect()
+}
+
+/// Returns the static gas and the dynamic gas formula of a valid opcode.
+fn opcode_cost(opcode: u8, spec_id: SpecId) -> (u64, Option<DynamicGas>) {
+    // Costs of empty inputs can't overflow.
+    let zero_len = |cost: Option<u64>| cost.expect("zero length cost");
+    let cold_access = spec_id
+        .is_enabled_in(SpecId::BERLIN)
+        .then_some(DynamicGas::ColdAccountAccess);
+
+    match opcode {
+        STOP | INVALID => (gas::ZERO, None),
+        RETURN | REVERT | RETURNCONTRACT => (gas::ZERO, Some(DynamicGas::MemoryExpansion)),
+
+        ADDRESS | ORIGIN | CALLER | CALLVALUE | CALLDATASIZE | CODESIZE | GASPRICE
+        | RETURNDATASIZE | COINBASE | TIMESTAMP | NUMBER | DIFFICULTY | GASLIMIT | CHAINID
+        | BASEFEE | BLOBBASEFEE | POP | PC | MSIZE | GAS | PUSH0 | DATASIZE | RJUMP => {
+            (gas::BASE, None)
+        }
+        ADD | SUB | NOT | LT | GT | SLT | SGT | EQ | ISZERO | AND | OR | XOR | BYTE | SHL | SHR
+        | SAR | CALLDATALOAD | BLOBHASH | DUPN | SWAPN | EXCHANGE | DATALOADN | RETURNDATALOAD => {
+            (gas::VERYLOW, None)
+        }
+        PUSH1..=PUSH32 | DUP1..=DUP16 | SWAP1..=SWAP16 => (gas::VERYLOW, None),
+        MUL | DIV | SDIV | MOD | SMOD | SIGNEXTEND | SELFBALANCE | CALLF | JUMPF => {
+            (gas::LOW, None)
+        }
+        ADDMOD | MULMOD | JUMP => (gas::MID, None),
+        JUMPI => (gas::HIGH, None),
+        JUMPDEST => (gas::JUMPDEST, None),
+        RJUMPI | RJUMPV => (gas::CONDITION_JUMP_GAS, None),
+        RETF => (gas::RETF_GAS, None),
+        DATALOAD => (gas::DATA_LOAD_GAS, None),
+        BLOCKHASH => (gas::BLOCKHASH, None),
+        TLOAD | TSTORE => (gas::WARM_STORAGE_READ_COST, None),
+
+        MLOAD | MSTORE | MSTORE8 => (gas::VERYLOW, Some(DynamicGas::MemoryExpansion)),
+        EXP => (
+            zero_len(gas::exp_cost(spec_id, Default::default())),
+            Some(DynamicGas::ExpByte),
+        ),
+        KECCAK256 => (
+            zero_len(gas::keccak256_cost(0)),
+            Some(DynamicGas::Keccak256Word),
+        ),
+        CALLDATACOPY | CODECOPY | RETURNDATACOPY | MCOPY | DATACOPY => (
+            zero_len(gas::verylowcopy_cost(0)),
+            Some(DynamicGas::CopyWord),
+        ),
+        LOG0..=LOG4 => (
+            zero_len(gas::log_cost(opcode - LOG0, 0)),
+            Some(DynamicGas::LogData),
+        ),
+
+        BALANCE => (gas::balance_cost(spec_id, false), cold_access),
+        EXTCODESIZE => (gas::extcodesize_cost(spec_id, false), cold_access),
+        EXTCODEHASH => (gas::extcodehash_cost(spec_id, false), cold_access),
+        EXTCODECOPY => (
+            zero_len(gas::extcodecopy_cost(spec_id, 0, false)),
+            Some(DynamicGas::ExtCodeCopy),
+        ),
+        SLOAD => (
+            gas::sload_cost(spec_id, false),
+            spec_id
+                .is_enabled_in(SpecId::BERLIN)
+                .then_some(DynamicGas::ColdSload),
+        ),
+        SSTORE => (gas::ZERO, Some(DynamicGas::Sstore)),
+
+        CREATE => (gas::CREATE, Some(DynamicGas::Create)),
+        CREATE2 => (zero_len(gas::create2_cost(0)), Some(DynamicGas::Create2)),
+        EOFCREATE => (gas::EOF_CREATE_GAS, Some(DynamicGas::EofCreate)),
+        CALL | CALLCODE | DELEGATECALL | STATICCALL => (
+            gas::call_cost(spec_id, false, false, false),
+            Some(DynamicGas::Call),
+        ),
+        // EXT*CALL are always priced with the Berlin access rules.
+        EXTCALL | EXTDELEGATECALL | EXTSTATICCALL => (
+            gas::call_cost(SpecId::BERLIN, false, false, false),
+            Some(DynamicGas::Call),
+        ),
+        SELFDESTRUCT => (
+            gas::selfdestruct_cost(
+                spec_id,
+                SelfDestructResult {
+                    had_value: false,
+                    target_exists: true,
+                    is_cold: false,
+                    previously_destroyed: false,
+                },
+            ),
+            Some(DynamicGas::Selfdestruct),
+        ),
+        _ => unreachable!("no gas cost for opcode 0x{opcode:02X}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        primitives::{Bytecode, Bytes, U256},
+        DummyHost, Gas, Interpreter,
+    };
+
+    #[test]
+    fn covers_all_opcodes() {
+        let table = opcode_gas_table(SpecId::LATEST);
+        let count = OPCODE_INFO_JUMPTABLE.iter().flatten().count();
+        assert_eq!(table.len(), count);
+        assert!(table.windows(2).all(|w| w[0].opcode < w[1].opcode));
+    }
+
+    #[test]
+    fn spec_dependent_costs() {
+        let sload = |spec_id| opcode_gas(OpCode::SLOAD, spec_id);
+        assert_eq!(sload(SpecId::FRONTIER).static_gas, 50);
+        assert_eq!(sload(SpecId::TANGERINE).static_gas, 200);
+        assert_eq!(sload(SpecId::ISTANBUL).static_gas, 800);
+        assert_eq!(sload(SpecId::ISTANBUL).dynamic_gas, None);
+        assert_eq!(sload(SpecId::BERLIN).static_gas, 100);
+        assert_eq!(
+            sload(SpecId::BERLIN).dynamic_gas,
+            Some(DynamicGas::ColdSload)
+        );
+
+        let shanghai = opcode_gas_table(SpecId::SHANGHAI);
+        assert!(shanghai.iter().any(|op| op.opcode == PUSH0));
+        assert!(!shanghai.iter().any(|op| op.opcode == TLOAD));
+        assert!(!opcode_gas_table(SpecId::MERGE)
+            .iter()
+            .any(|op| op.opcode == PUSH0));
+    }
+
+    /// Opcodes without dynamic gas must charge exactly their static gas.
+    #[test]
+    fn static_gas_matches_execution() {
+        let table = make_runtime_instruction_table::<DummyHost>();
+        for spec_id in [
+            SpecId::FRONTIER,
+            SpecId::TANGERINE,
+            SpecId::BYZANTIUM,
+            SpecId::ISTANBUL,
+            SpecId::BERLIN,
+            SpecId::CANCUN,
+            SpecId::LATEST,
+        ] {
+            for entry in opcode_gas_table(spec_id) {
+                if entry.dynamic_gas.is_some() || entry.since == SpecId::PRAGUE_EOF {
+                    continue;
+                }
+                let mut code = [0u8; 34];
+                code[0] = entry.opcode;
+                let mut interp =
+                    Interpreter::new_bytecode(Bytecode::LegacyRaw(Bytes::copy_from_slice(&code)))
+                        .with_spec_id(spec_id);
+                interp.gas = Gas::new(100_000);
+                let info = OpCode::new(entry.opcode).unwrap().info();
+                for _ in 0..info.inputs() {
+                    interp.stack.push(U256::ZERO).unwrap();
+                }
+                let mut host = DummyHost::default();
+                interp.step(&table, &mut host);
+                assert_eq!(
+                    interp.gas.spent(),
+                    entry.static_gas,
+                    "{} in {spec_id:?}",
+                    entry.name
+                );
+            }
+        }
+    }
+}
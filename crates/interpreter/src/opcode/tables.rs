@@ -1,7 +1,11 @@
 #![allow(clippy::wrong_self_convention)]
 
 use super::instruction;
-use crate::{instructions::control, primitives::Spec, Host, Interpreter};
+use crate::{
+    instructions::control,
+    primitives::{RuntimeSpec, Spec},
+    Host, Interpreter,
+};
 use std::boxed::Box;
 
 /// EVM opcode function signature.
@@ -11,7 +15,7 @@ pub type Instruction<H> = fn(&mut Interpreter, &mut H);
 pub type InstructionTable<H> = [Instruction<H>; 256];
 
 /// EVM dynamic opcode function signature.
-pub type DynInstruction<'a, H> = dyn Fn(&mut Interpreter, &mut H) + 'a;
+pub type DynInstruction<'a, H> = dyn Fn(&mut Interpreter, &mut H) + Send + Sync + 'a;
 
 /// EVM boxed dynamic opcode function signature.
 pub type BoxedInstruction<'a, H> = Box<DynInstruction<'a, H>>;
@@ -19,11 +23,80 @@ pub type BoxedInstruction<'a, H> = Box<DynInstruction<'a, H>>;
 /// A table of boxed instructions.
 pub type BoxedInstructionTable<'a, H> = [BoxedInstruction<'a, H>; 256];
 
-/// Either a plain, static instruction table, or a boxed, dynamic instruction table.
+/// Instruction with hooks called before and after it.
+pub struct HookedInstruction<H: ?Sized> {
+    /// The instruction.
+    pub instruction: Instruction<H>,
+    /// Hook called before the instruction.
+    pub before: Option<Instruction<H>>,
+    /// Hook called after the instruction, even if it failed.
+    pub after: Option<Instruction<H>>,
+}
+
+impl<H: ?Sized> Clone for HookedInstruction<H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H: ?Sized> Copy for HookedInstruction<H> {}
+
+impl<H: ?Sized> HookedInstruction<H> {
+    /// Creates an instruction without hooks.
+    #[inline]
+    pub const fn new(instruction: Instruction<H>) -> Self {
+        Self {
+            instruction,
+            before: None,
+            after: None,
+        }
+    }
+
+    /// Returns `true` if the instruction has a hook.
+    #[inline]
+    pub const fn is_hooked(&self) -> bool {
+        self.before.is_some() || self.after.is_some()
+    }
+}
+
+/// A table of plain instructions with optional hooks.
+pub type HookedInstructionTable<H> = [HookedInstruction<H>; 256];
+
+/// Entry of an instruction table, executed by the interpreter for its opcode.
+pub trait InstructionEntry<H: ?Sized> {
+    /// Executes the instruction.
+    fn execute(&self, interpreter: &mut Interpreter, host: &mut H);
+}
+
+impl<H: ?Sized, F: Fn(&mut Interpreter, &mut H)> InstructionEntry<H> for F {
+    #[inline]
+    fn execute(&self, interpreter: &mut Interpreter, host: &mut H) {
+        self(interpreter, host)
+    }
+}
+
+impl<H: ?Sized> InstructionEntry<H> for HookedInstruction<H> {
+    #[inline]
+    fn execute(&self, interpreter: &mut Interpreter, host: &mut H) {
+        if let Some(before) = self.before {
+            before(interpreter, host);
+        }
+        (self.instruction)(interpreter, host);
+        if let Some(after) = self.after {
+            after(interpreter, host);
+        }
+    }
+}
+
+/// Either a plain, static instruction table, a plain table with hooks on some instructions, or
+/// a boxed, dynamic instruction table.
 ///
-/// Note that `Plain` variant is about 10-20% faster in Interpreter execution.
+/// Note that `Plain` variant is about 10-20% faster in Interpreter execution. The `Hooked`
+/// variant only adds a check of the hooks to the `Plain` one, use it rather than the `Boxed`
+/// variant to observe a few opcodes.
 pub enum InstructionTables<'a, H: ?Sized> {
     Plain(InstructionTable<H>),
+    Hooked(HookedInstructionTable<H>),
     Boxed(BoxedInstructionTable<'a, H>),
 }
 
@@ -33,6 +106,29 @@ impl<'a, H: Host + ?Sized> InstructionTables<'a, H> {
     pub const fn new_plain<SPEC: Spec>() -> Self {
         Self::Plain(make_instruction_table::<H, SPEC>())
     }
+
+    /// Creates a plain instruction table shared by all specs. See
+    /// [`make_runtime_instruction_table`].
+    #[inline]
+    pub const fn new_runtime() -> Self {
+        Self::Plain(make_runtime_instruction_table::<H>())
+    }
+
+    /// Returns `true` if this is the unchanged plain instruction table of `SPEC`, see
+    /// [`Self::new_plain`].
+    ///
+    /// Instructions are compared by address, a table may be reported as changed if the same
+    /// instruction was instantiated more than once.
+    pub fn is_plain_of<SPEC: Spec>(&self) -> bool {
+        let Self::Plain(table) = self else {
+            return false;
+        };
+        let plain = make_instruction_table::<H, SPEC>();
+        table
+            .iter()
+            .zip(plain.iter())
+            .all(|(a, b)| core::ptr::fn_addr_eq(*a, *b))
+    }
 }
 
 impl<'a, H: Host + ?Sized + 'a> InstructionTables<'a, H> {
@@ -41,10 +137,45 @@ impl<'a, H: Host + ?Sized + 'a> InstructionTables<'a, H> {
     pub fn insert(&mut self, opcode: u8, instruction: Instruction<H>) {
         match self {
             Self::Plain(table) => table[opcode as usize] = instruction,
+            Self::Hooked(table) => table[opcode as usize].instruction = instruction,
             Self::Boxed(table) => table[opcode as usize] = Box::new(instruction),
         }
     }
 
+    /// Calls `before` and `after` around the instruction with the specified index, replacing the
+    /// previous hooks of the instruction.
+    ///
+    /// Plain tables are converted to hooked tables, so that the other instructions keep their
+    /// plain dispatch. Boxed tables stay boxed and the instruction is wrapped.
+    pub fn insert_hooks(
+        &mut self,
+        opcode: u8,
+        before: Option<Instruction<H>>,
+        after: Option<Instruction<H>>,
+    ) {
+        match self {
+            Self::Plain(table) => {
+                let mut hooked = (*table).map(HookedInstruction::new);
+                hooked[opcode as usize].before = before;
+                hooked[opcode as usize].after = after;
+                *self = Self::Hooked(hooked);
+            }
+            Self::Hooked(table) => {
+                table[opcode as usize].before = before;
+                table[opcode as usize].after = after;
+            }
+            Self::Boxed(_) => self.update_boxed(opcode, move |prev, interpreter, host| {
+                if let Some(before) = before {
+                    before(interpreter, host);
+                }
+                prev(interpreter, host);
+                if let Some(after) = after {
+                    after(interpreter, host);
+                }
+            }),
+        }
+    }
+
     /// Converts the current instruction table to a boxed variant if it is not already, and returns
     /// a mutable reference to the boxed table.
     #[inline]
@@ -54,14 +185,17 @@ impl<'a, H: Host + ?Sized + 'a> InstructionTables<'a, H> {
 
     /// Converts the current instruction table to a boxed variant if it is not already with `f`,
     /// and returns a mutable reference to the boxed table.
+    ///
+    /// Instructions of a hooked table that have hooks are boxed together with their hooks
+    /// instead of being passed to `f`.
     #[inline]
     pub fn to_boxed_with<F>(&mut self, f: F) -> &mut BoxedInstructionTable<'a, H>
     where
         F: FnMut(Instruction<H>) -> BoxedInstruction<'a, H>,
     {
         match self {
-            Self::Plain(_) => self.to_boxed_with_slow(f),
             Self::Boxed(boxed) => boxed,
+            _ => self.to_boxed_with_slow(f),
         }
     }
 
@@ -70,10 +204,13 @@ impl<'a, H: Host + ?Sized + 'a> InstructionTables<'a, H> {
     where
         F: FnMut(Instruction<H>) -> BoxedInstruction<'a, H>,
     {
-        let Self::Plain(table) = self else {
-            unreachable!()
+        let boxed = match self {
+            Self::Plain(table) => make_boxed_instruction_table(table, f),
+            // Hooked instructions are boxed with their hooks.
+            Self::Hooked(table) => make_boxed_hooked_instruction_table(table, f),
+            Self::Boxed(_) => unreachable!(),
         };
-        *self = Self::Boxed(make_boxed_instruction_table(table, f));
+        *self = Self::Boxed(boxed);
         let Self::Boxed(boxed) = self else {
             unreachable!()
         };
@@ -107,7 +244,7 @@ impl<'a, H: Host + ?Sized + 'a> InstructionTables<'a, H> {
     #[inline]
     pub fn update_boxed<F>(&mut self, opcode: u8, f: F)
     where
-        F: Fn(&DynInstruction<'a, H>, &mut Interpreter, &mut H) + 'a,
+        F: Fn(&DynInstruction<'a, H>, &mut Interpreter, &mut H) + Send + Sync + 'a,
     {
         update_boxed_instruction(self.get_boxed(opcode), f)
     }
@@ -116,14 +253,15 @@ impl<'a, H: Host + ?Sized + 'a> InstructionTables<'a, H> {
     #[inline]
     pub fn update_all<F>(&mut self, f: F)
     where
-        F: Fn(&DynInstruction<'a, H>, &mut Interpreter, &mut H) + Copy + 'a,
+        F: Fn(&DynInstruction<'a, H>, &mut Interpreter, &mut H) + Copy + Send + Sync + 'a,
     {
         // Don't go through `to_boxed` to avoid allocating the plain table twice.
         match self {
             Self::Plain(_) => {
                 self.to_boxed_with(|prev| Box::new(move |i, h| f(&prev, i, h)));
             }
-            Self::Boxed(boxed) => boxed
+            _ => self
+                .to_boxed()
                 .iter_mut()
                 .for_each(|instruction| update_boxed_instruction(instruction, f)),
         }
@@ -144,6 +282,25 @@ pub const fn make_instruction_table<H: Host + ?Sized, SPEC: Spec>() -> Instructi
     }
 }
 
+/// Make instruction table that reads the spec from [`Interpreter::spec_id`] at runtime.
+///
+/// Unlike [`make_instruction_table`], the spec dependent instructions are instantiated once
+/// instead of once per spec, which reduces code size and compile times of binaries that support
+/// many specs. In exchange the spec checks are no longer constant folded, making execution a
+/// few percent slower, see the `snailtracer` `eval` benchmarks in `revm`.
+#[inline]
+pub const fn make_runtime_instruction_table<H: Host + ?Sized>() -> InstructionTable<H> {
+    const {
+        let mut tables: InstructionTable<H> = [control::unknown; 256];
+        let mut i = 0;
+        while i < 256 {
+            tables[i] = instruction::<H, RuntimeSpec>(i as u8);
+            i += 1;
+        }
+        tables
+    }
+}
+
 /// Make boxed instruction table that calls `f` closure for every instruction.
 #[inline]
 pub fn make_boxed_instruction_table<'a, H, FN>(
@@ -157,12 +314,37 @@ where
     core::array::from_fn(|i| f(table[i]))
 }
 
+/// Make boxed instruction table that calls `f` closure for every instruction without hooks, and
+/// boxes the instructions with hooks together with their hooks.
+#[inline]
+pub fn make_boxed_hooked_instruction_table<'a, H, FN>(
+    table: &HookedInstructionTable<H>,
+    mut f: FN,
+) -> BoxedInstructionTable<'a, H>
+where
+    H: Host + ?Sized + 'a,
+    FN: FnMut(Instruction<H>) -> BoxedInstruction<'a, H>,
+{
+    core::array::from_fn(|i| {
+        let hooked = table[i];
+        if hooked.is_hooked() {
+            let boxed: BoxedInstruction<'a, H> =
+                Box::new(move |interpreter: &mut Interpreter, host: &mut H| {
+                    hooked.execute(interpreter, host)
+                });
+            boxed
+        } else {
+            f(hooked.instruction)
+        }
+    })
+}
+
 /// Updates a boxed instruction with a new one.
 #[inline]
 pub fn update_boxed_instruction<'a, H, F>(instruction: &mut BoxedInstruction<'a, H>, f: F)
 where
     H: Host + ?Sized + 'a,
-    F: Fn(&DynInstruction<'a, H>, &mut Interpreter, &mut H) + 'a,
+    F: Fn(&DynInstruction<'a, H>, &mut Interpreter, &mut H) + Send + Sync + 'a,
 {
     // NOTE: This first allocation gets elided by the compiler.
     let prev = core::mem::replace(instruction, Box::new(|_, _| {}));
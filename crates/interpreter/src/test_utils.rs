@@ -0,0 +1,26 @@
+//! Utilities shared by the tests of the interpreter and of the crates built on it.
+
+/// xorshift64, deterministic source of inputs for property tests.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self(0x9e3779b97f4a7c15)
+    }
+}
+
+impl Rng {
+    /// Returns the next value.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Returns the next value below `bound`.
+    pub fn next(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
@@ -1,5 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use revm_precompile::{
+    blake2,
     bn128::{
         add::ISTANBUL_ADD_GAS_COST,
         pair::{ISTANBUL_PAIR_BASE, ISTANBUL_PAIR_PER_POINT},
@@ -145,9 +146,38 @@ pub fn benchmark_crypto_precompiles(c: &mut Criterion) {
     });
 }
 
+/// Benchmarks the BLAKE2 `F` compression precompile with an increasing number of rounds.
+pub fn benchmark_blake2(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Blake2 F compression benchmarks");
+
+    let h: [u64; 8] = core::array::from_fn(|i| blake2::algo::IV[i] ^ i as u64);
+    let m: [u64; 16] = core::array::from_fn(|i| (i as u64).wrapping_mul(0x9e3779b97f4a7c15));
+    let t = [128, 0];
+
+    for rounds in [12, 1_000, 100_000] {
+        let mut input = (rounds as u32).to_be_bytes().to_vec();
+        input.extend(h.iter().flat_map(|h| h.to_le_bytes()));
+        input.extend(m.iter().flat_map(|m| m.to_le_bytes()));
+        input.extend(t.iter().flat_map(|t| t.to_le_bytes()));
+        input.push(1);
+        let input = Bytes::from(input);
+
+        group.bench_function(format!("precompile | {rounds} rounds"), |b| {
+            b.iter(|| black_box(blake2::run(&input, u64::MAX).unwrap()))
+        });
+        group.bench_function(format!("portable | {rounds} rounds"), |b| {
+            b.iter(|| {
+                let mut h = h;
+                blake2::algo::compress_portable(rounds, &mut h, m, t, true);
+                black_box(h)
+            })
+        });
+    }
+}
+
 criterion_group! {
     name = benches;
     config = Criterion::default();
-    targets = benchmark_crypto_precompiles
+    targets = benchmark_crypto_precompiles, benchmark_blake2
 }
 criterion_main!(benches);
@@ -4,8 +4,18 @@ use revm_primitives::{Bytes, PrecompileOutput};
 const F_ROUND: u64 = 1;
 const INPUT_LENGTH: usize = 213;
 
-pub const FUN: PrecompileWithAddress =
-    PrecompileWithAddress(crate::u64_to_address(9), Precompile::Standard(run));
+pub const FUN: PrecompileWithAddress = PrecompileWithAddress(
+    crate::u64_to_address(9),
+    Precompile::StandardWithGas(run, required_gas),
+);
+
+/// Returns the gas used by [`run`] for the input, `F_ROUND` per round.
+pub fn required_gas(input: &[u8]) -> u64 {
+    match input.get(..4) {
+        Some(rounds) => u32::from_be_bytes(rounds.try_into().unwrap()) as u64 * F_ROUND,
+        None => 0,
+    }
+}
 
 /// reference: <https://eips.ethereum.org/EIPS/eip-152>
 /// input format:
@@ -101,8 +111,22 @@ pub mod algo {
     // indicator flag "f".  Local vector v[0..15] is used in processing.  F
     // returns a new state vector.  The number of rounds, "r", is 12 for
     // BLAKE2b and 10 for BLAKE2s.  Rounds are numbered from 0 to r - 1.
-    #[allow(clippy::many_single_char_names)]
+    //
+    // Uses the AVX2 implementation if the `blake2-avx2` feature is enabled and the CPU supports it.
+    #[inline]
     pub fn compress(rounds: usize, h: &mut [u64; 8], m: [u64; 16], t: [u64; 2], f: bool) {
+        #[cfg(all(feature = "blake2-avx2", target_arch = "x86_64"))]
+        if std::is_x86_feature_detected!("avx2") {
+            // SAFETY: AVX2 is supported by the CPU.
+            unsafe { avx2::compress(rounds, h, m, t, f) };
+            return;
+        }
+        compress_portable(rounds, h, m, t, f)
+    }
+
+    /// Portable implementation of [`compress`].
+    #[allow(clippy::many_single_char_names)]
+    pub fn compress_portable(rounds: usize, h: &mut [u64; 8], m: [u64; 16], t: [u64; 2], f: bool) {
         let mut v = [0u64; 16];
         v[..h.len()].copy_from_slice(h); // First half from state.
         v[h.len()..].copy_from_slice(&IV); // Second half from IV.
@@ -131,4 +155,170 @@ pub mod algo {
             h[i] ^= v[i] ^ v[i + 8];
         }
     }
+
+    /// AVX2 implementation of [`compress`], holding each row of the state in a single register.
+    #[cfg(all(feature = "blake2-avx2", target_arch = "x86_64"))]
+    pub mod avx2 {
+        use super::{IV, SIGMA};
+        use core::arch::x86_64::*;
+
+        /// Lane rotations used to move the diagonals of the state into columns and back.
+        const ROTATE_LEFT_1: i32 = 0b00_11_10_01;
+        const ROTATE_LEFT_2: i32 = 0b01_00_11_10;
+        const ROTATE_LEFT_3: i32 = 0b10_01_00_11;
+
+        #[inline(always)]
+        unsafe fn load(a: u64, b: u64, c: u64, d: u64) -> __m256i {
+            _mm256_setr_epi64x(a as i64, b as i64, c as i64, d as i64)
+        }
+
+        #[inline(always)]
+        unsafe fn rotate_right_32(x: __m256i) -> __m256i {
+            _mm256_shuffle_epi32(x, 0b10_11_00_01)
+        }
+
+        #[inline(always)]
+        unsafe fn rotate_right_24(x: __m256i) -> __m256i {
+            _mm256_or_si256(_mm256_srli_epi64(x, 24), _mm256_slli_epi64(x, 40))
+        }
+
+        #[inline(always)]
+        unsafe fn rotate_right_16(x: __m256i) -> __m256i {
+            _mm256_or_si256(_mm256_srli_epi64(x, 16), _mm256_slli_epi64(x, 48))
+        }
+
+        #[inline(always)]
+        unsafe fn rotate_right_63(x: __m256i) -> __m256i {
+            _mm256_or_si256(_mm256_srli_epi64(x, 63), _mm256_add_epi64(x, x))
+        }
+
+        /// Applies the G function to the four columns (or diagonals) of the state at once.
+        #[inline(always)]
+        unsafe fn g(
+            a: &mut __m256i,
+            b: &mut __m256i,
+            c: &mut __m256i,
+            d: &mut __m256i,
+            x: __m256i,
+            y: __m256i,
+        ) {
+            *a = _mm256_add_epi64(_mm256_add_epi64(*a, *b), x);
+            *d = rotate_right_32(_mm256_xor_si256(*d, *a));
+            *c = _mm256_add_epi64(*c, *d);
+            *b = rotate_right_24(_mm256_xor_si256(*b, *c));
+            *a = _mm256_add_epi64(_mm256_add_epi64(*a, *b), y);
+            *d = rotate_right_16(_mm256_xor_si256(*d, *a));
+            *c = _mm256_add_epi64(*c, *d);
+            *b = rotate_right_63(_mm256_xor_si256(*b, *c));
+        }
+
+        /// See [`compress`](super::compress).
+        ///
+        /// # Safety
+        ///
+        /// The CPU must support AVX2.
+        #[target_feature(enable = "avx2")]
+        pub unsafe fn compress(
+            rounds: usize,
+            h: &mut [u64; 8],
+            m: [u64; 16],
+            t: [u64; 2],
+            f: bool,
+        ) {
+            let mut a = load(h[0], h[1], h[2], h[3]);
+            let mut b = load(h[4], h[5], h[6], h[7]);
+            let mut c = load(IV[0], IV[1], IV[2], IV[3]);
+            let mut d = load(
+                IV[4] ^ t[0],
+                IV[5] ^ t[1],
+                if f { !IV[6] } else { IV[6] },
+                IV[7],
+            );
+
+            for i in 0..rounds {
+                let s = &SIGMA[i % 10];
+                g(
+                    &mut a,
+                    &mut b,
+                    &mut c,
+                    &mut d,
+                    load(m[s[0]], m[s[2]], m[s[4]], m[s[6]]),
+                    load(m[s[1]], m[s[3]], m[s[5]], m[s[7]]),
+                );
+
+                b = _mm256_permute4x64_epi64(b, ROTATE_LEFT_1);
+                c = _mm256_permute4x64_epi64(c, ROTATE_LEFT_2);
+                d = _mm256_permute4x64_epi64(d, ROTATE_LEFT_3);
+                g(
+                    &mut a,
+                    &mut b,
+                    &mut c,
+                    &mut d,
+                    load(m[s[8]], m[s[10]], m[s[12]], m[s[14]]),
+                    load(m[s[9]], m[s[11]], m[s[13]], m[s[15]]),
+                );
+                b = _mm256_permute4x64_epi64(b, ROTATE_LEFT_3);
+                c = _mm256_permute4x64_epi64(c, ROTATE_LEFT_2);
+                d = _mm256_permute4x64_epi64(d, ROTATE_LEFT_1);
+            }
+
+            let h = h.as_mut_ptr() as *mut __m256i;
+            let lo = _mm256_xor_si256(_mm256_loadu_si256(h), _mm256_xor_si256(a, c));
+            let hi = _mm256_xor_si256(_mm256_loadu_si256(h.add(1)), _mm256_xor_si256(b, d));
+            _mm256_storeu_si256(h, lo);
+            _mm256_storeu_si256(h.add(1), hi);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::algo::*;
+    use revm_primitives::hex_literal::hex;
+    use std::vec::Vec;
+
+    #[test]
+    fn compress_abc() {
+        // BLAKE2b-512("abc") from RFC 7693, Appendix A.
+        let mut h = IV;
+        h[0] ^= 0x01010040;
+        let mut m = [0u64; 16];
+        m[0] = 0x636261;
+        compress(12, &mut h, m, [3, 0], true);
+
+        let out = h.iter().flat_map(|h| h.to_le_bytes()).collect::<Vec<_>>();
+        assert_eq!(
+            out,
+            hex!(
+                "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1"
+                "7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "blake2-avx2", target_arch = "x86_64"))]
+    fn compress_avx2_matches_portable() {
+        if !std::is_x86_feature_detected!("avx2") {
+            return;
+        }
+        let mut seed = 0x9e3779b97f4a7c15u64;
+        let mut next = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        for rounds in [0, 1, 10, 12, 25, 1000] {
+            let h: [u64; 8] = core::array::from_fn(|_| next());
+            let m: [u64; 16] = core::array::from_fn(|_| next());
+            let t = [next(), next()];
+            for f in [false, true] {
+                let (mut portable, mut simd) = (h, h);
+                compress_portable(rounds, &mut portable, m, t, f);
+                unsafe { avx2::compress(rounds, &mut simd, m, t, f) };
+                assert_eq!(portable, simd, "rounds: {rounds}, f: {f}");
+            }
+        }
+    }
 }
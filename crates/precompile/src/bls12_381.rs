@@ -1,4 +1,7 @@
-use crate::PrecompileWithAddress;
+use crate::{
+    registry::{GasModel, PrecompileEntry},
+    PrecompileSpecId, PrecompileWithAddress,
+};
 
 mod g1;
 pub mod g1_add;
@@ -30,6 +33,57 @@ pub fn precompiles() -> impl Iterator<Item = PrecompileWithAddress> {
     .into_iter()
 }
 
+/// Returns the BLS12-381 precompiles with their metadata.
+pub(crate) fn registry_entries() -> impl Iterator<Item = PrecompileEntry> {
+    let entry = |precompile, name, gas_model| {
+        PrecompileEntry::new(precompile, name, PrecompileSpecId::PRAGUE, gas_model)
+    };
+    [
+        entry(
+            g1_add::PRECOMPILE,
+            "BLS12_G1ADD",
+            GasModel::Fixed(g1_add::BASE_GAS_FEE),
+        ),
+        entry(
+            g1_mul::PRECOMPILE,
+            "BLS12_G1MUL",
+            GasModel::Fixed(g1_mul::BASE_GAS_FEE),
+        ),
+        entry(g1_msm::PRECOMPILE, "BLS12_G1MSM", GasModel::Dynamic),
+        entry(
+            g2_add::PRECOMPILE,
+            "BLS12_G2ADD",
+            GasModel::Fixed(g2_add::BASE_GAS_FEE),
+        ),
+        entry(
+            g2_mul::PRECOMPILE,
+            "BLS12_G2MUL",
+            GasModel::Fixed(g2_mul::BASE_GAS_FEE),
+        ),
+        entry(g2_msm::PRECOMPILE, "BLS12_G2MSM", GasModel::Dynamic),
+        entry(
+            pairing::PRECOMPILE,
+            "BLS12_PAIRING_CHECK",
+            GasModel::PerItem {
+                base: pairing::PAIRING_OFFSET_BASE,
+                per_item: pairing::PAIRING_MULTIPLIER_BASE,
+                item_len: pairing::INPUT_LENGTH,
+            },
+        ),
+        entry(
+            map_fp_to_g1::PRECOMPILE,
+            "BLS12_MAP_FP_TO_G1",
+            GasModel::Fixed(map_fp_to_g1::MAP_FP_TO_G1_BASE),
+        ),
+        entry(
+            map_fp2_to_g2::PRECOMPILE,
+            "BLS12_MAP_FP2_TO_G2",
+            GasModel::Fixed(map_fp2_to_g2::BASE_GAS_FEE),
+        ),
+    ]
+    .into_iter()
+}
+
 #[cfg(test)]
 mod test {
     use super::g1_add;
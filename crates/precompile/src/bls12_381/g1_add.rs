@@ -6,12 +6,14 @@ use blst::{
 use revm_primitives::{Bytes, Precompile, PrecompileError, PrecompileOutput, PrecompileResult};
 
 /// [EIP-2537](https://eips.ethereum.org/EIPS/eip-2537#specification) BLS12_G1ADD precompile.
-pub const PRECOMPILE: PrecompileWithAddress =
-    PrecompileWithAddress(u64_to_address(ADDRESS), Precompile::Standard(g1_add));
+pub const PRECOMPILE: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ADDRESS),
+    Precompile::StandardWithGas(g1_add, |_| BASE_GAS_FEE),
+);
 /// BLS12_G1ADD precompile address.
 pub const ADDRESS: u64 = 0x0b;
 /// Base gas fee for BLS12-381 g1_add operation.
-const BASE_GAS_FEE: u64 = 500;
+pub(super) const BASE_GAS_FEE: u64 = 500;
 
 /// Input length of g1_add operation.
 const INPUT_LENGTH: usize = 256;
@@ -9,12 +9,19 @@ use blst::{blst_p1, blst_p1_affine, blst_p1_from_affine, blst_p1_to_affine, p1_a
 use revm_primitives::{Bytes, Precompile, PrecompileError, PrecompileOutput, PrecompileResult};
 
 /// [EIP-2537](https://eips.ethereum.org/EIPS/eip-2537#specification) BLS12_G1MSM precompile.
-pub const PRECOMPILE: PrecompileWithAddress =
-    PrecompileWithAddress(u64_to_address(ADDRESS), Precompile::Standard(g1_msm));
+pub const PRECOMPILE: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ADDRESS),
+    Precompile::StandardWithGas(g1_msm, required_gas),
+);
 
 /// BLS12_G1MSM precompile address.
 pub const ADDRESS: u64 = 0x0d;
 
+/// Returns the gas used by the precompile for the input.
+fn required_gas(input: &[u8]) -> u64 {
+    msm_required_gas(input.len() / g1_mul::INPUT_LENGTH, g1_mul::BASE_GAS_FEE)
+}
+
 /// Implements EIP-2537 G1MSM precompile.
 /// G1 multi-scalar-multiplication call expects `160*k` bytes as an input that is interpreted
 /// as byte concatenation of `k` slices each of them being a byte concatenation
@@ -35,7 +42,7 @@ pub(super) fn g1_msm(input: &Bytes, gas_limit: u64) -> PrecompileResult {
     }
 
     let k = input_len / g1_mul::INPUT_LENGTH;
-    let required_gas = msm_required_gas(k, g1_mul::BASE_GAS_FEE);
+    let required_gas = required_gas(input);
     if required_gas > gas_limit {
         return Err(PrecompileError::OutOfGas.into());
     }
@@ -7,8 +7,10 @@ use blst::{blst_p1, blst_p1_affine, blst_p1_from_affine, blst_p1_mult, blst_p1_t
 use revm_primitives::{Bytes, Precompile, PrecompileError, PrecompileOutput, PrecompileResult};
 
 /// [EIP-2537](https://eips.ethereum.org/EIPS/eip-2537#specification) BLS12_G1MUL precompile.
-pub const PRECOMPILE: PrecompileWithAddress =
-    PrecompileWithAddress(u64_to_address(ADDRESS), Precompile::Standard(g1_mul));
+pub const PRECOMPILE: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ADDRESS),
+    Precompile::StandardWithGas(g1_mul, |_| BASE_GAS_FEE),
+);
 /// BLS12_G1MUL precompile address.
 pub const ADDRESS: u64 = 0x0c;
 /// Base gas fee for BLS12-381 g1_mul operation.
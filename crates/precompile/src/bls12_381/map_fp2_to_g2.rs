@@ -8,14 +8,16 @@ use blst::{blst_map_to_g2, blst_p2, blst_p2_affine, blst_p2_to_affine};
 use revm_primitives::{Bytes, Precompile, PrecompileError, PrecompileOutput, PrecompileResult};
 
 /// [EIP-2537](https://eips.ethereum.org/EIPS/eip-2537#specification) BLS12_MAP_FP2_TO_G2 precompile.
-pub const PRECOMPILE: PrecompileWithAddress =
-    PrecompileWithAddress(u64_to_address(ADDRESS), Precompile::Standard(map_fp2_to_g2));
+pub const PRECOMPILE: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ADDRESS),
+    Precompile::StandardWithGas(map_fp2_to_g2, |_| BASE_GAS_FEE),
+);
 
 /// BLS12_MAP_FP2_TO_G2 precompile address.
 pub const ADDRESS: u64 = 0x13;
 
 /// Base gas fee for BLS12-381 map_fp2_to_g2 operation.
-const BASE_GAS_FEE: u64 = 75000;
+pub(super) const BASE_GAS_FEE: u64 = 75000;
 
 /// Field-to-curve call expects 128 bytes as an input that is interpreted as
 /// an element of Fp2. Output of this call is 256 bytes and is an encoded G2
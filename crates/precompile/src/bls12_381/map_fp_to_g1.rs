@@ -7,14 +7,16 @@ use blst::{blst_map_to_g1, blst_p1, blst_p1_affine, blst_p1_to_affine};
 use revm_primitives::{Bytes, Precompile, PrecompileError, PrecompileOutput, PrecompileResult};
 
 /// [EIP-2537](https://eips.ethereum.org/EIPS/eip-2537#specification) BLS12_MAP_FP_TO_G1 precompile.
-pub const PRECOMPILE: PrecompileWithAddress =
-    PrecompileWithAddress(u64_to_address(ADDRESS), Precompile::Standard(map_fp_to_g1));
+pub const PRECOMPILE: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ADDRESS),
+    Precompile::StandardWithGas(map_fp_to_g1, |_| MAP_FP_TO_G1_BASE),
+);
 
 /// BLS12_MAP_FP_TO_G1 precompile address.
 pub const ADDRESS: u64 = 0x12;
 
 /// Base gas fee for BLS12-381 map_fp_to_g1 operation.
-const MAP_FP_TO_G1_BASE: u64 = 5500;
+pub(super) const MAP_FP_TO_G1_BASE: u64 = 5500;
 
 /// Field-to-curve call expects 64 bytes as an input that is interpreted as an
 /// element of Fp. Output of this call is 128 bytes and is an encoded G1 point.
@@ -9,17 +9,24 @@ use revm_primitives::{
 };
 
 /// [EIP-2537](https://eips.ethereum.org/EIPS/eip-2537#specification) BLS12_PAIRING precompile.
-pub const PRECOMPILE: PrecompileWithAddress =
-    PrecompileWithAddress(u64_to_address(ADDRESS), Precompile::Standard(pairing));
+pub const PRECOMPILE: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ADDRESS),
+    Precompile::StandardWithGas(pairing, required_gas),
+);
 /// BLS12_PAIRING precompile address.
 pub const ADDRESS: u64 = 0x11;
 
 /// Multiplier gas fee for BLS12-381 pairing operation.
-const PAIRING_MULTIPLIER_BASE: u64 = 43000;
+pub(super) const PAIRING_MULTIPLIER_BASE: u64 = 43000;
 /// Offset gas fee for BLS12-381 pairing operation.
-const PAIRING_OFFSET_BASE: u64 = 65000;
+pub(super) const PAIRING_OFFSET_BASE: u64 = 65000;
 /// Input length of pairing operation.
-const INPUT_LENGTH: usize = 384;
+pub(super) const INPUT_LENGTH: usize = 384;
+
+/// Returns the gas used by the precompile for the input.
+fn required_gas(input: &[u8]) -> u64 {
+    PAIRING_MULTIPLIER_BASE * (input.len() / INPUT_LENGTH) as u64 + PAIRING_OFFSET_BASE
+}
 
 /// Pairing call expects 384*k (k being a positive integer) bytes as an inputs
 /// that is interpreted as byte concatenation of k slices. Each slice has the
@@ -43,7 +50,7 @@ pub(super) fn pairing(input: &Bytes, gas_limit: u64) -> PrecompileResult {
     }
 
     let k = input_len / INPUT_LENGTH;
-    let required_gas: u64 = PAIRING_MULTIPLIER_BASE * k as u64 + PAIRING_OFFSET_BASE;
+    let required_gas = required_gas(input);
     if required_gas > gas_limit {
         return Err(PrecompileError::OutOfGas.into());
     }
@@ -14,13 +14,19 @@ pub mod add {
     pub const ISTANBUL_ADD_GAS_COST: u64 = 150;
     pub const ISTANBUL: PrecompileWithAddress = PrecompileWithAddress(
         ADDRESS,
-        Precompile::Standard(|input, gas_limit| run_add(input, ISTANBUL_ADD_GAS_COST, gas_limit)),
+        Precompile::StandardWithGas(
+            |input, gas_limit| run_add(input, ISTANBUL_ADD_GAS_COST, gas_limit),
+            |_| ISTANBUL_ADD_GAS_COST,
+        ),
     );
 
     pub const BYZANTIUM_ADD_GAS_COST: u64 = 500;
     pub const BYZANTIUM: PrecompileWithAddress = PrecompileWithAddress(
         ADDRESS,
-        Precompile::Standard(|input, gas_limit| run_add(input, BYZANTIUM_ADD_GAS_COST, gas_limit)),
+        Precompile::StandardWithGas(
+            |input, gas_limit| run_add(input, BYZANTIUM_ADD_GAS_COST, gas_limit),
+            |_| BYZANTIUM_ADD_GAS_COST,
+        ),
     );
 }
 
@@ -32,13 +38,19 @@ pub mod mul {
     pub const ISTANBUL_MUL_GAS_COST: u64 = 6_000;
     pub const ISTANBUL: PrecompileWithAddress = PrecompileWithAddress(
         ADDRESS,
-        Precompile::Standard(|input, gas_limit| run_mul(input, ISTANBUL_MUL_GAS_COST, gas_limit)),
+        Precompile::StandardWithGas(
+            |input, gas_limit| run_mul(input, ISTANBUL_MUL_GAS_COST, gas_limit),
+            |_| ISTANBUL_MUL_GAS_COST,
+        ),
     );
 
     pub const BYZANTIUM_MUL_GAS_COST: u64 = 40_000;
     pub const BYZANTIUM: PrecompileWithAddress = PrecompileWithAddress(
         ADDRESS,
-        Precompile::Standard(|input, gas_limit| run_mul(input, BYZANTIUM_MUL_GAS_COST, gas_limit)),
+        Precompile::StandardWithGas(
+            |input, gas_limit| run_mul(input, BYZANTIUM_MUL_GAS_COST, gas_limit),
+            |_| BYZANTIUM_MUL_GAS_COST,
+        ),
     );
 }
 
@@ -51,28 +63,34 @@ pub mod pair {
     pub const ISTANBUL_PAIR_BASE: u64 = 45_000;
     pub const ISTANBUL: PrecompileWithAddress = PrecompileWithAddress(
         ADDRESS,
-        Precompile::Standard(|input, gas_limit| {
-            run_pair(
-                input,
-                ISTANBUL_PAIR_PER_POINT,
-                ISTANBUL_PAIR_BASE,
-                gas_limit,
-            )
-        }),
+        Precompile::StandardWithGas(
+            |input, gas_limit| {
+                run_pair(
+                    input,
+                    ISTANBUL_PAIR_PER_POINT,
+                    ISTANBUL_PAIR_BASE,
+                    gas_limit,
+                )
+            },
+            |input| pair_required_gas(input, ISTANBUL_PAIR_PER_POINT, ISTANBUL_PAIR_BASE),
+        ),
     );
 
     pub const BYZANTIUM_PAIR_PER_POINT: u64 = 80_000;
     pub const BYZANTIUM_PAIR_BASE: u64 = 100_000;
     pub const BYZANTIUM: PrecompileWithAddress = PrecompileWithAddress(
         ADDRESS,
-        Precompile::Standard(|input, gas_limit| {
-            run_pair(
-                input,
-                BYZANTIUM_PAIR_PER_POINT,
-                BYZANTIUM_PAIR_BASE,
-                gas_limit,
-            )
-        }),
+        Precompile::StandardWithGas(
+            |input, gas_limit| {
+                run_pair(
+                    input,
+                    BYZANTIUM_PAIR_PER_POINT,
+                    BYZANTIUM_PAIR_BASE,
+                    gas_limit,
+                )
+            },
+            |input| pair_required_gas(input, BYZANTIUM_PAIR_PER_POINT, BYZANTIUM_PAIR_BASE),
+        ),
     );
 }
 
@@ -160,13 +178,18 @@ pub fn run_mul(input: &[u8], gas_cost: u64, gas_limit: u64) -> PrecompileResult
     Ok(PrecompileOutput::new(gas_cost, output.into()))
 }
 
+/// Returns the gas used by [`run_pair`] for the input.
+pub fn pair_required_gas(input: &[u8], pair_per_point_cost: u64, pair_base_cost: u64) -> u64 {
+    (input.len() / PAIR_ELEMENT_LEN) as u64 * pair_per_point_cost + pair_base_cost
+}
+
 pub fn run_pair(
     input: &[u8],
     pair_per_point_cost: u64,
     pair_base_cost: u64,
     gas_limit: u64,
 ) -> PrecompileResult {
-    let gas_used = (input.len() / PAIR_ELEMENT_LEN) as u64 * pair_per_point_cost + pair_base_cost;
+    let gas_used = pair_required_gas(input, pair_per_point_cost, pair_base_cost);
     if gas_used > gas_limit {
         return Err(Error::OutOfGas.into());
     }
@@ -419,6 +442,11 @@ mod tests {
         )
         .unwrap();
         assert_eq!(outcome.bytes, expected);
+        assert_eq!(
+            pair::BYZANTIUM.1.required_gas(&input),
+            Some(outcome.gas_used)
+        );
+        assert_eq!(pair::ISTANBUL.1.required_gas(&input), Some(113_000));
 
         // out of gas test
         let input = hex::decode(
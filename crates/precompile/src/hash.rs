@@ -3,19 +3,40 @@ use crate::{Error, Precompile, PrecompileResult, PrecompileWithAddress};
 use revm_primitives::{Bytes, PrecompileOutput};
 use sha2::Digest;
 
-pub const SHA256: PrecompileWithAddress =
-    PrecompileWithAddress(crate::u64_to_address(2), Precompile::Standard(sha256_run));
+pub const SHA256: PrecompileWithAddress = PrecompileWithAddress(
+    crate::u64_to_address(2),
+    Precompile::StandardWithGas(sha256_run, sha256_required_gas),
+);
 
 pub const RIPEMD160: PrecompileWithAddress = PrecompileWithAddress(
     crate::u64_to_address(3),
-    Precompile::Standard(ripemd160_run),
+    Precompile::StandardWithGas(ripemd160_run, ripemd160_required_gas),
 );
 
+/// The base cost of the SHA256 operation.
+pub const SHA256_BASE: u64 = 60;
+/// The cost per word of the SHA256 operation.
+pub const SHA256_PER_WORD: u64 = 12;
+/// The base cost of the RIPEMD160 operation.
+pub const RIPEMD160_BASE: u64 = 600;
+/// The cost per word of the RIPEMD160 operation.
+pub const RIPEMD160_PER_WORD: u64 = 120;
+
+/// Returns the gas used by [`sha256_run`] for the input.
+pub fn sha256_required_gas(input: &[u8]) -> u64 {
+    calc_linear_cost_u32(input.len(), SHA256_BASE, SHA256_PER_WORD)
+}
+
+/// Returns the gas used by [`ripemd160_run`] for the input.
+pub fn ripemd160_required_gas(input: &[u8]) -> u64 {
+    calc_linear_cost_u32(input.len(), RIPEMD160_BASE, RIPEMD160_PER_WORD)
+}
+
 /// See: <https://ethereum.github.io/yellowpaper/paper.pdf>
 /// See: <https://docs.soliditylang.org/en/develop/units-and-global-variables.html#mathematical-and-cryptographic-functions>
 /// See: <https://etherscan.io/address/0000000000000000000000000000000000000002>
 pub fn sha256_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
-    let cost = calc_linear_cost_u32(input.len(), 60, 12);
+    let cost = sha256_required_gas(input);
     if cost > gas_limit {
         Err(Error::OutOfGas.into())
     } else {
@@ -28,7 +49,7 @@ pub fn sha256_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
 /// See: <https://docs.soliditylang.org/en/develop/units-and-global-variables.html#mathematical-and-cryptographic-functions>
 /// See: <https://etherscan.io/address/0000000000000000000000000000000000000003>
 pub fn ripemd160_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
-    let gas_used = calc_linear_cost_u32(input.len(), 600, 120);
+    let gas_used = ripemd160_required_gas(input);
     if gas_used > gas_limit {
         Err(Error::OutOfGas.into())
     } else {
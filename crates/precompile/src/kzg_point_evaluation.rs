@@ -10,7 +10,7 @@ use revm_primitives::{hex_literal::hex, Bytes, Env, PrecompileOutput};
 use sha2::{Digest, Sha256};
 
 pub const POINT_EVALUATION: PrecompileWithAddress =
-    PrecompileWithAddress(ADDRESS, Precompile::Env(run));
+    PrecompileWithAddress(ADDRESS, Precompile::EnvWithGas(run, |_| GAS_COST));
 
 pub const ADDRESS: Address = crate::u64_to_address(0x0A);
 pub const GAS_COST: u64 = 50_000;
@@ -18,12 +18,14 @@ pub mod identity;
 #[cfg(any(feature = "c-kzg", feature = "kzg-rs"))]
 pub mod kzg_point_evaluation;
 pub mod modexp;
+pub mod registry;
 pub mod secp256k1;
 #[cfg(feature = "secp256r1")]
 pub mod secp256r1;
 pub mod utilities;
 
 pub use fatal_precompile::fatal_precompile;
+pub use registry::{GasModel, PrecompileEntry, PrecompileMetadata};
 
 #[cfg(all(feature = "c-kzg", feature = "kzg-rs"))]
 // silence kzg-rs lint as c-kzg will be used as default if both are enabled.
@@ -35,10 +37,9 @@ pub use primitives::{
 #[doc(hidden)]
 pub use revm_primitives as primitives;
 
-use cfg_if::cfg_if;
 use core::hash::Hash;
 use once_cell::race::OnceBox;
-use std::{boxed::Box, vec::Vec};
+use std::{boxed::Box, collections::BTreeMap, vec::Vec};
 
 pub fn calc_linear_cost_u32(len: usize, base: u64, word: u64) -> u64 {
     (len as u64 + 32 - 1) / 32 * word + base
@@ -50,6 +51,8 @@ pub struct Precompiles {
     inner: HashMap<Address, Precompile>,
     /// Addresses of precompile.
     addresses: HashSet<Address>,
+    /// Metadata of the precompiles that have it, ordered by address.
+    metadata: BTreeMap<Address, PrecompileMetadata>,
 }
 
 impl Precompiles {
@@ -69,16 +72,7 @@ impl Precompiles {
     /// Returns precompiles for Homestead spec.
     pub fn homestead() -> &'static Self {
         static INSTANCE: OnceBox<Precompiles> = OnceBox::new();
-        INSTANCE.get_or_init(|| {
-            let mut precompiles = Precompiles::default();
-            precompiles.extend([
-                secp256k1::ECRECOVER,
-                hash::SHA256,
-                hash::RIPEMD160,
-                identity::FUN,
-            ]);
-            Box::new(precompiles)
-        })
+        INSTANCE.get_or_init(|| Box::new(Self::from_registry(PrecompileSpecId::HOMESTEAD)))
     }
 
     /// Returns inner HashMap of precompiles.
@@ -89,49 +83,19 @@ impl Precompiles {
     /// Returns precompiles for Byzantium spec.
     pub fn byzantium() -> &'static Self {
         static INSTANCE: OnceBox<Precompiles> = OnceBox::new();
-        INSTANCE.get_or_init(|| {
-            let mut precompiles = Self::homestead().clone();
-            precompiles.extend([
-                // EIP-196: Precompiled contracts for addition and scalar multiplication on the elliptic curve alt_bn128.
-                // EIP-197: Precompiled contracts for optimal ate pairing check on the elliptic curve alt_bn128.
-                bn128::add::BYZANTIUM,
-                bn128::mul::BYZANTIUM,
-                bn128::pair::BYZANTIUM,
-                // EIP-198: Big integer modular exponentiation.
-                modexp::BYZANTIUM,
-            ]);
-            Box::new(precompiles)
-        })
+        INSTANCE.get_or_init(|| Box::new(Self::from_registry(PrecompileSpecId::BYZANTIUM)))
     }
 
     /// Returns precompiles for Istanbul spec.
     pub fn istanbul() -> &'static Self {
         static INSTANCE: OnceBox<Precompiles> = OnceBox::new();
-        INSTANCE.get_or_init(|| {
-            let mut precompiles = Self::byzantium().clone();
-            precompiles.extend([
-                // EIP-1108: Reduce alt_bn128 precompile gas costs.
-                bn128::add::ISTANBUL,
-                bn128::mul::ISTANBUL,
-                bn128::pair::ISTANBUL,
-                // EIP-152: Add BLAKE2 compression function `F` precompile.
-                blake2::FUN,
-            ]);
-            Box::new(precompiles)
-        })
+        INSTANCE.get_or_init(|| Box::new(Self::from_registry(PrecompileSpecId::ISTANBUL)))
     }
 
     /// Returns precompiles for Berlin spec.
     pub fn berlin() -> &'static Self {
         static INSTANCE: OnceBox<Precompiles> = OnceBox::new();
-        INSTANCE.get_or_init(|| {
-            let mut precompiles = Self::istanbul().clone();
-            precompiles.extend([
-                // EIP-2565: ModExp Gas Cost.
-                modexp::BERLIN,
-            ]);
-            Box::new(precompiles)
-        })
+        INSTANCE.get_or_init(|| Box::new(Self::from_registry(PrecompileSpecId::BERLIN)))
     }
 
     /// Returns precompiles for Cancun spec.
@@ -140,43 +104,15 @@ impl Precompiles {
     /// effectively making this the same as Berlin.
     pub fn cancun() -> &'static Self {
         static INSTANCE: OnceBox<Precompiles> = OnceBox::new();
-        INSTANCE.get_or_init(|| {
-            let mut precompiles = Self::berlin().clone();
-
-            // EIP-4844: Shard Blob Transactions
-            cfg_if! {
-                if #[cfg(any(feature = "c-kzg", feature = "kzg-rs"))] {
-                    let precompile = kzg_point_evaluation::POINT_EVALUATION.clone();
-                } else {
-                    // TODO move constants to separate file.
-                    let precompile = fatal_precompile(u64_to_address(0x0A), "c-kzg feature is not enabled".into());
-                }
-            }
-
-            precompiles.extend([
-                precompile,
-            ]);
-
-            Box::new(precompiles)
-        })
+        INSTANCE.get_or_init(|| Box::new(Self::from_registry(PrecompileSpecId::CANCUN)))
     }
 
     /// Returns precompiles for Prague spec.
+    ///
+    /// BLS12-381 precompiles are not included in no_std builds.
     pub fn prague() -> &'static Self {
         static INSTANCE: OnceBox<Precompiles> = OnceBox::new();
-        INSTANCE.get_or_init(|| {
-            let precompiles = Self::cancun().clone();
-
-            // Don't include BLS12-381 precompiles in no_std builds.
-            #[cfg(feature = "blst")]
-            let precompiles = {
-                let mut precompiles = precompiles;
-                precompiles.extend(bls12_381::precompiles());
-                precompiles
-            };
-
-            Box::new(precompiles)
-        })
+        INSTANCE.get_or_init(|| Box::new(Self::from_registry(PrecompileSpecId::PRAGUE)))
     }
 
     /// Returns the precompiles for the latest spec.
@@ -208,6 +144,13 @@ impl Precompiles {
         self.inner.get(address)
     }
 
+    /// Returns the gas the precompile at the given address charges for the input, without
+    /// executing it. See [`Precompile::required_gas`].
+    #[inline]
+    pub fn required_gas(&self, address: &Address, input: &[u8]) -> Option<u64> {
+        self.get(address)?.required_gas(input)
+    }
+
     /// Returns the precompile for the given address.
     #[inline]
     pub fn get_mut(&mut self, address: &Address) -> Option<&mut Precompile> {
@@ -229,15 +172,48 @@ impl Precompiles {
         &self.addresses
     }
 
+    /// Returns the metadata of the precompile at the given address.
+    #[inline]
+    pub fn metadata(&self, address: &Address) -> Option<&PrecompileMetadata> {
+        self.metadata.get(address)
+    }
+
+    /// Returns an iterator over the precompiles metadata, in ascending address order.
+    ///
+    /// Precompiles added without metadata are skipped.
+    #[inline]
+    pub fn metadata_iter(&self) -> impl Iterator<Item = (&Address, &PrecompileMetadata)> {
+        self.metadata.iter()
+    }
+
     /// Extends the precompiles with the given precompiles.
     ///
-    /// Other precompiles with overwrite existing precompiles.
+    /// Other precompiles with overwrite existing precompiles, together with their metadata.
     #[inline]
     pub fn extend(&mut self, other: impl IntoIterator<Item = PrecompileWithAddress>) {
         let items = other.into_iter().collect::<Vec<_>>();
+        for item in &items {
+            self.metadata.remove(item.address());
+        }
         self.addresses.extend(items.iter().map(|p| *p.address()));
         self.inner.extend(items.into_iter().map(Into::into));
     }
+
+    /// Extends the precompiles with the given precompiles and their metadata.
+    ///
+    /// Other precompiles with overwrite existing precompiles.
+    #[inline]
+    pub fn extend_with_metadata(&mut self, other: impl IntoIterator<Item = PrecompileEntry>) {
+        for PrecompileEntry {
+            precompile,
+            metadata,
+        } in other
+        {
+            self.metadata.insert(precompile.0, metadata);
+            self.addresses.insert(precompile.0);
+            self.inner.insert(precompile.0, precompile.1);
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -292,7 +268,7 @@ impl PrecompileSpecId {
             ISTANBUL | MUIR_GLACIER => Self::ISTANBUL,
             BERLIN | LONDON | ARROW_GLACIER | GRAY_GLACIER | MERGE | SHANGHAI => Self::BERLIN,
             CANCUN => Self::CANCUN,
-            PRAGUE | PRAGUE_EOF => Self::PRAGUE,
+            PRAGUE | PRAGUE_EOF | OSAKA => Self::PRAGUE,
             LATEST => Self::LATEST,
             #[cfg(feature = "optimism")]
             BEDROCK | REGOLITH | CANYON => Self::BERLIN,
@@ -9,11 +9,19 @@ use revm_primitives::{Bytes, PrecompileOutput};
 
 pub const BYZANTIUM: PrecompileWithAddress = PrecompileWithAddress(
     crate::u64_to_address(5),
-    Precompile::Standard(byzantium_run),
+    Precompile::StandardWithGas(byzantium_run, byzantium_required_gas),
 );
 
-pub const BERLIN: PrecompileWithAddress =
-    PrecompileWithAddress(crate::u64_to_address(5), Precompile::Standard(berlin_run));
+pub const BERLIN: PrecompileWithAddress = PrecompileWithAddress(
+    crate::u64_to_address(5),
+    Precompile::StandardWithGas(berlin_run, berlin_required_gas),
+);
+
+// The format of input is:
+// <length_of_BASE> <length_of_EXPONENT> <length_of_MODULUS> <BASE> <EXPONENT> <MODULUS>
+// Where every length is a 32-byte left-padded integer representing the number of bytes
+// to be taken up by the next value
+const HEADER_LENGTH: usize = 96;
 
 /// See: <https://eips.ethereum.org/EIPS/eip-198>
 /// See: <https://etherscan.io/address/0000000000000000000000000000000000000005>
@@ -29,6 +37,54 @@ pub fn berlin_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
     })
 }
 
+/// Returns the gas used by [`byzantium_run`] for the input.
+pub fn byzantium_required_gas(input: &[u8]) -> u64 {
+    required_gas_inner(input, 0, byzantium_gas_calc)
+}
+
+/// Returns the gas used by [`berlin_run`] for the input.
+pub fn berlin_required_gas(input: &[u8]) -> u64 {
+    required_gas_inner(input, 200, berlin_gas_calc)
+}
+
+/// Calculates the gas from the input header, without reading the base and modulus.
+///
+/// Lengths are checked in the same order as [`run_inner`]; lengths that make the precompile
+/// fail are priced at `u64::MAX`.
+pub fn required_gas_inner<F>(input: &[u8], min_gas: u64, calc_gas: F) -> u64
+where
+    F: FnOnce(u64, u64, u64, &U256) -> u64,
+{
+    let len_at = |offset| {
+        let len = U256::from_be_bytes(right_pad_with_offset::<32>(input, offset).into_owned());
+        usize::try_from(len).ok()
+    };
+    let (Some(base_len), Some(mod_len)) = (len_at(0), len_at(64)) else {
+        return u64::MAX;
+    };
+    // The exponent length is not read when both the base and mod length are zero.
+    if base_len == 0 && mod_len == 0 {
+        return min_gas;
+    }
+    let Some(exp_len) = len_at(32) else {
+        return u64::MAX;
+    };
+    let input = input.get(HEADER_LENGTH..).unwrap_or_default();
+    let exp_highp = read_exp_highp(input, base_len, exp_len);
+    calc_gas(base_len as u64, exp_len as u64, mod_len as u64, &exp_highp)
+}
+
+/// Reads the first 32 bytes of the exponent, used to calculate the iteration count.
+fn read_exp_highp(input: &[u8], base_len: usize, exp_len: usize) -> U256 {
+    // Used to extract ADJUSTED_EXPONENT_LENGTH.
+    let exp_highp_len = min(exp_len, 32);
+    // get right padded bytes so if data.len is less then exp_len we will get right padded zeroes.
+    let right_padded_highp = right_pad_with_offset::<32>(input, base_len);
+    // If exp_len is less then 32 bytes get only exp_len bytes and do left padding.
+    let out = left_pad::<32>(&right_padded_highp[..exp_highp_len]);
+    U256::from_be_bytes(out.into_owned())
+}
+
 pub fn calculate_iteration_count(exp_length: u64, exp_highp: &U256) -> u64 {
     let mut iteration_count: u64 = 0;
 
@@ -53,12 +109,6 @@ where
         return Err(Error::OutOfGas.into());
     }
 
-    // The format of input is:
-    // <length_of_BASE> <length_of_EXPONENT> <length_of_MODULUS> <BASE> <EXPONENT> <MODULUS>
-    // Where every length is a 32-byte left-padded integer representing the number of bytes
-    // to be taken up by the next value
-    const HEADER_LENGTH: usize = 96;
-
     // Extract the header.
     let base_len = U256::from_be_bytes(right_pad_with_offset::<32>(input, 0).into_owned());
     let exp_len = U256::from_be_bytes(right_pad_with_offset::<32>(input, 32).into_owned());
@@ -82,19 +132,10 @@ where
         return Err(Error::ModexpModOverflow.into());
     };
 
-    // Used to extract ADJUSTED_EXPONENT_LENGTH.
-    let exp_highp_len = min(exp_len, 32);
-
     // Throw away the header data as we already extracted lengths.
     let input = input.get(HEADER_LENGTH..).unwrap_or_default();
 
-    let exp_highp = {
-        // get right padded bytes so if data.len is less then exp_len we will get right padded zeroes.
-        let right_padded_highp = right_pad_with_offset::<32>(input, base_len);
-        // If exp_len is less then 32 bytes get only exp_len bytes and do left padding.
-        let out = left_pad::<32>(&right_padded_highp[..exp_highp_len]);
-        U256::from_be_bytes(out.into_owned())
-    };
+    let exp_highp = read_exp_highp(input, base_len, exp_len);
 
     // Check if we have enough gas.
     let gas_cost = calc_gas(base_len as u64, exp_len as u64, mod_len as u64, &exp_highp);
@@ -376,6 +417,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_modexp_required_gas() {
+        for test in TESTS.iter() {
+            let input: Bytes = hex::decode(test.input).unwrap().into();
+            let byzantium = byzantium_run(&input, u64::MAX).unwrap().gas_used;
+            let berlin = berlin_run(&input, u64::MAX).unwrap().gas_used;
+            assert_eq!(
+                byzantium_required_gas(&input),
+                byzantium,
+                "test:{}",
+                test.name
+            );
+            assert_eq!(berlin_required_gas(&input), berlin, "test:{}", test.name);
+        }
+        assert_eq!(berlin_required_gas(&[]), 200);
+
+        // base and mod length are zero, the exponent length does not fit in `usize`.
+        let mut input = [0u8; 96];
+        input[32] = 0x80;
+        let input = Bytes::from(input.to_vec());
+        let gas_used = berlin_run(&input, u64::MAX).unwrap().gas_used;
+        assert_eq!(berlin_required_gas(&input), gas_used);
+    }
+
     #[test]
     fn test_berlin_modexp_empty_input() {
         let res = berlin_run(&Bytes::new(), 100_000).unwrap();
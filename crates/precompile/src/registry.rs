@@ -0,0 +1,295 @@
+//! Versioned registry of the precompiles and their metadata.
+//!
+//! The precompile sets returned by [`Precompiles::new`] are derived from [`registry`], so the
+//! metadata of every precompile in them can be introspected, e.g. to serve the active precompiles
+//! of a chain over RPC.
+use crate::{
+    blake2, bn128, hash, identity, modexp, secp256k1, PrecompileSpecId, PrecompileWithAddress,
+    Precompiles,
+};
+use cfg_if::cfg_if;
+use once_cell::race::OnceBox;
+use std::{boxed::Box, vec::Vec};
+
+/// Gas schedule of a precompile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GasModel {
+    /// Same cost for every input.
+    Fixed(u64),
+    /// `base + word * ceil(len / 32)`, where `len` is the input length.
+    Linear { base: u64, word: u64 },
+    /// `base + per_item * (len / item_len)`, where `len` is the input length.
+    PerItem {
+        base: u64,
+        per_item: u64,
+        item_len: usize,
+    },
+    /// Cost calculated from the input content, see [`Precompile::required_gas`](crate::Precompile::required_gas).
+    Dynamic,
+}
+
+/// Metadata of a precompile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PrecompileMetadata {
+    /// Name of the precompile, as used by [EIP-7910](https://eips.ethereum.org/EIPS/eip-7910).
+    pub name: &'static str,
+    /// Spec from which this version of the precompile is active.
+    pub since: PrecompileSpecId,
+    /// Gas schedule of the precompile.
+    pub gas_model: GasModel,
+}
+
+impl PrecompileMetadata {
+    /// Returns the gas the precompile charges for the input, if the gas model does not depend on
+    /// the input content. See [`Precompile::required_gas`](crate::Precompile::required_gas) for the other precompiles.
+    pub fn required_gas(&self, input_len: usize) -> Option<u64> {
+        match self.gas_model {
+            GasModel::Fixed(gas) => Some(gas),
+            GasModel::Linear { base, word } => {
+                Some(crate::calc_linear_cost_u32(input_len, base, word))
+            }
+            GasModel::PerItem {
+                base,
+                per_item,
+                item_len,
+            } => Some((input_len / item_len) as u64 * per_item + base),
+            GasModel::Dynamic => None,
+        }
+    }
+}
+
+/// Precompile with its address and metadata.
+#[derive(Clone, Debug)]
+pub struct PrecompileEntry {
+    /// Precompile and its address.
+    pub precompile: PrecompileWithAddress,
+    /// Metadata of the precompile.
+    pub metadata: PrecompileMetadata,
+}
+
+impl PrecompileEntry {
+    /// Creates a new entry.
+    pub fn new(
+        precompile: PrecompileWithAddress,
+        name: &'static str,
+        since: PrecompileSpecId,
+        gas_model: GasModel,
+    ) -> Self {
+        Self {
+            precompile,
+            metadata: PrecompileMetadata {
+                name,
+                since,
+                gas_model,
+            },
+        }
+    }
+
+    /// Returns `true` if the entry is active in the given spec.
+    ///
+    /// Note that a later entry at the same address may replace it.
+    #[inline]
+    pub fn is_active_in(&self, spec: PrecompileSpecId) -> bool {
+        self.metadata.since <= spec
+    }
+}
+
+/// Returns all precompiles, ordered by the spec that activates them.
+///
+/// An entry replaces the previous entries at the same address from its
+/// [`since`](PrecompileMetadata::since) spec onwards.
+pub fn registry() -> &'static [PrecompileEntry] {
+    static INSTANCE: OnceBox<Vec<PrecompileEntry>> = OnceBox::new();
+    INSTANCE.get_or_init(|| {
+        use PrecompileSpecId::*;
+        let entry = PrecompileEntry::new;
+        let mut entries = Vec::from([
+            entry(
+                secp256k1::ECRECOVER,
+                "ECREC",
+                HOMESTEAD,
+                GasModel::Fixed(secp256k1::ECRECOVER_BASE),
+            ),
+            entry(
+                hash::SHA256,
+                "SHA256",
+                HOMESTEAD,
+                GasModel::Linear {
+                    base: hash::SHA256_BASE,
+                    word: hash::SHA256_PER_WORD,
+                },
+            ),
+            entry(
+                hash::RIPEMD160,
+                "RIPEMD160",
+                HOMESTEAD,
+                GasModel::Linear {
+                    base: hash::RIPEMD160_BASE,
+                    word: hash::RIPEMD160_PER_WORD,
+                },
+            ),
+            entry(
+                identity::FUN,
+                "ID",
+                HOMESTEAD,
+                GasModel::Linear {
+                    base: identity::IDENTITY_BASE,
+                    word: identity::IDENTITY_PER_WORD,
+                },
+            ),
+            // EIP-196: Precompiled contracts for addition and scalar multiplication on the elliptic curve alt_bn128.
+            // EIP-197: Precompiled contracts for optimal ate pairing check on the elliptic curve alt_bn128.
+            entry(
+                bn128::add::BYZANTIUM,
+                "BN254_ADD",
+                BYZANTIUM,
+                GasModel::Fixed(bn128::add::BYZANTIUM_ADD_GAS_COST),
+            ),
+            entry(
+                bn128::mul::BYZANTIUM,
+                "BN254_MUL",
+                BYZANTIUM,
+                GasModel::Fixed(bn128::mul::BYZANTIUM_MUL_GAS_COST),
+            ),
+            entry(
+                bn128::pair::BYZANTIUM,
+                "BN254_PAIRING",
+                BYZANTIUM,
+                GasModel::PerItem {
+                    base: bn128::pair::BYZANTIUM_PAIR_BASE,
+                    per_item: bn128::pair::BYZANTIUM_PAIR_PER_POINT,
+                    item_len: bn128::PAIR_ELEMENT_LEN,
+                },
+            ),
+            // EIP-198: Big integer modular exponentiation.
+            entry(modexp::BYZANTIUM, "MODEXP", BYZANTIUM, GasModel::Dynamic),
+            // EIP-1108: Reduce alt_bn128 precompile gas costs.
+            entry(
+                bn128::add::ISTANBUL,
+                "BN254_ADD",
+                ISTANBUL,
+                GasModel::Fixed(bn128::add::ISTANBUL_ADD_GAS_COST),
+            ),
+            entry(
+                bn128::mul::ISTANBUL,
+                "BN254_MUL",
+                ISTANBUL,
+                GasModel::Fixed(bn128::mul::ISTANBUL_MUL_GAS_COST),
+            ),
+            entry(
+                bn128::pair::ISTANBUL,
+                "BN254_PAIRING",
+                ISTANBUL,
+                GasModel::PerItem {
+                    base: bn128::pair::ISTANBUL_PAIR_BASE,
+                    per_item: bn128::pair::ISTANBUL_PAIR_PER_POINT,
+                    item_len: bn128::PAIR_ELEMENT_LEN,
+                },
+            ),
+            // EIP-152: Add BLAKE2 compression function `F` precompile.
+            entry(blake2::FUN, "BLAKE2F", ISTANBUL, GasModel::Dynamic),
+            // EIP-2565: ModExp Gas Cost.
+            entry(modexp::BERLIN, "MODEXP", BERLIN, GasModel::Dynamic),
+        ]);
+
+        // EIP-4844: Shard Blob Transactions
+        cfg_if! {
+            if #[cfg(any(feature = "c-kzg", feature = "kzg-rs"))] {
+                let precompile = crate::kzg_point_evaluation::POINT_EVALUATION.clone();
+                let gas_cost = crate::kzg_point_evaluation::GAS_COST;
+            } else {
+                let precompile = crate::fatal_precompile(crate::u64_to_address(0x0A), "c-kzg feature is not enabled".into());
+                let gas_cost = 50_000;
+            }
+        }
+        entries.push(entry(
+            precompile,
+            "KZG_POINT_EVALUATION",
+            CANCUN,
+            GasModel::Fixed(gas_cost),
+        ));
+
+        // Don't include BLS12-381 precompiles in no_std builds.
+        #[cfg(feature = "blst")]
+        entries.extend(crate::bls12_381::registry_entries());
+
+        Box::new(entries)
+    })
+}
+
+impl Precompiles {
+    /// Creates the precompiles of the given spec from the [`registry`].
+    pub fn from_registry(spec: PrecompileSpecId) -> Self {
+        let mut precompiles = Self::default();
+        precompiles.extend_with_metadata(
+            registry()
+                .iter()
+                .filter(|entry| entry.is_active_in(spec))
+                .cloned(),
+        );
+        precompiles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::u64_to_address;
+
+    #[test]
+    fn registry_metadata() {
+        let specs = [
+            PrecompileSpecId::HOMESTEAD,
+            PrecompileSpecId::BYZANTIUM,
+            PrecompileSpecId::ISTANBUL,
+            PrecompileSpecId::BERLIN,
+            PrecompileSpecId::CANCUN,
+            PrecompileSpecId::PRAGUE,
+        ];
+        for spec in specs {
+            let precompiles = Precompiles::new(spec);
+            assert_eq!(precompiles.metadata_iter().count(), precompiles.len());
+            for (address, metadata) in precompiles.metadata_iter() {
+                assert!(metadata.since <= spec);
+                // The fallback of the point evaluation precompile does not expose its gas.
+                #[cfg(not(any(feature = "c-kzg", feature = "kzg-rs")))]
+                if *address == u64_to_address(0x0A) {
+                    continue;
+                }
+                let precompile = precompiles.get(address).unwrap();
+                let input = [0u8; 384];
+                if let Some(gas) = metadata.required_gas(input.len()) {
+                    assert_eq!(
+                        precompile.required_gas(&input),
+                        Some(gas),
+                        "{}",
+                        metadata.name
+                    );
+                }
+            }
+        }
+
+        let berlin = Precompiles::berlin();
+        let pairing = berlin.metadata(&u64_to_address(8)).unwrap();
+        assert_eq!(pairing.name, "BN254_PAIRING");
+        assert_eq!(pairing.since, PrecompileSpecId::ISTANBUL);
+        let names = berlin
+            .metadata_iter()
+            .map(|(_, metadata)| metadata.name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "ECREC",
+                "SHA256",
+                "RIPEMD160",
+                "ID",
+                "MODEXP",
+                "BN254_ADD",
+                "BN254_MUL",
+                "BN254_PAIRING",
+                "BLAKE2F"
+            ]
+        );
+    }
+}
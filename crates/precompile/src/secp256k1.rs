@@ -1,11 +1,15 @@
 use crate::{utilities::right_pad, Error, Precompile, PrecompileResult, PrecompileWithAddress};
-use revm_primitives::{alloy_primitives::B512, Bytes, PrecompileOutput, B256};
+use revm_primitives::{alloy_primitives::B512, Address, Bytes, PrecompileOutput, B256};
+use std::vec::Vec;
 
 pub const ECRECOVER: PrecompileWithAddress = PrecompileWithAddress(
     crate::u64_to_address(1),
-    Precompile::Standard(ec_recover_run),
+    Precompile::StandardWithGas(ec_recover_run, |_| ECRECOVER_BASE),
 );
 
+/// Gas used by [`ec_recover_run`].
+pub const ECRECOVER_BASE: u64 = 3_000;
+
 pub use self::secp256k1::ecrecover;
 
 #[cfg(not(feature = "secp256k1"))]
@@ -67,8 +71,6 @@ mod secp256k1 {
 }
 
 pub fn ec_recover_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
-    const ECRECOVER_BASE: u64 = 3_000;
-
     if ECRECOVER_BASE > gas_limit {
         return Err(Error::OutOfGas.into());
     }
@@ -89,3 +91,101 @@ pub fn ec_recover_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
         .unwrap_or_default();
     Ok(PrecompileOutput::new(ECRECOVER_BASE, out))
 }
+
+/// Signature to recover the signer of, see [`ecrecover_batch`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RecoveryRequest {
+    /// `r` and `s` of the signature.
+    pub sig: B512,
+    /// Recovery id, `0` or `1`.
+    pub recid: u8,
+    /// Signed message hash.
+    pub msg: B256,
+}
+
+/// Minimum number of signatures recovered by each thread of [`ecrecover_batch`].
+#[cfg(all(feature = "std", not(feature = "deterministic")))]
+const MIN_BATCH_PER_THREAD: usize = 16;
+
+/// Recovers the signers of many signatures at once, e.g. the authorities of EIP-7702
+/// authorizations, using the same backend as the [`ECRECOVER`] precompile.
+///
+/// With the `std` feature the signatures are split between the available cores, unless the
+/// `deterministic` feature is enabled. Returns the signers in request order, `None` for
+/// signatures that fail to recover.
+pub fn ecrecover_batch(requests: &[RecoveryRequest]) -> Vec<Option<Address>> {
+    let mut signers = vec![None; requests.len()];
+
+    #[cfg(all(feature = "std", not(feature = "deterministic")))]
+    {
+        let threads = std::thread::available_parallelism()
+            .map_or(1, usize::from)
+            .min(requests.len() / MIN_BATCH_PER_THREAD);
+        if threads > 1 {
+            let chunk = requests.len().div_ceil(threads);
+            std::thread::scope(|scope| {
+                for (requests, signers) in requests.chunks(chunk).zip(signers.chunks_mut(chunk)) {
+                    scope.spawn(|| recover_into(requests, signers));
+                }
+            });
+            return signers;
+        }
+    }
+
+    recover_into(requests, &mut signers);
+    signers
+}
+
+fn recover_into(requests: &[RecoveryRequest], signers: &mut [Option<Address>]) {
+    for (request, signer) in requests.iter().zip(signers) {
+        if request.recid > 1 {
+            continue;
+        }
+        *signer = ecrecover(&request.sig, request.recid, &request.msg)
+            .ok()
+            .map(|hash| Address::from_word(hash));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::ecdsa::SigningKey;
+    use revm_primitives::keccak256;
+
+    #[test]
+    fn recover_batch() {
+        let requests_and_signers = (1..=100u8)
+            .map(|i| {
+                let key = SigningKey::from_bytes(&B256::with_last_byte(i).0.into()).unwrap();
+                let msg = keccak256([i]);
+                let (sig, recid) = key.sign_prehash_recoverable(msg.as_slice()).unwrap();
+                let public = key.verifying_key().to_encoded_point(false);
+                let signer = Address::from_word(keccak256(&public.as_bytes()[1..]));
+                let request = RecoveryRequest {
+                    sig: B512::from_slice(&sig.to_bytes()),
+                    recid: recid.to_byte(),
+                    msg,
+                };
+                (request, signer)
+            })
+            .collect::<Vec<_>>();
+        let mut requests = requests_and_signers
+            .iter()
+            .map(|(request, _)| *request)
+            .collect::<Vec<_>>();
+        let mut expected = requests_and_signers
+            .iter()
+            .map(|(_, signer)| Some(*signer))
+            .collect::<Vec<_>>();
+
+        // Invalid recovery id and invalid signature.
+        requests[3].recid = 27;
+        expected[3] = None;
+        requests[7].sig = B512::ZERO;
+        expected[7] = None;
+
+        assert_eq!(ecrecover_batch(&requests), expected);
+        assert_eq!(ecrecover_batch(&requests[..5]), expected[..5]);
+    }
+}
@@ -9,6 +9,7 @@
 use crate::{u64_to_address, Precompile, PrecompileWithAddress};
 use p256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey};
 use revm_primitives::{Bytes, PrecompileError, PrecompileOutput, PrecompileResult, B256};
+use sha2::{Digest, Sha256};
 
 /// Base gas fee for secp256r1 p256verify operation.
 const P256VERIFY_BASE: u64 = 3450;
@@ -19,8 +20,10 @@ pub fn precompiles() -> impl Iterator<Item = PrecompileWithAddress> {
 }
 
 /// [EIP-7212](https://eips.ethereum.org/EIPS/eip-7212#specification) secp256r1 precompile.
-pub const P256VERIFY: PrecompileWithAddress =
-    PrecompileWithAddress(u64_to_address(0x100), Precompile::Standard(p256_verify));
+pub const P256VERIFY: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(0x100),
+    Precompile::StandardWithGas(p256_verify, |_| P256VERIFY_BASE),
+);
 
 /// secp256r1 precompile logic. It takes the input bytes sent to the precompile
 /// and the gas limit. The output represents the result of verifying the
@@ -43,6 +46,54 @@ pub fn p256_verify(input: &Bytes, gas_limit: u64) -> PrecompileResult {
     Ok(PrecompileOutput::new(P256VERIFY_BASE, result))
 }
 
+/// Returns `true` if the signature included in the input is valid, with the same result as the
+/// [`P256VERIFY`] precompile. Usable off-chain, e.g. to validate passkey signatures of smart
+/// wallets before submitting them.
+///
+/// The input is encoded as for [`p256_verify`], see [`p256_verify_input`]. All inputs are public,
+/// so the verification does not need to run in constant time.
+#[inline]
+pub fn verify(input: &[u8]) -> bool {
+    verify_impl(input).is_some()
+}
+
+/// Assembles the input of the [`P256VERIFY`] precompile.
+pub fn p256_verify_input(msg: &B256, r: &B256, s: &B256, x: &B256, y: &B256) -> [u8; 160] {
+    let mut input = [0u8; 160];
+    for (chunk, value) in input.chunks_exact_mut(32).zip([msg, r, s, x, y]) {
+        chunk.copy_from_slice(value.as_slice());
+    }
+    input
+}
+
+/// Returns the message hash signed by a WebAuthn authenticator.
+///
+/// This is `sha256(authenticatorData || sha256(clientDataJSON))`, see
+/// <https://www.w3.org/TR/webauthn-2/#sctn-op-get-assertion>.
+pub fn webauthn_message_hash(authenticator_data: &[u8], client_data_json: &[u8]) -> B256 {
+    let client_data_hash = Sha256::digest(client_data_json);
+    let mut hasher = Sha256::new();
+    hasher.update(authenticator_data);
+    hasher.update(client_data_hash);
+    B256::from_slice(&hasher.finalize())
+}
+
+/// Assembles the input of the [`P256VERIFY`] precompile for a WebAuthn assertion signed with the
+/// public key `(x, y)`.
+///
+/// `r` and `s` are the signature components decoded from the DER signature of the assertion.
+pub fn webauthn_verify_input(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    r: &B256,
+    s: &B256,
+    x: &B256,
+    y: &B256,
+) -> [u8; 160] {
+    let msg = webauthn_message_hash(authenticator_data, client_data_json);
+    p256_verify_input(&msg, r, s, x, y)
+}
+
 /// Returns `Some(())` if the signature included in the input byte slice is
 /// valid, `None` otherwise.
 pub fn verify_impl(input: &[u8]) -> Option<()> {
@@ -128,4 +179,34 @@ mod test {
 
         assert_eq!(result.is_some(), expect_success);
     }
+
+    #[test]
+    fn test_webauthn_verify() {
+        use p256::ecdsa::{signature::hazmat::PrehashSigner, SigningKey};
+
+        let key = SigningKey::from_slice(&[7; 32]).unwrap();
+        let point = key.verifying_key().to_encoded_point(false);
+        let x = B256::from_slice(point.x().unwrap());
+        let y = B256::from_slice(point.y().unwrap());
+
+        let authenticator_data = [0x49; 37];
+        let client_data_json =
+            br#"{"type":"webauthn.get","challenge":"AAEC","origin":"https://example.com"}"#;
+        let msg = webauthn_message_hash(&authenticator_data, client_data_json);
+        let signature: Signature = key.sign_prehash(msg.as_slice()).unwrap();
+        let r = B256::from_slice(&signature.r().to_bytes());
+        let s = B256::from_slice(&signature.s().to_bytes());
+
+        let input = webauthn_verify_input(&authenticator_data, client_data_json, &r, &s, &x, &y);
+        assert_eq!(input, p256_verify_input(&msg, &r, &s, &x, &y));
+        assert!(verify(&input));
+        let outcome = p256_verify(&Bytes::copy_from_slice(&input), P256VERIFY_BASE).unwrap();
+        assert_eq!(outcome.bytes, Bytes::from(B256::with_last_byte(1)));
+
+        // Signing another client data fails on and off chain.
+        let input = webauthn_verify_input(&authenticator_data, b"{}", &r, &s, &x, &y);
+        assert!(!verify(&input));
+        let outcome = p256_verify(&Bytes::copy_from_slice(&input), P256VERIFY_BASE).unwrap();
+        assert!(outcome.bytes.is_empty());
+    }
 }
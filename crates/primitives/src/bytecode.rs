@@ -1,9 +1,14 @@
+pub mod eip7702;
 pub mod eof;
 pub mod legacy;
 
+pub use eip7702::{
+    delegated_address, delegation_designator, EIP7702_DESIGNATOR_LEN, EIP7702_MAGIC,
+    EIP7702_MAGIC_BYTES, EIP7702_VERSION,
+};
 use eof::EofDecodeError;
 pub use eof::{Eof, EOF_MAGIC, EOF_MAGIC_BYTES, EOF_MAGIC_HASH};
-pub use legacy::{JumpTable, LegacyAnalyzedBytecode};
+pub use legacy::{CompressedJumpTable, JumpTable, LegacyAnalyzedBytecode};
 use std::sync::Arc;
 
 use crate::{keccak256, Bytes, B256, KECCAK_EMPTY};
@@ -0,0 +1,51 @@
+//! EIP-7702 delegation designator.
+
+use crate::{Address, Bytes};
+
+/// Magic of the delegation designator in u16 form.
+pub const EIP7702_MAGIC: u16 = 0xEF01;
+
+/// Magic of the delegation designator in array form.
+pub static EIP7702_MAGIC_BYTES: Bytes = crate::bytes!("ef01");
+
+/// Version of the delegation designator.
+pub const EIP7702_VERSION: u8 = 0;
+
+/// Length of the delegation designator: magic, version and address.
+pub const EIP7702_DESIGNATOR_LEN: usize = 23;
+
+/// Returns the delegation designator `0xef0100 || address` of an account whose code is
+/// delegated to `address`.
+///
+/// This is the code the `EXTCODE*` instructions see on a delegated account.
+pub fn delegation_designator(address: Address) -> Bytes {
+    let mut designator = [0u8; EIP7702_DESIGNATOR_LEN];
+    designator[..2].copy_from_slice(&EIP7702_MAGIC.to_be_bytes());
+    designator[2] = EIP7702_VERSION;
+    designator[3..].copy_from_slice(address.as_slice());
+    Bytes::copy_from_slice(&designator)
+}
+
+/// Returns the address the code is delegated to, if `code` is a delegation designator.
+pub fn delegated_address(code: &[u8]) -> Option<Address> {
+    let rest = code.strip_prefix(&EIP7702_MAGIC.to_be_bytes())?;
+    let (&version, address) = rest.split_first()?;
+    (version == EIP7702_VERSION && address.len() == 20).then(|| Address::from_slice(address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address;
+
+    #[test]
+    fn designator() {
+        let address = address!("00000000000000000000000000000000000000aa");
+        let designator = delegation_designator(address);
+        assert_eq!(designator.len(), EIP7702_DESIGNATOR_LEN);
+        assert!(designator.starts_with(&EIP7702_MAGIC_BYTES));
+        assert_eq!(delegated_address(&designator), Some(address));
+        assert_eq!(delegated_address(&designator[..22]), None);
+        assert_eq!(delegated_address(&[0xef, 0x00]), None);
+    }
+}
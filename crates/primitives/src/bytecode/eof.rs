@@ -1,9 +1,11 @@
 mod body;
+mod builder;
 mod decode_helpers;
 mod header;
 mod types_section;
 
 pub use body::EofBody;
+pub use builder::{EofBuildError, EofBuilder};
 pub use header::EofHeader;
 pub use types_section::TypesSection;
 
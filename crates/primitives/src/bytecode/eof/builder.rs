@@ -0,0 +1,247 @@
+use super::{Eof, EofBody, TypesSection};
+use crate::Bytes;
+use core::fmt;
+use std::vec::Vec;
+
+/// Builder of EOF containers.
+///
+/// Sections are added in order, the header is computed from them on [`build`](Self::build),
+/// which checks the limits that [`Eof::decode`] checks so that the built container always
+/// decodes back to itself.
+///
+/// # Example
+///
+/// ```
+/// use revm_primitives::{bytes, eof::{EofBuilder, TypesSection}};
+///
+/// let eof = EofBuilder::new()
+///     .code_section(TypesSection::new(0, 0x80, 0), bytes!("00"))
+///     .data(bytes!("c0ffee"))
+///     .build()
+///     .unwrap();
+/// assert_eq!(eof.data(), &[0xc0, 0xff, 0xee]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EofBuilder {
+    types_section: Vec<TypesSection>,
+    code_section: Vec<Bytes>,
+    container_section: Vec<Bytes>,
+    data_section: Bytes,
+    data_size: Option<u16>,
+}
+
+impl EofBuilder {
+    /// Creates a builder without any section.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a code section with its types.
+    pub fn code_section(mut self, types: TypesSection, code: impl Into<Bytes>) -> Self {
+        self.types_section.push(types);
+        self.code_section.push(code.into());
+        self
+    }
+
+    /// Adds a subcontainer section.
+    pub fn container(mut self, container: impl Into<Bytes>) -> Self {
+        self.container_section.push(container.into());
+        self
+    }
+
+    /// Sets the data section.
+    pub fn data(mut self, data: impl Into<Bytes>) -> Self {
+        self.data_section = data.into();
+        self
+    }
+
+    /// Sets the data size of the header, which defaults to the length of the data section.
+    ///
+    /// Initcode containers declare more data than they contain, the rest being appended by
+    /// `RETURNCONTRACT` on deployment.
+    pub fn declared_data_size(mut self, data_size: u16) -> Self {
+        self.data_size = Some(data_size);
+        self
+    }
+
+    /// Validates the sections and assembles the container.
+    pub fn build(self) -> Result<Eof, EofBuildError> {
+        if self.code_section.is_empty() {
+            return Err(EofBuildError::ZeroCodeSections);
+        }
+        // more than 1024 code sections are not allowed
+        if self.code_section.len() > 0x0400 {
+            return Err(EofBuildError::TooManyCodeSections);
+        }
+        // the number of container sections may not exceed 256
+        if self.container_section.len() > 0x0100 {
+            return Err(EofBuildError::TooManyContainerSections);
+        }
+        for (index, types) in self.types_section.iter().enumerate() {
+            if types.validate().is_err() {
+                return Err(EofBuildError::InvalidTypes(index));
+            }
+        }
+        for (index, code) in self.code_section.iter().enumerate() {
+            if code.is_empty() {
+                return Err(EofBuildError::EmptyCodeSection(index));
+            }
+        }
+        for (index, container) in self.container_section.iter().enumerate() {
+            if container.is_empty() {
+                return Err(EofBuildError::EmptyContainer(index));
+            }
+        }
+        let too_large = |section: &Bytes| section.len() > u16::MAX as usize;
+        if self.code_section.iter().any(too_large)
+            || self.container_section.iter().any(too_large)
+            || too_large(&self.data_section)
+        {
+            return Err(EofBuildError::SectionTooLarge);
+        }
+        let data_len = self.data_section.len() as u16;
+        let data_size = self.data_size.unwrap_or(data_len);
+        if data_len > data_size {
+            return Err(EofBuildError::DataLargerThanDeclared);
+        }
+
+        let mut eof = EofBody {
+            types_section: self.types_section,
+            code_section: self.code_section,
+            container_section: self.container_section,
+            data_section: self.data_section,
+            is_data_filled: true,
+        }
+        .into_eof();
+        if data_size != data_len {
+            eof.header.data_size = data_size;
+            eof.body.is_data_filled = false;
+            eof.raw = eof.encode_slow();
+        }
+        Ok(eof)
+    }
+}
+
+/// Errors of [`EofBuilder::build`].
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum EofBuildError {
+    /// No code section was added.
+    ZeroCodeSections,
+    /// More than 1024 code sections.
+    TooManyCodeSections,
+    /// More than 256 container sections.
+    TooManyContainerSections,
+    /// Code section at the index is empty.
+    EmptyCodeSection(usize),
+    /// Container section at the index is empty.
+    EmptyContainer(usize),
+    /// A section is longer than 0xFFFF bytes.
+    SectionTooLarge,
+    /// Types of the code section at the index are out of bounds.
+    InvalidTypes(usize),
+    /// Data section is longer than the declared data size.
+    DataLargerThanDeclared,
+}
+
+impl fmt::Display for EofBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroCodeSections => f.write_str("No code section"),
+            Self::TooManyCodeSections => f.write_str("More than 1024 code sections"),
+            Self::TooManyContainerSections => f.write_str("More than 256 container sections"),
+            Self::EmptyCodeSection(index) => write!(f, "Code section {index} is empty"),
+            Self::EmptyContainer(index) => write!(f, "Container section {index} is empty"),
+            Self::SectionTooLarge => f.write_str("Section is longer than 0xFFFF bytes"),
+            Self::InvalidTypes(index) => write!(f, "Invalid types of code section {index}"),
+            Self::DataLargerThanDeclared => {
+                f.write_str("Data section is longer than the declared data size")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EofBuildError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytes;
+    use std::vec;
+
+    #[test]
+    fn build_minimal() {
+        let eof = EofBuilder::new()
+            .code_section(TypesSection::new(0, 0x80, 0), bytes!("fe"))
+            .build()
+            .unwrap();
+        assert_eq!(eof.raw, bytes!("ef000101000402000100010400000000800000fe"));
+        assert_eq!(Eof::decode(eof.raw.clone()), Ok(eof));
+    }
+
+    #[test]
+    fn round_trip() {
+        let inner = EofBuilder::new()
+            .code_section(TypesSection::new(0, 0x80, 0), bytes!("00"))
+            .build()
+            .unwrap();
+        let eof = EofBuilder::new()
+            .code_section(TypesSection::new(0, 0x80, 2), bytes!("60016000e3000100"))
+            .code_section(TypesSection::new(2, 0, 2), bytes!("5050e4"))
+            .container(inner.raw.clone())
+            .data(bytes!("0102"))
+            .build()
+            .unwrap();
+        assert_eq!(eof.header.code_sizes, vec![8, 3]);
+        assert_eq!(eof.header.container_sizes, vec![inner.raw.len() as u16]);
+        assert_eq!(Eof::decode(eof.raw.clone()), Ok(eof));
+
+        // Initcode with data appended on deployment.
+        let eof = EofBuilder::new()
+            .code_section(TypesSection::new(0, 0x80, 0), bytes!("00"))
+            .data(bytes!("01"))
+            .declared_data_size(4)
+            .build()
+            .unwrap();
+        assert_eq!(eof.header.data_size, 4);
+        assert!(!eof.body.is_data_filled);
+        assert_eq!(Eof::decode(eof.raw.clone()), Ok(eof));
+    }
+
+    #[test]
+    fn build_errors() {
+        let stop = TypesSection::new(0, 0x80, 0);
+        assert_eq!(
+            EofBuilder::new().build(),
+            Err(EofBuildError::ZeroCodeSections)
+        );
+        assert_eq!(
+            EofBuilder::new()
+                .code_section(stop, bytes!("00"))
+                .code_section(stop, Bytes::new())
+                .build(),
+            Err(EofBuildError::EmptyCodeSection(1))
+        );
+        assert_eq!(
+            EofBuilder::new()
+                .code_section(TypesSection::new(1, 0x80, 0), bytes!("00"))
+                .build(),
+            Err(EofBuildError::InvalidTypes(0))
+        );
+        assert_eq!(
+            EofBuilder::new()
+                .code_section(stop, bytes!("00"))
+                .container(Bytes::new())
+                .build(),
+            Err(EofBuildError::EmptyContainer(0))
+        );
+        assert_eq!(
+            EofBuilder::new()
+                .code_section(stop, bytes!("00"))
+                .data(bytes!("0102"))
+                .declared_data_size(1)
+                .build(),
+            Err(EofBuildError::DataLargerThanDeclared)
+        );
+    }
+}
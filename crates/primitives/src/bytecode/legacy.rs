@@ -1,6 +1,6 @@
 mod jump_map;
 
-pub use jump_map::JumpTable;
+pub use jump_map::{CompressedJumpTable, JumpTable};
 
 use crate::Bytes;
 use bitvec::{bitvec, order::Lsb0};
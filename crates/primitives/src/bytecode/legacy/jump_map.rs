@@ -1,6 +1,6 @@
 use crate::hex;
-use bitvec::vec::BitVec;
-use std::{fmt::Debug, sync::Arc};
+use bitvec::{bitvec, order::Lsb0, vec::BitVec};
+use std::{fmt::Debug, sync::Arc, vec::Vec};
 
 /// A map of valid `jump` destinations.
 #[derive(Clone, Default, PartialEq, Eq, Hash)]
@@ -33,4 +33,150 @@ impl JumpTable {
     pub fn is_valid(&self, pc: usize) -> bool {
         pc < self.0.len() && self.0[pc]
     }
+
+    /// Returns the compressed form of the jump map.
+    #[inline]
+    pub fn compress(&self) -> CompressedJumpTable {
+        CompressedJumpTable::new(self)
+    }
+}
+
+/// A map of valid `jump` destinations, stored as the distances between consecutive destinations.
+///
+/// Jump destinations are sparse in deployed contracts, so the distances encoded as LEB128 take
+/// a fraction of the space of [JumpTable], which keeps one bit per byte of code. Lookups are
+/// linear, so it is meant for caching and persisting analysed bytecode; use
+/// [CompressedJumpTable::decompress] before execution.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompressedJumpTable {
+    /// Length of the jump map in bits.
+    len: usize,
+    /// LEB128 encoded distance of every jump destination to the previous one.
+    deltas: Vec<u8>,
+}
+
+impl CompressedJumpTable {
+    /// Compresses the jump map.
+    pub fn new(table: &JumpTable) -> Self {
+        let mut deltas = Vec::new();
+        let mut prev = 0;
+        for pc in table.0.iter_ones() {
+            let mut delta = pc - prev;
+            prev = pc;
+            while delta >= 0x80 {
+                deltas.push(delta as u8 | 0x80);
+                delta >>= 7;
+            }
+            deltas.push(delta as u8);
+        }
+        Self {
+            len: table.0.len(),
+            deltas,
+        }
+    }
+
+    /// Returns the length of the jump map in bits.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the jump map is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the size of the encoded jump destinations in bytes.
+    #[inline]
+    pub fn encoded_len(&self) -> usize {
+        self.deltas.len()
+    }
+
+    /// Returns an iterator over the valid jump destinations in ascending order.
+    ///
+    /// Malformed input, e.g. from an untrusted cache, ends the iteration.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut bytes = self.deltas.iter();
+        let mut pc = 0usize;
+        core::iter::from_fn(move || {
+            let mut delta = 0usize;
+            let mut shift = 0;
+            loop {
+                let byte = *bytes.next()?;
+                if shift >= usize::BITS {
+                    return None;
+                }
+                delta |= ((byte & 0x7f) as usize) << shift;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+                shift += 7;
+            }
+            pc = pc.checked_add(delta)?;
+            Some(pc)
+        })
+        .take_while(|&pc| pc < self.len)
+    }
+
+    /// Check if `pc` is a valid jump destination.
+    pub fn is_valid(&self, pc: usize) -> bool {
+        self.iter()
+            .take_while(|&dest| dest <= pc)
+            .any(|dest| dest == pc)
+    }
+
+    /// Decompresses the jump map.
+    pub fn decompress(&self) -> JumpTable {
+        let mut jumps = bitvec![u8, Lsb0; 0; self.len];
+        for pc in self.iter() {
+            jumps.set(pc, true);
+        }
+        JumpTable(Arc::new(jumps))
+    }
+}
+
+impl From<&JumpTable> for CompressedJumpTable {
+    #[inline]
+    fn from(table: &JumpTable) -> Self {
+        Self::new(table)
+    }
+}
+
+impl From<&CompressedJumpTable> for JumpTable {
+    #[inline]
+    fn from(table: &CompressedJumpTable) -> Self {
+        table.decompress()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compressed_jump_table() {
+        let mut jumps = bitvec![u8, Lsb0; 0; 40_000];
+        for pc in [0, 1, 127, 128, 20_000, 39_999] {
+            jumps.set(pc, true);
+        }
+        let table = JumpTable(Arc::new(jumps));
+
+        let compressed = table.compress();
+        assert_eq!(compressed.len(), 40_000);
+        assert!(compressed.encoded_len() < table.as_slice().len());
+        assert_eq!(
+            compressed.iter().collect::<Vec<_>>(),
+            [0, 1, 127, 128, 20_000, 39_999]
+        );
+        assert!(compressed.is_valid(128));
+        assert!(!compressed.is_valid(129));
+        assert_eq!(compressed.decompress(), table);
+
+        // A destination past the end of the map is ignored.
+        let mut truncated = compressed.clone();
+        truncated.len = 20_000;
+        assert_eq!(truncated.iter().last(), Some(128));
+    }
 }
@@ -26,6 +26,20 @@ pub const BLOCKHASH_SERVE_WINDOW: usize = 8192;
 /// This is named `HISTORY_STORAGE_ADDRESS` in the EIP.
 pub const BLOCKHASH_STORAGE_ADDRESS: Address = address!("25a219378dad9b3503c8268c9ca836a52427a4fb");
 
+/// EIP-4788: Beacon block root in the EVM
+///
+/// The address of the contract storing the parent beacon block roots.
+pub const BEACON_ROOTS_ADDRESS: Address = address!("000f3df6d732807ef1319fb7b8bb8522d0beac02");
+
+/// EIP-4788: Beacon block root in the EVM
+///
+/// The number of roots kept by the beacon roots contract.
+///
+/// # Note
+///
+/// This is named `HISTORY_BUFFER_LENGTH` in the EIP.
+pub const BEACON_ROOTS_HISTORY_BUFFER_LENGTH: u64 = 8191;
+
 /// EIP-3860: Limit and meter initcode
 ///
 /// Limit of maximum initcode size is `2 * MAX_CODE_SIZE`.
@@ -1,7 +1,11 @@
 use crate::{Account, AccountInfo, Address, Bytecode, HashMap, B256, U256};
 use auto_impl::auto_impl;
 
+mod async_db;
+pub mod batch;
 pub mod components;
+pub use async_db::AsyncDatabase;
+pub use batch::{AccountChange, ChangeBatch, CommitBatchAdapter, DatabaseCommitBatch};
 pub use components::{
     BlockHash, BlockHashRef, DatabaseComponentError, DatabaseComponents, State, StateRef,
 };
@@ -23,6 +27,30 @@ pub trait Database {
 
     /// Get block hash by block number.
     fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error>;
+
+    /// Get the analysed bytecode by its hash, e.g. legacy code with its jump table.
+    ///
+    /// Persistent databases can return analysis results saved with
+    /// [`Database::store_code_analysis`] so that the code returned by [`Database::code_by_hash`]
+    /// is not analysed again. Returns `None` by default.
+    #[inline]
+    fn code_analysis_by_hash(&mut self, code_hash: B256) -> Result<Option<Bytecode>, Self::Error> {
+        let _ = code_hash;
+        Ok(None)
+    }
+
+    /// Store the analysed bytecode of the given code hash.
+    ///
+    /// Called after code without a stored analysis is analysed. Does nothing by default.
+    #[inline]
+    fn store_code_analysis(
+        &mut self,
+        code_hash: B256,
+        bytecode: &Bytecode,
+    ) -> Result<(), Self::Error> {
+        let _ = (code_hash, bytecode);
+        Ok(())
+    }
 }
 
 /// EVM database commit interface.
@@ -30,6 +58,16 @@ pub trait Database {
 pub trait DatabaseCommit {
     /// Commit changes to the database.
     fn commit(&mut self, changes: HashMap<Address, Account>);
+
+    /// Commit the changes of a single account to the database.
+    ///
+    /// Used to stream the changes of a transaction into the database without collecting them
+    /// first. The default implementation commits a map with only this account, databases should
+    /// override it to avoid the allocation.
+    #[inline]
+    fn commit_account(&mut self, address: Address, account: Account) {
+        self.commit(HashMap::from_iter([(address, account)]));
+    }
 }
 
 /// EVM database interface.
@@ -54,6 +92,28 @@ pub trait DatabaseRef {
 
     /// Get block hash by block number.
     fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error>;
+
+    /// Get the analysed bytecode by its hash. Returns `None` by default.
+    ///
+    /// See [`Database::code_analysis_by_hash`].
+    #[inline]
+    fn code_analysis_by_hash_ref(&self, code_hash: B256) -> Result<Option<Bytecode>, Self::Error> {
+        let _ = code_hash;
+        Ok(None)
+    }
+
+    /// Store the analysed bytecode of the given code hash. Does nothing by default.
+    ///
+    /// See [`Database::store_code_analysis`].
+    #[inline]
+    fn store_code_analysis_ref(
+        &self,
+        code_hash: B256,
+        bytecode: &Bytecode,
+    ) -> Result<(), Self::Error> {
+        let _ = (code_hash, bytecode);
+        Ok(())
+    }
 }
 
 /// Wraps a [`DatabaseRef`] to provide a [`Database`] implementation.
@@ -89,6 +149,20 @@ impl<T: DatabaseRef> Database for WrapDatabaseRef<T> {
     fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
         self.0.block_hash_ref(number)
     }
+
+    #[inline]
+    fn code_analysis_by_hash(&mut self, code_hash: B256) -> Result<Option<Bytecode>, Self::Error> {
+        self.0.code_analysis_by_hash_ref(code_hash)
+    }
+
+    #[inline]
+    fn store_code_analysis(
+        &mut self,
+        code_hash: B256,
+        bytecode: &Bytecode,
+    ) -> Result<(), Self::Error> {
+        self.0.store_code_analysis_ref(code_hash, bytecode)
+    }
 }
 
 impl<T: DatabaseRef + DatabaseCommit> DatabaseCommit for WrapDatabaseRef<T> {
@@ -96,4 +170,9 @@ impl<T: DatabaseRef + DatabaseCommit> DatabaseCommit for WrapDatabaseRef<T> {
     fn commit(&mut self, changes: HashMap<Address, Account>) {
         self.0.commit(changes)
     }
+
+    #[inline]
+    fn commit_account(&mut self, address: Address, account: Account) {
+        self.0.commit_account(address, account)
+    }
 }
@@ -0,0 +1,35 @@
+use crate::{AccountInfo, Address, Bytecode, B256, U256};
+use core::future::Future;
+
+/// Asynchronous EVM database interface, for state fetched over the network.
+///
+/// Contains the same methods as [`Database`](super::Database), returning futures. The EVM
+/// executes synchronously, so the database has to be wrapped in an adapter blocking on the
+/// futures, e.g. `WrapAsyncDb` in revm.
+pub trait AsyncDatabase {
+    /// The database error type.
+    type Error: Send;
+
+    /// Get basic account information.
+    fn basic(
+        &mut self,
+        address: Address,
+    ) -> impl Future<Output = Result<Option<AccountInfo>, Self::Error>> + Send;
+
+    /// Get account code by its hash.
+    fn code_by_hash(
+        &mut self,
+        code_hash: B256,
+    ) -> impl Future<Output = Result<Bytecode, Self::Error>> + Send;
+
+    /// Get storage value of address at index.
+    fn storage(
+        &mut self,
+        address: Address,
+        index: U256,
+    ) -> impl Future<Output = Result<U256, Self::Error>> + Send;
+
+    /// Get block hash by block number.
+    fn block_hash(&mut self, number: u64)
+        -> impl Future<Output = Result<B256, Self::Error>> + Send;
+}
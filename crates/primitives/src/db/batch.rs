@@ -0,0 +1,280 @@
+//! Ordered and deduplicated commit of state changes.
+use crate::{
+    db::{Database, DatabaseCommit, DatabaseRef},
+    Account, AccountInfo, Address, Bytecode, HashMap, B256, U256,
+};
+use auto_impl::auto_impl;
+use std::collections::BTreeMap;
+
+/// Change of a single account in a [`ChangeBatch`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccountChange {
+    /// New account information, `None` if the account was destroyed.
+    pub info: Option<AccountInfo>,
+    /// Whether the storage of the account was wiped before the storage changes of the batch,
+    /// because the account was destroyed or created.
+    pub storage_cleared: bool,
+}
+
+/// Changes of one or more transactions, sorted by address and storage slot.
+///
+/// Every account and every storage slot appear once, with the value of the last commit applied
+/// to the batch. Persistent databases can write the accounts and then the storage in the
+/// iteration order of the maps, which is deterministic.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChangeBatch {
+    /// Changed accounts.
+    pub accounts: BTreeMap<Address, AccountChange>,
+    /// Changed storage slots, keyed by account address and slot index.
+    pub storage: BTreeMap<(Address, U256), U256>,
+}
+
+impl ChangeBatch {
+    /// Create an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a batch from the changes of a transaction.
+    pub fn from_changes(changes: HashMap<Address, Account>) -> Self {
+        let mut batch = Self::new();
+        batch.extend(changes);
+        batch
+    }
+
+    /// Returns `true` if the batch does not contain any change.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty() && self.storage.is_empty()
+    }
+
+    /// Apply the changes of a transaction on top of the batch.
+    ///
+    /// Accounts that are not touched are skipped, and only the changed storage slots are kept.
+    pub fn extend(&mut self, changes: HashMap<Address, Account>) {
+        for (address, account) in changes {
+            self.push_account(address, account);
+        }
+    }
+
+    /// Apply the changes of a single account on top of the batch.
+    pub fn push_account(&mut self, address: Address, account: Account) {
+        if !account.is_touched() {
+            return;
+        }
+        let destroyed = account.is_selfdestructed();
+        if destroyed || account.is_created() {
+            self.clear_storage(address);
+        }
+        let change = self.accounts.entry(address).or_default();
+        change.storage_cleared |= destroyed || account.is_created();
+        if destroyed {
+            change.info = None;
+            return;
+        }
+        change.info = Some(account.info.clone());
+        self.storage.extend(
+            account
+                .changed_storage_slots()
+                .map(|(slot, value)| ((address, *slot), value.present_value())),
+        );
+    }
+
+    /// Merge a later batch into this one.
+    pub fn merge(&mut self, other: ChangeBatch) {
+        for (address, change) in other.accounts {
+            if change.storage_cleared {
+                self.clear_storage(address);
+            }
+            let entry = self.accounts.entry(address).or_default();
+            entry.storage_cleared |= change.storage_cleared;
+            entry.info = change.info;
+        }
+        self.storage.extend(other.storage);
+    }
+
+    /// Remove the storage changes of the account.
+    fn clear_storage(&mut self, address: Address) {
+        let slots = self
+            .storage
+            .range((address, U256::ZERO)..=(address, U256::MAX))
+            .map(|(key, _)| *key)
+            .collect::<std::vec::Vec<_>>();
+        for key in slots {
+            self.storage.remove(&key);
+        }
+    }
+}
+
+/// EVM database commit interface for databases that persist changes in batches.
+///
+/// Use [`CommitBatchAdapter`] to provide a [`DatabaseCommit`] implementation.
+#[auto_impl(&mut, Box)]
+pub trait DatabaseCommitBatch {
+    /// Commit the sorted changes to the database.
+    fn commit_batch(&mut self, batch: ChangeBatch);
+}
+
+/// Wraps a [`DatabaseCommitBatch`] to provide a [`DatabaseCommit`] implementation.
+///
+/// The changes of every commit are converted to a [`ChangeBatch`]. The [`Database`] and
+/// [`DatabaseRef`] implementations are forwarded to the inner database.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CommitBatchAdapter<T>(pub T);
+
+impl<T: DatabaseCommitBatch> DatabaseCommit for CommitBatchAdapter<T> {
+    #[inline]
+    fn commit(&mut self, changes: HashMap<Address, Account>) {
+        self.0.commit_batch(ChangeBatch::from_changes(changes))
+    }
+
+    #[inline]
+    fn commit_account(&mut self, address: Address, account: Account) {
+        let mut batch = ChangeBatch::new();
+        batch.push_account(address, account);
+        self.0.commit_batch(batch)
+    }
+}
+
+impl<T: Database> Database for CommitBatchAdapter<T> {
+    type Error = T::Error;
+
+    #[inline]
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.0.basic(address)
+    }
+
+    #[inline]
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.0.code_by_hash(code_hash)
+    }
+
+    #[inline]
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.0.storage(address, index)
+    }
+
+    #[inline]
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.0.block_hash(number)
+    }
+
+    #[inline]
+    fn code_analysis_by_hash(&mut self, code_hash: B256) -> Result<Option<Bytecode>, Self::Error> {
+        self.0.code_analysis_by_hash(code_hash)
+    }
+
+    #[inline]
+    fn store_code_analysis(
+        &mut self,
+        code_hash: B256,
+        bytecode: &Bytecode,
+    ) -> Result<(), Self::Error> {
+        self.0.store_code_analysis(code_hash, bytecode)
+    }
+}
+
+impl<T: DatabaseRef> DatabaseRef for CommitBatchAdapter<T> {
+    type Error = T::Error;
+
+    #[inline]
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.0.basic_ref(address)
+    }
+
+    #[inline]
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.0.code_by_hash_ref(code_hash)
+    }
+
+    #[inline]
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.0.storage_ref(address, index)
+    }
+
+    #[inline]
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.0.block_hash_ref(number)
+    }
+
+    #[inline]
+    fn code_analysis_by_hash_ref(&self, code_hash: B256) -> Result<Option<Bytecode>, Self::Error> {
+        self.0.code_analysis_by_hash_ref(code_hash)
+    }
+
+    #[inline]
+    fn store_code_analysis_ref(
+        &self,
+        code_hash: B256,
+        bytecode: &Bytecode,
+    ) -> Result<(), Self::Error> {
+        self.0.store_code_analysis_ref(code_hash, bytecode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EvmStorageSlot;
+
+    fn account(nonce: u64, storage: &[(u64, u64, u64)]) -> Account {
+        let mut account = Account::from(AccountInfo {
+            nonce,
+            ..Default::default()
+        });
+        account.mark_touch();
+        account.storage = storage
+            .iter()
+            .map(|&(slot, original, present)| {
+                (
+                    U256::from(slot),
+                    EvmStorageSlot::new_changed(U256::from(original), U256::from(present)),
+                )
+            })
+            .collect();
+        account
+    }
+
+    #[test]
+    fn sorted_and_deduplicated() {
+        let (a, b) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let mut batch = ChangeBatch::from_changes(HashMap::from_iter([
+            (b, account(1, &[(2, 0, 1), (1, 0, 1), (3, 5, 5)])),
+            (a, account(1, &[])),
+        ]));
+        batch.extend(HashMap::from_iter([(b, account(2, &[(1, 1, 7)]))]));
+
+        assert_eq!(batch.accounts.keys().copied().collect::<Vec<_>>(), [a, b]);
+        assert_eq!(batch.accounts[&b].info.as_ref().unwrap().nonce, 2);
+        // The unchanged slot 3 is skipped and slot 1 keeps its last value.
+        assert_eq!(
+            batch.storage.into_iter().collect::<Vec<_>>(),
+            [
+                ((b, U256::from(1)), U256::from(7)),
+                ((b, U256::from(2)), U256::from(1))
+            ]
+        );
+    }
+
+    #[test]
+    fn destroyed_account_clears_storage() {
+        let address = Address::with_last_byte(1);
+        let mut batch =
+            ChangeBatch::from_changes(HashMap::from_iter([(address, account(1, &[(1, 0, 1)]))]));
+        let mut destroyed = account(1, &[]);
+        destroyed.mark_selfdestruct();
+        let mut later = ChangeBatch::new();
+        later.push_account(address, destroyed);
+        batch.merge(later);
+
+        assert!(batch.storage.is_empty());
+        assert_eq!(
+            batch.accounts[&address],
+            AccountChange {
+                info: None,
+                storage_cleared: true
+            }
+        );
+    }
+}
@@ -80,4 +80,8 @@ impl<S: DatabaseCommit, BH: BlockHashRef> DatabaseCommit for DatabaseComponents<
     fn commit(&mut self, changes: HashMap<Address, Account>) {
         self.state.commit(changes);
     }
+
+    fn commit_account(&mut self, address: Address, account: Account) {
+        self.state.commit_account(address, account);
+    }
 }
@@ -0,0 +1,101 @@
+//! Fixed hashing of the `deterministic` feature.
+//!
+//! With the `deterministic` feature [`HashMap`](crate::HashMap) and [`HashSet`](crate::HashSet)
+//! hash with [`FixedState`] instead of a randomly seeded hasher, so that their iteration order,
+//! e.g. of the state of a [`ResultAndState`](crate::ResultAndState), only depends on the
+//! operations made on them. The hash of a value is the same on every platform, including 32-bit
+//! zkVM guests, which makes outputs byte-identical between a host and a guest.
+use core::hash::{BuildHasherDefault, Hasher};
+
+/// [`BuildHasher`](core::hash::BuildHasher) of [`FixedHasher`].
+pub type FixedState = BuildHasherDefault<FixedHasher>;
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+/// Unseeded hasher with the same output on every platform.
+///
+/// Words are mixed like the Fx hasher of rustc, integers are hashed as little-endian 64-bit
+/// words whatever the width of `usize`. Not resistant to collision attacks, the keys of the
+/// maps of the EVM are hashes and addresses that are costly to grind.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FixedHasher {
+    hash: u64,
+}
+
+impl FixedHasher {
+    #[inline]
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FixedHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let remainder = chunks.remainder();
+        if !remainder.is_empty() {
+            let mut word = [0; 8];
+            word[..remainder.len()].copy_from_slice(remainder);
+            self.add(u64::from_le_bytes(word));
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.add(i as u64);
+        self.add((i >> 64) as u64);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash.rotate_left(26)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Address;
+    use core::hash::BuildHasher;
+
+    #[test]
+    fn fixed_hashes() {
+        let state = FixedState::default();
+        assert_eq!(state.hash_one(1u64), 0xdc9c882a5545f306);
+        assert_eq!(state.hash_one(1usize), 0xdc9c882a5545f306);
+        // Length prefix, then the bytes in words of 8.
+        assert_eq!(
+            state.hash_one(Address::with_last_byte(1)),
+            0x8f594de1a445e43a
+        );
+    }
+}
@@ -1,18 +1,29 @@
+pub mod blob_sidecar;
 pub mod eip7702;
 pub mod handler_cfg;
-
+pub mod template;
+pub mod transaction;
+
+#[cfg(feature = "c-kzg")]
+pub use blob_sidecar::kzg_to_versioned_hash;
+pub use blob_sidecar::BlobSidecar;
+#[cfg(feature = "eip7702_signer")]
+pub use eip7702::AuthorizationSigner;
 pub use eip7702::{
-    Authorization, AuthorizationList, RecoveredAuthorization, Signature, SignedAuthorization,
+    Authorization, AuthorizationList, OptionalNonce, RecoveredAuthorization, Signature,
+    SignedAuthorization,
 };
 pub use handler_cfg::{CfgEnvWithHandlerCfg, EnvWithHandlerCfg, HandlerCfg};
+pub use template::{TxEnvTemplate, TxOverrides};
+pub use transaction::Transaction;
 
 use crate::{
-    calc_blob_gasprice, AccessListItem, Account, Address, Bytes, InvalidHeader, InvalidTransaction,
-    Spec, SpecId, B256, GAS_PER_BLOB, KECCAK_EMPTY, MAX_BLOB_NUMBER_PER_BLOCK, MAX_CODE_SIZE,
-    MAX_INITCODE_SIZE, U256, VERSIONED_HASH_VERSION_KZG,
+    calc_blob_gasprice, AccessListItem, Account, Address, Bytes, Eip, EipSet, InvalidHeader,
+    InvalidTransaction, Spec, SpecId, B256, BLOCK_HASH_HISTORY, GAS_PER_BLOB, KECCAK_EMPTY,
+    MAX_CODE_SIZE, U256,
 };
 use alloy_primitives::TxKind;
-use core::cmp::{min, Ordering};
+use core::cmp::Ordering;
 use core::hash::Hash;
 use std::boxed::Box;
 use std::vec::Vec;
@@ -45,11 +56,7 @@ impl Env {
     /// Calculates the effective gas price of the transaction.
     #[inline]
     pub fn effective_gas_price(&self) -> U256 {
-        if let Some(priority_fee) = self.tx.gas_priority_fee {
-            min(self.tx.gas_price, self.block.basefee + priority_fee)
-        } else {
-            self.tx.gas_price
-        }
+        self.tx.effective_gas_price(self.block.basefee)
     }
 
     /// Calculates the [EIP-4844] `data_fee` of the transaction.
@@ -60,7 +67,7 @@ impl Env {
     #[inline]
     pub fn calc_data_fee(&self) -> Option<U256> {
         self.block.get_blob_gasprice().map(|blob_gas_price| {
-            U256::from(blob_gas_price).saturating_mul(U256::from(self.tx.get_total_blob_gas()))
+            U256::from(blob_gas_price).saturating_mul(U256::from(self.tx.total_blob_gas()))
         })
     }
 
@@ -72,8 +79,8 @@ impl Env {
     /// See EIP-4844:
     /// <https://github.com/ethereum/EIPs/blob/master/EIPS/eip-4844.md#execution-layer-validation>
     pub fn calc_max_data_fee(&self) -> Option<U256> {
-        self.tx.max_fee_per_blob_gas.map(|max_fee_per_blob_gas| {
-            max_fee_per_blob_gas.saturating_mul(U256::from(self.tx.get_total_blob_gas()))
+        self.tx.max_fee_per_blob_gas().map(|max_fee_per_blob_gas| {
+            max_fee_per_blob_gas.saturating_mul(U256::from(self.tx.total_blob_gas()))
         })
     }
 
@@ -81,7 +88,7 @@ impl Env {
     #[inline]
     pub fn validate_block_env<SPEC: Spec>(&self) -> Result<(), InvalidHeader> {
         // `prevrandao` is required for the merge
-        if SPEC::enabled(SpecId::MERGE) && self.block.prevrandao.is_none() {
+        if self.block.difficulty_opcode_value(SPEC::SPEC_ID).is_none() {
             return Err(InvalidHeader::PrevrandaoNotSet);
         }
         // `excess_blob_gas` is required for Cancun
@@ -96,118 +103,7 @@ impl Env {
     /// Return initial spend gas (Gas needed to execute transaction).
     #[inline]
     pub fn validate_tx<SPEC: Spec>(&self) -> Result<(), InvalidTransaction> {
-        // Check if the transaction's chain id is correct
-        if let Some(tx_chain_id) = self.tx.chain_id {
-            if tx_chain_id != self.cfg.chain_id {
-                return Err(InvalidTransaction::InvalidChainId);
-            }
-        }
-
-        // Check if gas_limit is more than block_gas_limit
-        if !self.cfg.is_block_gas_limit_disabled()
-            && U256::from(self.tx.gas_limit) > self.block.gas_limit
-        {
-            return Err(InvalidTransaction::CallerGasLimitMoreThanBlock);
-        }
-
-        // Check that access list is empty for transactions before BERLIN
-        if !SPEC::enabled(SpecId::BERLIN) && !self.tx.access_list.is_empty() {
-            return Err(InvalidTransaction::AccessListNotSupported);
-        }
-
-        // BASEFEE tx check
-        if SPEC::enabled(SpecId::LONDON) {
-            if let Some(priority_fee) = self.tx.gas_priority_fee {
-                if priority_fee > self.tx.gas_price {
-                    // or gas_max_fee for eip1559
-                    return Err(InvalidTransaction::PriorityFeeGreaterThanMaxFee);
-                }
-            }
-
-            // check minimal cost against basefee
-            if !self.cfg.is_base_fee_check_disabled()
-                && self.effective_gas_price() < self.block.basefee
-            {
-                return Err(InvalidTransaction::GasPriceLessThanBasefee);
-            }
-        }
-
-        // EIP-3860: Limit and meter initcode
-        if SPEC::enabled(SpecId::SHANGHAI) && self.tx.transact_to.is_create() {
-            let max_initcode_size = self
-                .cfg
-                .limit_contract_code_size
-                .map(|limit| limit.saturating_mul(2))
-                .unwrap_or(MAX_INITCODE_SIZE);
-            if self.tx.data.len() > max_initcode_size {
-                return Err(InvalidTransaction::CreateInitCodeSizeLimit);
-            }
-        }
-
-        // - For before CANCUN, check that `blob_hashes` and `max_fee_per_blob_gas` are empty / not set
-        if !SPEC::enabled(SpecId::CANCUN)
-            && (self.tx.max_fee_per_blob_gas.is_some() || !self.tx.blob_hashes.is_empty())
-        {
-            return Err(InvalidTransaction::BlobVersionedHashesNotSupported);
-        }
-
-        // Presence of max_fee_per_blob_gas means that this is blob transaction.
-        if let Some(max) = self.tx.max_fee_per_blob_gas {
-            // ensure that the user was willing to at least pay the current blob gasprice
-            let price = self.block.get_blob_gasprice().expect("already checked");
-            if U256::from(price) > max {
-                return Err(InvalidTransaction::BlobGasPriceGreaterThanMax);
-            }
-
-            // there must be at least one blob
-            if self.tx.blob_hashes.is_empty() {
-                return Err(InvalidTransaction::EmptyBlobs);
-            }
-
-            // The field `to` deviates slightly from the semantics with the exception
-            // that it MUST NOT be nil and therefore must always represent
-            // a 20-byte address. This means that blob transactions cannot
-            // have the form of a create transaction.
-            if self.tx.transact_to.is_create() {
-                return Err(InvalidTransaction::BlobCreateTransaction);
-            }
-
-            // all versioned blob hashes must start with VERSIONED_HASH_VERSION_KZG
-            for blob in self.tx.blob_hashes.iter() {
-                if blob[0] != VERSIONED_HASH_VERSION_KZG {
-                    return Err(InvalidTransaction::BlobVersionNotSupported);
-                }
-            }
-
-            // ensure the total blob gas spent is at most equal to the limit
-            // assert blob_gas_used <= MAX_BLOB_GAS_PER_BLOCK
-            let num_blobs = self.tx.blob_hashes.len();
-            if num_blobs > MAX_BLOB_NUMBER_PER_BLOCK as usize {
-                return Err(InvalidTransaction::TooManyBlobs {
-                    have: num_blobs,
-                    max: MAX_BLOB_NUMBER_PER_BLOCK as usize,
-                });
-            }
-        } else {
-            // if max_fee_per_blob_gas is not set, then blob_hashes must be empty
-            if !self.tx.blob_hashes.is_empty() {
-                return Err(InvalidTransaction::BlobVersionedHashesNotSupported);
-            }
-        }
-
-        // check if EIP-7702 transaction is enabled.
-        if !SPEC::enabled(SpecId::PRAGUE) && self.tx.authorization_list.is_some() {
-            return Err(InvalidTransaction::AuthorizationListNotSupported);
-        }
-
-        if self.tx.authorization_list.is_some() {
-            // Check if other fields are unset.
-            if self.tx.max_fee_per_blob_gas.is_some() || !self.tx.blob_hashes.is_empty() {
-                return Err(InvalidTransaction::AuthorizationListInvalidFields);
-            }
-        }
-
-        Ok(())
+        transaction::validate_tx::<SPEC, _>(&self.cfg, &self.block, &self.tx)
     }
 
     /// Validate transaction against state.
@@ -216,15 +112,21 @@ impl Env {
         &self,
         account: &mut Account,
     ) -> Result<(), InvalidTransaction> {
+        // An impersonated sender can be a contract and use any nonce.
+        let impersonated = self.cfg.impersonate_caller;
+
         // EIP-3607: Reject transactions from senders with deployed code
         // This EIP is introduced after london but there was no collision in past
         // so we can leave it enabled always
-        if !self.cfg.is_eip3607_disabled() && account.info.code_hash != KECCAK_EMPTY {
+        if !self.cfg.is_eip3607_disabled()
+            && !impersonated
+            && account.info.code_hash != KECCAK_EMPTY
+        {
             return Err(InvalidTransaction::RejectCallerWithCode);
         }
 
         // Check that the transaction's nonce is correct
-        if let Some(tx) = self.tx.nonce {
+        if let Some(tx) = self.tx.nonce.filter(|_| !impersonated) {
             let state = account.info.nonce;
             match tx.cmp(&state) {
                 Ordering::Greater => {
@@ -237,33 +139,66 @@ impl Env {
             }
         }
 
-        let mut balance_check = U256::from(self.tx.gas_limit)
+        // Gas of a sponsored transaction is paid by the sponsor, see
+        // `Env::validate_gas_sponsor_against_state`.
+        let balance_check = if self.tx.is_sponsored() {
+            self.tx.value
+        } else {
+            self.max_gas_fee::<SPEC>()?
+                .checked_add(self.tx.value)
+                .ok_or(InvalidTransaction::OverflowPaymentInTransaction)?
+        };
+
+        // Check if account has enough balance for gas_limit*gas_price and value transfer.
+        // Transfer will be done inside `*_inner` functions.
+        self.check_balance(account, balance_check, impersonated)
+    }
+
+    /// Validates that the gas sponsor of the transaction can pay for its maximum gas fee.
+    #[inline]
+    pub fn validate_gas_sponsor_against_state<SPEC: Spec>(
+        &self,
+        sponsor: &mut Account,
+    ) -> Result<(), InvalidTransaction> {
+        let max_gas_fee = self.max_gas_fee::<SPEC>()?;
+        self.check_balance(sponsor, max_gas_fee, false)
+    }
+
+    /// Returns the maximum fee the transaction can pay for gas, including the blob data fee.
+    fn max_gas_fee<SPEC: Spec>(&self) -> Result<U256, InvalidTransaction> {
+        let mut max_gas_fee = U256::from(self.tx.gas_limit)
             .checked_mul(self.tx.gas_price)
-            .and_then(|gas_cost| gas_cost.checked_add(self.tx.value))
             .ok_or(InvalidTransaction::OverflowPaymentInTransaction)?;
 
         if SPEC::enabled(SpecId::CANCUN) {
             // if the tx is not a blob tx, this will be None, so we add zero
             let data_fee = self.calc_max_data_fee().unwrap_or_default();
-            balance_check = balance_check
+            max_gas_fee = max_gas_fee
                 .checked_add(U256::from(data_fee))
                 .ok_or(InvalidTransaction::OverflowPaymentInTransaction)?;
         }
+        Ok(max_gas_fee)
+    }
 
-        // Check if account has enough balance for gas_limit*gas_price and value transfer.
-        // Transfer will be done inside `*_inner` functions.
-        if balance_check > account.info.balance {
-            if self.cfg.is_balance_check_disabled() {
+    /// Checks that the account balance covers the amount, crediting the missing balance to an
+    /// impersonated account.
+    fn check_balance(
+        &self,
+        account: &mut Account,
+        amount: U256,
+        impersonated: bool,
+    ) -> Result<(), InvalidTransaction> {
+        if amount > account.info.balance {
+            if self.cfg.is_balance_check_disabled() || impersonated {
                 // Add transaction cost to balance to ensure execution doesn't fail.
-                account.info.balance = balance_check;
+                account.info.balance = amount;
             } else {
                 return Err(InvalidTransaction::LackOfFundForMaxFee {
-                    fee: Box::new(balance_check),
+                    fee: Box::new(amount),
                     balance: Box::new(account.info.balance),
                 });
             }
         }
-
         Ok(())
     }
 }
@@ -276,6 +211,11 @@ pub struct CfgEnv {
     /// Chain ID of the EVM, it will be compared to the transaction's Chain ID.
     /// Chain ID is introduced EIP-155
     pub chain_id: u64,
+    /// Chain ID returned by the `CHAINID` opcode. If not set, `chain_id` is used.
+    ///
+    /// Transaction and authorization validation always use `chain_id`, this only changes what
+    /// the executed contracts observe.
+    pub exec_chain_id: Option<u64>,
     /// KZG Settings for point evaluation precompile. By default, this is loaded from the ethereum mainnet trusted setup.
     #[cfg(any(feature = "c-kzg", feature = "kzg-rs"))]
     #[cfg_attr(feature = "serde", serde(skip))]
@@ -288,7 +228,58 @@ pub struct CfgEnv {
     /// If some it will effects EIP-170: Contract code size limit. Useful to increase this because of tests.
     /// By default it is 0x6000 (~25kb).
     pub limit_contract_code_size: Option<usize>,
-    /// A hard memory limit in bytes beyond which [crate::result::OutOfGasError::Memory] cannot be resized.
+    /// Number of most recent blocks whose hashes are available to the `BLOCKHASH` opcode.
+    /// Older or future block numbers return zero. By default it is [`BLOCK_HASH_HISTORY`] (256).
+    pub block_hash_window: Option<u64>,
+    /// EIPs enabled on top of the ones activated by the spec.
+    ///
+    /// Useful for research forks and testing an EIP before its fork is scheduled.
+    pub experimental_eips: EipSet,
+    /// `EXTCODESIZE`, `EXTCODECOPY` and `EXTCODEHASH` of accounts delegated with EIP-7702 read
+    /// the code they delegate to, like early Prague devnets, instead of the delegation
+    /// designator. By default, it is set to `false`.
+    pub expose_delegated_code: bool,
+    /// Maximum number of journal entries and logs of a transaction. The frame that grows the
+    /// journal past it halts with [`HaltReason::JournalLimitExceeded`](crate::HaltReason::JournalLimitExceeded).
+    ///
+    /// Bounds the memory used by adversarial transactions that touch large amounts of state.
+    /// By default, it is set to `None`.
+    pub journal_limit: Option<usize>,
+    /// Recipients of the base fee and priority fee of transactions.
+    ///
+    /// Lets custom networks redirect fees without replacing the `reward_beneficiary` handle.
+    /// By default, the base fee is burned and the priority fee is paid to the coinbase.
+    pub fee_vaults: FeeVaults,
+    /// Accounts and storage slots that are warm at the start of every transaction, on top of
+    /// the access list of the transaction.
+    ///
+    /// Used by networks with system contracts that are always warm. Unlike the access list,
+    /// they don't add to the intrinsic gas. By default, it is empty.
+    pub warm_accesses: Vec<AccessListItem>,
+    /// Executes transactions for any sender, like an impersonated account of a development node.
+    ///
+    /// The nonce of the transaction and EIP-3607 are not checked, and a sender that can not pay
+    /// for the maximum gas fee and the value is credited the missing balance. The state is
+    /// updated as for a regular transaction, and the credited balance is reported in
+    /// [`ResultAndState::impersonation`](crate::ResultAndState::impersonation).
+    /// By default, it is set to `false`.
+    pub impersonate_caller: bool,
+    /// Leaves the beneficiary and the fee vaults untouched when the fee paid to them is zero,
+    /// e.g. for transactions with a zero gas price on devnets or for system transactions.
+    ///
+    /// Touching them otherwise adds them to the state changes, which other clients may not do.
+    /// By default, it is set to `false`.
+    pub skip_zero_fee_rewards: bool,
+    /// Maximum gas limit of a transaction, enforced during validation on top of the block gas
+    /// limit.
+    ///
+    /// Used by networks that cap the gas of a single transaction, like [EIP-7825].
+    /// By default, it is set to `None`.
+    ///
+    /// [EIP-7825]: https://eips.ethereum.org/EIPS/eip-7825
+    pub tx_gas_limit_cap: Option<u64>,
+    /// A hard memory limit in bytes beyond which memory cannot be resized, halting with
+    /// [crate::result::HaltReason::MemoryLimitExceeded].
     ///
     /// In cases where the gas limit may be extraordinarily high, it is recommended to set this to
     /// a sane value to prevent memory allocation panics. Defaults to `2^32 - 1` bytes per
@@ -322,6 +313,31 @@ pub struct CfgEnv {
     /// By default, it is set to `false`.
     #[cfg(feature = "optional_beneficiary_reward")]
     pub disable_beneficiary_reward: bool,
+    /// Records the input of every KECCAK256 instruction together with its hash into
+    /// [`ResultAndState::keccak_preimages`](crate::ResultAndState::keccak_preimages).
+    /// By default, it is set to `false`.
+    #[cfg(feature = "keccak_preimages")]
+    pub record_keccak_preimages: bool,
+    /// Statically verifies the stack bounds of legacy contracts before running them, and skips
+    /// the stack underflow checks of the instructions of the verified ones.
+    /// By default, it is set to `false`.
+    #[cfg(feature = "legacy_stack_verification")]
+    pub verify_legacy_stack: bool,
+    /// Wall-clock time a transaction can spend executing bytecode, after which it fails with
+    /// [`EVMError::Timeout`](crate::EVMError::Timeout).
+    /// By default, it is set to `None`.
+    #[cfg(feature = "execution_timeout")]
+    pub execution_timeout: Option<core::time::Duration>,
+    /// Counts the executed opcodes into
+    /// [`ResultAndState::opcode_stats`](crate::ResultAndState::opcode_stats).
+    /// By default, it is set to [`OpcodeStatsLevel::Disabled`](crate::OpcodeStatsLevel::Disabled).
+    #[cfg(feature = "opcode_stats")]
+    pub opcode_stats: crate::OpcodeStatsLevel,
+    /// Records where the gas of transactions is spent into
+    /// [`ResultAndState::gas_profile`](crate::ResultAndState::gas_profile).
+    /// By default, it is set to `false`.
+    #[cfg(feature = "gas_profile")]
+    pub gas_profile: bool,
 }
 
 impl CfgEnv {
@@ -331,11 +347,44 @@ impl CfgEnv {
         self.limit_contract_code_size.unwrap_or(MAX_CODE_SIZE)
     }
 
+    /// Returns the `BLOCKHASH` lookback window from [`Self::block_hash_window`] if set
+    /// or default [`BLOCK_HASH_HISTORY`] value.
+    pub fn block_hash_window(&self) -> u64 {
+        self.block_hash_window.unwrap_or(BLOCK_HASH_HISTORY as u64)
+    }
+
+    /// Returns `true` if the EIP is activated by `spec_id` or enabled in
+    /// [`Self::experimental_eips`].
+    #[inline]
+    pub fn is_eip_enabled(&self, spec_id: SpecId, eip: Eip) -> bool {
+        eip.is_active_in(spec_id) || self.experimental_eips.contains(eip)
+    }
+
+    /// Enables the EIP regardless of the spec.
+    pub fn with_experimental_eip(mut self, eip: Eip) -> Self {
+        self.experimental_eips.insert(eip);
+        self
+    }
+
     pub fn with_chain_id(mut self, chain_id: u64) -> Self {
         self.chain_id = chain_id;
         self
     }
 
+    /// Sets the chain ID returned by the `CHAINID` opcode.
+    pub fn with_exec_chain_id(mut self, exec_chain_id: u64) -> Self {
+        self.exec_chain_id = Some(exec_chain_id);
+        self
+    }
+
+    /// Returns the chain ID visible to the executed contracts.
+    ///
+    /// This is [`Self::exec_chain_id`] if set, otherwise [`Self::chain_id`].
+    #[inline]
+    pub fn execution_chain_id(&self) -> u64 {
+        self.exec_chain_id.unwrap_or(self.chain_id)
+    }
+
     #[cfg(feature = "optional_eip3607")]
     pub fn is_eip3607_disabled(&self) -> bool {
         self.disable_eip3607
@@ -395,14 +444,44 @@ impl CfgEnv {
     pub fn is_beneficiary_reward_disabled(&self) -> bool {
         false
     }
+
+    #[cfg(feature = "keccak_preimages")]
+    pub fn is_keccak_preimage_recording_enabled(&self) -> bool {
+        self.record_keccak_preimages
+    }
+
+    #[cfg(not(feature = "keccak_preimages"))]
+    pub fn is_keccak_preimage_recording_enabled(&self) -> bool {
+        false
+    }
+
+    #[cfg(feature = "legacy_stack_verification")]
+    pub fn is_legacy_stack_verification_enabled(&self) -> bool {
+        self.verify_legacy_stack
+    }
+
+    #[cfg(not(feature = "legacy_stack_verification"))]
+    pub fn is_legacy_stack_verification_enabled(&self) -> bool {
+        false
+    }
 }
 
 impl Default for CfgEnv {
     fn default() -> Self {
         Self {
             chain_id: 1,
+            exec_chain_id: None,
             perf_analyse_created_bytecodes: AnalysisKind::default(),
             limit_contract_code_size: None,
+            block_hash_window: None,
+            experimental_eips: EipSet::new(),
+            expose_delegated_code: false,
+            journal_limit: None,
+            fee_vaults: FeeVaults::default(),
+            warm_accesses: Vec::new(),
+            impersonate_caller: false,
+            skip_zero_fee_rewards: false,
+            tx_gas_limit_cap: None,
             #[cfg(any(feature = "c-kzg", feature = "kzg-rs"))]
             kzg_settings: crate::kzg::EnvKzgSettings::Default,
             #[cfg(feature = "memory_limit")]
@@ -419,6 +498,16 @@ impl Default for CfgEnv {
             disable_base_fee: false,
             #[cfg(feature = "optional_beneficiary_reward")]
             disable_beneficiary_reward: false,
+            #[cfg(feature = "keccak_preimages")]
+            record_keccak_preimages: false,
+            #[cfg(feature = "legacy_stack_verification")]
+            verify_legacy_stack: false,
+            #[cfg(feature = "execution_timeout")]
+            execution_timeout: None,
+            #[cfg(feature = "opcode_stats")]
+            opcode_stats: crate::OpcodeStatsLevel::Disabled,
+            #[cfg(feature = "gas_profile")]
+            gas_profile: false,
         }
     }
 }
@@ -444,7 +533,9 @@ pub struct BlockEnv {
     pub basefee: U256,
     /// The difficulty of the block.
     ///
-    /// Unused after the Paris (AKA the merge) upgrade, and replaced by `prevrandao`.
+    /// Returned by the `DIFFICULTY` opcode before the Paris (AKA the merge) upgrade, and set this
+    /// to the header difficulty when replaying pre-merge blocks. Unused afterwards, where it is
+    /// replaced by `prevrandao`. See [`BlockEnv::difficulty_opcode_value`].
     pub difficulty: U256,
     /// The output of the randomness beacon provided by the beacon chain.
     ///
@@ -452,6 +543,8 @@ pub struct BlockEnv {
     ///
     /// NOTE: `prevrandao` can be found in a block in place of `mix_hash`.
     ///
+    /// Only read from the Paris upgrade onwards, pre-merge blocks can leave it unset.
+    ///
     /// [EIP-4399]: https://eips.ethereum.org/EIPS/eip-4399
     pub prevrandao: Option<B256>,
     /// Excess blob gas and blob gasprice.
@@ -462,6 +555,15 @@ pub struct BlockEnv {
     ///
     /// [EIP-4844]: https://eips.ethereum.org/EIPS/eip-4844
     pub blob_excess_gas_and_price: Option<BlobExcessGasAndPrice>,
+    /// Root of the parent beacon block.
+    ///
+    /// From the Cancun upgrade, it is stored in the [`BEACON_ROOTS_ADDRESS`](crate::BEACON_ROOTS_ADDRESS)
+    /// contract before the transaction is executed, see [EIP-4788]. Leave it unset to skip the
+    /// write, e.g. if the block executor makes the system call itself.
+    ///
+    /// [EIP-4788]: https://eips.ethereum.org/EIPS/eip-4788
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub parent_beacon_block_root: Option<B256>,
 }
 
 impl BlockEnv {
@@ -494,6 +596,24 @@ impl BlockEnv {
             .map(|a| a.excess_blob_gas)
     }
 
+    /// Returns the value pushed by the `DIFFICULTY` opcode in the given spec.
+    ///
+    /// This is [`difficulty`](Self::difficulty) before the Paris (AKA the merge) upgrade and
+    /// [`prevrandao`](Self::prevrandao) from it onwards, see [EIP-4399].
+    ///
+    /// Returns `None` if `Merge` is enabled and `prevrandao` is not set. This is enforced in
+    /// [`Env::validate_block_env`].
+    ///
+    /// [EIP-4399]: https://eips.ethereum.org/EIPS/eip-4399
+    #[inline]
+    pub fn difficulty_opcode_value(&self, spec_id: SpecId) -> Option<U256> {
+        if spec_id.is_enabled_in(SpecId::MERGE) {
+            self.prevrandao.map(|prevrandao| prevrandao.into())
+        } else {
+            Some(self.difficulty)
+        }
+    }
+
     /// Clears environment and resets fields to default values.
     #[inline]
     pub fn clear(&mut self) {
@@ -512,6 +632,7 @@ impl Default for BlockEnv {
             difficulty: U256::ZERO,
             prevrandao: Some(B256::ZERO),
             blob_excess_gas_and_price: Some(BlobExcessGasAndPrice::new(0)),
+            parent_beacon_block_root: None,
         }
     }
 }
@@ -574,6 +695,14 @@ pub struct TxEnv {
     /// [EIP-4844]: https://eips.ethereum.org/EIPS/eip-4844
     pub max_fee_per_blob_gas: Option<U256>,
 
+    /// The blobs of the transaction, with their commitments and proofs.
+    ///
+    /// Optional, if set the sidecar is verified against [`Self::blob_hashes`] during
+    /// validation with the KZG settings of [`CfgEnv`], so invalid blob proofs are rejected as
+    /// they are by consensus. See [`BlobSidecar::verify`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub blob_sidecar: Option<BlobSidecar>,
+
     /// List of authorizations, that contains the signature that authorizes this
     /// caller to place the code to signer account.
     ///
@@ -582,6 +711,14 @@ pub struct TxEnv {
     /// [EIP-Set EOA account code for one transaction](https://eips.ethereum.org/EIPS/eip-7702)
     pub authorization_list: Option<AuthorizationList>,
 
+    /// Account that pays for the gas of the transaction instead of the caller.
+    ///
+    /// The sponsor is charged the gas fee before execution and refunded the unused gas, the
+    /// caller still pays the transferred value. Used to simulate sponsored transactions, e.g.
+    /// of paymasters and relayers, without overriding balances.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gas_sponsor: Option<Address>,
+
     #[cfg_attr(feature = "serde", serde(flatten))]
     #[cfg(feature = "optimism")]
     /// Optimism fields.
@@ -626,7 +763,9 @@ impl Default for TxEnv {
             access_list: Vec::new(),
             blob_hashes: Vec::new(),
             max_fee_per_blob_gas: None,
+            blob_sidecar: None,
             authorization_list: None,
+            gas_sponsor: None,
             #[cfg(feature = "optimism")]
             optimism: OptimismFields::default(),
         }
@@ -708,6 +847,73 @@ pub enum CreateScheme {
     },
 }
 
+/// Recipients of the parts of the transaction fee, see [`CfgEnv::fee_vaults`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeeVaults {
+    /// Recipient of the base fee, burned by default.
+    ///
+    /// Only used since London, before that there is no base fee.
+    pub base_fee: FeeRecipient,
+    /// Recipient of the priority fee, the block coinbase by default.
+    pub priority_fee: FeeRecipient,
+}
+
+impl Default for FeeVaults {
+    fn default() -> Self {
+        Self {
+            base_fee: FeeRecipient::Burn,
+            priority_fee: FeeRecipient::Coinbase,
+        }
+    }
+}
+
+impl FeeVaults {
+    /// Returns the account receiving the base fee, `None` if it is burned.
+    #[inline]
+    pub const fn base_fee_recipient(&self, coinbase: Address) -> Option<Address> {
+        self.base_fee.resolve(coinbase)
+    }
+
+    /// Returns the account receiving the priority fee, `None` if it is burned.
+    #[inline]
+    pub const fn priority_fee_recipient(&self, coinbase: Address) -> Option<Address> {
+        self.priority_fee.resolve(coinbase)
+    }
+
+    /// Returns the accounts receiving a part of the fee.
+    #[inline]
+    pub fn recipients(&self, coinbase: Address) -> impl Iterator<Item = Address> {
+        self.priority_fee_recipient(coinbase)
+            .into_iter()
+            .chain(self.base_fee_recipient(coinbase))
+    }
+}
+
+/// Destination of a part of the transaction fee, see [`FeeVaults`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FeeRecipient {
+    /// The fee is burned.
+    Burn,
+    /// The fee is paid to the block coinbase.
+    Coinbase,
+    /// The fee is paid to the account.
+    Account(Address),
+}
+
+impl FeeRecipient {
+    /// Returns the account receiving the fee, `None` if it is burned.
+    #[inline]
+    pub const fn resolve(self, coinbase: Address) -> Option<Address> {
+        match self {
+            Self::Burn => None,
+            Self::Coinbase => Some(coinbase),
+            Self::Account(address) => Some(address),
+        }
+    }
+}
+
 /// What bytecode analysis to perform.
 #[derive(Clone, Default, Debug, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -723,6 +929,41 @@ pub enum AnalysisKind {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_difficulty_opcode_value() {
+        let mut block = BlockEnv {
+            difficulty: U256::from(0x1234),
+            prevrandao: None,
+            ..Default::default()
+        };
+        assert_eq!(
+            block.difficulty_opcode_value(SpecId::LONDON),
+            Some(U256::from(0x1234))
+        );
+        assert_eq!(block.difficulty_opcode_value(SpecId::MERGE), None);
+
+        block.prevrandao = Some(B256::with_last_byte(0x56));
+        assert_eq!(
+            block.difficulty_opcode_value(SpecId::LONDON),
+            Some(U256::from(0x1234))
+        );
+        assert_eq!(
+            block.difficulty_opcode_value(SpecId::MERGE),
+            Some(U256::from(0x56))
+        );
+
+        let mut env = Env {
+            block,
+            ..Default::default()
+        };
+        env.block.prevrandao = None;
+        assert_eq!(env.validate_block_env::<crate::LondonSpec>(), Ok(()));
+        assert_eq!(
+            env.validate_block_env::<crate::MergeSpec>(),
+            Err(InvalidHeader::PrevrandaoNotSet)
+        );
+    }
+
     #[test]
     fn test_validate_tx_chain_id() {
         let mut env = Env::default();
@@ -734,6 +975,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_exec_chain_id_does_not_affect_validation() {
+        let mut env = Env::default();
+        env.tx.chain_id = Some(1);
+        env.cfg = CfgEnv::default().with_chain_id(1).with_exec_chain_id(5);
+        assert_eq!(env.validate_tx::<crate::LatestSpec>(), Ok(()));
+        assert_eq!(env.cfg.execution_chain_id(), 5);
+
+        env.cfg.exec_chain_id = None;
+        assert_eq!(env.cfg.execution_chain_id(), 1);
+    }
+
+    #[test]
+    fn test_experimental_eip7702() {
+        let mut env = Env::default();
+        env.tx.authorization_list = Some(AuthorizationList::Recovered(vec![]));
+        assert_eq!(
+            env.validate_tx::<crate::CancunSpec>(),
+            Err(InvalidTransaction::AuthorizationListNotSupported)
+        );
+
+        env.cfg = CfgEnv::default().with_experimental_eip(Eip::Eip7702);
+        assert_eq!(env.validate_tx::<crate::CancunSpec>(), Ok(()));
+    }
+
     #[test]
     fn test_validate_tx_access_list() {
         let mut env = Env::default();
@@ -0,0 +1,82 @@
+use crate::{Bytes, FixedBytes};
+use std::vec::Vec;
+
+/// The blobs of an [EIP-4844] transaction, with their commitments and proofs.
+///
+/// Attached to [`TxEnv::blob_sidecar`](crate::TxEnv::blob_sidecar), the sidecar is verified
+/// against the versioned hashes of the transaction the same way consensus does, see
+/// [`BlobSidecar::verify`].
+///
+/// [EIP-4844]: https://eips.ethereum.org/EIPS/eip-4844
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlobSidecar {
+    /// The blobs, 131072 bytes each.
+    pub blobs: Vec<Bytes>,
+    /// The KZG commitments of the blobs.
+    pub commitments: Vec<FixedBytes<48>>,
+    /// The KZG proofs of the blobs.
+    pub proofs: Vec<FixedBytes<48>>,
+}
+
+#[cfg(feature = "c-kzg")]
+impl BlobSidecar {
+    /// Verifies the sidecar against the blob versioned hashes of the transaction.
+    ///
+    /// There must be one blob, commitment and proof per versioned hash, each commitment must
+    /// hash to its versioned hash and the proofs must be valid for the blobs.
+    pub fn verify(
+        &self,
+        blob_hashes: &[crate::B256],
+        kzg_settings: &crate::KzgSettings,
+    ) -> Result<(), crate::InvalidTransaction> {
+        use crate::InvalidTransaction;
+
+        let len = blob_hashes.len();
+        if self.blobs.len() != len || self.commitments.len() != len || self.proofs.len() != len {
+            return Err(InvalidTransaction::BlobSidecarLengthMismatch);
+        }
+        for (commitment, hash) in self.commitments.iter().zip(blob_hashes) {
+            if kzg_to_versioned_hash(commitment.as_slice()) != *hash {
+                return Err(InvalidTransaction::BlobCommitmentMismatch);
+            }
+        }
+
+        let blobs = self
+            .blobs
+            .iter()
+            .map(|blob| c_kzg::Blob::from_bytes(blob))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| InvalidTransaction::InvalidBlobProof)?;
+        let commitments = self
+            .commitments
+            .iter()
+            .map(|commitment| c_kzg::Bytes48::from(commitment.0))
+            .collect::<Vec<_>>();
+        let proofs = self
+            .proofs
+            .iter()
+            .map(|proof| c_kzg::Bytes48::from(proof.0))
+            .collect::<Vec<_>>();
+        match c_kzg::KzgProof::verify_blob_kzg_proof_batch(
+            &blobs,
+            &commitments,
+            &proofs,
+            kzg_settings,
+        ) {
+            Ok(true) => Ok(()),
+            _ => Err(InvalidTransaction::InvalidBlobProof),
+        }
+    }
+}
+
+/// `VERSIONED_HASH_VERSION_KZG ++ sha256(commitment)[1..]`
+#[cfg(feature = "c-kzg")]
+#[inline]
+pub fn kzg_to_versioned_hash(commitment: &[u8]) -> crate::B256 {
+    use sha2::{Digest, Sha256};
+
+    let mut hash: [u8; 32] = Sha256::digest(commitment).into();
+    hash[0] = crate::VERSIONED_HASH_VERSION_KZG;
+    hash.into()
+}
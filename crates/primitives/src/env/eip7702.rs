@@ -1,5 +1,10 @@
-pub use alloy_eips::eip7702::{Authorization, SignedAuthorization};
+#[cfg(feature = "eip7702_signer")]
+mod signer;
+
+pub use alloy_eips::eip7702::{Authorization, OptionalNonce, SignedAuthorization};
 pub use alloy_primitives::Signature;
+#[cfg(feature = "eip7702_signer")]
+pub use signer::AuthorizationSigner;
 
 use crate::Address;
 use core::ops::Deref;
@@ -52,6 +57,23 @@ impl AuthorizationList {
         }
     }
 
+    /// Appends a signed authorization, recovering it if the list is recovered.
+    pub fn push(&mut self, authorization: SignedAuthorization) {
+        match self {
+            Self::Signed(signed) => signed.push(authorization),
+            Self::Recovered(recovered) => recovered.push(authorization.into()),
+        }
+    }
+
+    /// Returns the authorities of the authorizations, in the order of [`Self::recovered_iter`].
+    ///
+    /// The authority is `None` if it could not be recovered.
+    pub fn authorities(&self) -> Vec<Option<Address>> {
+        self.recovered_iter()
+            .map(|authorization| authorization.authority())
+            .collect()
+    }
+
     /// Returns recovered authorizations list.
     pub fn into_recovered(self) -> Self {
         let Self::Signed(signed) = self else {
@@ -0,0 +1,93 @@
+//! Signing of EIP-7702 authorizations, for tests and tooling.
+use super::{Authorization, OptionalNonce, SignedAuthorization};
+use crate::{Address, AuthorizationList, Signature, TxEnv, B256};
+use k256::ecdsa::SigningKey;
+use std::vec::Vec;
+
+/// Signs EIP-7702 authorizations with a secp256k1 key.
+#[derive(Clone, Debug)]
+pub struct AuthorizationSigner {
+    key: SigningKey,
+    address: Address,
+}
+
+impl AuthorizationSigner {
+    /// Creates a signer from a signing key.
+    pub fn new(key: SigningKey) -> Self {
+        let address = Address::from_private_key(&key);
+        Self { key, address }
+    }
+
+    /// Creates a signer from the bytes of a secret key.
+    ///
+    /// Fails if the bytes are not a valid secret key.
+    pub fn from_bytes(secret: &B256) -> Result<Self, k256::ecdsa::Error> {
+        SigningKey::from_slice(secret.as_slice()).map(Self::new)
+    }
+
+    /// Returns the authority address of the authorizations signed by this signer.
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Signs the authorization.
+    pub fn sign(&self, authorization: Authorization) -> SignedAuthorization {
+        let (signature, recovery_id) = self
+            .key
+            .sign_prehash_recoverable(authorization.signature_hash().as_slice())
+            .expect("prehash is 32 bytes");
+        authorization.into_signed(Signature::from((signature, recovery_id)))
+    }
+
+    /// Signs an authorization to delegate to `address` on the chain `chain_id`, `0` meaning
+    /// any chain.
+    ///
+    /// If `nonce` is `None`, the authorization is valid for any nonce of the authority.
+    pub fn authorize(
+        &self,
+        chain_id: u64,
+        address: Address,
+        nonce: Option<u64>,
+    ) -> SignedAuthorization {
+        self.sign(Authorization {
+            chain_id,
+            address,
+            nonce: OptionalNonce::new(nonce),
+        })
+    }
+
+    /// Signs the authorization and appends it to the authorization list of the transaction.
+    pub fn authorize_tx(&self, tx: &mut TxEnv, authorization: Authorization) {
+        tx.authorization_list
+            .get_or_insert_with(|| AuthorizationList::Signed(Vec::new()))
+            .push(self.sign(authorization));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_and_recover() {
+        let signer = AuthorizationSigner::from_bytes(&B256::with_last_byte(1)).unwrap();
+        // Address of the secret key 1.
+        assert_eq!(
+            signer.address(),
+            crate::address!("7e5f4552091a69125d5dfcb7b8c2659029395bdf")
+        );
+
+        let delegate = Address::with_last_byte(2);
+        let signed = signer.authorize(1, delegate, Some(3));
+        assert_eq!(signed.recover_authority().unwrap(), signer.address());
+
+        let mut tx = TxEnv::default();
+        signer.authorize_tx(&mut tx, signed.into_parts().0);
+        let list = tx.authorization_list.unwrap();
+        assert!(matches!(list, AuthorizationList::Signed(_)));
+        assert_eq!(list.authorities(), [Some(signer.address())]);
+        let recovered = list.recovered_iter().next().unwrap();
+        assert_eq!(recovered.address, delegate);
+        assert_eq!(recovered.nonce(), Some(3));
+    }
+}
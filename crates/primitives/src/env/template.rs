@@ -0,0 +1,113 @@
+use crate::{Address, TxEnv, U256};
+
+/// Fields of a [`TxEnv`] that change between instantiations of a [`TxEnvTemplate`].
+///
+/// Fields set to `None` are taken from the template.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TxOverrides {
+    /// Caller of the transaction.
+    pub caller: Option<Address>,
+    /// Value sent to the destination.
+    pub value: Option<U256>,
+    /// Nonce of the transaction.
+    pub nonce: Option<u64>,
+}
+
+impl TxOverrides {
+    /// Creates overrides of the caller and value.
+    #[inline]
+    pub fn new(caller: Address, value: U256) -> Self {
+        Self {
+            caller: Some(caller),
+            value: Some(value),
+            nonce: None,
+        }
+    }
+
+    /// Writes the overridden fields into the transaction.
+    #[inline]
+    pub fn apply(&self, tx: &mut TxEnv) {
+        if let Some(caller) = self.caller {
+            tx.caller = caller;
+        }
+        if let Some(value) = self.value {
+            tx.value = value;
+        }
+        if let Some(nonce) = self.nonce {
+            tx.nonce = Some(nonce);
+        }
+    }
+}
+
+/// Transaction that is simulated with many different callers and values.
+///
+/// None of the overridable fields are used by the static transaction validation or the initial
+/// gas calculation, so these only need to be done once for all instantiations, see
+/// `Evm::transact_many`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TxEnvTemplate {
+    tx: TxEnv,
+}
+
+impl TxEnvTemplate {
+    /// Creates a new template from the transaction.
+    #[inline]
+    pub fn new(tx: TxEnv) -> Self {
+        Self { tx }
+    }
+
+    /// Returns the transaction of the template.
+    #[inline]
+    pub fn tx(&self) -> &TxEnv {
+        &self.tx
+    }
+
+    /// Returns the transaction with the overrides applied.
+    ///
+    /// The calldata is shared with the template and not copied.
+    #[inline]
+    pub fn instantiate(&self, overrides: &TxOverrides) -> TxEnv {
+        let mut tx = self.tx.clone();
+        overrides.apply(&mut tx);
+        tx
+    }
+}
+
+impl From<TxEnv> for TxEnvTemplate {
+    #[inline]
+    fn from(tx: TxEnv) -> Self {
+        Self::new(tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bytes, TxKind};
+
+    #[test]
+    fn instantiate() {
+        let template = TxEnvTemplate::new(TxEnv {
+            caller: Address::with_last_byte(1),
+            transact_to: TxKind::Call(Address::with_last_byte(2)),
+            data: bytes!("a9059cbb"),
+            nonce: Some(3),
+            ..Default::default()
+        });
+
+        let tx = template.instantiate(&TxOverrides::new(Address::with_last_byte(4), U256::from(5)));
+        assert_eq!(tx.caller, Address::with_last_byte(4));
+        assert_eq!(tx.value, U256::from(5));
+        assert_eq!(tx.nonce, Some(3));
+        assert_eq!(tx.data.as_ptr(), template.tx().data.as_ptr());
+
+        let tx = template.instantiate(&TxOverrides {
+            nonce: Some(7),
+            ..Default::default()
+        });
+        assert_eq!(tx.caller, Address::with_last_byte(1));
+        assert_eq!(tx.nonce, Some(7));
+    }
+}
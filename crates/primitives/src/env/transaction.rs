@@ -0,0 +1,595 @@
+use crate::{
+    AccessListItem, Address, AuthorizationList, BlobSidecar, BlockEnv, Bytes, CfgEnv, Eip,
+    InvalidTransaction, Spec, SpecId, TxEnv, TxKind, B256, GAS_PER_BLOB, MAX_BLOB_NUMBER_PER_BLOCK,
+    MAX_INITCODE_SIZE, U256, VERSIONED_HASH_VERSION_KZG,
+};
+use core::cmp::min;
+use std::boxed::Box;
+
+/// Read access to the fields of a transaction that are common to all transaction types.
+///
+/// Chain specific transaction types can wrap a [`TxEnv`] with their own fields and implement this
+/// trait by delegating to it with [`impl_transaction!`](crate::impl_transaction), which makes
+/// [`validate_tx`] and the initial gas calculation usable for them.
+pub trait Transaction {
+    /// Caller aka Author aka transaction signer.
+    fn caller(&self) -> Address;
+
+    /// The gas limit of the transaction.
+    fn gas_limit(&self) -> u64;
+
+    /// The gas price of the transaction, or the max fee per gas for EIP-1559 transactions.
+    fn gas_price(&self) -> U256;
+
+    /// The destination of the transaction.
+    fn transact_to(&self) -> TxKind;
+
+    /// The value sent to the destination.
+    fn value(&self) -> U256;
+
+    /// The data of the transaction.
+    fn data(&self) -> &Bytes;
+
+    /// The nonce of the transaction, `None` skips the nonce check.
+    fn nonce(&self) -> Option<u64>;
+
+    /// The chain ID of the transaction, `None` skips the chain ID check.
+    fn chain_id(&self) -> Option<u64>;
+
+    /// The EIP-2930 access list.
+    fn access_list(&self) -> &[AccessListItem];
+
+    /// The EIP-1559 priority fee per gas.
+    fn gas_priority_fee(&self) -> Option<U256>;
+
+    /// The EIP-4844 blob versioned hashes.
+    fn blob_hashes(&self) -> &[B256];
+
+    /// The EIP-4844 max fee per blob gas.
+    fn max_fee_per_blob_gas(&self) -> Option<U256>;
+
+    /// The EIP-7702 authorization list.
+    fn authorization_list(&self) -> Option<&AuthorizationList>;
+
+    /// The EIP-4844 blobs with their commitments and proofs, if attached.
+    #[inline]
+    fn blob_sidecar(&self) -> Option<&BlobSidecar> {
+        None
+    }
+
+    /// The account paying for the gas instead of the caller, if any.
+    #[inline]
+    fn gas_sponsor(&self) -> Option<Address> {
+        None
+    }
+
+    /// Returns the account that pays for the gas of the transaction.
+    #[inline]
+    fn gas_payer(&self) -> Address {
+        self.gas_sponsor().unwrap_or(self.caller())
+    }
+
+    /// Returns `true` if the gas is paid by a sponsor other than the caller.
+    #[inline]
+    fn is_sponsored(&self) -> bool {
+        self.gas_payer() != self.caller()
+    }
+
+    /// The EIP-2718 enveloped encoding of the transaction, if known.
+    ///
+    /// Used by chains that charge for the encoded transaction size.
+    #[inline]
+    fn enveloped_tx(&self) -> Option<&Bytes> {
+        None
+    }
+
+    /// Returns `true` if the transaction creates a contract.
+    #[inline]
+    fn is_create(&self) -> bool {
+        self.transact_to().is_create()
+    }
+
+    /// Calculates the effective gas price of the transaction at the given `basefee`.
+    #[inline]
+    fn effective_gas_price(&self, basefee: U256) -> U256 {
+        match self.gas_priority_fee() {
+            Some(priority_fee) => min(self.gas_price(), basefee + priority_fee),
+            None => self.gas_price(),
+        }
+    }
+
+    /// Total blob gas used by the transaction.
+    #[inline]
+    fn total_blob_gas(&self) -> u64 {
+        GAS_PER_BLOB * self.blob_hashes().len() as u64
+    }
+}
+
+impl Transaction for TxEnv {
+    #[inline]
+    fn caller(&self) -> Address {
+        self.caller
+    }
+
+    #[inline]
+    fn gas_limit(&self) -> u64 {
+        self.gas_limit
+    }
+
+    #[inline]
+    fn gas_price(&self) -> U256 {
+        self.gas_price
+    }
+
+    #[inline]
+    fn transact_to(&self) -> TxKind {
+        self.transact_to
+    }
+
+    #[inline]
+    fn value(&self) -> U256 {
+        self.value
+    }
+
+    #[inline]
+    fn data(&self) -> &Bytes {
+        &self.data
+    }
+
+    #[inline]
+    fn nonce(&self) -> Option<u64> {
+        self.nonce
+    }
+
+    #[inline]
+    fn chain_id(&self) -> Option<u64> {
+        self.chain_id
+    }
+
+    #[inline]
+    fn access_list(&self) -> &[AccessListItem] {
+        &self.access_list
+    }
+
+    #[inline]
+    fn gas_priority_fee(&self) -> Option<U256> {
+        self.gas_priority_fee
+    }
+
+    #[inline]
+    fn blob_hashes(&self) -> &[B256] {
+        &self.blob_hashes
+    }
+
+    #[inline]
+    fn max_fee_per_blob_gas(&self) -> Option<U256> {
+        self.max_fee_per_blob_gas
+    }
+
+    #[inline]
+    fn authorization_list(&self) -> Option<&AuthorizationList> {
+        self.authorization_list.as_ref()
+    }
+
+    #[inline]
+    fn blob_sidecar(&self) -> Option<&BlobSidecar> {
+        self.blob_sidecar.as_ref()
+    }
+
+    #[inline]
+    fn gas_sponsor(&self) -> Option<Address> {
+        self.gas_sponsor
+    }
+
+    #[cfg(feature = "optimism")]
+    #[inline]
+    fn enveloped_tx(&self) -> Option<&Bytes> {
+        self.optimism.enveloped_tx.as_ref()
+    }
+}
+
+/// Implements [`Transaction`] for a type by delegating to one of its fields.
+///
+/// Provided methods such as [`Transaction::enveloped_tx`] can be overridden in an optional
+/// trailing block.
+///
+/// ```
+/// use revm_primitives::{impl_transaction, Bytes, Transaction, TxEnv, B256};
+///
+/// struct DepositTx {
+///     base: TxEnv,
+///     source_hash: B256,
+///     mint: u128,
+///     envelope: Bytes,
+/// }
+///
+/// impl_transaction!(DepositTx, base, {
+///     fn enveloped_tx(&self) -> Option<&Bytes> {
+///         Some(&self.envelope)
+///     }
+/// });
+/// ```
+#[macro_export]
+macro_rules! impl_transaction {
+    ($ty:ty, $field:ident $(, { $($item:item)* })?) => {
+        impl $crate::Transaction for $ty {
+            #[inline]
+            fn caller(&self) -> $crate::Address {
+                $crate::Transaction::caller(&self.$field)
+            }
+
+            #[inline]
+            fn gas_limit(&self) -> u64 {
+                $crate::Transaction::gas_limit(&self.$field)
+            }
+
+            #[inline]
+            fn gas_price(&self) -> $crate::U256 {
+                $crate::Transaction::gas_price(&self.$field)
+            }
+
+            #[inline]
+            fn transact_to(&self) -> $crate::TxKind {
+                $crate::Transaction::transact_to(&self.$field)
+            }
+
+            #[inline]
+            fn value(&self) -> $crate::U256 {
+                $crate::Transaction::value(&self.$field)
+            }
+
+            #[inline]
+            fn data(&self) -> &$crate::Bytes {
+                $crate::Transaction::data(&self.$field)
+            }
+
+            #[inline]
+            fn nonce(&self) -> Option<u64> {
+                $crate::Transaction::nonce(&self.$field)
+            }
+
+            #[inline]
+            fn chain_id(&self) -> Option<u64> {
+                $crate::Transaction::chain_id(&self.$field)
+            }
+
+            #[inline]
+            fn access_list(&self) -> &[$crate::AccessListItem] {
+                $crate::Transaction::access_list(&self.$field)
+            }
+
+            #[inline]
+            fn gas_priority_fee(&self) -> Option<$crate::U256> {
+                $crate::Transaction::gas_priority_fee(&self.$field)
+            }
+
+            #[inline]
+            fn blob_hashes(&self) -> &[$crate::B256] {
+                $crate::Transaction::blob_hashes(&self.$field)
+            }
+
+            #[inline]
+            fn max_fee_per_blob_gas(&self) -> Option<$crate::U256> {
+                $crate::Transaction::max_fee_per_blob_gas(&self.$field)
+            }
+
+            #[inline]
+            fn authorization_list(&self) -> Option<&$crate::AuthorizationList> {
+                $crate::Transaction::authorization_list(&self.$field)
+            }
+
+            #[inline]
+            fn blob_sidecar(&self) -> Option<&$crate::BlobSidecar> {
+                $crate::Transaction::blob_sidecar(&self.$field)
+            }
+
+            #[inline]
+            fn gas_sponsor(&self) -> Option<$crate::Address> {
+                $crate::Transaction::gas_sponsor(&self.$field)
+            }
+
+            $($($item)*)?
+        }
+    };
+}
+
+/// Validates the transaction against the configuration and the block it is in.
+///
+/// This is [`Env::validate_tx`](crate::Env::validate_tx) for any [`Transaction`].
+pub fn validate_tx<SPEC: Spec, T: Transaction + ?Sized>(
+    cfg: &CfgEnv,
+    block: &BlockEnv,
+    tx: &T,
+) -> Result<(), InvalidTransaction> {
+    // Check if the transaction's chain id is correct
+    if let Some(tx_chain_id) = tx.chain_id() {
+        if tx_chain_id != cfg.chain_id {
+            return Err(InvalidTransaction::InvalidChainId);
+        }
+    }
+
+    // Check if gas_limit is more than block_gas_limit
+    if !cfg.is_block_gas_limit_disabled() && U256::from(tx.gas_limit()) > block.gas_limit {
+        return Err(InvalidTransaction::CallerGasLimitMoreThanBlock {
+            gas_limit: tx.gas_limit(),
+            block_gas_limit: Box::new(block.gas_limit),
+        });
+    }
+
+    // Check if gas_limit is more than the transaction gas limit cap of the network
+    if let Some(cap) = cfg.tx_gas_limit_cap {
+        if tx.gas_limit() > cap {
+            return Err(InvalidTransaction::TxGasLimitGreaterThanCap {
+                gas_limit: tx.gas_limit(),
+                cap,
+            });
+        }
+    }
+
+    // Check that access list is empty for transactions before BERLIN
+    if !SPEC::enabled(SpecId::BERLIN) && !tx.access_list().is_empty() {
+        return Err(InvalidTransaction::AccessListNotSupported);
+    }
+
+    // BASEFEE tx check
+    if SPEC::enabled(SpecId::LONDON) {
+        if let Some(priority_fee) = tx.gas_priority_fee() {
+            if priority_fee > tx.gas_price() {
+                // or gas_max_fee for eip1559
+                return Err(InvalidTransaction::PriorityFeeGreaterThanMaxFee {
+                    priority_fee: Box::new(priority_fee),
+                    max_fee: Box::new(tx.gas_price()),
+                });
+            }
+        }
+
+        // check minimal cost against basefee
+        let gas_price = tx.effective_gas_price(block.basefee);
+        if !cfg.is_base_fee_check_disabled() && gas_price < block.basefee {
+            return Err(InvalidTransaction::GasPriceLessThanBasefee {
+                gas_price: Box::new(gas_price),
+                basefee: Box::new(block.basefee),
+            });
+        }
+    }
+
+    // EIP-3860: Limit and meter initcode
+    if SPEC::enabled(SpecId::SHANGHAI) && tx.is_create() {
+        let max_initcode_size = cfg
+            .limit_contract_code_size
+            .map(|limit| limit.saturating_mul(2))
+            .unwrap_or(MAX_INITCODE_SIZE);
+        if tx.data().len() > max_initcode_size {
+            return Err(InvalidTransaction::CreateInitCodeSizeLimit);
+        }
+    }
+
+    // - For before CANCUN, check that `blob_hashes` and `max_fee_per_blob_gas` are empty / not set
+    if !SPEC::enabled(SpecId::CANCUN)
+        && (tx.max_fee_per_blob_gas().is_some() || !tx.blob_hashes().is_empty())
+    {
+        return Err(InvalidTransaction::BlobVersionedHashesNotSupported);
+    }
+
+    // Presence of max_fee_per_blob_gas means that this is blob transaction.
+    if let Some(max) = tx.max_fee_per_blob_gas() {
+        // ensure that the user was willing to at least pay the current blob gasprice
+        let price = block.get_blob_gasprice().expect("already checked");
+        if U256::from(price) > max {
+            return Err(InvalidTransaction::BlobGasPriceGreaterThanMax {
+                blob_gasprice: price,
+                max_fee_per_blob_gas: Box::new(max),
+            });
+        }
+
+        // there must be at least one blob
+        if tx.blob_hashes().is_empty() {
+            return Err(InvalidTransaction::EmptyBlobs);
+        }
+
+        // The field `to` deviates slightly from the semantics with the exception
+        // that it MUST NOT be nil and therefore must always represent
+        // a 20-byte address. This means that blob transactions cannot
+        // have the form of a create transaction.
+        if tx.is_create() {
+            return Err(InvalidTransaction::BlobCreateTransaction);
+        }
+
+        // all versioned blob hashes must start with VERSIONED_HASH_VERSION_KZG
+        for blob in tx.blob_hashes() {
+            if blob[0] != VERSIONED_HASH_VERSION_KZG {
+                return Err(InvalidTransaction::BlobVersionNotSupported);
+            }
+        }
+
+        // ensure the total blob gas spent is at most equal to the limit
+        // assert blob_gas_used <= MAX_BLOB_GAS_PER_BLOCK
+        let num_blobs = tx.blob_hashes().len();
+        if num_blobs > MAX_BLOB_NUMBER_PER_BLOCK as usize {
+            return Err(InvalidTransaction::TooManyBlobs {
+                have: num_blobs,
+                max: MAX_BLOB_NUMBER_PER_BLOCK as usize,
+            });
+        }
+
+        // verify the attached blobs against the versioned hashes
+        #[cfg(feature = "c-kzg")]
+        if let Some(sidecar) = tx.blob_sidecar() {
+            sidecar.verify(tx.blob_hashes(), cfg.kzg_settings.get())?;
+        }
+        #[cfg(not(feature = "c-kzg"))]
+        if tx.blob_sidecar().is_some() {
+            return Err(InvalidTransaction::BlobSidecarNotSupported);
+        }
+    } else {
+        // if max_fee_per_blob_gas is not set, then blob_hashes and the sidecar must be empty
+        if !tx.blob_hashes().is_empty() || tx.blob_sidecar().is_some() {
+            return Err(InvalidTransaction::BlobVersionedHashesNotSupported);
+        }
+    }
+
+    // check if EIP-7702 transaction is enabled.
+    if !cfg.is_eip_enabled(SPEC::SPEC_ID, Eip::Eip7702) && tx.authorization_list().is_some() {
+        return Err(InvalidTransaction::AuthorizationListNotSupported);
+    }
+
+    if tx.authorization_list().is_some() {
+        // Check if other fields are unset.
+        if tx.max_fee_per_blob_gas().is_some() || !tx.blob_hashes().is_empty() {
+            return Err(InvalidTransaction::AuthorizationListInvalidFields);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CancunSpec, Env};
+
+    struct DepositTx {
+        base: TxEnv,
+        mint: u128,
+        envelope: Bytes,
+    }
+
+    impl_transaction!(DepositTx, base, {
+        fn enveloped_tx(&self) -> Option<&Bytes> {
+            Some(&self.envelope)
+        }
+    });
+
+    #[test]
+    fn custom_transaction() {
+        let env = Env::default();
+        let mut tx = DepositTx {
+            base: TxEnv::default(),
+            mint: 10,
+            envelope: Bytes::from_static(&[0x7e]),
+        };
+        assert_eq!(tx.mint, 10);
+        assert_eq!(tx.enveloped_tx(), Some(&tx.envelope));
+        assert_eq!(
+            validate_tx::<CancunSpec, _>(&env.cfg, &env.block, &tx),
+            Ok(())
+        );
+
+        tx.base.chain_id = Some(env.cfg.chain_id + 1);
+        assert_eq!(
+            validate_tx::<CancunSpec, _>(&env.cfg, &env.block, &tx),
+            Err(InvalidTransaction::InvalidChainId)
+        );
+
+        assert!(!tx.is_sponsored());
+        tx.base.gas_sponsor = Some(Address::with_last_byte(1));
+        assert!(tx.is_sponsored());
+        assert_eq!(tx.gas_payer(), Address::with_last_byte(1));
+    }
+
+    #[test]
+    fn fee_errors_report_values() {
+        let mut env = Env::default();
+        env.block.basefee = U256::from(10);
+        let mut tx = TxEnv {
+            gas_price: U256::from(12),
+            gas_priority_fee: Some(U256::from(13)),
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_tx::<CancunSpec, _>(&env.cfg, &env.block, &tx),
+            Err(InvalidTransaction::PriorityFeeGreaterThanMaxFee {
+                priority_fee: Box::new(U256::from(13)),
+                max_fee: Box::new(U256::from(12)),
+            })
+        );
+
+        tx.gas_price = U256::from(9);
+        tx.gas_priority_fee = Some(U256::from(1));
+        let err = validate_tx::<CancunSpec, _>(&env.cfg, &env.block, &tx).unwrap_err();
+        assert_eq!(
+            err,
+            InvalidTransaction::GasPriceLessThanBasefee {
+                gas_price: Box::new(U256::from(9)),
+                basefee: Box::new(U256::from(10)),
+            }
+        );
+        assert_eq!(err.to_string(), "gas price (9) is less than basefee (10)");
+    }
+
+    #[test]
+    fn tx_gas_limit_cap() {
+        let mut env = Env::default();
+        env.cfg.tx_gas_limit_cap = Some(1 << 24);
+        let mut tx = TxEnv {
+            gas_limit: 1 << 24,
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_tx::<CancunSpec, _>(&env.cfg, &env.block, &tx),
+            Ok(())
+        );
+
+        tx.gas_limit += 1;
+        assert_eq!(
+            validate_tx::<CancunSpec, _>(&env.cfg, &env.block, &tx),
+            Err(InvalidTransaction::TxGasLimitGreaterThanCap {
+                gas_limit: (1 << 24) + 1,
+                cap: 1 << 24,
+            })
+        );
+    }
+
+    #[cfg(feature = "c-kzg")]
+    #[test]
+    fn blob_sidecar() {
+        use c_kzg::{Blob, KzgCommitment, KzgProof, BYTES_PER_BLOB};
+
+        let env = Env::default();
+        let settings = env.cfg.kzg_settings.get();
+        let mut bytes = vec![0; BYTES_PER_BLOB];
+        bytes[31] = 1;
+        let blob = Blob::from_bytes(&bytes).unwrap();
+        let commitment = KzgCommitment::blob_to_kzg_commitment(&blob, settings)
+            .unwrap()
+            .to_bytes();
+        let proof = KzgProof::compute_blob_kzg_proof(&blob, &commitment, settings)
+            .unwrap()
+            .to_bytes();
+        let sidecar = BlobSidecar {
+            blobs: vec![bytes.into()],
+            commitments: vec![commitment.into_inner().into()],
+            proofs: vec![proof.into_inner().into()],
+        };
+        let mut tx = TxEnv {
+            max_fee_per_blob_gas: Some(U256::from(1)),
+            blob_hashes: vec![crate::kzg_to_versioned_hash(commitment.as_slice())],
+            blob_sidecar: Some(sidecar.clone()),
+            ..Default::default()
+        };
+        let validate = |tx: &TxEnv| validate_tx::<CancunSpec, _>(&env.cfg, &env.block, tx);
+        assert_eq!(validate(&tx), Ok(()));
+
+        tx.blob_sidecar = Some(BlobSidecar {
+            proofs: sidecar.commitments.clone(),
+            ..sidecar.clone()
+        });
+        assert_eq!(validate(&tx), Err(InvalidTransaction::InvalidBlobProof));
+
+        tx.blob_sidecar = Some(BlobSidecar {
+            proofs: Vec::new(),
+            ..sidecar.clone()
+        });
+        assert_eq!(
+            validate(&tx),
+            Err(InvalidTransaction::BlobSidecarLengthMismatch)
+        );
+
+        tx.blob_sidecar = Some(sidecar);
+        tx.blob_hashes[0].0[31] ^= 1;
+        assert_eq!(
+            validate(&tx),
+            Err(InvalidTransaction::BlobCommitmentMismatch)
+        );
+    }
+}
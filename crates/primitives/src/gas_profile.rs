@@ -0,0 +1,175 @@
+//! Gas spent per opcode, contract and call depth.
+use crate::{Address, HashMap};
+use core::fmt;
+use std::{boxed::Box, vec::Vec};
+
+/// Gas spent by each opcode.
+///
+/// Calls and creations are charged without the gas given to the subcall, which the frame of the
+/// subcall is charged for.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct OpcodeGasUsage(Box<[u64; 256]>);
+
+impl Default for OpcodeGasUsage {
+    fn default() -> Self {
+        Self(Box::new([0; 256]))
+    }
+}
+
+impl fmt::Debug for OpcodeGasUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl OpcodeGasUsage {
+    /// Creates zeroed usage.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Charges gas to the opcode.
+    #[inline]
+    pub fn record(&mut self, opcode: u8, gas: u64) {
+        self.0[opcode as usize] += gas;
+    }
+
+    /// Returns the gas spent by the opcode.
+    #[inline]
+    pub fn get(&self, opcode: u8) -> u64 {
+        self.0[opcode as usize]
+    }
+
+    /// Returns the gas spent by all opcodes.
+    pub fn total(&self) -> u64 {
+        self.0.iter().sum()
+    }
+
+    /// Returns `true` if no gas was spent.
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|&gas| gas == 0)
+    }
+
+    /// Returns the opcodes that spent gas with the gas they spent, in ascending opcode order.
+    pub fn iter(&self) -> impl Iterator<Item = (u8, u64)> + '_ {
+        (0..=u8::MAX)
+            .zip(self.0.iter().copied())
+            .filter(|&(_, gas)| gas != 0)
+    }
+
+    /// Adds the usage of `other`.
+    pub fn merge(&mut self, other: &Self) {
+        for (gas, other) in self.0.iter_mut().zip(other.0.iter()) {
+            *gas += other;
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for OpcodeGasUsage {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for OpcodeGasUsage {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let usage = std::collections::BTreeMap::<u8, u64>::deserialize(deserializer)?;
+        let mut this = Self::new();
+        for (opcode, gas) in usage {
+            this.0[opcode as usize] = gas;
+        }
+        Ok(this)
+    }
+}
+
+/// Gas spent by the execution of a transaction, broken down by opcode, contract and call depth.
+///
+/// Only gas spent executing bytecode and precompiles and depositing the code of created contracts
+/// is recorded, the intrinsic gas and the refund of the transaction are not. Each frame is charged for the gas it spent itself,
+/// excluding its subcalls.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GasProfile {
+    /// Gas spent by each opcode, over all frames.
+    pub per_opcode: OpcodeGasUsage,
+    /// Gas spent by the frames executing the code of each address.
+    ///
+    /// Code run through `DELEGATECALL` is charged to the address the code was loaded from.
+    pub per_contract: HashMap<Address, u64>,
+    /// Gas spent by the frames at each call depth, starting with the transaction frame.
+    pub per_depth: Vec<u64>,
+}
+
+impl GasProfile {
+    /// Records a finished frame at `depth` that executed the code of `code_address`.
+    pub fn record(&mut self, code_address: Address, depth: usize, usage: &OpcodeGasUsage) {
+        self.per_opcode.merge(usage);
+        self.record_gas(code_address, depth, usage.total());
+    }
+
+    /// Records gas spent outside of the interpreter, e.g. by a precompile.
+    pub fn record_gas(&mut self, address: Address, depth: usize, gas: u64) {
+        *self.per_contract.entry(address).or_default() += gas;
+        if self.per_depth.len() <= depth {
+            self.per_depth.resize(depth + 1, 0);
+        }
+        self.per_depth[depth] += gas;
+    }
+
+    /// Adds the profile of `other`, e.g. of a subcall.
+    pub fn merge(&mut self, other: &Self) {
+        self.per_opcode.merge(&other.per_opcode);
+        for (address, gas) in &other.per_contract {
+            *self.per_contract.entry(*address).or_default() += gas;
+        }
+        if self.per_depth.len() < other.per_depth.len() {
+            self.per_depth.resize(other.per_depth.len(), 0);
+        }
+        for (gas, other) in self.per_depth.iter_mut().zip(&other.per_depth) {
+            *gas += other;
+        }
+    }
+
+    /// Returns the gas spent by all frames.
+    pub fn total(&self) -> u64 {
+        self.per_depth.iter().sum()
+    }
+
+    /// Returns `true` if nothing was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.per_contract.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gas_profile() {
+        let mut frame = OpcodeGasUsage::new();
+        frame.record(0x60, 3);
+        frame.record(0x60, 3);
+        frame.record(0x54, 2100);
+
+        let mut profile = GasProfile::default();
+        profile.record(Address::ZERO, 0, &frame);
+        profile.record(Address::ZERO, 2, &frame);
+        profile.record_gas(Address::with_last_byte(1), 1, 3000);
+        assert_eq!(profile.per_opcode.get(0x60), 12);
+        assert_eq!(profile.per_contract[&Address::ZERO], 2 * 2106);
+        assert_eq!(profile.per_depth, [2106, 3000, 2106]);
+        assert_eq!(profile.total(), 2 * 2106 + 3000);
+        assert_eq!(frame.iter().collect::<Vec<_>>(), [(0x54, 2100), (0x60, 6)]);
+
+        let mut parent = GasProfile::default();
+        parent.record(Address::ZERO, 0, &frame);
+        parent.merge(&profile);
+        assert_eq!(parent.per_contract[&Address::ZERO], 3 * 2106);
+        assert_eq!(parent.per_depth, [2 * 2106, 3000, 2106]);
+        assert_eq!(parent.per_opcode.get(0x54), 3 * 2100);
+    }
+}
@@ -10,19 +10,29 @@ extern crate alloc as std;
 mod bytecode;
 mod constants;
 pub mod db;
+#[cfg(feature = "deterministic")]
+pub mod deterministic;
 pub mod env;
+#[cfg(feature = "gas_profile")]
+pub mod gas_profile;
 
 #[cfg(any(feature = "c-kzg", feature = "kzg-rs"))]
 pub mod kzg;
+#[cfg(feature = "opcode_stats")]
+pub mod opcode_stats;
 pub mod precompile;
+pub mod receipt;
 pub mod result;
+#[cfg(feature = "keccak_preimages")]
+pub mod slot_provenance;
 pub mod specification;
 pub mod state;
 pub mod utilities;
 pub use alloy_eips::eip2930::{AccessList, AccessListItem};
+pub use alloy_eips::eip4895::Withdrawal;
 pub use alloy_primitives::{
-    self, address, b256, bytes, fixed_bytes, hex, hex_literal, ruint, uint, Address, Bytes,
-    FixedBytes, Log, LogData, TxKind, B256, I256, U256,
+    self, address, b256, bytes, fixed_bytes, hex, hex_literal, ruint, uint, Address, Bloom,
+    BloomInput, Bytes, FixedBytes, Log, LogData, TxKind, B256, I256, U256,
 };
 pub use bitvec;
 pub use bytecode::*;
@@ -30,7 +40,14 @@ pub use constants::*;
 pub use env::*;
 
 cfg_if::cfg_if! {
-    if #[cfg(all(not(feature = "hashbrown"), feature = "std"))] {
+    if #[cfg(feature = "deterministic")] {
+        pub use hashbrown::{hash_map, hash_set};
+        pub use deterministic::{FixedHasher, FixedState};
+        /// [`hashbrown::HashMap`] hashing with [`FixedState`].
+        pub type HashMap<K, V> = hashbrown::HashMap<K, V, FixedState>;
+        /// [`hashbrown::HashSet`] hashing with [`FixedState`].
+        pub type HashSet<T> = hashbrown::HashSet<T, FixedState>;
+    } else if #[cfg(all(not(feature = "hashbrown"), feature = "std"))] {
         pub use std::collections::{hash_map, hash_set, HashMap, HashSet};
         use hashbrown as _;
     } else {
@@ -38,10 +55,17 @@ cfg_if::cfg_if! {
     }
 }
 
+#[cfg(feature = "gas_profile")]
+pub use gas_profile::{GasProfile, OpcodeGasUsage};
 #[cfg(any(feature = "c-kzg", feature = "kzg-rs"))]
 pub use kzg::{EnvKzgSettings, KzgSettings};
+#[cfg(feature = "opcode_stats")]
+pub use opcode_stats::{OpcodeCounts, OpcodeStats, OpcodeStatsLevel};
 pub use precompile::*;
+pub use receipt::{build_receipt, logs_bloom, BloomAccumulator, Receipt};
 pub use result::*;
+#[cfg(feature = "keccak_preimages")]
+pub use slot_provenance::{slot_key_path, SlotKey, SlotKeyPath};
 pub use specification::*;
 pub use state::*;
 pub use utilities::*;
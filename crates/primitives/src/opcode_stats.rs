@@ -0,0 +1,155 @@
+//! Opcode counters recorded during execution.
+use crate::{Address, HashMap};
+use core::fmt;
+use std::boxed::Box;
+
+/// What opcode statistics are recorded, see [`CfgEnv::opcode_stats`](crate::CfgEnv::opcode_stats).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OpcodeStatsLevel {
+    /// Nothing is recorded.
+    #[default]
+    Disabled,
+    /// Opcodes executed by the whole transaction are counted.
+    Transaction,
+    /// Opcodes are counted for the whole transaction and for each executed code address.
+    Contract,
+}
+
+impl OpcodeStatsLevel {
+    /// Returns `true` if opcodes are counted.
+    #[inline]
+    pub const fn is_enabled(self) -> bool {
+        !matches!(self, Self::Disabled)
+    }
+}
+
+/// Number of executed instructions of each opcode.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct OpcodeCounts(Box<[u64; 256]>);
+
+impl Default for OpcodeCounts {
+    fn default() -> Self {
+        Self(Box::new([0; 256]))
+    }
+}
+
+impl fmt::Debug for OpcodeCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl OpcodeCounts {
+    /// Creates zeroed counters.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts one execution of the opcode.
+    #[inline]
+    pub fn increment(&mut self, opcode: u8) {
+        self.0[opcode as usize] += 1;
+    }
+
+    /// Returns the number of executions of the opcode.
+    #[inline]
+    pub fn get(&self, opcode: u8) -> u64 {
+        self.0[opcode as usize]
+    }
+
+    /// Returns the number of executed instructions.
+    pub fn total(&self) -> u64 {
+        self.0.iter().sum()
+    }
+
+    /// Returns `true` if no instruction was counted.
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|&count| count == 0)
+    }
+
+    /// Returns the executed opcodes with their counts, in ascending opcode order.
+    pub fn iter(&self) -> impl Iterator<Item = (u8, u64)> + '_ {
+        (0..=u8::MAX)
+            .zip(self.0.iter().copied())
+            .filter(|&(_, count)| count != 0)
+    }
+
+    /// Adds the counts of `other`.
+    pub fn merge(&mut self, other: &Self) {
+        for (count, other) in self.0.iter_mut().zip(other.0.iter()) {
+            *count += other;
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for OpcodeCounts {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for OpcodeCounts {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let counts = std::collections::BTreeMap::<u8, u64>::deserialize(deserializer)?;
+        let mut this = Self::new();
+        for (opcode, count) in counts {
+            this.0[opcode as usize] = count;
+        }
+        Ok(this)
+    }
+}
+
+/// Opcode statistics of a transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OpcodeStats {
+    /// Counts of the whole transaction.
+    pub total: OpcodeCounts,
+    /// Counts of each executed code address.
+    ///
+    /// Only recorded with [`OpcodeStatsLevel::Contract`]. Code run through `DELEGATECALL` is
+    /// counted for the address the code was loaded from.
+    pub per_contract: HashMap<Address, OpcodeCounts>,
+}
+
+impl OpcodeStats {
+    /// Adds the counts of a finished frame that executed the code of `code_address`.
+    ///
+    /// The counts are only recorded per contract if `code_address` is set.
+    pub fn record(&mut self, code_address: Option<Address>, counts: &OpcodeCounts) {
+        self.total.merge(counts);
+        if let Some(address) = code_address {
+            self.per_contract.entry(address).or_default().merge(counts);
+        }
+    }
+
+    /// Returns `true` if no instruction was counted.
+    pub fn is_empty(&self) -> bool {
+        self.total.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opcode_stats() {
+        let mut frame = OpcodeCounts::new();
+        frame.increment(0x60);
+        frame.increment(0x60);
+        frame.increment(0x00);
+
+        let mut stats = OpcodeStats::default();
+        stats.record(Some(Address::ZERO), &frame);
+        stats.record(None, &frame);
+        assert_eq!(stats.total.get(0x60), 4);
+        assert_eq!(stats.total.total(), 6);
+        assert_eq!(stats.per_contract[&Address::ZERO].total(), 3);
+        assert_eq!(frame.iter().collect::<Vec<_>>(), [(0x00, 1), (0x60, 2)]);
+    }
+}
@@ -26,17 +26,29 @@ impl PrecompileOutput {
 
 pub type StandardPrecompileFn = fn(&Bytes, u64) -> PrecompileResult;
 pub type EnvPrecompileFn = fn(&Bytes, u64, env: &Env) -> PrecompileResult;
+/// Returns the gas a precompile charges for the input, without executing it.
+pub type RequiredGasFn = fn(&[u8]) -> u64;
 
 /// Stateful precompile trait. It is used to create
 /// a arc precompile Precompile::Stateful.
 pub trait StatefulPrecompile: Sync + Send {
     fn call(&self, bytes: &Bytes, gas_limit: u64, env: &Env) -> PrecompileResult;
+
+    /// Returns the gas the precompile charges for the input, see [`Precompile::required_gas`].
+    fn required_gas(&self, _input: &[u8]) -> Option<u64> {
+        None
+    }
 }
 
 /// Mutable stateful precompile trait. It is used to create
 /// a boxed precompile in Precompile::StatefulMut.
 pub trait StatefulPrecompileMut: DynClone + Send + Sync {
     fn call_mut(&mut self, bytes: &Bytes, gas_limit: u64, env: &Env) -> PrecompileResult;
+
+    /// Returns the gas the precompile charges for the input, see [`Precompile::required_gas`].
+    fn required_gas(&self, _input: &[u8]) -> Option<u64> {
+        None
+    }
 }
 
 dyn_clone::clone_trait_object!(StatefulPrecompileMut);
@@ -54,6 +66,10 @@ pub enum Precompile {
     Standard(StandardPrecompileFn),
     /// Similar to Standard but takes reference to environment.
     Env(EnvPrecompileFn),
+    /// Standard precompile with a function that calculates its gas cost.
+    StandardWithGas(StandardPrecompileFn, RequiredGasFn),
+    /// Env precompile with a function that calculates its gas cost.
+    EnvWithGas(EnvPrecompileFn, RequiredGasFn),
     /// Stateful precompile that is Arc over [`StatefulPrecompile`] trait.
     /// It takes a reference to input, gas limit and environment.
     Stateful(StatefulPrecompileArc),
@@ -91,6 +107,8 @@ impl fmt::Debug for Precompile {
         match self {
             Precompile::Standard(_) => f.write_str("Standard"),
             Precompile::Env(_) => f.write_str("Env"),
+            Precompile::StandardWithGas(..) => f.write_str("StandardWithGas"),
+            Precompile::EnvWithGas(..) => f.write_str("EnvWithGas"),
             Precompile::Stateful(_) => f.write_str("Stateful"),
             Precompile::StatefulMut(_) => f.write_str("StatefulMut"),
         }
@@ -113,6 +131,8 @@ impl Precompile {
         match *self {
             Precompile::Standard(p) => p(bytes, gas_limit),
             Precompile::Env(p) => p(bytes, gas_limit, env),
+            Precompile::StandardWithGas(p, _) => p(bytes, gas_limit),
+            Precompile::EnvWithGas(p, _) => p(bytes, gas_limit, env),
             Precompile::Stateful(ref p) => p.call(bytes, gas_limit, env),
             Precompile::StatefulMut(ref mut p) => p.call_mut(bytes, gas_limit, env),
         }
@@ -125,12 +145,29 @@ impl Precompile {
         match *self {
             Precompile::Standard(p) => p(bytes, gas_limit),
             Precompile::Env(p) => p(bytes, gas_limit, env),
+            Precompile::StandardWithGas(p, _) => p(bytes, gas_limit),
+            Precompile::EnvWithGas(p, _) => p(bytes, gas_limit, env),
             Precompile::Stateful(ref p) => p.call(bytes, gas_limit, env),
             Precompile::StatefulMut(_) => Err(PrecompileErrors::Fatal {
                 msg: "call_ref on mutable stateful precompile".into(),
             }),
         }
     }
+
+    /// Returns the gas the precompile charges for the input, without executing it.
+    ///
+    /// The result is only meaningful for valid inputs, as a failing precompile consumes all the
+    /// gas given to it. Returns `None` if the precompile does not expose its gas schedule.
+    pub fn required_gas(&self, input: &[u8]) -> Option<u64> {
+        match *self {
+            Precompile::Standard(_) | Precompile::Env(_) => None,
+            Precompile::StandardWithGas(_, gas) | Precompile::EnvWithGas(_, gas) => {
+                Some(gas(input))
+            }
+            Precompile::Stateful(ref p) => p.required_gas(input),
+            Precompile::StatefulMut(ref p) => p.required_gas(input),
+        }
+    }
 }
 
 /// Precompile errors.
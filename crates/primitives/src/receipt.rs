@@ -0,0 +1,164 @@
+//! Transaction receipts.
+use crate::{Bloom, ExecutionResult, Log};
+use std::vec::Vec;
+
+/// EIP-658 receipt of an executed transaction, with its logs bloom.
+///
+/// Fields match `alloy_consensus::ReceiptWithBloom` and `alloy_consensus::ReceiptEnvelope`, where
+/// `tx_type` is the EIP-2718 transaction type.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Receipt {
+    /// EIP-2718 transaction type, `0` for legacy transactions.
+    pub tx_type: u8,
+    /// Whether the transaction succeeded.
+    pub success: bool,
+    /// Gas used in the block up to and including this transaction.
+    pub cumulative_gas_used: u64,
+    /// Logs emitted by the transaction.
+    pub logs: Vec<Log>,
+    /// Bloom filter of the logs.
+    pub logs_bloom: Bloom,
+}
+
+impl Receipt {
+    /// Builds the receipt of a transaction.
+    ///
+    /// `cumulative_gas_used` must include the gas used by this transaction.
+    pub fn new(result: &ExecutionResult, cumulative_gas_used: u64, tx_type: u8) -> Self {
+        let logs = result.logs().to_vec();
+        Self {
+            tx_type,
+            success: result.is_success(),
+            cumulative_gas_used,
+            logs_bloom: logs_bloom(&logs),
+            logs,
+        }
+    }
+}
+
+/// Builds the receipt of a transaction. See [`Receipt::new`].
+#[inline]
+pub fn build_receipt(result: &ExecutionResult, cumulative_gas_used: u64, tx_type: u8) -> Receipt {
+    Receipt::new(result, cumulative_gas_used, tx_type)
+}
+
+/// Calculates the bloom filter of the logs.
+///
+/// The bloom of a block is the union of the blooms of its receipts.
+pub fn logs_bloom<'a>(logs: impl IntoIterator<Item = &'a Log>) -> Bloom {
+    logs.into_iter().collect::<BloomAccumulator>().into_bloom()
+}
+
+/// Logs bloom that is built incrementally while executing a block.
+///
+/// Logs can be added as they are emitted, and receipts that already carry their bloom are merged
+/// without hashing their logs again.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BloomAccumulator {
+    bloom: Bloom,
+}
+
+impl BloomAccumulator {
+    /// Creates an empty accumulator.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { bloom: Bloom::ZERO }
+    }
+
+    /// Adds the log address and topics.
+    #[inline]
+    pub fn accrue_log(&mut self, log: &Log) {
+        self.bloom.accrue_log(log);
+    }
+
+    /// Merges an already calculated bloom, e.g. the bloom of a receipt.
+    #[inline]
+    pub fn accrue_bloom(&mut self, bloom: &Bloom) {
+        self.bloom.accrue_bloom(bloom);
+    }
+
+    /// Merges the bloom of the receipt.
+    #[inline]
+    pub fn accrue_receipt(&mut self, receipt: &Receipt) {
+        self.accrue_bloom(&receipt.logs_bloom);
+    }
+
+    /// Returns the accumulated bloom.
+    #[inline]
+    pub const fn bloom(&self) -> &Bloom {
+        &self.bloom
+    }
+
+    /// Consumes the accumulator and returns the accumulated bloom.
+    #[inline]
+    pub const fn into_bloom(self) -> Bloom {
+        self.bloom
+    }
+}
+
+impl<'a> Extend<&'a Log> for BloomAccumulator {
+    fn extend<T: IntoIterator<Item = &'a Log>>(&mut self, iter: T) {
+        for log in iter {
+            self.accrue_log(log);
+        }
+    }
+}
+
+impl<'a> FromIterator<&'a Log> for BloomAccumulator {
+    fn from_iter<T: IntoIterator<Item = &'a Log>>(iter: T) -> Self {
+        let mut accumulator = Self::new();
+        accumulator.extend(iter);
+        accumulator
+    }
+}
+
+impl From<BloomAccumulator> for Bloom {
+    #[inline]
+    fn from(accumulator: BloomAccumulator) -> Self {
+        accumulator.bloom
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{address, b256, bytes, BloomInput, Output, SuccessReason};
+
+    #[test]
+    fn receipt_bloom() {
+        let log = Log::new_unchecked(
+            address!("22341ae42d6dd7384bc8584e50419ea3ac75b83f"),
+            vec![b256!(
+                "04491edcd115127caedbd478e2e7895ed80c7847e903431f94f9cfa579cad47f"
+            )],
+            bytes!(""),
+        );
+        let result = ExecutionResult::Success {
+            reason: SuccessReason::Stop,
+            gas_used: 21_000,
+            gas_refunded: 0,
+            logs: vec![log.clone()],
+            output: Output::Call(bytes!("")),
+        };
+
+        let receipt = build_receipt(&result, 42_000, 2);
+        assert!(receipt.success);
+        assert_eq!(receipt.tx_type, 2);
+        assert_eq!(receipt.cumulative_gas_used, 42_000);
+        assert_eq!(receipt.logs, [log.clone()]);
+        let bloom = receipt.logs_bloom;
+        assert!(bloom.contains_input(BloomInput::Raw(&log.address[..])));
+        assert!(bloom.contains_input(BloomInput::Raw(&log.topics()[0][..])));
+        assert_eq!(logs_bloom([]), Bloom::ZERO);
+
+        // Folding logs one by one and merging receipts give the same block bloom.
+        let mut from_logs = BloomAccumulator::new();
+        from_logs.accrue_log(&log);
+        let mut from_receipts = BloomAccumulator::new();
+        from_receipts.accrue_receipt(&receipt);
+        from_receipts.accrue_receipt(&Receipt::default());
+        assert_eq!(from_logs, from_receipts);
+        assert_eq!(Bloom::from(from_logs), bloom);
+    }
+}
@@ -15,6 +15,93 @@ pub struct ResultAndState {
     pub result: ExecutionResult,
     /// State that got updated
     pub state: EvmState,
+    /// [EIP-4844] blob gas used by the transaction.
+    ///
+    /// [EIP-4844]: https://eips.ethereum.org/EIPS/eip-4844
+    pub blob_gas_used: u64,
+    /// [EIP-4844] blob fee paid by the transaction, `blob_gas_used * blob_gasprice`.
+    ///
+    /// [EIP-4844]: https://eips.ethereum.org/EIPS/eip-4844
+    pub blob_fee: U256,
+    /// Preimages of all KECCAK256 hashes computed during execution, keyed by hash.
+    ///
+    /// Only filled when [`CfgEnv::record_keccak_preimages`](crate::CfgEnv::record_keccak_preimages) is set.
+    #[cfg(feature = "keccak_preimages")]
+    pub keccak_preimages: crate::HashMap<crate::B256, Bytes>,
+    /// Opcodes executed by the transaction.
+    ///
+    /// Only filled when [`CfgEnv::opcode_stats`](crate::CfgEnv::opcode_stats) is enabled.
+    #[cfg(feature = "opcode_stats")]
+    pub opcode_stats: crate::OpcodeStats,
+    /// Gas spent by the transaction per opcode, contract and call depth, taken from the result
+    /// of the transaction frame.
+    ///
+    /// Only filled when [`CfgEnv::gas_profile`](crate::CfgEnv::gas_profile) is set.
+    #[cfg(feature = "gas_profile")]
+    pub gas_profile: crate::GasProfile,
+    /// Set if the transaction was executed for an impersonated sender.
+    ///
+    /// See [`CfgEnv::impersonate_caller`](crate::CfgEnv::impersonate_caller).
+    pub impersonation: Option<Impersonation>,
+    /// Logs of the result with the frame that emitted them, in the order of
+    /// [`ExecutionResult::logs`].
+    #[cfg(feature = "log_origins")]
+    pub logs_with_origins: Vec<LogWithOrigin>,
+    /// Logs discarded by reverted frames, in the order they were reverted.
+    ///
+    /// Only filled when the EVM is built with `EvmBuilder::with_record_reverted_logs`.
+    pub reverted_logs: Vec<RevertedLog>,
+}
+
+/// Log with the frame that emitted it, see [`ResultAndState::logs_with_origins`].
+#[cfg(feature = "log_origins")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogWithOrigin {
+    /// The emitted log.
+    pub log: Log,
+    /// Frame that emitted the log.
+    pub origin: LogOrigin,
+}
+
+/// Frame that emitted a log.
+#[cfg(feature = "log_origins")]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogOrigin {
+    /// Index of the log in emission order within the transaction.
+    ///
+    /// Logs of reverted frames are counted too, so indices of the kept logs can have gaps.
+    pub index: usize,
+    /// Depth of the emitting frame, the first frame of the transaction has depth 1.
+    pub depth: usize,
+    /// Target addresses of the frames from the first frame to the emitting one.
+    pub frames: Vec<Address>,
+}
+
+/// Log that was emitted inside a frame that got reverted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RevertedLog {
+    /// The reverted log.
+    pub log: Log,
+    /// Depth of the frame whose revert discarded the log.
+    pub depth: usize,
+    /// Index the log had in the transaction logs before it was reverted.
+    ///
+    /// Logs reverted by the same frame have consecutive indices starting at the revert point.
+    pub log_index: usize,
+}
+
+/// Marker of a transaction executed for an impersonated sender.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Impersonation {
+    /// The impersonated sender.
+    pub caller: Address,
+    /// Balance credited to the sender to cover the maximum gas fee and the value of the
+    /// transaction. It is included in the balance of the sender in the state.
+    pub minted: U256,
 }
 
 /// Result of a transaction execution.
@@ -150,6 +237,12 @@ pub enum EVMError<DBError> {
     Custom(String),
     /// Precompile error.
     Precompile(String),
+    /// Execution ran past the timeout set in `CfgEnv`.
+    #[cfg(feature = "execution_timeout")]
+    Timeout,
+    /// Execution ended with a result that handlers should never return, e.g. an internal
+    /// interpreter flag. Indicates a bug in a handler or in the interpreter.
+    Internal(String),
 }
 
 impl<DBError> EVMError<DBError> {
@@ -164,6 +257,9 @@ impl<DBError> EVMError<DBError> {
             Self::Database(e) => EVMError::Database(op(e)),
             Self::Precompile(e) => EVMError::Precompile(e),
             Self::Custom(e) => EVMError::Custom(e),
+            #[cfg(feature = "execution_timeout")]
+            Self::Timeout => EVMError::Timeout,
+            Self::Internal(e) => EVMError::Internal(e),
         }
     }
 }
@@ -175,7 +271,9 @@ impl<DBError: std::error::Error + 'static> std::error::Error for EVMError<DBErro
             Self::Transaction(e) => Some(e),
             Self::Header(e) => Some(e),
             Self::Database(e) => Some(e),
-            Self::Precompile(_) | Self::Custom(_) => None,
+            Self::Precompile(_) | Self::Custom(_) | Self::Internal(_) => None,
+            #[cfg(feature = "execution_timeout")]
+            Self::Timeout => None,
         }
     }
 }
@@ -187,6 +285,9 @@ impl<DBError: fmt::Display> fmt::Display for EVMError<DBError> {
             Self::Header(e) => write!(f, "header validation error: {e}"),
             Self::Database(e) => write!(f, "database error: {e}"),
             Self::Precompile(e) | Self::Custom(e) => f.write_str(e),
+            #[cfg(feature = "execution_timeout")]
+            Self::Timeout => f.write_str("execution timed out"),
+            Self::Internal(e) => write!(f, "internal error: {e}"),
         }
     }
 }
@@ -251,17 +352,36 @@ pub enum InvalidTransaction {
     /// - `gas_priority_fee`: The extra amount a user is willing to give directly to the miner, often referred to as the "tip".
     ///
     /// Provided `gas_priority_fee` exceeds the total `gas_max_fee`.
-    PriorityFeeGreaterThanMaxFee,
+    PriorityFeeGreaterThanMaxFee {
+        priority_fee: Box<U256>,
+        max_fee: Box<U256>,
+    },
     /// EIP-1559: `gas_price` is less than `basefee`.
-    GasPriceLessThanBasefee,
+    ///
+    /// `gas_price` is the effective gas price of the transaction at the `basefee`.
+    GasPriceLessThanBasefee {
+        gas_price: Box<U256>,
+        basefee: Box<U256>,
+    },
     /// `gas_limit` in the tx is bigger than `block_gas_limit`.
-    CallerGasLimitMoreThanBlock,
+    CallerGasLimitMoreThanBlock {
+        gas_limit: u64,
+        block_gas_limit: Box<U256>,
+    },
+    /// `gas_limit` in the tx is bigger than [`CfgEnv::tx_gas_limit_cap`](crate::CfgEnv::tx_gas_limit_cap).
+    TxGasLimitGreaterThanCap {
+        gas_limit: u64,
+        cap: u64,
+    },
     /// Initial gas for a Call is bigger than `gas_limit`.
     ///
     /// Initial gas for a Call contains:
     /// - initial stipend gas
     /// - gas for access list and input data
-    CallGasCostMoreThanGasLimit,
+    CallGasCostMoreThanGasLimit {
+        initial_gas: u64,
+        gas_limit: u64,
+    },
     /// EIP-3607 Reject transactions from senders with deployed code
     RejectCallerWithCode,
     /// Transaction account does not have enough amount of ether to cover transferred value and gas_limit*gas_price.
@@ -287,12 +407,17 @@ pub enum InvalidTransaction {
     InvalidChainId,
     /// Access list is not supported for blocks before the Berlin hardfork.
     AccessListNotSupported,
+    /// `gas_priority_fee` is not supported on chains without EIP-1559.
+    PriorityFeeNotSupported,
     /// `max_fee_per_blob_gas` is not supported for blocks before the Cancun hardfork.
     MaxFeePerBlobGasNotSupported,
     /// `blob_hashes`/`blob_versioned_hashes` is not supported for blocks before the Cancun hardfork.
     BlobVersionedHashesNotSupported,
     /// Block `blob_gas_price` is greater than tx-specified `max_fee_per_blob_gas` after Cancun.
-    BlobGasPriceGreaterThanMax,
+    BlobGasPriceGreaterThanMax {
+        blob_gasprice: u128,
+        max_fee_per_blob_gas: Box<U256>,
+    },
     /// There should be at least one blob in Blob transaction.
     EmptyBlobs,
     /// Blob transaction can't be a create transaction.
@@ -305,6 +430,14 @@ pub enum InvalidTransaction {
     },
     /// Blob transaction contains a versioned hash with an incorrect version
     BlobVersionNotSupported,
+    /// The attached blob sidecar doesn't have one blob, commitment and proof per versioned hash.
+    BlobSidecarLengthMismatch,
+    /// A commitment of the attached blob sidecar doesn't match its versioned hash.
+    BlobCommitmentMismatch,
+    /// The attached blobs are malformed or their KZG proofs are invalid.
+    InvalidBlobProof,
+    /// Verification of an attached blob sidecar requires the `c-kzg` feature.
+    BlobSidecarNotSupported,
     /// EOF crate should have `to` address
     EofCrateShouldHaveToAddress,
     /// EIP-7702 is not enabled.
@@ -338,17 +471,44 @@ impl fmt::Display for OptimismInvalidTransaction {
 impl fmt::Display for InvalidTransaction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::PriorityFeeGreaterThanMaxFee => {
-                write!(f, "priority fee is greater than max fee")
+            Self::PriorityFeeGreaterThanMaxFee {
+                priority_fee,
+                max_fee,
+            } => {
+                write!(
+                    f,
+                    "priority fee ({priority_fee}) is greater than max fee ({max_fee})"
+                )
             }
-            Self::GasPriceLessThanBasefee => {
-                write!(f, "gas price is less than basefee")
+            Self::GasPriceLessThanBasefee { gas_price, basefee } => {
+                write!(
+                    f,
+                    "gas price ({gas_price}) is less than basefee ({basefee})"
+                )
             }
-            Self::CallerGasLimitMoreThanBlock => {
-                write!(f, "caller gas limit exceeds the block gas limit")
+            Self::CallerGasLimitMoreThanBlock {
+                gas_limit,
+                block_gas_limit,
+            } => {
+                write!(
+                    f,
+                    "caller gas limit ({gas_limit}) exceeds the block gas limit ({block_gas_limit})"
+                )
             }
-            Self::CallGasCostMoreThanGasLimit => {
-                write!(f, "call gas cost exceeds the gas limit")
+            Self::TxGasLimitGreaterThanCap { gas_limit, cap } => {
+                write!(
+                    f,
+                    "gas limit {gas_limit} exceeds the transaction gas limit cap {cap}"
+                )
+            }
+            Self::CallGasCostMoreThanGasLimit {
+                initial_gas,
+                gas_limit,
+            } => {
+                write!(
+                    f,
+                    "call gas cost ({initial_gas}) exceeds the gas limit ({gas_limit})"
+                )
             }
             Self::RejectCallerWithCode => {
                 write!(f, "reject transactions from senders with deployed code")
@@ -373,14 +533,21 @@ impl fmt::Display for InvalidTransaction {
             }
             Self::InvalidChainId => write!(f, "invalid chain ID"),
             Self::AccessListNotSupported => write!(f, "access list not supported"),
+            Self::PriorityFeeNotSupported => write!(f, "priority fee not supported"),
             Self::MaxFeePerBlobGasNotSupported => {
                 write!(f, "max fee per blob gas not supported")
             }
             Self::BlobVersionedHashesNotSupported => {
                 write!(f, "blob versioned hashes not supported")
             }
-            Self::BlobGasPriceGreaterThanMax => {
-                write!(f, "blob gas price is greater than max fee per blob gas")
+            Self::BlobGasPriceGreaterThanMax {
+                blob_gasprice,
+                max_fee_per_blob_gas,
+            } => {
+                write!(
+                    f,
+                    "blob gas price ({blob_gasprice}) is greater than max fee per blob gas ({max_fee_per_blob_gas})"
+                )
             }
             Self::EmptyBlobs => write!(f, "empty blobs"),
             Self::BlobCreateTransaction => write!(f, "blob create transaction"),
@@ -388,6 +555,14 @@ impl fmt::Display for InvalidTransaction {
                 write!(f, "too many blobs, have {have}, max {max}")
             }
             Self::BlobVersionNotSupported => write!(f, "blob version not supported"),
+            Self::BlobSidecarLengthMismatch => {
+                write!(f, "blob sidecar length doesn't match the versioned hashes")
+            }
+            Self::BlobCommitmentMismatch => {
+                write!(f, "blob commitment doesn't match its versioned hash")
+            }
+            Self::InvalidBlobProof => write!(f, "invalid blob KZG proof"),
+            Self::BlobSidecarNotSupported => write!(f, "blob sidecar verification not supported"),
             Self::EofCrateShouldHaveToAddress => write!(f, "EOF crate should have `to` address"),
             Self::AuthorizationListNotSupported => write!(f, "authorization list not supported"),
             Self::AuthorizationListInvalidFields => {
@@ -469,18 +644,41 @@ pub enum HaltReason {
     EOFFunctionStackOverflow,
     /// Check for target address validity is only done inside subcall.
     InvalidEXTCALLTarget,
+    /// The memory limit of the EVM was exceeded, see `CfgEnv::memory_limit`. Not an out of gas
+    /// error, the gas limit was not reached.
+    MemoryLimitExceeded,
+    /// `RETURNDATACOPY` read past the end of the return data buffer.
+    ReturnDataOutOfBounds,
+    /// The journal grew past the limit set in `CfgEnv::journal_limit`.
+    JournalLimitExceeded,
 
     /* Optimism errors */
     #[cfg(feature = "optimism")]
     FailedDeposit,
 }
 
+impl HaltReason {
+    /// Returns `true` if the halt was caused by the exhaustion of a resource: gas, memory, return
+    /// data, call depth or journal entries.
+    pub const fn is_resource_exhaustion(&self) -> bool {
+        matches!(
+            self,
+            Self::OutOfGas(_)
+                | Self::MemoryLimitExceeded
+                | Self::ReturnDataOutOfBounds
+                | Self::CallTooDeep
+                | Self::EOFFunctionStackOverflow
+                | Self::JournalLimitExceeded
+        )
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OutOfGasError {
     // Basic OOG error
     Basic,
-    // Tried to expand past REVM limit
+    // Tried to expand past REVM limit. No longer returned, see [`HaltReason::MemoryLimitExceeded`].
     MemoryLimit,
     // Basic OOG error from memory expansion
     Memory,
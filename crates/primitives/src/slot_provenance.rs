@@ -0,0 +1,183 @@
+//! Reconstruction of storage slot key paths from recorded KECCAK256 preimages.
+//!
+//! Solidity places mapping values at `keccak256(key . base_slot)` and dynamic array elements at
+//! `keccak256(base_slot) + index`. With the preimages recorded during execution these hashes can
+//! be unwound back to the declared slot, e.g. `3[0x…abc]` for `balances[0xabc]` at slot 3.
+use crate::{Address, Bytes, EvmState, HashMap, ResultAndState, B256, U256};
+use core::fmt;
+use std::vec::Vec;
+
+/// Maximum number of nested keccak levels that are unwound.
+pub const MAX_KEY_PATH_DEPTH: usize = 16;
+
+/// Maximum distance of a slot from a known hash to be considered a field or element of it.
+pub const MAX_SLOT_OFFSET: u64 = 1 << 16;
+
+/// Single step of a [`SlotKeyPath`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SlotKey {
+    /// Mapping lookup, slot is `keccak256(key . base)`.
+    Mapping(Bytes),
+    /// Start of dynamic array (or long `bytes`/`string`) data, slot is `keccak256(base)`.
+    ArrayData,
+    /// Offset added to the hashed slot, either an array index or a struct field.
+    Offset(U256),
+}
+
+/// Storage slot expressed as a declared root slot followed by mapping keys and offsets.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SlotKeyPath {
+    /// Slot that is not derived from any known preimage.
+    pub root: U256,
+    /// Keys applied to the root slot, outermost first.
+    pub path: Vec<SlotKey>,
+}
+
+impl SlotKeyPath {
+    /// Returns `true` if the slot was not derived from any known preimage.
+    #[inline]
+    pub fn is_root(&self) -> bool {
+        self.path.is_empty()
+    }
+}
+
+impl fmt::Display for SlotKeyPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.root)?;
+        for key in &self.path {
+            match key {
+                SlotKey::Mapping(key) => write!(f, "[{key}]")?,
+                SlotKey::ArrayData => f.write_str("[]")?,
+                SlotKey::Offset(offset) => write!(f, "+{offset}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reconstructs the key path of `slot` using the given preimages.
+pub fn slot_key_path(slot: U256, preimages: &HashMap<B256, Bytes>) -> SlotKeyPath {
+    let mut path = Vec::new();
+    let mut current = slot;
+    for _ in 0..MAX_KEY_PATH_DEPTH {
+        let Some((hash, offset)) = find_hashed_base(current, preimages) else {
+            break;
+        };
+        if offset != U256::ZERO {
+            path.push(SlotKey::Offset(offset));
+        }
+        let input = &preimages[&hash];
+        if input.len() == 32 {
+            path.push(SlotKey::ArrayData);
+            current = U256::from_be_slice(input);
+        } else {
+            let (key, base) = input.split_at(input.len() - 32);
+            path.push(SlotKey::Mapping(Bytes::copy_from_slice(key)));
+            current = U256::from_be_slice(base);
+        }
+    }
+    path.reverse();
+    SlotKeyPath {
+        root: current,
+        path,
+    }
+}
+
+/// Finds the known hash `slot` was derived from, returning it with the offset from it.
+///
+/// Exact matches are preferred, otherwise the closest hash below `slot` within
+/// [`MAX_SLOT_OFFSET`] is used.
+fn find_hashed_base(slot: U256, preimages: &HashMap<B256, Bytes>) -> Option<(B256, U256)> {
+    let key = B256::from(slot);
+    if preimages.get(&key).is_some_and(|input| input.len() >= 32) {
+        return Some((key, U256::ZERO));
+    }
+    preimages
+        .iter()
+        .filter(|(_, input)| input.len() >= 32)
+        .filter_map(|(hash, _)| {
+            let offset = slot.checked_sub(U256::from_be_bytes(hash.0))?;
+            (offset <= U256::from(MAX_SLOT_OFFSET)).then_some((*hash, offset))
+        })
+        .min_by_key(|(_, offset)| *offset)
+}
+
+/// Returns the key paths of all changed storage slots in `state` that are derived from a known
+/// preimage.
+pub fn state_slot_provenance(
+    state: &EvmState,
+    preimages: &HashMap<B256, Bytes>,
+) -> HashMap<Address, HashMap<U256, SlotKeyPath>> {
+    state
+        .iter()
+        .filter_map(|(address, account)| {
+            let slots: HashMap<_, _> = account
+                .changed_storage_slots()
+                .map(|(slot, _)| (*slot, slot_key_path(*slot, preimages)))
+                .filter(|(_, path)| !path.is_root())
+                .collect();
+            (!slots.is_empty()).then_some((*address, slots))
+        })
+        .collect()
+}
+
+impl ResultAndState {
+    /// Annotates changed storage slots with their reconstructed key path.
+    ///
+    /// See [`state_slot_provenance`].
+    pub fn slot_provenance(&self) -> HashMap<Address, HashMap<U256, SlotKeyPath>> {
+        state_slot_provenance(&self.state, &self.keccak_preimages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keccak256;
+
+    fn record(preimages: &mut HashMap<B256, Bytes>, input: Vec<u8>) -> U256 {
+        let hash = keccak256(&input);
+        preimages.insert(hash, input.into());
+        hash.into()
+    }
+
+    #[test]
+    fn nested_mapping_and_array() {
+        let mut preimages = HashMap::default();
+        let owner = B256::with_last_byte(0xab);
+        let spender = B256::with_last_byte(0xcd);
+
+        // allowances[owner][spender] at slot 1.
+        let inner = record(
+            &mut preimages,
+            [owner.0, U256::from(1).to_be_bytes::<32>()].concat(),
+        );
+        let slot = record(
+            &mut preimages,
+            [spender.0, inner.to_be_bytes::<32>()].concat(),
+        );
+        let path = slot_key_path(slot, &preimages);
+        assert_eq!(path.root, U256::from(1));
+        assert_eq!(
+            path.path,
+            vec![
+                SlotKey::Mapping(owner.into()),
+                SlotKey::Mapping(spender.into())
+            ]
+        );
+
+        // items[5] of dynamic array at slot 2.
+        let data = record(&mut preimages, U256::from(2).to_be_bytes_vec());
+        let path = slot_key_path(data + U256::from(5), &preimages);
+        assert_eq!(path.root, U256::from(2));
+        assert_eq!(
+            path.path,
+            vec![SlotKey::ArrayData, SlotKey::Offset(U256::from(5))]
+        );
+        assert_eq!(path.to_string(), "2[]+5");
+
+        assert!(slot_key_path(U256::from(7), &preimages).is_root());
+    }
+}
@@ -30,6 +30,7 @@ pub enum SpecId {
     CANCUN = 17,          // Cancun                 19426587 (Timestamp: 1710338135)
     PRAGUE = 18,          // Prague                 TBD
     PRAGUE_EOF = 19,      // Prague+EOF             TBD
+    OSAKA = 20,           // Osaka                  TBD
     #[default]
     LATEST = u8::MAX,
 }
@@ -68,6 +69,7 @@ pub enum SpecId {
     GRANITE = 23,
     PRAGUE = 24,
     PRAGUE_EOF = 25,
+    OSAKA = 26,
     #[default]
     LATEST = u8::MAX,
 }
@@ -111,6 +113,7 @@ impl From<&str> for SpecId {
             "Cancun" => Self::CANCUN,
             "Prague" => Self::PRAGUE,
             "PragueEOF" => Self::PRAGUE_EOF,
+            "Osaka" => Self::OSAKA,
             #[cfg(feature = "optimism")]
             "Bedrock" => SpecId::BEDROCK,
             #[cfg(feature = "optimism")]
@@ -151,6 +154,7 @@ impl From<SpecId> for &'static str {
             SpecId::CANCUN => "Cancun",
             SpecId::PRAGUE => "Prague",
             SpecId::PRAGUE_EOF => "PragueEOF",
+            SpecId::OSAKA => "Osaka",
             #[cfg(feature = "optimism")]
             SpecId::BEDROCK => "Bedrock",
             #[cfg(feature = "optimism")]
@@ -168,6 +172,111 @@ impl From<SpecId> for &'static str {
     }
 }
 
+/// EIPs that can be enabled ahead of their fork through [`EipSet`].
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, enumn::N)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Eip {
+    /// EIP-2537: BLS12-381 curve operation precompiles.
+    Eip2537 = 0,
+    /// EIP-2935: Serve historical block hashes from state.
+    Eip2935 = 1,
+    /// EIP-7702: Set EOA account code.
+    Eip7702 = 2,
+    /// EIP-7692: EVM Object Format (EOF) meta EIP.
+    Eip7692 = 3,
+}
+
+impl Eip {
+    /// Returns the first spec in which this EIP is enabled by default.
+    #[inline]
+    pub const fn activation(self) -> SpecId {
+        match self {
+            Self::Eip2537 | Self::Eip2935 | Self::Eip7702 => SpecId::PRAGUE,
+            Self::Eip7692 => SpecId::PRAGUE_EOF,
+        }
+    }
+
+    /// Returns `true` if this EIP is enabled by default in the given spec.
+    #[inline]
+    pub const fn is_active_in(self, spec_id: SpecId) -> bool {
+        spec_id.is_enabled_in(self.activation())
+    }
+}
+
+/// Set of [`Eip`]s.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EipSet(u64);
+
+impl EipSet {
+    /// Returns an empty set.
+    #[inline]
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Returns the set with the given EIP added.
+    #[inline]
+    pub const fn with(self, eip: Eip) -> Self {
+        Self(self.0 | Self::bit(eip))
+    }
+
+    /// Adds the EIP to the set, returning `true` if it was not present.
+    #[inline]
+    pub fn insert(&mut self, eip: Eip) -> bool {
+        let present = self.contains(eip);
+        self.0 |= Self::bit(eip);
+        !present
+    }
+
+    /// Removes the EIP from the set, returning `true` if it was present.
+    #[inline]
+    pub fn remove(&mut self, eip: Eip) -> bool {
+        let present = self.contains(eip);
+        self.0 &= !Self::bit(eip);
+        present
+    }
+
+    /// Returns `true` if the set contains the EIP.
+    #[inline]
+    pub const fn contains(&self, eip: Eip) -> bool {
+        self.0 & Self::bit(eip) != 0
+    }
+
+    /// Returns `true` if the set is empty.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline]
+    const fn bit(eip: Eip) -> u64 {
+        1 << eip as u8
+    }
+
+    /// Returns an iterator over the EIPs in the set.
+    pub fn iter(&self) -> impl Iterator<Item = Eip> + '_ {
+        (0..u64::BITS as u8)
+            .filter(|bit| self.0 & (1 << bit) != 0)
+            .filter_map(Eip::n)
+    }
+}
+
+impl FromIterator<Eip> for EipSet {
+    fn from_iter<T: IntoIterator<Item = Eip>>(iter: T) -> Self {
+        iter.into_iter().fold(Self::new(), Self::with)
+    }
+}
+
+impl Extend<Eip> for EipSet {
+    fn extend<T: IntoIterator<Item = Eip>>(&mut self, iter: T) {
+        for eip in iter {
+            self.insert(eip);
+        }
+    }
+}
+
 pub trait Spec: Sized + 'static {
     /// The specification ID.
     const SPEC_ID: SpecId;
@@ -179,6 +288,36 @@ pub trait Spec: Sized + 'static {
     }
 }
 
+/// Selects the specification used by an instruction.
+///
+/// Every [`Spec`] selects itself at compile time, [`RuntimeSpec`] defers to the spec that is
+/// known only at runtime.
+pub trait SpecSelector: 'static {
+    /// Returns the selected specification ID, `runtime` is the spec of the running EVM.
+    fn resolve(runtime: SpecId) -> SpecId;
+}
+
+impl<S: Spec> SpecSelector for S {
+    #[inline(always)]
+    fn resolve(_runtime: SpecId) -> SpecId {
+        S::SPEC_ID
+    }
+}
+
+/// [`SpecSelector`] that uses the spec of the running EVM.
+///
+/// Instructions instantiated with it are shared by all specs, trading constant folded spec
+/// checks for one instantiation instead of one per spec.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RuntimeSpec;
+
+impl SpecSelector for RuntimeSpec {
+    #[inline(always)]
+    fn resolve(runtime: SpecId) -> SpecId {
+        runtime
+    }
+}
+
 macro_rules! spec {
     ($spec_id:ident, $spec_name:ident) => {
         #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -210,6 +349,7 @@ spec!(SHANGHAI, ShanghaiSpec);
 spec!(CANCUN, CancunSpec);
 spec!(PRAGUE, PragueSpec);
 spec!(PRAGUE_EOF, PragueEofSpec);
+spec!(OSAKA, OsakaSpec);
 
 spec!(LATEST, LatestSpec);
 
@@ -294,6 +434,10 @@ macro_rules! spec_to_generic {
                 use $crate::PragueEofSpec as SPEC;
                 $e
             }
+            $crate::SpecId::OSAKA => {
+                use $crate::OsakaSpec as SPEC;
+                $e
+            }
         }
     }};
 }
@@ -365,6 +509,10 @@ macro_rules! spec_to_generic {
                 use $crate::PragueEofSpec as SPEC;
                 $e
             }
+            $crate::SpecId::OSAKA => {
+                use $crate::OsakaSpec as SPEC;
+                $e
+            }
             $crate::SpecId::BEDROCK => {
                 use $crate::BedrockSpec as SPEC;
                 $e
@@ -433,8 +581,25 @@ mod tests {
         spec_to_generic!(GRANITE, assert_eq!(SPEC::SPEC_ID, GRANITE));
         spec_to_generic!(PRAGUE, assert_eq!(SPEC::SPEC_ID, PRAGUE));
         spec_to_generic!(PRAGUE_EOF, assert_eq!(SPEC::SPEC_ID, PRAGUE_EOF));
+        spec_to_generic!(OSAKA, assert_eq!(SPEC::SPEC_ID, OSAKA));
         spec_to_generic!(LATEST, assert_eq!(SPEC::SPEC_ID, LATEST));
     }
+
+    #[test]
+    fn eip_set() {
+        let mut set = EipSet::new().with(Eip::Eip7702);
+        assert!(set.contains(Eip::Eip7702));
+        assert!(!set.contains(Eip::Eip2537));
+        assert!(set.insert(Eip::Eip7692));
+        assert!(!set.insert(Eip::Eip7692));
+        assert_eq!(set.iter().collect::<Vec<_>>(), [Eip::Eip7702, Eip::Eip7692]);
+        assert!(set.remove(Eip::Eip7702));
+        assert_eq!(set, [Eip::Eip7692].into_iter().collect());
+
+        assert!(!Eip::Eip7702.is_active_in(CANCUN));
+        assert!(Eip::Eip7702.is_active_in(OSAKA));
+        assert!(!Eip::Eip7692.is_active_in(PRAGUE));
+    }
 }
 
 #[cfg(feature = "optimism")]
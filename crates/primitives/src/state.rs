@@ -2,15 +2,19 @@ use crate::{Address, Bytecode, HashMap, B256, KECCAK_EMPTY, U256};
 use bitflags::bitflags;
 use core::hash::{Hash, Hasher};
 
+pub mod existence;
+pub mod storage;
+pub use existence::{
+    charges_new_account, is_account_dead, is_removed_when_touched, is_state_clear_enabled,
+};
+pub use storage::{EvmStorage, EVM_STORAGE_INLINE_CAPACITY};
+
 /// EVM State is a mapping from addresses to accounts.
 pub type EvmState = HashMap<Address, Account>;
 
 /// Structure used for EIP-1153 transient storage.
 pub type TransientStorage = HashMap<(Address, U256), U256>;
 
-/// An account's Storage is a mapping from 256-bit integer keys to [EvmStorageSlot]s.
-pub type EvmStorage = HashMap<U256, EvmStorageSlot>;
-
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Account {
@@ -57,7 +61,7 @@ impl Account {
     pub fn new_not_existing() -> Self {
         Self {
             info: AccountInfo::default(),
-            storage: HashMap::new(),
+            storage: EvmStorage::new(),
             status: AccountStatus::LoadedAsNotExisting,
         }
     }
@@ -146,7 +150,7 @@ impl From<AccountInfo> for Account {
     fn from(info: AccountInfo) -> Self {
         Self {
             info,
-            storage: HashMap::new(),
+            storage: EvmStorage::new(),
             status: AccountStatus::Loaded,
         }
     }
@@ -0,0 +1,87 @@
+//! Rules for empty and non-existent accounts, before and after
+//! [EIP-161](https://eips.ethereum.org/EIPS/eip-161).
+//!
+//! An account is empty if it has no code, a zero nonce and a zero balance. Since
+//! [SpecId::SPURIOUS_DRAGON], empty accounts are treated as non-existent, and empty accounts
+//! touched by a transaction are removed from the state. Before it, an empty account exists once
+//! it was touched, e.g. by a call without value, and is kept in the state.
+use super::Account;
+use crate::SpecId;
+
+/// Returns `true` if empty accounts are treated as non-existent and removed when touched.
+#[inline]
+pub const fn is_state_clear_enabled(spec_id: SpecId) -> bool {
+    spec_id.is_enabled_in(SpecId::SPURIOUS_DRAGON)
+}
+
+/// Returns `true` if the loaded account is treated as non-existent.
+///
+/// Before [SpecId::SPURIOUS_DRAGON], only accounts that were not in the database and were not
+/// touched since do not exist. Since, every empty account does not exist.
+#[inline]
+pub fn is_account_dead(spec_id: SpecId, account: &Account) -> bool {
+    if is_state_clear_enabled(spec_id) {
+        account.is_empty()
+    } else {
+        account.is_loaded_as_not_existing() && !account.is_touched()
+    }
+}
+
+/// Returns `true` if the account, changed by a transaction, is removed from the state at the end
+/// of it: a touched empty account since [SpecId::SPURIOUS_DRAGON].
+///
+/// Before it, touched empty accounts are created in the state.
+#[inline]
+pub fn is_removed_when_touched(spec_id: SpecId, account: &Account) -> bool {
+    is_state_clear_enabled(spec_id) && account.is_touched() && account.is_empty()
+}
+
+/// Returns `true` if sending to the account, by a `CALL` or a `SELFDESTRUCT`, is charged for the
+/// creation of a new account.
+///
+/// Before [SpecId::SPURIOUS_DRAGON], the charge applies to every non-existent target. Since, it
+/// only applies if value is sent, as an empty target is otherwise not created.
+#[inline]
+pub const fn charges_new_account(spec_id: SpecId, transfers_value: bool, is_dead: bool) -> bool {
+    if is_state_clear_enabled(spec_id) {
+        transfers_value && is_dead
+    } else {
+        is_dead
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AccountInfo, AccountStatus, U256};
+
+    #[test]
+    fn touched_empty_account() {
+        let mut account = Account::new_not_existing();
+        assert!(is_account_dead(SpecId::FRONTIER, &account));
+        assert!(is_account_dead(SpecId::SPURIOUS_DRAGON, &account));
+
+        // A touched empty account exists before Spurious Dragon, and is removed after.
+        account.mark_touch();
+        assert!(!is_account_dead(SpecId::FRONTIER, &account));
+        assert!(is_account_dead(SpecId::SPURIOUS_DRAGON, &account));
+        assert!(!is_removed_when_touched(SpecId::FRONTIER, &account));
+        assert!(is_removed_when_touched(SpecId::SPURIOUS_DRAGON, &account));
+
+        let account = Account {
+            info: AccountInfo::from_balance(U256::from(1)),
+            status: AccountStatus::Touched,
+            ..Default::default()
+        };
+        assert!(!is_account_dead(SpecId::SPURIOUS_DRAGON, &account));
+        assert!(!is_removed_when_touched(SpecId::SPURIOUS_DRAGON, &account));
+    }
+
+    #[test]
+    fn new_account_charge() {
+        assert!(charges_new_account(SpecId::FRONTIER, false, true));
+        assert!(!charges_new_account(SpecId::FRONTIER, true, false));
+        assert!(!charges_new_account(SpecId::SPURIOUS_DRAGON, false, true));
+        assert!(charges_new_account(SpecId::SPURIOUS_DRAGON, true, true));
+    }
+}
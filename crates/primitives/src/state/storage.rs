@@ -0,0 +1,721 @@
+use super::EvmStorageSlot;
+use crate::{hash_map, HashMap, U256};
+use core::{fmt, mem, ops::Index, slice};
+use std::vec::{self, Vec};
+
+/// Number of slots kept in a plain vector before the storage is moved into a hash map.
+///
+/// Most accounts touched by a transaction access only a handful of slots, for which a linear
+/// scan is cheaper than hashing the key.
+pub const EVM_STORAGE_INLINE_CAPACITY: usize = 8;
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "deterministic")] {
+        type MapOccupiedEntry<'a> =
+            hash_map::OccupiedEntry<'a, U256, EvmStorageSlot, crate::FixedState>;
+        type MapVacantEntry<'a> =
+            hash_map::VacantEntry<'a, U256, EvmStorageSlot, crate::FixedState>;
+    } else if #[cfg(all(not(feature = "hashbrown"), feature = "std"))] {
+        type MapOccupiedEntry<'a> = hash_map::OccupiedEntry<'a, U256, EvmStorageSlot>;
+        type MapVacantEntry<'a> = hash_map::VacantEntry<'a, U256, EvmStorageSlot>;
+    } else {
+        type MapOccupiedEntry<'a> =
+            hash_map::OccupiedEntry<'a, U256, EvmStorageSlot, hash_map::DefaultHashBuilder>;
+        type MapVacantEntry<'a> =
+            hash_map::VacantEntry<'a, U256, EvmStorageSlot, hash_map::DefaultHashBuilder>;
+    }
+}
+
+/// An account's Storage is a mapping from 256-bit integer keys to [EvmStorageSlot]s.
+///
+/// Up to [EVM_STORAGE_INLINE_CAPACITY] slots are stored in a vector and looked up linearly,
+/// larger storages are moved into a [HashMap]. Iteration order is unspecified.
+///
+/// The type used to be an alias of `HashMap<U256, EvmStorageSlot>` and keeps the methods of the
+/// map that callers used on it, including indexing and the entry API. Code that needs the map
+/// itself can convert between the two with [From].
+#[derive(Clone)]
+pub struct EvmStorage {
+    repr: Repr,
+}
+
+#[derive(Clone)]
+enum Repr {
+    Inline(Vec<(U256, EvmStorageSlot)>),
+    Map(HashMap<U256, EvmStorageSlot>),
+}
+
+impl Default for EvmStorage {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EvmStorage {
+    /// Creates an empty storage. It does not allocate until the first slot is inserted.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            repr: Repr::Inline(Vec::new()),
+        }
+    }
+
+    /// Creates an empty storage with room for at least `capacity` slots.
+    ///
+    /// A capacity above [EVM_STORAGE_INLINE_CAPACITY] allocates the hash map up front.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity > EVM_STORAGE_INLINE_CAPACITY {
+            Self {
+                repr: Repr::Map(HashMap::with_capacity_and_hasher(
+                    capacity,
+                    Default::default(),
+                )),
+            }
+        } else {
+            Self {
+                repr: Repr::Inline(Vec::with_capacity(capacity)),
+            }
+        }
+    }
+
+    /// Returns the number of slots.
+    #[inline]
+    pub fn len(&self) -> usize {
+        match &self.repr {
+            Repr::Inline(slots) => slots.len(),
+            Repr::Map(map) => map.len(),
+        }
+    }
+
+    /// Returns `true` if there are no slots.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the storage has been moved into a hash map.
+    #[inline]
+    pub fn is_spilled(&self) -> bool {
+        matches!(self.repr, Repr::Map(_))
+    }
+
+    /// Returns the slot at `key`.
+    #[inline]
+    pub fn get(&self, key: &U256) -> Option<&EvmStorageSlot> {
+        match &self.repr {
+            Repr::Inline(slots) => slots.iter().find(|(k, _)| k == key).map(|(_, slot)| slot),
+            Repr::Map(map) => map.get(key),
+        }
+    }
+
+    /// Returns a mutable reference to the slot at `key`.
+    #[inline]
+    pub fn get_mut(&mut self, key: &U256) -> Option<&mut EvmStorageSlot> {
+        match &mut self.repr {
+            Repr::Inline(slots) => slots
+                .iter_mut()
+                .find(|(k, _)| k == key)
+                .map(|(_, slot)| slot),
+            Repr::Map(map) => map.get_mut(key),
+        }
+    }
+
+    /// Returns `true` if the storage contains a slot at `key`.
+    #[inline]
+    pub fn contains_key(&self, key: &U256) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Inserts the slot, returning the previous one at the same key.
+    pub fn insert(&mut self, key: U256, slot: EvmStorageSlot) -> Option<EvmStorageSlot> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(slot)),
+            Entry::Vacant(entry) => {
+                entry.insert(slot);
+                None
+            }
+        }
+    }
+
+    /// Removes the slot at `key` and returns it.
+    pub fn remove(&mut self, key: &U256) -> Option<EvmStorageSlot> {
+        match &mut self.repr {
+            Repr::Inline(slots) => {
+                let index = slots.iter().position(|(k, _)| k == key)?;
+                Some(slots.swap_remove(index).1)
+            }
+            Repr::Map(map) => map.remove(key),
+        }
+    }
+
+    /// Removes all slots.
+    ///
+    /// A spilled storage keeps its hash map, so its allocation can be reused.
+    #[inline]
+    pub fn clear(&mut self) {
+        match &mut self.repr {
+            Repr::Inline(slots) => slots.clear(),
+            Repr::Map(map) => map.clear(),
+        }
+    }
+
+    /// Removes all slots and returns them as an iterator, keeping the allocation.
+    #[inline]
+    pub fn drain(&mut self) -> Drain<'_> {
+        Drain(match &mut self.repr {
+            Repr::Inline(slots) => DrainRepr::Inline(slots.drain(..)),
+            Repr::Map(map) => DrainRepr::Map(map.drain()),
+        })
+    }
+
+    /// Retains only the slots for which the predicate returns `true`.
+    pub fn retain(&mut self, mut f: impl FnMut(&U256, &mut EvmStorageSlot) -> bool) {
+        match &mut self.repr {
+            Repr::Inline(slots) => slots.retain_mut(|(key, slot)| f(key, slot)),
+            Repr::Map(map) => map.retain(|key, slot| f(key, slot)),
+        }
+    }
+
+    /// Gets the entry at `key` for in-place manipulation.
+    pub fn entry(&mut self, key: U256) -> Entry<'_> {
+        if let Repr::Inline(slots) = &self.repr {
+            return match slots.iter().position(|(k, _)| *k == key) {
+                Some(index) => {
+                    let Repr::Inline(slots) = &mut self.repr else {
+                        unreachable!()
+                    };
+                    Entry::Occupied(OccupiedEntry(OccupiedRepr::Inline { slots, index }))
+                }
+                None => Entry::Vacant(VacantEntry(VacantRepr::Inline { storage: self, key })),
+            };
+        }
+        let Repr::Map(map) = &mut self.repr else {
+            unreachable!()
+        };
+        match map.entry(key) {
+            hash_map::Entry::Occupied(entry) => {
+                Entry::Occupied(OccupiedEntry(OccupiedRepr::Map(entry)))
+            }
+            hash_map::Entry::Vacant(entry) => Entry::Vacant(VacantEntry(VacantRepr::Map(entry))),
+        }
+    }
+
+    /// Returns an iterator over the keys and slots.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_> {
+        Iter(match &self.repr {
+            Repr::Inline(slots) => IterRepr::Inline(slots.iter()),
+            Repr::Map(map) => IterRepr::Map(map.iter()),
+        })
+    }
+
+    /// Returns an iterator over the keys and mutable slots.
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut(match &mut self.repr {
+            Repr::Inline(slots) => IterMutRepr::Inline(slots.iter_mut()),
+            Repr::Map(map) => IterMutRepr::Map(map.iter_mut()),
+        })
+    }
+
+    /// Returns an iterator over the keys.
+    #[inline]
+    pub fn keys(&self) -> impl Iterator<Item = &U256> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the slots.
+    #[inline]
+    pub fn values(&self) -> impl Iterator<Item = &EvmStorageSlot> {
+        self.iter().map(|(_, slot)| slot)
+    }
+
+    /// Returns an iterator over the mutable slots.
+    #[inline]
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut EvmStorageSlot> {
+        self.iter_mut().map(|(_, slot)| slot)
+    }
+
+    /// Moves inline slots into a hash map with room for `additional` more slots.
+    fn spill(&mut self, additional: usize) -> &mut HashMap<U256, EvmStorageSlot> {
+        if let Repr::Inline(slots) = &mut self.repr {
+            let mut map =
+                HashMap::with_capacity_and_hasher(slots.len() + additional, Default::default());
+            map.extend(mem::take(slots));
+            self.repr = Repr::Map(map);
+        }
+        match &mut self.repr {
+            Repr::Map(map) => map,
+            Repr::Inline(_) => unreachable!(),
+        }
+    }
+}
+
+/// A view into a single slot of an [EvmStorage], which may either be vacant or occupied.
+pub enum Entry<'a> {
+    /// An occupied slot.
+    Occupied(OccupiedEntry<'a>),
+    /// A vacant slot.
+    Vacant(VacantEntry<'a>),
+}
+
+impl<'a> Entry<'a> {
+    /// Returns the key of the entry.
+    #[inline]
+    pub fn key(&self) -> &U256 {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Inserts `default` if the entry is vacant and returns a mutable reference to the slot.
+    #[inline]
+    pub fn or_insert(self, default: EvmStorageSlot) -> &'a mut EvmStorageSlot {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the result of `default` if the entry is vacant and returns a mutable reference to
+    /// the slot.
+    #[inline]
+    pub fn or_insert_with(
+        self,
+        default: impl FnOnce() -> EvmStorageSlot,
+    ) -> &'a mut EvmStorageSlot {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Inserts a default slot if the entry is vacant and returns a mutable reference to the slot.
+    #[inline]
+    pub fn or_default(self) -> &'a mut EvmStorageSlot {
+        self.or_insert_with(Default::default)
+    }
+
+    /// Calls `f` with the slot if the entry is occupied.
+    #[inline]
+    pub fn and_modify(mut self, f: impl FnOnce(&mut EvmStorageSlot)) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+/// A view into an occupied slot of an [EvmStorage].
+pub struct OccupiedEntry<'a>(OccupiedRepr<'a>);
+
+enum OccupiedRepr<'a> {
+    Inline {
+        slots: &'a mut Vec<(U256, EvmStorageSlot)>,
+        index: usize,
+    },
+    Map(MapOccupiedEntry<'a>),
+}
+
+impl<'a> OccupiedEntry<'a> {
+    /// Returns the key of the entry.
+    #[inline]
+    pub fn key(&self) -> &U256 {
+        match &self.0 {
+            OccupiedRepr::Inline { slots, index } => &slots[*index].0,
+            OccupiedRepr::Map(entry) => entry.key(),
+        }
+    }
+
+    /// Returns a reference to the slot.
+    #[inline]
+    pub fn get(&self) -> &EvmStorageSlot {
+        match &self.0 {
+            OccupiedRepr::Inline { slots, index } => &slots[*index].1,
+            OccupiedRepr::Map(entry) => entry.get(),
+        }
+    }
+
+    /// Returns a mutable reference to the slot.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut EvmStorageSlot {
+        match &mut self.0 {
+            OccupiedRepr::Inline { slots, index } => &mut slots[*index].1,
+            OccupiedRepr::Map(entry) => entry.get_mut(),
+        }
+    }
+
+    /// Converts the entry into a mutable reference to the slot.
+    #[inline]
+    pub fn into_mut(self) -> &'a mut EvmStorageSlot {
+        match self.0 {
+            OccupiedRepr::Inline { slots, index } => &mut slots[index].1,
+            OccupiedRepr::Map(entry) => entry.into_mut(),
+        }
+    }
+
+    /// Replaces the slot, returning the previous one.
+    #[inline]
+    pub fn insert(&mut self, slot: EvmStorageSlot) -> EvmStorageSlot {
+        mem::replace(self.get_mut(), slot)
+    }
+}
+
+/// A view into a vacant slot of an [EvmStorage].
+pub struct VacantEntry<'a>(VacantRepr<'a>);
+
+enum VacantRepr<'a> {
+    Inline {
+        storage: &'a mut EvmStorage,
+        key: U256,
+    },
+    Map(MapVacantEntry<'a>),
+}
+
+impl<'a> VacantEntry<'a> {
+    /// Returns the key of the entry.
+    #[inline]
+    pub fn key(&self) -> &U256 {
+        match &self.0 {
+            VacantRepr::Inline { key, .. } => key,
+            VacantRepr::Map(entry) => entry.key(),
+        }
+    }
+
+    /// Inserts the slot and returns a mutable reference to it.
+    ///
+    /// Moves the storage into a hash map if the inline capacity is exceeded.
+    pub fn insert(self, slot: EvmStorageSlot) -> &'a mut EvmStorageSlot {
+        match self.0 {
+            VacantRepr::Inline { storage, key } => {
+                if storage.len() >= EVM_STORAGE_INLINE_CAPACITY {
+                    return storage.spill(1).entry(key).or_insert(slot);
+                }
+                let Repr::Inline(slots) = &mut storage.repr else {
+                    unreachable!()
+                };
+                slots.push((key, slot));
+                &mut slots.last_mut().unwrap().1
+            }
+            VacantRepr::Map(entry) => entry.insert(slot),
+        }
+    }
+}
+
+/// Iterator over the keys and slots of an [EvmStorage].
+#[derive(Clone)]
+pub struct Iter<'a>(IterRepr<'a>);
+
+#[derive(Clone)]
+enum IterRepr<'a> {
+    Inline(slice::Iter<'a, (U256, EvmStorageSlot)>),
+    Map(hash_map::Iter<'a, U256, EvmStorageSlot>),
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a U256, &'a EvmStorageSlot);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            IterRepr::Inline(iter) => iter.next().map(|(key, slot)| (key, slot)),
+            IterRepr::Map(iter) => iter.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            IterRepr::Inline(iter) => iter.size_hint(),
+            IterRepr::Map(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+/// Iterator over the keys and mutable slots of an [EvmStorage].
+pub struct IterMut<'a>(IterMutRepr<'a>);
+
+enum IterMutRepr<'a> {
+    Inline(slice::IterMut<'a, (U256, EvmStorageSlot)>),
+    Map(hash_map::IterMut<'a, U256, EvmStorageSlot>),
+}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = (&'a U256, &'a mut EvmStorageSlot);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            IterMutRepr::Inline(iter) => iter.next().map(|(key, slot)| (&*key, slot)),
+            IterMutRepr::Map(iter) => iter.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            IterMutRepr::Inline(iter) => iter.size_hint(),
+            IterMutRepr::Map(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl ExactSizeIterator for IterMut<'_> {}
+
+/// Owning iterator over the keys and slots of an [EvmStorage].
+pub struct IntoIter(IntoIterRepr);
+
+enum IntoIterRepr {
+    Inline(vec::IntoIter<(U256, EvmStorageSlot)>),
+    Map(hash_map::IntoIter<U256, EvmStorageSlot>),
+}
+
+impl Iterator for IntoIter {
+    type Item = (U256, EvmStorageSlot);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            IntoIterRepr::Inline(iter) => iter.next(),
+            IntoIterRepr::Map(iter) => iter.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            IntoIterRepr::Inline(iter) => iter.size_hint(),
+            IntoIterRepr::Map(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+/// Draining iterator over the keys and slots of an [EvmStorage].
+pub struct Drain<'a>(DrainRepr<'a>);
+
+enum DrainRepr<'a> {
+    Inline(vec::Drain<'a, (U256, EvmStorageSlot)>),
+    Map(hash_map::Drain<'a, U256, EvmStorageSlot>),
+}
+
+impl Iterator for Drain<'_> {
+    type Item = (U256, EvmStorageSlot);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            DrainRepr::Inline(iter) => iter.next(),
+            DrainRepr::Map(iter) => iter.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            DrainRepr::Inline(iter) => iter.size_hint(),
+            DrainRepr::Map(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl ExactSizeIterator for Drain<'_> {}
+
+impl IntoIterator for EvmStorage {
+    type Item = (U256, EvmStorageSlot);
+    type IntoIter = IntoIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(match self.repr {
+            Repr::Inline(slots) => IntoIterRepr::Inline(slots.into_iter()),
+            Repr::Map(map) => IntoIterRepr::Map(map.into_iter()),
+        })
+    }
+}
+
+impl<'a> IntoIterator for &'a EvmStorage {
+    type Item = (&'a U256, &'a EvmStorageSlot);
+    type IntoIter = Iter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut EvmStorage {
+    type Item = (&'a U256, &'a mut EvmStorageSlot);
+    type IntoIter = IterMut<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl Extend<(U256, EvmStorageSlot)> for EvmStorage {
+    fn extend<T: IntoIterator<Item = (U256, EvmStorageSlot)>>(&mut self, iter: T) {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        if self.len() + lower > EVM_STORAGE_INLINE_CAPACITY {
+            self.spill(lower).extend(iter);
+            return;
+        }
+        for (key, slot) in iter {
+            self.insert(key, slot);
+        }
+    }
+}
+
+impl FromIterator<(U256, EvmStorageSlot)> for EvmStorage {
+    fn from_iter<T: IntoIterator<Item = (U256, EvmStorageSlot)>>(iter: T) -> Self {
+        let mut storage = Self::new();
+        storage.extend(iter);
+        storage
+    }
+}
+
+impl<const N: usize> From<[(U256, EvmStorageSlot); N]> for EvmStorage {
+    #[inline]
+    fn from(slots: [(U256, EvmStorageSlot); N]) -> Self {
+        slots.into_iter().collect()
+    }
+}
+
+impl From<HashMap<U256, EvmStorageSlot>> for EvmStorage {
+    #[inline]
+    fn from(map: HashMap<U256, EvmStorageSlot>) -> Self {
+        if map.len() > EVM_STORAGE_INLINE_CAPACITY {
+            Self {
+                repr: Repr::Map(map),
+            }
+        } else {
+            map.into_iter().collect()
+        }
+    }
+}
+
+impl From<EvmStorage> for HashMap<U256, EvmStorageSlot> {
+    #[inline]
+    fn from(storage: EvmStorage) -> Self {
+        match storage.repr {
+            Repr::Inline(slots) => slots.into_iter().collect(),
+            Repr::Map(map) => map,
+        }
+    }
+}
+
+impl Index<&U256> for EvmStorage {
+    type Output = EvmStorageSlot;
+
+    /// Returns the slot at `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no slot at `key`, like indexing a [HashMap].
+    #[inline]
+    fn index(&self, key: &U256) -> &EvmStorageSlot {
+        self.get(key).expect("storage slot not found")
+    }
+}
+
+impl PartialEq for EvmStorage {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(key, slot)| other.get(key) == Some(slot))
+    }
+}
+
+impl Eq for EvmStorage {}
+
+impl fmt::Debug for EvmStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for EvmStorage {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for EvmStorage {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        HashMap::<U256, EvmStorageSlot>::deserialize(deserializer).map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inline_and_spilled_storage() {
+        let mut storage = EvmStorage::new();
+        for i in 0..EVM_STORAGE_INLINE_CAPACITY {
+            assert_eq!(
+                storage.insert(U256::from(i), EvmStorageSlot::new(U256::from(i))),
+                None
+            );
+        }
+        assert!(!storage.is_spilled());
+        let inline = storage.clone();
+
+        // Overwriting an existing slot does not spill.
+        let slot = EvmStorageSlot::new_changed(U256::ZERO, U256::from(1));
+        assert_eq!(
+            storage.insert(U256::ZERO, slot.clone()),
+            Some(EvmStorageSlot::new(U256::ZERO))
+        );
+        assert!(!storage.is_spilled());
+
+        let key = U256::from(EVM_STORAGE_INLINE_CAPACITY);
+        match storage.entry(key) {
+            Entry::Vacant(entry) => {
+                entry.insert(EvmStorageSlot::new(key)).present_value = U256::ZERO
+            }
+            Entry::Occupied(_) => panic!("slot should be vacant"),
+        }
+        assert!(storage.is_spilled());
+        assert_eq!(storage.len(), EVM_STORAGE_INLINE_CAPACITY + 1);
+        assert_eq!(storage.get(&U256::ZERO), Some(&slot));
+        assert!(storage.get(&key).unwrap().is_changed());
+
+        // Equality does not depend on the representation or order.
+        storage.insert(U256::ZERO, EvmStorageSlot::new(U256::ZERO));
+        storage.remove(&key);
+        assert_eq!(storage, inline);
+        let mut reversed: Vec<_> = inline.clone().into_iter().collect();
+        reversed.reverse();
+        let reversed: EvmStorage = reversed.into_iter().collect();
+        assert_eq!(reversed, inline);
+
+        storage.retain(|_, slot| slot.present_value < U256::from(2));
+        let mut keys: Vec<_> = storage.keys().copied().collect();
+        keys.sort();
+        assert_eq!(keys, [U256::ZERO, U256::from(1)]);
+    }
+
+    #[test]
+    fn hash_map_compatible_api() {
+        for capacity in [0, EVM_STORAGE_INLINE_CAPACITY + 1] {
+            let mut storage = EvmStorage::with_capacity(capacity);
+            assert_eq!(storage.is_spilled(), capacity > EVM_STORAGE_INLINE_CAPACITY);
+            storage.entry(U256::from(1)).or_default().present_value = U256::from(2);
+            storage
+                .entry(U256::from(1))
+                .and_modify(|slot| slot.present_value += U256::from(1));
+            assert_eq!(storage[&U256::from(1)].present_value, U256::from(3));
+
+            let drained: Vec<_> = storage.drain().collect();
+            assert_eq!(drained.len(), 1);
+            assert!(storage.is_empty());
+        }
+    }
+}
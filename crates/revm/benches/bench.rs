@@ -1,13 +1,19 @@
 use criterion::{
-    criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, Criterion,
+    criterion_group, criterion_main, measurement::WallTime, BatchSize, BenchmarkGroup, Criterion,
 };
 use revm::{
     db::BenchmarkDB,
     interpreter::{analysis::to_analysed, Contract, DummyHost, Interpreter},
-    primitives::{address, bytes, hex, BerlinSpec, Bytecode, Bytes, TxKind, U256},
-    Evm,
+    primitives::{
+        address, bytes, hex, AccountInfo, Address, BerlinSpec, Bytecode, Bytes, SpecId, TxEnv,
+        TxEnvTemplate, TxKind, TxOverrides, U256,
+    },
+    Evm, InMemoryDB,
+};
+use revm_interpreter::{
+    opcode::{make_instruction_table, make_runtime_instruction_table},
+    SharedMemory, EMPTY_SHARED_MEMORY,
 };
-use revm_interpreter::{opcode::make_instruction_table, SharedMemory, EMPTY_SHARED_MEMORY};
 use std::time::Duration;
 
 fn analysis(c: &mut Criterion) {
@@ -81,6 +87,181 @@ fn transfer(c: &mut Criterion) {
     g.finish();
 }
 
+fn big_memory(c: &mut Criterion) {
+    // CALLDATACOPY the whole calldata to memory, MCOPY it one word forward (overlapping)
+    // and CODECOPY the code after it.
+    let code = bytes!("365f5f37365f60205e385f363900");
+    let mut evm = Evm::builder()
+        .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(code)))
+        .with_spec_id(SpecId::CANCUN)
+        .modify_tx_env(|tx| {
+            tx.caller = address!("0000000000000000000000000000000000000001");
+            tx.transact_to = TxKind::Call(address!("0000000000000000000000000000000000000000"));
+        })
+        .build();
+
+    let mut g = c.benchmark_group("big_memory");
+    g.noise_threshold(0.03)
+        .warm_up_time(Duration::from_secs(1))
+        .sample_size(10);
+    for size_mib in [1, 4] {
+        evm.context.evm.env.tx.data = vec![0xff; size_mib << 20].into();
+        g.bench_function(format!("copy/{size_mib}MiB"), |b| {
+            b.iter(|| evm.transact().unwrap())
+        });
+    }
+    g.finish();
+}
+
+fn block_replay(c: &mut Criterion) {
+    // Balance transfers, token transfers writing two slots and counter increments writing one,
+    // the accounts with a handful of touched slots that make up most of a mainnet block.
+    let token = address!("0000000000000000000000000000000000000020");
+    let counter = address!("0000000000000000000000000000000000000010");
+    let mut db = InMemoryDB::default();
+    // CALLER SLOAD PUSH1 1 SWAP1 SUB CALLER SSTORE
+    // PUSH0 CALLDATALOAD DUP1 SLOAD PUSH1 1 ADD SWAP1 SSTORE STOP
+    db.insert_account_info(
+        token,
+        AccountInfo::from_bytecode(Bytecode::new_raw(bytes!(
+            "33546001900333555f358054600101905500"
+        ))),
+    );
+    // PUSH1 0 SLOAD PUSH1 1 ADD PUSH1 0 SSTORE STOP
+    db.insert_account_info(
+        counter,
+        AccountInfo::from_bytecode(Bytecode::new_raw(bytes!("60005460010160005500"))),
+    );
+
+    let senders: Vec<Address> = (1..=50u64)
+        .map(|i| Address::from_word((U256::from(i) << 64).into()))
+        .collect();
+    for sender in &senders {
+        db.insert_account_info(*sender, AccountInfo::from_balance(U256::from(u64::MAX)));
+        db.insert_account_storage(
+            token,
+            U256::from_be_slice(sender.as_slice()),
+            U256::from(1_000_000),
+        )
+        .unwrap();
+    }
+
+    let mut nonces = vec![0; senders.len()];
+    let block: Vec<TxEnv> = (0..200)
+        .map(|i| {
+            let sender = i % senders.len();
+            let nonce = nonces[sender];
+            nonces[sender] += 1;
+            let recipient = senders[(i * 7 + 3) % senders.len()];
+            let (transact_to, value, data) = match i % 3 {
+                0 => (recipient, U256::from(1), Bytes::new()),
+                1 => (
+                    token,
+                    U256::ZERO,
+                    Bytes::copy_from_slice(recipient.into_word().as_slice()),
+                ),
+                _ => (counter, U256::ZERO, Bytes::new()),
+            };
+            TxEnv {
+                caller: senders[sender],
+                transact_to: TxKind::Call(transact_to),
+                value,
+                data,
+                nonce: Some(nonce),
+                gas_limit: 100_000,
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    let mut g = c.benchmark_group("block_replay");
+    g.noise_threshold(0.03)
+        .warm_up_time(Duration::from_secs(1))
+        .sample_size(20);
+    g.bench_function(format!("transfers/{}", block.len()), |b| {
+        b.iter_batched(
+            || db.clone(),
+            |db| {
+                let mut evm = Evm::builder()
+                    .with_db(db)
+                    .with_spec_id(SpecId::CANCUN)
+                    .build();
+                for tx in &block {
+                    evm.context.evm.env.tx = tx.clone();
+                    evm.transact_commit().unwrap();
+                }
+                evm
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    g.finish();
+}
+
+fn transact_many(c: &mut Criterion) {
+    // The token of `block_replay`, simulated for many callers without committing.
+    let token = address!("0000000000000000000000000000000000000020");
+    let mut db = InMemoryDB::default();
+    db.insert_account_info(
+        token,
+        AccountInfo::from_bytecode(Bytecode::new_raw(bytes!(
+            "33546001900333555f358054600101905500"
+        ))),
+    );
+    let callers: Vec<Address> = (1..=100u64)
+        .map(|i| Address::from_word((U256::from(i) << 64).into()))
+        .collect();
+    for caller in &callers {
+        db.insert_account_info(*caller, AccountInfo::from_balance(U256::from(u64::MAX)));
+        db.insert_account_storage(
+            token,
+            U256::from_be_slice(caller.as_slice()),
+            U256::from(1_000_000),
+        )
+        .unwrap();
+    }
+
+    let template = TxEnvTemplate::new(TxEnv {
+        transact_to: TxKind::Call(token),
+        data: Bytes::copy_from_slice(callers[0].into_word().as_slice()),
+        gas_limit: 100_000,
+        ..Default::default()
+    });
+    let overrides: Vec<TxOverrides> = callers
+        .iter()
+        .map(|caller| TxOverrides::new(*caller, U256::ZERO))
+        .collect();
+    let mut evm = Evm::builder()
+        .with_db(db)
+        .with_spec_id(SpecId::CANCUN)
+        .build();
+
+    // Every transaction clones the accounts kept from the previous ones, compare against
+    // transacting the instantiations one by one.
+    let mut g = c.benchmark_group("transact_many");
+    g.noise_threshold(0.03)
+        .warm_up_time(Duration::from_secs(1))
+        .sample_size(20);
+    g.bench_function(format!("batch/{}", overrides.len()), |b| {
+        b.iter(|| {
+            evm.transact_many(&template, overrides.iter().copied())
+                .unwrap()
+        })
+    });
+    g.bench_function(format!("loop/{}", overrides.len()), |b| {
+        b.iter(|| {
+            overrides
+                .iter()
+                .map(|overrides| {
+                    evm.context.evm.env.tx = template.instantiate(overrides);
+                    evm.transact()
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+    g.finish();
+}
+
 fn bench_transact<EXT>(g: &mut BenchmarkGroup<'_, WallTime>, evm: &mut Evm<'_, EXT, BenchmarkDB>) {
     let state = match evm.context.evm.db.0 {
         Bytecode::LegacyRaw(_) => "raw",
@@ -92,26 +273,35 @@ fn bench_transact<EXT>(g: &mut BenchmarkGroup<'_, WallTime>, evm: &mut Evm<'_, E
 }
 
 fn bench_eval(g: &mut BenchmarkGroup<'_, WallTime>, evm: &mut Evm<'static, (), BenchmarkDB>) {
-    g.bench_function("eval", |b| {
-        let contract = Contract {
-            input: evm.context.evm.env.tx.data.clone(),
-            bytecode: to_analysed(evm.context.evm.db.0.clone()),
-            ..Default::default()
-        };
-        let mut shared_memory = SharedMemory::new();
-        let mut host = DummyHost::new(*evm.context.evm.env.clone());
-        let instruction_table = make_instruction_table::<DummyHost, BerlinSpec>();
-        b.iter(move || {
-            // replace memory with empty memory to use it inside interpreter.
-            // Later return memory back.
-            let temp = core::mem::replace(&mut shared_memory, EMPTY_SHARED_MEMORY);
-            let mut interpreter = Interpreter::new(contract.clone(), u64::MAX, false);
-            let res = interpreter.run(temp, &instruction_table, &mut host);
-            shared_memory = interpreter.take_memory();
-            host.clear();
-            res
-        })
-    });
+    let tables = [
+        ("eval", make_instruction_table::<DummyHost, BerlinSpec>()),
+        (
+            "eval/runtime_spec",
+            make_runtime_instruction_table::<DummyHost>(),
+        ),
+    ];
+    for (id, instruction_table) in tables {
+        g.bench_function(id, |b| {
+            let contract = Contract {
+                input: evm.context.evm.env.tx.data.clone(),
+                bytecode: to_analysed(evm.context.evm.db.0.clone()),
+                ..Default::default()
+            };
+            let mut shared_memory = SharedMemory::new();
+            let mut host = DummyHost::new(*evm.context.evm.env.clone());
+            b.iter(move || {
+                // replace memory with empty memory to use it inside interpreter.
+                // Later return memory back.
+                let temp = core::mem::replace(&mut shared_memory, EMPTY_SHARED_MEMORY);
+                let mut interpreter = Interpreter::new(contract.clone(), u64::MAX, false)
+                    .with_spec_id(SpecId::BERLIN);
+                let res = interpreter.run(temp, &instruction_table, &mut host);
+                shared_memory = interpreter.take_memory();
+                host.clear();
+                res
+            })
+        });
+    }
 }
 
 fn bytecode(s: &str) -> Bytecode {
@@ -124,6 +314,9 @@ criterion_group!(
     analysis,
     snailtracer,
     transfer,
+    big_memory,
+    block_replay,
+    transact_many,
 );
 criterion_main!(benches);
 
@@ -0,0 +1,35 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use revm::{
+    primitives::{address, bytes, AccountInfo, Bytecode, TxEnv, TxKind, U256},
+    BenchHarness, Evm, InMemoryDB,
+};
+
+fn stateful(c: &mut Criterion) {
+    let caller = address!("1000000000000000000000000000000000000000");
+    let counter = address!("0000000000000000000000000000000000000010");
+    let mut db = InMemoryDB::default();
+    db.insert_account_info(caller, AccountInfo::from_balance(U256::from(u64::MAX)));
+    // PUSH1 0 SLOAD PUSH1 1 ADD PUSH1 0 SSTORE STOP
+    db.insert_account_info(
+        counter,
+        AccountInfo::from_bytecode(Bytecode::new_raw(bytes!("60005460010160005500"))),
+    );
+    let increment = |nonce| TxEnv {
+        caller,
+        transact_to: TxKind::Call(counter),
+        nonce: Some(nonce),
+        gas_limit: 100_000,
+        ..Default::default()
+    };
+
+    let mut harness = BenchHarness::new(Evm::builder().with_db(db).build());
+    harness.setup((0..10).map(increment)).unwrap();
+    harness.set_target(increment(10));
+
+    let mut g = c.benchmark_group("stateful");
+    g.bench_function("counter_increment", |b| b.iter(|| harness.run().unwrap()));
+    g.finish();
+}
+
+criterion_group!(benches, stateful);
+criterion_main!(benches);
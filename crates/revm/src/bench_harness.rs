@@ -0,0 +1,161 @@
+//! Benchmarks of transactions on top of the state built by setup transactions.
+use crate::{
+    primitives::{EVMError, EVMResult, ExecutionResult, TxEnv},
+    Evm, InMemoryDB,
+};
+use core::{convert::Infallible, fmt};
+use std::vec::Vec;
+
+/// Error of a setup transaction of a [`BenchHarness`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SetupError {
+    /// The setup transaction at `index` is invalid.
+    Evm {
+        index: usize,
+        error: EVMError<Infallible>,
+    },
+    /// The setup transaction at `index` reverted or halted.
+    Failed {
+        index: usize,
+        result: ExecutionResult,
+    },
+}
+
+impl fmt::Display for SetupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Evm { index, error } => write!(f, "setup transaction {index}: {error}"),
+            Self::Failed { index, result } => {
+                write!(f, "setup transaction {index} failed: {result:?}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SetupError {}
+
+/// Harness benchmarking a transaction on a realistic state.
+///
+/// The state is built by setup transactions, e.g. deployments and token transfers, that are
+/// committed to an [`InMemoryDB`]. The benchmarked transaction is then executed without
+/// committing, so every iteration starts from the same state.
+pub struct BenchHarness<'a, EXT> {
+    evm: Evm<'a, EXT, InMemoryDB>,
+}
+
+impl<'a, EXT> BenchHarness<'a, EXT> {
+    /// Creates a harness executing with `evm`, on top of its database.
+    pub fn new(evm: Evm<'a, EXT, InMemoryDB>) -> Self {
+        Self { evm }
+    }
+
+    /// Returns the [`Evm`] of the harness.
+    pub fn evm(&self) -> &Evm<'a, EXT, InMemoryDB> {
+        &self.evm
+    }
+
+    /// Returns the [`Evm`] of the harness, e.g. to insert accounts in its database.
+    pub fn evm_mut(&mut self) -> &mut Evm<'a, EXT, InMemoryDB> {
+        &mut self.evm
+    }
+
+    /// Returns the [`Evm`] of the harness.
+    pub fn into_evm(self) -> Evm<'a, EXT, InMemoryDB> {
+        self.evm
+    }
+
+    /// Executes and commits the setup transactions in order.
+    ///
+    /// Stops at the first transaction that is invalid or does not succeed.
+    pub fn setup(
+        &mut self,
+        txs: impl IntoIterator<Item = TxEnv>,
+    ) -> Result<Vec<ExecutionResult>, SetupError> {
+        let mut results = Vec::new();
+        for (index, tx) in txs.into_iter().enumerate() {
+            *self.evm.tx_mut() = tx;
+            let result = self
+                .evm
+                .transact_commit()
+                .map_err(|error| SetupError::Evm { index, error })?;
+            if !result.is_success() {
+                return Err(SetupError::Failed { index, result });
+            }
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    /// Sets the benchmarked transaction.
+    pub fn set_target(&mut self, tx: TxEnv) {
+        *self.evm.tx_mut() = tx;
+    }
+
+    /// Executes the benchmarked transaction without committing it.
+    pub fn run(&mut self) -> EVMResult<Infallible> {
+        self.evm.transact()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{bytes, AccountInfo, Address, Bytecode, TxKind, U256};
+
+    const CALLER: Address = Address::with_last_byte(1);
+    const COUNTER: Address = Address::with_last_byte(0x10);
+
+    fn harness() -> BenchHarness<'static, ()> {
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(CALLER, AccountInfo::from_balance(U256::from(1_000_000)));
+        // Increments slot 0.
+        //
+        // PUSH1 0 SLOAD PUSH1 1 ADD PUSH1 0 SSTORE STOP
+        db.insert_account_info(
+            COUNTER,
+            AccountInfo::from_bytecode(Bytecode::new_raw(bytes!("60005460010160005500"))),
+        );
+        BenchHarness::new(Evm::builder().with_db(db).build())
+    }
+
+    fn increment(nonce: u64) -> TxEnv {
+        TxEnv {
+            caller: CALLER,
+            transact_to: TxKind::Call(COUNTER),
+            nonce: Some(nonce),
+            gas_limit: 100_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn runs_on_setup_state() {
+        let mut harness = harness();
+        let results = harness.setup((0..3).map(increment)).unwrap();
+        assert_eq!(results.len(), 3);
+
+        harness.set_target(increment(3));
+        let first = harness.run().unwrap();
+        assert_eq!(
+            first.state[&COUNTER].storage[&U256::ZERO].present_value,
+            U256::from(4)
+        );
+        // Nothing is committed.
+        assert_eq!(harness.run().unwrap(), first);
+    }
+
+    #[test]
+    fn setup_errors() {
+        let mut harness = harness();
+        let err = harness.setup([increment(0), increment(0)]).unwrap_err();
+        assert!(matches!(err, SetupError::Evm { index: 1, .. }));
+
+        let out_of_gas = TxEnv {
+            gas_limit: 21_010,
+            ..increment(1)
+        };
+        let err = harness.setup([out_of_gas]).unwrap_err();
+        assert!(matches!(err, SetupError::Failed { index: 0, .. }));
+    }
+}
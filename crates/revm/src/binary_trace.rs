@@ -0,0 +1,479 @@
+//! Compact binary format for the touched state and traces of transactions.
+//!
+//! Meant for pipelines that persist large numbers of simulations, where JSON is too slow and too
+//! large. A stream starts with a header of [`MAGIC`] and [`SCHEMA_VERSION`], followed by any
+//! number of [`TxRecord`]s:
+//!
+//! ```text
+//! stream  = MAGIC version:u8 record*
+//! record  = success:u8 gas_used:varint account_count:varint account* step_count:varint step*
+//! account = address:20 flags:u8 balance:u256 nonce:varint code_hash:32 slot_count:varint slot*
+//! slot    = key:u256 original:u256 present:u256
+//! step    = depth:varint pc:varint opcode:u8 gas_remaining:varint
+//! ```
+//!
+//! `varint` is an unsigned LEB128 integer and `u256` is a length byte followed by the big-endian
+//! bytes of the value without leading zeros.
+use crate::{
+    interpreter::Interpreter,
+    primitives::{db::Database, Address, EvmState, ResultAndState, B256, U256},
+    EvmContext, Inspector,
+};
+use core::fmt;
+use std::vec::Vec;
+
+/// Magic bytes at the start of a stream.
+pub const MAGIC: [u8; 4] = *b"RVMT";
+
+/// Version of the format written by this module.
+pub const SCHEMA_VERSION: u8 = 1;
+
+/// Account touched by a transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TouchedAccount {
+    /// Address of the account.
+    pub address: Address,
+    /// Account was created by the transaction.
+    pub created: bool,
+    /// Account was selfdestructed by the transaction.
+    pub selfdestructed: bool,
+    /// Balance after the transaction.
+    pub balance: U256,
+    /// Nonce after the transaction.
+    pub nonce: u64,
+    /// Code hash after the transaction.
+    pub code_hash: B256,
+    /// Changed storage slots, sorted by key.
+    pub storage: Vec<SlotChange>,
+}
+
+/// Storage slot changed by a transaction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SlotChange {
+    /// Key of the slot.
+    pub key: U256,
+    /// Value before the transaction.
+    pub original: U256,
+    /// Value after the transaction.
+    pub present: U256,
+}
+
+/// Executed instruction, recorded by [`StepRecorder`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TraceStep {
+    /// Call depth of the frame, starting at 0 for the transaction frame.
+    pub depth: u64,
+    /// Program counter of the instruction.
+    pub pc: u64,
+    /// Opcode of the instruction.
+    pub opcode: u8,
+    /// Gas remaining before the instruction.
+    pub gas_remaining: u64,
+}
+
+/// Touched state and trace of a transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TxRecord {
+    /// Transaction succeeded.
+    pub success: bool,
+    /// Gas used by the transaction.
+    pub gas_used: u64,
+    /// Touched accounts, sorted by address.
+    pub accounts: Vec<TouchedAccount>,
+    /// Executed instructions.
+    pub steps: Vec<TraceStep>,
+}
+
+impl TxRecord {
+    /// Creates a record from the output of a transaction and its recorded steps.
+    pub fn new(result: &ResultAndState, steps: Vec<TraceStep>) -> Self {
+        Self {
+            success: result.result.is_success(),
+            gas_used: result.result.gas_used(),
+            accounts: touched_accounts(&result.state),
+            steps,
+        }
+    }
+
+    /// Appends the encoded record to `out`.
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.success as u8);
+        write_varint(out, self.gas_used);
+        write_varint(out, self.accounts.len() as u64);
+        for account in &self.accounts {
+            out.extend_from_slice(account.address.as_slice());
+            out.push(account.created as u8 | (account.selfdestructed as u8) << 1);
+            write_u256(out, account.balance);
+            write_varint(out, account.nonce);
+            out.extend_from_slice(account.code_hash.as_slice());
+            write_varint(out, account.storage.len() as u64);
+            for slot in &account.storage {
+                write_u256(out, slot.key);
+                write_u256(out, slot.original);
+                write_u256(out, slot.present);
+            }
+        }
+        write_varint(out, self.steps.len() as u64);
+        for step in &self.steps {
+            write_varint(out, step.depth);
+            write_varint(out, step.pc);
+            out.push(step.opcode);
+            write_varint(out, step.gas_remaining);
+        }
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, DecodeError> {
+        let success = reader.read_bool()?;
+        let gas_used = reader.read_varint()?;
+        let accounts = (0..reader.read_len()?)
+            .map(|_| {
+                let address = Address::from_slice(reader.read_bytes(20)?);
+                let flags = reader.read_u8()?;
+                if flags > 0b11 {
+                    return Err(DecodeError::InvalidValue);
+                }
+                Ok(TouchedAccount {
+                    address,
+                    created: flags & 1 != 0,
+                    selfdestructed: flags & 2 != 0,
+                    balance: reader.read_u256()?,
+                    nonce: reader.read_varint()?,
+                    code_hash: B256::from_slice(reader.read_bytes(32)?),
+                    storage: (0..reader.read_len()?)
+                        .map(|_| {
+                            Ok(SlotChange {
+                                key: reader.read_u256()?,
+                                original: reader.read_u256()?,
+                                present: reader.read_u256()?,
+                            })
+                        })
+                        .collect::<Result<_, _>>()?,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        let steps = (0..reader.read_len()?)
+            .map(|_| {
+                Ok(TraceStep {
+                    depth: reader.read_varint()?,
+                    pc: reader.read_varint()?,
+                    opcode: reader.read_u8()?,
+                    gas_remaining: reader.read_varint()?,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            success,
+            gas_used,
+            accounts,
+            steps,
+        })
+    }
+}
+
+/// Returns the accounts touched in the state, with their changed storage slots.
+pub fn touched_accounts(state: &EvmState) -> Vec<TouchedAccount> {
+    let mut accounts = state
+        .iter()
+        .filter(|(_, account)| account.is_touched())
+        .map(|(address, account)| {
+            let mut storage = account
+                .changed_storage_slots()
+                .map(|(key, slot)| SlotChange {
+                    key: *key,
+                    original: slot.original_value(),
+                    present: slot.present_value(),
+                })
+                .collect::<Vec<_>>();
+            storage.sort_unstable_by_key(|slot| slot.key);
+            TouchedAccount {
+                address: *address,
+                created: account.is_created(),
+                selfdestructed: account.is_selfdestructed(),
+                balance: account.info.balance,
+                nonce: account.info.nonce,
+                code_hash: account.info.code_hash,
+                storage,
+            }
+        })
+        .collect::<Vec<_>>();
+    accounts.sort_unstable_by_key(|account| account.address);
+    accounts
+}
+
+/// Appends the header of a stream to `out`.
+pub fn write_header(out: &mut Vec<u8>) {
+    out.extend_from_slice(&MAGIC);
+    out.push(SCHEMA_VERSION);
+}
+
+/// Error decoding a stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DecodeError {
+    /// Stream does not start with [`MAGIC`].
+    InvalidMagic,
+    /// Stream was written with an unsupported version of the format.
+    UnsupportedVersion(u8),
+    /// Stream ends in the middle of a record.
+    UnexpectedEnd,
+    /// Value out of its range.
+    InvalidValue,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMagic => f.write_str("invalid magic bytes"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported schema version {version}")
+            }
+            Self::UnexpectedEnd => f.write_str("unexpected end of stream"),
+            Self::InvalidValue => f.write_str("invalid value"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// Iterator over the records of a stream.
+#[derive(Clone, Debug)]
+pub struct RecordReader<'a> {
+    reader: Reader<'a>,
+    version: u8,
+    failed: bool,
+}
+
+impl<'a> RecordReader<'a> {
+    /// Reads the header of the stream.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        let mut reader = Reader { bytes };
+        if reader.read_bytes(MAGIC.len()).ok() != Some(&MAGIC[..]) {
+            return Err(DecodeError::InvalidMagic);
+        }
+        let version = reader.read_u8()?;
+        if version != SCHEMA_VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+        Ok(Self {
+            reader,
+            version,
+            failed: false,
+        })
+    }
+
+    /// Returns the schema version of the stream.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+}
+
+impl Iterator for RecordReader<'_> {
+    type Item = Result<TxRecord, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.reader.bytes.is_empty() {
+            return None;
+        }
+        let record = TxRecord::decode(&mut self.reader);
+        self.failed = record.is_err();
+        Some(record)
+    }
+}
+
+/// Reads the fields of a record.
+#[derive(Clone, Debug)]
+pub(crate) struct Reader<'a> {
+    pub(crate) bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < len {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        let (bytes, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(bytes)
+    }
+
+    pub(crate) fn read_u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub(crate) fn read_bool(&mut self) -> Result<bool, DecodeError> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(DecodeError::InvalidValue),
+        }
+    }
+
+    pub(crate) fn read_varint(&mut self) -> Result<u64, DecodeError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_u8()?;
+            let bits = (byte & 0x7f) as u64;
+            if shift == 63 && bits > 1 {
+                return Err(DecodeError::InvalidValue);
+            }
+            value |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(DecodeError::InvalidValue)
+    }
+
+    /// Reads a length, which can not be larger than the remaining bytes.
+    pub(crate) fn read_len(&mut self) -> Result<usize, DecodeError> {
+        let len = self.read_varint()?;
+        if len > self.bytes.len() as u64 {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        Ok(len as usize)
+    }
+
+    pub(crate) fn read_u256(&mut self) -> Result<U256, DecodeError> {
+        let len = self.read_u8()? as usize;
+        if len > 32 {
+            return Err(DecodeError::InvalidValue);
+        }
+        Ok(U256::from_be_slice(self.read_bytes(len)?))
+    }
+}
+
+pub(crate) fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+pub(crate) fn write_u256(out: &mut Vec<u8>, value: U256) {
+    let bytes = value.to_be_bytes::<32>();
+    let start = bytes.iter().position(|byte| *byte != 0).unwrap_or(32);
+    out.push((32 - start) as u8);
+    out.extend_from_slice(&bytes[start..]);
+}
+
+/// [Inspector] that records the executed instructions for a [`TxRecord`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StepRecorder {
+    steps: Vec<TraceStep>,
+}
+
+impl StepRecorder {
+    /// Returns the recorded steps.
+    pub fn steps(&self) -> &[TraceStep] {
+        &self.steps
+    }
+
+    /// Takes the recorded steps, leaving the recorder empty for the next transaction.
+    pub fn take_steps(&mut self) -> Vec<TraceStep> {
+        core::mem::take(&mut self.steps)
+    }
+}
+
+impl<DB: Database> Inspector<DB> for StepRecorder {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        self.steps.push(TraceStep {
+            depth: context.journaled_state.depth(),
+            pc: interp.program_counter() as u64,
+            opcode: interp.current_opcode(),
+            gas_remaining: interp.gas.remaining(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::BenchmarkDB,
+        inspector_handle_register,
+        primitives::{bytes, Bytecode, TxKind},
+        Evm,
+    };
+
+    #[test]
+    fn varint_and_u256() {
+        for value in [0, 1, 0x7f, 0x80, 300, u64::MAX] {
+            let mut out = Vec::new();
+            write_varint(&mut out, value);
+            assert_eq!(Reader { bytes: &out }.read_varint(), Ok(value));
+        }
+        for value in [U256::ZERO, U256::from(0x1234), U256::MAX] {
+            let mut out = Vec::new();
+            write_u256(&mut out, value);
+            assert_eq!(Reader { bytes: &out }.read_u256(), Ok(value));
+        }
+        let mut out = Vec::new();
+        write_u256(&mut out, U256::from(0x1234));
+        assert_eq!(out, [2, 0x12, 0x34]);
+    }
+
+    #[test]
+    fn roundtrip() {
+        // PUSH1 0x2a PUSH1 0 SSTORE STOP
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(bytes!(
+                "602a60005500"
+            ))))
+            .with_external_context(StepRecorder::default())
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        let result = evm.transact().unwrap();
+        let record = TxRecord::new(&result, evm.context.external.take_steps());
+        assert!(record.success);
+        assert_eq!(
+            record
+                .steps
+                .iter()
+                .map(|step| step.opcode)
+                .collect::<Vec<_>>(),
+            [0x60, 0x60, 0x55, 0x00]
+        );
+        let contract = record
+            .accounts
+            .iter()
+            .find(|account| account.address == Address::ZERO)
+            .unwrap();
+        assert_eq!(
+            contract.storage,
+            [SlotChange {
+                key: U256::ZERO,
+                original: U256::ZERO,
+                present: U256::from(0x2a),
+            }]
+        );
+
+        let mut out = Vec::new();
+        write_header(&mut out);
+        record.encode(&mut out);
+        record.encode(&mut out);
+        let reader = RecordReader::new(&out).unwrap();
+        assert_eq!(reader.version(), SCHEMA_VERSION);
+        let records = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(records, [record.clone(), record]);
+
+        assert_eq!(
+            RecordReader::new(b"JSON").unwrap_err(),
+            DecodeError::InvalidMagic
+        );
+        let mut reader = RecordReader::new(&out[..out.len() - 1]).unwrap();
+        assert!(reader.next().unwrap().is_ok());
+        assert_eq!(reader.next(), Some(Err(DecodeError::UnexpectedEnd)));
+        assert_eq!(reader.next(), None);
+        out[MAGIC.len()] = SCHEMA_VERSION + 1;
+        assert_eq!(
+            RecordReader::new(&out).unwrap_err(),
+            DecodeError::UnsupportedVersion(SCHEMA_VERSION + 1)
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     db::{Database, DatabaseRef, EmptyDB, WrapDatabaseRef},
     handler::register,
+    interpreter::gas::SharedGasSchedule,
     primitives::{
         BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, Env, EnvWithHandlerCfg, HandlerCfg, SpecId, TxEnv,
     },
@@ -412,6 +413,20 @@ impl<'a, BuilderStage, EXT, DB: Database> EvmBuilder<'a, BuilderStage, EXT, DB>
         self
     }
 
+    /// Sets the gas costs charged by the interpreter and the transaction, replacing the costs of
+    /// the spec.
+    pub fn with_gas_schedule(mut self, gas_schedule: impl Into<SharedGasSchedule>) -> Self {
+        self.context.evm.inner.gas_schedule = Some(gas_schedule.into());
+        self
+    }
+
+    /// Sets whether logs discarded by reverted frames are returned in
+    /// [`ResultAndState::reverted_logs`](crate::primitives::ResultAndState::reverted_logs).
+    pub fn with_record_reverted_logs(mut self, record: bool) -> Self {
+        self.context.evm.inner.journaled_state.record_reverted_logs = record;
+        self
+    }
+
     /// Clears Environment of EVM.
     pub fn with_clear_env(mut self) -> Self {
         self.context.evm.env.clear();
@@ -450,12 +465,12 @@ mod test {
     };
     use revm_interpreter::{gas, Host, Interpreter};
     use revm_precompile::PrecompileOutput;
-    use std::{cell::RefCell, rc::Rc, sync::Arc};
+    use std::sync::{Arc, Mutex};
 
     /// Custom evm context
     #[derive(Default, Clone, Debug)]
     pub(crate) struct CustomContext {
-        pub(crate) inner: Rc<RefCell<u8>>,
+        pub(crate) inner: Arc<Mutex<u8>>,
     }
 
     #[test]
@@ -466,7 +481,7 @@ mod test {
 
         // initialize the custom context and make sure it's zero
         let custom_context = CustomContext::default();
-        assert_eq!(*custom_context.inner.borrow(), 0);
+        assert_eq!(*custom_context.inner.lock().unwrap(), 0);
 
         let to_capture = custom_context.clone();
         let mut evm = Evm::builder()
@@ -484,7 +499,7 @@ mod test {
                 let custom_instruction = Box::new(
                     move |_interp: &mut Interpreter, _host: &mut Context<(), InMemoryDB>| {
                         // modify the value
-                        let mut inner = custom_context.inner.borrow_mut();
+                        let mut inner = custom_context.inner.lock().unwrap();
                         *inner += 1;
                     },
                 );
@@ -500,7 +515,7 @@ mod test {
         let _result_and_state = evm.transact().unwrap();
 
         // ensure the custom context was modified
-        assert_eq!(*custom_context.inner.borrow(), 1);
+        assert_eq!(*custom_context.inner.lock().unwrap(), 1);
     }
 
     #[test]
@@ -533,6 +548,146 @@ mod test {
         assert_eq!(result_and_state.result.gas_used(), EXPECTED_RESULT_GAS);
     }
 
+    #[test]
+    fn custom_gas_schedule() {
+        // PUSH1 1 PUSH1 2 ADD STOP
+        let code = Bytecode::new_raw([0x60, 0x01, 0x60, 0x02, 0x01, 0x00].into());
+        let code_hash = code.hash_slow();
+        let to_addr = address!("ffffffffffffffffffffffffffffffffffffffff");
+        let gas_used = |schedule: Option<gas::GasSchedule>| {
+            let mut builder = Evm::builder()
+                .with_db(InMemoryDB::default())
+                .modify_db(|db| {
+                    db.insert_account_info(
+                        to_addr,
+                        AccountInfo::new(U256::ZERO, 0, code_hash, code.clone()),
+                    )
+                })
+                .modify_tx_env(|tx| tx.transact_to = TxKind::Call(to_addr));
+            if let Some(schedule) = schedule {
+                builder = builder.with_gas_schedule(schedule);
+            }
+            builder.build().transact().unwrap().result.gas_used()
+        };
+
+        assert_eq!(gas_used(None), 21000 + 3 * 3);
+        let schedule = gas::GasSchedule {
+            verylow: 1,
+            tx_base: 1000,
+            ..gas::GasSchedule::for_spec(SpecId::LATEST)
+        };
+        assert_eq!(gas_used(Some(schedule)), 1000 + 3);
+
+        // PUSH1 1 PUSH1 0 RETURN, deploys one byte of code.
+        let create_gas_used = |schedule: gas::GasSchedule| {
+            Evm::builder()
+                .with_db(InMemoryDB::default())
+                .modify_tx_env(|tx| {
+                    tx.transact_to = TxKind::Create;
+                    tx.data = Bytes::from_static(&[0x60, 0x01, 0x60, 0x00, 0xf3]);
+                })
+                .with_gas_schedule(schedule)
+                .build()
+                .transact()
+                .unwrap()
+                .result
+                .gas_used()
+        };
+        let spec = gas::GasSchedule::for_spec(SpecId::LATEST);
+        let cheap = gas::GasSchedule {
+            code_deposit: 0,
+            tx_create: 50000,
+            initcode_word: 0,
+            tx_data_non_zero: 1,
+            ..spec
+        };
+        // One word of initcode and four non-zero bytes of data.
+        assert_eq!(
+            create_gas_used(spec) - create_gas_used(cheap),
+            200 + 3000 + 2 + 4 * 15
+        );
+    }
+
+    #[test]
+    fn record_reverted_logs() {
+        // PUSH1 0 PUSH1 0 LOG0 PUSH1 0 PUSH1 0 REVERT
+        let code = Bytecode::new_raw([0x60, 0, 0x60, 0, 0xa0, 0x60, 0, 0x60, 0, 0xfd].into());
+        let code_hash = code.hash_slow();
+        let to_addr = address!("ffffffffffffffffffffffffffffffffffffffff");
+        let reverted_logs = |record: bool| {
+            Evm::builder()
+                .with_db(InMemoryDB::default())
+                .modify_db(|db| {
+                    db.insert_account_info(
+                        to_addr,
+                        AccountInfo::new(U256::ZERO, 0, code_hash, code.clone()),
+                    )
+                })
+                .modify_tx_env(|tx| tx.transact_to = TxKind::Call(to_addr))
+                .with_record_reverted_logs(record)
+                .build()
+                .transact()
+                .unwrap()
+                .reverted_logs
+        };
+
+        assert!(reverted_logs(false).is_empty());
+        let logs = reverted_logs(true);
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].log.address, to_addr);
+        assert_eq!((logs[0].depth, logs[0].log_index), (1, 0));
+    }
+
+    #[test]
+    #[cfg(feature = "log_origins")]
+    fn log_origins() {
+        use crate::primitives::LogOrigin;
+
+        let (outer, inner) = (Address::with_last_byte(0xaa), Address::with_last_byte(0xbb));
+        // PUSH1 0 PUSH1 0 PUSH1 0 PUSH1 0 PUSH1 0 PUSH1 0xbb GAS CALL POP PUSH1 0 PUSH1 0 LOG0 STOP
+        let outer_code = Bytecode::new_raw(
+            [
+                0x60, 0, 0x60, 0, 0x60, 0, 0x60, 0, 0x60, 0, 0x60, 0xbb, 0x5a, 0xf1, 0x50, 0x60, 0,
+                0x60, 0, 0xa0, 0x00,
+            ]
+            .into(),
+        );
+        // PUSH1 0 PUSH1 0 LOG0 STOP
+        let inner_code = Bytecode::new_raw([0x60, 0, 0x60, 0, 0xa0, 0x00].into());
+        let result = Evm::builder()
+            .with_db(InMemoryDB::default())
+            .modify_db(|db| {
+                db.insert_account_info(outer, AccountInfo::from_bytecode(outer_code));
+                db.insert_account_info(inner, AccountInfo::from_bytecode(inner_code));
+            })
+            .modify_tx_env(|tx| tx.transact_to = TxKind::Call(outer))
+            .build()
+            .transact()
+            .unwrap();
+
+        assert!(result.result.is_success());
+        let logs = &result.logs_with_origins;
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].log.address, inner);
+        assert_eq!(
+            logs[0].origin,
+            LogOrigin {
+                index: 0,
+                depth: 2,
+                frames: vec![outer, inner],
+            }
+        );
+        assert_eq!(logs[1].log.address, outer);
+        assert_eq!(
+            logs[1].origin,
+            LogOrigin {
+                index: 1,
+                depth: 1,
+                frames: vec![outer],
+            }
+        );
+    }
+
     #[test]
     fn simple_build() {
         // build without external with latest spec
@@ -0,0 +1,91 @@
+//! Calldata statistics used to estimate the cost of posting transactions on L1.
+//!
+//! These are the routines of the Optimism L1 cost functions, usable by any L2 fee estimator.
+
+mod fast_lz;
+
+pub use fast_lz::flz_compress_len;
+
+/// Size and compressibility of calldata.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CalldataStats {
+    /// Number of zero bytes.
+    pub zero_bytes: u64,
+    /// Number of non-zero bytes.
+    pub non_zero_bytes: u64,
+    /// Length after FastLZ compression, see [`flz_compress_len`].
+    pub fastlz_size: u32,
+    /// Estimated length after brotli compression scaled by 1e6, see [`brotli_size_estimate`].
+    pub brotli_estimate: u64,
+}
+
+impl CalldataStats {
+    /// Returns the length of the calldata.
+    pub fn len(&self) -> u64 {
+        self.zero_bytes + self.non_zero_bytes
+    }
+
+    /// Returns `true` if the calldata is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the gas cost of the calldata, with the given cost per zero and non-zero byte.
+    pub fn byte_gas(&self, zero_byte_cost: u64, non_zero_byte_cost: u64) -> u64 {
+        self.zero_bytes
+            .saturating_mul(zero_byte_cost)
+            .saturating_add(self.non_zero_bytes.saturating_mul(non_zero_byte_cost))
+    }
+}
+
+/// Returns the statistics of the calldata.
+pub fn calldata_stats(input: &[u8]) -> CalldataStats {
+    let (zero_bytes, non_zero_bytes) = count_zero_bytes(input);
+    let fastlz_size = flz_compress_len(input);
+    CalldataStats {
+        zero_bytes,
+        non_zero_bytes,
+        fastlz_size,
+        brotli_estimate: brotli_size_estimate(fastlz_size),
+    }
+}
+
+/// Returns the number of zero and non-zero bytes of the input.
+pub fn count_zero_bytes(input: &[u8]) -> (u64, u64) {
+    let zero_bytes = input.iter().filter(|byte| **byte == 0).count() as u64;
+    (zero_bytes, input.len() as u64 - zero_bytes)
+}
+
+/// Estimates the length after brotli compression, scaled by 1e6, of data with the given FastLZ
+/// compressed length.
+///
+/// This is the linear regression of the Optimism Fjord L1 cost function:
+/// `max(minTransactionSize, intercept + fastlzCoef*fastlzSize)`.
+pub fn brotli_size_estimate(fastlz_size: u32) -> u64 {
+    (fastlz_size as u64)
+        .saturating_mul(836_500)
+        .saturating_sub(42_585_600)
+        .max(100_000_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::bytes;
+
+    #[test]
+    fn stats() {
+        let stats = calldata_stats(&bytes!("FA00CADE0000"));
+        assert_eq!(stats.zero_bytes, 3);
+        assert_eq!(stats.non_zero_bytes, 3);
+        assert_eq!(stats.len(), 6);
+        assert_eq!(stats.byte_gas(4, 16), 3 * 4 + 3 * 16);
+        // Below the minimum transaction size of 100 bytes.
+        assert_eq!(stats.brotli_estimate, 100_000_000);
+
+        assert!(calldata_stats(&[]).is_empty());
+        // estimatedSize = 836500*202 - 42585600 = 126387400
+        assert_eq!(brotli_size_estimate(202), 126_387_400);
+    }
+}
@@ -1,6 +1,6 @@
 /// Returns the length of the data after compression through FastLZ, based on
-// https://github.com/Vectorized/solady/blob/5315d937d79b335c668896d7533ac603adac5315/js/solady.js
-pub(crate) fn flz_compress_len(input: &[u8]) -> u32 {
+/// <https://github.com/Vectorized/solady/blob/5315d937d79b335c668896d7533ac603adac5315/js/solady.js>
+pub fn flz_compress_len(input: &[u8]) -> u32 {
     let mut idx: u32 = 2;
 
     let idx_limit: u32 = if input.len() < 13 {
@@ -0,0 +1,17 @@
+//! Ethereum Classic hardforks, monetary policy and handler register.
+//!
+//! Ethereum Classic hardforks activate subsets of the Ethereum EIPs, so each [ClassicHardfork]
+//! maps onto the closest [SpecId](crate::primitives::SpecId) and the
+//! [`classic_handle_register`] disables what Ethereum Classic did not adopt: EIP-1559 fees,
+//! the `BASEFEE` opcode and EIP-4399 `PREVRANDAO`.
+
+mod handler_register;
+mod hardfork;
+mod monetary_policy;
+
+pub use handler_register::{classic_handle_register, difficulty, reward_beneficiary, validate_env};
+pub use hardfork::{ClassicHardfork, CHAIN_ID};
+pub use monetary_policy::{
+    block_reward, block_reward_increments, era, ommer_inclusion_reward, ommer_reward, ERA_LENGTH,
+    INITIAL_BLOCK_REWARD,
+};
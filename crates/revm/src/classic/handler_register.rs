@@ -0,0 +1,190 @@
+//! Handler related to the Ethereum Classic chain.
+
+use super::ClassicHardfork;
+use crate::{
+    handler::{mainnet, register::EvmHandler},
+    interpreter::{instructions::control, opcode, Gas, Host, Interpreter},
+    primitives::{
+        db::Database, spec_to_generic, EVMError, Env, InvalidTransaction, LondonSpec, Spec, SpecId,
+        Transaction, U256,
+    },
+    Context,
+};
+use std::sync::Arc;
+
+/// Register that executes transactions with the Ethereum Classic rules.
+///
+/// Should be appended before the inspector handle register, so that inspectors see the
+/// replaced instructions.
+pub fn classic_handle_register<DB: Database, EXT>(handler: &mut EvmHandler<'_, EXT, DB>) {
+    spec_to_generic!(handler.cfg.spec_id, {
+        // EIP-1559 transactions are not supported.
+        handler.validation.env = Arc::new(validate_env::<SPEC, DB>);
+        // There is no base fee to burn.
+        handler.post_execution.reward_beneficiary = Arc::new(reward_beneficiary::<SPEC, EXT, DB>);
+    });
+    // EIP-3198 BASEFEE opcode was not adopted.
+    handler
+        .instruction_table
+        .insert(opcode::BASEFEE, control::unknown);
+    // Blocks are mined with proof of work, without EIP-4399 PREVRANDAO.
+    handler
+        .instruction_table
+        .insert(opcode::DIFFICULTY, difficulty);
+    // EIP-160 was activated by DieHard, before the Spurious Dragon spec that includes it.
+    if !handler.cfg.spec_id.is_enabled_in(SpecId::SPURIOUS_DRAGON) {
+        handler.instruction_table.insert(opcode::EXP, exp);
+    }
+}
+
+/// Validate environment for the Ethereum Classic chain.
+///
+/// Blocks are validated with the rules before the merge, as they do not have a `prevrandao`,
+/// and transactions can not set a priority fee.
+pub fn validate_env<SPEC: Spec, DB: Database>(env: &Env) -> Result<(), EVMError<DB::Error>> {
+    // Important: validate block before tx.
+    if SPEC::enabled(SpecId::MERGE) {
+        env.validate_block_env::<LondonSpec>()?;
+    } else {
+        env.validate_block_env::<SPEC>()?;
+    }
+    if env.tx.gas_priority_fee().is_some() {
+        return Err(InvalidTransaction::PriorityFeeNotSupported.into());
+    }
+    env.validate_tx::<SPEC>()?;
+    Ok(())
+}
+
+/// Reward beneficiary with the whole gas fee.
+#[inline]
+pub fn reward_beneficiary<SPEC: Spec, EXT, DB: Database>(
+    context: &mut Context<EXT, DB>,
+    gas: &Gas,
+) -> Result<(), EVMError<DB::Error>> {
+    let beneficiary = context.evm.env.block.coinbase;
+    let fee = context.evm.env.effective_gas_price() * U256::from(gas.used());
+    mainnet::credit_fee(context, beneficiary, fee)
+}
+
+/// `DIFFICULTY` opcode, returning the block difficulty in all specs.
+pub fn difficulty<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut H) {
+    crate::interpreter::gas!(interpreter, interpreter.gas_schedule.base);
+    crate::interpreter::push!(interpreter, host.env().block.difficulty);
+}
+
+/// `EXP` byte cost of EIP-160.
+const EIP160_EXP_BYTE: u64 = 50;
+
+/// `EXP` opcode, charging the EIP-160 byte cost from the [DieHard](ClassicHardfork::DieHard)
+/// activation block.
+pub fn exp<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut H) {
+    let die_hard =
+        host.env().block.number >= U256::from(ClassicHardfork::DieHard.activation_block());
+    crate::interpreter::pop_top!(interpreter, op1, op2);
+    let cost = if die_hard && !op2.is_zero() {
+        Some(interpreter.gas_schedule.exp + EIP160_EXP_BYTE * op2.byte_len() as u64)
+    } else {
+        interpreter.gas_schedule.exp_cost(*op2)
+    };
+    crate::interpreter::gas_or_fail!(interpreter, cost);
+    *op2 = op1.pow(*op2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        classic::ClassicHardfork,
+        db::BenchmarkDB,
+        primitives::{bytes, Address, Bytecode, ExecutionResult, HaltReason, TxKind},
+        Evm,
+    };
+
+    fn classic_evm(code: Bytecode, fork: ClassicHardfork) -> Evm<'static, (), BenchmarkDB> {
+        Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(code))
+            .with_spec_id(fork.spec_id())
+            .append_handler_register(classic_handle_register)
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+                tx.gas_price = U256::from(10);
+            })
+            .modify_block_env(|block| {
+                block.basefee = U256::from(7);
+                block.difficulty = U256::from(0x1234);
+                block.coinbase = Address::with_last_byte(0xcb);
+                block.number = U256::from(fork.activation_block());
+            })
+            .build()
+    }
+
+    #[test]
+    fn classic_rules() {
+        // DIFFICULTY PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+        let mut evm = classic_evm(
+            Bytecode::new_raw(bytes!("4460005260206000f3")),
+            ClassicHardfork::Spiral,
+        );
+        let result = evm.transact().unwrap();
+        let ExecutionResult::Success {
+            output, gas_used, ..
+        } = result.result
+        else {
+            panic!("execution failed: {:?}", result.result);
+        };
+        assert_eq!(U256::from_be_slice(output.data()), U256::from(0x1234));
+        // The coinbase receives the base fee too.
+        let coinbase = &result.state[&Address::with_last_byte(0xcb)];
+        assert_eq!(coinbase.info.balance, U256::from(10 * gas_used));
+
+        // EIP-1559 transactions are rejected.
+        evm.context.evm.env.tx.gas_priority_fee = Some(U256::from(1));
+        assert!(matches!(
+            evm.transact(),
+            Err(EVMError::Transaction(
+                InvalidTransaction::PriorityFeeNotSupported
+            ))
+        ));
+
+        // BASEFEE
+        let mut evm = classic_evm(Bytecode::new_raw(bytes!("4800")), ClassicHardfork::Spiral);
+        let result = evm.transact().unwrap().result;
+        assert!(matches!(
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::OpcodeNotFound,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn die_hard_exp_cost() {
+        // PUSH2 0x0100 PUSH1 2 EXP STOP, an exponent of two bytes.
+        let code = Bytecode::new_raw(bytes!("61010060020a00"));
+        let gas_used = |fork| {
+            let mut evm = classic_evm(code.clone(), fork);
+            evm.transact().unwrap().result.gas_used()
+        };
+        // EIP-160 raises the byte cost from 10 to 50.
+        let before = gas_used(ClassicHardfork::GasReprice);
+        assert_eq!(gas_used(ClassicHardfork::DieHard), before + 2 * 40);
+        assert_eq!(gas_used(ClassicHardfork::Atlantis), before + 2 * 40);
+    }
+
+    #[test]
+    fn skip_zero_fee_rewards() {
+        let coinbase = Address::with_last_byte(0xcb);
+        let touched = |skip_zero_fee_rewards| {
+            let mut evm = classic_evm(Bytecode::new_raw(bytes!("00")), ClassicHardfork::Spiral);
+            evm.context.evm.env.tx.gas_price = U256::ZERO;
+            evm.context.evm.env.block.basefee = U256::ZERO;
+            evm.context.evm.env.cfg.skip_zero_fee_rewards = skip_zero_fee_rewards;
+            evm.transact().unwrap().state.contains_key(&coinbase)
+        };
+        assert!(touched(false));
+        assert!(!touched(true));
+    }
+}
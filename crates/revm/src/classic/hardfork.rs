@@ -0,0 +1,129 @@
+use crate::primitives::SpecId;
+
+/// Chain id of Ethereum Classic mainnet.
+pub const CHAIN_ID: u64 = 61;
+
+/// Ethereum Classic hardforks, in activation order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ClassicHardfork {
+    Frontier,
+    Homestead,
+    /// EIP-150 gas cost changes.
+    GasReprice,
+    /// EIP-155 replay protection and EIP-160 `EXP` cost increase.
+    DieHard,
+    /// ECIP-1017 monetary policy.
+    Gotham,
+    /// ECIP-1041 difficulty bomb removal.
+    DefuseDifficultyBomb,
+    /// Spurious Dragon and Byzantium EIPs.
+    Atlantis,
+    /// Constantinople EIPs, without EIP-1283.
+    Agharta,
+    /// Istanbul EIPs.
+    Phoenix,
+    /// ECIP-1099 DAG size limit.
+    Thanos,
+    /// Berlin EIPs.
+    Magneto,
+    /// London EIPs EIP-3529 and EIP-3541.
+    Mystique,
+    /// Shanghai EIPs EIP-3651, EIP-3855 and EIP-3860.
+    Spiral,
+}
+
+impl ClassicHardfork {
+    /// All hardforks, in activation order.
+    pub const ALL: [Self; 13] = [
+        Self::Frontier,
+        Self::Homestead,
+        Self::GasReprice,
+        Self::DieHard,
+        Self::Gotham,
+        Self::DefuseDifficultyBomb,
+        Self::Atlantis,
+        Self::Agharta,
+        Self::Phoenix,
+        Self::Thanos,
+        Self::Magneto,
+        Self::Mystique,
+        Self::Spiral,
+    ];
+
+    /// Returns the block at which the hardfork activated on Ethereum Classic mainnet.
+    pub const fn activation_block(self) -> u64 {
+        match self {
+            Self::Frontier => 0,
+            Self::Homestead => 1_150_000,
+            Self::GasReprice => 2_500_000,
+            Self::DieHard => 3_000_000,
+            Self::Gotham => 5_000_000,
+            Self::DefuseDifficultyBomb => 5_900_000,
+            Self::Atlantis => 8_772_000,
+            Self::Agharta => 9_573_000,
+            Self::Phoenix => 10_500_839,
+            Self::Thanos => 11_700_000,
+            Self::Magneto => 13_189_133,
+            Self::Mystique => 14_525_000,
+            Self::Spiral => 19_250_000,
+        }
+    }
+
+    /// Returns the hardfork active at the given Ethereum Classic mainnet block.
+    pub fn from_block(number: u64) -> Self {
+        Self::ALL
+            .into_iter()
+            .rev()
+            .find(|fork| fork.activation_block() <= number)
+            .unwrap_or(Self::Frontier)
+    }
+
+    /// Returns the spec the hardfork executes transactions with.
+    ///
+    /// The EIPs of the spec that Ethereum Classic did not adopt are disabled by the
+    /// [`classic_handle_register`](super::classic_handle_register). It also charges the EIP-160
+    /// `EXP` cost of [DieHard](Self::DieHard), which is part of the later Spurious Dragon spec,
+    /// from the DieHard activation block.
+    pub const fn spec_id(self) -> SpecId {
+        match self {
+            Self::Frontier => SpecId::FRONTIER,
+            Self::Homestead => SpecId::HOMESTEAD,
+            Self::GasReprice | Self::DieHard | Self::Gotham | Self::DefuseDifficultyBomb => {
+                SpecId::TANGERINE
+            }
+            Self::Atlantis => SpecId::BYZANTIUM,
+            Self::Agharta => SpecId::PETERSBURG,
+            Self::Phoenix | Self::Thanos => SpecId::ISTANBUL,
+            Self::Magneto => SpecId::BERLIN,
+            Self::Mystique => SpecId::LONDON,
+            Self::Spiral => SpecId::SHANGHAI,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hardfork_from_block() {
+        assert_eq!(ClassicHardfork::from_block(0), ClassicHardfork::Frontier);
+        assert_eq!(
+            ClassicHardfork::from_block(8_771_999),
+            ClassicHardfork::DefuseDifficultyBomb
+        );
+        assert_eq!(
+            ClassicHardfork::from_block(8_772_000),
+            ClassicHardfork::Atlantis
+        );
+        assert_eq!(
+            ClassicHardfork::from_block(u64::MAX).spec_id(),
+            SpecId::SHANGHAI
+        );
+        // Activation blocks and specs never go backwards.
+        for forks in ClassicHardfork::ALL.windows(2) {
+            assert!(forks[0].activation_block() < forks[1].activation_block());
+            assert!(forks[0].spec_id() <= forks[1].spec_id());
+        }
+    }
+}
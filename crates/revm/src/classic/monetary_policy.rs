@@ -0,0 +1,92 @@
+//! ECIP-1017 monetary policy, see <https://ecips.ethereumclassic.org/ECIPs/ecip-1017>.
+use crate::primitives::Address;
+use std::vec::Vec;
+
+/// Number of blocks in an era, after which the block reward is reduced by 20%.
+pub const ERA_LENGTH: u64 = 5_000_000;
+
+/// Block reward of the first era, 5 ETC.
+pub const INITIAL_BLOCK_REWARD: u128 = 5_000_000_000_000_000_000;
+
+/// Returns the era of the block, starting at `0` for the first [ERA_LENGTH] blocks.
+#[inline]
+pub const fn era(block: u64) -> u64 {
+    block.saturating_sub(1) / ERA_LENGTH
+}
+
+/// Returns the reward of the miner of the block, excluding transaction fees and ommer inclusion
+/// rewards.
+pub fn block_reward(block: u64) -> u128 {
+    let mut reward = INITIAL_BLOCK_REWARD;
+    for _ in 0..era(block) {
+        if reward == 0 {
+            break;
+        }
+        reward = reward * 4 / 5;
+    }
+    reward
+}
+
+/// Returns the reward of the miner of an ommer mined at `ommer_block` and included in `block`.
+///
+/// The first era uses the Ethereum reward that decreases with the ommer distance, later eras pay
+/// 1/32 of the block reward.
+pub fn ommer_reward(block: u64, ommer_block: u64) -> u128 {
+    let reward = block_reward(block);
+    if era(block) == 0 {
+        let depth = block.saturating_sub(ommer_block).min(8) as u128;
+        (8 - depth) * reward / 8
+    } else {
+        reward / 32
+    }
+}
+
+/// Returns the reward of the miner of the block for each included ommer.
+#[inline]
+pub fn ommer_inclusion_reward(block: u64) -> u128 {
+    block_reward(block) / 32
+}
+
+/// Returns the balance increments rewarding the miner of the block and the miners of its ommers,
+/// given as `(beneficiary, block number)`.
+///
+/// The increments can be applied with [State::increment_balances](crate::State::increment_balances)
+/// after executing the transactions of the block.
+pub fn block_reward_increments(
+    block: u64,
+    beneficiary: Address,
+    ommers: &[(Address, u64)],
+) -> Vec<(Address, u128)> {
+    let miner_reward = block_reward(block) + ommer_inclusion_reward(block) * ommers.len() as u128;
+    core::iter::once((beneficiary, miner_reward))
+        .chain(ommers.iter().map(|&(ommer_beneficiary, ommer_block)| {
+            (ommer_beneficiary, ommer_reward(block, ommer_block))
+        }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ETC: u128 = 1_000_000_000_000_000_000;
+
+    #[test]
+    fn ecip1017_rewards() {
+        assert_eq!(block_reward(1), 5 * ETC);
+        assert_eq!(block_reward(5_000_000), 5 * ETC);
+        assert_eq!(block_reward(5_000_001), 4 * ETC);
+        assert_eq!(block_reward(10_000_001), 32 * ETC / 10);
+        assert_eq!(block_reward(u64::MAX), 0);
+
+        // Ethereum ommer rewards in the first era.
+        assert_eq!(ommer_reward(100, 99), 5 * ETC * 7 / 8);
+        assert_eq!(ommer_reward(5_000_001, 5_000_000), 4 * ETC / 32);
+
+        let (miner, ommer) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        assert_eq!(
+            block_reward_increments(5_000_001, miner, &[(ommer, 4_999_999)]),
+            [(miner, 4 * ETC + 4 * ETC / 32), (ommer, 4 * ETC / 32)]
+        );
+    }
+}
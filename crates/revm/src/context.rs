@@ -8,14 +8,13 @@ pub use context_precompiles::{
 };
 pub use evm_context::EvmContext;
 pub use inner_evm_context::InnerEvmContext;
-use revm_interpreter::as_usize_saturated;
 
 use crate::{
     db::{Database, EmptyDB},
     interpreter::{Host, LoadAccountResult, SStoreResult, SelfDestructResult},
-    primitives::{Address, Bytes, Env, HandlerCfg, Log, B256, BLOCK_HASH_HISTORY, U256},
+    primitives::{Address, Bytes, Env, HandlerCfg, Log, B256, U256},
 };
-use std::boxed::Box;
+use std::{boxed::Box, vec::Vec};
 
 /// Main Context structure that contains both EvmContext and External context.
 pub struct Context<EXT, DB: Database> {
@@ -68,6 +67,12 @@ impl<EXT, DB: Database> Context<EXT, DB> {
     pub fn new(evm: EvmContext<DB>, external: EXT) -> Context<EXT, DB> {
         Context { evm, external }
     }
+
+    /// Emits a log from the given address. See [`InnerEvmContext::emit_log`].
+    #[inline]
+    pub fn emit_log(&mut self, address: Address, topics: Vec<B256>, data: Bytes) -> bool {
+        self.evm.emit_log(address, topics, data)
+    }
 }
 
 /// Context with handler configuration.
@@ -97,6 +102,19 @@ where
     }
 }
 
+impl<EXT, DB: Database> Context<EXT, DB> {
+    /// Returns `None`, stopping the interpreter, if the host operation that returned `result`
+    /// grew the journal past [`CfgEnv::journal_limit`]. The frame is then halted with
+    /// [`HaltReason::JournalLimitExceeded`].
+    ///
+    /// [`CfgEnv::journal_limit`]: crate::primitives::CfgEnv::journal_limit
+    /// [`HaltReason::JournalLimitExceeded`]: crate::primitives::HaltReason::JournalLimitExceeded
+    #[inline]
+    fn check_journal_limit<T>(&self, result: Option<T>) -> Option<T> {
+        result.filter(|_| !self.evm.is_journal_limit_exceeded())
+    }
+}
+
 impl<EXT, DB: Database> Host for Context<EXT, DB> {
     /// Returns reference to Environment.
     #[inline]
@@ -108,90 +126,164 @@ impl<EXT, DB: Database> Host for Context<EXT, DB> {
         &mut self.evm.env
     }
 
+    /// Returns the block hash from the database.
+    ///
+    /// The `BLOCKHASH` lookback window, see [`CfgEnv::block_hash_window`], is enforced by the
+    /// instruction so `number` is passed to the database unchanged.
+    ///
+    /// [`CfgEnv::block_hash_window`]: crate::primitives::CfgEnv::block_hash_window
     fn block_hash(&mut self, number: u64) -> Option<B256> {
-        let block_number = as_usize_saturated!(self.env().block.number);
-        let requested_number = usize::try_from(number).unwrap_or(usize::MAX);
-
-        let Some(diff) = block_number.checked_sub(requested_number) else {
-            return Some(B256::ZERO);
-        };
-
-        // blockhash should push zero if number is same as current block number.
-        if diff == 0 {
-            return Some(B256::ZERO);
-        }
-
-        if diff <= BLOCK_HASH_HISTORY {
-            return self
-                .evm
-                .block_hash(number)
-                .map_err(|e| self.evm.error = Err(e))
-                .ok();
-        }
-
-        Some(B256::ZERO)
+        self.evm
+            .block_hash(number)
+            .map_err(|e| self.evm.error = Err(e))
+            .ok()
     }
 
     fn load_account(&mut self, address: Address) -> Option<LoadAccountResult> {
-        self.evm
+        let result = self
+            .evm
             .load_account_exist(address)
             .map_err(|e| self.evm.error = Err(e))
-            .ok()
+            .ok();
+        self.check_journal_limit(result)
     }
 
     fn balance(&mut self, address: Address) -> Option<(U256, bool)> {
-        self.evm
+        let result = self
+            .evm
             .balance(address)
             .map_err(|e| self.evm.error = Err(e))
-            .ok()
+            .ok();
+        self.check_journal_limit(result)
     }
 
     fn code(&mut self, address: Address) -> Option<(Bytes, bool)> {
-        self.evm
+        let result = self
+            .evm
             .code(address)
             .map_err(|e| self.evm.error = Err(e))
-            .ok()
+            .ok();
+        self.check_journal_limit(result)
     }
 
     fn code_hash(&mut self, address: Address) -> Option<(B256, bool)> {
-        self.evm
+        let result = self
+            .evm
             .code_hash(address)
             .map_err(|e| self.evm.error = Err(e))
-            .ok()
+            .ok();
+        self.check_journal_limit(result)
     }
 
     fn sload(&mut self, address: Address, index: U256) -> Option<(U256, bool)> {
-        self.evm
+        let result = self
+            .evm
             .sload(address, index)
             .map_err(|e| self.evm.error = Err(e))
-            .ok()
+            .ok();
+        self.check_journal_limit(result)
     }
 
     fn sstore(&mut self, address: Address, index: U256, value: U256) -> Option<SStoreResult> {
-        self.evm
+        let result = self
+            .evm
             .sstore(address, index, value)
             .map_err(|e| self.evm.error = Err(e))
-            .ok()
+            .ok();
+        self.check_journal_limit(result)
     }
 
     fn tload(&mut self, address: Address, index: U256) -> U256 {
         self.evm.tload(address, index)
     }
 
-    fn tstore(&mut self, address: Address, index: U256, value: U256) {
-        self.evm.tstore(address, index, value)
+    fn tstore(&mut self, address: Address, index: U256, value: U256) -> Option<()> {
+        self.evm.tstore(address, index, value);
+        self.check_journal_limit(Some(()))
     }
 
-    fn log(&mut self, log: Log) {
+    fn log(&mut self, log: Log) -> Option<()> {
         self.evm.journaled_state.log(log);
+        self.check_journal_limit(Some(()))
     }
 
-    fn selfdestruct(&mut self, address: Address, target: Address) -> Option<SelfDestructResult> {
+    #[cfg(feature = "keccak_preimages")]
+    fn record_keccak_preimage(&mut self, input: &[u8], hash: B256) {
         self.evm
+            .journaled_state
+            .keccak_preimages
+            .entry(hash)
+            .or_insert_with(|| Bytes::copy_from_slice(input));
+    }
+
+    fn selfdestruct(&mut self, address: Address, target: Address) -> Option<SelfDestructResult> {
+        let result = self
+            .evm
             .inner
             .journaled_state
             .selfdestruct(address, target, &mut self.evm.inner.db)
             .map_err(|e| self.evm.error = Err(e))
-            .ok()
+            .ok();
+        self.check_journal_limit(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        db::InMemoryDB,
+        primitives::{keccak256, AccountInfo, Address, Bytecode, Bytes, TxKind, B256, U256},
+        Evm,
+    };
+
+    /// Executes `BLOCKHASH(number)` in block `block_number` and returns the pushed hash.
+    fn block_hash(block_number: u64, window: Option<u64>, number: u16) -> B256 {
+        // PUSH2 number BLOCKHASH PUSH1 0 MSTORE PUSH1 0x20 PUSH1 0 RETURN
+        let [hi, lo] = number.to_be_bytes();
+        let code = Bytes::from(vec![
+            0x61, hi, lo, 0x40, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3,
+        ]);
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            Address::ZERO,
+            AccountInfo::from_bytecode(Bytecode::new_raw(code)),
+        );
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .modify_block_env(|block| block.number = U256::from(block_number))
+            .modify_cfg_env(|cfg| cfg.block_hash_window = window)
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+            })
+            .build();
+        let output = evm.transact().unwrap().result.into_output().unwrap();
+        B256::from_slice(&output)
+    }
+
+    /// Hash the database returns for `number`, see `EmptyDB`.
+    fn db_hash(number: u64) -> B256 {
+        keccak256(number.to_string().as_bytes())
+    }
+
+    #[test]
+    fn block_hash_default_window() {
+        assert_eq!(block_hash(1000, None, 999), db_hash(999));
+        assert_eq!(block_hash(1000, None, 744), db_hash(744));
+        assert_eq!(block_hash(1000, None, 743), B256::ZERO);
+        // The current block and future blocks are zero.
+        assert_eq!(block_hash(1000, None, 1000), B256::ZERO);
+        assert_eq!(block_hash(1000, None, 1001), B256::ZERO);
+    }
+
+    #[test]
+    fn block_hash_configured_window() {
+        // A wider window reaches further back and the number reaches the database unchanged.
+        assert_eq!(block_hash(1000, Some(1000), 743), db_hash(743));
+        assert_eq!(block_hash(1000, Some(1000), 0), db_hash(0));
+        // A narrower window cuts off blocks inside the default one.
+        assert_eq!(block_hash(1000, Some(8), 992), db_hash(992));
+        assert_eq!(block_hash(1000, Some(8), 991), B256::ZERO);
     }
 }
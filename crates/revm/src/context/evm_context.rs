@@ -4,16 +4,17 @@ use revm_precompile::PrecompileErrors;
 use super::inner_evm_context::InnerEvmContext;
 use crate::{
     db::Database,
+    inspector::InspectorInputs,
     interpreter::{
-        analysis::validate_eof, return_ok, CallInputs, Contract, CreateInputs, EOFCreateInputs,
-        EOFCreateKind, Gas, InstructionResult, Interpreter, InterpreterResult,
+        analysis::validate_eof, create2_address, return_ok, CallInputs, CallOutcome, Contract,
+        CreateInputs, EOFCreateInputs, EOFCreateKind, Gas, InstructionResult, Interpreter,
+        InterpreterResult,
     },
     primitives::{
-        keccak256, Address, Bytecode, Bytes, CreateScheme, EVMError, Env, Eof,
-        SpecId::{self, *},
-        B256, EOF_MAGIC_BYTES,
+        keccak256, Address, Bytecode, Bytes, CreateScheme, EVMError, Eip, Env, Eof, SpecId,
+        Transaction, B256, EOF_MAGIC_BYTES,
     },
-    ContextPrecompiles, FrameOrResult, CALL_STACK_LIMIT,
+    ContextPrecompiles, FrameOrResult, FrameResult, FrameTags, CALL_STACK_LIMIT,
 };
 use core::{
     fmt,
@@ -27,6 +28,10 @@ pub struct EvmContext<DB: Database> {
     pub inner: InnerEvmContext<DB>,
     /// Precompiles that are available for evm.
     pub precompiles: ContextPrecompiles<DB>,
+    /// Data attached by inspectors to the active frames.
+    pub frame_tags: FrameTags,
+    /// Inputs of the active frames, used by the inspector handle register.
+    pub(crate) inspector_inputs: InspectorInputs,
 }
 
 impl<DB: Database + Clone> Clone for EvmContext<DB>
@@ -37,6 +42,8 @@ where
         Self {
             inner: self.inner.clone(),
             precompiles: ContextPrecompiles::default(),
+            frame_tags: FrameTags::default(),
+            inspector_inputs: InspectorInputs::default(),
         }
     }
 }
@@ -74,6 +81,8 @@ impl<DB: Database> EvmContext<DB> {
         Self {
             inner: InnerEvmContext::new(db),
             precompiles: ContextPrecompiles::default(),
+            frame_tags: FrameTags::default(),
+            inspector_inputs: InspectorInputs::default(),
         }
     }
 
@@ -83,6 +92,8 @@ impl<DB: Database> EvmContext<DB> {
         Self {
             inner: InnerEvmContext::new_with_env(db, env),
             precompiles: ContextPrecompiles::default(),
+            frame_tags: FrameTags::default(),
+            inspector_inputs: InspectorInputs::default(),
         }
     }
 
@@ -94,6 +105,8 @@ impl<DB: Database> EvmContext<DB> {
         EvmContext {
             inner: self.inner.with_db(db),
             precompiles: ContextPrecompiles::default(),
+            frame_tags: FrameTags::default(),
+            inspector_inputs: InspectorInputs::default(),
         }
     }
 
@@ -122,11 +135,7 @@ impl<DB: Database> EvmContext<DB> {
             return Ok(None);
         };
 
-        let mut result = InterpreterResult {
-            result: InstructionResult::Return,
-            gas,
-            output: Bytes::new(),
-        };
+        let mut result = InterpreterResult::new(InstructionResult::Return, Bytes::new(), gas);
 
         match outcome {
             Ok(output) => {
@@ -157,22 +166,31 @@ impl<DB: Database> EvmContext<DB> {
     ) -> Result<FrameOrResult, EVMError<DB::Error>> {
         let gas = Gas::new(inputs.gas_limit);
 
-        let return_result = |instruction_result: InstructionResult| {
-            Ok(FrameOrResult::new_call_result(
-                InterpreterResult {
-                    result: instruction_result,
-                    gas,
-                    output: Bytes::new(),
-                },
-                inputs.return_memory_offset.clone(),
+        let call_result = |result: InterpreterResult| {
+            FrameOrResult::Result(FrameResult::Call(
+                CallOutcome::new(result, inputs.return_memory_offset.clone())
+                    .with_static(inputs.is_static),
             ))
         };
+        let return_result = |instruction_result: InstructionResult| {
+            Ok(call_result(InterpreterResult::new(
+                instruction_result,
+                Bytes::new(),
+                gas,
+            )))
+        };
 
         // Check depth
         if self.journaled_state.depth() > CALL_STACK_LIMIT {
             return return_result(InstructionResult::CallTooDeep);
         }
 
+        // EOF bytecode can only call with `EXT*CALL`. Validated EOF code never contains the
+        // legacy call opcodes, so this is checked here once instead of in the instructions.
+        if inputs.caller_is_eof && !inputs.scheme.is_ext() {
+            return return_result(InstructionResult::LegacyOpcodeDisabledInEOF);
+        }
+
         // Make account warm and loaded
         let _ = self
             .inner
@@ -181,6 +199,8 @@ impl<DB: Database> EvmContext<DB> {
 
         // Create subroutine checkpoint
         let checkpoint = self.journaled_state.checkpoint();
+        #[cfg(feature = "log_origins")]
+        self.journaled_state.enter_frame(inputs.target_address);
 
         // Touch address. For "EIP-158 State Clear", this will erase empty accounts.
         match inputs.value {
@@ -211,10 +231,7 @@ impl<DB: Database> EvmContext<DB> {
             } else {
                 self.journaled_state.checkpoint_revert(checkpoint);
             }
-            Ok(FrameOrResult::new_call_result(
-                result,
-                inputs.return_memory_offset.clone(),
-            ))
+            Ok(call_result(result))
         } else {
             let (account, _) = self
                 .inner
@@ -228,6 +245,7 @@ impl<DB: Database> EvmContext<DB> {
             if inputs.scheme.is_ext_delegate_call()
                 && !bytecode.bytes_slice().starts_with(&EOF_MAGIC_BYTES)
             {
+                self.journaled_state.checkpoint_revert(checkpoint);
                 return return_result(InstructionResult::InvalidExtDelegateCallTarget);
             }
 
@@ -238,11 +256,30 @@ impl<DB: Database> EvmContext<DB> {
 
             let contract =
                 Contract::new_with_context(inputs.input.clone(), bytecode, Some(code_hash), inputs);
+            #[cfg(feature = "legacy_stack_verification")]
+            let contract = {
+                let mut contract = contract;
+                if self.env.cfg.is_legacy_stack_verification_enabled() {
+                    // The verdict only depends on the code, verify each code hash once.
+                    contract.stack_verified = *self
+                        .inner
+                        .journaled_state
+                        .stack_verified
+                        .entry(code_hash)
+                        .or_insert_with(|| contract.verify_stack());
+                }
+                contract
+            };
+            if inputs.is_static && self.journaled_state.static_depth.is_none() {
+                self.journaled_state.static_depth = Some(self.journaled_state.depth);
+            }
             // Create interpreter and executes call and push new CallStackFrame.
             Ok(FrameOrResult::new_call_frame(
                 inputs.return_memory_offset.clone(),
                 checkpoint,
-                Interpreter::new(contract, gas.limit(), inputs.is_static),
+                Interpreter::new(contract, gas.limit(), inputs.is_static)
+                    .with_spec_id(self.spec_id())
+                    .with_gas_schedule(self.gas_schedule_for(self.spec_id())),
             ))
         }
     }
@@ -256,11 +293,7 @@ impl<DB: Database> EvmContext<DB> {
     ) -> Result<FrameOrResult, EVMError<DB::Error>> {
         let return_error = |e| {
             Ok(FrameOrResult::new_create_result(
-                InterpreterResult {
-                    result: e,
-                    gas: Gas::new(inputs.gas_limit),
-                    output: Bytes::new(),
-                },
+                InterpreterResult::new(e, Bytes::new(), Gas::new(inputs.gas_limit)),
                 None,
             ))
         };
@@ -270,8 +303,15 @@ impl<DB: Database> EvmContext<DB> {
             return return_error(InstructionResult::CallTooDeep);
         }
 
+        // EOF bytecode can only create with `EOFCREATE`, legacy initcode is rejected.
+        if inputs.caller_is_eof {
+            return return_error(InstructionResult::LegacyOpcodeDisabledInEOF);
+        }
+
         // Prague EOF
-        if spec_id.is_enabled_in(PRAGUE_EOF) && inputs.init_code.starts_with(&EOF_MAGIC_BYTES) {
+        if self.env.cfg.is_eip_enabled(spec_id, Eip::Eip7692)
+            && inputs.init_code.starts_with(&EOF_MAGIC_BYTES)
+        {
             return return_error(InstructionResult::CreateInitCodeStartingEF00);
         }
 
@@ -297,7 +337,7 @@ impl<DB: Database> EvmContext<DB> {
             CreateScheme::Create => inputs.caller.create(old_nonce),
             CreateScheme::Create2 { salt } => {
                 init_code_hash = keccak256(&inputs.init_code);
-                inputs.caller.create2(salt.to_be_bytes(), init_code_hash)
+                create2_address(inputs.caller, salt, init_code_hash)
             }
         };
 
@@ -337,7 +377,9 @@ impl<DB: Database> EvmContext<DB> {
         Ok(FrameOrResult::new_create_frame(
             created_address,
             checkpoint,
-            Interpreter::new(contract, inputs.gas_limit, false),
+            Interpreter::new(contract, inputs.gas_limit, false)
+                .with_spec_id(spec_id)
+                .with_gas_schedule(self.gas_schedule_for(spec_id)),
         ))
     }
 
@@ -350,11 +392,7 @@ impl<DB: Database> EvmContext<DB> {
     ) -> Result<FrameOrResult, EVMError<DB::Error>> {
         let return_error = |e| {
             Ok(FrameOrResult::new_eofcreate_result(
-                InterpreterResult {
-                    result: e,
-                    gas: Gas::new(inputs.gas_limit),
-                    output: Bytes::new(),
-                },
+                InterpreterResult::new(e, Bytes::new(), Gas::new(inputs.gas_limit)),
                 None,
             ))
         };
@@ -384,8 +422,8 @@ impl<DB: Database> EvmContext<DB> {
                 let nonce = self
                     .env
                     .tx
-                    .nonce
-                    .map(|nonce| self.env.tx.caller.create(nonce));
+                    .nonce()
+                    .map(|nonce| self.env.tx.caller().create(nonce));
 
                 (input, eof, nonce)
             }
@@ -445,7 +483,9 @@ impl<DB: Database> EvmContext<DB> {
             inputs.value,
         );
 
-        let mut interpreter = Interpreter::new(contract, inputs.gas_limit, false);
+        let mut interpreter = Interpreter::new(contract, inputs.gas_limit, false)
+            .with_spec_id(spec_id)
+            .with_gas_schedule(self.gas_schedule_for(spec_id));
         // EOF init will enable RETURNCONTRACT opcode.
         interpreter.set_is_eof_init();
 
@@ -482,6 +522,7 @@ pub(crate) mod test_utils {
             value: CallValue::Transfer(U256::ZERO),
             scheme: revm_interpreter::CallScheme::Call,
             is_eof: false,
+            caller_is_eof: false,
             is_static: false,
             return_memory_offset: 0..0,
         }
@@ -515,14 +556,17 @@ pub(crate) mod test_utils {
         EvmContext {
             inner: InnerEvmContext {
                 env,
-                journaled_state: JournaledState::new(SpecId::CANCUN, HashSet::new()),
+                journaled_state: JournaledState::new(SpecId::CANCUN, HashSet::default()),
                 db,
                 error: Ok(()),
                 valid_authorizations: Vec::new(),
+                gas_schedule: None,
                 #[cfg(feature = "optimism")]
                 l1_block_info: None,
             },
             precompiles: ContextPrecompiles::default(),
+            frame_tags: FrameTags::default(),
+            inspector_inputs: InspectorInputs::default(),
         }
     }
 
@@ -531,14 +575,17 @@ pub(crate) mod test_utils {
         EvmContext {
             inner: InnerEvmContext {
                 env,
-                journaled_state: JournaledState::new(SpecId::CANCUN, HashSet::new()),
+                journaled_state: JournaledState::new(SpecId::CANCUN, HashSet::default()),
                 db,
                 error: Ok(()),
                 valid_authorizations: Default::default(),
+                gas_schedule: None,
                 #[cfg(feature = "optimism")]
                 l1_block_info: None,
             },
             precompiles: ContextPrecompiles::default(),
+            frame_tags: FrameTags::default(),
+            inspector_inputs: InspectorInputs::default(),
         }
     }
 }
@@ -555,6 +602,57 @@ mod tests {
     use std::boxed::Box;
     use test_utils::*;
 
+    #[test]
+    fn test_emit_log_reverts_with_frame() {
+        let mut context =
+            test_utils::create_empty_evm_context(Box::<Env>::default(), EmptyDB::default());
+        let address = address!("dead10000000000000000000000000000001dead");
+        let checkpoint = context.journaled_state.checkpoint();
+        assert!(context.emit_log(address, vec![B256::ZERO], Bytes::new()));
+        assert!(!context.emit_log(address, vec![B256::ZERO; 5], Bytes::new()));
+        assert_eq!(context.journaled_state.logs.len(), 1);
+        context.journaled_state.checkpoint_revert(checkpoint);
+        assert!(context.journaled_state.logs.is_empty());
+    }
+
+    #[test]
+    fn test_extcode_of_delegated_and_eof_accounts() {
+        use crate::primitives::{delegation_designator, AccountInfo, EOF_MAGIC_HASH};
+
+        let authority = address!("00000000000000000000000000000000000000a0");
+        let delegate = address!("00000000000000000000000000000000000000de");
+        let eof = address!("00000000000000000000000000000000000000ef");
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00]));
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(delegate, AccountInfo::from_bytecode(code.clone()));
+        db.insert_account_info(
+            eof,
+            AccountInfo::from_bytecode(Bytecode::Eof(Arc::new(Eof::default()))),
+        );
+        let mut context = create_cache_db_evm_context(Box::<Env>::default(), db);
+        // What the EIP-7702 pre-execution does for a valid authorization.
+        context
+            .inner
+            .journaled_state
+            .load_account(authority, &mut context.inner.db)
+            .unwrap();
+        context.journaled_state.set_code(authority, code.clone());
+        context.valid_authorizations.push((authority, delegate));
+
+        let designator = delegation_designator(delegate);
+        assert_eq!(context.code(authority).unwrap().0, designator);
+        assert_eq!(
+            context.code_hash(authority).unwrap().0,
+            keccak256(&designator)
+        );
+        assert_eq!(context.code(eof).unwrap().0, EOF_MAGIC_BYTES);
+        assert_eq!(context.code_hash(eof).unwrap().0, EOF_MAGIC_HASH);
+
+        context.env.cfg.expose_delegated_code = true;
+        assert_eq!(context.code(authority).unwrap().0, code.original_bytes());
+        assert_eq!(context.code_hash(authority).unwrap().0, code.hash_slow());
+    }
+
     // Tests that the `EVMContext::make_call_frame` function returns an error if the
     // call stack is too deep.
     #[test]
@@ -599,6 +697,67 @@ mod tests {
         assert_eq!(evm_context.journaled_state.depth, 0);
     }
 
+    // Tests that EXTDELEGATECALL to a non-EOF contract fails and reverts the created checkpoint.
+    #[test]
+    fn test_make_call_frame_ext_delegate_call_to_legacy() {
+        let env = Env::default();
+        let db = EmptyDB::default();
+        let mut evm_context = test_utils::create_empty_evm_context(Box::new(env), db);
+        let contract = address!("dead10000000000000000000000000000001dead");
+        let mut call_inputs = test_utils::create_mock_call_inputs(contract);
+        call_inputs.scheme = revm_interpreter::CallScheme::ExtDelegateCall;
+        call_inputs.value = CallValue::Apparent(U256::ZERO);
+        call_inputs.is_eof = true;
+        call_inputs.caller_is_eof = true;
+        let res = evm_context.make_call_frame(&call_inputs);
+        let Ok(FrameOrResult::Result(result)) = res else {
+            panic!("Expected FrameOrResult::Result");
+        };
+        assert_eq!(
+            result.interpreter_result().result,
+            InstructionResult::InvalidExtDelegateCallTarget
+        );
+        assert_eq!(evm_context.journaled_state.depth, 0);
+    }
+
+    // Tests that legacy calls and creates initiated from EOF bytecode are rejected before a
+    // checkpoint is created.
+    #[test]
+    fn test_make_frame_legacy_from_eof() {
+        let env = Env::default();
+        let db = EmptyDB::default();
+        let mut evm_context = test_utils::create_empty_evm_context(Box::new(env), db);
+        let contract = address!("dead10000000000000000000000000000001dead");
+        let mut call_inputs = test_utils::create_mock_call_inputs(contract);
+        call_inputs.caller_is_eof = true;
+        let Ok(FrameOrResult::Result(result)) = evm_context.make_call_frame(&call_inputs) else {
+            panic!("Expected FrameOrResult::Result");
+        };
+        assert_eq!(
+            result.interpreter_result().result,
+            InstructionResult::LegacyOpcodeDisabledInEOF
+        );
+
+        let mut create_inputs = CreateInputs::builder()
+            .caller(contract)
+            .init_code(Bytes::from_static(&[0x00]))
+            .gas(100_000)
+            .build()
+            .unwrap();
+        create_inputs.caller_is_eof = true;
+        let Ok(FrameOrResult::Result(result)) =
+            evm_context.make_create_frame(SpecId::CANCUN, &create_inputs)
+        else {
+            panic!("Expected FrameOrResult::Result");
+        };
+        assert_eq!(
+            result.interpreter_result().result,
+            InstructionResult::LegacyOpcodeDisabledInEOF
+        );
+        assert_eq!(evm_context.journaled_state.journal, vec![Vec::new()]);
+        assert_eq!(evm_context.journaled_state.depth, 0);
+    }
+
     #[test]
     fn test_make_call_frame_missing_code_context() {
         let env = Env::default();
@@ -638,4 +797,39 @@ mod tests {
         };
         assert_eq!(call_frame.return_memory_range, 0..0,);
     }
+
+    #[test]
+    #[cfg(feature = "legacy_stack_verification")]
+    fn test_make_call_frame_caches_stack_verification() {
+        let mut env = Env::default();
+        env.cfg.verify_legacy_stack = true;
+        let mut cdb = CacheDB::new(EmptyDB::default());
+        // PUSH1 0 POP STOP
+        let by = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00, 0x50, 0x00]));
+        let code_hash = by.hash_slow();
+        let contract = address!("dead10000000000000000000000000000001dead");
+        cdb.insert_account_info(contract, crate::primitives::AccountInfo::from_bytecode(by));
+        let mut evm_context = create_cache_db_evm_context(Box::new(env), cdb);
+        let call_inputs = test_utils::create_mock_call_inputs(contract);
+        let stack_verified = |evm_context: &mut EvmContext<_>| {
+            let Ok(FrameOrResult::Frame(Frame::Call(call_frame))) =
+                evm_context.make_call_frame(&call_inputs)
+            else {
+                panic!("Expected FrameOrResult::Frame(Frame::Call(..))");
+            };
+            call_frame.frame_data.interpreter.contract.stack_verified
+        };
+
+        assert!(stack_verified(&mut evm_context));
+        assert_eq!(
+            evm_context.journaled_state.stack_verified.get(&code_hash),
+            Some(&true)
+        );
+        // The cached verdict is used instead of verifying the code again.
+        evm_context
+            .journaled_state
+            .stack_verified
+            .insert(code_hash, false);
+        assert!(!stack_verified(&mut evm_context));
+    }
 }
@@ -1,15 +1,15 @@
 use crate::{
     db::Database,
     interpreter::{
-        analysis::to_analysed, gas, return_ok, InstructionResult, InterpreterResult,
-        LoadAccountResult, SStoreResult, SelfDestructResult,
+        analysis::to_analysed, gas::SharedGasSchedule, return_ok, InstructionResult,
+        InterpreterResult, LoadAccountResult, SStoreResult, SelfDestructResult,
     },
     journaled_state::JournaledState,
     primitives::{
-        AccessListItem, Account, Address, AnalysisKind, Bytecode, Bytes, CfgEnv, EVMError, Env,
-        Eof, HashSet, Spec,
+        delegation_designator, keccak256, AccessListItem, Account, Address, AnalysisKind, Bytecode,
+        Bytes, CfgEnv, EVMError, Env, Eof, HashSet, Log, Spec,
         SpecId::{self, *},
-        B256, EOF_MAGIC_BYTES, EOF_MAGIC_HASH, U256,
+        Transaction, B256, EOF_MAGIC_BYTES, EOF_MAGIC_HASH, U256,
     },
     JournalCheckpoint,
 };
@@ -27,8 +27,12 @@ pub struct InnerEvmContext<DB: Database> {
     pub db: DB,
     /// Error that happened during execution.
     pub error: Result<(), EVMError<DB::Error>>,
-    /// EIP-7702 Authorization list of accounts that needs to be cleared.
-    pub valid_authorizations: Vec<Address>,
+    /// EIP-7702 Authorization list of accounts that needs to be cleared, with the address their
+    /// code is delegated to.
+    pub valid_authorizations: Vec<(Address, Address)>,
+    /// Gas costs charged by the interpreter and the transaction, replacing the costs of the spec
+    /// when set. Shared by every frame.
+    pub gas_schedule: Option<SharedGasSchedule>,
     /// Used as temporary value holder to store L1 block info.
     #[cfg(feature = "optimism")]
     pub l1_block_info: Option<crate::optimism::L1BlockInfo>,
@@ -45,6 +49,7 @@ where
             db: self.db.clone(),
             error: self.error.clone(),
             valid_authorizations: self.valid_authorizations.clone(),
+            gas_schedule: self.gas_schedule.clone(),
             #[cfg(feature = "optimism")]
             l1_block_info: self.l1_block_info.clone(),
         }
@@ -55,10 +60,11 @@ impl<DB: Database> InnerEvmContext<DB> {
     pub fn new(db: DB) -> Self {
         Self {
             env: Box::default(),
-            journaled_state: JournaledState::new(SpecId::LATEST, HashSet::new()),
+            journaled_state: JournaledState::new(SpecId::LATEST, HashSet::default()),
             db,
             error: Ok(()),
             valid_authorizations: Default::default(),
+            gas_schedule: None,
             #[cfg(feature = "optimism")]
             l1_block_info: None,
         }
@@ -69,10 +75,11 @@ impl<DB: Database> InnerEvmContext<DB> {
     pub fn new_with_env(db: DB, env: Box<Env>) -> Self {
         Self {
             env,
-            journaled_state: JournaledState::new(SpecId::LATEST, HashSet::new()),
+            journaled_state: JournaledState::new(SpecId::LATEST, HashSet::default()),
             db,
             error: Ok(()),
             valid_authorizations: Default::default(),
+            gas_schedule: None,
             #[cfg(feature = "optimism")]
             l1_block_info: None,
         }
@@ -89,6 +96,7 @@ impl<DB: Database> InnerEvmContext<DB> {
             db,
             error: Ok(()),
             valid_authorizations: Default::default(),
+            gas_schedule: self.gas_schedule,
             #[cfg(feature = "optimism")]
             l1_block_info: self.l1_block_info,
         }
@@ -100,7 +108,17 @@ impl<DB: Database> InnerEvmContext<DB> {
         self.journaled_state.spec
     }
 
-    /// Load access list for berlin hard fork.
+    /// Returns the gas costs charged in `spec_id`, the configured
+    /// [`gas_schedule`](Self::gas_schedule) or the costs of the spec.
+    #[inline]
+    pub fn gas_schedule_for(&self, spec_id: SpecId) -> SharedGasSchedule {
+        self.gas_schedule
+            .clone()
+            .unwrap_or_else(|| SharedGasSchedule::for_spec(spec_id))
+    }
+
+    /// Load access list for berlin hard fork, together with the
+    /// [`CfgEnv::warm_accesses`] of the network.
     ///
     /// Loading of accounts/storages is needed to make them warm.
     #[inline]
@@ -108,7 +126,12 @@ impl<DB: Database> InnerEvmContext<DB> {
         for AccessListItem {
             address,
             storage_keys,
-        } in self.env.tx.access_list.iter()
+        } in self
+            .env
+            .tx
+            .access_list()
+            .iter()
+            .chain(&self.env.cfg.warm_accesses)
         {
             self.journaled_state.initial_account_load(
                 *address,
@@ -179,15 +202,19 @@ impl<DB: Database> InnerEvmContext<DB> {
 
     /// Return account code bytes and if address is cold loaded.
     ///
-    /// In case of EOF account it will return `EOF_MAGIC` (0xEF00) as code.
+    /// In case of EOF account it will return `EOF_MAGIC` (0xEF00) as code, and for an account
+    /// delegated with EIP-7702 its delegation designator.
     #[inline]
     pub fn code(&mut self, address: Address) -> Result<(Bytes, bool), EVMError<DB::Error>> {
+        let delegate = self.delegated_address(address);
         self.journaled_state
             .load_code(address, &mut self.db)
             .map(|(a, is_cold)| {
                 // SAFETY: safe to unwrap as load_code will insert code if it is empty.
                 let code = a.info.code.as_ref().unwrap();
-                if code.is_eof() {
+                if let Some(delegate) = delegate {
+                    (delegation_designator(delegate), is_cold)
+                } else if code.is_eof() {
                     (EOF_MAGIC_BYTES.clone(), is_cold)
                 } else {
                     (code.original_bytes(), is_cold)
@@ -195,13 +222,33 @@ impl<DB: Database> InnerEvmContext<DB> {
             })
     }
 
+    /// Returns the address the code of `authority` is delegated to in this transaction.
+    ///
+    /// Always `None` if [`CfgEnv::expose_delegated_code`] is set, as delegated accounts are then
+    /// seen with the code they delegate to.
+    #[inline]
+    pub fn delegated_address(&self, authority: Address) -> Option<Address> {
+        if self.env.cfg.expose_delegated_code {
+            return None;
+        }
+        self.valid_authorizations
+            .iter()
+            .find(|(address, _)| *address == authority)
+            .map(|(_, delegate)| *delegate)
+    }
+
     /// Get code hash of address.
     ///
     /// In case of EOF account it will return `EOF_MAGIC_HASH`
-    /// (the hash of `0xEF00`).
+    /// (the hash of `0xEF00`), and for an account delegated with EIP-7702 the hash of its
+    /// delegation designator.
     #[inline]
     pub fn code_hash(&mut self, address: Address) -> Result<(B256, bool), EVMError<DB::Error>> {
+        let delegate = self.delegated_address(address);
         let (acc, is_cold) = self.journaled_state.load_code(address, &mut self.db)?;
+        if let Some(delegate) = delegate {
+            return Ok((keccak256(delegation_designator(delegate)), is_cold));
+        }
         if acc.is_empty() {
             return Ok((B256::ZERO, is_cold));
         }
@@ -234,6 +281,16 @@ impl<DB: Database> InnerEvmContext<DB> {
             .sstore(address, index, value, &mut self.db)
     }
 
+    /// Returns `true` if the journal has more entries and logs than [`CfgEnv::journal_limit`], in
+    /// which case the frames are halted with
+    /// [`HaltReason::JournalLimitExceeded`](crate::primitives::HaltReason::JournalLimitExceeded).
+    #[inline]
+    pub fn is_journal_limit_exceeded(&self) -> bool {
+        self.env.cfg.journal_limit.is_some_and(|limit| {
+            self.journaled_state.entry_count() + self.journaled_state.logs.len() > limit
+        })
+    }
+
     /// Returns transient storage value.
     #[inline]
     pub fn tload(&mut self, address: Address, index: U256) -> U256 {
@@ -246,6 +303,20 @@ impl<DB: Database> InnerEvmContext<DB> {
         self.journaled_state.tstore(address, index, value)
     }
 
+    /// Emits a log from the given address, as the `LOG` instructions do.
+    ///
+    /// The log is added to the journal, so it is discarded if the current frame reverts. Intended
+    /// for stateful precompiles and custom handlers that need to emit events.
+    ///
+    /// Returns `false` and does not emit the log if there are more than four topics.
+    pub fn emit_log(&mut self, address: Address, topics: Vec<B256>, data: Bytes) -> bool {
+        let Some(log) = Log::new(address, topics, data) else {
+            return false;
+        };
+        self.journaled_state.log(log);
+        true
+    }
+
     /// Selfdestructs the account.
     #[inline]
     pub fn selfdestruct(
@@ -283,7 +354,8 @@ impl<DB: Database> InnerEvmContext<DB> {
         }
 
         // deduct gas for code deployment.
-        let gas_for_code = interpreter_result.output.len() as u64 * gas::CODEDEPOSIT;
+        let gas_for_code = interpreter_result.output.len() as u64
+            * self.gas_schedule_for(SPEC::SPEC_ID).code_deposit;
         if !interpreter_result.gas.record_cost(gas_for_code) {
             self.journaled_state.checkpoint_revert(journal_checkpoint);
             interpreter_result.result = InstructionResult::OutOfGas;
@@ -315,6 +387,30 @@ impl<DB: Database> InnerEvmContext<DB> {
         } else {
             self.journaled_state.checkpoint_revert(journal_checkpoint);
         }
+        // Leave the static context when its outermost frame returns.
+        if self
+            .journaled_state
+            .static_depth
+            .is_some_and(|depth| depth > self.journaled_state.depth)
+        {
+            self.journaled_state.static_depth = None;
+        }
+    }
+
+    /// Returns the call depth of the outermost frame of the current static context, or `None`
+    /// outside of static calls.
+    ///
+    /// Frames called from a static frame are static as well, whatever their call scheme, so
+    /// every frame deeper than this depth is static.
+    #[inline]
+    pub fn static_depth(&self) -> Option<usize> {
+        self.journaled_state.static_depth
+    }
+
+    /// Returns `true` if the current frame is executed in a static context.
+    #[inline]
+    pub fn is_static(&self) -> bool {
+        self.journaled_state.static_depth.is_some()
     }
 
     /// Handles create return.
@@ -349,7 +445,8 @@ impl<DB: Database> InnerEvmContext<DB> {
             interpreter_result.result = InstructionResult::CreateContractSizeLimit;
             return;
         }
-        let gas_for_code = interpreter_result.output.len() as u64 * gas::CODEDEPOSIT;
+        let gas_for_code = interpreter_result.output.len() as u64
+            * self.gas_schedule_for(SPEC::SPEC_ID).code_deposit;
         if !interpreter_result.gas.record_cost(gas_for_code) {
             // record code deposit gas cost and check if we are out of gas.
             // EIP-2 point 3: If contract creation does not have enough gas to pay for the
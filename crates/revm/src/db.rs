@@ -1,23 +1,31 @@
 //! [Database] implementations.
 
-#[cfg(any(feature = "alloydb", feature = "ethersdb"))]
+#[cfg(any(feature = "alloydb", feature = "ethersdb", feature = "asyncdb"))]
 mod utils;
 
 #[cfg(feature = "alloydb")]
 mod alloydb;
+#[cfg(feature = "asyncdb")]
+mod async_db;
 pub mod emptydb;
 #[cfg(feature = "ethersdb")]
 mod ethersdb;
 pub mod in_memory_db;
+pub mod recording;
+pub mod state_override;
 pub mod states;
 
 pub use crate::primitives::db::*;
 #[cfg(feature = "alloydb")]
 pub use alloydb::AlloyDB;
+#[cfg(feature = "asyncdb")]
+pub use async_db::WrapAsyncDb;
 pub use emptydb::{EmptyDB, EmptyDBTyped};
 #[cfg(feature = "ethersdb")]
 pub use ethersdb::EthersDB;
 pub use in_memory_db::*;
+pub use recording::{MinimalState, RecordingDB};
+pub use state_override::{AccountOverride, StateOverride, StateOverrideDb, StateOverrideError};
 pub use states::{
     AccountRevert, AccountStatus, BundleAccount, BundleState, CacheState, DBBox,
     OriginalValuesKnown, PlainAccount, RevertToSlot, State, StateBuilder, StateDBBox,
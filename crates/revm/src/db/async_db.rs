@@ -0,0 +1,138 @@
+use tokio::runtime::{Handle, Runtime};
+
+use crate::primitives::{db::AsyncDatabase, AccountInfo, Address, Bytecode, B256, U256};
+use crate::Database;
+
+use super::utils::HandleOrRuntime;
+
+/// Wraps an [`AsyncDatabase`] to provide a [`Database`] implementation, blocking on a tokio
+/// runtime until each future completes.
+#[derive(Debug)]
+pub struct WrapAsyncDb<T: AsyncDatabase> {
+    db: T,
+    rt: HandleOrRuntime,
+}
+
+impl<T: AsyncDatabase> WrapAsyncDb<T> {
+    /// Wraps the database, blocking on the current tokio runtime.
+    ///
+    /// Returns `None` if no tokio runtime is available or if the current runtime is a current-thread runtime.
+    pub fn new(db: T) -> Option<Self> {
+        let rt = match Handle::try_current() {
+            Ok(handle) => match handle.runtime_flavor() {
+                tokio::runtime::RuntimeFlavor::CurrentThread => return None,
+                _ => HandleOrRuntime::Handle(handle),
+            },
+            Err(_) => return None,
+        };
+        Some(Self { db, rt })
+    }
+
+    /// Wraps the database, blocking on the given runtime.
+    ///
+    /// Refer to [tokio::runtime::Builder] how to create a runtime if you are in synchronous world.
+    /// If you are already using something like [tokio::main], call WrapAsyncDb::new instead.
+    pub fn with_runtime(db: T, runtime: Runtime) -> Self {
+        Self {
+            db,
+            rt: HandleOrRuntime::Runtime(runtime),
+        }
+    }
+
+    /// Wraps the database, blocking on the runtime of the given handle.
+    ///
+    /// The runtime has to be a multi-thread runtime if this is called from within it.
+    pub fn with_handle(db: T, handle: Handle) -> Self {
+        Self {
+            db,
+            rt: HandleOrRuntime::Handle(handle),
+        }
+    }
+
+    /// Returns the wrapped database.
+    pub fn into_inner(self) -> T {
+        self.db
+    }
+}
+
+impl<T: AsyncDatabase> Database for WrapAsyncDb<T> {
+    type Error = T::Error;
+
+    #[inline]
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.rt.block_on(self.db.basic(address))
+    }
+
+    #[inline]
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.rt.block_on(self.db.code_by_hash(code_hash))
+    }
+
+    #[inline]
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.rt.block_on(self.db.storage(address, index))
+    }
+
+    #[inline]
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.rt.block_on(self.db.block_hash(number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{CacheDB, DatabaseRef, EmptyDB},
+        primitives::{address, bytes, TxKind},
+        Evm,
+    };
+    use std::convert::Infallible;
+
+    /// Serves the accounts of a [`CacheDB`] asynchronously.
+    struct AsyncCacheDb(CacheDB<EmptyDB>);
+
+    impl AsyncDatabase for AsyncCacheDb {
+        type Error = Infallible;
+
+        async fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+            tokio::task::yield_now().await;
+            self.0.basic_ref(address)
+        }
+
+        async fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+            self.0.code_by_hash_ref(code_hash)
+        }
+
+        async fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+            self.0.storage_ref(address, index)
+        }
+
+        async fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+            self.0.block_hash_ref(number)
+        }
+    }
+
+    #[test]
+    fn transact() {
+        let contract = address!("0000000000000000000000000000000000000100");
+        let mut cache = CacheDB::new(EmptyDB::default());
+        // PUSH1 1 PUSH1 0 SSTORE STOP
+        cache.insert_account_info(
+            contract,
+            AccountInfo::from_bytecode(Bytecode::new_raw(bytes!("6001600055"))),
+        );
+        let db = WrapAsyncDb::with_runtime(AsyncCacheDb(cache), Runtime::new().unwrap());
+
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .modify_tx_env(|tx| tx.transact_to = TxKind::Call(contract))
+            .build();
+        let result = evm.transact().unwrap();
+        assert!(result.result.is_success());
+        assert_eq!(
+            result.state[&contract].storage[&U256::ZERO].present_value,
+            U256::from(1)
+        );
+    }
+}
@@ -43,14 +43,14 @@ impl<ExtDB: Default> Default for CacheDB<ExtDB> {
 
 impl<ExtDB> CacheDB<ExtDB> {
     pub fn new(db: ExtDB) -> Self {
-        let mut contracts = HashMap::new();
+        let mut contracts = HashMap::default();
         contracts.insert(KECCAK_EMPTY, Bytecode::default());
         contracts.insert(B256::ZERO, Bytecode::default());
         Self {
-            accounts: HashMap::new(),
+            accounts: HashMap::default(),
             contracts,
             logs: Vec::default(),
-            block_hashes: HashMap::new(),
+            block_hashes: HashMap::default(),
             db,
         }
     }
@@ -129,39 +129,43 @@ impl<ExtDB: DatabaseRef> CacheDB<ExtDB> {
 
 impl<ExtDB> DatabaseCommit for CacheDB<ExtDB> {
     fn commit(&mut self, changes: HashMap<Address, Account>) {
-        for (address, mut account) in changes {
-            if !account.is_touched() {
-                continue;
-            }
-            if account.is_selfdestructed() {
-                let db_account = self.accounts.entry(address).or_default();
-                db_account.storage.clear();
-                db_account.account_state = AccountState::NotExisting;
-                db_account.info = AccountInfo::default();
-                continue;
-            }
-            let is_newly_created = account.is_created();
-            self.insert_contract(&mut account.info);
+        for (address, account) in changes {
+            self.commit_account(address, account);
+        }
+    }
 
+    fn commit_account(&mut self, address: Address, mut account: Account) {
+        if !account.is_touched() {
+            return;
+        }
+        if account.is_selfdestructed() {
             let db_account = self.accounts.entry(address).or_default();
-            db_account.info = account.info;
-
-            db_account.account_state = if is_newly_created {
-                db_account.storage.clear();
-                AccountState::StorageCleared
-            } else if db_account.account_state.is_storage_cleared() {
-                // Preserve old account state if it already exists
-                AccountState::StorageCleared
-            } else {
-                AccountState::Touched
-            };
-            db_account.storage.extend(
-                account
-                    .storage
-                    .into_iter()
-                    .map(|(key, value)| (key, value.present_value())),
-            );
+            db_account.storage.clear();
+            db_account.account_state = AccountState::NotExisting;
+            db_account.info = AccountInfo::default();
+            return;
         }
+        let is_newly_created = account.is_created();
+        self.insert_contract(&mut account.info);
+
+        let db_account = self.accounts.entry(address).or_default();
+        db_account.info = account.info;
+
+        db_account.account_state = if is_newly_created {
+            db_account.storage.clear();
+            AccountState::StorageCleared
+        } else if db_account.account_state.is_storage_cleared() {
+            // Preserve old account state if it already exists
+            AccountState::StorageCleared
+        } else {
+            AccountState::Touched
+        };
+        db_account.storage.extend(
+            account
+                .storage
+                .into_iter()
+                .map(|(key, value)| (key, value.present_value())),
+        );
     }
 }
 
@@ -244,6 +248,20 @@ impl<ExtDB: DatabaseRef> Database for CacheDB<ExtDB> {
             }
         }
     }
+
+    fn code_analysis_by_hash(&mut self, code_hash: B256) -> Result<Option<Bytecode>, Self::Error> {
+        self.code_analysis_by_hash_ref(code_hash)
+    }
+
+    /// Replaces the cached code with its analysis and stores it in the underlying database.
+    fn store_code_analysis(
+        &mut self,
+        code_hash: B256,
+        bytecode: &Bytecode,
+    ) -> Result<(), Self::Error> {
+        self.contracts.insert(code_hash, bytecode.clone());
+        self.db.store_code_analysis_ref(code_hash, bytecode)
+    }
 }
 
 impl<ExtDB: DatabaseRef> DatabaseRef for CacheDB<ExtDB> {
@@ -288,6 +306,21 @@ impl<ExtDB: DatabaseRef> DatabaseRef for CacheDB<ExtDB> {
             None => self.db.block_hash_ref(number),
         }
     }
+
+    fn code_analysis_by_hash_ref(&self, code_hash: B256) -> Result<Option<Bytecode>, Self::Error> {
+        match self.contracts.get(&code_hash) {
+            Some(code) if code.is_execution_ready() => Ok(Some(code.clone())),
+            _ => self.db.code_analysis_by_hash_ref(code_hash),
+        }
+    }
+
+    fn store_code_analysis_ref(
+        &self,
+        code_hash: B256,
+        bytecode: &Bytecode,
+    ) -> Result<(), Self::Error> {
+        self.db.store_code_analysis_ref(code_hash, bytecode)
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -0,0 +1,282 @@
+//! Recording of the pre-state read by the execution of transactions.
+use super::{CacheDB, DatabaseCommit, DatabaseRef, DbAccount, InMemoryDB};
+use crate::{
+    primitives::{Account, AccountInfo, Address, Bytecode, HashMap, B256, U256},
+    Database,
+};
+use core::cell::RefCell;
+use std::collections::BTreeMap;
+
+/// Subset of the pre-state that was read by the execution of transactions.
+///
+/// Holds exactly the accounts, storage slots, contracts and block hashes read by the EVM, with
+/// their values before the first transaction. Loaded into an [`InMemoryDB`] together with the
+/// same environments, it re-executes the transactions with identical results, which makes it
+/// a small and self-contained reproduction of them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MinimalState {
+    /// Accounts read, `None` if the account did not exist. The code is in `contracts`.
+    pub accounts: BTreeMap<Address, Option<AccountInfo>>,
+    /// Storage slots read, by account.
+    pub storage: BTreeMap<Address, BTreeMap<U256, U256>>,
+    /// Contracts read, by code hash.
+    pub contracts: BTreeMap<B256, Bytecode>,
+    /// Block hashes read, by block number.
+    pub block_hashes: BTreeMap<u64, B256>,
+}
+
+impl MinimalState {
+    /// Returns `true` if nothing was read.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+            && self.storage.is_empty()
+            && self.contracts.is_empty()
+            && self.block_hashes.is_empty()
+    }
+
+    /// Inserts the state into the cache of the database.
+    pub fn load_into<ExtDB>(&self, db: &mut CacheDB<ExtDB>) {
+        for (address, info) in &self.accounts {
+            match info {
+                Some(info) => db.insert_account_info(*address, info.clone()),
+                None => {
+                    db.accounts.insert(*address, DbAccount::new_not_existing());
+                }
+            }
+        }
+        for (address, slots) in &self.storage {
+            db.accounts
+                .entry(*address)
+                .or_default()
+                .storage
+                .extend(slots.iter().map(|(slot, value)| (*slot, *value)));
+        }
+        db.contracts.extend(
+            self.contracts
+                .iter()
+                .map(|(hash, code)| (*hash, code.clone())),
+        );
+        db.block_hashes.extend(
+            self.block_hashes
+                .iter()
+                .map(|(number, hash)| (U256::from(*number), *hash)),
+        );
+    }
+
+    /// Creates an [`InMemoryDB`] holding the state.
+    pub fn into_db(self) -> InMemoryDB {
+        let mut db = InMemoryDB::default();
+        self.load_into(&mut db);
+        db
+    }
+
+    /// Records the account, keeping the first value read.
+    fn record_account(&mut self, address: Address, info: &Option<AccountInfo>) {
+        if self.accounts.contains_key(&address) {
+            return;
+        }
+        let info = info.clone().map(|mut info| {
+            if let Some(code) = info.code.take() {
+                self.contracts.entry(info.code_hash).or_insert(code);
+            }
+            info
+        });
+        self.accounts.insert(address, info);
+    }
+}
+
+/// Database wrapper that records the [`MinimalState`] read from the inner database.
+///
+/// Only the first read of every value is recorded, so that the recorded state stays the
+/// pre-state when changes are committed to the wrapper between transactions.
+///
+/// The analysis cache of the inner database is bypassed, so that every code read is recorded.
+#[derive(Debug, Default)]
+pub struct RecordingDB<DB> {
+    /// The inner database.
+    pub db: DB,
+    state: RefCell<MinimalState>,
+}
+
+impl<DB> RecordingDB<DB> {
+    /// Wraps the database.
+    pub fn new(db: DB) -> Self {
+        Self {
+            db,
+            state: RefCell::default(),
+        }
+    }
+
+    /// Returns a copy of the state recorded so far.
+    pub fn state(&self) -> MinimalState {
+        self.state.borrow().clone()
+    }
+
+    /// Returns the inner database and the recorded state.
+    pub fn into_parts(self) -> (DB, MinimalState) {
+        (self.db, self.state.into_inner())
+    }
+
+    fn record_account(&self, address: Address, info: &Option<AccountInfo>) {
+        self.state.borrow_mut().record_account(address, info);
+    }
+
+    fn record_code(&self, code_hash: B256, code: &Bytecode) {
+        self.state
+            .borrow_mut()
+            .contracts
+            .entry(code_hash)
+            .or_insert_with(|| code.clone());
+    }
+
+    fn record_storage(&self, address: Address, index: U256, value: U256) {
+        self.state
+            .borrow_mut()
+            .storage
+            .entry(address)
+            .or_default()
+            .entry(index)
+            .or_insert(value);
+    }
+
+    fn record_block_hash(&self, number: u64, hash: B256) {
+        self.state
+            .borrow_mut()
+            .block_hashes
+            .entry(number)
+            .or_insert(hash);
+    }
+}
+
+impl<DB: Database> Database for RecordingDB<DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let info = self.db.basic(address)?;
+        self.record_account(address, &info);
+        Ok(info)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let code = self.db.code_by_hash(code_hash)?;
+        self.record_code(code_hash, &code);
+        Ok(code)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let value = self.db.storage(address, index)?;
+        self.record_storage(address, index, value);
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        let hash = self.db.block_hash(number)?;
+        self.record_block_hash(number, hash);
+        Ok(hash)
+    }
+}
+
+impl<DB: DatabaseRef> DatabaseRef for RecordingDB<DB> {
+    type Error = DB::Error;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let info = self.db.basic_ref(address)?;
+        self.record_account(address, &info);
+        Ok(info)
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let code = self.db.code_by_hash_ref(code_hash)?;
+        self.record_code(code_hash, &code);
+        Ok(code)
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let value = self.db.storage_ref(address, index)?;
+        self.record_storage(address, index, value);
+        Ok(value)
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        let hash = self.db.block_hash_ref(number)?;
+        self.record_block_hash(number, hash);
+        Ok(hash)
+    }
+}
+
+impl<DB: DatabaseCommit> DatabaseCommit for RecordingDB<DB> {
+    fn commit(&mut self, changes: HashMap<Address, Account>) {
+        self.db.commit(changes)
+    }
+
+    fn commit_account(&mut self, address: Address, account: Account) {
+        self.db.commit_account(address, account)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        primitives::{bytes, ExecutionResult, TxKind},
+        Evm,
+    };
+
+    const CALLER: Address = Address::with_last_byte(1);
+    const CONTRACT: Address = Address::with_last_byte(0x10);
+
+    fn transact<DB>(db: DB) -> (ExecutionResult, DB)
+    where
+        DB: Database + DatabaseCommit,
+        DB::Error: core::fmt::Debug,
+    {
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .modify_tx_env(|tx| {
+                tx.caller = CALLER;
+                tx.transact_to = TxKind::Call(CONTRACT);
+            })
+            .build();
+        let result = evm.transact_commit().unwrap();
+        (result, evm.into_context().evm.inner.db)
+    }
+
+    #[test]
+    fn reexecute_from_minimal_state() {
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(CALLER, AccountInfo::from_balance(U256::from(1_000_000)));
+        // PUSH1 1 SLOAD PUSH1 5 BALANCE ADD PUSH1 0 SSTORE STOP
+        db.insert_account_info(
+            CONTRACT,
+            AccountInfo::from_bytecode(Bytecode::new_raw(bytes!("6001546005310160005500"))),
+        );
+        for (slot, value) in [(1, 7), (2, 9)] {
+            db.insert_account_storage(CONTRACT, U256::from(slot), U256::from(value))
+                .unwrap();
+        }
+        for other in [5, 6] {
+            db.insert_account_info(
+                Address::with_last_byte(other),
+                AccountInfo::from_balance(U256::from(other)),
+            );
+        }
+
+        let (result, recording) = transact(RecordingDB::new(db));
+        let (_, state) = recording.into_parts();
+        assert!(state.accounts.contains_key(&Address::with_last_byte(5)));
+        assert!(!state.accounts.contains_key(&Address::with_last_byte(6)));
+        // Slot 2 is never read, slot 0 is read by SSTORE.
+        assert_eq!(
+            state.storage[&CONTRACT].keys().copied().collect::<Vec<_>>(),
+            [U256::ZERO, U256::from(1)]
+        );
+
+        let (reexecuted, db) = transact(state.into_db());
+        assert_eq!(reexecuted, result);
+        assert_eq!(
+            db.accounts[&CONTRACT].storage[&U256::ZERO],
+            U256::from(7 + 5)
+        );
+    }
+}
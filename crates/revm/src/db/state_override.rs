@@ -0,0 +1,318 @@
+//! State overrides for `eth_call` style simulations.
+use super::DatabaseRef;
+use crate::{
+    primitives::{AccountInfo, Address, Bytecode, Bytes, HashMap, B256, U256},
+    Database,
+};
+use core::fmt;
+
+/// Override of an account, mirroring the account object of the `eth_call` state override set.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct AccountOverride {
+    /// Replaces the balance.
+    pub balance: Option<U256>,
+    /// Replaces the nonce.
+    pub nonce: Option<u64>,
+    /// Replaces the code.
+    pub code: Option<Bytes>,
+    /// Replaces the whole storage, slots that are not set read as zero.
+    pub state: Option<HashMap<U256, U256>>,
+    /// Replaces the given slots, other slots are read from the inner database.
+    pub state_diff: Option<HashMap<U256, U256>>,
+}
+
+/// State override set, by account.
+pub type StateOverride = HashMap<Address, AccountOverride>;
+
+/// Error returned by [`StateOverrideDb::new`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StateOverrideError {
+    /// The override of the account sets both `state` and `state_diff`.
+    BothStateAndStateDiff(Address),
+    /// The override code of the account is malformed EOF.
+    InvalidCode(Address),
+}
+
+impl fmt::Display for StateOverrideError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BothStateAndStateDiff(address) => {
+                write!(f, "account {address} has both 'state' and 'stateDiff'")
+            }
+            Self::InvalidCode(address) => write!(f, "account {address} has invalid code"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StateOverrideError {}
+
+/// Database wrapper that applies a [`StateOverride`] on top of the inner database.
+///
+/// Overridden accounts exist even if they don't exist in the inner database. The inner
+/// database is never written to, changes are not committed through the wrapper.
+#[derive(Clone, Debug, Default)]
+pub struct StateOverrideDb<DB> {
+    /// The inner database.
+    pub db: DB,
+    overrides: StateOverride,
+    /// Override code by hash.
+    codes: HashMap<B256, Bytecode>,
+    /// Hash of the override code, by account.
+    code_hashes: HashMap<Address, B256>,
+}
+
+impl<DB> StateOverrideDb<DB> {
+    /// Wraps the database with the overrides.
+    pub fn new(db: DB, overrides: StateOverride) -> Result<Self, StateOverrideError> {
+        let mut codes = HashMap::default();
+        let mut code_hashes = HashMap::default();
+        for (address, account) in &overrides {
+            if account.state.is_some() && account.state_diff.is_some() {
+                return Err(StateOverrideError::BothStateAndStateDiff(*address));
+            }
+            if let Some(code) = &account.code {
+                let code = Bytecode::new_raw_checked(code.clone())
+                    .map_err(|_| StateOverrideError::InvalidCode(*address))?;
+                let hash = code.hash_slow();
+                code_hashes.insert(*address, hash);
+                codes.insert(hash, code);
+            }
+        }
+        Ok(Self {
+            db,
+            overrides,
+            codes,
+            code_hashes,
+        })
+    }
+
+    /// Returns the overrides.
+    pub fn overrides(&self) -> &StateOverride {
+        &self.overrides
+    }
+
+    /// Returns the inner database.
+    pub fn into_inner(self) -> DB {
+        self.db
+    }
+
+    /// Applies the override of the account to the account read from the inner database.
+    fn override_basic(&self, address: Address, info: Option<AccountInfo>) -> Option<AccountInfo> {
+        let Some(account) = self.overrides.get(&address) else {
+            return info;
+        };
+        let mut info = info.unwrap_or_default();
+        if let Some(balance) = account.balance {
+            info.balance = balance;
+        }
+        if let Some(nonce) = account.nonce {
+            info.nonce = nonce;
+        }
+        if let Some(&code_hash) = self.code_hashes.get(&address) {
+            info.code_hash = code_hash;
+            info.code = self.codes.get(&code_hash).cloned();
+        }
+        Some(info)
+    }
+
+    /// Returns the overridden value of the slot, `None` if it is read from the inner database.
+    fn override_storage(&self, address: Address, index: U256) -> Option<U256> {
+        let account = self.overrides.get(&address)?;
+        if let Some(state) = &account.state {
+            return Some(state.get(&index).copied().unwrap_or_default());
+        }
+        account.state_diff.as_ref()?.get(&index).copied()
+    }
+}
+
+impl<DB: Database> Database for StateOverrideDb<DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let info = self.db.basic(address)?;
+        Ok(self.override_basic(address, info))
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        match self.codes.get(&code_hash) {
+            Some(code) => Ok(code.clone()),
+            None => self.db.code_by_hash(code_hash),
+        }
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        match self.override_storage(address, index) {
+            Some(value) => Ok(value),
+            None => self.db.storage(address, index),
+        }
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.db.block_hash(number)
+    }
+
+    fn code_analysis_by_hash(&mut self, code_hash: B256) -> Result<Option<Bytecode>, Self::Error> {
+        self.db.code_analysis_by_hash(code_hash)
+    }
+
+    fn store_code_analysis(
+        &mut self,
+        code_hash: B256,
+        bytecode: &Bytecode,
+    ) -> Result<(), Self::Error> {
+        self.db.store_code_analysis(code_hash, bytecode)
+    }
+}
+
+impl<DB: DatabaseRef> DatabaseRef for StateOverrideDb<DB> {
+    type Error = DB::Error;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let info = self.db.basic_ref(address)?;
+        Ok(self.override_basic(address, info))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        match self.codes.get(&code_hash) {
+            Some(code) => Ok(code.clone()),
+            None => self.db.code_by_hash_ref(code_hash),
+        }
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        match self.override_storage(address, index) {
+            Some(value) => Ok(value),
+            None => self.db.storage_ref(address, index),
+        }
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.db.block_hash_ref(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::InMemoryDB,
+        primitives::{bytes, ExecutionResult, Output, TxKind},
+        Evm,
+    };
+
+    const CALLER: Address = Address::with_last_byte(1);
+    const CONTRACT: Address = Address::with_last_byte(0x10);
+
+    fn inner_db() -> InMemoryDB {
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(CALLER, AccountInfo::from_balance(U256::from(1_000_000)));
+        db.insert_account_info(CONTRACT, AccountInfo::from_balance(U256::from(5)));
+        for (slot, value) in [(1, 7), (2, 9)] {
+            db.insert_account_storage(CONTRACT, U256::from(slot), U256::from(value))
+                .unwrap();
+        }
+        db
+    }
+
+    fn slots(state: bool) -> AccountOverride {
+        let slots = HashMap::from_iter([(U256::from(1), U256::from(70))]);
+        AccountOverride {
+            state: state.then(|| slots.clone()),
+            state_diff: (!state).then_some(slots),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn account_fields() {
+        let code = bytes!("6001");
+        let overrides = StateOverride::from_iter([(
+            CONTRACT,
+            AccountOverride {
+                balance: Some(U256::from(42)),
+                nonce: Some(3),
+                code: Some(code.clone()),
+                ..Default::default()
+            },
+        )]);
+        let mut db = StateOverrideDb::new(inner_db(), overrides).unwrap();
+
+        let info = db.basic(CONTRACT).unwrap().unwrap();
+        assert_eq!((info.balance, info.nonce), (U256::from(42), 3));
+        let bytecode = Bytecode::new_raw(code);
+        assert_eq!(info.code_hash, bytecode.hash_slow());
+        assert_eq!(db.code_by_hash(info.code_hash).unwrap(), bytecode);
+        // Not overridden accounts are untouched.
+        assert_eq!(
+            db.basic(CALLER).unwrap().unwrap().balance,
+            U256::from(1_000_000)
+        );
+    }
+
+    #[test]
+    fn state_and_state_diff() {
+        let mut db = StateOverrideDb::new(
+            inner_db(),
+            StateOverride::from_iter([(CONTRACT, slots(true))]),
+        )
+        .unwrap();
+        assert_eq!(db.storage(CONTRACT, U256::from(1)).unwrap(), U256::from(70));
+        assert_eq!(db.storage(CONTRACT, U256::from(2)).unwrap(), U256::ZERO);
+
+        let db = StateOverrideDb::new(
+            inner_db(),
+            StateOverride::from_iter([(CONTRACT, slots(false))]),
+        )
+        .unwrap();
+        assert_eq!(
+            db.storage_ref(CONTRACT, U256::from(1)).unwrap(),
+            U256::from(70)
+        );
+        assert_eq!(
+            db.storage_ref(CONTRACT, U256::from(2)).unwrap(),
+            U256::from(9)
+        );
+
+        let both = AccountOverride {
+            state: Some(HashMap::default()),
+            ..slots(false)
+        };
+        assert_eq!(
+            StateOverrideDb::new(inner_db(), StateOverride::from_iter([(CONTRACT, both)]))
+                .unwrap_err(),
+            StateOverrideError::BothStateAndStateDiff(CONTRACT)
+        );
+    }
+
+    #[test]
+    fn call_with_overrides() {
+        // PUSH1 1 SLOAD PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+        let overrides = StateOverride::from_iter([(
+            CONTRACT,
+            AccountOverride {
+                code: Some(bytes!("60015460005260206000f3")),
+                ..slots(false)
+            },
+        )]);
+        let db = StateOverrideDb::new(inner_db(), overrides).unwrap();
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .modify_tx_env(|tx| {
+                tx.caller = CALLER;
+                tx.transact_to = TxKind::Call(CONTRACT);
+            })
+            .build();
+        let result = evm.transact().unwrap().result;
+        let ExecutionResult::Success {
+            output: Output::Call(output),
+            ..
+        } = result
+        else {
+            panic!("unexpected result {result:?}");
+        };
+        assert_eq!(U256::from_be_slice(&output), U256::from(70));
+    }
+}
@@ -5,7 +5,8 @@ use super::{
 use crate::db::EmptyDB;
 use revm_interpreter::primitives::{
     db::{Database, DatabaseCommit},
-    hash_map, Account, AccountInfo, Address, Bytecode, HashMap, B256, BLOCK_HASH_HISTORY, U256,
+    hash_map, Account, AccountInfo, Address, Bytecode, HashMap, Withdrawal, B256,
+    BLOCK_HASH_HISTORY, U256,
 };
 use std::{
     boxed::Box,
@@ -106,6 +107,22 @@ impl<DB: Database> State<DB> {
         Ok(())
     }
 
+    /// Credits the EIP-4895 withdrawals of a block, see [`crate::apply_withdrawals`].
+    pub fn apply_withdrawals(&mut self, withdrawals: &[Withdrawal]) -> Result<(), DB::Error> {
+        self.increment_balances(crate::withdrawal_increments(withdrawals))
+    }
+
+    /// Moves the balances of the DAO accounts to the refund contract, see
+    /// [`crate::apply_dao_fork`]. Returns the moved balance.
+    pub fn apply_dao_fork(&mut self) -> Result<U256, DB::Error> {
+        let drained: u128 = self
+            .drain_balances(crate::DAO_HARDFORK_ACCOUNTS)?
+            .into_iter()
+            .sum();
+        self.increment_balances([(crate::DAO_HARDFORK_BENEFICIARY, drained)])?;
+        Ok(U256::from(drained))
+    }
+
     /// Drain balances from given account and return those values.
     ///
     /// It is used for DAO hardfork state change to move values from given accounts.
@@ -187,9 +204,9 @@ impl<DB: Database> State<DB> {
                 let account = match info {
                     None => CacheAccount::new_loaded_not_existing(),
                     Some(acc) if acc.is_empty() => {
-                        CacheAccount::new_loaded_empty_eip161(HashMap::new())
+                        CacheAccount::new_loaded_empty_eip161(HashMap::default())
                     }
-                    Some(acc) => CacheAccount::new_loaded(acc, HashMap::new()),
+                    Some(acc) => CacheAccount::new_loaded(acc, HashMap::default()),
                 };
                 Ok(entry.insert(account))
             }
@@ -288,6 +305,22 @@ impl<DB: Database> Database for State<DB> {
             }
         }
     }
+
+    fn code_analysis_by_hash(&mut self, code_hash: B256) -> Result<Option<Bytecode>, Self::Error> {
+        match self.cache.contracts.get(&code_hash) {
+            Some(code) if code.is_execution_ready() => Ok(Some(code.clone())),
+            _ => self.database.code_analysis_by_hash(code_hash),
+        }
+    }
+
+    fn store_code_analysis(
+        &mut self,
+        code_hash: B256,
+        bytecode: &Bytecode,
+    ) -> Result<(), Self::Error> {
+        self.cache.contracts.insert(code_hash, bytecode.clone());
+        self.database.store_code_analysis(code_hash, bytecode)
+    }
 }
 
 impl<DB: Database> DatabaseCommit for State<DB> {
@@ -295,6 +328,15 @@ impl<DB: Database> DatabaseCommit for State<DB> {
         let transitions = self.cache.apply_evm_state(evm_state);
         self.apply_transition(transitions);
     }
+
+    fn commit_account(&mut self, address: Address, account: Account) {
+        let Some(transition) = self.cache.apply_account_state(address, account) else {
+            return;
+        };
+        if let Some(s) = self.transition_state.as_mut() {
+            s.add_transition(address, transition)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -365,7 +407,7 @@ mod tests {
             nonce: 1,
             ..Default::default()
         };
-        let existing_account_initial_storage = HashMap::<U256, U256>::from([
+        let existing_account_initial_storage = HashMap::<U256, U256>::from_iter([
             (slot1, U256::from(100)), // 0x01 => 100
             (slot2, U256::from(200)), // 0x02 => 200
         ]);
@@ -393,7 +435,7 @@ mod tests {
                     info: Some(existing_account_changed_info.clone()),
                     previous_status: AccountStatus::Loaded,
                     previous_info: Some(existing_account_initial_info.clone()),
-                    storage: HashMap::from([(
+                    storage: HashMap::from_iter([(
                         slot1,
                         StorageSlot::new_changed(
                             *existing_account_initial_storage.get(&slot1).unwrap(),
@@ -426,7 +468,7 @@ mod tests {
                     info: Some(new_account_changed_info2.clone()),
                     previous_status: AccountStatus::InMemoryChange,
                     previous_info: Some(new_account_changed_info),
-                    storage: HashMap::from([(
+                    storage: HashMap::from_iter([(
                         slot1,
                         StorageSlot::new_changed(U256::ZERO, U256::from(1)),
                     )]),
@@ -440,7 +482,7 @@ mod tests {
                     info: Some(existing_account_changed_info.clone()),
                     previous_status: AccountStatus::InMemoryChange,
                     previous_info: Some(existing_account_changed_info.clone()),
-                    storage: HashMap::from([
+                    storage: HashMap::from_iter([
                         (
                             slot1,
                             StorageSlot::new_changed(U256::from(100), U256::from(1_000)),
@@ -477,7 +519,7 @@ mod tests {
                     AccountRevert {
                         account: AccountInfoRevert::DeleteIt,
                         previous_status: AccountStatus::LoadedNotExisting,
-                        storage: HashMap::from([(slot1, RevertToSlot::Some(U256::ZERO))]),
+                        storage: HashMap::from_iter([(slot1, RevertToSlot::Some(U256::ZERO))]),
                         wipe_storage: false,
                     }
                 ),
@@ -486,7 +528,7 @@ mod tests {
                     AccountRevert {
                         account: AccountInfoRevert::RevertTo(existing_account_initial_info.clone()),
                         previous_status: AccountStatus::Loaded,
-                        storage: HashMap::from([
+                        storage: HashMap::from_iter([
                             (
                                 slot1,
                                 RevertToSlot::Some(
@@ -516,7 +558,7 @@ mod tests {
                 info: Some(new_account_changed_info2),
                 original_info: None,
                 status: AccountStatus::InMemoryChange,
-                storage: HashMap::from([(
+                storage: HashMap::from_iter([(
                     slot1,
                     StorageSlot::new_changed(U256::ZERO, U256::from(1))
                 )]),
@@ -532,7 +574,7 @@ mod tests {
                 info: Some(existing_account_changed_info),
                 original_info: Some(existing_account_initial_info),
                 status: AccountStatus::InMemoryChange,
-                storage: HashMap::from([
+                storage: HashMap::from_iter([
                     (
                         slot1,
                         StorageSlot::new_changed(
@@ -620,7 +662,7 @@ mod tests {
                     info: Some(existing_account_with_storage_info.clone()),
                     previous_status: AccountStatus::Loaded,
                     previous_info: Some(existing_account_with_storage_info.clone()),
-                    storage: HashMap::from([
+                    storage: HashMap::from_iter([
                         (
                             slot1,
                             StorageSlot::new_changed(U256::from(1), U256::from(10)),
@@ -661,7 +703,7 @@ mod tests {
                     info: Some(existing_account_with_storage_info.clone()),
                     previous_status: AccountStatus::Changed,
                     previous_info: Some(existing_account_with_storage_info.clone()),
-                    storage: HashMap::from([
+                    storage: HashMap::from_iter([
                         (
                             slot1,
                             StorageSlot::new_changed(U256::from(10), U256::from(1)),
@@ -718,7 +760,7 @@ mod tests {
                 info: Some(existing_account_info.clone()),
                 previous_status: AccountStatus::Destroyed,
                 previous_info: None,
-                storage: HashMap::from([(
+                storage: HashMap::from_iter([(
                     slot1,
                     StorageSlot::new_changed(U256::ZERO, U256::from(1)),
                 )]),
@@ -748,7 +790,7 @@ mod tests {
                 info: Some(existing_account_info.clone()),
                 previous_status: AccountStatus::DestroyedAgain,
                 previous_info: None,
-                storage: HashMap::from([(
+                storage: HashMap::from_iter([(
                     slot2,
                     StorageSlot::new_changed(U256::ZERO, U256::from(2)),
                 )]),
@@ -762,12 +804,12 @@ mod tests {
 
         assert_eq!(
             bundle_state.state,
-            HashMap::from([(
+            HashMap::from_iter([(
                 existing_account_address,
                 BundleAccount {
                     info: Some(existing_account_info.clone()),
                     original_info: Some(existing_account_info.clone()),
-                    storage: HashMap::from([(
+                    storage: HashMap::from_iter([(
                         slot2,
                         StorageSlot::new_changed(U256::ZERO, U256::from(2))
                     )]),
@@ -783,7 +825,7 @@ mod tests {
                 AccountRevert {
                     account: AccountInfoRevert::DoNothing,
                     previous_status: AccountStatus::Loaded,
-                    storage: HashMap::from([(slot2, RevertToSlot::Destroyed)]),
+                    storage: HashMap::from_iter([(slot2, RevertToSlot::Destroyed)]),
                     wipe_storage: true,
                 }
             )])])
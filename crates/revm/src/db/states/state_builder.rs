@@ -2,7 +2,7 @@ use super::{cache::CacheState, state::DBBox, BundleState, State, TransitionState
 use crate::db::EmptyDB;
 use revm_interpreter::primitives::{
     db::{Database, DatabaseRef, WrapDatabaseRef},
-    B256,
+    is_state_clear_enabled, SpecId, B256,
 };
 use std::collections::BTreeMap;
 
@@ -100,6 +100,18 @@ impl<DB: Database> StateBuilder<DB> {
         }
     }
 
+    /// Sets the state clear flag from the spec, disabled before
+    /// [SpecId::SPURIOUS_DRAGON] to replay early mainnet blocks.
+    ///
+    /// Use the same spec in the configuration of the [`Evm`](crate::Evm), which selects the
+    /// rules of the journal.
+    pub fn with_spec_id(self, spec_id: SpecId) -> Self {
+        Self {
+            with_state_clear: is_state_clear_enabled(spec_id),
+            ..self
+        }
+    }
+
     /// Allows setting prestate that is going to be used for execution.
     /// This bundle state will act as additional layer of cache.
     /// and State after not finding data inside StateCache will try to find it inside BundleState.
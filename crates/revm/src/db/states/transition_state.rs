@@ -11,7 +11,7 @@ pub struct TransitionState {
 impl TransitionState {
     /// Create new transition state with one transition.
     pub fn single(address: Address, transition: TransitionAccount) -> Self {
-        let mut transitions = HashMap::new();
+        let mut transitions = HashMap::default();
         transitions.insert(address, transition);
         TransitionState { transitions }
     }
@@ -23,14 +23,19 @@ impl TransitionState {
 
     pub fn add_transitions(&mut self, transitions: Vec<(Address, TransitionAccount)>) {
         for (address, account) in transitions {
-            match self.transitions.entry(address) {
-                Entry::Occupied(entry) => {
-                    let entry = entry.into_mut();
-                    entry.update(account);
-                }
-                Entry::Vacant(entry) => {
-                    entry.insert(account);
-                }
+            self.add_transition(address, account);
+        }
+    }
+
+    /// Add the transition of a single account.
+    pub fn add_transition(&mut self, address: Address, account: TransitionAccount) {
+        match self.transitions.entry(address) {
+            Entry::Occupied(entry) => {
+                let entry = entry.into_mut();
+                entry.update(account);
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(account);
             }
         }
     }
@@ -0,0 +1,164 @@
+//! Deployment of contracts from a controlled account, without transactions.
+use crate::{
+    interpreter::{create2_address, CreateInputs, InstructionResult},
+    primitives::{keccak256, Address, Bytes, EVMError, U256},
+    Database, DatabaseCommit, Evm,
+};
+use core::fmt;
+
+/// Deploys contracts from a controlled account and commits them to the database.
+///
+/// Deployments run the init code with [`Evm::create_frame`], so the deployer doesn't need a
+/// balance and only its nonce changes. The resulting state is committed right away, together
+/// with any state left in the journal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Deployer {
+    /// Account the contracts are deployed from.
+    pub address: Address,
+    /// Gas limit of each deployment.
+    pub gas_limit: u64,
+}
+
+impl Deployer {
+    /// Default gas limit of a deployment.
+    pub const DEFAULT_GAS_LIMIT: u64 = 30_000_000;
+
+    /// Creates a deployer for the given account.
+    pub fn new(address: Address) -> Self {
+        Self {
+            address,
+            gas_limit: Self::DEFAULT_GAS_LIMIT,
+        }
+    }
+
+    /// Sets the gas limit of each deployment.
+    pub fn with_gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    /// Deploys the contract with `CREATE` and returns its address.
+    ///
+    /// The address depends on the nonce of the deployer, which is increased.
+    pub fn deploy<EXT, DB: Database + DatabaseCommit>(
+        &self,
+        evm: &mut Evm<'_, EXT, DB>,
+        init_code: Bytes,
+    ) -> Result<Address, DeployError<DB::Error>> {
+        self.create(evm, None, init_code)
+    }
+
+    /// Deploys the contract with `CREATE2` and returns its address, see
+    /// [`Deployer::create2_address`].
+    pub fn deploy_create2<EXT, DB: Database + DatabaseCommit>(
+        &self,
+        evm: &mut Evm<'_, EXT, DB>,
+        salt: U256,
+        init_code: Bytes,
+    ) -> Result<Address, DeployError<DB::Error>> {
+        self.create(evm, Some(salt), init_code)
+    }
+
+    /// Returns the address of a contract deployed with `CREATE2`.
+    pub fn create2_address(&self, salt: U256, init_code: &[u8]) -> Address {
+        create2_address(self.address, salt, keccak256(init_code))
+    }
+
+    fn create<EXT, DB: Database + DatabaseCommit>(
+        &self,
+        evm: &mut Evm<'_, EXT, DB>,
+        salt: Option<U256>,
+        init_code: Bytes,
+    ) -> Result<Address, DeployError<DB::Error>> {
+        let mut inputs = CreateInputs::builder()
+            .caller(self.address)
+            .init_code(init_code)
+            .gas(self.gas_limit);
+        if let Some(salt) = salt {
+            inputs = inputs.salt(salt);
+        }
+        let inputs = inputs.build().expect("caller and gas limit are set");
+
+        let outcome = evm.create_frame(inputs).map_err(DeployError::Evm)?;
+        let (state, _) = evm.context.evm.journaled_state.finalize();
+        match outcome.address {
+            Some(address) if outcome.result.is_ok() => {
+                evm.context.evm.db.commit(state);
+                Ok(address)
+            }
+            _ => Err(DeployError::Failed {
+                result: outcome.result.result,
+                output: outcome.result.output,
+            }),
+        }
+    }
+}
+
+/// Error of a [`Deployer`].
+#[derive(Debug)]
+pub enum DeployError<DBError> {
+    /// The EVM failed, e.g. on a database error.
+    Evm(EVMError<DBError>),
+    /// The init code reverted or halted, nothing was committed.
+    Failed {
+        /// How the execution ended.
+        result: InstructionResult,
+        /// Revert data, if any.
+        output: Bytes,
+    },
+}
+
+impl<DBError: fmt::Display> fmt::Display for DeployError<DBError> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Evm(e) => e.fmt(f),
+            Self::Failed { result, .. } => write!(f, "deployment failed: {result:?}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<DBError: std::error::Error + 'static> std::error::Error for DeployError<DBError> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Evm(e) => Some(e),
+            Self::Failed { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::InMemoryDB, primitives::bytes};
+
+    #[test]
+    fn deploy() {
+        let mut evm = Evm::builder().with_db(InMemoryDB::default()).build();
+        let deployer = Deployer::new(Address::with_last_byte(0xde));
+        // Deploys `STOP`: PUSH1 0 PUSH1 0 MSTORE8 PUSH1 1 PUSH1 0 RETURN
+        let init_code = bytes!("600060005360016000f3");
+
+        let first = deployer.deploy(&mut evm, init_code.clone()).unwrap();
+        assert_eq!(first, deployer.address.create(0));
+        let second = deployer.deploy(&mut evm, init_code.clone()).unwrap();
+        assert_eq!(second, deployer.address.create(1));
+
+        let salt = U256::from(7);
+        let address = deployer
+            .deploy_create2(&mut evm, salt, init_code.clone())
+            .unwrap();
+        assert_eq!(address, deployer.create2_address(salt, &init_code));
+        assert!(evm.db().accounts.contains_key(&address));
+        assert_eq!(evm.db().accounts[&deployer.address].info.nonce, 3);
+
+        // Deploying to the same address again fails.
+        assert!(matches!(
+            deployer.deploy_create2(&mut evm, salt, init_code),
+            Err(DeployError::Failed {
+                result: InstructionResult::CreateCollision,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,426 @@
+//! Differential execution of a transaction on two differently configured EVMs.
+use crate::{
+    interpreter::Interpreter,
+    primitives::{
+        Account, Address, Bytes, EVMError, EVMResult, EvmState, ExecutionResult, Log, TxEnv, U256,
+    },
+    Database, Evm, EvmContext, Inspector,
+};
+use std::{collections::BTreeSet, vec::Vec};
+
+/// Executes the same transaction on two [Evm]s and reports where the executions diverge.
+///
+/// The EVMs can differ in spec id, handler registers or external context, e.g. to validate a
+/// hardfork against the previous one or a custom chain handler against mainnet. Both should be
+/// built on top of the same state. Transactions are not committed, so the runner can be reused.
+pub struct DifferentialRunner<'a, 'b, EXTA, DBA: Database, EXTB, DBB: Database> {
+    /// EVM reported on the left side of a [Divergence].
+    pub left: Evm<'a, EXTA, DBA>,
+    /// EVM reported on the right side of a [Divergence].
+    pub right: Evm<'b, EXTB, DBB>,
+}
+
+impl<'a, 'b, EXTA, DBA: Database, EXTB, DBB: Database>
+    DifferentialRunner<'a, 'b, EXTA, DBA, EXTB, DBB>
+{
+    /// Creates a new runner from the two EVMs.
+    pub fn new(left: Evm<'a, EXTA, DBA>, right: Evm<'b, EXTB, DBB>) -> Self {
+        Self { left, right }
+    }
+
+    /// Sets the transaction on both EVMs.
+    pub fn with_tx_env(mut self, tx: TxEnv) -> Self {
+        self.set_tx_env(tx);
+        self
+    }
+
+    /// Sets the transaction on both EVMs.
+    pub fn set_tx_env(&mut self, tx: TxEnv) {
+        self.left.context.evm.env.tx = tx.clone();
+        self.right.context.evm.env.tx = tx;
+    }
+
+    /// Executes the transaction on both EVMs without committing and compares the results.
+    pub fn run(&mut self) -> DifferentialReport<DBA::Error, DBB::Error> {
+        DifferentialReport::new(self.left.transact(), self.right.transact())
+    }
+
+    /// Returns the two EVMs.
+    pub fn into_inner(self) -> (Evm<'a, EXTA, DBA>, Evm<'b, EXTB, DBB>) {
+        (self.left, self.right)
+    }
+}
+
+impl<'a, 'b, DBA: Database, DBB: Database>
+    DifferentialRunner<'a, 'b, StepTracer, DBA, StepTracer, DBB>
+{
+    /// Executes the transaction on both EVMs like [`Self::run`], and compares the executed
+    /// instructions step by step.
+    ///
+    /// Both EVMs need the [`inspector_handle_register`](crate::inspector_handle_register) for the
+    /// steps to be recorded. Returns the first divergent step, with up to `context` preceding
+    /// steps.
+    pub fn run_steps(
+        &mut self,
+        context: usize,
+    ) -> (
+        DifferentialReport<DBA::Error, DBB::Error>,
+        Option<StepDivergence>,
+    ) {
+        self.left.context.external.clear();
+        self.right.context.external.clear();
+        let report = self.run();
+        let divergence = StepDivergence::find(
+            self.left.context.external.steps(),
+            self.right.context.external.steps(),
+            context,
+        );
+        (report, divergence)
+    }
+}
+
+/// Instruction executed by an EVM, recorded by [StepTracer].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Step {
+    /// Call depth of the frame, starting at 1 for the transaction frame.
+    pub depth: u64,
+    /// Account whose code is executed.
+    pub address: Address,
+    /// Program counter of the instruction.
+    pub pc: usize,
+    /// Opcode of the instruction.
+    pub opcode: u8,
+    /// Gas remaining before the instruction.
+    pub gas_remaining: u64,
+    /// Stack before the instruction, with the top at the end.
+    pub stack: Vec<U256>,
+}
+
+/// [Inspector] that records every executed instruction, see
+/// [`DifferentialRunner::run_steps`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StepTracer {
+    steps: Vec<Step>,
+}
+
+impl StepTracer {
+    /// Returns the recorded steps.
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Clears the recorded steps.
+    pub fn clear(&mut self) {
+        self.steps.clear();
+    }
+}
+
+impl<DB: Database> Inspector<DB> for StepTracer {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        self.steps.push(Step {
+            depth: context.journaled_state.depth(),
+            address: interp.contract.target_address,
+            pc: interp.program_counter(),
+            opcode: interp.current_opcode(),
+            gas_remaining: interp.gas.remaining(),
+            stack: interp.stack.data().clone(),
+        });
+    }
+}
+
+/// First step at which two step streams differ.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StepDivergence {
+    /// Index of the step in both streams.
+    pub index: usize,
+    /// Step of the left EVM, `None` if its execution ended before.
+    pub left: Option<Step>,
+    /// Step of the right EVM, `None` if its execution ended before.
+    pub right: Option<Step>,
+    /// Steps preceding the divergent one, which are the same in both streams.
+    pub context: Vec<Step>,
+}
+
+impl StepDivergence {
+    /// Returns the first step at which the streams differ, with up to `context` preceding steps.
+    ///
+    /// Steps are compared by all of their fields, including the gas remaining.
+    pub fn find(left: &[Step], right: &[Step], context: usize) -> Option<Self> {
+        let index = left
+            .iter()
+            .zip(right)
+            .position(|(left, right)| left != right)
+            .or_else(|| (left.len() != right.len()).then_some(left.len().min(right.len())))?;
+        Some(Self {
+            index,
+            left: left.get(index).cloned(),
+            right: right.get(index).cloned(),
+            context: left[index.saturating_sub(context)..index].to_vec(),
+        })
+    }
+}
+
+/// A difference between the left and the right execution of a [DifferentialRunner].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Divergence {
+    /// Only one side failed, or both failed with different validation errors.
+    Error,
+    /// The executions ended differently, e.g. success and revert, or halts with different reasons.
+    Status,
+    /// Gas used by the transaction differs.
+    GasUsed { left: u64, right: u64 },
+    /// Gas refunded to the caller differs.
+    GasRefunded { left: u64, right: u64 },
+    /// Returned data differs.
+    Output {
+        left: Option<Bytes>,
+        right: Option<Bytes>,
+    },
+    /// Emitted logs differ.
+    Logs { left: Vec<Log>, right: Vec<Log> },
+    /// The account was touched on only one side, or its balance, nonce, code hash or
+    /// self-destruct status differs.
+    Account { address: Address },
+    /// Value of a storage slot after the execution differs.
+    Storage {
+        address: Address,
+        slot: U256,
+        left: U256,
+        right: U256,
+    },
+}
+
+/// Results of both executions of a [DifferentialRunner] and their divergences.
+#[derive(Debug)]
+pub struct DifferentialReport<EA, EB> {
+    /// Result of the left EVM.
+    pub left: EVMResult<EA>,
+    /// Result of the right EVM.
+    pub right: EVMResult<EB>,
+    /// Divergences, with touched accounts in ascending address order.
+    pub divergences: Vec<Divergence>,
+}
+
+impl<EA, EB> DifferentialReport<EA, EB> {
+    /// Compares the two results.
+    pub fn new(left: EVMResult<EA>, right: EVMResult<EB>) -> Self {
+        let mut divergences = Vec::new();
+        match (&left, &right) {
+            (Ok(left), Ok(right)) => {
+                diff_result(&left.result, &right.result, &mut divergences);
+                diff_state(&left.state, &right.state, &mut divergences);
+            }
+            (Err(left), Err(right)) => {
+                let same = match (left, right) {
+                    (EVMError::Transaction(left), EVMError::Transaction(right)) => left == right,
+                    (EVMError::Header(left), EVMError::Header(right)) => left == right,
+                    _ => false,
+                };
+                if !same {
+                    divergences.push(Divergence::Error);
+                }
+            }
+            _ => divergences.push(Divergence::Error),
+        }
+        Self {
+            left,
+            right,
+            divergences,
+        }
+    }
+
+    /// Returns `true` if no divergence was found.
+    #[inline]
+    pub fn is_equivalent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+fn diff_result(left: &ExecutionResult, right: &ExecutionResult, out: &mut Vec<Divergence>) {
+    let same_status = match (left, right) {
+        (
+            ExecutionResult::Success { reason: left, .. },
+            ExecutionResult::Success { reason: right, .. },
+        ) => left == right,
+        (ExecutionResult::Revert { .. }, ExecutionResult::Revert { .. }) => true,
+        (
+            ExecutionResult::Halt { reason: left, .. },
+            ExecutionResult::Halt { reason: right, .. },
+        ) => left == right,
+        _ => false,
+    };
+    if !same_status {
+        out.push(Divergence::Status);
+    }
+    if left.gas_used() != right.gas_used() {
+        out.push(Divergence::GasUsed {
+            left: left.gas_used(),
+            right: right.gas_used(),
+        });
+    }
+    let gas_refunded = |result: &ExecutionResult| match result {
+        ExecutionResult::Success { gas_refunded, .. } => *gas_refunded,
+        _ => 0,
+    };
+    if gas_refunded(left) != gas_refunded(right) {
+        out.push(Divergence::GasRefunded {
+            left: gas_refunded(left),
+            right: gas_refunded(right),
+        });
+    }
+    if left.output() != right.output() {
+        out.push(Divergence::Output {
+            left: left.output().cloned(),
+            right: right.output().cloned(),
+        });
+    }
+    if left.logs() != right.logs() {
+        out.push(Divergence::Logs {
+            left: left.logs().to_vec(),
+            right: right.logs().to_vec(),
+        });
+    }
+}
+
+fn diff_state(left: &EvmState, right: &EvmState, out: &mut Vec<Divergence>) {
+    let touched = |state: &EvmState| {
+        state
+            .iter()
+            .filter(|(_, account)| account.is_touched())
+            .map(|(address, _)| *address)
+            .collect::<BTreeSet<_>>()
+    };
+    let addresses = &touched(left) | &touched(right);
+    for address in addresses {
+        let (Some(l), Some(r)) = (
+            left.get(&address).filter(|account| account.is_touched()),
+            right.get(&address).filter(|account| account.is_touched()),
+        ) else {
+            out.push(Divergence::Account { address });
+            continue;
+        };
+        if l.info != r.info || l.is_selfdestructed() != r.is_selfdestructed() {
+            out.push(Divergence::Account { address });
+        }
+        diff_storage(address, l, r, out);
+    }
+}
+
+fn diff_storage(address: Address, left: &Account, right: &Account, out: &mut Vec<Divergence>) {
+    let slots = left
+        .changed_storage_slots()
+        .chain(right.changed_storage_slots())
+        .map(|(slot, _)| *slot)
+        .collect::<BTreeSet<_>>();
+    for slot in slots {
+        let (l, r) = (left.storage.get(&slot), right.storage.get(&slot));
+        // A slot that was not loaded on one side still holds the original value.
+        let original = l.or(r).map(|slot| slot.original_value).unwrap_or_default();
+        let l = l.map_or(original, |slot| slot.present_value);
+        let r = r.map_or(original, |slot| slot.present_value);
+        if l != r {
+            out.push(Divergence::Storage {
+                address,
+                slot,
+                left: l,
+                right: r,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::BenchmarkDB,
+        primitives::{bytes, Bytecode, SpecId, TxKind},
+    };
+
+    #[test]
+    fn diverges_on_new_opcode() {
+        // PUSH1 0 TLOAD STOP
+        let evm = |spec_id| {
+            Evm::builder()
+                .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(bytes!(
+                    "60005c00"
+                ))))
+                .with_spec_id(spec_id)
+                .build()
+        };
+        let tx = TxEnv {
+            caller: Address::with_last_byte(1),
+            transact_to: TxKind::Call(Address::ZERO),
+            gas_limit: 100_000,
+            ..Default::default()
+        };
+
+        let mut runner = DifferentialRunner::new(evm(SpecId::CANCUN), evm(SpecId::CANCUN))
+            .with_tx_env(tx.clone());
+        assert!(runner.run().is_equivalent());
+
+        let (left, _) = runner.into_inner();
+        let mut runner = DifferentialRunner::new(left, evm(SpecId::SHANGHAI)).with_tx_env(tx);
+        let report = runner.run();
+        assert!(report.left.as_ref().unwrap().result.is_success());
+        assert!(report.right.as_ref().unwrap().result.is_halt());
+        assert_eq!(report.divergences[0], Divergence::Status);
+        assert!(matches!(
+            report.divergences[1],
+            Divergence::GasUsed { left, right: 100_000 } if left < 100_000
+        ));
+    }
+
+    #[test]
+    fn step_divergence() {
+        // PUSH1 1 PUSH1 2 ADD PUSH1 0 MSTORE PUSH1 0x20 PUSH1 0 RETURN
+        let add = Bytecode::new_raw(bytes!("600160020160005260206000f3"));
+        // Replaces ADD with MUL.
+        let mul = Bytecode::new_raw(bytes!("600160020260005260206000f3"));
+        let evm = |code| {
+            Evm::builder()
+                .with_db(BenchmarkDB::new_bytecode(code))
+                .with_external_context(StepTracer::default())
+                .append_handler_register(crate::inspector_handle_register)
+                .build()
+        };
+        let tx = TxEnv {
+            caller: Address::with_last_byte(1),
+            transact_to: TxKind::Call(Address::ZERO),
+            gas_limit: 100_000,
+            ..Default::default()
+        };
+
+        let mut runner =
+            DifferentialRunner::new(evm(add.clone()), evm(add.clone())).with_tx_env(tx.clone());
+        let (report, divergence) = runner.run_steps(2);
+        assert!(report.is_equivalent());
+        assert_eq!(divergence, None);
+
+        let (left, _) = runner.into_inner();
+        let mut runner = DifferentialRunner::new(left, evm(mul)).with_tx_env(tx);
+        let (report, divergence) = runner.run_steps(2);
+        assert!(!report.is_equivalent());
+        let divergence = divergence.unwrap();
+        assert_eq!(divergence.index, 2);
+        let (left, right) = (divergence.left.unwrap(), divergence.right.unwrap());
+        assert_eq!((left.pc, left.opcode, left.depth), (4, 0x01, 1));
+        assert_eq!((right.pc, right.opcode), (4, 0x02));
+        assert_eq!(left.stack, [U256::from(1), U256::from(2)]);
+        assert_eq!(
+            divergence
+                .context
+                .iter()
+                .map(|step| step.pc)
+                .collect::<Vec<_>>(),
+            [0, 2]
+        );
+
+        // A stream that ends earlier diverges at its end.
+        let steps = runner.left.context.external.steps();
+        let divergence = StepDivergence::find(steps, &steps[..3], 1).unwrap();
+        assert_eq!(divergence.index, 3);
+        assert_eq!(divergence.right, None);
+        assert_eq!(divergence.context, steps[2..3]);
+    }
+}
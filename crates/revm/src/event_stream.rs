@@ -0,0 +1,589 @@
+//! Streaming of execution events to tracers running in another thread or process.
+//!
+//! [`EventStreamInspector`] sends every event to an [`EventSink`] as soon as it happens, so the
+//! memory used by the inspector does not grow with the trace. With a bounded channel, the
+//! execution waits for the consumer when it is too slow. With a [`FrameWriter`], events are
+//! written as length-prefixed frames to any [`io::Write`], e.g. a socket or a file, and read
+//! back with a [`FrameReader`]:
+//!
+//! ```text
+//! frame      = len:u32le event
+//! event      = 0 step | 1 call | 2 call_end | 3 create | 4 create_end
+//! step       = depth:varint pc:varint opcode:u8 gas_remaining:varint
+//! call       = depth:varint scheme:u8 caller:20 target:20 value:u256 gas_limit:varint bytes
+//! create     = depth:varint salt_flag:u8 salt:u256? caller:20 value:u256 gas_limit:varint bytes
+//! call_end   = depth:varint success:u8 gas_used:varint bytes
+//! create_end = depth:varint success:u8 gas_used:varint address_flag:u8 address:20? bytes
+//! bytes      = len:varint byte*
+//! ```
+//!
+//! `varint` and `u256` are encoded as in [`binary_trace`](crate::binary_trace).
+use crate::{
+    binary_trace::{write_u256, write_varint, DecodeError, Reader, TraceStep},
+    interpreter::{
+        CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, CreateScheme,
+        Interpreter, InterpreterResult,
+    },
+    primitives::{db::Database, Address, Bytes, U256},
+    EvmContext, Inspector,
+};
+use std::{io, sync::mpsc, vec::Vec};
+
+/// Event of the execution of a transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TraceEvent {
+    /// An instruction is about to be executed.
+    Step(TraceStep),
+    /// A call frame starts.
+    Call {
+        /// Call depth of the caller.
+        depth: u64,
+        /// Kind of call.
+        scheme: CallScheme,
+        /// Caller of the frame.
+        caller: Address,
+        /// Account whose storage is used by the frame.
+        target: Address,
+        /// Value of the call.
+        value: U256,
+        /// Gas limit of the frame.
+        gas_limit: u64,
+        /// Input of the call.
+        input: Bytes,
+    },
+    /// A call frame ended.
+    CallEnd(FrameEnd),
+    /// A create frame starts.
+    Create {
+        /// Call depth of the creator.
+        depth: u64,
+        /// Salt of a `CREATE2`, `None` for `CREATE`.
+        salt: Option<U256>,
+        /// Creator of the contract.
+        caller: Address,
+        /// Value sent to the contract.
+        value: U256,
+        /// Gas limit of the frame.
+        gas_limit: u64,
+        /// Init code of the contract.
+        init_code: Bytes,
+    },
+    /// A create frame ended.
+    CreateEnd {
+        /// How the frame ended.
+        end: FrameEnd,
+        /// Address of the created contract, if any.
+        address: Option<Address>,
+    },
+}
+
+/// How a call or create frame ended.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FrameEnd {
+    /// Call depth of the caller.
+    pub depth: u64,
+    /// The frame succeeded.
+    pub success: bool,
+    /// Gas used by the frame.
+    pub gas_used: u64,
+    /// Output or revert data.
+    pub output: Bytes,
+}
+
+impl FrameEnd {
+    fn new(depth: u64, result: &InterpreterResult) -> Self {
+        Self {
+            depth,
+            success: result.is_ok(),
+            gas_used: result.gas.spent(),
+            output: result.output.clone(),
+        }
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        write_varint(out, self.depth);
+        out.push(self.success as u8);
+        write_varint(out, self.gas_used);
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            depth: reader.read_varint()?,
+            success: reader.read_bool()?,
+            gas_used: reader.read_varint()?,
+            output: Bytes::new(),
+        })
+    }
+}
+
+impl TraceEvent {
+    /// Appends the encoded event, without its frame length, to `out`.
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Step(step) => {
+                out.push(0);
+                write_varint(out, step.depth);
+                write_varint(out, step.pc);
+                out.push(step.opcode);
+                write_varint(out, step.gas_remaining);
+            }
+            Self::Call {
+                depth,
+                scheme,
+                caller,
+                target,
+                value,
+                gas_limit,
+                input,
+            } => {
+                out.push(1);
+                write_varint(out, *depth);
+                out.push(encode_scheme(*scheme));
+                out.extend_from_slice(caller.as_slice());
+                out.extend_from_slice(target.as_slice());
+                write_u256(out, *value);
+                write_varint(out, *gas_limit);
+                write_bytes(out, input);
+            }
+            Self::CallEnd(end) => {
+                out.push(2);
+                end.encode(out);
+                write_bytes(out, &end.output);
+            }
+            Self::Create {
+                depth,
+                salt,
+                caller,
+                value,
+                gas_limit,
+                init_code,
+            } => {
+                out.push(3);
+                write_varint(out, *depth);
+                out.push(salt.is_some() as u8);
+                if let Some(salt) = salt {
+                    write_u256(out, *salt);
+                }
+                out.extend_from_slice(caller.as_slice());
+                write_u256(out, *value);
+                write_varint(out, *gas_limit);
+                write_bytes(out, init_code);
+            }
+            Self::CreateEnd { end, address } => {
+                out.push(4);
+                end.encode(out);
+                out.push(address.is_some() as u8);
+                if let Some(address) = address {
+                    out.extend_from_slice(address.as_slice());
+                }
+                write_bytes(out, &end.output);
+            }
+        }
+    }
+
+    /// Decodes an event encoded with [`TraceEvent::encode`].
+    pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let reader = &mut Reader { bytes };
+        let event = match reader.read_u8()? {
+            0 => Self::Step(TraceStep {
+                depth: reader.read_varint()?,
+                pc: reader.read_varint()?,
+                opcode: reader.read_u8()?,
+                gas_remaining: reader.read_varint()?,
+            }),
+            1 => Self::Call {
+                depth: reader.read_varint()?,
+                scheme: decode_scheme(reader.read_u8()?)?,
+                caller: Address::from_slice(reader.read_bytes(20)?),
+                target: Address::from_slice(reader.read_bytes(20)?),
+                value: reader.read_u256()?,
+                gas_limit: reader.read_varint()?,
+                input: read_bytes(reader)?,
+            },
+            2 => {
+                let mut end = FrameEnd::decode(reader)?;
+                end.output = read_bytes(reader)?;
+                Self::CallEnd(end)
+            }
+            3 => Self::Create {
+                depth: reader.read_varint()?,
+                salt: match reader.read_bool()? {
+                    true => Some(reader.read_u256()?),
+                    false => None,
+                },
+                caller: Address::from_slice(reader.read_bytes(20)?),
+                value: reader.read_u256()?,
+                gas_limit: reader.read_varint()?,
+                init_code: read_bytes(reader)?,
+            },
+            4 => {
+                let mut end = FrameEnd::decode(reader)?;
+                let address = match reader.read_bool()? {
+                    true => Some(Address::from_slice(reader.read_bytes(20)?)),
+                    false => None,
+                };
+                end.output = read_bytes(reader)?;
+                Self::CreateEnd { end, address }
+            }
+            _ => return Err(DecodeError::InvalidValue),
+        };
+        if !reader.bytes.is_empty() {
+            return Err(DecodeError::InvalidValue);
+        }
+        Ok(event)
+    }
+}
+
+fn encode_scheme(scheme: CallScheme) -> u8 {
+    match scheme {
+        CallScheme::Call => 0,
+        CallScheme::CallCode => 1,
+        CallScheme::DelegateCall => 2,
+        CallScheme::StaticCall => 3,
+        CallScheme::ExtCall => 4,
+        CallScheme::ExtStaticCall => 5,
+        CallScheme::ExtDelegateCall => 6,
+    }
+}
+
+fn decode_scheme(byte: u8) -> Result<CallScheme, DecodeError> {
+    Ok(match byte {
+        0 => CallScheme::Call,
+        1 => CallScheme::CallCode,
+        2 => CallScheme::DelegateCall,
+        3 => CallScheme::StaticCall,
+        4 => CallScheme::ExtCall,
+        5 => CallScheme::ExtStaticCall,
+        6 => CallScheme::ExtDelegateCall,
+        _ => return Err(DecodeError::InvalidValue),
+    })
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn read_bytes(reader: &mut Reader<'_>) -> Result<Bytes, DecodeError> {
+    let len = reader.read_len()?;
+    Ok(Bytes::copy_from_slice(reader.read_bytes(len)?))
+}
+
+/// Destination of the events of an [`EventStreamInspector`].
+pub trait EventSink {
+    /// Error returned when the event can not be sent.
+    type Error;
+
+    /// Sends the event.
+    fn send(&mut self, event: TraceEvent) -> Result<(), Self::Error>;
+}
+
+/// Sends the events to a bounded channel, waiting while it is full.
+impl EventSink for mpsc::SyncSender<TraceEvent> {
+    type Error = mpsc::SendError<TraceEvent>;
+
+    fn send(&mut self, event: TraceEvent) -> Result<(), Self::Error> {
+        mpsc::SyncSender::send(self, event)
+    }
+}
+
+/// Sends the events to an unbounded channel.
+impl EventSink for mpsc::Sender<TraceEvent> {
+    type Error = mpsc::SendError<TraceEvent>;
+
+    fn send(&mut self, event: TraceEvent) -> Result<(), Self::Error> {
+        mpsc::Sender::send(self, event)
+    }
+}
+
+/// Writes the events as length-prefixed frames.
+///
+/// Every frame is written with a single call to [`io::Write::write_all`], wrap the writer in an
+/// [`io::BufWriter`] to batch them.
+#[derive(Debug, Default)]
+pub struct FrameWriter<W> {
+    writer: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: io::Write> FrameWriter<W> {
+    /// Creates a frame writer.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Returns the writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: io::Write> EventSink for FrameWriter<W> {
+    type Error = io::Error;
+
+    fn send(&mut self, event: TraceEvent) -> Result<(), Self::Error> {
+        self.buffer.clear();
+        self.buffer.extend_from_slice(&[0; 4]);
+        event.encode(&mut self.buffer);
+        let len = u32::try_from(self.buffer.len() - 4)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "event too large"))?;
+        self.buffer[..4].copy_from_slice(&len.to_le_bytes());
+        self.writer.write_all(&self.buffer)
+    }
+}
+
+/// Reads the events written by a [`FrameWriter`].
+///
+/// Iteration stops at the end of the reader, or after the first error.
+#[derive(Debug, Default)]
+pub struct FrameReader<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    done: bool,
+}
+
+impl<R: io::Read> FrameReader<R> {
+    /// Creates a frame reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+            done: false,
+        }
+    }
+
+    fn read_event(&mut self) -> io::Result<Option<TraceEvent>> {
+        let mut len = [0; 4];
+        // The stream can only end between frames.
+        match self.reader.read(&mut len[..1])? {
+            0 => return Ok(None),
+            _ => self.reader.read_exact(&mut len[1..])?,
+        }
+        self.buffer.resize(u32::from_le_bytes(len) as usize, 0);
+        self.reader.read_exact(&mut self.buffer)?;
+        TraceEvent::decode(&self.buffer)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl<R: io::Read> Iterator for FrameReader<R> {
+    type Item = io::Result<TraceEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let event = self.read_event().transpose();
+        self.done = !matches!(event, Some(Ok(_)));
+        event
+    }
+}
+
+/// [Inspector] that sends the events of the execution to an [`EventSink`].
+///
+/// The first error of the sink stops the stream, the execution continues without sending
+/// events. The error is returned by [`EventStreamInspector::error`].
+pub struct EventStreamInspector<S: EventSink> {
+    sink: S,
+    steps: bool,
+    error: Option<S::Error>,
+}
+
+impl<S: EventSink> EventStreamInspector<S> {
+    /// Creates an inspector sending all events to the sink.
+    pub fn new(sink: S) -> Self {
+        Self {
+            sink,
+            steps: true,
+            error: None,
+        }
+    }
+
+    /// Sets whether [`TraceEvent::Step`] events are sent, only frame events are sent otherwise.
+    pub fn with_steps(mut self, steps: bool) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    /// Returns the error that stopped the stream, if any.
+    pub fn error(&self) -> Option<&S::Error> {
+        self.error.as_ref()
+    }
+
+    /// Returns the sink and the error that stopped the stream, if any.
+    pub fn into_parts(self) -> (S, Option<S::Error>) {
+        (self.sink, self.error)
+    }
+
+    fn emit(&mut self, event: TraceEvent) {
+        if self.error.is_none() {
+            self.error = self.sink.send(event).err();
+        }
+    }
+}
+
+impl<DB: Database, S: EventSink> Inspector<DB> for EventStreamInspector<S> {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        if self.steps {
+            self.emit(TraceEvent::Step(TraceStep {
+                depth: context.journaled_state.depth(),
+                pc: interp.program_counter() as u64,
+                opcode: interp.current_opcode(),
+                gas_remaining: interp.gas.remaining(),
+            }));
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.emit(TraceEvent::Call {
+            depth: context.journaled_state.depth(),
+            scheme: inputs.scheme,
+            caller: inputs.caller,
+            target: inputs.target_address,
+            value: inputs.call_value(),
+            gas_limit: inputs.gas_limit,
+            input: inputs.input.clone(),
+        });
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        let end = FrameEnd::new(context.journaled_state.depth(), &outcome.result);
+        self.emit(TraceEvent::CallEnd(end));
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.emit(TraceEvent::Create {
+            depth: context.journaled_state.depth(),
+            salt: match inputs.scheme {
+                CreateScheme::Create => None,
+                CreateScheme::Create2 { salt } => Some(salt),
+            },
+            caller: inputs.caller,
+            value: inputs.value,
+            gas_limit: inputs.gas_limit,
+            init_code: inputs.init_code.clone(),
+        });
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.emit(TraceEvent::CreateEnd {
+            end: FrameEnd::new(context.journaled_state.depth(), &outcome.result),
+            address: outcome.address,
+        });
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::BenchmarkDB,
+        inspector_handle_register,
+        primitives::{bytes, Bytecode, TxKind},
+        Evm,
+    };
+    use std::thread;
+
+    fn transact<S: EventSink>(inspector: EventStreamInspector<S>) -> EventStreamInspector<S> {
+        // PUSH1 0x2a PUSH1 0 SSTORE STOP
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(bytes!(
+                "602a60005500"
+            ))))
+            .with_external_context(inspector)
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TxKind::Call(Address::ZERO);
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        evm.transact().unwrap();
+        evm.into_context().external
+    }
+
+    #[test]
+    fn bounded_channel() {
+        // The channel only holds one event, the execution waits for the consumer.
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let consumer = thread::spawn(move || receiver.into_iter().collect::<Vec<_>>());
+        let (sender, error) = transact(EventStreamInspector::new(sender)).into_parts();
+        assert!(error.is_none());
+        drop(sender);
+
+        let events = consumer.join().unwrap();
+        assert_eq!(events.len(), 6);
+        assert!(matches!(
+            events[0],
+            TraceEvent::Call {
+                depth: 0,
+                scheme: CallScheme::Call,
+                ..
+            }
+        ));
+        let opcodes = events[1..5]
+            .iter()
+            .map(|event| match event {
+                TraceEvent::Step(step) => step.opcode,
+                _ => panic!("expected a step, got {event:?}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(opcodes, [0x60, 0x60, 0x55, 0x00]);
+        assert!(matches!(
+            &events[5],
+            TraceEvent::CallEnd(FrameEnd { success: true, .. })
+        ));
+    }
+
+    #[test]
+    fn frames() {
+        let inspector = transact(EventStreamInspector::new(FrameWriter::new(Vec::new())));
+        let (writer, error) = inspector.into_parts();
+        assert!(error.is_none());
+        let stream = writer.into_inner();
+
+        let (sender, receiver) = mpsc::channel();
+        transact(EventStreamInspector::new(sender));
+        let expected = receiver.into_iter().collect::<Vec<_>>();
+        let events = FrameReader::new(stream.as_slice())
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(events, expected);
+
+        // A truncated stream is an error.
+        let mut reader = FrameReader::new(&stream[..stream.len() - 1]);
+        assert!(reader.by_ref().last().unwrap().is_err());
+        assert!(reader.next().is_none());
+
+        // Without steps, only the frame events are written.
+        let inspector =
+            transact(EventStreamInspector::new(FrameWriter::new(Vec::new())).with_steps(false));
+        let stream = inspector.into_parts().0.into_inner();
+        assert_eq!(FrameReader::new(stream.as_slice()).count(), 2);
+    }
+}
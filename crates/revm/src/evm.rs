@@ -3,13 +3,17 @@ use crate::{
     db::{Database, DatabaseCommit, EmptyDB},
     handler::Handler,
     interpreter::{
-        CallInputs, CreateInputs, EOFCreateInputs, Host, InterpreterAction, SharedMemory,
+        CallInputs, CallOutcome, CreateInputs, CreateOutcome, EOFCreateInputs, Host,
+        InstructionResult, InterpreterAction, InterpreterResult, SharedMemory,
     },
     primitives::{
-        specification::SpecId, BlockEnv, CfgEnv, EVMError, EVMResult, EnvWithHandlerCfg,
-        ExecutionResult, HandlerCfg, ResultAndState, TxEnv, TxKind, EOF_MAGIC_BYTES,
+        hash_map::Entry, specification::SpecId, Account, Address, BlockEnv, Bytes, CfgEnv,
+        EVMError, EVMResult, Eip, EnvWithHandlerCfg, EvmState, EvmStorageSlot, ExecutionResult,
+        HandlerCfg, ResultAndState, Transaction, TxEnv, TxEnvTemplate, TxKind, TxOverrides,
+        EOF_MAGIC_BYTES,
     },
-    Context, ContextWithHandlerCfg, Frame, FrameOrResult, FrameResult,
+    Context, ContextPrecompiles, ContextWithHandlerCfg, Frame, FrameOrResult, FrameResult,
+    PendingFrames, ShallowOutcome, WarmState,
 };
 use core::fmt;
 use std::{boxed::Box, vec::Vec};
@@ -43,10 +47,20 @@ where
 impl<EXT, DB: Database + DatabaseCommit> Evm<'_, EXT, DB> {
     /// Commit the changes to the database.
     pub fn transact_commit(&mut self) -> Result<ExecutionResult, EVMError<DB::Error>> {
-        let ResultAndState { result, state } = self.transact()?;
+        let ResultAndState { result, state, .. } = self.transact()?;
         self.context.evm.db.commit(state);
         Ok(result)
     }
+
+    /// Commit the changes to the database one account at a time, with
+    /// [`DatabaseCommit::commit_account`].
+    ///
+    /// Like [`Evm::transact_streaming`], the accounts are streamed out of the journal and its
+    /// map is kept for the next transaction.
+    pub fn transact_commit_streaming(&mut self) -> Result<ExecutionResult, EVMError<DB::Error>> {
+        self.transact_streaming_inner(|db, address, account| db.commit_account(address, account))
+            .map(|output| output.result)
+    }
 }
 
 impl<'a> Evm<'a, (), EmptyDB> {
@@ -77,7 +91,80 @@ impl<'a, EXT, DB: Database> Evm<'a, EXT, DB> {
     pub fn run_the_loop(&mut self, first_frame: Frame) -> Result<FrameResult, EVMError<DB::Error>> {
         let mut call_stack: Vec<Frame> = Vec::with_capacity(1025);
         call_stack.push(first_frame);
+        let mut shared_memory = self.new_shared_memory();
+
+        match self.run_frames(&mut call_stack, &mut shared_memory, usize::MAX)? {
+            LoopExit::Result(result) => Ok(result),
+            LoopExit::Pending(_) => unreachable!("sub-calls are executed without a depth limit"),
+        }
+    }
+
+    /// Runs `first_frame` like [`Evm::run_the_loop`], but returns the sub-calls requested by
+    /// frames at `max_depth` instead of executing them, the first frame having depth 1.
+    ///
+    /// The caller resolves the sub-call, e.g. with [`Evm::call_frame`] or by computing the
+    /// outcome itself, and continues with [`Evm::resume_shallow`]. Sub-calls that are not
+    /// executed by the EVM skip the handler and the inspector entirely, so the value transfer
+    /// and the depth check are up to the caller.
+    pub fn run_the_loop_shallow(
+        &mut self,
+        first_frame: Frame,
+        max_depth: usize,
+    ) -> Result<ShallowOutcome, EVMError<DB::Error>> {
+        let mut call_stack: Vec<Frame> = Vec::with_capacity(max_depth.min(1025));
+        call_stack.push(first_frame);
+        let shared_memory = self.new_shared_memory();
+        self.continue_shallow(call_stack, shared_memory, max_depth)
+    }
 
+    /// Inserts the `result` of the sub-call requested by the top frame of `pending` and
+    /// continues the shallow execution.
+    ///
+    /// The gas of the result is returned to the frame, so its limit should be the gas limit
+    /// of the requested sub-call. For calls, the output is copied to the memory range of the
+    /// [`CallOutcome`].
+    pub fn resume_shallow(
+        &mut self,
+        pending: PendingFrames,
+        result: FrameResult,
+    ) -> Result<ShallowOutcome, EVMError<DB::Error>> {
+        let PendingFrames {
+            mut call_stack,
+            mut shared_memory,
+            max_depth,
+            ..
+        } = pending;
+        let output = self
+            .insert_frame_result(&mut call_stack, &mut shared_memory, result)
+            .and_then(|()| self.continue_shallow(call_stack, shared_memory, max_depth));
+        if output.is_err() {
+            self.clear();
+        }
+        output
+    }
+
+    /// Runs the frames of a shallow execution until they return or request a sub-call.
+    fn continue_shallow(
+        &mut self,
+        mut call_stack: Vec<Frame>,
+        mut shared_memory: SharedMemory,
+        max_depth: usize,
+    ) -> Result<ShallowOutcome, EVMError<DB::Error>> {
+        Ok(
+            match self.run_frames(&mut call_stack, &mut shared_memory, max_depth)? {
+                LoopExit::Result(result) => ShallowOutcome::Result(result),
+                LoopExit::Pending(action) => ShallowOutcome::Pending(PendingFrames {
+                    action,
+                    call_stack,
+                    shared_memory,
+                    max_depth,
+                }),
+            },
+        )
+    }
+
+    /// Creates the memory shared by the frames of a call loop.
+    fn new_shared_memory(&self) -> SharedMemory {
         #[cfg(feature = "memory_limit")]
         let mut shared_memory =
             SharedMemory::new_with_memory_limit(self.context.evm.env.cfg.memory_limit);
@@ -85,20 +172,54 @@ impl<'a, EXT, DB: Database> Evm<'a, EXT, DB> {
         let mut shared_memory = SharedMemory::new();
 
         shared_memory.new_context();
+        shared_memory
+    }
 
-        // Peek the last stack frame.
-        let mut stack_frame = call_stack.last_mut().unwrap();
-
+    /// Executes the frames of `call_stack` until the first one returns, or until a frame at
+    /// `max_depth` or deeper requests a sub-call.
+    #[inline]
+    fn run_frames(
+        &mut self,
+        call_stack: &mut Vec<Frame>,
+        shared_memory: &mut SharedMemory,
+        max_depth: usize,
+    ) -> Result<LoopExit, EVMError<DB::Error>> {
         loop {
-            // Execute the frame.
+            // Execute the top frame.
+            let stack_frame = call_stack.last_mut().expect("call stack is never empty");
             let next_action =
                 self.handler
-                    .execute_frame(stack_frame, &mut shared_memory, &mut self.context)?;
+                    .execute_frame(stack_frame, shared_memory, &mut self.context)?;
 
             // Take error and break the loop, if any.
             // This error can be set in the Interpreter when it interacts with the context.
             self.context.evm.take_error()?;
 
+            // Halt the frame if it grew the journal past `CfgEnv::journal_limit`.
+            let next_action = if self.context.evm.is_journal_limit_exceeded() {
+                InterpreterAction::Return {
+                    result: InterpreterResult::new(
+                        InstructionResult::JournalLimitExceeded,
+                        Bytes::new(),
+                        *stack_frame.interpreter().gas(),
+                    ),
+                }
+            } else {
+                next_action
+            };
+
+            // Leave the sub-calls of the deepest frames to the caller.
+            if call_stack.len() >= max_depth
+                && matches!(
+                    next_action,
+                    InterpreterAction::Call { .. }
+                        | InterpreterAction::Create { .. }
+                        | InterpreterAction::EOFCreate { .. }
+                )
+            {
+                return Ok(LoopExit::Pending(next_action));
+            }
+
             let exec = &mut self.handler.execution;
             let frame_or_result = match next_action {
                 InterpreterAction::Call { inputs } => exec.call(&mut self.context, inputs)?,
@@ -138,34 +259,52 @@ impl<'a, EXT, DB: Database> Evm<'a, EXT, DB> {
                 FrameOrResult::Frame(frame) => {
                     shared_memory.new_context();
                     call_stack.push(frame);
-                    stack_frame = call_stack.last_mut().unwrap();
                 }
                 FrameOrResult::Result(result) => {
-                    let Some(top_frame) = call_stack.last_mut() else {
+                    if call_stack.is_empty() {
                         // Break the loop if there are no more frames.
-                        return Ok(result);
-                    };
-                    stack_frame = top_frame;
-                    let ctx = &mut self.context;
-                    // Insert result to the top frame.
-                    match result {
-                        FrameResult::Call(outcome) => {
-                            // return_call
-                            exec.insert_call_outcome(ctx, stack_frame, &mut shared_memory, outcome)?
-                        }
-                        FrameResult::Create(outcome) => {
-                            // return_create
-                            exec.insert_create_outcome(ctx, stack_frame, outcome)?
-                        }
-                        FrameResult::EOFCreate(outcome) => {
-                            // return_eofcreate
-                            exec.insert_eofcreate_outcome(ctx, stack_frame, outcome)?
-                        }
+                        return Ok(LoopExit::Result(result));
                     }
+                    self.insert_frame_result(call_stack, shared_memory, result)?;
                 }
             }
         }
     }
+
+    /// Inserts the result of a sub-call to the top frame of `call_stack`.
+    #[inline]
+    fn insert_frame_result(
+        &mut self,
+        call_stack: &mut [Frame],
+        shared_memory: &mut SharedMemory,
+        result: FrameResult,
+    ) -> Result<(), EVMError<DB::Error>> {
+        let stack_frame = call_stack.last_mut().expect("call stack is never empty");
+        let exec = &mut self.handler.execution;
+        let ctx = &mut self.context;
+        match result {
+            FrameResult::Call(outcome) => {
+                // return_call
+                exec.insert_call_outcome(ctx, stack_frame, shared_memory, outcome)
+            }
+            FrameResult::Create(outcome) => {
+                // return_create
+                exec.insert_create_outcome(ctx, stack_frame, outcome)
+            }
+            FrameResult::EOFCreate(outcome) => {
+                // return_eofcreate
+                exec.insert_eofcreate_outcome(ctx, stack_frame, outcome)
+            }
+        }
+    }
+}
+
+/// Reason the frames of a call loop stopped executing.
+enum LoopExit {
+    /// The first frame returned.
+    Result(FrameResult),
+    /// A frame at the depth limit requested a sub-call.
+    Pending(InterpreterAction),
 }
 
 impl<EXT, DB: Database> Evm<'_, EXT, DB> {
@@ -198,7 +337,10 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
         let initial_gas_spend = self
             .handler
             .validation()
-            .initial_tx_gas(&self.context.evm.env)
+            .initial_tx_gas(
+                &self.context.evm.env,
+                &self.context.evm.gas_schedule_for(self.spec_id()),
+            )
             .map_err(|e| {
                 self.clear();
                 e
@@ -213,10 +355,10 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
     #[inline]
     fn preverify_transaction_inner(&mut self) -> Result<u64, EVMError<DB::Error>> {
         self.handler.validation().env(&self.context.evm.env)?;
-        let initial_gas_spend = self
-            .handler
-            .validation()
-            .initial_tx_gas(&self.context.evm.env)?;
+        let initial_gas_spend = self.handler.validation().initial_tx_gas(
+            &self.context.evm.env,
+            &self.context.evm.gas_schedule_for(self.spec_id()),
+        )?;
         self.handler
             .validation()
             .tx_against_state(&mut self.context)?;
@@ -239,6 +381,314 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
         output
     }
 
+    /// Transact transaction and pass every account of the state to `visitor`.
+    ///
+    /// The accounts are drained out of the journal when the transaction is finalized instead of
+    /// being moved into the returned state, which stays empty unless the `end` handler builds
+    /// one, e.g. for a failed deposit. The map of the journal is kept for the next transaction,
+    /// so processing a block does not allocate a new map for every transaction.
+    pub fn transact_streaming<F>(&mut self, mut visitor: F) -> EVMResult<DB::Error>
+    where
+        F: FnMut(Address, Account),
+    {
+        self.transact_streaming_inner(|_, address, account| visitor(address, account))
+    }
+
+    /// Transact transaction and pass every account of the state to `visitor`, together with the
+    /// database.
+    fn transact_streaming_inner<F>(&mut self, mut visitor: F) -> EVMResult<DB::Error>
+    where
+        F: FnMut(&mut DB, Address, Account),
+    {
+        let initial_gas_spend = self.preverify_transaction_inner().map_err(|e| {
+            self.clear();
+            e
+        })?;
+
+        // The `output` handler leaves the state in the journal.
+        self.context.evm.journaled_state.keep_finalized_state = true;
+        let output = self.transact_preverified_inner(initial_gas_spend);
+        let mut state = core::mem::take(&mut self.context.evm.journaled_state.state);
+        if output.is_err() {
+            state.clear();
+        }
+        let mut output = self.handler.post_execution().end(&mut self.context, output);
+
+        match &mut output {
+            Ok(output) => {
+                let db = &mut self.context.evm.db;
+                for (address, account) in state.drain().chain(output.state.drain()) {
+                    visitor(db, address, account);
+                }
+            }
+            Err(_) => state.clear(),
+        }
+        self.clear();
+        self.context.evm.journaled_state.state = state;
+        output
+    }
+
+    /// Transacts the template once for every overrides, without committing the state.
+    ///
+    /// The environment is validated and the initial gas is calculated only once, as they do not
+    /// depend on the overridden fields, while every transaction is still validated against the
+    /// state. All transactions run against the same database state, so the accounts and storage
+    /// slots loaded by a transaction are kept, as they were before it, and start the next
+    /// transactions cold instead of being loaded again. The state of every result also contains
+    /// the accounts kept from the previous transactions.
+    ///
+    /// The kept accounts are cloned into the journal before every transaction, so the cost per
+    /// transaction grows with the number of accounts and slots loaded by the whole batch. The
+    /// info of an account touched by a transaction is fetched again from the database the first
+    /// time it is kept. See the `transact_many` benchmark for the comparison with transacting
+    /// every instantiation on its own.
+    ///
+    /// Returns an error if the template fails validation, otherwise the result of every
+    /// transaction in the order of the overrides. The transaction environment is left set to the
+    /// last instantiation.
+    pub fn transact_many<I>(
+        &mut self,
+        template: &TxEnvTemplate,
+        overrides: I,
+    ) -> Result<Vec<EVMResult<DB::Error>>, EVMError<DB::Error>>
+    where
+        I: IntoIterator<Item = TxOverrides>,
+    {
+        *self.tx_mut() = template.tx().clone();
+        let validation = self.handler.validation();
+        let initial_gas_spend = validation
+            .env(&self.context.evm.env)
+            .and_then(|_| {
+                validation.initial_tx_gas(
+                    &self.context.evm.env,
+                    &self.context.evm.gas_schedule_for(self.spec_id()),
+                )
+            })
+            .map_err(|e| {
+                self.clear();
+                e
+            })?;
+
+        let mut loaded = EvmState::default();
+        Ok(overrides
+            .into_iter()
+            .map(|overrides| {
+                overrides.apply(self.tx_mut());
+                self.context.evm.journaled_state.state = loaded.clone();
+                self.handler
+                    .validation()
+                    .tx_against_state(&mut self.context)
+                    .map_err(|e| {
+                        self.clear();
+                        e
+                    })?;
+                let output = self.transact_preverified_inner(initial_gas_spend);
+                let output = self.handler.post_execution().end(&mut self.context, output);
+                if let Ok(output) = &output {
+                    self.keep_loaded_state(&output.state, &mut loaded);
+                }
+                self.clear();
+                output
+            })
+            .collect())
+    }
+
+    /// Adds the accounts and storage slots of `state` to `loaded`, cold and as they were before
+    /// the transaction, see [`Self::transact_many`].
+    ///
+    /// Accounts created or destroyed by the transaction and the warm preloaded ones are skipped,
+    /// the info of the touched accounts is loaded again from the database.
+    fn keep_loaded_state(&mut self, state: &EvmState, loaded: &mut EvmState) {
+        let evm = &mut self.context.evm.inner;
+        for (address, account) in state {
+            if account.is_created()
+                || account.is_selfdestructed()
+                || evm
+                    .journaled_state
+                    .warm_preloaded_addresses
+                    .contains(address)
+            {
+                continue;
+            }
+            let kept = match loaded.entry(*address) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let mut kept = if account.is_touched() {
+                        // The info may have changed. On error, the next transaction loads the
+                        // account itself.
+                        let Ok(info) = evm.db.basic(*address) else {
+                            continue;
+                        };
+                        info.map_or_else(Account::new_not_existing, Account::from)
+                    } else if account.is_loaded_as_not_existing() {
+                        Account::new_not_existing()
+                    } else {
+                        Account::from(account.info.clone())
+                    };
+                    kept.mark_cold();
+                    entry.insert(kept)
+                }
+            };
+            for (key, slot) in account.storage.iter() {
+                kept.storage.entry(*key).or_insert_with(|| {
+                    let mut slot = EvmStorageSlot::new(slot.original_value);
+                    slot.mark_cold();
+                    slot
+                });
+            }
+        }
+    }
+
+    /// Transacts the transactions one after another against the same state, without committing
+    /// it, while sharing the EIP-2929 warm accounts and storage slots between them.
+    ///
+    /// Every transaction starts with the accounts and storage slots of `warm_state` warm, and the
+    /// accesses of every successful transaction are added to it, so it can seed the next
+    /// simulation of the same transactions.
+    ///
+    /// The gas used is "optimistic" and deviates from consensus, see [`WarmState`]. Transact with
+    /// an empty warm state and a single transaction to get the consensus result.
+    pub fn transact_with_shared_warm_state(
+        &mut self,
+        txs: &[TxEnv],
+        warm_state: &mut WarmState,
+    ) -> Vec<EVMResult<DB::Error>> {
+        txs.iter()
+            .map(|tx| {
+                *self.tx_mut() = tx.clone();
+                self.load_warm_state(warm_state).map_err(|e| {
+                    self.clear();
+                    e
+                })?;
+                let output = self.transact()?;
+                warm_state.extend_from_state(&output.state);
+                Ok(output)
+            })
+            .collect()
+    }
+
+    /// Loads the accounts and storage slots of the warm state into the journaled state, which
+    /// makes them warm.
+    fn load_warm_state(&mut self, warm_state: &WarmState) -> Result<(), EVMError<DB::Error>> {
+        let evm = &mut self.context.evm.inner;
+        for (address, keys) in &warm_state.accounts {
+            evm.journaled_state.initial_account_load(
+                *address,
+                keys.iter().copied(),
+                &mut evm.db,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Executes a single message call against the current state, without a transaction.
+    ///
+    /// Transaction validation, the deduction of the caller balance, gas refunds and the
+    /// beneficiary reward are skipped, the call is executed with the gas limit of the inputs.
+    ///
+    /// State changes are kept in the journaled state, so consecutive calls see each other's
+    /// changes. They are returned with the state of the next transaction, or can be taken with
+    /// [`JournaledState::finalize`](crate::JournaledState::finalize). On error, the journaled
+    /// state is cleared.
+    pub fn call_frame(&mut self, inputs: CallInputs) -> Result<CallOutcome, EVMError<DB::Error>> {
+        let result = self.run_frame(
+            |evm| {
+                evm.handler
+                    .execution()
+                    .call(&mut evm.context, Box::new(inputs))
+            },
+            Self::run_the_loop,
+        )?;
+        match result {
+            FrameResult::Call(outcome) => Ok(outcome),
+            _ => unreachable!("call frame returned a create result"),
+        }
+    }
+
+    /// Executes a single call like [`Evm::call_frame`], but returns the sub-calls requested by
+    /// frames at `max_depth` instead of executing them, see [`Evm::run_the_loop_shallow`].
+    ///
+    /// With a `max_depth` of 1, only the code of the called account is executed.
+    pub fn call_frame_shallow(
+        &mut self,
+        inputs: CallInputs,
+        max_depth: usize,
+    ) -> Result<ShallowOutcome, EVMError<DB::Error>> {
+        self.run_frame(
+            |evm| {
+                evm.handler
+                    .execution()
+                    .call(&mut evm.context, Box::new(inputs))
+            },
+            |evm, first_frame| evm.run_the_loop_shallow(first_frame, max_depth),
+        )
+    }
+
+    /// Executes a single create against the current state, without a transaction.
+    ///
+    /// Like [`Evm::call_frame`], the nonce of the caller is increased but it pays nothing.
+    pub fn create_frame(
+        &mut self,
+        inputs: CreateInputs,
+    ) -> Result<CreateOutcome, EVMError<DB::Error>> {
+        let result = self.run_frame(
+            |evm| {
+                evm.handler
+                    .execution()
+                    .create(&mut evm.context, Box::new(inputs))
+            },
+            Self::run_the_loop,
+        )?;
+        match result {
+            FrameResult::Create(outcome) => Ok(outcome),
+            _ => unreachable!("create frame returned a call result"),
+        }
+    }
+
+    /// Returns the precompiles of the handler, with the precompiles of the EIPs enabled in
+    /// [`CfgEnv::experimental_eips`] ahead of their fork.
+    fn load_precompiles(&self) -> ContextPrecompiles<DB> {
+        let precompiles = self.handler.pre_execution().load_precompiles();
+        // EIP-2537: BLS12-381 curve operation precompiles.
+        #[cfg(feature = "blst")]
+        if !Eip::Eip2537.is_active_in(self.spec_id())
+            && self
+                .context
+                .evm
+                .env
+                .cfg
+                .experimental_eips
+                .contains(Eip::Eip2537)
+        {
+            let mut precompiles = precompiles;
+            precompiles.extend(crate::precompile::bls12_381::precompiles());
+            return precompiles;
+        }
+        precompiles
+    }
+
+    /// Runs the frame made by `make_frame` and its children with `run_loop`, clearing the state
+    /// on error.
+    fn run_frame<T: From<FrameResult>>(
+        &mut self,
+        make_frame: impl FnOnce(&mut Self) -> Result<FrameOrResult, EVMError<DB::Error>>,
+        run_loop: impl FnOnce(&mut Self, Frame) -> Result<T, EVMError<DB::Error>>,
+    ) -> Result<T, EVMError<DB::Error>> {
+        let run = || {
+            let precompiles = self.load_precompiles();
+            self.context.evm.set_precompiles(precompiles);
+            match make_frame(self)? {
+                FrameOrResult::Frame(first_frame) => run_loop(self, first_frame),
+                FrameOrResult::Result(result) => Ok(result.into()),
+            }
+        };
+        let output = run();
+        if output.is_err() {
+            self.clear();
+        }
+        output
+    }
+
     /// Returns the reference of handler configuration
     #[inline]
     pub fn handler_cfg(&self) -> &HandlerCfg {
@@ -325,6 +775,7 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
     /// Transact pre-verified transaction.
     fn transact_preverified_inner(&mut self, initial_gas_spend: u64) -> EVMResult<DB::Error> {
         let spec_id = self.spec_id();
+        let precompiles = self.load_precompiles();
         let ctx = &mut self.context;
         let pre_exec = self.handler.pre_execution();
 
@@ -332,25 +783,24 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
         pre_exec.load_accounts(ctx)?;
 
         // load precompiles
-        let precompiles = pre_exec.load_precompiles();
         ctx.evm.set_precompiles(precompiles);
 
         // deduce caller balance with its limit.
         pre_exec.deduct_caller(ctx)?;
 
-        let gas_limit = ctx.evm.env.tx.gas_limit - initial_gas_spend;
+        let gas_limit = ctx.evm.env.tx.gas_limit() - initial_gas_spend;
 
         let exec = self.handler.execution();
         // call inner handling of call/create
-        let first_frame_or_result = match ctx.evm.env.tx.transact_to {
+        let first_frame_or_result = match ctx.evm.env.tx.transact_to() {
             TxKind::Call(_) => exec.call(
                 ctx,
                 CallInputs::new_boxed(&ctx.evm.env.tx, gas_limit).unwrap(),
             )?,
             TxKind::Create => {
                 // if first byte of data is magic 0xEF00, then it is EOFCreate.
-                if spec_id.is_enabled_in(SpecId::PRAGUE_EOF)
-                    && ctx.env().tx.data.starts_with(&EOF_MAGIC_BYTES)
+                if ctx.env().cfg.is_eip_enabled(spec_id, Eip::Eip7692)
+                    && ctx.env().tx.data().starts_with(&EOF_MAGIC_BYTES)
                 {
                     exec.eofcreate(
                         ctx,
@@ -387,3 +837,358 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
         post_exec.output(ctx, result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::BenchmarkDB,
+        primitives::{bytes, AccountInfo, Address, Bytecode, InvalidTransaction, B256, U256},
+    };
+
+    fn assert_send<T: Send>() {}
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn send_and_sync() {
+        use crate::{
+            handler::register::EvmHandler, inspectors::NoOpInspector,
+            interpreter::opcode::InstructionTables, InMemoryDB,
+        };
+
+        assert_send::<Evm<'static, (), InMemoryDB>>();
+        assert_send::<Evm<'static, NoOpInspector, InMemoryDB>>();
+        assert_send_sync::<EvmHandler<'static, NoOpInspector, InMemoryDB>>();
+        assert_send_sync::<InstructionTables<'static, Context<NoOpInspector, InMemoryDB>>>();
+        assert_send::<Context<NoOpInspector, InMemoryDB>>();
+
+        // Databases that are not `Sync` still build an Evm.
+        let db = crate::db::RecordingDB::new(EmptyDB::default());
+        Evm::builder().with_db(db).build();
+        let db: crate::DBBox<'_, core::convert::Infallible> = Box::new(EmptyDB::default());
+        Evm::builder()
+            .with_db(crate::State::builder().with_database(db).build())
+            .build();
+    }
+
+    #[test]
+    fn pre_spurious_dragon_replay() {
+        use crate::{
+            primitives::{is_removed_when_touched, AccountInfo, SpecId, TxKind},
+            InMemoryDB,
+        };
+
+        let target = Address::with_last_byte(0xff);
+        let transact = |spec_id: SpecId| {
+            let mut db = InMemoryDB::default();
+            db.insert_account_info(
+                Address::with_last_byte(1),
+                AccountInfo::from_balance(U256::from(1_000_000)),
+            );
+            // CALL with no gas and no value to an account that does not exist.
+            // PUSH1 0 PUSH1 0 PUSH1 0 PUSH1 0 PUSH1 0 PUSH1 0xff PUSH1 0 CALL STOP
+            db.insert_account_info(
+                Address::ZERO,
+                AccountInfo::from_bytecode(Bytecode::new_raw(bytes!(
+                    "6000600060006000600060ff6000f100"
+                ))),
+            );
+            let mut evm = Evm::builder()
+                .with_db(db)
+                .with_spec_id(spec_id)
+                .modify_tx_env(|tx| {
+                    tx.caller = Address::with_last_byte(1);
+                    tx.transact_to = TxKind::Call(Address::ZERO);
+                    tx.gas_limit = 100_000;
+                })
+                .build();
+            // The journal follows the spec of the configuration.
+            assert_eq!(evm.context.evm.journaled_state.spec, spec_id);
+            evm.transact().unwrap()
+        };
+
+        // Before Spurious Dragon, the empty target is charged as a new account and kept.
+        let frontier = transact(SpecId::FRONTIER);
+        assert_eq!(frontier.result.gas_used(), 21_000 + 7 * 3 + 40 + 25_000);
+        assert!(!is_removed_when_touched(
+            SpecId::FRONTIER,
+            &frontier.state[&target]
+        ));
+
+        let spurious_dragon = transact(SpecId::SPURIOUS_DRAGON);
+        assert_eq!(spurious_dragon.result.gas_used(), 21_000 + 7 * 3 + 700);
+        assert!(is_removed_when_touched(
+            SpecId::SPURIOUS_DRAGON,
+            &spurious_dragon.state[&target]
+        ));
+    }
+
+    #[test]
+    fn call_frame_without_transaction() {
+        // PUSH1 0x2a PUSH1 0 MSTORE PUSH1 0x20 PUSH1 0 RETURN
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(bytes!(
+                "602a60005260206000f3"
+            ))))
+            .build();
+        // The caller doesn't exist and pays nothing.
+        let inputs = CallInputs::builder()
+            .caller(Address::with_last_byte(0xff))
+            .to(Address::ZERO)
+            .gas(100_000)
+            .build()
+            .unwrap();
+
+        let outcome = evm.call_frame(inputs).unwrap();
+        assert!(outcome.result.is_ok());
+        assert_eq!(U256::from_be_slice(outcome.output()), U256::from(42));
+        assert!(outcome.gas().spent() > 0);
+        assert_eq!(evm.context.evm.journaled_state.depth(), 0);
+    }
+
+    #[test]
+    fn shallow_call_frame() {
+        use crate::interpreter::{Gas, InstructionResult, InterpreterResult};
+
+        // CALL(GAS, 0, 0, 0, 0, 0, 0x20) POP PUSH1 0x20 PUSH1 0 RETURN
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(bytes!(
+                "6020600060006000600060005af15060206000f3"
+            ))))
+            .build();
+        let inputs = CallInputs::builder()
+            .caller(Address::with_last_byte(0xff))
+            .to(Address::ZERO)
+            .gas(100_000)
+            .build()
+            .unwrap();
+
+        // The account calls itself, the call at depth 2 is left to the caller.
+        let ShallowOutcome::Pending(pending) = evm.call_frame_shallow(inputs, 2).unwrap() else {
+            panic!("expected a pending call");
+        };
+        assert_eq!(pending.depth(), 2);
+        let InterpreterAction::Call { inputs } = &pending.action else {
+            panic!("expected a call action");
+        };
+        let outcome = CallOutcome::new(
+            InterpreterResult::new(
+                InstructionResult::Return,
+                U256::from(7).to_be_bytes::<32>().into(),
+                Gas::new(inputs.gas_limit),
+            ),
+            inputs.return_memory_offset.clone(),
+        );
+
+        // Both frames return the output of the resolved call.
+        let ShallowOutcome::Result(FrameResult::Call(outcome)) = evm
+            .resume_shallow(pending, FrameResult::Call(outcome))
+            .unwrap()
+        else {
+            panic!("expected the result of the first frame");
+        };
+        assert!(outcome.result.is_ok());
+        assert_eq!(U256::from_be_slice(outcome.output()), U256::from(7));
+        assert_eq!(evm.context.evm.journaled_state.depth(), 0);
+    }
+    #[test]
+    fn transact_many_overrides() {
+        /// Counts the storage reads of the [BenchmarkDB].
+        struct CountingDB(BenchmarkDB, usize);
+
+        impl Database for CountingDB {
+            type Error = core::convert::Infallible;
+
+            fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+                self.0.basic(address)
+            }
+
+            fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+                self.0.code_by_hash(code_hash)
+            }
+
+            fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+                self.1 += 1;
+                self.0.storage(address, index)
+            }
+
+            fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+                self.0.block_hash(number)
+            }
+        }
+
+        // CALLVALUE CALLER PUSH1 0 SSTORE PUSH1 1 SSTORE STOP
+        let mut evm = Evm::builder()
+            .with_db(CountingDB(
+                BenchmarkDB::new_bytecode(Bytecode::new_raw(bytes!("3433600055600155"))),
+                0,
+            ))
+            .build();
+        let template = TxEnvTemplate::new(TxEnv {
+            caller: Address::with_last_byte(1),
+            transact_to: TxKind::Call(Address::ZERO),
+            gas_limit: 100_000,
+            ..Default::default()
+        });
+        let overrides = [
+            TxOverrides::new(Address::with_last_byte(1), U256::from(10)),
+            TxOverrides::new(Address::with_last_byte(1), U256::from(20)),
+            // The caller can not pay the value.
+            TxOverrides::new(Address::with_last_byte(1), U256::MAX),
+        ];
+
+        let results = evm.transact_many(&template, overrides).unwrap();
+        assert_eq!(results.len(), 3);
+        for (result, overrides) in results.iter().zip(overrides).take(2) {
+            let ResultAndState { result, state, .. } = result.as_ref().unwrap();
+            assert!(result.is_success());
+            let storage = &state[&Address::ZERO].storage;
+            assert_eq!(
+                storage[&U256::ZERO].present_value,
+                U256::from_be_slice(overrides.caller.unwrap().as_slice())
+            );
+            assert_eq!(
+                storage[&U256::from(1)].present_value,
+                overrides.value.unwrap()
+            );
+        }
+        // The slots loaded by the first transaction are reused cold, with the same gas.
+        assert_eq!(evm.context.evm.db.1, 2);
+        assert_eq!(
+            results[0].as_ref().unwrap().result.gas_used(),
+            results[1].as_ref().unwrap().result.gas_used()
+        );
+        assert!(matches!(
+            results[2],
+            Err(EVMError::Transaction(
+                InvalidTransaction::LackOfFundForMaxFee { .. }
+            ))
+        ));
+        assert_eq!(evm.tx().value, U256::MAX);
+
+        // The template is validated once.
+        let template = TxEnvTemplate::new(TxEnv {
+            gas_limit: 0,
+            ..template.tx().clone()
+        });
+        assert!(evm.transact_many(&template, overrides).is_err());
+    }
+
+    #[test]
+    fn shared_warm_state() {
+        // PUSH1 0 SLOAD STOP
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(bytes!(
+                "60005400"
+            ))))
+            .build();
+        let tx = TxEnv {
+            caller: Address::with_last_byte(1),
+            transact_to: TxKind::Call(Address::ZERO),
+            gas_limit: 100_000,
+            ..Default::default()
+        };
+        let gas_used = |results: Vec<EVMResult<_>>| {
+            results
+                .into_iter()
+                .map(|result| result.unwrap().result.gas_used())
+                .collect::<Vec<_>>()
+        };
+
+        // Only the first transaction loads the slot cold.
+        let mut warm_state = WarmState::new();
+        let results =
+            evm.transact_with_shared_warm_state(&[tx.clone(), tx.clone()], &mut warm_state);
+        assert_eq!(gas_used(results), [21_000 + 3 + 2100, 21_000 + 3 + 100]);
+        assert!(warm_state.contains_storage(&Address::ZERO, &U256::ZERO));
+
+        // Seeded from the previous simulation.
+        let results = evm.transact_with_shared_warm_state(&[tx.clone()], &mut warm_state);
+        assert_eq!(gas_used(results), [21_000 + 3 + 100]);
+
+        // Consensus gas.
+        let results = evm.transact_with_shared_warm_state(&[tx], &mut WarmState::new());
+        assert_eq!(gas_used(results), [21_000 + 3 + 2100]);
+    }
+    #[test]
+    fn commit_streaming() {
+        use crate::{
+            db::{CacheDB, EmptyDB},
+            primitives::AccountInfo,
+            DatabaseRef,
+        };
+
+        // PUSH1 0x2a PUSH1 0 SSTORE STOP
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            Address::ZERO,
+            AccountInfo::from_bytecode(Bytecode::new_raw(bytes!("602a60005500"))),
+        );
+        let caller = Address::with_last_byte(1);
+        db.insert_account_info(caller, AccountInfo::from_balance(U256::from(1_000_000_000)));
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .modify_tx_env(|tx| {
+                tx.caller = caller;
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+            })
+            .build();
+
+        let mut visited = Vec::new();
+        let output = evm
+            .transact_streaming(|address, account| visited.push((address, account)))
+            .unwrap();
+        assert!(output.result.is_success());
+        assert!(output.state.is_empty());
+        let contract = &visited
+            .iter()
+            .find(|(address, _)| *address == Address::ZERO)
+            .unwrap()
+            .1;
+        assert_eq!(
+            contract.storage[&U256::ZERO].present_value,
+            U256::from(0x2a)
+        );
+        assert!(evm.context.evm.journaled_state.state.is_empty());
+        assert!(evm.context.evm.journaled_state.state.capacity() >= visited.len());
+
+        let result = evm.transact_commit_streaming().unwrap();
+        assert!(result.is_success());
+        let db = &evm.context.evm.db;
+        assert_eq!(
+            db.storage_ref(Address::ZERO, U256::ZERO).unwrap(),
+            U256::from(0x2a)
+        );
+        assert_eq!(db.basic_ref(caller).unwrap().unwrap().nonce, 1);
+    }
+
+    #[cfg(feature = "blst")]
+    #[test]
+    fn experimental_eip2537() {
+        use crate::primitives::{Eip, SpecId, TxKind};
+
+        let g1_add = crate::precompile::bls12_381::g1_add::PRECOMPILE.0;
+        let transact = |eip: bool| {
+            let mut evm = Evm::builder()
+                .with_spec_id(SpecId::CANCUN)
+                .modify_cfg_env(|cfg| {
+                    if eip {
+                        cfg.experimental_eips.insert(Eip::Eip2537);
+                    }
+                })
+                .modify_tx_env(|tx| {
+                    tx.caller = Address::with_last_byte(1);
+                    tx.transact_to = TxKind::Call(g1_add);
+                    tx.gas_limit = 100_000;
+                })
+                .build();
+            evm.transact().unwrap().result
+        };
+
+        // Before Prague the address is an empty account.
+        assert!(transact(false).is_success());
+        // The precompile rejects the empty input.
+        assert!(!transact(true).is_success());
+    }
+}
@@ -0,0 +1,157 @@
+//! Template for a chain built on the handler register machinery.
+//!
+//! The [`example_chain_handle_register`] implements a toy chain that differs from mainnet in
+//! three ways, each being one of the common extension points:
+//!
+//! * a custom precompile, [`KECCAK256`], added on top of the precompiles of the spec;
+//! * a custom transaction field, [`ExampleTxFields::expiry`], carried by the external context
+//!   because [`TxEnv`](crate::primitives::TxEnv) can't be extended;
+//! * a fee tweak, the base fee is paid to the [`TREASURY`] instead of being burned.
+//!
+//! Each extension wraps the previous handle, so the register composes with other registers
+//! like the inspector handle register.
+
+use crate::{
+    handler::register::EvmHandler,
+    precompile::{
+        calc_linear_cost_u32, u64_to_address, Error, Precompile, PrecompileResult,
+        PrecompileWithAddress,
+    },
+    primitives::{
+        address, db::Database, keccak256, Address, Bytes, EVMError, PrecompileOutput, SpecId, U256,
+    },
+};
+use std::{string::ToString, sync::Arc};
+
+/// Account receiving the base fee of transactions.
+pub const TREASURY: Address = address!("7e5f4552091a69125d5dfcb7b8c2659029395bdf");
+
+/// Precompile returning the keccak256 hash of its input, with the gas cost of the
+/// `KECCAK256` opcode.
+pub const KECCAK256: PrecompileWithAddress =
+    PrecompileWithAddress(u64_to_address(0x100), Precompile::Standard(keccak256_run));
+
+/// Runs the [`KECCAK256`] precompile.
+pub fn keccak256_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let gas_used = calc_linear_cost_u32(input.len(), 30, 6);
+    if gas_used > gas_limit {
+        return Err(Error::OutOfGas.into());
+    }
+    Ok(PrecompileOutput::new(
+        gas_used,
+        keccak256(input).0.to_vec().into(),
+    ))
+}
+
+/// Transaction fields of the example chain that are not part of the Ethereum transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExampleTxFields {
+    /// Last block timestamp the transaction can be included at.
+    pub expiry: Option<u64>,
+}
+
+/// Gives the example chain handle register access to the custom fields of the current
+/// transaction.
+pub trait ExampleChainTx {
+    /// Returns the custom fields of the current transaction.
+    fn example_tx(&self) -> &ExampleTxFields;
+}
+
+impl ExampleChainTx for ExampleTxFields {
+    fn example_tx(&self) -> &ExampleTxFields {
+        self
+    }
+}
+
+/// Register that executes transactions with the rules of the example chain.
+pub fn example_chain_handle_register<DB: Database, EXT: ExampleChainTx>(
+    handler: &mut EvmHandler<'_, EXT, DB>,
+) {
+    let spec_id = handler.cfg.spec_id;
+
+    // Custom precompile, added to the precompiles of the spec.
+    let prev_handle = handler.pre_execution.load_precompiles.clone();
+    handler.pre_execution.load_precompiles = Arc::new(move || {
+        let mut precompiles = prev_handle();
+        precompiles.extend([KECCAK256]);
+        precompiles
+    });
+
+    // Custom transaction field, validated against the block.
+    let prev_handle = handler.validation.tx_against_state.clone();
+    handler.validation.tx_against_state = Arc::new(move |ctx| {
+        if let Some(expiry) = ctx.external.example_tx().expiry {
+            if ctx.evm.env.block.timestamp > U256::from(expiry) {
+                return Err(EVMError::Custom("transaction expired".to_string()));
+            }
+        }
+        prev_handle(ctx)
+    });
+
+    // Fee tweak, the base fee goes to the treasury.
+    let prev_handle = handler.post_execution.reward_beneficiary.clone();
+    handler.post_execution.reward_beneficiary = Arc::new(move |ctx, gas| {
+        prev_handle(ctx, gas)?;
+        if !SpecId::enabled(spec_id, SpecId::LONDON) {
+            return Ok(());
+        }
+        let basefee = ctx.evm.env.block.basefee;
+        let (treasury, _) = ctx
+            .evm
+            .inner
+            .journaled_state
+            .load_account(TREASURY, &mut ctx.evm.inner.db)?;
+        treasury.mark_touch();
+        treasury.info.balance = treasury
+            .info
+            .balance
+            .saturating_add(basefee * U256::from(gas.used()));
+        Ok(())
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::BenchmarkDB,
+        primitives::{Bytecode, ExecutionResult, TxKind},
+        Evm,
+    };
+
+    #[test]
+    fn example_chain_rules() {
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new()))
+            .with_external_context(ExampleTxFields { expiry: Some(100) })
+            .append_handler_register(example_chain_handle_register)
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TxKind::Call(KECCAK256.0);
+                tx.data = Bytes::from_static(b"abc");
+                tx.gas_limit = 100_000;
+                tx.gas_price = U256::from(10);
+            })
+            .modify_block_env(|block| {
+                block.basefee = U256::from(7);
+                block.timestamp = U256::from(100);
+            })
+            .build();
+
+        let result = evm.transact().unwrap();
+        let ExecutionResult::Success {
+            output, gas_used, ..
+        } = result.result
+        else {
+            panic!("execution failed: {:?}", result.result);
+        };
+        assert_eq!(output.data()[..], keccak256(b"abc")[..]);
+        assert_eq!(
+            result.state[&TREASURY].info.balance,
+            U256::from(7 * gas_used)
+        );
+
+        evm.context.evm.env.block.timestamp = U256::from(101);
+        assert!(matches!(evm.transact(), Err(EVMError::Custom(_))));
+    }
+}
@@ -0,0 +1,145 @@
+//! Payment of transaction fees in a currency other than the native token.
+//!
+//! Chains like Celo let transactions pay for gas with ERC-20 tokens. The
+//! [`fee_currency_handle_register`] routes the gas payment of such transactions through a
+//! [`FeeCurrencyHandler`] implemented by the external context, while the transferred value is
+//! still paid in the native currency.
+
+mod erc20;
+
+pub use erc20::Erc20FeeCurrency;
+
+use crate::{
+    handler::{mainnet::fee_payments, register::EvmHandler},
+    primitives::{db::Database, Address, EVMError, Env, InvalidTransaction, Transaction, U256},
+    Context, EvmContext,
+};
+use core::mem;
+use std::{boxed::Box, sync::Arc};
+
+/// Moves fee currency balances on behalf of the fee currency handle register.
+///
+/// Operations should go through the journal of the [EvmContext] so that they are part of the
+/// transaction state.
+pub trait FeeCurrencyHandler<DB: Database> {
+    /// Returns the currency the current transaction pays its fees with, `None` for the native
+    /// currency.
+    fn fee_currency(&self, env: &Env) -> Option<Address>;
+
+    /// Returns the fee currency balance of the account.
+    fn balance_of(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        currency: Address,
+        account: Address,
+    ) -> Result<U256, EVMError<DB::Error>>;
+
+    /// Takes the amount from the fee currency balance of the account.
+    fn debit(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        currency: Address,
+        account: Address,
+        amount: U256,
+    ) -> Result<(), EVMError<DB::Error>>;
+
+    /// Adds the amount to the fee currency balance of the account.
+    fn credit(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        currency: Address,
+        account: Address,
+        amount: U256,
+    ) -> Result<(), EVMError<DB::Error>>;
+}
+
+/// Register that pays the fees of transactions with a fee currency through the
+/// [FeeCurrencyHandler] of the external context.
+///
+/// Gas prices of such transactions are denominated in the fee currency. The caller must hold
+/// `gas_limit * effective_gas_price` before execution and is charged for the gas it used
+/// afterwards, so that the execution sees the fee currency balances of the database. The
+/// transaction fails if it spent the tokens needed for the fee. The priority fee and the base
+/// fee are paid to the recipients of [`CfgEnv::fee_vaults`] or burned, as for the native
+/// currency. Blob transactions can't use a fee currency.
+///
+/// [`CfgEnv::fee_vaults`]: crate::primitives::CfgEnv::fee_vaults
+///
+/// Transactions without a fee currency are handled by the previous handles.
+pub fn fee_currency_handle_register<DB: Database, EXT: FeeCurrencyHandler<DB>>(
+    handler: &mut EvmHandler<'_, EXT, DB>,
+) {
+    let spec_id = handler.cfg.spec_id;
+
+    let prev_handle = handler.validation.tx_against_state.clone();
+    handler.validation.tx_against_state = Arc::new(move |ctx| {
+        let Some(currency) = ctx.external.fee_currency(&ctx.evm.env) else {
+            return prev_handle(ctx);
+        };
+        let tx = &ctx.evm.env.tx;
+        if !tx.blob_hashes.is_empty() {
+            return Err(EVMError::Custom(
+                "blob transactions can't pay fees in a fee currency".into(),
+            ));
+        }
+        let fee = U256::from(tx.gas_limit)
+            .checked_mul(tx.gas_price)
+            .ok_or(InvalidTransaction::OverflowPaymentInTransaction)?;
+        let caller = tx.caller;
+        let balance = ctx.external.balance_of(&mut ctx.evm, currency, caller)?;
+        if fee > balance && !ctx.evm.env.cfg.is_balance_check_disabled() {
+            return Err(InvalidTransaction::LackOfFundForMaxFee {
+                fee: Box::new(fee),
+                balance: Box::new(balance),
+            }
+            .into());
+        }
+        // The native balance only has to cover the transferred value.
+        without_gas_price(ctx, |ctx| prev_handle(ctx))
+    });
+
+    let prev_handle = handler.pre_execution.deduct_caller.clone();
+    handler.pre_execution.deduct_caller = Arc::new(move |ctx| {
+        if ctx.external.fee_currency(&ctx.evm.env).is_none() {
+            return prev_handle(ctx);
+        }
+        // Bumps the nonce and touches the caller without charging it native currency. The fee
+        // is debited after execution.
+        without_gas_price(ctx, |ctx| prev_handle(ctx))
+    });
+
+    let prev_handle = handler.post_execution.reimburse_caller.clone();
+    handler.post_execution.reimburse_caller = Arc::new(move |ctx, gas| {
+        let Some(currency) = ctx.external.fee_currency(&ctx.evm.env) else {
+            return prev_handle(ctx, gas);
+        };
+        let env = &ctx.evm.env;
+        let used = U256::from(gas.limit() - gas.reimbursed());
+        let fee = env.effective_gas_price().saturating_mul(used);
+        let caller = env.tx.caller();
+        ctx.external.debit(&mut ctx.evm, currency, caller, fee)
+    });
+
+    let prev_handle = handler.post_execution.reward_beneficiary.clone();
+    handler.post_execution.reward_beneficiary = Arc::new(move |ctx, gas| {
+        let Some(currency) = ctx.external.fee_currency(&ctx.evm.env) else {
+            return prev_handle(ctx, gas);
+        };
+        for (recipient, fee) in fee_payments(&ctx.evm.env, spec_id, gas) {
+            ctx.external
+                .credit(&mut ctx.evm, currency, recipient, fee)?;
+        }
+        Ok(())
+    });
+}
+
+/// Calls the handle with a zero gas price, so that it does not move native currency for gas.
+fn without_gas_price<EXT, DB: Database, R>(
+    ctx: &mut Context<EXT, DB>,
+    f: impl FnOnce(&mut Context<EXT, DB>) -> R,
+) -> R {
+    let gas_price = mem::take(&mut ctx.evm.env.tx.gas_price);
+    let result = f(ctx);
+    ctx.evm.env.tx.gas_price = gas_price;
+    result
+}
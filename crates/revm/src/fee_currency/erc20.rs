@@ -0,0 +1,246 @@
+use super::FeeCurrencyHandler;
+use crate::{
+    primitives::{
+        db::Database, keccak256, Address, EVMError, Env, HashMap, InvalidTransaction, U256,
+    },
+    EvmContext,
+};
+use std::{boxed::Box, format};
+
+/// Reference [FeeCurrencyHandler] for ERC-20 tokens that keep their balances in a
+/// `mapping(address => uint256)`.
+///
+/// Balances are read and written directly in the token storage, no token code is executed and
+/// no `Transfer` events are emitted. The token accounts and the touched balance slots are warm
+/// during the execution of the transaction.
+#[derive(Clone, Debug, Default)]
+pub struct Erc20FeeCurrency {
+    /// Currency the next transaction pays its fees with, `None` for the native currency.
+    pub fee_currency: Option<Address>,
+    /// Storage slot of the balance mapping of each accepted token.
+    balance_slots: HashMap<Address, U256>,
+}
+
+impl Erc20FeeCurrency {
+    /// Creates a handler that does not accept any token.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accepts the token whose balance mapping is declared at `balance_slot`.
+    pub fn with_token(mut self, token: Address, balance_slot: U256) -> Self {
+        self.insert_token(token, balance_slot);
+        self
+    }
+
+    /// Accepts the token whose balance mapping is declared at `balance_slot`.
+    pub fn insert_token(&mut self, token: Address, balance_slot: U256) {
+        self.balance_slots.insert(token, balance_slot);
+    }
+
+    /// Returns the storage slot holding the token balance of the account, `None` if the token is
+    /// not accepted.
+    pub fn balance_slot(&self, token: Address, account: Address) -> Option<U256> {
+        let mapping_slot = self.balance_slots.get(&token)?;
+        // keccak256(abi.encode(account, mapping_slot))
+        let mut preimage = [0u8; 64];
+        preimage[12..32].copy_from_slice(account.as_slice());
+        preimage[32..].copy_from_slice(&mapping_slot.to_be_bytes::<32>());
+        Some(U256::from_be_bytes(keccak256(preimage).0))
+    }
+
+    fn load_balance<DB: Database>(
+        &self,
+        context: &mut EvmContext<DB>,
+        token: Address,
+        account: Address,
+    ) -> Result<(U256, U256), EVMError<DB::Error>> {
+        let slot = self
+            .balance_slot(token, account)
+            .ok_or_else(|| EVMError::Custom(format!("unsupported fee currency {token}")))?;
+        context.load_account(token)?;
+        let (balance, _) = context.sload(token, slot)?;
+        Ok((slot, balance))
+    }
+
+    fn store_balance<DB: Database>(
+        context: &mut EvmContext<DB>,
+        token: Address,
+        slot: U256,
+        balance: U256,
+    ) -> Result<(), EVMError<DB::Error>> {
+        context.sstore(token, slot, balance)?;
+        context.touch(&token);
+        Ok(())
+    }
+}
+
+impl<DB: Database> FeeCurrencyHandler<DB> for Erc20FeeCurrency {
+    fn fee_currency(&self, _env: &Env) -> Option<Address> {
+        self.fee_currency
+    }
+
+    fn balance_of(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        currency: Address,
+        account: Address,
+    ) -> Result<U256, EVMError<DB::Error>> {
+        self.load_balance(context, currency, account)
+            .map(|(_, balance)| balance)
+    }
+
+    fn debit(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        currency: Address,
+        account: Address,
+        amount: U256,
+    ) -> Result<(), EVMError<DB::Error>> {
+        let (slot, balance) = self.load_balance(context, currency, account)?;
+        let balance = match balance.checked_sub(amount) {
+            Some(balance) => balance,
+            None if context.env.cfg.is_balance_check_disabled() => U256::ZERO,
+            None => {
+                return Err(InvalidTransaction::LackOfFundForMaxFee {
+                    fee: Box::new(amount),
+                    balance: Box::new(balance),
+                }
+                .into())
+            }
+        };
+        Self::store_balance(context, currency, slot, balance)
+    }
+
+    fn credit(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        currency: Address,
+        account: Address,
+        amount: U256,
+    ) -> Result<(), EVMError<DB::Error>> {
+        let (slot, balance) = self.load_balance(context, currency, account)?;
+        Self::store_balance(context, currency, slot, balance.saturating_add(amount))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{CacheDB, EmptyDB},
+        fee_currency::fee_currency_handle_register,
+        primitives::{address, AccountInfo, Bytecode, FeeRecipient, FeeVaults, TxKind},
+        Evm,
+    };
+
+    #[test]
+    fn pays_gas_in_token() {
+        let token = address!("00000000000000000000000000000000000000ff");
+        let caller = address!("0000000000000000000000000000000000000001");
+        let coinbase = address!("0000000000000000000000000000000000000002");
+        let fee_currency = Erc20FeeCurrency {
+            fee_currency: Some(token),
+            ..Erc20FeeCurrency::new().with_token(token, U256::from(2))
+        };
+        let caller_slot = fee_currency.balance_slot(token, caller).unwrap();
+        let coinbase_slot = fee_currency.balance_slot(token, coinbase).unwrap();
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_storage(token, caller_slot, U256::from(1_000_000))
+            .unwrap();
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .with_external_context(fee_currency)
+            .modify_block_env(|block| {
+                block.coinbase = coinbase;
+                block.basefee = U256::from(1);
+            })
+            .modify_tx_env(|tx| {
+                tx.caller = caller;
+                tx.transact_to = TxKind::Call(address!("0000000000000000000000000000000000000003"));
+                tx.gas_limit = 100_000;
+                tx.gas_price = U256::from(3);
+            })
+            .append_handler_register(fee_currency_handle_register)
+            .build();
+
+        // The caller has no native balance.
+        let result = evm.transact().unwrap();
+        assert_eq!(result.result.gas_used(), 21_000);
+        let storage = &result.state[&token].storage;
+        assert_eq!(
+            storage[&caller_slot].present_value,
+            U256::from(1_000_000 - 3 * 21_000)
+        );
+        assert_eq!(
+            storage[&coinbase_slot].present_value,
+            U256::from(2 * 21_000)
+        );
+        assert_eq!(result.state[&caller].info.nonce, 1);
+        assert_eq!(result.state[&caller].info.balance, U256::ZERO);
+
+        // The fee vaults are paid in the token too, the burned priority fee is paid to nobody.
+        let vault = address!("0000000000000000000000000000000000000004");
+        let vault_slot = evm.context.external.balance_slot(token, vault).unwrap();
+        evm.cfg_mut().fee_vaults = FeeVaults {
+            base_fee: FeeRecipient::Account(vault),
+            priority_fee: FeeRecipient::Burn,
+        };
+        let result = evm.transact().unwrap();
+        let storage = &result.state[&token].storage;
+        assert_eq!(storage[&vault_slot].present_value, U256::from(21_000));
+        assert!(!storage.contains_key(&coinbase_slot));
+        evm.cfg_mut().fee_vaults = FeeVaults::default();
+
+        // Not enough tokens to pay for the gas limit.
+        evm.context.evm.env.tx.gas_limit = 1_000_000;
+        assert!(matches!(
+            evm.transact().unwrap_err(),
+            EVMError::Transaction(InvalidTransaction::LackOfFundForMaxFee { .. })
+        ));
+    }
+
+    #[test]
+    fn debits_after_execution() {
+        let token = address!("00000000000000000000000000000000000000ff");
+        let caller = address!("0000000000000000000000000000000000000001");
+        let fee_currency = Erc20FeeCurrency {
+            fee_currency: Some(token),
+            ..Erc20FeeCurrency::new().with_token(token, U256::from(2))
+        };
+        let caller_slot = fee_currency.balance_slot(token, caller).unwrap();
+
+        // PUSH3 10_000_000 PUSH32 caller_slot SSTORE
+        let mut code = vec![0x62, 0x98, 0x96, 0x80, 0x7f];
+        code.extend_from_slice(&caller_slot.to_be_bytes::<32>());
+        code.push(0x55);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            token,
+            AccountInfo::from_bytecode(Bytecode::new_raw(code.into())),
+        );
+        db.insert_account_storage(token, caller_slot, U256::from(1_000_000))
+            .unwrap();
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .with_external_context(fee_currency)
+            .modify_tx_env(|tx| {
+                tx.caller = caller;
+                tx.transact_to = TxKind::Call(token);
+                tx.gas_limit = 100_000;
+                tx.gas_price = U256::from(3);
+            })
+            .append_handler_register(fee_currency_handle_register)
+            .build();
+
+        // The slot is clean when the transaction writes it.
+        let result = evm.transact().unwrap();
+        let gas_used = 21_000 + 3 + 3 + 2_100 + 2_900;
+        assert_eq!(result.result.gas_used(), gas_used);
+        assert_eq!(
+            result.state[&token].storage[&caller_slot].present_value,
+            U256::from(10_000_000 - 3 * gas_used)
+        );
+    }
+}
@@ -4,8 +4,11 @@ use crate::{
     JournalCheckpoint,
 };
 use core::ops::Range;
-use revm_interpreter::{CallOutcome, CreateOutcome, Gas, InstructionResult, InterpreterResult};
-use std::boxed::Box;
+use revm_interpreter::{
+    CallOutcome, CreateOutcome, Gas, InstructionResult, InterpreterAction, InterpreterResult,
+    SharedMemory,
+};
+use std::{boxed::Box, vec::Vec};
 
 /// Call CallStackFrame.
 #[derive(Debug)]
@@ -269,29 +272,71 @@ impl FrameOrResult {
         interpreter_result: InterpreterResult,
         address: Option<Address>,
     ) -> Self {
-        FrameOrResult::Result(FrameResult::Create(CreateOutcome {
-            result: interpreter_result,
+        FrameOrResult::Result(FrameResult::Create(CreateOutcome::new(
+            interpreter_result,
             address,
-        }))
+        )))
     }
 
     pub fn new_eofcreate_result(
         interpreter_result: InterpreterResult,
         address: Option<Address>,
     ) -> Self {
-        FrameOrResult::Result(FrameResult::EOFCreate(CreateOutcome {
-            result: interpreter_result,
+        FrameOrResult::Result(FrameResult::EOFCreate(CreateOutcome::new(
+            interpreter_result,
             address,
-        }))
+        )))
     }
 
     pub fn new_call_result(
         interpreter_result: InterpreterResult,
         memory_offset: Range<usize>,
     ) -> Self {
-        FrameOrResult::Result(FrameResult::Call(CallOutcome {
-            result: interpreter_result,
+        FrameOrResult::Result(FrameResult::Call(CallOutcome::new(
+            interpreter_result,
             memory_offset,
-        }))
+        )))
+    }
+}
+
+/// Outcome of a shallow execution, see [`Evm::run_the_loop_shallow`](crate::Evm::run_the_loop_shallow).
+#[derive(Debug)]
+pub enum ShallowOutcome {
+    /// The first frame returned.
+    Result(FrameResult),
+    /// A frame at the depth limit requested a sub-call that was not executed.
+    Pending(PendingFrames),
+}
+
+impl From<FrameResult> for ShallowOutcome {
+    fn from(result: FrameResult) -> Self {
+        Self::Result(result)
+    }
+}
+
+/// Suspended frames of a shallow execution, waiting for the result of the sub-call
+/// requested by the top frame.
+///
+/// The result is inserted with [`Evm::resume_shallow`](crate::Evm::resume_shallow).
+#[derive(Debug)]
+pub struct PendingFrames {
+    /// Sub-call requested by the top frame, either a `Call`, `Create` or `EOFCreate` action.
+    pub action: InterpreterAction,
+    pub(crate) call_stack: Vec<Frame>,
+    pub(crate) shared_memory: SharedMemory,
+    pub(crate) max_depth: usize,
+}
+
+impl PendingFrames {
+    /// Returns the number of suspended frames, the first frame having depth 1.
+    pub fn depth(&self) -> usize {
+        self.call_stack.len()
+    }
+
+    /// Returns the frame that requested the sub-call.
+    pub fn top_frame(&self) -> &Frame {
+        self.call_stack
+            .last()
+            .expect("pending frames are never empty")
     }
 }
@@ -8,7 +8,10 @@ pub use handle_types::*;
 
 // Includes.
 use crate::{
-    interpreter::{opcode::InstructionTables, Host, InterpreterAction, SharedMemory},
+    interpreter::{
+        opcode::{Instruction, InstructionTables},
+        Host, InterpreterAction, SharedMemory,
+    },
     primitives::{db::Database, spec_to_generic, EVMError, HandlerCfg, Spec, SpecId},
     Context, Frame,
 };
@@ -108,6 +111,21 @@ impl<'a, EXT, DB: Database> EvmHandler<'a, EXT, DB> {
         self.cfg.spec_id
     }
 
+    /// Calls `before` and `after` around the instruction of `opcode`, replacing its previous
+    /// hooks.
+    ///
+    /// Only the hooked instruction is wrapped, the other instructions keep their plain dispatch
+    /// unless the instruction table is already boxed. This makes it cheaper than a boxed
+    /// instruction for observing a few opcodes, e.g. `SSTORE` or `CALL`.
+    pub fn instruction_override(
+        &mut self,
+        opcode: u8,
+        before: Option<Instruction<Context<EXT, DB>>>,
+        after: Option<Instruction<Context<EXT, DB>>>,
+    ) {
+        self.instruction_table.insert_hooks(opcode, before, after);
+    }
+
     /// Executes call frame.
     pub fn execute_frame(
         &self,
@@ -115,6 +133,17 @@ impl<'a, EXT, DB: Database> EvmHandler<'a, EXT, DB> {
         shared_memory: &mut SharedMemory,
         context: &mut Context<EXT, DB>,
     ) -> Result<InterpreterAction, EVMError<DB::Error>> {
+        // Stack verification assumes the stack effects of the mainnet instructions, changed
+        // tables keep the stack checks.
+        #[cfg(feature = "legacy_stack_verification")]
+        {
+            let contract = &mut frame.interpreter_mut().contract;
+            if contract.stack_verified
+                && !spec_to_generic!(self.spec_id(), self.instruction_table.is_plain_of::<SPEC>())
+            {
+                contract.stack_verified = false;
+            }
+        }
         self.execution
             .execute_frame(frame, shared_memory, &self.instruction_table, context)
     }
@@ -218,35 +247,81 @@ impl<'a, EXT, DB: Database> EvmHandler<'a, EXT, DB> {
 
 #[cfg(test)]
 mod test {
-    use core::cell::RefCell;
-
     use crate::{db::EmptyDB, primitives::EVMError};
-    use std::{rc::Rc, sync::Arc};
+    use std::sync::{Arc, Mutex};
 
     use super::*;
 
     #[test]
     fn test_handler_register_pop() {
-        let register = |inner: &Rc<RefCell<i32>>| -> HandleRegisterBox<'_, (), EmptyDB> {
+        let register = |inner: &Arc<Mutex<i32>>| -> HandleRegisterBox<'_, (), EmptyDB> {
             let inner = inner.clone();
             Box::new(move |h| {
-                *inner.borrow_mut() += 1;
+                *inner.lock().unwrap() += 1;
                 h.post_execution.output = Arc::new(|_, _| Err(EVMError::Custom("test".to_string())))
             })
         };
 
         let mut handler = EvmHandler::<(), EmptyDB>::new(HandlerCfg::new(SpecId::LATEST));
-        let test = Rc::new(RefCell::new(0));
+        let test = Arc::new(Mutex::new(0));
 
         handler.append_handler_register_box(register(&test));
-        assert_eq!(*test.borrow(), 1);
+        assert_eq!(*test.lock().unwrap(), 1);
 
         handler.append_handler_register_box(register(&test));
-        assert_eq!(*test.borrow(), 2);
+        assert_eq!(*test.lock().unwrap(), 2);
 
         assert!(handler.pop_handle_register().is_some());
 
         // first handler is reapplied
-        assert_eq!(*test.borrow(), 3);
+        assert_eq!(*test.lock().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_plain_instruction_table() {
+        use crate::{
+            interpreter::{opcode, Interpreter},
+            primitives::{CancunSpec, ShanghaiSpec},
+        };
+        fn noop(_: &mut Interpreter, _: &mut Context<(), EmptyDB>) {}
+
+        let mut handler = EvmHandler::<(), EmptyDB>::new(HandlerCfg::new(SpecId::CANCUN));
+        let table = &mut handler.instruction_table;
+        assert!(table.is_plain_of::<CancunSpec>());
+        assert!(!table.is_plain_of::<ShanghaiSpec>());
+        table.insert(opcode::POP, noop);
+        assert!(!table.is_plain_of::<CancunSpec>());
+    }
+
+    #[test]
+    fn instruction_override() {
+        use crate::{
+            db::BenchmarkDB,
+            interpreter::{opcode, Interpreter},
+            primitives::{bytes, Bytecode},
+            Evm,
+        };
+
+        fn count(_: &mut Interpreter, host: &mut Context<u64, BenchmarkDB>) {
+            host.external += 1;
+        }
+
+        // PUSH1 1 PUSH1 0 SSTORE PUSH1 2 PUSH1 1 SSTORE STOP
+        let code = Bytecode::new_raw(bytes!("6001600055600260015500"));
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(code))
+            .with_external_context(0u64)
+            .modify_tx_env(|tx| tx.caller = crate::primitives::Address::with_last_byte(1))
+            .append_handler_register(|handler| {
+                handler.instruction_override(opcode::SSTORE, Some(count), None)
+            })
+            .build();
+        assert!(matches!(
+            evm.handler.instruction_table,
+            InstructionTables::Hooked(_)
+        ));
+
+        assert!(evm.transact().unwrap().result.is_success());
+        assert_eq!(evm.context.external, 2);
     }
 }
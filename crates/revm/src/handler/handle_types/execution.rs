@@ -14,6 +14,8 @@ use std::{boxed::Box, sync::Arc};
 /// Handles first frame return handle.
 pub type LastFrameReturnHandle<'a, EXT, DB> = Arc<
     dyn Fn(&mut Context<EXT, DB>, &mut FrameResult) -> Result<(), EVMError<<DB as Database>::Error>>
+        + Send
+        + Sync
         + 'a,
 >;
 
@@ -25,6 +27,8 @@ pub type ExecuteFrameHandle<'a, EXT, DB> = Arc<
             &InstructionTables<'_, Context<EXT, DB>>,
             &mut Context<EXT, DB>,
         ) -> Result<InterpreterAction, EVMError<<DB as Database>::Error>>
+        + Send
+        + Sync
         + 'a,
 >;
 
@@ -34,6 +38,8 @@ pub type FrameCallHandle<'a, EXT, DB> = Arc<
             &mut Context<EXT, DB>,
             Box<CallInputs>,
         ) -> Result<FrameOrResult, EVMError<<DB as Database>::Error>>
+        + Send
+        + Sync
         + 'a,
 >;
 
@@ -44,6 +50,8 @@ pub type FrameCallReturnHandle<'a, EXT, DB> = Arc<
             Box<CallFrame>,
             InterpreterResult,
         ) -> Result<CallOutcome, EVMError<<DB as Database>::Error>>
+        + Send
+        + Sync
         + 'a,
 >;
 
@@ -55,6 +63,8 @@ pub type InsertCallOutcomeHandle<'a, EXT, DB> = Arc<
             &mut SharedMemory,
             CallOutcome,
         ) -> Result<(), EVMError<<DB as Database>::Error>>
+        + Send
+        + Sync
         + 'a,
 >;
 
@@ -64,6 +74,8 @@ pub type FrameCreateHandle<'a, EXT, DB> = Arc<
             &mut Context<EXT, DB>,
             Box<CreateInputs>,
         ) -> Result<FrameOrResult, EVMError<<DB as Database>::Error>>
+        + Send
+        + Sync
         + 'a,
 >;
 
@@ -74,6 +86,8 @@ pub type FrameCreateReturnHandle<'a, EXT, DB> = Arc<
             Box<CreateFrame>,
             InterpreterResult,
         ) -> Result<CreateOutcome, EVMError<<DB as Database>::Error>>
+        + Send
+        + Sync
         + 'a,
 >;
 
@@ -84,6 +98,8 @@ pub type InsertCreateOutcomeHandle<'a, EXT, DB> = Arc<
             &mut Frame,
             CreateOutcome,
         ) -> Result<(), EVMError<<DB as Database>::Error>>
+        + Send
+        + Sync
         + 'a,
 >;
 
@@ -93,6 +109,8 @@ pub type FrameEOFCreateHandle<'a, EXT, DB> = Arc<
             &mut Context<EXT, DB>,
             Box<EOFCreateInputs>,
         ) -> Result<FrameOrResult, EVMError<<DB as Database>::Error>>
+        + Send
+        + Sync
         + 'a,
 >;
 
@@ -103,6 +121,8 @@ pub type FrameEOFCreateReturnHandle<'a, EXT, DB> = Arc<
             Box<EOFCreateFrame>,
             InterpreterResult,
         ) -> Result<CreateOutcome, EVMError<<DB as Database>::Error>>
+        + Send
+        + Sync
         + 'a,
 >;
 
@@ -113,6 +133,8 @@ pub type InsertEOFCreateOutcomeHandle<'a, EXT, DB> = Arc<
             &mut Frame,
             CreateOutcome,
         ) -> Result<(), EVMError<<DB as Database>::Error>>
+        + Send
+        + Sync
         + 'a,
 >;
 
@@ -8,8 +8,12 @@ use crate::{
 use std::sync::Arc;
 
 /// Reimburse the caller with ethereum it didn't spent.
-pub type ReimburseCallerHandle<'a, EXT, DB> =
-    Arc<dyn Fn(&mut Context<EXT, DB>, &Gas) -> EVMResultGeneric<(), <DB as Database>::Error> + 'a>;
+pub type ReimburseCallerHandle<'a, EXT, DB> = Arc<
+    dyn Fn(&mut Context<EXT, DB>, &Gas) -> EVMResultGeneric<(), <DB as Database>::Error>
+        + Send
+        + Sync
+        + 'a,
+>;
 
 /// Reward beneficiary with transaction rewards.
 pub type RewardBeneficiaryHandle<'a, EXT, DB> = ReimburseCallerHandle<'a, EXT, DB>;
@@ -20,6 +24,8 @@ pub type OutputHandle<'a, EXT, DB> = Arc<
             &mut Context<EXT, DB>,
             FrameResult,
         ) -> Result<ResultAndState, EVMError<<DB as Database>::Error>>
+        + Send
+        + Sync
         + 'a,
 >;
 
@@ -32,12 +38,14 @@ pub type EndHandle<'a, EXT, DB> = Arc<
             &mut Context<EXT, DB>,
             Result<ResultAndState, EVMError<<DB as Database>::Error>>,
         ) -> Result<ResultAndState, EVMError<<DB as Database>::Error>>
+        + Send
+        + Sync
         + 'a,
 >;
 
 /// Clear handle, doesn't have output, its purpose is to clear the
 /// context. It will always be called even on failed validation.
-pub type ClearHandle<'a, EXT, DB> = Arc<dyn Fn(&mut Context<EXT, DB>) + 'a>;
+pub type ClearHandle<'a, EXT, DB> = Arc<dyn Fn(&mut Context<EXT, DB>) + Send + Sync + 'a>;
 
 /// Handles related to post execution after the stack loop is finished.
 pub struct PostExecutionHandler<'a, EXT, DB: Database> {
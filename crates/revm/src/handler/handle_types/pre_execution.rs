@@ -7,17 +7,25 @@ use crate::{
 use std::sync::Arc;
 
 /// Loads precompiles into Evm
-pub type LoadPrecompilesHandle<'a, DB> = Arc<dyn Fn() -> ContextPrecompiles<DB> + 'a>;
+pub type LoadPrecompilesHandle<'a, DB> = Arc<dyn Fn() -> ContextPrecompiles<DB> + Send + Sync + 'a>;
 
 /// Load access list accounts and beneficiary.
 /// There is no need to load Caller as it is assumed that
 /// it will be loaded in DeductCallerHandle.
-pub type LoadAccountsHandle<'a, EXT, DB> =
-    Arc<dyn Fn(&mut Context<EXT, DB>) -> Result<(), EVMError<<DB as Database>::Error>> + 'a>;
+pub type LoadAccountsHandle<'a, EXT, DB> = Arc<
+    dyn Fn(&mut Context<EXT, DB>) -> Result<(), EVMError<<DB as Database>::Error>>
+        + Send
+        + Sync
+        + 'a,
+>;
 
 /// Deduct the caller to its limit.
-pub type DeductCallerHandle<'a, EXT, DB> =
-    Arc<dyn Fn(&mut Context<EXT, DB>) -> EVMResultGeneric<(), <DB as Database>::Error> + 'a>;
+pub type DeductCallerHandle<'a, EXT, DB> = Arc<
+    dyn Fn(&mut Context<EXT, DB>) -> EVMResultGeneric<(), <DB as Database>::Error>
+        + Send
+        + Sync
+        + 'a,
+>;
 
 /// Handles related to pre execution before the stack loop is started.
 pub struct PreExecutionHandler<'a, EXT, DB: Database> {
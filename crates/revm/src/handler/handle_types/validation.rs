@@ -1,5 +1,6 @@
 use crate::{
     handler::mainnet,
+    interpreter::gas::GasSchedule,
     primitives::{db::Database, EVMError, Env, Spec},
     Context,
 };
@@ -7,16 +8,21 @@ use std::sync::Arc;
 
 /// Handle that validates env.
 pub type ValidateEnvHandle<'a, DB> =
-    Arc<dyn Fn(&Env) -> Result<(), EVMError<<DB as Database>::Error>> + 'a>;
+    Arc<dyn Fn(&Env) -> Result<(), EVMError<<DB as Database>::Error>> + Send + Sync + 'a>;
 
 /// Handle that validates transaction environment against the state.
 /// Second parametar is initial gas.
-pub type ValidateTxEnvAgainstState<'a, EXT, DB> =
-    Arc<dyn Fn(&mut Context<EXT, DB>) -> Result<(), EVMError<<DB as Database>::Error>> + 'a>;
+pub type ValidateTxEnvAgainstState<'a, EXT, DB> = Arc<
+    dyn Fn(&mut Context<EXT, DB>) -> Result<(), EVMError<<DB as Database>::Error>>
+        + Send
+        + Sync
+        + 'a,
+>;
 
-/// Initial gas calculation handle
-pub type ValidateInitialTxGasHandle<'a, DB> =
-    Arc<dyn Fn(&Env) -> Result<u64, EVMError<<DB as Database>::Error>> + 'a>;
+/// Initial gas calculation handle, priced with the gas schedule of the EVM.
+pub type ValidateInitialTxGasHandle<'a, DB> = Arc<
+    dyn Fn(&Env, &GasSchedule) -> Result<u64, EVMError<<DB as Database>::Error>> + Send + Sync + 'a,
+>;
 
 /// Handles related to validation.
 pub struct ValidationHandler<'a, EXT, DB: Database> {
@@ -46,8 +52,12 @@ impl<'a, EXT, DB: Database> ValidationHandler<'a, EXT, DB> {
     }
 
     /// Initial gas
-    pub fn initial_tx_gas(&self, env: &Env) -> Result<u64, EVMError<DB::Error>> {
-        (self.initial_tx_gas)(env)
+    pub fn initial_tx_gas(
+        &self,
+        env: &Env,
+        gas_schedule: &GasSchedule,
+    ) -> Result<u64, EVMError<DB::Error>> {
+        (self.initial_tx_gas)(env, gas_schedule)
     }
 
     /// Validate ttansaction against the state.
@@ -10,6 +10,11 @@ pub use execution::{
     frame_return_with_refund_flag, insert_call_outcome, insert_create_outcome,
     insert_eofcreate_outcome, last_frame_return,
 };
-pub use post_execution::{clear, end, output, reimburse_caller, reward_beneficiary};
-pub use pre_execution::{deduct_caller, deduct_caller_inner, load_accounts, load_precompiles};
+pub use post_execution::{
+    clear, credit_fee, end, fee_payments, output, reimburse_caller, reward_beneficiary,
+};
+pub use pre_execution::{
+    apply_beacon_root, deduct_caller, deduct_caller_inner, deduct_gas_sponsor_inner, load_accounts,
+    load_precompiles,
+};
 pub use validation::{validate_env, validate_initial_tx_gas, validate_tx_against_state};
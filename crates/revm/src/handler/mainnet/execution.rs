@@ -5,7 +5,7 @@ use crate::{
         return_ok, return_revert, CallInputs, CreateInputs, CreateOutcome, Gas, InstructionResult,
         SharedMemory,
     },
-    primitives::{EVMError, Env, Spec, SpecId},
+    primitives::{EVMError, Env, Spec, SpecId, Transaction},
     CallFrame, Context, CreateFrame, Frame, FrameOrResult, FrameResult,
 };
 use core::mem;
@@ -24,17 +24,101 @@ pub fn execute_frame<SPEC: Spec, EXT, DB: Database>(
     context: &mut Context<EXT, DB>,
 ) -> Result<InterpreterAction, EVMError<DB::Error>> {
     let interpreter = frame.interpreter_mut();
+    #[cfg(feature = "opcode_stats")]
+    if interpreter.opcode_counts.is_none() && context.evm.env.cfg.opcode_stats.is_enabled() {
+        interpreter.opcode_counts = Some(Default::default());
+    }
+    #[cfg(feature = "gas_profile")]
+    if interpreter.gas_usage.is_none() && context.evm.env.cfg.gas_profile {
+        interpreter.gas_usage = Some(Default::default());
+    }
+    // The wall clock is not deterministic.
+    #[cfg(all(feature = "execution_timeout", not(feature = "deterministic")))]
+    if let Some(timeout) = context.evm.env.cfg.execution_timeout {
+        let deadline = *context
+            .evm
+            .journaled_state
+            .deadline
+            .get_or_insert_with(|| std::time::Instant::now() + timeout);
+        interpreter
+            .deadline
+            .get_or_insert_with(|| revm_interpreter::Deadline::new(deadline));
+    }
     let memory = mem::replace(shared_memory, EMPTY_SHARED_MEMORY);
-    let next_action = match instruction_tables {
-        InstructionTables::Plain(table) => interpreter.run(memory, table, context),
-        InstructionTables::Boxed(table) => interpreter.run(memory, table, context),
-    };
+    let next_action = interpreter.run_with_tables(memory, instruction_tables, context);
     // Take the shared memory back.
     *shared_memory = interpreter.take_memory();
 
+    // The interpreter stops with a fatal error once the deadline passes.
+    #[cfg(feature = "execution_timeout")]
+    if interpreter.instruction_result == InstructionResult::FatalExternalError
+        && context.evm.error.is_ok()
+        && interpreter
+            .deadline
+            .is_some_and(|deadline| deadline.is_expired())
+    {
+        return Err(EVMError::Timeout);
+    }
+
+    // Record the counters of the finished frame.
+    #[cfg(feature = "opcode_stats")]
+    if next_action.is_return() {
+        if let Some(counts) = interpreter.opcode_counts.take() {
+            let per_contract =
+                context.evm.env.cfg.opcode_stats == crate::primitives::OpcodeStatsLevel::Contract;
+            let code_address = per_contract.then(|| interpreter.contract.code_address());
+            context
+                .evm
+                .journaled_state
+                .opcode_stats
+                .record(code_address, &counts);
+        }
+    }
+
+    #[cfg(feature = "gas_profile")]
+    let next_action = attach_gas_profile(context, interpreter, next_action);
+
     Ok(next_action)
 }
 
+/// Attaches the gas spent by a finished frame and its subcalls to its result.
+#[cfg(feature = "gas_profile")]
+#[inline]
+fn attach_gas_profile<EXT, DB: Database>(
+    context: &Context<EXT, DB>,
+    interpreter: &mut crate::interpreter::Interpreter,
+    mut action: InterpreterAction,
+) -> InterpreterAction {
+    if let InterpreterAction::Return { result } = &mut action {
+        if let Some(usage) = interpreter.gas_usage.take() {
+            // The journal depth of the transaction frame is 1.
+            let depth = context.evm.journaled_state.depth() as usize - 1;
+            let mut profile = interpreter.gas_profile.take().unwrap_or_default();
+            profile.record(interpreter.contract.code_address(), depth, &usage);
+            result.gas_profile = Some(profile);
+        }
+    }
+    action
+}
+
+/// Charges the gas a creation spent after its frame returned, i.e. the code deposit, to the
+/// created contract.
+#[cfg(feature = "gas_profile")]
+#[inline]
+fn record_code_deposit<EXT, DB: Database>(
+    context: &Context<EXT, DB>,
+    result: &mut InterpreterResult,
+    created_address: crate::primitives::Address,
+    spent_by_frame: u64,
+) {
+    if let Some(profile) = &mut result.gas_profile {
+        // The checkpoint of the frame is closed, the journal is back at the depth of its parent.
+        let depth = context.evm.journaled_state.depth() as usize;
+        let deposit = result.gas.spent().saturating_sub(spent_by_frame);
+        profile.record_gas(created_address, depth, deposit);
+    }
+}
+
 /// Helper function called inside [`last_frame_return`]
 #[inline]
 pub fn frame_return_with_refund_flag<SPEC: Spec>(
@@ -48,7 +132,7 @@ pub fn frame_return_with_refund_flag<SPEC: Spec>(
     let refunded = gas.refunded();
 
     // Spend the gas limit. Gas is reimbursed when the tx returns successfully.
-    *gas = Gas::new_spent(env.tx.gas_limit);
+    *gas = Gas::new_spent(env.tx.gas_limit());
 
     match instruction_result {
         return_ok!() => {
@@ -68,6 +152,8 @@ pub fn frame_return_with_refund_flag<SPEC: Spec>(
     if refund_enabled {
         // EIP-3529: Reduction in refunds
         gas.set_final_refund(SPEC::SPEC_ID.is_enabled_in(SpecId::LONDON));
+    } else {
+        gas.set_refund(0);
     }
 }
 
@@ -87,7 +173,25 @@ pub fn call<SPEC: Spec, EXT, DB: Database>(
     context: &mut Context<EXT, DB>,
     inputs: Box<CallInputs>,
 ) -> Result<FrameOrResult, EVMError<DB::Error>> {
-    context.evm.make_call_frame(&inputs)
+    let frame_or_result = context.evm.make_call_frame(&inputs)?;
+
+    // Precompiles return without a frame, their gas is charged to their address.
+    #[cfg(feature = "gas_profile")]
+    let frame_or_result = match frame_or_result {
+        FrameOrResult::Result(FrameResult::Call(mut outcome))
+            if context.evm.env.cfg.gas_profile
+                && context.evm.precompiles.contains(&inputs.bytecode_address) =>
+        {
+            let depth = context.evm.journaled_state.depth() as usize;
+            let mut profile = crate::primitives::GasProfile::default();
+            profile.record_gas(inputs.bytecode_address, depth, outcome.gas().spent());
+            outcome.result.gas_profile = Some(profile);
+            FrameOrResult::Result(FrameResult::Call(outcome))
+        }
+        frame_or_result => frame_or_result,
+    };
+
+    Ok(frame_or_result)
 }
 
 #[inline]
@@ -99,10 +203,8 @@ pub fn call_return<EXT, DB: Database>(
     context
         .evm
         .call_return(&interpreter_result, frame.frame_data.checkpoint);
-    Ok(CallOutcome::new(
-        interpreter_result,
-        frame.return_memory_range,
-    ))
+    let is_static = frame.frame_data.interpreter.is_static;
+    Ok(CallOutcome::new(interpreter_result, frame.return_memory_range).with_static(is_static))
 }
 
 #[inline]
@@ -135,11 +237,20 @@ pub fn create_return<SPEC: Spec, EXT, DB: Database>(
     frame: Box<CreateFrame>,
     mut interpreter_result: InterpreterResult,
 ) -> Result<CreateOutcome, EVMError<DB::Error>> {
+    #[cfg(feature = "gas_profile")]
+    let spent_by_frame = interpreter_result.gas.spent();
     context.evm.create_return::<SPEC>(
         &mut interpreter_result,
         frame.created_address,
         frame.frame_data.checkpoint,
     );
+    #[cfg(feature = "gas_profile")]
+    record_code_deposit(
+        context,
+        &mut interpreter_result,
+        frame.created_address,
+        spent_by_frame,
+    );
     Ok(CreateOutcome::new(
         interpreter_result,
         Some(frame.created_address),
@@ -175,11 +286,20 @@ pub fn eofcreate_return<SPEC: Spec, EXT, DB: Database>(
     frame: Box<EOFCreateFrame>,
     mut interpreter_result: InterpreterResult,
 ) -> Result<CreateOutcome, EVMError<DB::Error>> {
+    #[cfg(feature = "gas_profile")]
+    let spent_by_frame = interpreter_result.gas.spent();
     context.evm.eofcreate_return::<SPEC>(
         &mut interpreter_result,
         frame.created_address,
         frame.frame_data.checkpoint,
     );
+    #[cfg(feature = "gas_profile")]
+    record_code_deposit(
+        context,
+        &mut interpreter_result,
+        frame.created_address,
+        spent_by_frame,
+    );
     Ok(CreateOutcome::new(
         interpreter_result,
         Some(frame.created_address),
@@ -212,11 +332,7 @@ mod tests {
         env.tx.gas_limit = 100;
 
         let mut first_frame = FrameResult::Call(CallOutcome::new(
-            InterpreterResult {
-                result: instruction_result,
-                output: Bytes::new(),
-                gas,
-            },
+            InterpreterResult::new(instruction_result, Bytes::new(), gas),
             0..0,
         ));
         frame_return_with_refund_flag::<CancunSpec>(&env, &mut first_frame, true);
@@ -247,6 +363,27 @@ mod tests {
         assert_eq!(gas.refunded(), 0);
     }
 
+    #[test]
+    fn test_gas_accounting_never_negative() {
+        use crate::interpreter::test_utils::Rng;
+
+        let results = [
+            InstructionResult::Stop,
+            InstructionResult::Revert,
+            InstructionResult::OutOfGas,
+        ];
+        let mut rng = Rng::default();
+        for _ in 0..500 {
+            let mut ret_gas = Gas::new(rng.next(101));
+            ret_gas.record_refund(rng.next(100) as i64);
+            let gas = call_last_frame_return(results[rng.next(3) as usize], ret_gas);
+            assert_eq!(gas.limit(), 100);
+            assert!(gas.refunded() >= 0);
+            assert!(gas.refund_gas().final_value() <= gas.spent());
+            assert_eq!(gas.used() + gas.reimbursed(), 100);
+        }
+    }
+
     #[test]
     fn test_revert_gas() {
         let gas = call_last_frame_return(InstructionResult::Revert, Gas::new(90));
@@ -254,4 +391,208 @@ mod tests {
         assert_eq!(gas.spent(), 10);
         assert_eq!(gas.refunded(), 0);
     }
+
+    #[cfg(feature = "gas_profile")]
+    #[test]
+    fn test_gas_profile() {
+        use crate::{
+            db::BenchmarkDB,
+            interpreter::opcode,
+            primitives::{bytes, Address, Bytecode, TxKind},
+            Evm,
+        };
+
+        let target = Address::ZERO;
+        let identity = Address::with_last_byte(4);
+        // Calls the identity precompile without input.
+        //
+        // PUSH1 0 PUSH1 0 PUSH1 0 PUSH1 0 PUSH1 4 GAS STATICCALL POP STOP
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(bytes!(
+                "600060006000600060045afa5000"
+            ))))
+            .modify_cfg_env(|cfg| cfg.gas_profile = true)
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TxKind::Call(target);
+                tx.gas_limit = 100_000;
+            })
+            .build();
+
+        let result = evm.transact().unwrap();
+        let profile = result.gas_profile;
+        // The call is charged its warm access cost, the gas used by the precompile is charged
+        // to the precompile.
+        assert_eq!(profile.per_opcode.get(opcode::STATICCALL), 100);
+        assert_eq!(profile.per_opcode.get(opcode::PUSH1), 15);
+        assert_eq!(profile.per_contract[&target], 119);
+        assert_eq!(profile.per_contract[&identity], 15);
+        assert_eq!(profile.per_depth, [119, 15]);
+        assert_eq!(result.result.gas_used(), 21_000 + profile.total());
+
+        evm.cfg_mut().gas_profile = false;
+        assert!(evm.transact().unwrap().gas_profile.is_empty());
+
+        // Transfers 1 wei to the caller with a zero gas limit, the callee gets the stipend.
+        //
+        // PUSH1 0 PUSH1 0 PUSH1 0 PUSH1 0 PUSH1 1 PUSH1 1 PUSH1 0 CALL POP STOP
+        *evm.db_mut() = BenchmarkDB::new_bytecode(Bytecode::new_raw(bytes!(
+            "6000600060006000600160016000f15000"
+        )));
+        evm.cfg_mut().gas_profile = true;
+        let profile = evm.transact().unwrap().gas_profile;
+        // Warm access and value transfer, the stipend is not paid by the caller.
+        assert_eq!(profile.per_opcode.get(opcode::CALL), 100 + 9000);
+
+        // Creates a contract with one byte of code.
+        //
+        // PUSH1 1 PUSH1 0 RETURN
+        evm.context.evm.env.tx.transact_to = TxKind::Create;
+        evm.context.evm.env.tx.data = bytes!("60016000f3");
+        let profile = evm.transact().unwrap().gas_profile;
+        let created = Address::with_last_byte(1).create(0);
+        assert_eq!(profile.per_opcode.total(), 3 + 3 + 3);
+        assert_eq!(profile.per_contract[&created], 3 + 3 + 3 + 200);
+        assert_eq!(profile.per_depth, [3 + 3 + 3 + 200]);
+    }
+
+    #[cfg(feature = "opcode_stats")]
+    #[test]
+    fn test_opcode_stats() {
+        use crate::{
+            db::BenchmarkDB,
+            interpreter::opcode,
+            primitives::{bytes, Address, Bytecode, OpcodeStatsLevel, TxKind},
+            Evm,
+        };
+
+        let target = Address::ZERO;
+        // PUSH1 1 PUSH1 2 ADD POP STOP
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(bytes!(
+                "6001600201500000"
+            ))))
+            .modify_cfg_env(|cfg| cfg.opcode_stats = OpcodeStatsLevel::Contract)
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TxKind::Call(target);
+                tx.gas_limit = 100_000;
+            })
+            .build();
+
+        let stats = evm.transact().unwrap().opcode_stats;
+        assert_eq!(stats.total.get(opcode::PUSH1), 2);
+        assert_eq!(stats.total.get(opcode::STOP), 1);
+        assert_eq!(stats.total.total(), 5);
+        assert_eq!(stats.per_contract[&target], stats.total);
+
+        evm.cfg_mut().opcode_stats = OpcodeStatsLevel::Disabled;
+        assert!(evm.transact().unwrap().opcode_stats.is_empty());
+    }
+
+    #[cfg(all(feature = "execution_timeout", not(feature = "deterministic")))]
+    #[test]
+    fn test_execution_timeout() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{bytes, Address, Bytecode, TxKind},
+            Evm,
+        };
+        use core::time::Duration;
+
+        // JUMPDEST PUSH1 0 JUMP
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(bytes!(
+                "5b600056"
+            ))))
+            .modify_cfg_env(|cfg| cfg.execution_timeout = Some(Duration::from_millis(10)))
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = u64::MAX / 2;
+            })
+            .build();
+
+        assert!(matches!(evm.transact(), Err(EVMError::Timeout)));
+    }
+
+    #[cfg(feature = "deterministic")]
+    #[test]
+    fn test_deterministic_output() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{hex, Address, Bytecode, TxKind},
+            Evm,
+        };
+
+        // PUSH1 i BALANCE POP for i in 1..=32, STOP
+        let code = (1..=32u8)
+            .map(|i| format!("60{i:02x}3150"))
+            .collect::<String>()
+            + "00";
+        let run = || {
+            let mut evm = Evm::builder()
+                .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(
+                    hex::decode(&code).unwrap().into(),
+                )))
+                .modify_tx_env(|tx| {
+                    tx.caller = Address::with_last_byte(1);
+                    tx.transact_to = TxKind::Call(Address::ZERO);
+                    tx.gas_limit = 1_000_000;
+                })
+                .build();
+            format!("{:?}", evm.transact().unwrap())
+        };
+
+        // Maps with the same history iterate in the same order, in any process.
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn test_journal_limit() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{bytes, Address, Bytecode, ExecutionResult, HaltReason, TxKind},
+            Evm,
+        };
+
+        // Loads a new cold slot, stores a new transient slot, or emits a log in every iteration.
+        // JUMPDEST GAS SLOAD POP PUSH1 0 JUMP
+        // JUMPDEST PUSH1 1 GAS TSTORE PUSH1 0 JUMP
+        // JUMPDEST PUSH1 0 PUSH1 0 LOG0 PUSH1 0 JUMP
+        for code in [
+            bytes!("5b5a5450600056"),
+            bytes!("5b60015a5d600056"),
+            bytes!("5b60006000a0600056"),
+        ] {
+            let mut evm = Evm::builder()
+                .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(code)))
+                .modify_cfg_env(|cfg| cfg.journal_limit = Some(100))
+                .modify_tx_env(|tx| {
+                    tx.caller = Address::with_last_byte(1);
+                    tx.transact_to = TxKind::Call(Address::ZERO);
+                    tx.gas_limit = 1_000_000;
+                })
+                .build();
+            let result = evm.transact().unwrap().result;
+            assert!(matches!(
+                result,
+                ExecutionResult::Halt {
+                    reason: HaltReason::JournalLimitExceeded,
+                    gas_used: 1_000_000,
+                }
+            ));
+            assert_eq!(evm.context.evm.journaled_state.entry_count(), 0);
+
+            evm.cfg_mut().journal_limit = None;
+            let result = evm.transact().unwrap().result;
+            assert!(matches!(
+                result,
+                ExecutionResult::Halt {
+                    reason: HaltReason::OutOfGas(_),
+                    ..
+                }
+            ));
+        }
+    }
 }
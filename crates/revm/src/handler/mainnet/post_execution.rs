@@ -1,8 +1,8 @@
 use crate::{
     interpreter::{Gas, SuccessOrHalt},
     primitives::{
-        db::Database, Bytecode, EVMError, ExecutionResult, ResultAndState, Spec, SpecId::LONDON,
-        KECCAK_EMPTY, U256,
+        db::Database, Address, Bytecode, EVMError, Env, ExecutionResult, ResultAndState, Spec,
+        SpecId, Transaction, KECCAK_EMPTY, U256,
     },
     Context, FrameResult,
 };
@@ -33,29 +33,77 @@ pub fn reward_beneficiary<SPEC: Spec, EXT, DB: Database>(
     context: &mut Context<EXT, DB>,
     gas: &Gas,
 ) -> Result<(), EVMError<DB::Error>> {
-    let beneficiary = context.evm.env.block.coinbase;
-    let effective_gas_price = context.evm.env.effective_gas_price();
+    for (recipient, fee) in fee_payments(&context.evm.env, SPEC::SPEC_ID, gas) {
+        credit_fee(context, recipient, fee)?;
+    }
+    Ok(())
+}
+
+/// Returns the accounts paid a part of the fee of a transaction that used `gas`, with their
+/// part, see [`CfgEnv::fee_vaults`]. Burned parts are left out, as are zero parts if
+/// [`CfgEnv::skip_zero_fee_rewards`] is set.
+///
+/// [`CfgEnv::fee_vaults`]: crate::primitives::CfgEnv::fee_vaults
+/// [`CfgEnv::skip_zero_fee_rewards`]: crate::primitives::CfgEnv::skip_zero_fee_rewards
+pub fn fee_payments(
+    env: &Env,
+    spec_id: SpecId,
+    gas: &Gas,
+) -> impl Iterator<Item = (Address, U256)> {
+    let fee_vaults = env.cfg.fee_vaults;
+    let coinbase = env.block.coinbase;
+    let effective_gas_price = env.effective_gas_price();
+    let basefee = env.block.basefee;
+    let is_london = spec_id.is_enabled_in(SpecId::LONDON);
+    let skip_zero_fees = env.cfg.skip_zero_fee_rewards;
 
-    // transfer fee to coinbase/beneficiary.
     // EIP-1559 discard basefee for coinbase transfer. Basefee amount of gas is discarded.
-    let coinbase_gas_price = if SPEC::enabled(LONDON) {
-        effective_gas_price.saturating_sub(context.evm.env.block.basefee)
+    let priority_gas_price = if is_london {
+        effective_gas_price.saturating_sub(basefee)
     } else {
         effective_gas_price
     };
+    let priority_fee = fee_vaults
+        .priority_fee_recipient(coinbase)
+        .map(|recipient| (recipient, priority_gas_price * U256::from(gas.used())));
 
-    let (coinbase_account, _) = context
+    // there is no base fee before London.
+    let base_fee = fee_vaults
+        .base_fee_recipient(coinbase)
+        .filter(|_| is_london)
+        .map(|recipient| {
+            let base_gas_price = basefee.min(effective_gas_price);
+            (recipient, base_gas_price * U256::from(gas.used()))
+        });
+
+    priority_fee
+        .into_iter()
+        .chain(base_fee)
+        .filter(move |(_, fee)| !(skip_zero_fees && fee.is_zero()))
+}
+
+/// Touches the recipient of a fee and adds the fee to its balance.
+///
+/// Zero fees leave the recipient untouched if [`CfgEnv::skip_zero_fee_rewards`] is set.
+///
+/// [`CfgEnv::skip_zero_fee_rewards`]: crate::primitives::CfgEnv::skip_zero_fee_rewards
+#[inline]
+pub fn credit_fee<EXT, DB: Database>(
+    context: &mut Context<EXT, DB>,
+    recipient: Address,
+    fee: U256,
+) -> Result<(), EVMError<DB::Error>> {
+    if fee.is_zero() && context.evm.env.cfg.skip_zero_fee_rewards {
+        return Ok(());
+    }
+    let (account, _) = context
         .evm
         .inner
         .journaled_state
-        .load_account(beneficiary, &mut context.evm.inner.db)?;
-
-    coinbase_account.mark_touch();
-    coinbase_account.info.balance = coinbase_account
-        .info
-        .balance
-        .saturating_add(coinbase_gas_price * U256::from(gas.spent() - gas.refunded() as u64));
+        .load_account(recipient, &mut context.evm.inner.db)?;
 
+    account.mark_touch();
+    account.info.balance = account.info.balance.saturating_add(fee);
     Ok(())
 }
 
@@ -64,20 +112,21 @@ pub fn reimburse_caller<SPEC: Spec, EXT, DB: Database>(
     context: &mut Context<EXT, DB>,
     gas: &Gas,
 ) -> Result<(), EVMError<DB::Error>> {
-    let caller = context.evm.env.tx.caller;
+    // the sponsor of a sponsored transaction paid for the gas.
+    let payer = context.evm.env.tx.gas_payer();
     let effective_gas_price = context.evm.env.effective_gas_price();
 
     // return balance of not spend gas.
-    let (caller_account, _) = context
+    let (payer_account, _) = context
         .evm
         .inner
         .journaled_state
-        .load_account(caller, &mut context.evm.inner.db)?;
+        .load_account(payer, &mut context.evm.inner.db)?;
 
-    caller_account.info.balance = caller_account
+    payer_account.info.balance = payer_account
         .info
         .balance
-        .saturating_add(effective_gas_price * U256::from(gas.remaining() + gas.refunded() as u64));
+        .saturating_add(effective_gas_price * U256::from(gas.reimbursed()));
 
     Ok(())
 }
@@ -90,23 +139,35 @@ pub fn output<EXT, DB: Database>(
 ) -> Result<ResultAndState, EVMError<DB::Error>> {
     context.evm.take_error()?;
     // used gas with refund calculated.
-    let gas_refunded = result.gas().refunded() as u64;
-    let final_gas_used = result.gas().spent() - gas_refunded;
+    let gas_refunded = result.gas().refund_gas().final_value();
+    let final_gas_used = result.gas().used();
     let output = result.output();
     let instruction_result = result.into_interpreter_result();
 
-    // reset journal and return present state.
-    let (mut state, logs) = context.evm.journaled_state.finalize();
-
     // clear code of authorized accounts.
-    for authorized in core::mem::take(&mut context.evm.inner.valid_authorizations).into_iter() {
-        let account = state
+    for (authorized, _) in core::mem::take(&mut context.evm.inner.valid_authorizations) {
+        let account = context
+            .evm
+            .journaled_state
+            .state
             .get_mut(&authorized)
             .expect("Authorized account must exist");
         account.info.code = Some(Bytecode::default());
         account.info.code_hash = KECCAK_EMPTY;
     }
 
+    // reset journal and return present state.
+    #[cfg(feature = "log_origins")]
+    let log_origins = core::mem::take(&mut context.evm.journaled_state.log_origins);
+    let (state, logs) = context.evm.journaled_state.finalize();
+    #[cfg(feature = "log_origins")]
+    let logs_with_origins = logs
+        .iter()
+        .cloned()
+        .zip(log_origins)
+        .map(|(log, origin)| crate::primitives::LogWithOrigin { log, origin })
+        .collect();
+
     let result = match instruction_result.result.into() {
         SuccessOrHalt::Success(reason) => ExecutionResult::Success {
             reason,
@@ -125,12 +186,213 @@ pub fn output<EXT, DB: Database>(
         },
         // Only two internal return flags.
         flag @ (SuccessOrHalt::FatalExternalError | SuccessOrHalt::Internal(_)) => {
-            panic!(
-                "Encountered unexpected internal return flag: {:?} with instruction result: {:?}",
-                flag, instruction_result
-            )
+            return Err(EVMError::Internal(format!(
+                "unexpected internal return flag {flag:?} with instruction result {:?}",
+                instruction_result.result
+            )));
         }
     };
 
-    Ok(ResultAndState { result, state })
+    let blob_gas_used = context.evm.env.tx.total_blob_gas();
+    let blob_fee = context.evm.env.calc_data_fee().unwrap_or_default();
+
+    Ok(ResultAndState {
+        result,
+        state,
+        blob_gas_used,
+        blob_fee,
+        #[cfg(feature = "keccak_preimages")]
+        keccak_preimages: core::mem::take(&mut context.evm.journaled_state.keccak_preimages),
+        #[cfg(feature = "opcode_stats")]
+        opcode_stats: core::mem::take(&mut context.evm.journaled_state.opcode_stats),
+        #[cfg(feature = "gas_profile")]
+        gas_profile: instruction_result.gas_profile.unwrap_or_default(),
+        impersonation: context.evm.journaled_state.impersonation.take(),
+        #[cfg(feature = "log_origins")]
+        logs_with_origins,
+        reverted_logs: context.evm.journaled_state.take_reverted_logs(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::EmptyDB,
+        interpreter::{CallOutcome, InstructionResult, InterpreterResult},
+        primitives::{Bytes, FeeRecipient, FeeVaults, LondonSpec},
+    };
+
+    #[test]
+    fn fee_vaults() {
+        let (coinbase, base_vault, tip_vault) = (
+            Address::with_last_byte(1),
+            Address::with_last_byte(2),
+            Address::with_last_byte(3),
+        );
+        let mut gas = Gas::new(100);
+        assert!(gas.record_cost(40));
+        let balance = |context: &mut Context<(), EmptyDB>, address| {
+            context
+                .evm
+                .inner
+                .journaled_state
+                .state
+                .get(&address)
+                .map(|account| account.info.balance)
+        };
+        let new_context = |fee_vaults| {
+            let mut context = Context::new_empty();
+            context.evm.env.block.coinbase = coinbase;
+            context.evm.env.block.basefee = U256::from(7);
+            context.evm.env.tx.gas_price = U256::from(10);
+            context.evm.env.cfg.fee_vaults = fee_vaults;
+            context
+        };
+
+        // mainnet, the base fee is burned.
+        let mut context = new_context(FeeVaults::default());
+        reward_beneficiary::<LondonSpec, _, _>(&mut context, &gas).unwrap();
+        assert_eq!(balance(&mut context, coinbase), Some(U256::from(3 * 40)));
+        assert_eq!(balance(&mut context, base_vault), None);
+
+        let mut context = new_context(FeeVaults {
+            base_fee: FeeRecipient::Account(base_vault),
+            priority_fee: FeeRecipient::Account(tip_vault),
+        });
+        reward_beneficiary::<LondonSpec, _, _>(&mut context, &gas).unwrap();
+        assert_eq!(balance(&mut context, coinbase), None);
+        assert_eq!(balance(&mut context, base_vault), Some(U256::from(7 * 40)));
+        assert_eq!(balance(&mut context, tip_vault), Some(U256::from(3 * 40)));
+
+        // both parts burned.
+        let mut context = new_context(FeeVaults {
+            base_fee: FeeRecipient::Burn,
+            priority_fee: FeeRecipient::Burn,
+        });
+        reward_beneficiary::<LondonSpec, _, _>(&mut context, &gas).unwrap();
+        assert_eq!(balance(&mut context, coinbase), None);
+    }
+
+    #[test]
+    fn skip_zero_fee_rewards() {
+        let coinbase = Address::with_last_byte(1);
+        let mut gas = Gas::new(100);
+        assert!(gas.record_cost(40));
+        let touched = |skip_zero_fee_rewards| {
+            let mut context: Context<(), EmptyDB> = Context::new_empty();
+            context.evm.env.block.coinbase = coinbase;
+            context.evm.env.cfg.skip_zero_fee_rewards = skip_zero_fee_rewards;
+            // zero gas price, as on devnets and for system transactions.
+            reward_beneficiary::<LondonSpec, _, _>(&mut context, &gas).unwrap();
+            context
+                .evm
+                .inner
+                .journaled_state
+                .state
+                .get(&coinbase)
+                .is_some_and(|account| account.is_touched())
+        };
+        assert!(touched(false));
+        assert!(!touched(true));
+
+        let mut env = Env::default();
+        env.cfg.skip_zero_fee_rewards = true;
+        assert_eq!(fee_payments(&env, SpecId::LONDON, &gas).count(), 0);
+    }
+
+    #[test]
+    fn internal_flags_are_errors() {
+        for result in [
+            InstructionResult::Continue,
+            InstructionResult::CallOrCreate,
+            InstructionResult::InvalidExtDelegateCallTarget,
+            InstructionResult::FatalExternalError,
+        ] {
+            let mut context = Context::new_empty();
+            let frame_result = FrameResult::Call(CallOutcome::new(
+                InterpreterResult::new(result, Bytes::new(), Gas::new(0)),
+                0..0,
+            ));
+            assert!(matches!(
+                output(&mut context, frame_result),
+                Err(EVMError::Internal(_))
+            ));
+        }
+
+        let mut context = Context::new_empty();
+        let frame_result = FrameResult::Call(CallOutcome::new(
+            InterpreterResult::new(InstructionResult::Stop, Bytes::new(), Gas::new(0)),
+            0..0,
+        ));
+        assert!(output(&mut context, frame_result)
+            .unwrap()
+            .result
+            .is_success());
+    }
+
+    #[test]
+    fn blob_gas_and_fee() {
+        use crate::primitives::{BlobExcessGasAndPrice, B256, GAS_PER_BLOB};
+
+        let stop = || {
+            FrameResult::Call(CallOutcome::new(
+                InterpreterResult::new(InstructionResult::Stop, Bytes::new(), Gas::new(0)),
+                0..0,
+            ))
+        };
+
+        // not a blob transaction.
+        let mut context: Context<(), EmptyDB> = Context::new_empty();
+        let result = output(&mut context, stop()).unwrap();
+        assert_eq!(result.blob_gas_used, 0);
+        assert_eq!(result.blob_fee, U256::ZERO);
+
+        let mut context: Context<(), EmptyDB> = Context::new_empty();
+        context.evm.env.tx.blob_hashes = vec![B256::ZERO; 2];
+        context.evm.env.block.blob_excess_gas_and_price = Some(BlobExcessGasAndPrice {
+            excess_blob_gas: 0,
+            blob_gasprice: 3,
+        });
+        let result = output(&mut context, stop()).unwrap();
+        assert_eq!(result.blob_gas_used, 2 * GAS_PER_BLOB);
+        assert_eq!(result.blob_fee, U256::from(3 * 2 * GAS_PER_BLOB));
+    }
+
+    #[cfg(feature = "keccak_preimages")]
+    #[test]
+    fn keccak_preimages() {
+        use crate::{
+            primitives::{address, keccak256, AccountInfo, TxKind},
+            Evm, InMemoryDB,
+        };
+
+        // PUSH1 0x2a PUSH1 0 MSTORE PUSH1 0x20 PUSH1 0 KECCAK256 STOP
+        let code =
+            Bytecode::new_raw([0x60, 0x2a, 0x60, 0, 0x52, 0x60, 0x20, 0x60, 0, 0x20, 0].into());
+        let code_hash = code.hash_slow();
+        let to = address!("ffffffffffffffffffffffffffffffffffffffff");
+        let preimages = |record: bool| {
+            Evm::builder()
+                .with_db(InMemoryDB::default())
+                .modify_db(|db| {
+                    db.insert_account_info(
+                        to,
+                        AccountInfo::new(U256::ZERO, 0, code_hash, code.clone()),
+                    )
+                })
+                .modify_tx_env(|tx| tx.transact_to = TxKind::Call(to))
+                .modify_cfg_env(|cfg| cfg.record_keccak_preimages = record)
+                .build()
+                .transact()
+                .unwrap()
+                .keccak_preimages
+        };
+
+        assert!(preimages(false).is_empty());
+        let input = U256::from(0x2a).to_be_bytes::<32>();
+        let preimages = preimages(true);
+        assert_eq!(preimages.len(), 1);
+        assert_eq!(preimages[&keccak256(input)][..], input);
+    }
 }
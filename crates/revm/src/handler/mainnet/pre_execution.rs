@@ -6,9 +6,10 @@ use crate::{
     precompile::PrecompileSpecId,
     primitives::{
         db::Database,
-        Account, EVMError, Env, Spec,
-        SpecId::{CANCUN, PRAGUE, SHANGHAI},
-        TxKind, BLOCKHASH_STORAGE_ADDRESS, KECCAK_EMPTY, U256,
+        Account, EVMError, Eip, Env, Spec,
+        SpecId::{CANCUN, SHANGHAI},
+        Transaction, TxKind, BEACON_ROOTS_ADDRESS, BEACON_ROOTS_HISTORY_BUFFER_LENGTH,
+        BLOCKHASH_STORAGE_ADDRESS, KECCAK_EMPTY, U256,
     },
     Context, ContextPrecompiles,
 };
@@ -20,6 +21,60 @@ pub fn load_precompiles<SPEC: Spec, DB: Database>() -> ContextPrecompiles<DB> {
     ContextPrecompiles::new(PrecompileSpecId::from_spec_id(SPEC::SPEC_ID))
 }
 
+/// EIP-4788: Beacon block root in the EVM.
+///
+/// Stores the `parent_beacon_block_root` of the block in the beacon roots contract, like the
+/// system call made before the transactions of the block. The storage writes of the contract are
+/// applied directly, they are idempotent so executing every transaction of the block with the
+/// root gives the same state. Does nothing if the root is unset or the contract is not deployed.
+///
+/// The contract and its slots are left cold, they are not part of the transaction accesses.
+#[inline]
+pub fn apply_beacon_root<SPEC: Spec, EXT, DB: Database>(
+    context: &mut Context<EXT, DB>,
+) -> Result<(), EVMError<DB::Error>> {
+    if !SPEC::enabled(CANCUN) {
+        return Ok(());
+    }
+    let Some(root) = context.evm.inner.env.block.parent_beacon_block_root else {
+        return Ok(());
+    };
+    let timestamp = context.evm.inner.env.block.timestamp;
+    let inner = &mut context.evm.inner;
+
+    let (account, _) = inner
+        .journaled_state
+        .load_code(BEACON_ROOTS_ADDRESS, &mut inner.db)?;
+    if account.info.code_hash == KECCAK_EMPTY {
+        account.mark_cold();
+        return Ok(());
+    }
+
+    let timestamp_index = timestamp % U256::from(BEACON_ROOTS_HISTORY_BUFFER_LENGTH);
+    let root_index = timestamp_index + U256::from(BEACON_ROOTS_HISTORY_BUFFER_LENGTH);
+    inner.journaled_state.sstore(
+        BEACON_ROOTS_ADDRESS,
+        timestamp_index,
+        timestamp,
+        &mut inner.db,
+    )?;
+    inner
+        .journaled_state
+        .sstore(BEACON_ROOTS_ADDRESS, root_index, root.into(), &mut inner.db)?;
+    inner.journaled_state.touch(&BEACON_ROOTS_ADDRESS);
+
+    let account = inner
+        .journaled_state
+        .state
+        .get_mut(&BEACON_ROOTS_ADDRESS)
+        .expect("beacon roots contract is loaded");
+    account.mark_cold();
+    for slot in account.storage.values_mut() {
+        slot.mark_cold();
+    }
+    Ok(())
+}
+
 /// Main load handle
 #[inline]
 pub fn load_accounts<SPEC: Spec, EXT, DB: Database>(
@@ -28,6 +83,10 @@ pub fn load_accounts<SPEC: Spec, EXT, DB: Database>(
     // set journaling state flag.
     context.evm.journaled_state.set_spec_id(SPEC::SPEC_ID);
 
+    // EIP-4788: Beacon block root in the EVM.
+    // Applied before any account is warmed, as it leaves the contract cold.
+    apply_beacon_root::<SPEC, EXT, DB>(context)?;
+
     // load coinbase
     // EIP-3651: Warm COINBASE. Starts the `COINBASE` address warm
     if SPEC::enabled(SHANGHAI) {
@@ -41,7 +100,12 @@ pub fn load_accounts<SPEC: Spec, EXT, DB: Database>(
 
     // Load blockhash storage address
     // EIP-2935: Serve historical block hashes from state
-    if SPEC::enabled(PRAGUE) {
+    if context
+        .evm
+        .env
+        .cfg
+        .is_eip_enabled(SPEC::SPEC_ID, Eip::Eip2935)
+    {
         context
             .evm
             .journaled_state
@@ -50,8 +114,13 @@ pub fn load_accounts<SPEC: Spec, EXT, DB: Database>(
     }
 
     // EIP-7702. Load bytecode to authorized accounts.
-    if SPEC::enabled(PRAGUE) {
-        if let Some(authorization_list) = context.evm.inner.env.tx.authorization_list.as_ref() {
+    if context
+        .evm
+        .env
+        .cfg
+        .is_eip_enabled(SPEC::SPEC_ID, Eip::Eip7702)
+    {
+        if let Some(authorization_list) = context.evm.inner.env.tx.authorization_list() {
             let mut valid_auths = Vec::with_capacity(authorization_list.len());
             for authorization in authorization_list.recovered_iter() {
                 // 1. recover authority and authorized addresses.
@@ -110,7 +179,7 @@ pub fn load_accounts<SPEC: Spec, EXT, DB: Database>(
                     code_hash,
                 );
 
-                valid_auths.push(authority);
+                valid_auths.push((authority, authorization.address));
             }
 
             context.evm.inner.valid_authorizations = valid_auths;
@@ -122,23 +191,17 @@ pub fn load_accounts<SPEC: Spec, EXT, DB: Database>(
 }
 
 /// Helper function that deducts the caller balance.
+///
+/// The gas of a sponsored transaction is deducted from the sponsor with
+/// [`deduct_gas_sponsor_inner`] instead, only the nonce of the caller is bumped.
 #[inline]
 pub fn deduct_caller_inner<SPEC: Spec>(caller_account: &mut Account, env: &Env) {
-    // Subtract gas costs from the caller's account.
-    // We need to saturate the gas cost to prevent underflow in case that `disable_balance_check` is enabled.
-    let mut gas_cost = U256::from(env.tx.gas_limit).saturating_mul(env.effective_gas_price());
-
-    // EIP-4844
-    if SPEC::enabled(CANCUN) {
-        let data_fee = env.calc_data_fee().expect("already checked");
-        gas_cost = gas_cost.saturating_add(data_fee);
+    if !env.tx.is_sponsored() {
+        deduct_gas_fee::<SPEC>(caller_account, env);
     }
 
-    // set new caller account balance.
-    caller_account.info.balance = caller_account.info.balance.saturating_sub(gas_cost);
-
     // bump the nonce for calls. Nonce for CREATE will be bumped in `handle_create`.
-    if matches!(env.tx.transact_to, TxKind::Call(_)) {
+    if matches!(env.tx.transact_to(), TxKind::Call(_)) {
         // Nonce is already checked
         caller_account.info.nonce = caller_account.info.nonce.saturating_add(1);
     }
@@ -147,6 +210,27 @@ pub fn deduct_caller_inner<SPEC: Spec>(caller_account: &mut Account, env: &Env)
     caller_account.mark_touch();
 }
 
+/// Helper function that deducts the gas fee of a sponsored transaction from the sponsor.
+#[inline]
+pub fn deduct_gas_sponsor_inner<SPEC: Spec>(sponsor_account: &mut Account, env: &Env) {
+    deduct_gas_fee::<SPEC>(sponsor_account, env);
+    sponsor_account.mark_touch();
+}
+
+/// Subtracts the gas cost of the transaction from the account.
+fn deduct_gas_fee<SPEC: Spec>(account: &mut Account, env: &Env) {
+    // We need to saturate the gas cost to prevent underflow in case that `disable_balance_check` is enabled.
+    let mut gas_cost = U256::from(env.tx.gas_limit()).saturating_mul(env.effective_gas_price());
+
+    // EIP-4844
+    if SPEC::enabled(CANCUN) {
+        let data_fee = env.calc_data_fee().expect("already checked");
+        gas_cost = gas_cost.saturating_add(data_fee);
+    }
+
+    account.info.balance = account.info.balance.saturating_sub(gas_cost);
+}
+
 /// Deducts the caller balance to the transaction limit.
 #[inline]
 pub fn deduct_caller<SPEC: Spec, EXT, DB: Database>(
@@ -157,10 +241,148 @@ pub fn deduct_caller<SPEC: Spec, EXT, DB: Database>(
         .evm
         .inner
         .journaled_state
-        .load_account(context.evm.inner.env.tx.caller, &mut context.evm.inner.db)?;
+        .load_account(context.evm.inner.env.tx.caller(), &mut context.evm.inner.db)?;
 
     // deduct gas cost from caller's account.
     deduct_caller_inner::<SPEC>(caller_account, &context.evm.inner.env);
 
+    if context.evm.inner.env.tx.is_sponsored() {
+        let (sponsor_account, _) = context.evm.inner.journaled_state.load_account(
+            context.evm.inner.env.tx.gas_payer(),
+            &mut context.evm.inner.db,
+        )?;
+        deduct_gas_sponsor_inner::<SPEC>(sponsor_account, &context.evm.inner.env);
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        db::{CacheDB, EmptyDB},
+        primitives::{address, AccountInfo, EVMError, InvalidTransaction, TxKind, U256},
+        Evm,
+    };
+
+    #[test]
+    fn sponsor_pays_gas() {
+        let caller = address!("0000000000000000000000000000000000000001");
+        let sponsor = address!("0000000000000000000000000000000000000002");
+        let to = address!("0000000000000000000000000000000000000003");
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            caller,
+            AccountInfo {
+                balance: U256::from(10),
+                ..Default::default()
+            },
+        );
+        db.insert_account_info(
+            sponsor,
+            AccountInfo {
+                balance: U256::from(1_000_000),
+                ..Default::default()
+            },
+        );
+
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .modify_tx_env(|tx| {
+                tx.caller = caller;
+                tx.gas_sponsor = Some(sponsor);
+                tx.transact_to = TxKind::Call(to);
+                tx.value = U256::from(10);
+                tx.gas_limit = 100_000;
+                tx.gas_price = U256::from(2);
+            })
+            .build();
+
+        let state = evm.transact().unwrap().state;
+        assert_eq!(state[&caller].info.balance, U256::ZERO);
+        assert_eq!(state[&caller].info.nonce, 1);
+        assert_eq!(state[&to].info.balance, U256::from(10));
+        assert_eq!(
+            state[&sponsor].info.balance,
+            U256::from(1_000_000 - 2 * 21_000)
+        );
+
+        // The sponsor can't pay for the gas limit.
+        evm.tx_mut().gas_limit = 1_000_000;
+        assert!(matches!(
+            evm.transact().unwrap_err(),
+            EVMError::Transaction(InvalidTransaction::LackOfFundForMaxFee { .. })
+        ));
+    }
+
+    #[test]
+    fn warm_accesses() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{bytes, AccessListItem, Address, Bytecode, B256},
+        };
+
+        // PUSH1 1 SLOAD STOP
+        let code = Bytecode::new_raw(bytes!("60015400"));
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(code))
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TxKind::Call(Address::ZERO);
+            })
+            .build();
+        assert_eq!(evm.transact().unwrap().result.gas_used(), 21_000 + 3 + 2100);
+
+        evm.cfg_mut().warm_accesses = vec![AccessListItem {
+            address: Address::ZERO,
+            storage_keys: vec![B256::with_last_byte(1)],
+        }];
+        assert_eq!(evm.transact().unwrap().result.gas_used(), 21_000 + 3 + 100);
+    }
+
+    #[test]
+    fn beacon_root() {
+        use crate::primitives::{
+            bytes, Bytecode, ExecutionResult, B256, BEACON_ROOTS_ADDRESS,
+            BEACON_ROOTS_HISTORY_BUFFER_LENGTH,
+        };
+
+        let caller = address!("0000000000000000000000000000000000000001");
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(caller, AccountInfo::from_balance(U256::from(1_000_000)));
+        // PUSH1 1 SLOAD PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+        db.insert_account_info(
+            BEACON_ROOTS_ADDRESS,
+            AccountInfo::from_bytecode(Bytecode::new_raw(bytes!("60015460005260206000f3"))),
+        );
+        let root = B256::with_last_byte(0xbe);
+
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .modify_tx_env(|tx| {
+                tx.caller = caller;
+                tx.transact_to = TxKind::Call(BEACON_ROOTS_ADDRESS);
+                tx.gas_limit = 100_000;
+            })
+            .modify_block_env(|block| block.timestamp = U256::from(1))
+            .build();
+        let without_root = evm.transact().unwrap().result;
+
+        evm.context.evm.env.block.parent_beacon_block_root = Some(root);
+        let result = evm.transact().unwrap();
+        let ExecutionResult::Success {
+            output, gas_used, ..
+        } = result.result
+        else {
+            panic!("execution failed: {:?}", result.result);
+        };
+        // The contract reads the timestamp stored before the transaction.
+        assert_eq!(U256::from_be_slice(output.data()), U256::from(1));
+        // The slot is still cold.
+        assert_eq!(gas_used, without_root.gas_used());
+
+        let storage = &result.state[&BEACON_ROOTS_ADDRESS].storage;
+        let root_index = U256::from(1 + BEACON_ROOTS_HISTORY_BUFFER_LENGTH);
+        assert_eq!(storage[&root_index].present_value, root.into());
+    }
+}
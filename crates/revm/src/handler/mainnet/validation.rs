@@ -1,7 +1,9 @@
-use revm_interpreter::gas;
+use revm_interpreter::gas::GasSchedule;
 
 use crate::{
-    primitives::{db::Database, EVMError, Env, InvalidTransaction, Spec},
+    primitives::{
+        db::Database, EVMError, Eip, Env, Impersonation, InvalidTransaction, Spec, Transaction,
+    },
     Context,
 };
 
@@ -18,48 +20,124 @@ pub fn validate_tx_against_state<SPEC: Spec, EXT, DB: Database>(
     context: &mut Context<EXT, DB>,
 ) -> Result<(), EVMError<DB::Error>> {
     // load acc
-    let tx_caller = context.evm.env.tx.caller;
+    let tx_caller = context.evm.env.tx.caller();
     let (caller_account, _) = context
         .evm
         .inner
         .journaled_state
         .load_account(tx_caller, &mut context.evm.inner.db)?;
 
+    let balance = caller_account.info.balance;
     context
         .evm
         .inner
         .env
         .validate_tx_against_state::<SPEC>(caller_account)
         .map_err(EVMError::Transaction)?;
+    if context.evm.inner.env.cfg.impersonate_caller {
+        let minted = caller_account.info.balance - balance;
+        context.evm.inner.journaled_state.impersonation = Some(Impersonation {
+            caller: tx_caller,
+            minted,
+        });
+    }
+
+    if context.evm.env.tx.is_sponsored() {
+        let (sponsor_account, _) = context.evm.inner.journaled_state.load_account(
+            context.evm.inner.env.tx.gas_payer(),
+            &mut context.evm.inner.db,
+        )?;
+
+        context
+            .evm
+            .inner
+            .env
+            .validate_gas_sponsor_against_state::<SPEC>(sponsor_account)
+            .map_err(EVMError::Transaction)?;
+    }
 
     Ok(())
 }
 
-/// Validate initial transaction gas.
+/// Validate initial transaction gas, priced with `gas_schedule`.
 pub fn validate_initial_tx_gas<SPEC: Spec, DB: Database>(
     env: &Env,
+    gas_schedule: &GasSchedule,
 ) -> Result<u64, EVMError<DB::Error>> {
-    let input = &env.tx.data;
-    let is_create = env.tx.transact_to.is_create();
-    let access_list = &env.tx.access_list;
-    let authorization_list_num = env
-        .tx
-        .authorization_list
-        .as_ref()
-        .map(|l| l.len() as u64)
-        .unwrap_or_default();
+    let mut initial_gas_spend = gas_schedule.transaction_initial_gas(SPEC::SPEC_ID, &env.tx);
 
-    let initial_gas_spend = gas::validate_initial_tx_gas(
-        SPEC::SPEC_ID,
-        input,
-        is_create,
-        access_list,
-        authorization_list_num,
-    );
+    // EIP-7702 enabled ahead of its fork is not priced by the spec.
+    if !Eip::Eip7702.is_active_in(SPEC::SPEC_ID) && env.cfg.experimental_eips.contains(Eip::Eip7702)
+    {
+        let authorization_list_num = env
+            .tx
+            .authorization_list()
+            .map(|l| l.len() as u64)
+            .unwrap_or_default();
+        initial_gas_spend += authorization_list_num * gas_schedule.per_auth_base;
+    }
 
     // Additional check to see if limit is big enough to cover initial gas.
-    if initial_gas_spend > env.tx.gas_limit {
-        return Err(InvalidTransaction::CallGasCostMoreThanGasLimit.into());
+    if initial_gas_spend > env.tx.gas_limit() {
+        return Err(InvalidTransaction::CallGasCostMoreThanGasLimit {
+            initial_gas: initial_gas_spend,
+            gas_limit: env.tx.gas_limit(),
+        }
+        .into());
     }
     Ok(initial_gas_spend)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        db::InMemoryDB,
+        primitives::{
+            bytes, AccountInfo, Address, Bytecode, EVMError, Impersonation, InvalidTransaction,
+            TxKind, U256,
+        },
+        Evm,
+    };
+
+    #[test]
+    fn impersonate_caller() {
+        let (caller, to) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let mut db = InMemoryDB::default();
+        // The sender is a contract without balance.
+        db.insert_account_info(
+            caller,
+            AccountInfo {
+                nonce: 5,
+                ..AccountInfo::from_bytecode(Bytecode::new_raw(bytes!("00")))
+            },
+        );
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .modify_tx_env(|tx| {
+                tx.caller = caller;
+                tx.transact_to = TxKind::Call(to);
+                tx.value = U256::from(10);
+                tx.gas_limit = 100_000;
+                tx.gas_price = U256::from(1);
+                tx.nonce = Some(0);
+            })
+            .build();
+        assert!(matches!(
+            evm.transact(),
+            Err(EVMError::Transaction(
+                InvalidTransaction::RejectCallerWithCode
+            ))
+        ));
+
+        evm.cfg_mut().impersonate_caller = true;
+        let result = evm.transact().unwrap();
+        let minted = U256::from(100_000 + 10);
+        assert_eq!(result.impersonation, Some(Impersonation { caller, minted }));
+        assert_eq!(result.result.gas_used(), 21_000);
+        // The credited balance pays for the gas used and the value, the rest is refunded.
+        let sender = &result.state[&caller].info;
+        assert_eq!(sender.balance, minted - U256::from(21_000 + 10));
+        assert_eq!(sender.nonce, 6);
+        assert_eq!(result.state[&to].info.balance, U256::from(10));
+    }
+}
@@ -8,7 +8,8 @@ pub type EvmHandler<'a, EXT, DB> = Handler<'a, Context<EXT, DB>, EXT, DB>;
 pub type HandleRegister<EXT, DB> = for<'a> fn(&mut EvmHandler<'a, EXT, DB>);
 
 // Boxed handle register
-pub type HandleRegisterBox<'a, EXT, DB> = Box<dyn for<'e> Fn(&mut EvmHandler<'e, EXT, DB>) + 'a>;
+pub type HandleRegisterBox<'a, EXT, DB> =
+    Box<dyn for<'e> Fn(&mut EvmHandler<'e, EXT, DB>) + Send + Sync + 'a>;
 
 pub enum HandleRegisters<'a, EXT, DB: Database> {
     /// Plain function register
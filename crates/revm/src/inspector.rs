@@ -1,11 +1,24 @@
+#[cfg(feature = "abi")]
+pub mod abi;
+mod access_list;
+mod call_stack;
 #[cfg(feature = "std")]
 mod customprinter;
 #[cfg(all(feature = "std", feature = "serde-json"))]
 mod eip3155;
+mod four_byte;
+mod frame_tags;
 mod gas;
 mod handler_register;
 mod noop;
+#[cfg(feature = "sourcemap")]
+pub mod sourcemap;
+mod time_travel;
+#[cfg(feature = "zstd_trace")]
+mod zstd_trace;
 
+pub use frame_tags::FrameTags;
+pub(crate) use handler_register::InspectorInputs;
 pub use handler_register::{inspector_handle_register, GetInspector};
 
 use crate::{
@@ -19,12 +32,24 @@ use auto_impl::auto_impl;
 
 /// [Inspector] implementations.
 pub mod inspectors {
+    #[cfg(feature = "abi")]
+    pub use super::abi::{self, AbiInspector};
+    pub use super::access_list::{create_access_list, AccessListInspector};
+    pub use super::call_stack::{CallStackInspector, FrameAddresses};
     #[cfg(feature = "std")]
     pub use super::customprinter::CustomPrintTracer;
     #[cfg(all(feature = "std", feature = "serde-json"))]
-    pub use super::eip3155::TracerEip3155;
+    pub use super::eip3155::{Eip3155Step, Eip3155Summary, TracerEip3155};
+    pub use super::four_byte::FourByteInspector;
     pub use super::gas::GasInspector;
     pub use super::noop::NoOpInspector;
+    #[cfg(feature = "sourcemap")]
+    pub use super::sourcemap;
+    pub use super::time_travel::{
+        FrameRecord, RecordedStep, StepDelta, TimeTravelRecorder, TimeTravelSession,
+    };
+    #[cfg(feature = "zstd_trace")]
+    pub use super::zstd_trace::{ZstdTraceReader, ZstdTraceWriter};
 }
 
 /// EVM [Interpreter] callbacks.
@@ -72,6 +97,31 @@ pub trait Inspector<DB: Database> {
         let _ = log;
     }
 
+    /// Called when a `TSTORE` of `address` sets the transient storage slot `key` from
+    /// `had_value` to `new_value`.
+    ///
+    /// Called for every successful `TSTORE`, including the ones that do not change the value and
+    /// are not journaled. Changes of reverted frames are undone by
+    /// [`JournalEntry::TransientStorageChange`](crate::JournalEntry::TransientStorageChange)
+    /// entries of the journal.
+    #[inline]
+    fn tstore(
+        &mut self,
+        interp: &mut Interpreter,
+        context: &mut EvmContext<DB>,
+        address: Address,
+        key: U256,
+        had_value: U256,
+        new_value: U256,
+    ) {
+        let _ = interp;
+        let _ = context;
+        let _ = address;
+        let _ = key;
+        let _ = had_value;
+        let _ = new_value;
+    }
+
     /// Called whenever a call to a contract is about to start.
     ///
     /// InstructionResulting anything other than [crate::interpreter::InstructionResult::Continue] overrides the result of the call.
@@ -167,4 +217,39 @@ pub trait Inspector<DB: Database> {
         let _ = target;
         let _ = value;
     }
+
+    /// Called when an instruction records a gas refund.
+    ///
+    /// `amount` is negative if the instruction takes back a refund recorded before, e.g. an
+    /// `SSTORE` that sets a cleared slot again.
+    ///
+    /// The refund is reported when the instruction executes, before it is known whether its
+    /// frame succeeds. Refunds of frames that revert later are reported too but are not part of
+    /// the refund of the transaction, see [Inspector::final_refund].
+    #[inline]
+    fn refund_recorded(&mut self, amount: i64, reason: RefundReason) {
+        let _ = amount;
+        let _ = reason;
+    }
+
+    /// Called at the end of the transaction with the refund that is `set` and its `cap`.
+    ///
+    /// `set` is the refund before the cap, the transaction is refunded the smaller of the two.
+    /// The cap is a part of the gas spent, see
+    /// [Gas::max_refund](crate::interpreter::Gas::max_refund). The refund is zero if the
+    /// transaction did not succeed.
+    #[inline]
+    fn final_refund(&mut self, set: u64, cap: u64) {
+        let _ = set;
+        let _ = cap;
+    }
+}
+
+/// Instruction that recorded a gas refund, see [Inspector::refund_recorded].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RefundReason {
+    /// `SSTORE` that clears a slot or restores its original value, see EIP-2200 and EIP-3529.
+    Sstore,
+    /// `SELFDESTRUCT` of an account, removed in London by EIP-3529.
+    Selfdestruct,
 }
@@ -0,0 +1,480 @@
+//! Registry of function signatures used to decode call data, and [`AbiInspector`] recording the
+//! decoded calls of a transaction.
+//!
+//! Only elementary ABI types are decoded (`address`, `bool`, `uintN`, `intN`, `bytesN`, `bytes`
+//! and `string`). Functions with other parameter types are still resolved by selector, but their
+//! arguments are left undecoded.
+
+use crate::{
+    interpreter::{CallInputs, CallOutcome},
+    primitives::{db::Database, keccak256, Address, Bytes, FixedBytes, HashMap, I256, U256},
+    EvmContext, Inspector,
+};
+use core::fmt;
+use std::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// Function selector, first four bytes of the call data.
+pub type Selector = FixedBytes<4>;
+
+/// Elementary ABI type of a function parameter.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AbiType {
+    /// `address`
+    Address,
+    /// `bool`
+    Bool,
+    /// `uintN`, with the size in bits.
+    Uint(usize),
+    /// `intN`, with the size in bits.
+    Int(usize),
+    /// `bytesN`, with the size in bytes.
+    FixedBytes(usize),
+    /// `bytes`
+    Bytes,
+    /// `string`
+    String,
+    /// Any other type, kept as written in the canonical signature.
+    Other(String),
+}
+
+impl AbiType {
+    /// Parses a canonical type name, e.g. `uint256`.
+    pub fn parse(ty: &str) -> Self {
+        let sized = |prefix: &str, max: usize, step: usize| {
+            let size = ty.strip_prefix(prefix)?;
+            if size.is_empty() {
+                return Some(max);
+            }
+            let size: usize = size.parse().ok()?;
+            (size != 0 && size <= max && size % step == 0).then_some(size)
+        };
+        match ty {
+            "address" => Self::Address,
+            "bool" => Self::Bool,
+            "bytes" => Self::Bytes,
+            "string" => Self::String,
+            _ if ty.starts_with("uint") => {
+                sized("uint", 256, 8).map_or_else(|| Self::other(ty), Self::Uint)
+            }
+            _ if ty.starts_with("int") => {
+                sized("int", 256, 8).map_or_else(|| Self::other(ty), Self::Int)
+            }
+            _ if ty.starts_with("bytes") => match sized("bytes", 32, 1) {
+                // plain `bytes` is handled above.
+                Some(size) => Self::FixedBytes(size),
+                None => Self::other(ty),
+            },
+            _ => Self::other(ty),
+        }
+    }
+
+    fn other(ty: &str) -> Self {
+        Self::Other(ty.to_string())
+    }
+}
+
+impl fmt::Display for AbiType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Address => f.write_str("address"),
+            Self::Bool => f.write_str("bool"),
+            Self::Uint(size) => write!(f, "uint{size}"),
+            Self::Int(size) => write!(f, "int{size}"),
+            Self::FixedBytes(size) => write!(f, "bytes{size}"),
+            Self::Bytes => f.write_str("bytes"),
+            Self::String => f.write_str("string"),
+            Self::Other(ty) => f.write_str(ty),
+        }
+    }
+}
+
+/// Decoded ABI value.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AbiValue {
+    Address(Address),
+    Bool(bool),
+    Uint(U256),
+    Int(I256),
+    FixedBytes(Bytes),
+    Bytes(Bytes),
+    String(String),
+}
+
+impl fmt::Display for AbiValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Address(address) => write!(f, "{address}"),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Uint(value) => write!(f, "{value}"),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::FixedBytes(bytes) | Self::Bytes(bytes) => write!(f, "{bytes}"),
+            Self::String(string) => write!(f, "{string:?}"),
+        }
+    }
+}
+
+/// Function signature registered in the [`AbiRegistry`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FunctionSignature {
+    /// Function name.
+    pub name: String,
+    /// Parameter types.
+    pub inputs: Vec<AbiType>,
+}
+
+impl FunctionSignature {
+    /// Parses a human readable signature such as `transfer(address to, uint256 amount)`.
+    ///
+    /// Parameter names are ignored. Returns `None` if the signature is malformed.
+    pub fn parse(signature: &str) -> Option<Self> {
+        let signature = signature.trim();
+        let signature = signature.strip_prefix("function ").unwrap_or(signature);
+        let (name, params) = signature.split_once('(')?;
+        let params = params.trim_end().strip_suffix(')')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        let mut inputs = Vec::new();
+        if !params.trim().is_empty() {
+            for param in split_params(params)? {
+                let ty = param.split_whitespace().next()?;
+                inputs.push(AbiType::parse(canonical_type(ty)));
+            }
+        }
+        Some(Self {
+            name: name.to_string(),
+            inputs,
+        })
+    }
+
+    /// Returns the canonical signature, e.g. `transfer(address,uint256)`.
+    pub fn canonical(&self) -> String {
+        let inputs: Vec<String> = self.inputs.iter().map(ToString::to_string).collect();
+        std::format!("{}({})", self.name, inputs.join(","))
+    }
+
+    /// Returns the function selector.
+    pub fn selector(&self) -> Selector {
+        Selector::from_slice(&keccak256(self.canonical().as_bytes())[..4])
+    }
+
+    /// Decodes the arguments from the call data without the selector.
+    ///
+    /// Returns `None` if a parameter type is not supported or the data is malformed.
+    pub fn decode_args(&self, data: &[u8]) -> Option<Vec<AbiValue>> {
+        self.inputs
+            .iter()
+            .enumerate()
+            .map(|(i, ty)| decode_value(ty, data, i * 32))
+            .collect()
+    }
+}
+
+impl fmt::Display for FunctionSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.canonical())
+    }
+}
+
+/// Call decoded with a registered [`FunctionSignature`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DecodedCall<'a> {
+    /// Matched function signature.
+    pub function: &'a FunctionSignature,
+    /// Decoded arguments, `None` if they could not be decoded.
+    pub args: Option<Vec<AbiValue>>,
+}
+
+impl fmt::Display for DecodedCall<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(args) = &self.args else {
+            return write!(f, "{}(..)", self.function.name);
+        };
+        write!(f, "{}(", self.function.name)?;
+        for (i, arg) in args.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{arg}")?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// Registry of function signatures indexed by selector.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AbiRegistry {
+    functions: HashMap<Selector, FunctionSignature>,
+}
+
+impl AbiRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses and registers the function signature, returning its selector.
+    ///
+    /// Returns `None` if the signature is malformed. A signature with the same selector is
+    /// replaced.
+    pub fn register(&mut self, signature: &str) -> Option<Selector> {
+        let function = FunctionSignature::parse(signature)?;
+        let selector = function.selector();
+        self.functions.insert(selector, function);
+        Some(selector)
+    }
+
+    /// Registers all given signatures, skipping the malformed ones.
+    pub fn with_signatures<'a>(mut self, signatures: impl IntoIterator<Item = &'a str>) -> Self {
+        for signature in signatures {
+            let _ = self.register(signature);
+        }
+        self
+    }
+
+    /// Returns the function registered for the selector.
+    pub fn get(&self, selector: &Selector) -> Option<&FunctionSignature> {
+        self.functions.get(selector)
+    }
+
+    /// Returns the number of registered functions.
+    pub fn len(&self) -> usize {
+        self.functions.len()
+    }
+
+    /// Returns `true` if no function is registered.
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    /// Decodes the call data if its selector is registered.
+    pub fn decode_call(&self, input: &[u8]) -> Option<DecodedCall<'_>> {
+        let selector = input.get(..4)?;
+        let function = self.get(&Selector::from_slice(selector))?;
+        Some(DecodedCall {
+            function,
+            args: function.decode_args(&input[4..]),
+        })
+    }
+}
+
+/// Call frame whose call data matched a registered function, see [`AbiInspector`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DecodedFrame {
+    /// Depth of the frame, the first frame of the transaction has depth 1.
+    pub depth: usize,
+    /// Target address of the call.
+    pub target: Address,
+    /// Matched function signature.
+    pub function: FunctionSignature,
+    /// Decoded arguments, `None` if they could not be decoded.
+    pub args: Option<Vec<AbiValue>>,
+}
+
+/// [Inspector] that decodes the call data of every call frame with an [`AbiRegistry`].
+///
+/// Calls whose selector is not registered are skipped.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AbiInspector {
+    registry: AbiRegistry,
+    calls: Vec<DecodedFrame>,
+}
+
+impl AbiInspector {
+    /// Creates an inspector decoding calls with the registry.
+    pub fn new(registry: AbiRegistry) -> Self {
+        Self {
+            registry,
+            calls: Vec::new(),
+        }
+    }
+
+    /// Returns the registry used to decode the calls.
+    pub fn registry(&self) -> &AbiRegistry {
+        &self.registry
+    }
+
+    /// Returns the decoded calls in the order they were made.
+    pub fn calls(&self) -> &[DecodedFrame] {
+        &self.calls
+    }
+
+    /// Takes the decoded calls, leaving the inspector ready for the next transaction.
+    pub fn take_calls(&mut self) -> Vec<DecodedFrame> {
+        core::mem::take(&mut self.calls)
+    }
+}
+
+impl<DB: Database> Inspector<DB> for AbiInspector {
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        if let Some(call) = self.registry.decode_call(&inputs.input) {
+            self.calls.push(DecodedFrame {
+                depth: context.journaled_state.depth() as usize + 1,
+                target: inputs.target_address,
+                function: call.function.clone(),
+                args: call.args,
+            });
+        }
+        None
+    }
+}
+
+/// Splits parameters at the top level commas, keeping tuples intact.
+fn split_params(params: &str) -> Option<Vec<&str>> {
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut out = Vec::new();
+    for (i, c) in params.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1)?,
+            ',' if depth == 0 => {
+                out.push(params[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    out.push(params[start..].trim());
+    (depth == 0 && out.iter().all(|p| !p.is_empty())).then_some(out)
+}
+
+/// Expands the `uint` and `int` aliases.
+fn canonical_type(ty: &str) -> &str {
+    match ty {
+        "uint" => "uint256",
+        "int" => "int256",
+        _ => ty,
+    }
+}
+
+fn word(data: &[u8], offset: usize) -> Option<&[u8]> {
+    data.get(offset..offset.checked_add(32)?)
+}
+
+fn word_usize(data: &[u8], offset: usize) -> Option<usize> {
+    usize::try_from(U256::from_be_slice(word(data, offset)?)).ok()
+}
+
+fn decode_value(ty: &AbiType, data: &[u8], head: usize) -> Option<AbiValue> {
+    let value = match ty {
+        AbiType::Address => AbiValue::Address(Address::from_slice(&word(data, head)?[12..])),
+        AbiType::Bool => AbiValue::Bool(word(data, head)?[31] != 0),
+        AbiType::Uint(_) => AbiValue::Uint(U256::from_be_slice(word(data, head)?)),
+        AbiType::Int(_) => AbiValue::Int(I256::from_raw(U256::from_be_slice(word(data, head)?))),
+        AbiType::FixedBytes(size) => {
+            AbiValue::FixedBytes(Bytes::copy_from_slice(&word(data, head)?[..*size]))
+        }
+        AbiType::Bytes | AbiType::String => {
+            let offset = word_usize(data, head)?;
+            let len = word_usize(data, offset)?;
+            let start = offset.checked_add(32)?;
+            let bytes = data.get(start..start.checked_add(len)?)?;
+            if *ty == AbiType::String {
+                AbiValue::String(String::from_utf8_lossy(bytes).into_owned())
+            } else {
+                AbiValue::Bytes(Bytes::copy_from_slice(bytes))
+            }
+        }
+        AbiType::Other(_) => return None,
+    };
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{address, hex};
+
+    #[test]
+    fn decode_transfer() {
+        let mut registry = AbiRegistry::new();
+        let selector = registry
+            .register("function transfer(address to, uint amount)")
+            .unwrap();
+        assert_eq!(selector, FixedBytes(hex!("a9059cbb")));
+
+        let input = hex!(
+            "a9059cbb"
+            "000000000000000000000000000000000000000000000000000000000000abcd"
+            "0000000000000000000000000000000000000000000000000000000000000064"
+        );
+        let to = address!("000000000000000000000000000000000000abcd");
+        let call = registry.decode_call(&input).unwrap();
+        assert_eq!(
+            call.args,
+            Some(vec![AbiValue::Address(to), AbiValue::Uint(U256::from(100))])
+        );
+        assert_eq!(call.to_string(), format!("transfer({to}, 100)"));
+    }
+
+    #[test]
+    fn decode_dynamic_and_unsupported() {
+        let registry = AbiRegistry::new().with_signatures(["setName(string)", "batch(uint256[])"]);
+        assert_eq!(registry.len(), 2);
+
+        let function = FunctionSignature::parse("setName(string)").unwrap();
+        let mut input = function.selector().to_vec();
+        input.extend_from_slice(&hex!(
+            "0000000000000000000000000000000000000000000000000000000000000020"
+            "0000000000000000000000000000000000000000000000000000000000000003"
+            "616263"
+        ));
+        let call = registry.decode_call(&input).unwrap();
+        assert_eq!(call.args, Some(vec![AbiValue::String("abc".to_string())]));
+
+        let function = FunctionSignature::parse("batch(uint256[])").unwrap();
+        let call = registry.decode_call(&function.selector()[..]).unwrap();
+        assert_eq!(call.args, None);
+        assert_eq!(call.to_string(), "batch(..)");
+    }
+
+    #[test]
+    fn inspector_records_calls() {
+        use crate::{
+            db::BenchmarkDB,
+            inspector_handle_register,
+            primitives::{bytes, Bytecode, TxKind},
+            Evm,
+        };
+
+        let registry = AbiRegistry::new().with_signatures(["transfer(address,uint256)"]);
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(bytes!("00"))))
+            .with_external_context(AbiInspector::new(registry))
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.data = bytes!(
+                    "a9059cbb"
+                    "000000000000000000000000000000000000000000000000000000000000abcd"
+                    "0000000000000000000000000000000000000000000000000000000000000064"
+                );
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        assert!(evm.transact().unwrap().result.is_success());
+
+        let calls = evm.context.external.take_calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!((calls[0].depth, calls[0].target), (1, Address::ZERO));
+        assert_eq!(calls[0].function.name, "transfer");
+        assert_eq!(
+            calls[0].args,
+            Some(vec![
+                AbiValue::Address(address!("000000000000000000000000000000000000abcd")),
+                AbiValue::Uint(U256::from(100)),
+            ])
+        );
+        assert!(evm.context.external.calls().is_empty());
+    }
+}
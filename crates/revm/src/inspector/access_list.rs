@@ -0,0 +1,172 @@
+//! AccessListInspector. Generates the EIP-2930 access list of a transaction.
+
+use crate::{
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome},
+    primitives::{
+        db::Database, AccessList, AccessListItem, Address, EVMError, ResultAndState, Transaction,
+        B256,
+    },
+    Evm, EvmContext, Inspector,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+
+/// [Inspector] that generates the access list of a transaction, equivalent to the access list
+/// tracer of geth used by `eth_createAccessList`.
+///
+/// When the transaction ends, every account and storage slot loaded by the journal is added to
+/// the list, including the ones loaded by reverted frames. The caller, the gas sponsor, the
+/// transaction target and the precompiles are warm anyway, they are only listed if some of their
+/// slots were loaded.
+///
+/// See [`create_access_list`] to iterate the access list to a fixpoint.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccessListInspector {
+    access_list: AccessList,
+}
+
+impl AccessListInspector {
+    /// Returns the access list of the last transaction.
+    pub fn access_list(&self) -> &AccessList {
+        &self.access_list
+    }
+
+    /// Returns the access list of the last transaction.
+    pub fn into_access_list(self) -> AccessList {
+        self.access_list
+    }
+
+    /// Collects the accounts and slots loaded by the journal, except the warm accounts without
+    /// loaded slots.
+    fn collect<DB: Database>(&mut self, context: &EvmContext<DB>, target: Address) {
+        let tx = &context.env.tx;
+        let excluded = [tx.caller(), tx.gas_payer(), target];
+        let mut accesses = BTreeMap::<Address, BTreeSet<B256>>::new();
+        for (address, account) in &context.journaled_state.state {
+            if account.storage.is_empty()
+                && (excluded.contains(address) || context.precompiles.contains(address))
+            {
+                continue;
+            }
+            let slots = account.storage.keys().map(|slot| B256::from(*slot));
+            accesses.entry(*address).or_default().extend(slots);
+        }
+        self.access_list = AccessList(
+            accesses
+                .into_iter()
+                .map(|(address, storage_keys)| AccessListItem {
+                    address,
+                    storage_keys: storage_keys.into_iter().collect(),
+                })
+                .collect(),
+        );
+    }
+}
+
+impl<DB: Database> Inspector<DB> for AccessListInspector {
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        if context.journaled_state.depth() == 0 {
+            self.collect(context, inputs.target_address);
+        }
+        outcome
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        if context.journaled_state.depth() == 0 {
+            let created = outcome.address.unwrap_or_default();
+            self.collect(context, created);
+        }
+        outcome
+    }
+}
+
+/// Generates the access list of the transaction of `evm` like `eth_createAccessList`.
+///
+/// The transaction is executed with the generated access list until it is the same as the one
+/// the transaction was executed with, as accessing warm accounts changes the gas available to
+/// the execution. Starts from the access list of the transaction, which is restored afterwards.
+///
+/// Returns the generated access list and the result of the last execution, that used the
+/// access list if it converged within `max_iterations` executions. The [`Evm`] must have the
+/// [`inspector_handle_register`](crate::inspector_handle_register) appended, nothing is
+/// committed to the database.
+pub fn create_access_list<DB: Database>(
+    evm: &mut Evm<'_, AccessListInspector, DB>,
+    max_iterations: usize,
+) -> Result<(AccessList, ResultAndState), EVMError<DB::Error>> {
+    let original = evm.tx().access_list.clone();
+    let mut execute = || -> Result<_, EVMError<DB::Error>> {
+        let mut iterations = 0;
+        loop {
+            let result = evm.transact()?;
+            iterations += 1;
+            let generated = evm.context.external.access_list();
+            if generated.0 == evm.tx().access_list || iterations >= max_iterations {
+                return Ok((generated.clone(), result));
+            }
+            evm.tx_mut().access_list = generated.0.clone();
+        }
+    };
+    let result = execute();
+    evm.tx_mut().access_list = original;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::BenchmarkDB,
+        inspector_handle_register,
+        primitives::{bytes, Bytecode, TxKind, U256},
+    };
+
+    #[test]
+    fn converges_to_accessed_state() {
+        // Reads slot 1 of itself and the balance of 0x42.
+        //
+        // PUSH1 1 SLOAD POP PUSH1 0x42 BALANCE POP STOP
+        let code = bytes!("600154506042315000");
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(code)))
+            .with_external_context(AccessListInspector::default())
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        let cold = evm.transact().unwrap().result.gas_used();
+
+        let (access_list, result) = create_access_list(&mut evm, 5).unwrap();
+        assert_eq!(
+            access_list.0,
+            [
+                AccessListItem {
+                    address: Address::ZERO,
+                    storage_keys: vec![B256::from(U256::from(1))],
+                },
+                AccessListItem {
+                    address: Address::with_last_byte(0x42),
+                    storage_keys: Vec::new(),
+                },
+            ]
+        );
+        // 2 * 2400 + 1900 for the list, 2000 + 2500 saved on the accesses.
+        assert_eq!(result.result.gas_used(), cold + 2200);
+        assert!(evm.tx().access_list.is_empty());
+    }
+}
@@ -0,0 +1,258 @@
+//! CallStackInspector. Helper Inspector that tracks the addresses of the active frames.
+
+use crate::{
+    interpreter::{
+        CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, EOFCreateInputs,
+        EOFCreateKind,
+    },
+    primitives::{db::Database, Address},
+    EvmContext, Inspector,
+};
+use std::vec::Vec;
+
+/// Addresses of a call or create frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FrameAddresses {
+    /// Caller of the frame, returned by `CALLER`.
+    pub caller: Address,
+    /// Executing account, returned by `ADDRESS`. Storage accessed by the frame belongs to it.
+    pub target_address: Address,
+    /// Account the executed code was loaded from. It differs from `target_address` in
+    /// DELEGATECALL and CALLCODE frames.
+    pub code_address: Address,
+    /// Call scheme, `None` for contract creation.
+    pub scheme: Option<CallScheme>,
+}
+
+impl FrameAddresses {
+    /// Returns `true` if the frame executes code of another account.
+    #[inline]
+    pub fn is_delegated(&self) -> bool {
+        self.code_address != self.target_address
+    }
+}
+
+/// Helper [Inspector] that keeps the stack of active frames, from the transaction frame to the
+/// currently executing one.
+///
+/// Meant to be embedded in other inspectors that need to attribute storage accesses or calls to
+/// the right accounts.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CallStackInspector {
+    frames: Vec<FrameAddresses>,
+}
+
+impl CallStackInspector {
+    /// Returns the active frames, outermost first.
+    pub fn frames(&self) -> &[FrameAddresses] {
+        &self.frames
+    }
+
+    /// Returns the currently executing frame.
+    pub fn current(&self) -> Option<&FrameAddresses> {
+        self.frames.last()
+    }
+
+    /// Returns the callers of the active frames, innermost first, ending with the transaction
+    /// caller.
+    pub fn caller_chain(&self) -> impl Iterator<Item = Address> + '_ {
+        self.frames.iter().rev().map(|frame| frame.caller)
+    }
+
+    fn push_create<DB: Database>(
+        &mut self,
+        context: &EvmContext<DB>,
+        caller: Address,
+        created_address: impl FnOnce(u64) -> Address,
+    ) {
+        let nonce = context
+            .journaled_state
+            .state
+            .get(&caller)
+            .map_or(0, |account| account.info.nonce);
+        let address = created_address(nonce);
+        self.frames.push(FrameAddresses {
+            caller,
+            target_address: address,
+            code_address: address,
+            scheme: None,
+        });
+    }
+}
+
+impl<DB: Database> Inspector<DB> for CallStackInspector {
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.frames.push(FrameAddresses {
+            caller: inputs.caller,
+            target_address: inputs.target_address,
+            code_address: inputs.bytecode_address,
+            scheme: Some(inputs.scheme),
+        });
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.frames.pop();
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.push_create(context, inputs.caller, |nonce| {
+            inputs.created_address(nonce)
+        });
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.frames.pop();
+        outcome
+    }
+
+    fn eofcreate(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut EOFCreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.push_create(context, inputs.caller, |nonce| match &inputs.kind {
+            EOFCreateKind::Opcode {
+                created_address, ..
+            } => *created_address,
+            EOFCreateKind::Tx { .. } => inputs.caller.create(nonce),
+        });
+        None
+    }
+
+    fn eofcreate_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &EOFCreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.frames.pop();
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{CacheDB, EmptyDB},
+        inspector_handle_register,
+        interpreter::{opcode, Interpreter},
+        primitives::{address, AccountInfo, Bytecode, Bytes, TxKind},
+        Evm,
+    };
+
+    /// Records the active frames and the contract addresses at the first `SLOAD`.
+    #[derive(Default)]
+    struct SloadAttribution {
+        call_stack: CallStackInspector,
+        sload: Option<(Vec<FrameAddresses>, Address, Address)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for SloadAttribution {
+        fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+            if interp.current_opcode() == opcode::SLOAD && self.sload.is_none() {
+                self.sload = Some((
+                    self.call_stack.frames().to_vec(),
+                    interp.contract.target_address(),
+                    interp.contract.code_address(),
+                ));
+            }
+        }
+
+        fn call(
+            &mut self,
+            context: &mut EvmContext<DB>,
+            inputs: &mut CallInputs,
+        ) -> Option<CallOutcome> {
+            self.call_stack.call(context, inputs)
+        }
+
+        fn call_end(
+            &mut self,
+            context: &mut EvmContext<DB>,
+            inputs: &CallInputs,
+            outcome: CallOutcome,
+        ) -> CallOutcome {
+            self.call_stack.call_end(context, inputs, outcome)
+        }
+    }
+
+    #[test]
+    fn delegatecall_frames() {
+        let caller = address!("1000000000000000000000000000000000000000");
+        let proxy = address!("00000000000000000000000000000000000000aa");
+        let implementation = address!("00000000000000000000000000000000000000bb");
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        // DELEGATECALL(gas, implementation, 0, 0, 0, 0) STOP
+        let mut code = vec![0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x73];
+        code.extend_from_slice(implementation.as_slice());
+        code.extend_from_slice(&[0x5a, 0xf4, 0x00]);
+        db.insert_account_info(
+            proxy,
+            AccountInfo::from_bytecode(Bytecode::new_raw(code.into())),
+        );
+        // SLOAD(0) STOP
+        db.insert_account_info(
+            implementation,
+            AccountInfo::from_bytecode(Bytecode::new_raw(Bytes::from_static(&[
+                0x60, 0x00, 0x54, 0x00,
+            ]))),
+        );
+
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .with_external_context(SloadAttribution::default())
+            .modify_tx_env(|tx| {
+                tx.caller = caller;
+                tx.transact_to = TxKind::Call(proxy);
+                tx.gas_limit = 100_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        evm.transact().unwrap();
+
+        let (frames, target, code_address) = evm.context.external.sload.clone().unwrap();
+        assert_eq!((target, code_address), (proxy, implementation));
+        assert_eq!(
+            frames,
+            [
+                FrameAddresses {
+                    caller,
+                    target_address: proxy,
+                    code_address: proxy,
+                    scheme: Some(CallScheme::Call),
+                },
+                FrameAddresses {
+                    caller,
+                    target_address: proxy,
+                    code_address: implementation,
+                    scheme: Some(CallScheme::DelegateCall),
+                },
+            ]
+        );
+        assert!(frames[1].is_delegated());
+        assert!(evm.context.external.call_stack.frames().is_empty());
+    }
+}
@@ -7,10 +7,14 @@ use crate::{
     EvmContext, Inspector,
 };
 use revm_interpreter::OpCode;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::io::Write;
 
 /// [EIP-3155](https://eips.ethereum.org/EIPS/eip-3155) tracer [Inspector].
+///
+/// Writes one JSON [`Eip3155Step`] line per operation to the writer, followed by an
+/// [`Eip3155Summary`] line per transaction, in the format consumed by `evmlab`, `goevmlab` and
+/// `t8n` tooling.
 pub struct TracerEip3155 {
     output: Box<dyn Write>,
     gas_inspector: GasInspector,
@@ -19,6 +23,7 @@ pub struct TracerEip3155 {
     print_summary: bool,
 
     stack: Vec<U256>,
+    return_data: String,
     pc: usize,
     opcode: u8,
     gas: u64,
@@ -27,72 +32,80 @@ pub struct TracerEip3155 {
     skip: bool,
     include_memory: bool,
     memory: Option<String>,
+    /// Source location of the current step.
+    source: Option<String>,
+    #[cfg(feature = "sourcemap")]
+    source_maps: crate::inspector::sourcemap::SourceMaps,
 }
 
-// # Output
-// The CUT MUST output a `json` object for EACH operation.
-#[derive(Serialize)]
+/// A line of the trace, output for each operation.
+///
+/// Can be deserialized to read back traces, e.g. to compare them with the ones of other clients.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct Output {
+pub struct Eip3155Step {
     // Required fields:
     /// Program counter
-    pc: u64,
+    pub pc: u64,
     /// OpCode
-    op: u8,
+    pub op: u8,
     /// Gas left before executing this operation
-    gas: String,
+    pub gas: String,
     /// Gas cost of this operation
-    gas_cost: String,
+    pub gas_cost: String,
     /// Array of all values on the stack
-    stack: Vec<String>,
+    pub stack: Vec<String>,
     /// Depth of the call stack
-    depth: u64,
-    /// Data returned by the function call
-    return_data: String,
+    pub depth: u64,
+    /// Data returned by the last call of the frame
+    pub return_data: String,
     /// Amount of **global** gas refunded
-    refund: String,
+    pub refund: String,
     /// Size of memory array
-    mem_size: String,
+    pub mem_size: u64,
 
     // Optional fields:
     /// Name of the operation
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    op_name: Option<&'static str>,
+    pub op_name: Option<String>,
     /// Description of an error (should contain revert reason if supported)
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    error: Option<String>,
+    pub error: Option<String>,
     /// Array of all allocated values
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    memory: Option<String>,
+    pub memory: Option<String>,
     /// Array of all stored values
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    storage: Option<HashMap<String, String>>,
+    pub storage: Option<HashMap<String, String>>,
     /// Array of values, Stack of the called function
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    return_stack: Option<Vec<String>>,
+    pub return_stack: Option<Vec<String>>,
+    /// Source location of the operation, as `file:line:column`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
 }
 
-// # Summary and error handling
-#[derive(Serialize)]
+/// The last line of the trace of a transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct Summary {
+pub struct Eip3155Summary {
     // Required fields:
     /// Root of the state trie after executing the transaction
-    state_root: String,
+    pub state_root: String,
     /// Return values of the function
-    output: String,
+    pub output: String,
     /// All gas used by the transaction
-    gas_used: String,
+    pub gas_used: String,
     /// Bool whether transaction was executed successfully
-    pass: bool,
+    pub pass: bool,
 
     // Optional fields:
     /// Time in nanoseconds needed to execute the transaction
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    time: Option<u128>,
+    pub time: Option<u128>,
     /// Name of the fork rules used for execution
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    fork: Option<String>,
+    pub fork: Option<String>,
 }
 
 impl TracerEip3155 {
@@ -107,6 +120,7 @@ impl TracerEip3155 {
         let Self {
             gas_inspector,
             stack,
+            return_data,
             pc,
             opcode,
             gas,
@@ -117,6 +131,7 @@ impl TracerEip3155 {
         } = self;
         *gas_inspector = GasInspector::default();
         stack.clear();
+        return_data.clear();
         *pc = 0;
         *opcode = 0;
         *gas = 0;
@@ -134,7 +149,11 @@ impl TracerEip3155 {
             print_summary: true,
             include_memory: false,
             stack: Default::default(),
+            return_data: Default::default(),
             memory: Default::default(),
+            source: None,
+            #[cfg(feature = "sourcemap")]
+            source_maps: Default::default(),
             pc: 0,
             opcode: 0,
             gas: 0,
@@ -156,6 +175,16 @@ impl TracerEip3155 {
         self
     }
 
+    /// Include the source location of each step of contracts with a registered source map.
+    #[cfg(feature = "sourcemap")]
+    pub fn with_source_maps(
+        mut self,
+        source_maps: crate::inspector::sourcemap::SourceMaps,
+    ) -> Self {
+        self.source_maps = source_maps;
+        self
+    }
+
     fn write_value(&mut self, value: &impl serde::Serialize) -> std::io::Result<()> {
         serde_json::to_writer(&mut *self.output, value)?;
         self.output.write_all(b"\n")?;
@@ -169,7 +198,7 @@ impl TracerEip3155 {
     ) {
         if self.print_summary {
             let spec_name: &str = context.spec_id().into();
-            let value = Summary {
+            let value = Eip3155Summary {
                 state_root: B256::ZERO.to_string(),
                 output: result.output.to_string(),
                 gas_used: hex_number(
@@ -192,6 +221,7 @@ impl<DB: Database> Inspector<DB> for TracerEip3155 {
     fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
         self.gas_inspector.step(interp, context);
         self.stack.clone_from(interp.stack.data());
+        self.return_data = hex::encode_prefixed(&interp.return_data_buffer);
         self.memory = if self.include_memory {
             Some(hex::encode_prefixed(interp.shared_memory.context_memory()))
         } else {
@@ -202,6 +232,14 @@ impl<DB: Database> Inspector<DB> for TracerEip3155 {
         self.mem_size = interp.shared_memory.len();
         self.gas = interp.gas.remaining();
         self.refunded = interp.gas.refunded();
+        #[cfg(feature = "sourcemap")]
+        {
+            self.source = interp.contract.hash.and_then(|hash| {
+                self.source_maps
+                    .location(&hash, self.pc)
+                    .map(|location| location.to_string())
+            });
+        }
     }
 
     fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
@@ -211,18 +249,18 @@ impl<DB: Database> Inspector<DB> for TracerEip3155 {
             return;
         }
 
-        let value = Output {
+        let value = Eip3155Step {
             pc: self.pc as u64,
             op: self.opcode,
             gas: hex_number(self.gas),
             gas_cost: hex_number(self.gas_inspector.last_gas_cost()),
             stack: self.stack.iter().map(hex_number_u256).collect(),
             depth: context.journaled_state.depth(),
-            return_data: "0x".to_string(),
+            return_data: core::mem::take(&mut self.return_data),
             refund: hex_number(self.refunded as u64),
-            mem_size: self.mem_size.to_string(),
+            mem_size: self.mem_size as u64,
 
-            op_name: OpCode::new(self.opcode).map(|i| i.as_str()),
+            op_name: OpCode::new(self.opcode).map(|i| i.as_str().to_string()),
             error: if !interp.instruction_result.is_ok() {
                 Some(format!("{:?}", interp.instruction_result))
             } else {
@@ -231,6 +269,7 @@ impl<DB: Database> Inspector<DB> for TracerEip3155 {
             memory: self.memory.take(),
             storage: None,
             return_stack: None,
+            source: self.source.take(),
         };
         let _ = self.write_value(&value);
     }
@@ -284,3 +323,66 @@ fn hex_number_u256(b: &U256) -> String {
         format!("0x{s}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::BenchmarkDB,
+        inspector_handle_register,
+        primitives::{address, bytes, Bytecode, TxKind},
+        Evm,
+    };
+    use std::{cell::RefCell, io, rc::Rc};
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn steps_and_summary() {
+        // Calls the identity precompile with one byte, then stops.
+        //
+        // PUSH1 0x2a PUSH1 0 MSTORE8
+        // PUSH1 0 PUSH1 0 PUSH1 1 PUSH1 0 PUSH1 4 GAS STATICCALL STOP
+        let code = bytes!("602a600053600060006001600060045afa00");
+        let output = SharedBuffer::default();
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(code)))
+            .with_external_context(TracerEip3155::new(Box::new(output.clone())))
+            .modify_tx_env(|tx| {
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TxKind::Call(address!("0000000000000000000000000000000000000000"));
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        evm.transact().unwrap();
+
+        let output = String::from_utf8(output.0.take()).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 12);
+        let steps = lines[..11]
+            .iter()
+            .map(|line| serde_json::from_str::<Eip3155Step>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(steps[0].op_name.as_deref(), Some("PUSH1"));
+        assert_eq!(steps[0].gas_cost, "0x3");
+        assert_eq!(steps[0].depth, 1);
+        assert_eq!((steps[2].mem_size, steps[3].mem_size), (0, 32));
+        assert_eq!(steps[2].stack, ["0x2a", "0x0"]);
+        // The return data of the call is visible from the next operation.
+        assert_eq!(steps[9].return_data, "0x");
+        assert_eq!(steps[10].return_data, "0x2a");
+        let summary = serde_json::from_str::<Eip3155Summary>(lines[11]).unwrap();
+        assert!(summary.pass);
+    }
+}
@@ -0,0 +1,107 @@
+//! FourByteInspector. Counts the function selectors called in a transaction.
+
+use crate::{
+    interpreter::{CallInputs, CallOutcome},
+    primitives::{db::Database, hex, FixedBytes, HashMap},
+    EvmContext, Inspector,
+};
+use std::string::String;
+
+/// [Inspector] that counts the calls per function selector and calldata size, equivalent to the
+/// `4byteTracer` of geth.
+///
+/// Every call frame with at least 4 bytes of input is counted, including the transaction call
+/// and DELEGATECALL, CALLCODE and STATICCALL frames. Calls to precompiles and contract creations
+/// are skipped.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FourByteInspector {
+    counts: HashMap<(FixedBytes<4>, usize), u64>,
+}
+
+impl FourByteInspector {
+    /// Returns the number of calls per selector and size of the calldata after the selector.
+    pub fn counts(&self) -> &HashMap<(FixedBytes<4>, usize), u64> {
+        &self.counts
+    }
+
+    /// Returns the counts keyed as in the output of geth, e.g. `0x27dc297e-128`.
+    pub fn geth_counts(&self) -> impl Iterator<Item = (String, u64)> + '_ {
+        self.counts.iter().map(|((selector, size), count)| {
+            (format!("{}-{size}", hex::encode_prefixed(selector)), *count)
+        })
+    }
+
+    /// Clears the counts.
+    pub fn clear(&mut self) {
+        self.counts.clear();
+    }
+}
+
+impl<DB: Database> Inspector<DB> for FourByteInspector {
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        if inputs.input.len() >= 4 && !context.precompiles.contains(&inputs.bytecode_address) {
+            let selector = FixedBytes::from_slice(&inputs.input[..4]);
+            *self
+                .counts
+                .entry((selector, inputs.input.len() - 4))
+                .or_default() += 1;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::BenchmarkDB,
+        inspector_handle_register,
+        primitives::{bytes, fixed_bytes, Address, Bytecode, TxKind},
+        Evm,
+    };
+
+    #[test]
+    fn counts_selectors() {
+        // If called with 36 bytes of calldata, calls itself with its first 4 bytes, then calls
+        // the identity precompile with them.
+        //
+        // CALLDATASIZE PUSH1 0x24 EQ PUSH1 0x08 JUMPI STOP
+        // JUMPDEST PUSH1 4 PUSH1 0 PUSH1 0 CALLDATACOPY
+        // PUSH1 0 PUSH1 0 PUSH1 4 PUSH1 0 PUSH1 0 ADDRESS GAS CALL POP
+        // PUSH1 0 PUSH1 0 PUSH1 4 PUSH1 0 PUSH1 0 PUSH1 4 GAS CALL STOP
+        let code = bytes!("36602414600857005b6004600060003760006000600460006000305af1506000600060046000600060045af100");
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(code)))
+            .with_external_context(FourByteInspector::default())
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.data = bytes!(
+                    "a9059cbb0000000000000000000000000000000000000000000000000000000000000001"
+                );
+                tx.gas_limit = 1_000_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        assert!(evm.transact().unwrap().result.is_success());
+
+        let inspector = &evm.context.external;
+        let selector = fixed_bytes!("a9059cbb");
+        assert_eq!(inspector.counts().len(), 2);
+        assert_eq!(inspector.counts()[&(selector, 32)], 1);
+        assert_eq!(inspector.counts()[&(selector, 0)], 1);
+        let mut counts = inspector.geth_counts().collect::<Vec<_>>();
+        counts.sort();
+        assert_eq!(
+            counts,
+            [
+                ("0xa9059cbb-0".to_string(), 1),
+                ("0xa9059cbb-32".to_string(), 1)
+            ]
+        );
+    }
+}
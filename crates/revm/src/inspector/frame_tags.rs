@@ -0,0 +1,151 @@
+use core::{any::Any, fmt};
+use std::{boxed::Box, vec::Vec};
+
+/// Typed data attached by inspectors to the active call and create frames.
+///
+/// The inspector handle register enters a new frame right before calling the `call`, `create`
+/// and `eofcreate` hooks and exits it after the matching `*_end` hook. A tag inserted in the
+/// `call` hook can therefore be read in `step` and nested hooks of the frame and is still
+/// available in `call_end`, without keeping a shadow stack keyed by depth.
+#[derive(Default)]
+pub struct FrameTags {
+    frames: Vec<Option<Box<dyn Any + Send>>>,
+}
+
+impl fmt::Debug for FrameTags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrameTags")
+            .field("depth", &self.frames.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl FrameTags {
+    /// Returns the number of active frames.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Attaches the tag to the current frame, replacing its previous tag.
+    ///
+    /// Does nothing outside of a frame.
+    pub fn insert<T: Any + Send>(&mut self, tag: T) {
+        if let Some(frame) = self.frames.last_mut() {
+            *frame = Some(Box::new(tag));
+        }
+    }
+
+    /// Returns the tag of the current frame if it has type `T`.
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.frames.last()?.as_ref()?.downcast_ref()
+    }
+
+    /// Returns the tag of the current frame if it has type `T`.
+    pub fn get_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.frames.last_mut()?.as_mut()?.downcast_mut()
+    }
+
+    /// Removes the tag of the current frame if it has type `T`.
+    pub fn take<T: Any>(&mut self) -> Option<T> {
+        let frame = self.frames.last_mut()?;
+        if !frame.as_ref()?.is::<T>() {
+            return None;
+        }
+        frame.take()?.downcast().ok().map(|tag| *tag)
+    }
+
+    /// Returns the tag of the frame that created the current one if it has type `T`.
+    ///
+    /// In the `call` and `create` hooks this is the tag of the calling frame.
+    pub fn parent<T: Any>(&self) -> Option<&T> {
+        let index = self.frames.len().checked_sub(2)?;
+        self.frames[index].as_ref()?.downcast_ref()
+    }
+
+    /// Returns the tag of type `T` of the innermost frame that has one.
+    pub fn find<T: Any>(&self) -> Option<&T> {
+        self.frames
+            .iter()
+            .rev()
+            .find_map(|tag| tag.as_ref()?.downcast_ref())
+    }
+
+    /// Enters a new frame without a tag at the given journal depth.
+    ///
+    /// Frames left over by a transaction that ended with an error are dropped.
+    #[inline]
+    pub(crate) fn enter(&mut self, depth: usize) {
+        self.frames.truncate(depth);
+        self.frames.push(None);
+    }
+
+    /// Exits the current frame, dropping its tag.
+    #[inline]
+    pub(crate) fn exit(&mut self) {
+        self.frames.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        db::BenchmarkDB,
+        inspector_handle_register,
+        interpreter::{CallInputs, CallOutcome, Interpreter},
+        primitives::{address, Bytecode, Bytes, TxKind},
+        Database, Evm, EvmContext, Inspector,
+    };
+
+    /// Counts the steps of each frame in a tag.
+    #[derive(Default)]
+    struct StepCounter {
+        counts: Vec<usize>,
+    }
+
+    impl<DB: Database> Inspector<DB> for StepCounter {
+        fn step(&mut self, _interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+            *context.frame_tags.get_mut::<usize>().unwrap() += 1;
+        }
+
+        fn call(
+            &mut self,
+            context: &mut EvmContext<DB>,
+            _inputs: &mut CallInputs,
+        ) -> Option<CallOutcome> {
+            assert!(context.frame_tags.parent::<usize>().is_none());
+            context.frame_tags.insert(0usize);
+            None
+        }
+
+        fn call_end(
+            &mut self,
+            context: &mut EvmContext<DB>,
+            _inputs: &CallInputs,
+            outcome: CallOutcome,
+        ) -> CallOutcome {
+            self.counts
+                .push(context.frame_tags.take::<usize>().unwrap());
+            outcome
+        }
+    }
+
+    #[test]
+    fn frame_tags() {
+        // PUSH1 0 POP STOP
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00, 0x50, 0x00]));
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(code))
+            .with_external_context(StepCounter::default())
+            .modify_tx_env(|tx| {
+                tx.caller = address!("0000000000000000000000000000000000000001");
+                tx.transact_to = TxKind::Call(address!("0000000000000000000000000000000000000000"));
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        evm.transact().unwrap();
+
+        assert_eq!(evm.context.external.counts, [3]);
+        assert_eq!(evm.context.evm.frame_tags.depth(), 0);
+    }
+}
@@ -1,13 +1,26 @@
 use crate::{
     db::Database,
     handler::register::EvmHandler,
-    interpreter::{opcode, InstructionResult, Interpreter},
-    primitives::EVMError,
+    inspector::RefundReason,
+    interpreter::{
+        opcode, CallInputs, CreateInputs, EOFCreateInputs, InstructionResult, Interpreter,
+    },
+    primitives::{EVMError, SpecId},
     Context, FrameOrResult, FrameResult, Inspector, JournalEntry,
 };
-use core::cell::RefCell;
 use revm_interpreter::opcode::DynInstruction;
-use std::{rc::Rc, sync::Arc, vec::Vec};
+use std::{boxed::Box, sync::Arc, vec::Vec};
+
+/// Inputs of the active frames, kept by the inspector handle register for the `*_end` calls.
+///
+/// Stored in the [`EvmContext`](crate::EvmContext) rather than shared between the handles, so
+/// that the handles stay `Send` and `Sync`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct InspectorInputs {
+    call: Vec<Box<CallInputs>>,
+    create: Vec<Box<CreateInputs>>,
+    eofcreate: Vec<Box<EOFCreateInputs>>,
+}
 
 /// Provides access to an `Inspector` instance.
 pub trait GetInspector<DB: Database> {
@@ -32,8 +45,8 @@ impl<DB: Database, INSP: Inspector<DB>> GetInspector<DB> for INSP {
 /// to use this register with any other register.
 ///
 /// A few instructions handlers are wrapped twice once for `step` and `step_end`
-/// and in case of Logs and Selfdestruct wrapper is wrapped again for the
-/// `log` and `selfdestruct` calls.
+/// and in case of Logs, Tstore and Selfdestruct wrapper is wrapped again for the
+/// `log`, `tstore` and `selfdestruct` calls.
 pub fn inspector_handle_register<DB: Database, EXT: GetInspector<DB>>(
     handler: &mut EvmHandler<'_, EXT, DB>,
 ) {
@@ -62,10 +75,52 @@ pub fn inspector_handle_register<DB: Database, EXT: GetInspector<DB>>(
         });
     }
 
+    // Register inspector TSTORE instruction.
+    table.update_boxed(opcode::TSTORE, |prev, interpreter, host| {
+        let address = interpreter.contract.target_address;
+        let key = interpreter.stack.peek(0);
+        let new_value = interpreter.stack.peek(1);
+        let had_value = key.map(|key| host.evm.journaled_state.tload(address, key));
+        prev(interpreter, host);
+        if interpreter.instruction_result != InstructionResult::Continue {
+            return;
+        }
+        if let (Ok(key), Ok(new_value), Ok(had_value)) = (key, new_value, had_value) {
+            host.external.get_inspector().tstore(
+                interpreter,
+                &mut host.evm,
+                address,
+                key,
+                had_value,
+                new_value,
+            );
+        }
+    });
+
+    // Register inspector SSTORE instruction to report refunds, including those of frames that
+    // revert later.
+    table.update_boxed(opcode::SSTORE, |prev, interpreter, host| {
+        let refunded = interpreter.gas.refunded();
+        prev(interpreter, host);
+        let amount = interpreter.gas.refunded() - refunded;
+        if amount != 0 {
+            host.external
+                .get_inspector()
+                .refund_recorded(amount, RefundReason::Sstore);
+        }
+    });
+
     // Register selfdestruct function.
     table.update_boxed(opcode::SELFDESTRUCT, |prev, interpreter, host| {
+        let refunded = interpreter.gas.refunded();
         // execute selfdestruct
         prev(interpreter, host);
+        let amount = interpreter.gas.refunded() - refunded;
+        if amount != 0 {
+            host.external
+                .get_inspector()
+                .refund_recorded(amount, RefundReason::Selfdestruct);
+        }
         // check if selfdestruct was successful and if journal entry is made.
         if let Some(JournalEntry::AccountDestroyed {
             address,
@@ -80,24 +135,22 @@ pub fn inspector_handle_register<DB: Database, EXT: GetInspector<DB>>(
         }
     });
 
-    // call and create input stack shared between handlers. They are used to share
-    // inputs in *_end Inspector calls.
-    let call_input_stack = Rc::<RefCell<Vec<_>>>::default();
-    let create_input_stack = Rc::<RefCell<Vec<_>>>::default();
-    let eofcreate_input_stack = Rc::<RefCell<Vec<_>>>::default();
+    // Call and create inputs are pushed to the stacks of `ctx.evm.inspector_inputs` and popped
+    // in the *_end Inspector calls.
 
     // Create handler
-    let create_input_stack_inner = create_input_stack.clone();
     let prev_handle = handler.execution.create.clone();
     handler.execution.create = Arc::new(
         move |ctx, mut inputs| -> Result<FrameOrResult, EVMError<DB::Error>> {
+            let depth = ctx.evm.journaled_state.depth() as usize;
+            ctx.evm.frame_tags.enter(depth);
             let inspector = ctx.external.get_inspector();
             // call inspector create to change input or return outcome.
             if let Some(outcome) = inspector.create(&mut ctx.evm, &mut inputs) {
-                create_input_stack_inner.borrow_mut().push(inputs.clone());
+                ctx.evm.inspector_inputs.create.push(inputs.clone());
                 return Ok(FrameOrResult::Result(FrameResult::Create(outcome)));
             }
-            create_input_stack_inner.borrow_mut().push(inputs.clone());
+            ctx.evm.inspector_inputs.create.push(inputs.clone());
 
             let mut frame_or_result = prev_handle(ctx, inputs);
             if let Ok(FrameOrResult::Frame(frame)) = &mut frame_or_result {
@@ -110,12 +163,13 @@ pub fn inspector_handle_register<DB: Database, EXT: GetInspector<DB>>(
     );
 
     // Call handler
-    let call_input_stack_inner = call_input_stack.clone();
     let prev_handle = handler.execution.call.clone();
     handler.execution.call = Arc::new(move |ctx, mut inputs| {
         // Call inspector to change input or return outcome.
+        let depth = ctx.evm.journaled_state.depth() as usize;
+        ctx.evm.frame_tags.enter(depth);
         let outcome = ctx.external.get_inspector().call(&mut ctx.evm, &mut inputs);
-        call_input_stack_inner.borrow_mut().push(inputs.clone());
+        ctx.evm.inspector_inputs.call.push(inputs.clone());
         if let Some(outcome) = outcome {
             return Ok(FrameOrResult::Result(FrameResult::Call(outcome)));
         }
@@ -132,17 +186,16 @@ pub fn inspector_handle_register<DB: Database, EXT: GetInspector<DB>>(
     // Calls inspector `eofcreate` and `initialize_interp` functions. Queues the inputs for the `eofcreate_end`` function.
     // Calls the old handler, and in case of inspector returning outcome,
     // returns the outcome without executing eofcreate.
-    let eofcreate_input_stack_inner = eofcreate_input_stack.clone();
     let prev_handle = handler.execution.eofcreate.clone();
     handler.execution.eofcreate = Arc::new(move |ctx, mut inputs| {
         // Call inspector to change input or return outcome.
+        let depth = ctx.evm.journaled_state.depth() as usize;
+        ctx.evm.frame_tags.enter(depth);
         let outcome = ctx
             .external
             .get_inspector()
             .eofcreate(&mut ctx.evm, &mut inputs);
-        eofcreate_input_stack_inner
-            .borrow_mut()
-            .push(inputs.clone());
+        ctx.evm.inspector_inputs.eofcreate.push(inputs.clone());
         if let Some(outcome) = outcome {
             return Ok(FrameOrResult::Result(FrameResult::EOFCreate(outcome)));
         }
@@ -158,62 +211,79 @@ pub fn inspector_handle_register<DB: Database, EXT: GetInspector<DB>>(
 
     // Pops eofcreate input from the stack and calls inspector `eofcreate_end` function.
     // preserve the old handler and calls it with the outcome.
-    let eofcreate_input_stack_inner = eofcreate_input_stack.clone();
     let prev_handle = handler.execution.insert_eofcreate_outcome.clone();
     handler.execution.insert_eofcreate_outcome = Arc::new(move |ctx, frame, mut outcome| {
-        let create_inputs = eofcreate_input_stack_inner.borrow_mut().pop().unwrap();
+        let create_inputs = ctx.evm.inspector_inputs.eofcreate.pop().unwrap();
         outcome = ctx
             .external
             .get_inspector()
             .eofcreate_end(&mut ctx.evm, &create_inputs, outcome);
+        ctx.evm.frame_tags.exit();
         prev_handle(ctx, frame, outcome)
     });
 
     // call outcome
-    let call_input_stack_inner = call_input_stack.clone();
     let prev_handle = handler.execution.insert_call_outcome.clone();
     handler.execution.insert_call_outcome =
         Arc::new(move |ctx, frame, shared_memory, mut outcome| {
-            let call_inputs = call_input_stack_inner.borrow_mut().pop().unwrap();
+            let call_inputs = ctx.evm.inspector_inputs.call.pop().unwrap();
             outcome = ctx
                 .external
                 .get_inspector()
                 .call_end(&mut ctx.evm, &call_inputs, outcome);
+            ctx.evm.frame_tags.exit();
             prev_handle(ctx, frame, shared_memory, outcome)
         });
 
     // create outcome
-    let create_input_stack_inner = create_input_stack.clone();
     let prev_handle = handler.execution.insert_create_outcome.clone();
     handler.execution.insert_create_outcome = Arc::new(move |ctx, frame, mut outcome| {
-        let create_inputs = create_input_stack_inner.borrow_mut().pop().unwrap();
+        let create_inputs = ctx.evm.inspector_inputs.create.pop().unwrap();
         outcome = ctx
             .external
             .get_inspector()
             .create_end(&mut ctx.evm, &create_inputs, outcome);
+        ctx.evm.frame_tags.exit();
         prev_handle(ctx, frame, outcome)
     });
 
     // last frame outcome
+    let is_london = handler.cfg.spec_id.is_enabled_in(SpecId::LONDON);
     let prev_handle = handler.execution.last_frame_return.clone();
     handler.execution.last_frame_return = Arc::new(move |ctx, frame_result| {
         let inspector = ctx.external.get_inspector();
         match frame_result {
             FrameResult::Call(outcome) => {
-                let call_inputs = call_input_stack.borrow_mut().pop().unwrap();
+                let call_inputs = ctx.evm.inspector_inputs.call.pop().unwrap();
                 *outcome = inspector.call_end(&mut ctx.evm, &call_inputs, outcome.clone());
             }
             FrameResult::Create(outcome) => {
-                let create_inputs = create_input_stack.borrow_mut().pop().unwrap();
+                let create_inputs = ctx.evm.inspector_inputs.create.pop().unwrap();
                 *outcome = inspector.create_end(&mut ctx.evm, &create_inputs, outcome.clone());
             }
             FrameResult::EOFCreate(outcome) => {
-                let eofcreate_inputs = eofcreate_input_stack.borrow_mut().pop().unwrap();
+                let eofcreate_inputs = ctx.evm.inspector_inputs.eofcreate.pop().unwrap();
                 *outcome =
                     inspector.eofcreate_end(&mut ctx.evm, &eofcreate_inputs, outcome.clone());
             }
         }
-        prev_handle(ctx, frame_result)
+        ctx.evm.frame_tags.exit();
+        // Refund recorded by the frame, before the previous handler caps it.
+        let recorded = frame_result.gas().refunded();
+        prev_handle(ctx, frame_result)?;
+
+        // The previous handler sets the capped refund, which is zero if the transaction did not
+        // succeed or refunds are disabled.
+        let gas = frame_result.gas();
+        let set = if gas.refunded() > 0 {
+            recorded as u64
+        } else {
+            0
+        };
+        ctx.external
+            .get_inspector()
+            .final_refund(set, gas.max_refund(is_london));
+        Ok(())
     });
 }
 
@@ -254,7 +324,8 @@ mod tests {
     use super::*;
     use crate::{
         inspectors::NoOpInspector,
-        interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome},
+        interpreter::{CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome},
+        primitives::{Address, U256},
         Evm, EvmContext,
     };
 
@@ -380,6 +451,185 @@ mod tests {
         assert!(inspector.call_end);
     }
 
+    #[derive(Default, Debug)]
+    struct StaticInspector {
+        calls: Vec<(CallScheme, bool, Option<usize>)>,
+        call_ends: Vec<(bool, InstructionResult)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for StaticInspector {
+        fn call(
+            &mut self,
+            context: &mut EvmContext<DB>,
+            inputs: &mut CallInputs,
+        ) -> Option<CallOutcome> {
+            self.calls
+                .push((inputs.scheme, inputs.is_static, context.static_depth()));
+            None
+        }
+
+        fn call_end(
+            &mut self,
+            _context: &mut EvmContext<DB>,
+            _inputs: &CallInputs,
+            outcome: CallOutcome,
+        ) -> CallOutcome {
+            self.call_ends
+                .push((outcome.is_static, *outcome.instruction_result()));
+            outcome
+        }
+    }
+
+    #[test]
+    fn test_static_context_through_delegate_call() {
+        use crate::{
+            db::InMemoryDB,
+            primitives::{bytes, AccountInfo, Address, Bytecode, TxKind},
+        };
+
+        let mut db = InMemoryDB::default();
+        let mut insert = |address: u8, code| {
+            let info = AccountInfo::from_bytecode(Bytecode::new_raw(code));
+            db.insert_account_info(Address::with_last_byte(address), info);
+        };
+        // STATICCALL 0x0b
+        insert(0x0a, bytes!("6000600060006000600b5afa5000"));
+        // DELEGATECALL 0x0c
+        insert(0x0b, bytes!("6000600060006000600c5af45000"));
+        // CALL the identity precompile, then SSTORE
+        insert(0x0c, bytes!("6000600060006000600060045af150600160005500"));
+
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .with_external_context(StaticInspector::default())
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TxKind::Call(Address::with_last_byte(0x0a));
+                tx.gas_limit = 1_000_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        assert!(evm.transact().unwrap().result.is_success());
+        assert_eq!(evm.context.evm.static_depth(), None);
+
+        let inspector = evm.into_context().external;
+        assert_eq!(
+            inspector.calls,
+            [
+                (CallScheme::Call, false, None),
+                (CallScheme::StaticCall, true, None),
+                (CallScheme::DelegateCall, true, Some(2)),
+                (CallScheme::Call, true, Some(2)),
+            ]
+        );
+        assert_eq!(
+            inspector.call_ends,
+            [
+                (true, InstructionResult::Return),
+                (true, InstructionResult::StateChangeDuringStaticCall),
+                (true, InstructionResult::Stop),
+                (false, InstructionResult::Stop),
+            ]
+        );
+    }
+
+    #[derive(Default, Debug)]
+    struct RefundInspector {
+        recorded: Vec<(i64, RefundReason)>,
+        final_refund: Option<(u64, u64)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for RefundInspector {
+        fn refund_recorded(&mut self, amount: i64, reason: RefundReason) {
+            self.recorded.push((amount, reason));
+        }
+
+        fn final_refund(&mut self, set: u64, cap: u64) {
+            self.final_refund = Some((set, cap));
+        }
+    }
+
+    #[test]
+    fn test_refund_hooks() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{bytes, Address, Bytecode, TxKind},
+        };
+
+        // PUSH1 1 PUSH1 0 SSTORE PUSH1 0 PUSH1 0 SSTORE STOP
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(bytes!(
+                "6001600055600060005500"
+            ))))
+            .with_external_context(RefundInspector::default())
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+
+        let inspector = evm.into_context().external;
+        // Restoring the original value refunds the cost of setting the slot.
+        assert_eq!(inspector.recorded, [(19_900, RefundReason::Sstore)]);
+        // 21000 intrinsic + 3 * 4 PUSH1 + 22100 cold SSTORE set + 100 warm SSTORE.
+        let spent = 21_000 + 12 + 22_100 + 100;
+        assert_eq!(inspector.final_refund, Some((19_900, spent / 5)));
+        assert_eq!(result.gas_used(), spent - spent / 5);
+    }
+
+    #[derive(Default, Debug)]
+    struct TstoreInspector {
+        changes: Vec<(U256, U256, U256)>,
+    }
+
+    impl<DB: Database> Inspector<DB> for TstoreInspector {
+        fn tstore(
+            &mut self,
+            _interp: &mut Interpreter,
+            _context: &mut EvmContext<DB>,
+            _address: Address,
+            key: U256,
+            had_value: U256,
+            new_value: U256,
+        ) {
+            self.changes.push((key, had_value, new_value));
+        }
+    }
+
+    #[test]
+    fn test_tstore_hook() {
+        use crate::{
+            db::BenchmarkDB,
+            primitives::{bytes, Bytecode, TxKind},
+        };
+
+        // TSTORE(0, 1) TSTORE(0, 1) TSTORE(0, 2) STOP
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(bytes!(
+                "600160005d600160005d600260005d00"
+            ))))
+            .with_external_context(TstoreInspector::default())
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+            })
+            .with_spec_id(SpecId::CANCUN)
+            .append_handler_register(inspector_handle_register)
+            .build();
+        assert!(evm.transact().unwrap().result.is_success());
+
+        let (zero, one, two) = (U256::ZERO, U256::from(1), U256::from(2));
+        assert_eq!(
+            evm.into_context().external.changes,
+            [(zero, zero, one), (zero, one, one), (zero, one, two)]
+        );
+    }
+
     #[test]
     fn test_inspector_reg() {
         let mut noop = NoOpInspector;
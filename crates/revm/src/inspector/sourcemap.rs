@@ -0,0 +1,270 @@
+//! Mapping of program counters to source code locations using solc source maps.
+//!
+//! Source maps are read from the `evm.deployedBytecode.sourceMap` output of the compiler, see
+//! <https://docs.soliditylang.org/en/latest/internals/source_mappings.html>. The file indices of a
+//! source map refer to the `id` of each source in the compiler output.
+
+use crate::{
+    interpreter::opcode,
+    primitives::{keccak256, HashMap, B256},
+};
+use core::fmt;
+use std::{string::String, vec::Vec};
+
+/// Whether an instruction jumps into or out of a function.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum JumpType {
+    /// `i`, jump into a function.
+    In,
+    /// `o`, return from a function.
+    Out,
+    /// `-`, regular jump or no jump.
+    #[default]
+    Regular,
+}
+
+/// Source range of a single instruction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SourceElement {
+    /// Byte offset of the range in the source file.
+    pub offset: usize,
+    /// Length of the range in bytes.
+    pub length: usize,
+    /// Source file index, `None` for compiler generated code.
+    pub file: Option<usize>,
+    /// Jump type of the instruction.
+    pub jump: JumpType,
+    /// Depth of the modifier the instruction belongs to.
+    pub modifier_depth: usize,
+}
+
+/// Decoded source map of a contract, one element per instruction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    elements: Vec<SourceElement>,
+}
+
+impl SourceMap {
+    /// Decodes a compressed solc source map such as `1:2:1;:9;2:1:2;;`.
+    ///
+    /// Empty fields inherit the value of the previous element. Returns `None` if the source map
+    /// is malformed.
+    pub fn parse(source_map: &str) -> Option<Self> {
+        let mut elements = Vec::new();
+        if source_map.is_empty() {
+            return Some(Self { elements });
+        }
+        let mut prev = SourceElement::default();
+        for item in source_map.split(';') {
+            let mut fields = item.split(':');
+            let mut next = || fields.next().filter(|field| !field.is_empty());
+            if let Some(offset) = next() {
+                prev.offset = offset.parse().ok()?;
+            }
+            if let Some(length) = next() {
+                prev.length = length.parse().ok()?;
+            }
+            if let Some(file) = next() {
+                let file: i64 = file.parse().ok()?;
+                prev.file = usize::try_from(file).ok();
+            }
+            if let Some(jump) = next() {
+                prev.jump = match jump {
+                    "i" => JumpType::In,
+                    "o" => JumpType::Out,
+                    "-" => JumpType::Regular,
+                    _ => return None,
+                };
+            }
+            if let Some(depth) = next() {
+                prev.modifier_depth = depth.parse().ok()?;
+            }
+            elements.push(prev);
+        }
+        Some(Self { elements })
+    }
+
+    /// Returns the element of the instruction at the given index.
+    #[inline]
+    pub fn get(&self, instruction: usize) -> Option<&SourceElement> {
+        self.elements.get(instruction)
+    }
+
+    /// Returns the number of elements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Returns `true` if the source map is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+}
+
+/// A source file, used to convert byte offsets into lines.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceFile {
+    /// Name of the file, e.g. its path.
+    pub name: String,
+    /// Byte offsets at which each line starts.
+    line_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    /// Creates a new source file from its content.
+    pub fn new(name: impl Into<String>, content: &str) -> Self {
+        let line_starts = core::iter::once(0)
+            .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self {
+            name: name.into(),
+            line_starts,
+        }
+    }
+
+    /// Returns the 1-based line and column of the byte offset.
+    pub fn line_column(&self, offset: usize) -> (usize, usize) {
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        (line, offset - self.line_starts[line - 1] + 1)
+    }
+}
+
+/// Location of an instruction in a source file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SourceLocation<'a> {
+    /// Name of the source file.
+    pub file: &'a str,
+    /// 1-based line.
+    pub line: usize,
+    /// 1-based column.
+    pub column: usize,
+}
+
+impl fmt::Display for SourceLocation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// Source map of a deployed contract together with the start of each instruction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct ContractSourceMap {
+    source_map: SourceMap,
+    /// Program counter of each instruction, in ascending order.
+    instructions: Vec<usize>,
+}
+
+impl ContractSourceMap {
+    fn new(bytecode: &[u8], source_map: SourceMap) -> Self {
+        let mut instructions = Vec::with_capacity(source_map.len());
+        let mut pc = 0;
+        while pc < bytecode.len() {
+            instructions.push(pc);
+            let push_size = bytecode[pc].wrapping_sub(opcode::PUSH1);
+            pc += if push_size < 32 {
+                push_size as usize + 2
+            } else {
+                1
+            };
+        }
+        Self {
+            source_map,
+            instructions,
+        }
+    }
+
+    fn element(&self, pc: usize) -> Option<&SourceElement> {
+        let instruction = self.instructions.binary_search(&pc).ok()?;
+        self.source_map.get(instruction)
+    }
+}
+
+/// Source maps of deployed contracts, keyed by code hash, and the source files they refer to.
+#[derive(Clone, Debug, Default)]
+pub struct SourceMaps {
+    files: HashMap<usize, SourceFile>,
+    contracts: HashMap<B256, ContractSourceMap>,
+}
+
+impl SourceMaps {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a source file under its compiler source `id`.
+    pub fn insert_file(&mut self, id: usize, file: SourceFile) {
+        self.files.insert(id, file);
+    }
+
+    /// Registers the source map of the deployed bytecode and returns its code hash.
+    ///
+    /// Returns `None` if the source map is malformed.
+    pub fn insert_contract(&mut self, bytecode: &[u8], source_map: &str) -> Option<B256> {
+        let source_map = SourceMap::parse(source_map)?;
+        let hash = keccak256(bytecode);
+        self.contracts
+            .insert(hash, ContractSourceMap::new(bytecode, source_map));
+        Some(hash)
+    }
+
+    /// Returns the source element of the instruction at `pc` in the code with the given hash.
+    pub fn element(&self, code_hash: &B256, pc: usize) -> Option<&SourceElement> {
+        self.contracts.get(code_hash)?.element(pc)
+    }
+
+    /// Returns the source location of the instruction at `pc` in the code with the given hash.
+    ///
+    /// Returns `None` for unknown code, compiler generated instructions and unknown files.
+    pub fn location(&self, code_hash: &B256, pc: usize) -> Option<SourceLocation<'_>> {
+        let element = self.element(code_hash, pc)?;
+        let file = self.files.get(&element.file?)?;
+        let (line, column) = file.line_column(element.offset);
+        Some(SourceLocation {
+            file: &file.name,
+            line,
+            column,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_source_map() {
+        let map = SourceMap::parse("1:2:1;:9;2:1:2:i;;3:4:-1:o:1").unwrap();
+        assert_eq!(map.len(), 5);
+        let fields = |i| {
+            let e: &SourceElement = map.get(i).unwrap();
+            (e.offset, e.length, e.file, e.jump, e.modifier_depth)
+        };
+        assert_eq!(fields(1), (1, 9, Some(1), JumpType::Regular, 0));
+        assert_eq!(fields(3), (2, 1, Some(2), JumpType::In, 0));
+        assert_eq!(fields(4), (3, 4, None, JumpType::Out, 1));
+        assert!(SourceMap::parse("1:2:1:x").is_none());
+    }
+
+    #[test]
+    fn source_location() {
+        let mut maps = SourceMaps::new();
+        maps.insert_file(0, SourceFile::new("A.sol", "contract A {\n  uint x;\n}\n"));
+        // PUSH1 0x80 PUSH1 0x40 MSTORE STOP
+        let hash = maps
+            .insert_contract(
+                &[0x60, 0x80, 0x60, 0x40, 0x52, 0x00],
+                "0:10:0;11:5;17:3;:0:-1",
+            )
+            .unwrap();
+
+        let location = maps.location(&hash, 4).unwrap();
+        assert_eq!(location.to_string(), "A.sol:2:5");
+        assert_eq!(maps.location(&hash, 2).unwrap().column, 12);
+        // push data and compiler generated code
+        assert!(maps.location(&hash, 1).is_none());
+        assert!(maps.location(&hash, 5).is_none());
+    }
+}
@@ -0,0 +1,451 @@
+use crate::{
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    primitives::{db::Database, Address, U256},
+    EvmContext, Inspector, JournalEntry, JournaledState,
+};
+use std::vec::Vec;
+
+/// Change made by an instruction, recorded by [`TimeTravelRecorder`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StepDelta {
+    /// The stack items above `keep` were replaced.
+    Stack {
+        /// Number of items at the bottom of the stack that did not change.
+        keep: usize,
+        /// Items above `keep` before the change.
+        before: Vec<U256>,
+        /// Items above `keep` after the change.
+        after: Vec<U256>,
+    },
+    /// A range of the memory was written, or the memory was expanded.
+    Memory {
+        /// Start of the changed range.
+        offset: usize,
+        /// Bytes of the range before the change, zeros past the previous length.
+        before: Vec<u8>,
+        /// Bytes of the range after the change.
+        after: Vec<u8>,
+        /// Length of the memory before the change.
+        len_before: usize,
+        /// Length of the memory after the change.
+        len_after: usize,
+    },
+    /// Entries added to the journal, the state changes of the instruction and its sub calls.
+    ///
+    /// Entries of checkpoints spilled to disk by a `JournalSpill` are missing.
+    Journal(Vec<JournalEntry>),
+}
+
+impl StepDelta {
+    fn apply(&self, stack: &mut Vec<U256>, memory: &mut Vec<u8>) {
+        match self {
+            Self::Stack { keep, after, .. } => {
+                stack.truncate(*keep);
+                stack.extend_from_slice(after);
+            }
+            Self::Memory {
+                offset,
+                after,
+                len_after,
+                ..
+            } => {
+                memory.resize(*len_after, 0);
+                memory[*offset..*offset + after.len()].copy_from_slice(after);
+            }
+            Self::Journal(_) => {}
+        }
+    }
+
+    fn undo(&self, stack: &mut Vec<U256>, memory: &mut Vec<u8>) {
+        match self {
+            Self::Stack { keep, before, .. } => {
+                stack.truncate(*keep);
+                stack.extend_from_slice(before);
+            }
+            Self::Memory {
+                offset,
+                before,
+                len_before,
+                ..
+            } => {
+                memory[*offset..*offset + before.len()].copy_from_slice(before);
+                memory.truncate(*len_before);
+            }
+            Self::Journal(_) => {}
+        }
+    }
+}
+
+/// Instruction executed by a frame, with the changes it made.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RecordedStep {
+    /// Program counter of the instruction.
+    pub pc: usize,
+    /// Opcode of the instruction.
+    pub opcode: u8,
+    /// Gas remaining before the instruction.
+    pub gas_remaining: u64,
+    /// Changes made by the instruction, in order.
+    pub deltas: Vec<StepDelta>,
+}
+
+/// Execution of a frame recorded by [`TimeTravelRecorder`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FrameRecord {
+    /// Call depth of the frame, starting at 0 for the transaction frame.
+    pub depth: usize,
+    /// Address of the called or created account, `None` if the creation failed.
+    pub address: Option<Address>,
+    /// Executed instructions.
+    pub steps: Vec<RecordedStep>,
+    /// Stack at the end of the frame.
+    pub stack: Vec<U256>,
+    /// Memory at the end of the frame.
+    pub memory: Vec<u8>,
+}
+
+impl FrameRecord {
+    /// Returns a session positioned at the end of the frame.
+    pub fn session(&self) -> TimeTravelSession<'_> {
+        TimeTravelSession::new(self)
+    }
+}
+
+/// Frame being executed.
+#[derive(Debug)]
+struct ActiveFrame {
+    record: FrameRecord,
+    /// Position in the journal after the last recorded change, see [`journal_position`].
+    journal: Option<(usize, usize)>,
+}
+
+impl ActiveFrame {
+    /// Records the changes since the last call on the last step.
+    fn record_deltas(&mut self, interp: &Interpreter, journaled_state: &JournaledState) {
+        let Some(step) = self.record.steps.last_mut() else {
+            return;
+        };
+        let stack = interp.stack.data();
+        let previous = &mut self.record.stack;
+        let keep = stack
+            .iter()
+            .zip(previous.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        if keep != stack.len() || keep != previous.len() {
+            step.deltas.push(StepDelta::Stack {
+                keep,
+                before: previous.split_off(keep),
+                after: stack[keep..].to_vec(),
+            });
+            previous.extend_from_slice(&stack[keep..]);
+        }
+
+        let memory = interp.shared_memory.context_memory();
+        let previous = &mut self.record.memory;
+        let len_before = previous.len();
+        previous.resize(memory.len().max(len_before), 0);
+        let first = memory.iter().zip(previous.iter()).position(|(a, b)| a != b);
+        if let Some(first) = first {
+            let last = memory
+                .iter()
+                .zip(previous.iter())
+                .rposition(|(a, b)| a != b)
+                .unwrap_or(first);
+            step.deltas.push(StepDelta::Memory {
+                offset: first,
+                before: previous[first..=last].to_vec(),
+                after: memory[first..=last].to_vec(),
+                len_before,
+                len_after: memory.len(),
+            });
+            previous[first..=last].copy_from_slice(&memory[first..=last]);
+        } else if memory.len() != len_before {
+            step.deltas.push(StepDelta::Memory {
+                offset: len_before,
+                before: Vec::new(),
+                after: Vec::new(),
+                len_before,
+                len_after: memory.len(),
+            });
+        }
+
+        let position = journal_position(journaled_state);
+        if let Some((index, len)) = self.journal.replace(position) {
+            let entries = journaled_state
+                .journal
+                .get(index.saturating_sub(1)..)
+                .into_iter()
+                .flatten()
+                .enumerate()
+                .flat_map(|(i, entries)| {
+                    &entries[if i == 0 { len.min(entries.len()) } else { 0 }..]
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            if !entries.is_empty() {
+                step.deltas.push(StepDelta::Journal(entries));
+            }
+        }
+    }
+}
+
+/// Returns the number of checkpoints of the journal and the length of the last one.
+fn journal_position(journaled_state: &JournaledState) -> (usize, usize) {
+    (
+        journaled_state.journal.len(),
+        journaled_state.journal.last().map_or(0, Vec::len),
+    )
+}
+
+/// [Inspector] that records the changes made by every instruction, for stepping backwards
+/// through the execution of a frame with a [`TimeTravelSession`].
+///
+/// Only the changes are recorded: the stack items replaced, the written range of the memory
+/// and the entries added to the journal. A frame is rebuilt from its state at the end by
+/// undoing them, instead of keeping a snapshot per step.
+#[derive(Debug, Default)]
+pub struct TimeTravelRecorder {
+    active: Vec<ActiveFrame>,
+    frames: Vec<FrameRecord>,
+}
+
+impl TimeTravelRecorder {
+    /// Returns the frames that ended, in the order they ended.
+    pub fn frames(&self) -> &[FrameRecord] {
+        &self.frames
+    }
+
+    /// Takes the recorded frames, leaving the recorder empty for the next transaction.
+    pub fn take_frames(&mut self) -> Vec<FrameRecord> {
+        self.active.clear();
+        core::mem::take(&mut self.frames)
+    }
+
+    fn enter(&mut self, depth: usize, address: Option<Address>) {
+        self.active.push(ActiveFrame {
+            record: FrameRecord {
+                depth,
+                address,
+                ..Default::default()
+            },
+            journal: None,
+        });
+    }
+
+    fn exit(&mut self) -> Option<&mut FrameRecord> {
+        let frame = self.active.pop()?;
+        self.frames.push(frame.record);
+        self.frames.last_mut()
+    }
+}
+
+impl<DB: Database> Inspector<DB> for TimeTravelRecorder {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        let Some(frame) = self.active.last_mut() else {
+            return;
+        };
+        // Sub calls of the last step write their output to the memory after its `step_end`.
+        frame.record_deltas(interp, &context.journaled_state);
+        if frame.journal.is_none() {
+            frame.journal = Some(journal_position(&context.journaled_state));
+        }
+        frame.record.steps.push(RecordedStep {
+            pc: interp.program_counter(),
+            opcode: interp.current_opcode(),
+            gas_remaining: interp.gas.remaining(),
+            deltas: Vec::new(),
+        });
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        if let Some(frame) = self.active.last_mut() {
+            frame.record_deltas(interp, &context.journaled_state);
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.enter(context.journaled_state.depth(), Some(inputs.target_address));
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.exit();
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        _inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.enter(context.journaled_state.depth(), None);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        if let Some(frame) = self.exit() {
+            frame.address = outcome.address;
+        }
+        outcome
+    }
+}
+
+/// Steps backwards and forwards through the instructions of a [`FrameRecord`].
+///
+/// The position is the number of executed instructions: the stack and the memory are the ones
+/// before the instruction at the position, or at the end of the frame once all of them are
+/// executed.
+#[derive(Clone, Debug)]
+pub struct TimeTravelSession<'a> {
+    frame: &'a FrameRecord,
+    position: usize,
+    stack: Vec<U256>,
+    memory: Vec<u8>,
+}
+
+impl<'a> TimeTravelSession<'a> {
+    /// Creates a session positioned at the end of the frame.
+    pub fn new(frame: &'a FrameRecord) -> Self {
+        Self {
+            frame,
+            position: frame.steps.len(),
+            stack: frame.stack.clone(),
+            memory: frame.memory.clone(),
+        }
+    }
+
+    /// Returns the number of executed instructions.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the instruction about to be executed, `None` at the end of the frame.
+    pub fn next_step(&self) -> Option<&'a RecordedStep> {
+        self.frame.steps.get(self.position)
+    }
+
+    /// Returns the current stack.
+    pub fn stack(&self) -> &[U256] {
+        &self.stack
+    }
+
+    /// Returns the current memory.
+    pub fn memory(&self) -> &[u8] {
+        &self.memory
+    }
+
+    /// Undoes the last executed instruction and returns it, `None` at the start of the frame.
+    pub fn step_back(&mut self) -> Option<&'a RecordedStep> {
+        self.position = self.position.checked_sub(1)?;
+        let step = &self.frame.steps[self.position];
+        for delta in step.deltas.iter().rev() {
+            delta.undo(&mut self.stack, &mut self.memory);
+        }
+        Some(step)
+    }
+
+    /// Executes the next instruction again and returns it, `None` at the end of the frame.
+    pub fn step_forward(&mut self) -> Option<&'a RecordedStep> {
+        let step = self.next_step()?;
+        for delta in &step.deltas {
+            delta.apply(&mut self.stack, &mut self.memory);
+        }
+        self.position += 1;
+        Some(step)
+    }
+
+    /// Moves to the position, clamped to the number of instructions of the frame.
+    pub fn seek(&mut self, position: usize) {
+        let position = position.min(self.frame.steps.len());
+        while self.position > position {
+            self.step_back();
+        }
+        while self.position < position {
+            self.step_forward();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::BenchmarkDB,
+        inspector_handle_register,
+        primitives::{bytes, Bytecode, TxKind},
+        Evm,
+    };
+
+    #[test]
+    fn step_back() {
+        // PUSH1 0x2a PUSH1 0 MSTORE PUSH1 7 PUSH1 1 SSTORE PUSH1 0 MLOAD STOP
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(bytes!(
+                "602a60005260076001556000515000"
+            ))))
+            .with_external_context(TimeTravelRecorder::default())
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TxKind::Call(Address::ZERO);
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        evm.transact().unwrap();
+        let frames = evm.context.external.take_frames();
+        assert_eq!(frames.len(), 1);
+        let frame = &frames[0];
+        assert_eq!(frame.address, Some(Address::ZERO));
+        assert_eq!(frame.steps.len(), 10);
+
+        let mut session = frame.session();
+        assert_eq!(session.memory().len(), 32);
+        assert!(session.stack().is_empty());
+
+        // Before STOP and POP, the loaded word is on the stack.
+        session.step_back();
+        session.step_back();
+        assert_eq!(session.next_step().unwrap().opcode, 0x50);
+        assert_eq!(session.stack(), [U256::from(0x2a)]);
+
+        // Before SSTORE, the value is recorded in the journal of the step.
+        session.seek(5);
+        let sstore = session.next_step().unwrap();
+        assert_eq!(sstore.opcode, 0x55);
+        assert!(sstore.deltas.iter().any(|delta| matches!(
+            delta,
+            StepDelta::Journal(entries)
+                if entries.iter().any(|entry| matches!(entry, JournalEntry::StorageChanged { .. }))
+        )));
+        assert_eq!(session.stack(), [U256::from(7), U256::from(1)]);
+
+        // Before MSTORE, the memory is empty.
+        session.seek(2);
+        assert_eq!(session.stack(), [U256::from(0x2a), U256::ZERO]);
+        assert!(session.memory().is_empty());
+        assert!(session.step_back().is_some());
+        assert!(session.step_back().is_some());
+        assert!(session.step_back().is_none());
+        assert_eq!(session.position(), 0);
+
+        // Replaying forward reaches the end state again.
+        session.seek(usize::MAX);
+        assert_eq!(session.position(), 10);
+        assert_eq!(session.memory(), frame.memory.as_slice());
+        assert_eq!(session.stack(), frame.stack.as_slice());
+    }
+}
@@ -0,0 +1,201 @@
+//! zstd-compressed JSONL struct logs, written and read with bounded memory.
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+use zstd::stream::{read::Decoder, write::Encoder};
+
+/// Writer compressing the JSONL output of a [`TracerEip3155`](super::TracerEip3155) with zstd.
+///
+/// The tracer flushes after every line, which would end a zstd block per line. Instead, the
+/// compressed data is flushed to the inner writer once the uncompressed data written since the
+/// last flush exceeds the flush threshold, so the memory used does not grow with the trace.
+///
+/// The zstd frame is completed by [`ZstdTraceWriter::finish`], or on drop, ignoring errors.
+pub struct ZstdTraceWriter<W: Write> {
+    encoder: Option<Encoder<'static, W>>,
+    flush_threshold: usize,
+    pending: usize,
+}
+
+impl ZstdTraceWriter<BufWriter<File>> {
+    /// Creates the file and writes the compressed trace to it.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> ZstdTraceWriter<W> {
+    /// Default zstd compression level.
+    pub const DEFAULT_LEVEL: i32 = 3;
+
+    /// Default number of uncompressed bytes between flushes of the compressed data.
+    pub const DEFAULT_FLUSH_THRESHOLD: usize = 1 << 20;
+
+    /// Creates a writer compressing with [`Self::DEFAULT_LEVEL`].
+    pub fn new(writer: W) -> io::Result<Self> {
+        Self::with_level(writer, Self::DEFAULT_LEVEL)
+    }
+
+    /// Creates a writer compressing with the given zstd level.
+    pub fn with_level(writer: W, level: i32) -> io::Result<Self> {
+        Ok(Self {
+            encoder: Some(Encoder::new(writer, level)?),
+            flush_threshold: Self::DEFAULT_FLUSH_THRESHOLD,
+            pending: 0,
+        })
+    }
+
+    /// Sets the number of uncompressed bytes between flushes of the compressed data.
+    pub fn with_flush_threshold(mut self, flush_threshold: usize) -> Self {
+        self.flush_threshold = flush_threshold;
+        self
+    }
+
+    /// Completes the zstd frame and returns the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let mut writer = self.encoder().finish()?;
+        writer.flush()?;
+        Ok(writer)
+    }
+
+    fn encoder(&mut self) -> Encoder<'static, W> {
+        self.encoder
+            .take()
+            .expect("encoder is only taken on finish")
+    }
+}
+
+impl<W: Write> Write for ZstdTraceWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self
+            .encoder
+            .as_mut()
+            .expect("encoder is only taken on finish")
+            .write(buf)?;
+        self.pending += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.pending < self.flush_threshold {
+            return Ok(());
+        }
+        self.pending = 0;
+        self.encoder
+            .as_mut()
+            .expect("encoder is only taken on finish")
+            .flush()
+    }
+}
+
+impl<W: Write> Drop for ZstdTraceWriter<W> {
+    fn drop(&mut self) {
+        if let Some(encoder) = self.encoder.take() {
+            let _ = encoder.finish().and_then(|mut writer| writer.flush());
+        }
+    }
+}
+
+/// Reads the lines of a trace written by a [`ZstdTraceWriter`], decompressing them as needed.
+pub struct ZstdTraceReader<R: Read> {
+    lines: io::Lines<BufReader<Decoder<'static, BufReader<R>>>>,
+}
+
+impl ZstdTraceReader<File> {
+    /// Opens the compressed trace file.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::new(File::open(path)?)
+    }
+}
+
+impl<R: Read> ZstdTraceReader<R> {
+    /// Creates a reader of the compressed trace.
+    pub fn new(reader: R) -> io::Result<Self> {
+        Ok(Self {
+            lines: BufReader::new(Decoder::new(reader)?).lines(),
+        })
+    }
+}
+
+impl<R: Read> Iterator for ZstdTraceReader<R> {
+    type Item = io::Result<serde_json::Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = match self.lines.next()? {
+            Ok(line) => line,
+            Err(e) => return Some(Err(e)),
+        };
+        Some(serde_json::from_str(&line).map_err(io::Error::from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::BenchmarkDB,
+        inspector_handle_register,
+        inspectors::TracerEip3155,
+        primitives::{address, bytes, Bytecode, TxKind},
+        Evm,
+    };
+
+    #[test]
+    fn write_and_read() {
+        let path = std::env::temp_dir().join(format!("trace-{}.jsonl.zst", std::process::id()));
+        let writer = ZstdTraceWriter::create(&path)
+            .unwrap()
+            .with_flush_threshold(64);
+
+        // PUSH1 1 PUSH1 2 ADD STOP
+        let mut evm = Evm::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_raw(bytes!(
+                "600160020100"
+            ))))
+            .with_external_context(TracerEip3155::new(Box::new(writer)))
+            .modify_tx_env(|tx| {
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TxKind::Call(address!("0000000000000000000000000000000000000000"));
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        evm.transact().unwrap();
+        // Dropping the tracer completes the zstd frame.
+        drop(evm);
+
+        let lines = ZstdTraceReader::open(&path)
+            .unwrap()
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        // One line per instruction and the summary.
+        assert_eq!(lines.len(), 5);
+        let opcodes = lines[..4]
+            .iter()
+            .map(|line| line["op"].as_u64().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(opcodes, [0x60, 0x60, 0x01, 0x00]);
+        assert_eq!(lines[4]["pass"], true);
+    }
+
+    #[test]
+    fn finish() {
+        let mut writer = ZstdTraceWriter::new(Vec::new()).unwrap();
+        for line in 0..1000 {
+            writeln!(writer, "{{\"pc\":{line}}}").unwrap();
+            writer.flush().unwrap();
+        }
+        let compressed = writer.finish().unwrap();
+
+        let lines = ZstdTraceReader::new(compressed.as_slice())
+            .unwrap()
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(lines.len(), 1000);
+        assert_eq!(lines[999]["pc"], 999);
+        // Lines are not flushed one by one.
+        assert!(compressed.len() < 1000 * 4);
+    }
+}
@@ -1,13 +1,24 @@
 use crate::{
-    interpreter::{InstructionResult, LoadAccountResult, SStoreResult, SelfDestructResult},
+    interpreter::{
+        analysis::to_analysed, InstructionResult, LoadAccountResult, SStoreResult,
+        SelfDestructResult,
+    },
     primitives::{
-        db::Database, hash_map::Entry, Account, Address, Bytecode, EVMError, EvmState,
-        EvmStorageSlot, HashMap, HashSet, Log, SpecId, SpecId::*, TransientStorage, B256,
-        KECCAK_EMPTY, PRECOMPILE3, U256,
+        db::Database, hash_map::Entry, is_account_dead, state::storage::Entry as StorageEntry,
+        Account, Address, Bytecode, EVMError, EvmState, EvmStorageSlot, HashMap, HashSet, Log,
+        SpecId, SpecId::*, TransientStorage, B256, KECCAK_EMPTY, PRECOMPILE3, U256,
     },
 };
 use core::mem;
-use std::vec::Vec;
+use std::{sync::Arc, vec::Vec};
+
+mod snapshot;
+#[cfg(feature = "journal_spill")]
+mod spill;
+pub use crate::primitives::RevertedLog;
+pub use snapshot::JournalSnapshot;
+#[cfg(feature = "journal_spill")]
+pub use spill::JournalSpill;
 
 /// JournalState is internal EVM state that is used to contain state and track changes to that state.
 /// It contains journal of changes that happened to state so that they can be reverted.
@@ -20,10 +31,24 @@ pub struct JournaledState {
     pub transient_storage: TransientStorage,
     /// logs
     pub logs: Vec<Log>,
+    /// Origin of each of the `logs`, in the same order.
+    #[cfg(feature = "log_origins")]
+    pub log_origins: Vec<crate::primitives::LogOrigin>,
+    /// Number of logs emitted by the transaction, including the reverted ones.
+    #[cfg(feature = "log_origins")]
+    pub log_count: usize,
+    /// Target addresses of the active frames, outermost first. See [`JournaledState::enter_frame`].
+    #[cfg(feature = "log_origins")]
+    pub frames: Vec<Address>,
     /// how deep are we in call stack.
     pub depth: usize,
+    /// Depth of the outermost static call frame, `None` outside of static calls.
+    pub static_depth: Option<usize>,
     /// journal with changes that happened between calls.
     pub journal: Vec<Vec<JournalEntry>>,
+    /// Number of entries in all but the last checkpoint of the journal, including the spilled
+    /// ones. See [`JournaledState::entry_count`].
+    pub sealed_entries: usize,
     /// Ethereum before EIP-161 differently defined empty and not-existing account
     /// Spec is needed for two things SpuriousDragon's `EIP-161 State clear`,
     /// and for Cancun's `EIP-6780: SELFDESTRUCT in same transaction`
@@ -35,6 +60,61 @@ pub struct JournaledState {
     /// Note that this not include newly loaded accounts, account and storage
     /// is considered warm if it is found in the `State`.
     pub warm_preloaded_addresses: HashSet<Address>,
+    /// Whether logs discarded by [`JournaledState::checkpoint_revert`] are kept in
+    /// `reverted_logs`. Disabled by default.
+    pub record_reverted_logs: bool,
+    /// Whether [`JournaledState::finalize`] leaves the state in the journal and returns an empty
+    /// map, set by [`Evm::transact_streaming`](crate::Evm::transact_streaming) to stream the
+    /// accounts out of the journal. Disabled by default.
+    pub keep_finalized_state: bool,
+    /// Logs emitted inside reverted frames, in the order they were reverted.
+    ///
+    /// Only populated when `record_reverted_logs` is set. They are not part of the receipt and are
+    /// not cleared by [`JournaledState::finalize`]. The `output` handler takes them into
+    /// [`ResultAndState::reverted_logs`](crate::primitives::ResultAndState::reverted_logs),
+    /// [`JournaledState::take_reverted_logs`] collects them when the journal is used directly.
+    pub reverted_logs: Vec<RevertedLog>,
+    /// Analysed bytecode interned by code hash.
+    ///
+    /// Every account with the same code hash shares one copy of its bytecode and jump table.
+    /// Code of a hash never changes, so the cache is kept across transactions, see
+    /// [`JournaledState::clear_code_cache`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub code_cache: HashMap<B256, Bytecode>,
+    /// Result of the static stack verification of legacy bytecode by code hash, kept across
+    /// transactions like [Self::code_cache]. See [`Contract::verify_stack`].
+    ///
+    /// [`Contract::verify_stack`]: crate::interpreter::Contract::verify_stack
+    #[cfg(feature = "legacy_stack_verification")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub stack_verified: HashMap<B256, bool>,
+    /// Accounts of the last snapshot taken or restored, shared by the next snapshot where they
+    /// did not change. See [`JournaledState::snapshot`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub snapshot_accounts: Arc<HashMap<Address, Arc<Account>>>,
+    /// Accounts changed since the last snapshot taken or restored by journal entries that are no
+    /// longer in the journal, or by [`JournaledState::initial_account_load`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub snapshot_dirty: HashSet<Address>,
+    /// Number of journal entries, see [`JournaledState::entry_count`], when the last snapshot was
+    /// taken or restored. The entries after it change the accounts of the next snapshot.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub snapshot_mark: usize,
+    /// KECCAK256 preimages recorded during the transaction, keyed by hash.
+    #[cfg(feature = "keccak_preimages")]
+    pub keccak_preimages: HashMap<B256, crate::primitives::Bytes>,
+    /// Opcodes executed by the finished frames of the transaction.
+    #[cfg(feature = "opcode_stats")]
+    pub opcode_stats: crate::primitives::OpcodeStats,
+    /// Set by the validation of a transaction for an impersonated sender.
+    pub impersonation: Option<crate::primitives::Impersonation>,
+    /// Wall-clock deadline of the transaction, set when its first frame is executed.
+    #[cfg(feature = "execution_timeout")]
+    pub deadline: Option<std::time::Instant>,
+    /// Writes sealed checkpoints of large journals to disk, see [`JournalSpill`].
+    #[cfg(feature = "journal_spill")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub spill: Option<JournalSpill>,
 }
 
 impl JournaledState {
@@ -51,13 +131,39 @@ impl JournaledState {
     ///
     pub fn new(spec: SpecId, warm_preloaded_addresses: HashSet<Address>) -> JournaledState {
         Self {
-            state: HashMap::new(),
+            state: HashMap::default(),
             transient_storage: TransientStorage::default(),
             logs: Vec::new(),
+            #[cfg(feature = "log_origins")]
+            log_origins: Vec::new(),
+            #[cfg(feature = "log_origins")]
+            log_count: 0,
+            #[cfg(feature = "log_origins")]
+            frames: Vec::new(),
             journal: vec![vec![]],
+            sealed_entries: 0,
             depth: 0,
+            static_depth: None,
             spec,
             warm_preloaded_addresses,
+            record_reverted_logs: false,
+            keep_finalized_state: false,
+            reverted_logs: Vec::new(),
+            code_cache: HashMap::default(),
+            #[cfg(feature = "legacy_stack_verification")]
+            stack_verified: HashMap::default(),
+            snapshot_accounts: Arc::default(),
+            snapshot_dirty: HashSet::default(),
+            snapshot_mark: 0,
+            #[cfg(feature = "keccak_preimages")]
+            keccak_preimages: HashMap::default(),
+            #[cfg(feature = "opcode_stats")]
+            opcode_stats: Default::default(),
+            impersonation: None,
+            #[cfg(feature = "execution_timeout")]
+            deadline: None,
+            #[cfg(feature = "journal_spill")]
+            spill: None,
         }
     }
 
@@ -92,37 +198,143 @@ impl JournaledState {
         }
     }
 
-    /// Clears the JournaledState. Preserving only the spec.
+    /// Clears the JournaledState. Preserving only the spec, the settings, including the emptied
+    /// journal spill, and the caches kept across transactions, [Self::code_cache] and
+    /// [Self::snapshot_accounts].
+    ///
+    /// Snapshots taken before stay valid, as after [Self::finalize]. The accounts changed since
+    /// the last snapshot taken or restored are kept in [Self::snapshot_dirty], so that the next
+    /// [Self::snapshot] or [Self::restore] finds them without comparing the accounts.
     pub fn clear(&mut self) {
+        self.record_snapshot_changes();
         let spec = self.spec;
-        *self = Self::new(spec, HashSet::new());
+        let record_reverted_logs = self.record_reverted_logs;
+        let code_cache = mem::take(&mut self.code_cache);
+        #[cfg(feature = "legacy_stack_verification")]
+        let stack_verified = mem::take(&mut self.stack_verified);
+        let snapshot_accounts = mem::take(&mut self.snapshot_accounts);
+        let snapshot_dirty = mem::take(&mut self.snapshot_dirty);
+        #[cfg(feature = "journal_spill")]
+        let mut spill = self.spill.take();
+        *self = Self::new(spec, HashSet::default());
+        self.record_reverted_logs = record_reverted_logs;
+        self.code_cache = code_cache;
+        #[cfg(feature = "legacy_stack_verification")]
+        {
+            self.stack_verified = stack_verified;
+        }
+        self.snapshot_accounts = snapshot_accounts;
+        self.snapshot_dirty = snapshot_dirty;
+        #[cfg(feature = "journal_spill")]
+        {
+            if let Some(spill) = &mut spill {
+                spill.clear();
+            }
+            self.spill = spill;
+        }
+    }
+
+    /// Takes the logs recorded from reverted frames.
+    #[inline]
+    pub fn take_reverted_logs(&mut self) -> Vec<RevertedLog> {
+        mem::take(&mut self.reverted_logs)
     }
 
     /// Does cleanup and returns modified state.
     ///
-    /// This resets the [JournaledState] to its initial state in [Self::new]
+    /// This resets the [JournaledState] to its initial state in [Self::new]. The state is left in
+    /// the journal, and an empty map returned, if [Self::keep_finalized_state] is set.
     #[inline]
     pub fn finalize(&mut self) -> (EvmState, Vec<Log>) {
+        self.record_snapshot_changes();
         let Self {
             state,
             transient_storage,
             logs,
+            #[cfg(feature = "log_origins")]
+            log_origins,
+            #[cfg(feature = "log_origins")]
+            log_count,
+            #[cfg(feature = "log_origins")]
+            frames,
             depth,
+            static_depth,
             journal,
+            sealed_entries,
+            keep_finalized_state,
             // kept, see [Self::new]
             spec: _,
             warm_preloaded_addresses: _,
+            // kept until taken, see [Self::take_reverted_logs]
+            record_reverted_logs: _,
+            reverted_logs: _,
+            // code of a hash is immutable, see [Self::code_cache]
+            code_cache: _,
+            #[cfg(feature = "legacy_stack_verification")]
+            stack_verified,
+            // snapshots stay valid across transactions, see [Self::snapshot]
+            snapshot_accounts: _,
+            snapshot_dirty: _,
+            snapshot_mark,
+            #[cfg(feature = "keccak_preimages")]
+            keccak_preimages,
+            #[cfg(feature = "opcode_stats")]
+            opcode_stats,
+            impersonation: _,
+            #[cfg(feature = "execution_timeout")]
+            deadline,
+            #[cfg(feature = "journal_spill")]
+            spill,
         } = self;
+        // feature-gated fields are bound by name, rustfmt mis-indents `#[cfg] field: _`.
+        // verification results are keyed by code hash, like [Self::code_cache]
+        #[cfg(feature = "legacy_stack_verification")]
+        let _ = stack_verified;
+        // taken by the `output` handler.
+        #[cfg(feature = "keccak_preimages")]
+        let _ = keccak_preimages;
+        #[cfg(feature = "opcode_stats")]
+        let _ = opcode_stats;
+
+        #[cfg(feature = "execution_timeout")]
+        {
+            *deadline = None;
+        }
 
         *transient_storage = TransientStorage::default();
+        #[cfg(feature = "journal_spill")]
+        if let Some(spill) = spill {
+            spill.clear();
+        }
+
         *journal = vec![vec![]];
+        *sealed_entries = 0;
+        *snapshot_mark = 0;
         *depth = 0;
-        let state = mem::take(state);
+        *static_depth = None;
+        // taken by the `output` handler before finalizing.
+        #[cfg(feature = "log_origins")]
+        {
+            log_origins.clear();
+            *log_count = 0;
+            frames.clear();
+        }
+        let state = if *keep_finalized_state {
+            EvmState::default()
+        } else {
+            mem::take(state)
+        };
         let logs = mem::take(logs);
 
         (state, logs)
     }
 
+    /// Drops all interned bytecode, see [Self::code_cache].
+    #[inline]
+    pub fn clear_code_cache(&mut self) {
+        self.code_cache.clear();
+    }
+
     /// Returns the _loaded_ [Account] for the given address.
     ///
     /// This assumes that the account has already been loaded.
@@ -157,7 +369,12 @@ impl JournaledState {
             .push(JournalEntry::CodeChange { address });
 
         account.info.code_hash = hash;
-        account.info.code = Some(code);
+        account.info.code = Some(
+            self.code_cache
+                .entry(hash)
+                .or_insert_with(|| to_analysed(code))
+                .clone(),
+        );
     }
 
     /// use it only if you know that acc is warm
@@ -256,6 +473,8 @@ impl JournaledState {
     ) -> Result<JournalCheckpoint, InstructionResult> {
         // Enter subroutine
         let checkpoint = self.checkpoint();
+        #[cfg(feature = "log_origins")]
+        self.enter_frame(address);
 
         // Newly created account is present, as we just loaded it.
         let account = self.state.get_mut(&address).unwrap();
@@ -416,6 +635,12 @@ impl JournaledState {
         }
     }
 
+    /// Returns the number of entries in the journal.
+    #[inline]
+    pub fn entry_count(&self) -> usize {
+        self.sealed_entries + self.journal.last().map_or(0, Vec::len)
+    }
+
     /// Makes a checkpoint that in case of Revert can bring back state to this point.
     #[inline]
     pub fn checkpoint(&mut self) -> JournalCheckpoint {
@@ -424,40 +649,98 @@ impl JournaledState {
             journal_i: self.journal.len(),
         };
         self.depth += 1;
+        self.sealed_entries += self.journal.last().map_or(0, Vec::len);
         self.journal.push(Default::default());
+        #[cfg(feature = "journal_spill")]
+        if let Some(spill) = &mut self.spill {
+            let dirty = (!self.snapshot_accounts.is_empty()).then_some(&mut self.snapshot_dirty);
+            spill.spill(&mut self.journal, self.sealed_entries, dirty);
+        }
         checkpoint
     }
 
+    /// Records `address` as the target of the frame entered by the last checkpoint.
+    ///
+    /// Logs emitted by the frame are attributed to it, see [`LogOrigin::frames`].
+    ///
+    /// [`LogOrigin::frames`]: crate::primitives::LogOrigin::frames
+    #[cfg(feature = "log_origins")]
+    #[inline]
+    pub fn enter_frame(&mut self, address: Address) {
+        self.frames.truncate(self.depth.saturating_sub(1));
+        self.frames.push(address);
+    }
+
     /// Commit the checkpoint.
     #[inline]
     pub fn checkpoint_commit(&mut self) {
         self.depth -= 1;
+        #[cfg(feature = "log_origins")]
+        self.frames.truncate(self.depth);
     }
 
     /// Reverts all changes to state until given checkpoint.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a spilled checkpoint can not be read back, see [`JournalSpill`].
     #[inline]
     pub fn checkpoint_revert(&mut self, checkpoint: JournalCheckpoint) {
+        // The checkpoint before the reverted ones is written to again.
+        let reopened = checkpoint.journal_i.saturating_sub(1);
+        #[cfg(feature = "journal_spill")]
+        if let Some(spill) = &mut self.spill {
+            spill.restore(&mut self.journal, reopened);
+        }
+        let leng = self.journal.len();
+        self.sealed_entries -= self.journal[reopened..leng - 1]
+            .iter()
+            .map(Vec::len)
+            .sum::<usize>();
+
         let is_spurious_dragon_enabled = SpecId::enabled(self.spec, SPURIOUS_DRAGON);
         let state = &mut self.state;
         let transient_storage = &mut self.transient_storage;
+        let reverted_depth = self.depth;
+        let record_snapshot = !self.snapshot_accounts.is_empty();
+        let snapshot_dirty = &mut self.snapshot_dirty;
         self.depth -= 1;
+        #[cfg(feature = "log_origins")]
+        self.frames.truncate(self.depth);
         // iterate over last N journals sets and revert our global state
-        let leng = self.journal.len();
         self.journal
             .iter_mut()
             .rev()
             .take(leng - checkpoint.journal_i)
             .for_each(|cs| {
+                let entries = mem::take(cs);
+                // reverting changes the accounts of a snapshot taken inside the frame.
+                if record_snapshot {
+                    snapshot::record_dirty(snapshot_dirty, &entries);
+                }
                 Self::journal_revert(
                     state,
                     transient_storage,
-                    mem::take(cs),
+                    entries,
                     is_spurious_dragon_enabled,
                 )
             });
 
-        self.logs.truncate(checkpoint.log_i);
+        if self.record_reverted_logs {
+            let reverted = self.logs.drain(checkpoint.log_i..).enumerate();
+            self.reverted_logs
+                .extend(reverted.map(|(i, log)| RevertedLog {
+                    log,
+                    depth: reverted_depth,
+                    log_index: checkpoint.log_i + i,
+                }));
+        } else {
+            self.logs.truncate(checkpoint.log_i);
+        }
+        #[cfg(feature = "log_origins")]
+        self.log_origins.truncate(checkpoint.log_i);
         self.journal.truncate(checkpoint.journal_i);
+        self.snapshot_mark = self.snapshot_mark.min(self.entry_count());
     }
 
     /// Performances selfdestruct action.
@@ -533,6 +816,8 @@ impl JournaledState {
     }
 
     /// Initial load of account. This load will not be tracked inside journal
+    ///
+    /// Cold accounts and storage slots already in the state are marked warm.
     #[inline]
     pub fn initial_account_load<DB: Database>(
         &mut self,
@@ -540,9 +825,16 @@ impl JournaledState {
         storage_keys: impl IntoIterator<Item = U256>,
         db: &mut DB,
     ) -> Result<&mut Account, EVMError<DB::Error>> {
+        if !self.snapshot_accounts.is_empty() {
+            self.snapshot_dirty.insert(address);
+        }
         // load or get account.
         let account = match self.state.entry(address) {
-            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Occupied(entry) => {
+                let account = entry.into_mut();
+                account.mark_warm();
+                account
+            }
             Entry::Vacant(vac) => vac.insert(
                 db.basic(address)
                     .map_err(EVMError::Database)?
@@ -552,11 +844,16 @@ impl JournaledState {
         };
         // preload storages.
         for storage_key in storage_keys.into_iter() {
-            if let Entry::Vacant(entry) = account.storage.entry(storage_key) {
-                let storage = db
-                    .storage(address, storage_key)
-                    .map_err(EVMError::Database)?;
-                entry.insert(EvmStorageSlot::new(storage));
+            match account.storage.entry(storage_key) {
+                StorageEntry::Occupied(mut entry) => {
+                    entry.get_mut().mark_warm();
+                }
+                StorageEntry::Vacant(entry) => {
+                    let storage = db
+                        .storage(address, storage_key)
+                        .map_err(EVMError::Database)?;
+                    entry.insert(EvmStorageSlot::new(storage));
+                }
             }
         }
         Ok(account)
@@ -612,37 +909,58 @@ impl JournaledState {
     ) -> Result<LoadAccountResult, EVMError<DB::Error>> {
         let spec = self.spec;
         let (acc, is_cold) = self.load_account(address, db)?;
-
-        let is_spurious_dragon_enabled = SpecId::enabled(spec, SPURIOUS_DRAGON);
-        let is_empty = if is_spurious_dragon_enabled {
-            acc.is_empty()
-        } else {
-            let loaded_not_existing = acc.is_loaded_as_not_existing();
-            let is_not_touched = !acc.is_touched();
-            loaded_not_existing && is_not_touched
-        };
+        let is_empty = is_account_dead(spec, acc);
 
         Ok(LoadAccountResult { is_empty, is_cold })
     }
 
     /// Loads code.
+    ///
+    /// Legacy bytecode is analysed once per code hash and shared through [Self::code_cache].
+    /// Analysis stored in the database is used if present, otherwise new analysis results are
+    /// handed to [Database::store_code_analysis].
     #[inline]
     pub fn load_code<DB: Database>(
         &mut self,
         address: Address,
         db: &mut DB,
     ) -> Result<(&mut Account, bool), EVMError<DB::Error>> {
-        let (acc, is_cold) = self.load_account(address, db)?;
-        if acc.info.code.is_none() {
-            if acc.info.code_hash == KECCAK_EMPTY {
-                let empty = Bytecode::default();
-                acc.info.code = Some(empty);
-            } else {
-                let code = db
-                    .code_by_hash(acc.info.code_hash)
-                    .map_err(EVMError::Database)?;
-                acc.info.code = Some(code);
+        let (_, is_cold) = self.load_account(address, db)?;
+        let acc = self.state.get_mut(&address).unwrap();
+        if acc.info.code_hash == KECCAK_EMPTY {
+            if acc.info.code.is_none() {
+                acc.info.code = Some(Bytecode::default());
             }
+        } else if !acc
+            .info
+            .code
+            .as_ref()
+            .is_some_and(Bytecode::is_execution_ready)
+        {
+            let code = match self.code_cache.entry(acc.info.code_hash) {
+                Entry::Occupied(entry) => entry.get().clone(),
+                Entry::Vacant(entry) => {
+                    let hash = acc.info.code_hash;
+                    let code = match db.code_analysis_by_hash(hash).map_err(EVMError::Database)? {
+                        Some(code) => code,
+                        None => {
+                            let code = match acc.info.code.take() {
+                                Some(code) => code,
+                                None => db.code_by_hash(hash).map_err(EVMError::Database)?,
+                            };
+                            let analysed = !code.is_execution_ready();
+                            let code = to_analysed(code);
+                            if analysed {
+                                db.store_code_analysis(hash, &code)
+                                    .map_err(EVMError::Database)?;
+                            }
+                            code
+                        }
+                    };
+                    entry.insert(code).clone()
+                }
+            };
+            acc.info.code = Some(code);
         }
         Ok((acc, is_cold))
     }
@@ -664,12 +982,12 @@ impl JournaledState {
         // only if account is created in this tx we can assume that storage is empty.
         let is_newly_created = account.is_created();
         let (value, is_cold) = match account.storage.entry(key) {
-            Entry::Occupied(occ) => {
+            StorageEntry::Occupied(occ) => {
                 let slot = occ.into_mut();
                 let is_cold = slot.mark_warm();
                 (slot.present_value, is_cold)
             }
-            Entry::Vacant(vac) => {
+            StorageEntry::Vacant(vac) => {
                 // if storage was cleared, we don't need to ping db.
                 let value = if is_newly_created {
                     U256::ZERO
@@ -799,6 +1117,15 @@ impl JournaledState {
     /// push log into subroutine
     #[inline]
     pub fn log(&mut self, log: Log) {
+        #[cfg(feature = "log_origins")]
+        {
+            self.log_origins.push(crate::primitives::LogOrigin {
+                index: self.log_count,
+                depth: self.depth,
+                frames: self.frames.clone(),
+            });
+            self.log_count += 1;
+        }
         self.logs.push(log);
     }
 }
@@ -876,3 +1203,158 @@ pub struct JournalCheckpoint {
     log_i: usize,
     journal_i: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{Bytes, LogData};
+
+    fn log(n: u8) -> Log {
+        Log {
+            address: Address::with_last_byte(n),
+            data: LogData::new_unchecked(vec![], Bytes::new()),
+        }
+    }
+
+    #[test]
+    fn record_reverted_logs() {
+        let mut journal = JournaledState::new(SpecId::CANCUN, HashSet::default());
+        journal.record_reverted_logs = true;
+
+        let _root = journal.checkpoint();
+        journal.log(log(1));
+
+        let outer = journal.checkpoint();
+        journal.log(log(2));
+        let inner = journal.checkpoint();
+        journal.log(log(3));
+        journal.checkpoint_revert(inner);
+        journal.log(log(4));
+        journal.checkpoint_revert(outer);
+        journal.checkpoint_commit();
+
+        assert_eq!(journal.logs, vec![log(1)]);
+        let reverted = journal.take_reverted_logs();
+        assert_eq!(
+            reverted,
+            vec![
+                RevertedLog {
+                    log: log(3),
+                    depth: 3,
+                    log_index: 2
+                },
+                RevertedLog {
+                    log: log(2),
+                    depth: 2,
+                    log_index: 1
+                },
+                RevertedLog {
+                    log: log(4),
+                    depth: 2,
+                    log_index: 2
+                },
+            ]
+        );
+        assert!(journal.reverted_logs.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "log_origins")]
+    fn log_origins() {
+        use crate::primitives::LogOrigin;
+
+        let mut journal = JournaledState::new(SpecId::CANCUN, HashSet::default());
+        let (a, b, c) = (
+            Address::with_last_byte(0xa),
+            Address::with_last_byte(0xb),
+            Address::with_last_byte(0xc),
+        );
+
+        let _root = journal.checkpoint();
+        journal.enter_frame(a);
+        let reverted = journal.checkpoint();
+        journal.enter_frame(b);
+        journal.log(log(1));
+        journal.checkpoint_revert(reverted);
+        let _inner = journal.checkpoint();
+        journal.enter_frame(c);
+        journal.log(log(2));
+        journal.checkpoint_commit();
+        journal.log(log(3));
+
+        assert_eq!(
+            journal.log_origins,
+            vec![
+                LogOrigin {
+                    index: 1,
+                    depth: 2,
+                    frames: vec![a, c],
+                },
+                LogOrigin {
+                    index: 2,
+                    depth: 1,
+                    frames: vec![a],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn tstore_reverts_per_checkpoint() {
+        let mut journal = JournaledState::new(SpecId::CANCUN, HashSet::default());
+        let (address, key) = (Address::with_last_byte(0xa), U256::from(1));
+
+        let _root = journal.checkpoint();
+        journal.tstore(address, key, U256::from(1));
+        let inner = journal.checkpoint();
+        journal.tstore(address, key, U256::from(2));
+        // Unchanged values are not journaled.
+        journal.tstore(address, key, U256::from(2));
+        assert_eq!(
+            journal.journal.last().unwrap(),
+            &[JournalEntry::TransientStorageChange {
+                address,
+                key,
+                had_value: U256::from(1),
+            }]
+        );
+
+        journal.checkpoint_revert(inner);
+        assert_eq!(journal.tload(address, key), U256::from(1));
+    }
+
+    #[test]
+    fn load_code_interns_bytecode() {
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x5b, 0x00]));
+        let mut db = crate::db::CacheDB::new(crate::db::EmptyDB::default());
+        for n in 1..=2 {
+            db.insert_account_info(
+                Address::with_last_byte(n),
+                crate::primitives::AccountInfo::from_bytecode(code.clone()),
+            );
+        }
+
+        let mut journal = JournaledState::new(SpecId::CANCUN, HashSet::default());
+        let mut load = |journal: &mut JournaledState, n| {
+            let (acc, _) = journal
+                .load_code(Address::with_last_byte(n), &mut db)
+                .unwrap();
+            acc.info.code.clone().unwrap()
+        };
+        let first = load(&mut journal, 1);
+        journal.finalize();
+        let second = load(&mut journal, 2);
+
+        assert!(first.is_execution_ready());
+        assert_eq!(first.original_bytes(), code.original_bytes());
+        assert!(Arc::ptr_eq(
+            &first.legacy_jump_table().unwrap().0,
+            &second.legacy_jump_table().unwrap().0
+        ));
+        assert_eq!(journal.code_cache.len(), 1);
+        journal.clear();
+        assert_eq!(journal.code_cache.len(), 1);
+        // The analysis is stored in the database and reused by a new journal.
+        assert_eq!(db.code_analysis_by_hash(code.hash_slow()), Ok(Some(first)));
+    }
+}
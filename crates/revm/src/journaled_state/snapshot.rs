@@ -0,0 +1,306 @@
+use super::{JournalEntry, JournaledState};
+use crate::primitives::{Account, Address, HashMap, HashSet};
+use core::mem;
+use std::sync::Arc;
+
+/// Copy of a [`JournaledState`] that it can be restored to, see [`JournaledState::snapshot`].
+///
+/// Accounts are shared with the snapshot taken or restored before it where they did not
+/// change, cloning a snapshot only clones reference counts.
+#[derive(Clone, Debug)]
+pub struct JournalSnapshot {
+    /// Loaded accounts.
+    accounts: Arc<HashMap<Address, Arc<Account>>>,
+    /// Everything else, with empty `state`, `code_cache`, `stack_verified` and
+    /// `snapshot_accounts`.
+    rest: JournaledState,
+}
+
+impl JournalSnapshot {
+    /// Returns the loaded accounts.
+    pub fn accounts(&self) -> &HashMap<Address, Arc<Account>> {
+        &self.accounts
+    }
+
+    /// Returns the journal without its accounts.
+    pub fn journal(&self) -> &JournaledState {
+        &self.rest
+    }
+}
+
+/// Adds the accounts changed by `entries` to `dirty`.
+pub(super) fn record_dirty<'a>(
+    dirty: &mut HashSet<Address>,
+    entries: impl IntoIterator<Item = &'a JournalEntry>,
+) {
+    for entry in entries {
+        match *entry {
+            JournalEntry::AccountDestroyed {
+                address, target, ..
+            }
+            | JournalEntry::BalanceTransfer {
+                from: address,
+                to: target,
+                ..
+            } => {
+                dirty.insert(address);
+                dirty.insert(target);
+            }
+            JournalEntry::AccountWarmed { address }
+            | JournalEntry::AccountTouched { address }
+            | JournalEntry::NonceChange { address }
+            | JournalEntry::AccountCreated { address }
+            | JournalEntry::StorageChanged { address, .. }
+            | JournalEntry::StorageWarmed { address, .. }
+            | JournalEntry::CodeChange { address } => {
+                dirty.insert(address);
+            }
+            // transient storage is restored with the rest of the journal.
+            JournalEntry::TransientStorageChange { .. } => {}
+        }
+    }
+}
+
+impl JournaledState {
+    /// Takes a snapshot of the journal that it can be restored to with [`Self::restore`].
+    ///
+    /// Unlike a [checkpoint](Self::checkpoint), a snapshot stays valid across frames and
+    /// transactions, e.g. after [`Self::finalize`], and can be restored any number of times.
+    /// Simulations exploring several branches from the same pre-state snapshot it once and
+    /// restore it before each branch.
+    ///
+    /// Only the accounts changed since the last snapshot taken or restored are cloned, the
+    /// others are shared with it. They are found from the journal entries recorded since then,
+    /// the accounts loaded by [`Self::initial_account_load`] and the warm preloaded accounts,
+    /// which are compared as they are changed without journal entries, e.g. by the fee
+    /// payments. Other changes made to [`Self::state`] directly are not picked up. The
+    /// interned bytecode is not part of the snapshot, see [`Self::code_cache`].
+    pub fn snapshot(&mut self) -> JournalSnapshot {
+        let mut accounts = mem::take(&mut self.snapshot_accounts);
+        let mut dirty = self.take_snapshot_dirty();
+        self.compare_preloaded(&accounts, &mut dirty);
+        if !dirty.is_empty() || accounts.len() != self.state.len() {
+            let shared = Arc::make_mut(&mut accounts);
+            for address in dirty {
+                match self.state.get(&address) {
+                    Some(account) => shared.insert(address, Arc::new(account.clone())),
+                    None => shared.remove(&address),
+                };
+            }
+            // the state was taken by `finalize` or `clear`, or no snapshot was taken yet.
+            if shared.len() != self.state.len() {
+                shared.retain(|address, _| self.state.contains_key(address));
+                for (address, account) in &self.state {
+                    shared
+                        .entry(*address)
+                        .or_insert_with(|| Arc::new(account.clone()));
+                }
+            }
+        }
+
+        let state = mem::take(&mut self.state);
+        let code_cache = mem::take(&mut self.code_cache);
+        #[cfg(feature = "legacy_stack_verification")]
+        let stack_verified = mem::take(&mut self.stack_verified);
+        self.snapshot_mark = self.entry_count();
+        let rest = self.clone();
+        self.state = state;
+        self.code_cache = code_cache;
+        #[cfg(feature = "legacy_stack_verification")]
+        {
+            self.stack_verified = stack_verified;
+        }
+        self.snapshot_accounts = accounts.clone();
+
+        JournalSnapshot { accounts, rest }
+    }
+
+    /// Restores the journal to the snapshot.
+    ///
+    /// Only the accounts changed since the last snapshot taken or restored, found as in
+    /// [`Self::snapshot`], and the accounts in which that snapshot differs from this one are
+    /// cloned from it. Accounts loaded after the snapshot are removed.
+    pub fn restore(&mut self, snapshot: &JournalSnapshot) {
+        let last = mem::take(&mut self.snapshot_accounts);
+        let mut dirty = self.take_snapshot_dirty();
+        self.compare_preloaded(&snapshot.accounts, &mut dirty);
+        if !Arc::ptr_eq(&last, &snapshot.accounts) {
+            for (address, account) in snapshot.accounts.iter() {
+                if !last
+                    .get(address)
+                    .is_some_and(|last| Arc::ptr_eq(last, account))
+                {
+                    dirty.insert(*address);
+                }
+            }
+            dirty.extend(
+                last.keys()
+                    .filter(|address| !snapshot.accounts.contains_key(*address)),
+            );
+        }
+
+        let mut state = mem::take(&mut self.state);
+        let code_cache = mem::take(&mut self.code_cache);
+        #[cfg(feature = "legacy_stack_verification")]
+        let stack_verified = mem::take(&mut self.stack_verified);
+        *self = snapshot.rest.clone();
+
+        for address in dirty {
+            match snapshot.accounts.get(&address) {
+                Some(account) => state.insert(address, (**account).clone()),
+                None => state.remove(&address),
+            };
+        }
+        // the state was taken by `finalize` or `clear`.
+        if state.len() != snapshot.accounts.len() {
+            state.retain(|address, _| snapshot.accounts.contains_key(address));
+            for (address, account) in snapshot.accounts.iter() {
+                state.entry(*address).or_insert_with(|| (**account).clone());
+            }
+        }
+        self.state = state;
+        self.code_cache = code_cache;
+        #[cfg(feature = "legacy_stack_verification")]
+        {
+            self.stack_verified = stack_verified;
+        }
+        self.snapshot_accounts = snapshot.accounts.clone();
+    }
+
+    /// Moves the accounts changed by the journal entries recorded since the last snapshot
+    /// taken or restored to [`Self::snapshot_dirty`], before the journal is cleared.
+    ///
+    /// Nothing is recorded until a snapshot of loaded accounts is taken.
+    #[inline]
+    pub(super) fn record_snapshot_changes(&mut self) {
+        if !self.snapshot_accounts.is_empty() {
+            self.snapshot_dirty = self.take_snapshot_dirty();
+        }
+    }
+
+    /// Takes the accounts changed since the last snapshot taken or restored.
+    fn take_snapshot_dirty(&mut self) -> HashSet<Address> {
+        let mut dirty = mem::take(&mut self.snapshot_dirty);
+        let recorded = self.entry_count().saturating_sub(self.snapshot_mark);
+        let entries = self
+            .journal
+            .iter()
+            .rev()
+            .flat_map(|entries| entries.iter().rev());
+        record_dirty(&mut dirty, entries.take(recorded));
+        dirty
+    }
+
+    /// Adds the warm preloaded accounts that differ from `accounts` to `dirty`.
+    fn compare_preloaded(
+        &self,
+        accounts: &HashMap<Address, Arc<Account>>,
+        dirty: &mut HashSet<Address>,
+    ) {
+        for address in &self.warm_preloaded_addresses {
+            let Some(account) = self.state.get(address) else {
+                continue;
+            };
+            if !accounts
+                .get(address)
+                .is_some_and(|shared| **shared == *account)
+            {
+                dirty.insert(*address);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::InMemoryDB,
+        primitives::{AccountInfo, SpecId, U256},
+    };
+
+    #[test]
+    fn restore_across_transactions() {
+        let (a, b, c) = (
+            Address::with_last_byte(0xa),
+            Address::with_last_byte(0xb),
+            Address::with_last_byte(0xc),
+        );
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(a, AccountInfo::from_balance(U256::from(100)));
+        db.insert_account_info(b, AccountInfo::from_balance(U256::from(100)));
+
+        let mut journal = JournaledState::new(SpecId::CANCUN, HashSet::default());
+        journal.load_account(a, &mut db).unwrap();
+        journal.load_account(b, &mut db).unwrap();
+        let pre = journal.snapshot();
+        let pre_state = journal.state.clone();
+
+        for _ in 0..2 {
+            journal.checkpoint();
+            journal.transfer(&a, &c, U256::from(10), &mut db).unwrap();
+            journal
+                .sstore(b, U256::from(1), U256::from(1), &mut db)
+                .unwrap();
+            journal.checkpoint_commit();
+            let (state, _) = journal.finalize();
+            assert_eq!(state[&c].info.balance, U256::from(10));
+
+            journal.restore(&pre);
+            assert_eq!(journal.state, pre_state);
+            assert_eq!(journal.journal, pre.journal().journal);
+        }
+
+        // Only the changed account is cloned.
+        journal
+            .sstore(b, U256::from(1), U256::from(2), &mut db)
+            .unwrap();
+        let branch = journal.snapshot();
+        assert!(Arc::ptr_eq(&pre.accounts()[&a], &branch.accounts()[&a]));
+        assert!(!Arc::ptr_eq(&pre.accounts()[&b], &branch.accounts()[&b]));
+        // Nothing changed, everything is shared.
+        let again = journal.snapshot();
+        assert!(Arc::ptr_eq(&branch.accounts, &again.accounts));
+    }
+
+    #[test]
+    fn restore_changed_accounts() {
+        let (a, b, c) = (
+            Address::with_last_byte(0xa),
+            Address::with_last_byte(0xb),
+            Address::with_last_byte(0xc),
+        );
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(a, AccountInfo::from_balance(U256::from(100)));
+
+        let mut journal = JournaledState::new(SpecId::CANCUN, HashSet::default());
+        journal.load_account(a, &mut db).unwrap();
+        journal.load_account(b, &mut db).unwrap();
+        let pre = journal.snapshot();
+        let pre_state = journal.state.clone();
+
+        // Changes before the last snapshot are found from the snapshots.
+        journal.transfer(&a, &c, U256::from(10), &mut db).unwrap();
+        let _branch = journal.snapshot();
+        journal
+            .sstore(b, U256::from(1), U256::from(1), &mut db)
+            .unwrap();
+        journal.restore(&pre);
+        assert_eq!(journal.state, pre_state);
+
+        // Reverting a frame changes the accounts of a snapshot taken inside it.
+        let checkpoint = journal.checkpoint();
+        journal
+            .sstore(b, U256::from(1), U256::from(2), &mut db)
+            .unwrap();
+        let inner = journal.snapshot();
+        let inner_state = journal.state.clone();
+        journal.checkpoint_revert(checkpoint);
+        assert_eq!(
+            journal.state[&b].storage[&U256::from(1)].present_value,
+            U256::ZERO
+        );
+        journal.restore(&inner);
+        assert_eq!(journal.state, inner_state);
+    }
+}
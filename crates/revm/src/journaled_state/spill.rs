@@ -0,0 +1,203 @@
+use super::JournalEntry;
+use crate::primitives::{Address, HashSet};
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    path::PathBuf,
+    vec::Vec,
+};
+
+/// Writes sealed checkpoints of a large journal to disk, and reads them back when they are
+/// reverted.
+///
+/// Only checkpoints that are no longer written to are spilled, oldest first, once the entries
+/// of the sealed checkpoints kept in memory exceed the threshold. They are needed again only if
+/// the execution reverts to them, which keeps the revert behaviour unchanged. Spilled files are
+/// removed when they are read back, when the journal is finalized, and on drop.
+///
+/// Set it in [`JournaledState::spill`](super::JournaledState::spill).
+#[derive(Debug)]
+pub struct JournalSpill {
+    dir: PathBuf,
+    threshold: usize,
+    id: u64,
+    /// Length of the spilled checkpoints, by index.
+    spilled: BTreeMap<usize, usize>,
+    spilled_entries: usize,
+}
+
+impl JournalSpill {
+    /// Creates a spill writing to files in `dir` once more than `threshold` entries of sealed
+    /// checkpoints are in memory.
+    pub fn new(dir: impl Into<PathBuf>, threshold: usize) -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self {
+            dir: dir.into(),
+            threshold,
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            spilled: BTreeMap::new(),
+            spilled_entries: 0,
+        }
+    }
+
+    /// Returns the number of entries on disk.
+    pub fn spilled_entries(&self) -> usize {
+        self.spilled_entries
+    }
+
+    fn path(&self, index: usize) -> PathBuf {
+        self.dir.join(format!(
+            "journal-{}-{}-{index}.json",
+            std::process::id(),
+            self.id
+        ))
+    }
+
+    /// Spills the oldest sealed checkpoints until at most `threshold` of the `sealed_entries` are
+    /// in memory. A checkpoint that can not be written stays in memory.
+    ///
+    /// The accounts changed by the spilled entries are added to `snapshot_dirty`, if given, see
+    /// [`JournaledState::snapshot`](super::JournaledState::snapshot).
+    pub(super) fn spill(
+        &mut self,
+        journal: &mut [Vec<JournalEntry>],
+        sealed_entries: usize,
+        mut snapshot_dirty: Option<&mut HashSet<Address>>,
+    ) {
+        let Some((_, sealed)) = journal.split_last_mut() else {
+            return;
+        };
+        let mut in_memory = sealed_entries - self.spilled_entries;
+        for (index, entries) in sealed.iter_mut().enumerate() {
+            if in_memory <= self.threshold {
+                break;
+            }
+            if entries.is_empty() || self.spilled.contains_key(&index) {
+                continue;
+            }
+            let written = File::create(self.path(index)).and_then(|file| {
+                let mut writer = BufWriter::new(file);
+                serde_json::to_writer(&mut writer, &*entries)?;
+                writer.flush()
+            });
+            if written.is_err() {
+                let _ = fs::remove_file(self.path(index));
+                continue;
+            }
+            in_memory -= entries.len();
+            self.spilled_entries += entries.len();
+            self.spilled.insert(index, entries.len());
+            if let Some(dirty) = snapshot_dirty.as_deref_mut() {
+                super::snapshot::record_dirty(dirty, &*entries);
+            }
+            *entries = Vec::new();
+        }
+    }
+
+    /// Reads back the spilled checkpoints from `from` onwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a file can not be read, as the journal can not be reverted without it.
+    pub(super) fn restore(&mut self, journal: &mut [Vec<JournalEntry>], from: usize) {
+        let indices = self.spilled.range(from..).map(|(index, _)| *index);
+        for index in indices.collect::<Vec<_>>() {
+            let len = self.spilled.remove(&index).unwrap();
+            let path = self.path(index);
+            let entries: Vec<JournalEntry> = File::open(&path)
+                .and_then(|file| Ok(serde_json::from_reader(BufReader::new(file))?))
+                .unwrap_or_else(|e| panic!("failed to read spilled journal {path:?}: {e}"));
+            debug_assert_eq!(entries.len(), len);
+            let _ = fs::remove_file(path);
+            self.spilled_entries -= len;
+            journal[index] = entries;
+        }
+    }
+
+    /// Removes all spilled checkpoints.
+    pub(super) fn clear(&mut self) {
+        for index in core::mem::take(&mut self.spilled).into_keys() {
+            let _ = fs::remove_file(self.path(index));
+        }
+        self.spilled_entries = 0;
+    }
+}
+
+/// Cloned spills have their own copies of the spilled files.
+///
+/// # Panics
+///
+/// Panics if a file can not be copied.
+impl Clone for JournalSpill {
+    fn clone(&self) -> Self {
+        let mut clone = Self::new(self.dir.clone(), self.threshold);
+        for index in self.spilled.keys() {
+            fs::copy(self.path(*index), clone.path(*index))
+                .unwrap_or_else(|e| panic!("failed to copy spilled journal: {e}"));
+        }
+        clone.spilled = self.spilled.clone();
+        clone.spilled_entries = self.spilled_entries;
+        clone
+    }
+}
+
+/// Spills are equal if they have the same configuration and spilled checkpoints.
+impl PartialEq for JournalSpill {
+    fn eq(&self, other: &Self) -> bool {
+        self.dir == other.dir && self.threshold == other.threshold && self.spilled == other.spilled
+    }
+}
+
+impl Eq for JournalSpill {}
+
+impl Drop for JournalSpill {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        primitives::{Address, HashSet, SpecId, U256},
+        JournalSpill, JournaledState,
+    };
+
+    #[test]
+    fn spill_and_revert() {
+        let dir = std::env::temp_dir();
+        let mut journal = JournaledState::new(SpecId::CANCUN, HashSet::default());
+        journal.spill = Some(JournalSpill::new(&dir, 2));
+        let address = Address::with_last_byte(1);
+        let tstore = |journal: &mut JournaledState, key: u64| {
+            journal.tstore(address, U256::from(key), U256::from(1))
+        };
+
+        let outer = journal.checkpoint();
+        tstore(&mut journal, 1);
+        tstore(&mut journal, 2);
+        tstore(&mut journal, 3);
+        let _inner = journal.checkpoint();
+        tstore(&mut journal, 4);
+        let inner = journal.checkpoint();
+        // The first checkpoint has more entries than the threshold.
+        assert_eq!(journal.spill.as_ref().unwrap().spilled_entries(), 3);
+        assert!(journal.journal[1].is_empty());
+        assert_eq!(journal.entry_count(), 4);
+
+        tstore(&mut journal, 5);
+        journal.checkpoint_revert(inner);
+        assert_eq!(journal.entry_count(), 4);
+        assert_eq!(journal.tload(address, U256::from(5)), U256::ZERO);
+
+        // Reverting to the outer checkpoint reads the spilled entries back.
+        journal.checkpoint_revert(outer);
+        assert_eq!(journal.spill.as_ref().unwrap().spilled_entries(), 0);
+        assert_eq!(journal.entry_count(), 0);
+        for key in 1..=4 {
+            assert_eq!(journal.tload(address, U256::from(key)), U256::ZERO);
+        }
+    }
+}
@@ -8,41 +8,90 @@ extern crate alloc as std;
 
 // Define modules.
 
+#[cfg(feature = "bench_harness")]
+mod bench_harness;
+pub mod binary_trace;
 mod builder;
+pub mod calldata;
+#[cfg(feature = "ethereum-classic")]
+pub mod classic;
 mod context;
+mod dao_fork;
 
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 
 pub mod db;
+pub mod deployer;
+pub mod differential;
+#[cfg(feature = "std")]
+pub mod event_stream;
 mod evm;
+#[cfg(feature = "examples_chain")]
+pub mod examples_chain;
+#[cfg(feature = "fee_currency")]
+pub mod fee_currency;
 mod frame;
 pub mod handler;
 mod inspector;
 mod journaled_state;
 #[cfg(feature = "optimism")]
 pub mod optimism;
+#[cfg(feature = "std")]
+mod parallel_executor;
+#[cfg(feature = "std")]
+pub mod salt_miner;
+#[cfg(feature = "std")]
+mod simulation_pool;
+mod warm_state;
+mod withdrawals;
 
 // Export items.
 
+#[cfg(feature = "bench_harness")]
+pub use bench_harness::{BenchHarness, SetupError};
 pub use builder::EvmBuilder;
 pub use context::{
     Context, ContextPrecompile, ContextPrecompiles, ContextStatefulPrecompile,
     ContextStatefulPrecompileArc, ContextStatefulPrecompileBox, ContextStatefulPrecompileMut,
     ContextWithHandlerCfg, EvmContext, InnerEvmContext,
 };
+pub use dao_fork::{
+    apply_dao_fork, DAO_FORK_BLOCK, DAO_HARDFORK_ACCOUNTS, DAO_HARDFORK_BENEFICIARY,
+};
 pub use db::{
     CacheState, DBBox, State, StateBuilder, StateDBBox, TransitionAccount, TransitionState,
 };
 pub use db::{Database, DatabaseCommit, DatabaseRef, InMemoryDB};
+pub use deployer::{DeployError, Deployer};
+pub use differential::{
+    DifferentialReport, DifferentialRunner, Divergence, StepDivergence, StepTracer,
+};
 pub use evm::{Evm, CALL_STACK_LIMIT};
-pub use frame::{CallFrame, CreateFrame, Frame, FrameData, FrameOrResult, FrameResult};
+pub use frame::{
+    CallFrame, CreateFrame, Frame, FrameData, FrameOrResult, FrameResult, PendingFrames,
+    ShallowOutcome,
+};
 pub use handler::Handler;
-pub use inspector::{inspector_handle_register, inspectors, GetInspector, Inspector};
-pub use journaled_state::{JournalCheckpoint, JournalEntry, JournaledState};
+pub use inspector::{
+    inspector_handle_register, inspectors, FrameTags, GetInspector, Inspector, RefundReason,
+};
+#[cfg(feature = "journal_spill")]
+pub use journaled_state::JournalSpill;
+pub use journaled_state::{
+    JournalCheckpoint, JournalEntry, JournalSnapshot, JournaledState, RevertedLog,
+};
 // export Optimism types, helpers, and constants
 #[cfg(feature = "optimism")]
 pub use optimism::{L1BlockInfo, BASE_FEE_RECIPIENT, L1_BLOCK_CONTRACT, L1_FEE_RECIPIENT};
+#[cfg(feature = "std")]
+pub use parallel_executor::{AccessSet, ParallelExecutor, ParallelOutcome};
+#[cfg(feature = "std")]
+pub use salt_miner::{SaltMatch, SaltMiner};
+#[cfg(feature = "std")]
+pub use simulation_pool::{Simulation, SimulationDB, SimulationPool};
+pub use warm_state::WarmState;
+pub use withdrawals::{apply_withdrawals, withdrawal_increments};
 
 // Reexport libraries
 
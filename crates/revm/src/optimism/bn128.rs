@@ -7,7 +7,16 @@ pub(crate) mod pair {
     const GRANITE_MAX_INPUT_SIZE: usize = 112687;
     pub(crate) const GRANITE: PrecompileWithAddress = PrecompileWithAddress(
         bn128::pair::ADDRESS,
-        Precompile::Standard(|input, gas_limit| run_pair(input, gas_limit)),
+        Precompile::StandardWithGas(
+            |input, gas_limit| run_pair(input, gas_limit),
+            |input| {
+                bn128::pair_required_gas(
+                    input,
+                    bn128::pair::ISTANBUL_PAIR_PER_POINT,
+                    bn128::pair::ISTANBUL_PAIR_BASE,
+                )
+            },
+        ),
     );
 
     pub(crate) fn run_pair(input: &[u8], gas_limit: u64) -> PrecompileResult {
@@ -2,7 +2,7 @@
 
 use crate::{
     handler::{
-        mainnet::{self, deduct_caller_inner},
+        mainnet::{self, deduct_caller_inner, deduct_gas_sponsor_inner},
         register::EvmHandler,
     },
     interpreter::{return_ok, return_revert, Gas, InstructionResult},
@@ -10,11 +10,10 @@ use crate::{
     primitives::{
         db::Database, spec_to_generic, Account, EVMError, Env, ExecutionResult, HaltReason,
         HashMap, InvalidTransaction, OptimismInvalidTransaction, ResultAndState, Spec, SpecId,
-        SpecId::REGOLITH, U256,
+        SpecId::REGOLITH, Transaction, U256,
     },
     Context, ContextPrecompiles, FrameResult,
 };
-use core::ops::Mul;
 use revm_precompile::{secp256r1, PrecompileSpecId};
 use std::string::ToString;
 use std::sync::Arc;
@@ -81,7 +80,7 @@ pub fn last_frame_return<SPEC: Spec, EXT, DB: Database>(
     let env = context.evm.inner.env();
     let is_deposit = env.tx.optimism.source_hash.is_some();
     let tx_system = env.tx.optimism.is_system_transaction;
-    let tx_gas_limit = env.tx.gas_limit;
+    let tx_gas_limit = env.tx.gas_limit();
     let is_regolith = SPEC::enabled(REGOLITH);
 
     let instruction_result = frame_result.interpreter_result().result;
@@ -138,7 +137,9 @@ pub fn last_frame_return<SPEC: Spec, EXT, DB: Database>(
     }
     // Prior to Regolith, deposit transactions did not receive gas refunds.
     let is_gas_refund_disabled = env.cfg.is_gas_refund_disabled() || (is_deposit && !is_regolith);
-    if !is_gas_refund_disabled {
+    if is_gas_refund_disabled {
+        gas.set_refund(0);
+    } else {
         gas.set_final_refund(SPEC::SPEC_ID.is_enabled_in(SpecId::LONDON));
     }
     Ok(())
@@ -195,7 +196,7 @@ pub fn deduct_caller<SPEC: Spec, EXT, DB: Database>(
         .evm
         .inner
         .journaled_state
-        .load_account(context.evm.inner.env.tx.caller, &mut context.evm.inner.db)?;
+        .load_account(context.evm.inner.env.tx.caller(), &mut context.evm.inner.db)?;
 
     // If the transaction is a deposit with a `mint` value, add the mint value
     // in wei to the caller's balance. This should be persisted to the database
@@ -235,6 +236,15 @@ pub fn deduct_caller<SPEC: Spec, EXT, DB: Database>(
         }
         caller_account.info.balance = caller_account.info.balance.saturating_sub(tx_l1_cost);
     }
+
+    // The L1 data fee is paid by the caller, the sponsor only pays for L2 gas.
+    if context.evm.inner.env.tx.is_sponsored() {
+        let (sponsor_account, _) = context.evm.inner.journaled_state.load_account(
+            context.evm.inner.env.tx.gas_payer(),
+            &mut context.evm.inner.db,
+        )?;
+        deduct_gas_sponsor_inner::<SPEC>(sponsor_account, &context.evm.inner.env);
+    }
     Ok(())
 }
 
@@ -269,28 +279,11 @@ pub fn reward_beneficiary<SPEC: Spec, EXT, DB: Database>(
         let l1_cost = l1_block_info.calculate_tx_l1_cost(enveloped_tx, SPEC::SPEC_ID);
 
         // Send the L1 cost of the transaction to the L1 Fee Vault.
-        let (l1_fee_vault_account, _) = context
-            .evm
-            .inner
-            .journaled_state
-            .load_account(optimism::L1_FEE_RECIPIENT, &mut context.evm.inner.db)?;
-        l1_fee_vault_account.mark_touch();
-        l1_fee_vault_account.info.balance += l1_cost;
+        mainnet::credit_fee(context, optimism::L1_FEE_RECIPIENT, l1_cost)?;
 
         // Send the base fee of the transaction to the Base Fee Vault.
-        let (base_fee_vault_account, _) = context
-            .evm
-            .inner
-            .journaled_state
-            .load_account(optimism::BASE_FEE_RECIPIENT, &mut context.evm.inner.db)?;
-        base_fee_vault_account.mark_touch();
-        base_fee_vault_account.info.balance += context
-            .evm
-            .inner
-            .env
-            .block
-            .basefee
-            .mul(U256::from(gas.spent() - gas.refunded() as u64));
+        let base_fee = context.evm.inner.env.block.basefee * U256::from(gas.used());
+        mainnet::credit_fee(context, optimism::BASE_FEE_RECIPIENT, base_fee)?;
     }
     Ok(())
 }
@@ -333,7 +326,7 @@ pub fn end<SPEC: Spec, EXT, DB: Database>(
             // also returned as a special Halt variant so that consumers can more
             // easily distinguish between a failed deposit and a failed
             // normal transaction.
-            let caller = context.evm.inner.env().tx.caller;
+            let caller = context.evm.inner.env().tx.caller();
 
             // Increment sender nonce and account balance for the mint amount. Deposits
             // always persist the mint amount, even if the transaction fails.
@@ -353,7 +346,7 @@ pub fn end<SPEC: Spec, EXT, DB: Database>(
                 acc.mark_touch();
                 acc
             };
-            let state = HashMap::from([(caller, account)]);
+            let state = HashMap::from_iter([(caller, account)]);
 
             // The gas used of a failed deposit post-regolith is the gas
             // limit of the transaction. pre-regolith, it is the gas limit
@@ -367,7 +360,7 @@ pub fn end<SPEC: Spec, EXT, DB: Database>(
                 .is_system_transaction
                 .unwrap_or(false);
             let gas_used = if SPEC::enabled(REGOLITH) || !is_system_tx {
-                context.evm.inner.env().tx.gas_limit
+                context.evm.inner.env().tx.gas_limit()
             } else {
                 0
             };
@@ -378,6 +371,18 @@ pub fn end<SPEC: Spec, EXT, DB: Database>(
                     gas_used,
                 },
                 state,
+                blob_gas_used: 0,
+                blob_fee: U256::ZERO,
+                #[cfg(feature = "keccak_preimages")]
+                keccak_preimages: Default::default(),
+                #[cfg(feature = "opcode_stats")]
+                opcode_stats: Default::default(),
+                #[cfg(feature = "gas_profile")]
+                gas_profile: Default::default(),
+                impersonation: None,
+                #[cfg(feature = "log_origins")]
+                logs_with_origins: Default::default(),
+                reverted_logs: Default::default(),
             })
         } else {
             Err(err)
@@ -408,11 +413,7 @@ mod tests {
         let mut ctx = Context::new_empty();
         ctx.evm.inner.env = Box::new(env);
         let mut first_frame = FrameResult::Call(CallOutcome::new(
-            InterpreterResult {
-                result: instruction_result,
-                output: Bytes::new(),
-                gas,
-            },
+            InterpreterResult::new(instruction_result, Bytes::new(), gas),
             0..0,
         ));
         last_frame_return::<SPEC, _, _>(&mut ctx, &mut first_frame).unwrap();
@@ -478,6 +479,42 @@ mod tests {
         assert_eq!(gas.refunded(), 0);
     }
 
+    #[test]
+    fn test_gas_accounting_never_negative() {
+        use crate::interpreter::test_utils::Rng;
+
+        let results = [
+            InstructionResult::Stop,
+            InstructionResult::Revert,
+            InstructionResult::OutOfGas,
+        ];
+        let mut rng = Rng::default();
+        for _ in 0..500 {
+            let gas_limit = rng.next(1_000_000) + 1;
+            let mut ret_gas = Gas::new(rng.next(gas_limit + 1));
+            ret_gas.record_refund(rng.next(gas_limit) as i64);
+            let result = results[rng.next(3) as usize];
+            let source_hash = (rng.next(2) == 0).then_some(B256::ZERO);
+            let is_system = rng.next(2) == 0;
+
+            for regolith in [false, true] {
+                let mut env = Env::default();
+                env.tx.gas_limit = gas_limit;
+                env.tx.optimism.source_hash = source_hash;
+                env.tx.optimism.is_system_transaction = Some(is_system);
+                let gas = if regolith {
+                    call_last_frame_return::<RegolithSpec>(env, result, ret_gas)
+                } else {
+                    call_last_frame_return::<BedrockSpec>(env, result, ret_gas)
+                };
+                assert_eq!(gas.limit(), gas_limit);
+                assert!(gas.refunded() >= 0);
+                assert!(gas.refund_gas().final_value() <= gas.spent());
+                assert_eq!(gas.used() + gas.reimbursed(), gas_limit);
+            }
+        }
+    }
+
     #[test]
     fn test_commit_mint_value() {
         let caller = Address::ZERO;
@@ -1,6 +1,6 @@
-use crate::optimism::fast_lz::flz_compress_len;
+use crate::calldata::{brotli_size_estimate, count_zero_bytes, flz_compress_len};
 use crate::primitives::{address, db::Database, Address, SpecId, U256};
-use core::ops::Mul;
+use core::{fmt, ops::Mul};
 
 const ZERO_BYTE_COST: u64 = 4;
 const NON_ZERO_BYTE_COST: u64 = 16;
@@ -26,6 +26,16 @@ const ECOTONE_L1_FEE_SCALARS_SLOT: U256 = U256::from_limbs([3u64, 0, 0, 0]);
 /// An empty 64-bit set of scalar values.
 const EMPTY_SCALARS: [u8; 8] = [0u8; 8];
 
+/// Selector of `setL1BlockValues`, the Bedrock L1 attributes transaction.
+const BEDROCK_L1_INFO_SELECTOR: [u8; 4] = [0x01, 0x5d, 0x8e, 0xb9];
+/// Length of the Bedrock L1 attributes calldata: the selector followed by eight ABI words.
+const BEDROCK_L1_INFO_LEN: usize = 4 + 8 * 32;
+
+/// Selector of `setL1BlockValuesEcotone`, the L1 attributes transaction since Ecotone.
+const ECOTONE_L1_INFO_SELECTOR: [u8; 4] = [0x44, 0x0a, 0x5e, 0x20];
+/// Length of the tightly packed Ecotone L1 attributes calldata.
+const ECOTONE_L1_INFO_LEN: usize = 164;
+
 /// The address of L1 fee recipient.
 pub const L1_FEE_RECIPIENT: Address = address!("420000000000000000000000000000000000001A");
 
@@ -62,7 +72,97 @@ pub struct L1BlockInfo {
     pub(crate) empty_scalars: bool,
 }
 
+/// Estimates the compressed size in bytes of the enveloped transaction, scaled by 1e6, as done
+/// by the [SpecId::FJORD] L1 cost function.
+///
+/// The estimate is a linear regression over the FastLZ compressed length, see
+/// [`brotli_size_estimate`].
+pub fn tx_estimated_size_fjord(input: &[u8]) -> U256 {
+    U256::from(brotli_size_estimate(flz_compress_len(input)))
+}
+
+/// Error returned by [`L1BlockInfo::try_from_attributes_tx`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum L1BlockInfoError {
+    /// The calldata does not start with a known L1 attributes selector.
+    UnknownSelector,
+    /// The calldata length does not match the format of its selector.
+    InvalidLength,
+    /// The calldata uses the Ecotone format but Ecotone is not active.
+    EcotoneNotActive,
+}
+
+impl fmt::Display for L1BlockInfoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownSelector => f.write_str("unknown L1 attributes selector"),
+            Self::InvalidLength => f.write_str("invalid L1 attributes calldata length"),
+            Self::EcotoneNotActive => f.write_str("Ecotone L1 attributes before Ecotone"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for L1BlockInfoError {}
+
 impl L1BlockInfo {
+    /// Parses the L1 block info from the calldata of the L1 attributes deposit transaction, the
+    /// first transaction of every L2 block.
+    ///
+    /// Both the Bedrock `setL1BlockValues` and the Ecotone `setL1BlockValuesEcotone` formats are
+    /// supported, Fjord uses the Ecotone format. The first Ecotone block still carries Bedrock
+    /// calldata, in which case the info is marked to use the Bedrock cost function, as
+    /// [`L1BlockInfo::try_fetch`] does.
+    pub fn try_from_attributes_tx(
+        calldata: &[u8],
+        spec_id: SpecId,
+    ) -> Result<L1BlockInfo, L1BlockInfoError> {
+        let Some((selector, data)) = calldata.split_first_chunk::<4>() else {
+            return Err(L1BlockInfoError::UnknownSelector);
+        };
+        match *selector {
+            BEDROCK_L1_INFO_SELECTOR => {
+                if calldata.len() != BEDROCK_L1_INFO_LEN {
+                    return Err(L1BlockInfoError::InvalidLength);
+                }
+                let word = |i: usize| U256::from_be_slice(&data[i * 32..(i + 1) * 32]);
+                let is_ecotone = spec_id.is_enabled_in(SpecId::ECOTONE);
+                Ok(L1BlockInfo {
+                    l1_base_fee: word(2),
+                    l1_fee_overhead: Some(word(6)),
+                    l1_base_fee_scalar: word(7),
+                    l1_blob_base_fee: is_ecotone.then_some(U256::ZERO),
+                    l1_blob_base_fee_scalar: is_ecotone.then_some(U256::ZERO),
+                    empty_scalars: is_ecotone,
+                })
+            }
+            ECOTONE_L1_INFO_SELECTOR => {
+                if calldata.len() != ECOTONE_L1_INFO_LEN {
+                    return Err(L1BlockInfoError::InvalidLength);
+                }
+                if !spec_id.is_enabled_in(SpecId::ECOTONE) {
+                    return Err(L1BlockInfoError::EcotoneNotActive);
+                }
+                // Packed layout after the selector: baseFeeScalar (u32), blobBaseFeeScalar (u32),
+                // sequenceNumber (u64), timestamp (u64), number (u64), basefee (u256),
+                // blobBaseFee (u256), hash (bytes32) and batcherHash (bytes32).
+                let l1_base_fee_scalar = U256::from_be_slice(&data[0..4]);
+                let l1_blob_base_fee_scalar = U256::from_be_slice(&data[4..8]);
+                let l1_base_fee = U256::from_be_slice(&data[32..64]);
+                let l1_blob_base_fee = U256::from_be_slice(&data[64..96]);
+                Ok(L1BlockInfo {
+                    l1_base_fee,
+                    l1_fee_overhead: None,
+                    l1_base_fee_scalar,
+                    l1_blob_base_fee: Some(l1_blob_base_fee),
+                    l1_blob_base_fee_scalar: Some(l1_blob_base_fee_scalar),
+                    empty_scalars: false,
+                })
+            }
+            _ => Err(L1BlockInfoError::UnknownSelector),
+        }
+    }
+
     /// Try to fetch the L1 block info from the database.
     pub fn try_fetch<DB: Database>(db: &mut DB, spec_id: SpecId) -> Result<L1BlockInfo, DB::Error> {
         // Ensure the L1 Block account is loaded into the cache after Ecotone. With EIP-4788, it is no longer the case
@@ -126,20 +226,16 @@ impl L1BlockInfo {
     /// account for the empty signature.
     pub fn data_gas(&self, input: &[u8], spec_id: SpecId) -> U256 {
         if spec_id.is_enabled_in(SpecId::FJORD) {
-            let estimated_size = self.tx_estimated_size_fjord(input);
+            let estimated_size = tx_estimated_size_fjord(input);
 
             return estimated_size
                 .saturating_mul(U256::from(NON_ZERO_BYTE_COST))
                 .wrapping_div(U256::from(1_000_000));
         };
 
-        let mut rollup_data_gas_cost = U256::from(input.iter().fold(0, |acc, byte| {
-            acc + if *byte == 0x00 {
-                ZERO_BYTE_COST
-            } else {
-                NON_ZERO_BYTE_COST
-            }
-        }));
+        let (zero_bytes, non_zero_bytes) = count_zero_bytes(input);
+        let mut rollup_data_gas_cost =
+            U256::from(zero_bytes * ZERO_BYTE_COST + non_zero_bytes * NON_ZERO_BYTE_COST);
 
         // Prior to regolith, an extra 68 non zero bytes were included in the rollup data costs.
         if !spec_id.is_enabled_in(SpecId::REGOLITH) {
@@ -149,18 +245,6 @@ impl L1BlockInfo {
         rollup_data_gas_cost
     }
 
-    // Calculate the estimated compressed transaction size in bytes, scaled by 1e6.
-    // This value is computed based on the following formula:
-    // max(minTransactionSize, intercept + fastlzCoef*fastlzSize)
-    fn tx_estimated_size_fjord(&self, input: &[u8]) -> U256 {
-        let fastlz_size = U256::from(flz_compress_len(input));
-
-        fastlz_size
-            .saturating_mul(U256::from(836_500))
-            .saturating_sub(U256::from(42_585_600))
-            .max(U256::from(100_000_000))
-    }
-
     /// Calculate the gas cost of a transaction based on L1 block data posted on L2, depending on the [SpecId] passed.
     pub fn calculate_tx_l1_cost(&self, input: &[u8], spec_id: SpecId) -> U256 {
         // If the input is a deposit transaction or empty, the default value is zero.
@@ -219,7 +303,7 @@ impl L1BlockInfo {
     /// `estimatedSize*(baseFeeScalar*l1BaseFee*16 + blobFeeScalar*l1BlobBaseFee)/1e12`
     fn calculate_tx_l1_cost_fjord(&self, input: &[u8]) -> U256 {
         let l1_fee_scaled = self.calculate_l1_fee_scaled_ecotone();
-        let estimated_size = self.tx_estimated_size_fjord(input);
+        let estimated_size = tx_estimated_size_fjord(input);
 
         estimated_size
             .saturating_mul(l1_fee_scaled)
@@ -246,6 +330,57 @@ mod tests {
     use super::*;
     use crate::primitives::bytes;
 
+    #[test]
+    fn test_try_from_attributes_tx() {
+        // setL1BlockValues(number, timestamp, basefee, hash, sequenceNumber, batcherHash,
+        // l1FeeOverhead, l1FeeScalar)
+        let mut bedrock = BEDROCK_L1_INFO_SELECTOR.to_vec();
+        for word in [1u64, 2, 1_000, 0, 3, 0, 188, 684_000] {
+            bedrock.extend_from_slice(&U256::from(word).to_be_bytes::<32>());
+        }
+        let info = L1BlockInfo::try_from_attributes_tx(&bedrock, SpecId::REGOLITH).unwrap();
+        assert_eq!(info.l1_base_fee, U256::from(1_000));
+        assert_eq!(info.l1_fee_overhead, Some(U256::from(188)));
+        assert_eq!(info.l1_base_fee_scalar, U256::from(684_000));
+        assert_eq!(info.l1_blob_base_fee, None);
+
+        // First Ecotone block still uses the Bedrock cost function.
+        let info = L1BlockInfo::try_from_attributes_tx(&bedrock, SpecId::ECOTONE).unwrap();
+        assert!(info.empty_scalars);
+        let input = bytes!("FACADE");
+        assert_eq!(
+            info.calculate_tx_l1_cost(&input, SpecId::ECOTONE),
+            info.calculate_tx_l1_cost(&input, SpecId::REGOLITH)
+        );
+
+        let mut ecotone = ECOTONE_L1_INFO_SELECTOR.to_vec();
+        ecotone.extend_from_slice(&1_368u32.to_be_bytes());
+        ecotone.extend_from_slice(&810_949u32.to_be_bytes());
+        ecotone.extend_from_slice(&[0; 24]);
+        ecotone.extend_from_slice(&U256::from(1_000).to_be_bytes::<32>());
+        ecotone.extend_from_slice(&U256::from(7).to_be_bytes::<32>());
+        ecotone.extend_from_slice(&[0; 64]);
+        let info = L1BlockInfo::try_from_attributes_tx(&ecotone, SpecId::FJORD).unwrap();
+        assert_eq!(info.l1_base_fee, U256::from(1_000));
+        assert_eq!(info.l1_base_fee_scalar, U256::from(1_368));
+        assert_eq!(info.l1_blob_base_fee, Some(U256::from(7)));
+        assert_eq!(info.l1_blob_base_fee_scalar, Some(U256::from(810_949)));
+        assert!(!info.empty_scalars);
+
+        assert_eq!(
+            L1BlockInfo::try_from_attributes_tx(&ecotone, SpecId::CANYON).unwrap_err(),
+            L1BlockInfoError::EcotoneNotActive
+        );
+        assert_eq!(
+            L1BlockInfo::try_from_attributes_tx(&ecotone[..100], SpecId::FJORD).unwrap_err(),
+            L1BlockInfoError::InvalidLength
+        );
+        assert_eq!(
+            L1BlockInfo::try_from_attributes_tx(&[0; 4], SpecId::FJORD).unwrap_err(),
+            L1BlockInfoError::UnknownSelector
+        );
+    }
+
     #[test]
     fn test_data_gas_non_zero_bytes() {
         let l1_block_info = L1BlockInfo {
@@ -365,6 +500,21 @@ mod tests {
         assert_eq!(gas_cost, U256::from(1048));
     }
 
+    #[test]
+    fn test_tx_estimated_size_fjord() {
+        // Below the minimum transaction size of 100 bytes.
+        assert_eq!(tx_estimated_size_fjord(&[]), U256::from(100_000_000));
+        assert_eq!(
+            tx_estimated_size_fjord(&bytes!("FACADE")),
+            U256::from(100_000_000)
+        );
+
+        // fastLzSize = 202
+        // estimatedSize = 836500*202 - 42585600 = 126387400
+        let input = bytes!("02f901550a758302df1483be21b88304743f94f80e51afb613d764fa61751affd3313c190a86bb870151bd62fd12adb8e41ef24f3f000000000000000000000000000000000000000000000000000000000000006e000000000000000000000000af88d065e77c8cc2239327c5edb3a432268e5831000000000000000000000000000000000000000000000000000000000003c1e5000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000a000000000000000000000000000000000000000000000000000000000000000148c89ed219d02f1a5be012c689b4f5b731827bebe000000000000000000000000c001a033fd89cb37c31b2cba46b6466e040c61fc9b2a3675a7f5f493ebd5ad77c497f8a07cdf65680e238392693019b4092f610222e71b7cec06449cb922b93b6a12744e");
+        assert_eq!(tx_estimated_size_fjord(&input), U256::from(126_387_400));
+    }
+
     #[test]
     fn test_calculate_tx_l1_cost_fjord() {
         // l1FeeScaled = baseFeeScalar*l1BaseFee*16 + blobFeeScalar*l1BlobBaseFee
@@ -0,0 +1,330 @@
+//! Speculative parallel execution of a batch of transactions with conflict detection.
+use crate::{
+    db::CacheDB,
+    handler::register::EvmHandler,
+    primitives::{Address, EVMError, EVMResult, EvmState, HashSet, ResultAndState, TxEnv, U256},
+    Database, DatabaseCommit, DatabaseRef, Evm, Simulation, SimulationDB, SimulationPool,
+};
+use std::{string::ToString, sync::Arc, vec::Vec};
+
+/// Accounts and storage slots accessed by a transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccessSet {
+    /// Accessed accounts.
+    pub accounts: HashSet<Address>,
+    /// Accessed storage slots.
+    pub storage: HashSet<(Address, U256)>,
+}
+
+impl AccessSet {
+    /// Returns `true` if the sets share an account or a storage slot.
+    pub fn intersects(&self, other: &Self) -> bool {
+        !self.accounts.is_disjoint(&other.accounts) || !self.storage.is_disjoint(&other.storage)
+    }
+
+    /// Adds the accesses of `other` to the set.
+    pub fn extend(&mut self, other: &Self) {
+        self.accounts.extend(other.accounts.iter().copied());
+        self.storage.extend(other.storage.iter().copied());
+    }
+
+    /// Returns the accounts and slots loaded by the execution, except the fee recipients.
+    ///
+    /// Every account and slot read by the journal is part of the state of the result.
+    pub fn reads(state: &EvmState, fee_recipients: &[Address]) -> Self {
+        let mut reads = Self::default();
+        for (address, account) in state {
+            if !fee_recipients.contains(address) {
+                reads.accounts.insert(*address);
+            }
+            reads
+                .storage
+                .extend(account.storage.keys().map(|slot| (*address, *slot)));
+        }
+        reads
+    }
+
+    /// Returns the accounts and slots changed by the execution on top of `db`.
+    ///
+    /// Balance changes of the fee recipients are not writes, they are merged as increments.
+    pub fn writes<DB: DatabaseRef>(
+        state: &EvmState,
+        db: &DB,
+        fee_recipients: &[Address],
+    ) -> Result<Self, DB::Error> {
+        let mut writes = Self::default();
+        for (address, account) in state {
+            if !account.is_touched() {
+                continue;
+            }
+            writes.storage.extend(
+                account
+                    .storage
+                    .iter()
+                    .filter(|(_, slot)| slot.is_changed())
+                    .map(|(slot, _)| (*address, *slot)),
+            );
+            let original = db.basic_ref(*address)?.unwrap_or_default();
+            let info = &account.info;
+            let changed = account.is_created()
+                || account.is_selfdestructed()
+                || info.nonce != original.nonce
+                || info.code_hash != original.code_hash
+                || (info.balance != original.balance && !fee_recipients.contains(address));
+            if changed {
+                writes.accounts.insert(*address);
+            }
+        }
+        Ok(writes)
+    }
+}
+
+/// Results of a [`ParallelExecutor`] batch.
+pub struct ParallelOutcome<DB: DatabaseRef> {
+    /// Results of the transactions, in order. Equal to the results of a sequential execution.
+    pub results: Vec<EVMResult<DB::Error>>,
+    /// Indices of the transactions re-executed because of a conflict.
+    pub reexecuted: Vec<usize>,
+    /// Changes of the batch on top of the base state.
+    pub db: SimulationDB<DB>,
+}
+
+/// Executes a batch of transactions in parallel, with the results of a sequential execution.
+///
+/// All transactions are first executed speculatively on the base state by a
+/// [`SimulationPool`]. They are then validated in order: the accounts and slots a transaction
+/// read, see [`AccessSet::reads`], are checked against the ones written by the transactions
+/// before it. Transactions without conflict are committed as is, the others are executed again
+/// on top of the committed changes.
+///
+/// Fee payments to the coinbase and the fee vaults would make every transaction conflict, so
+/// they are merged as balance increments. Transactions that read a fee recipient, e.g. with
+/// `BALANCE` or as their caller, are always executed again.
+pub struct ParallelExecutor<DB: DatabaseRef, F> {
+    pool: SimulationPool<DB>,
+    build_evm: Arc<F>,
+}
+
+impl<DB, F> ParallelExecutor<DB, F>
+where
+    DB: DatabaseRef + Send + Sync + 'static,
+    DB::Error: Send + 'static,
+    F: Fn(SimulationDB<DB>) -> Evm<'static, (), SimulationDB<DB>> + Send + Sync + 'static,
+{
+    /// Creates an executor of `threads` workers executing with the [`Evm`] built by `build_evm`.
+    ///
+    /// See [`SimulationPool::with_evm`].
+    pub fn new(base: Arc<DB>, threads: usize, build_evm: F) -> Self {
+        let build_evm = Arc::new(build_evm);
+        let pool = SimulationPool::with_evm(base, threads, {
+            let build_evm = build_evm.clone();
+            move |db| {
+                build_evm(db)
+                    .modify()
+                    .append_handler_register(reject_fee_recipient_reads)
+                    .build()
+            }
+        });
+        Self { pool, build_evm }
+    }
+
+    /// Returns the base state.
+    pub fn base(&self) -> &Arc<DB> {
+        self.pool.base()
+    }
+
+    /// Executes the transactions on top of the base state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a worker panicked while executing one of the transactions.
+    pub fn execute(&self, txs: Vec<TxEnv>) -> ParallelOutcome<DB> {
+        let speculative = self
+            .pool
+            .simulate_all(txs.iter().cloned().map(Simulation::new));
+
+        let mut evm = (self.build_evm)(CacheDB::new(self.base().clone()));
+        let fee_recipients = evm
+            .cfg()
+            .fee_vaults
+            .recipients(evm.block().coinbase)
+            .collect::<Vec<_>>();
+
+        let mut written = AccessSet::default();
+        let mut results = Vec::with_capacity(txs.len());
+        let mut reexecuted = Vec::new();
+        for (i, (tx, speculative)) in txs.into_iter().zip(speculative).enumerate() {
+            let result = match speculative {
+                Ok(result)
+                    if !AccessSet::reads(&result.state, &fee_recipients).intersects(&written) =>
+                {
+                    Ok(self.rebase_fees(result, evm.db(), &fee_recipients))
+                }
+                _ => {
+                    reexecuted.push(i);
+                    *evm.tx_mut() = tx;
+                    evm.transact()
+                }
+            };
+            let result = result.and_then(|result| {
+                let writes = AccessSet::writes(&result.state, evm.db(), &fee_recipients)
+                    .map_err(EVMError::Database)?;
+                written.extend(&writes);
+                evm.db_mut().commit(result.state.clone());
+                Ok(result)
+            });
+            results.push(result);
+        }
+
+        ParallelOutcome {
+            results,
+            reexecuted,
+            db: evm.into_context().evm.inner.db,
+        }
+    }
+
+    /// Moves the fee payments of a speculative result on top of the committed balances of the
+    /// fee recipients.
+    fn rebase_fees(
+        &self,
+        mut result: ResultAndState,
+        db: &SimulationDB<DB>,
+        fee_recipients: &[Address],
+    ) -> ResultAndState {
+        for recipient in fee_recipients {
+            let Some(account) = result.state.get_mut(recipient) else {
+                continue;
+            };
+            // Both reads are of cached or base accounts, that were read by the execution.
+            let base = self.base().basic_ref(*recipient).ok().flatten();
+            let committed = db.basic_ref(*recipient).ok().flatten();
+            let base = base.map(|info| info.balance).unwrap_or_default();
+            let committed = committed.map(|info| info.balance).unwrap_or_default();
+            account.info.balance = account
+                .info
+                .balance
+                .wrapping_sub(base)
+                .wrapping_add(committed);
+        }
+        result
+    }
+}
+
+/// Fails the speculative execution of transactions that read a fee recipient, so that they
+/// are executed again on top of the fees paid by the transactions before them.
+///
+/// The journal only loads a fee recipient before the reward if the execution accessed it.
+fn reject_fee_recipient_reads<DB: Database>(handler: &mut EvmHandler<'_, (), DB>) {
+    let prev_handle = handler.post_execution.reward_beneficiary.clone();
+    handler.post_execution.reward_beneficiary = Arc::new(move |ctx, gas| {
+        let env = &ctx.evm.env;
+        let state = &ctx.evm.journaled_state.state;
+        if env
+            .cfg
+            .fee_vaults
+            .recipients(env.block.coinbase)
+            .any(|recipient| state.contains_key(&recipient))
+        {
+            return Err(EVMError::Custom(
+                "speculative execution read a fee recipient".to_string(),
+            ));
+        }
+        prev_handle(ctx, gas)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::InMemoryDB,
+        primitives::{bytes, AccountInfo, Bytecode, TxKind},
+    };
+
+    fn transfer(caller: u8, to: u8, nonce: u64) -> TxEnv {
+        TxEnv {
+            caller: Address::with_last_byte(caller),
+            transact_to: TxKind::Call(Address::with_last_byte(to)),
+            value: U256::from(10),
+            nonce: Some(nonce),
+            gas_price: U256::from(1),
+            ..Default::default()
+        }
+    }
+
+    fn build_evm(db: SimulationDB<InMemoryDB>) -> Evm<'static, (), SimulationDB<InMemoryDB>> {
+        Evm::builder()
+            .with_db(db)
+            .modify_block_env(|block| block.coinbase = Address::with_last_byte(0xc0))
+            .build()
+    }
+
+    /// Executes `txs` in parallel and checks the outcome against a sequential execution.
+    fn assert_sequential(base: InMemoryDB, txs: Vec<TxEnv>, accounts: &[Address]) -> Vec<usize> {
+        let executor = ParallelExecutor::new(Arc::new(base.clone()), 2, build_evm);
+        let outcome = executor.execute(txs.clone());
+
+        let mut sequential = build_evm(CacheDB::new(Arc::new(base)));
+        for (tx, result) in txs.into_iter().zip(&outcome.results) {
+            *sequential.tx_mut() = tx;
+            assert_eq!(
+                sequential.transact_commit().unwrap(),
+                result.as_ref().unwrap().result
+            );
+        }
+        let sequential = sequential.into_context().evm.inner.db;
+        for address in accounts {
+            assert_eq!(
+                outcome.db.basic_ref(*address).unwrap(),
+                sequential.basic_ref(*address).unwrap(),
+            );
+            assert_eq!(
+                outcome.db.storage_ref(*address, U256::ZERO).unwrap(),
+                sequential.storage_ref(*address, U256::ZERO).unwrap(),
+            );
+        }
+        outcome.reexecuted
+    }
+
+    #[test]
+    fn conflicting_transactions_are_reexecuted() {
+        let mut base = InMemoryDB::default();
+        for caller in 1..=3 {
+            base.insert_account_info(
+                Address::with_last_byte(caller),
+                AccountInfo::from_balance(U256::from(1_000_000)),
+            );
+        }
+        let txs = vec![
+            transfer(1, 0x10, 0),
+            transfer(2, 0x20, 0),
+            // Reads the balance of the first caller, and its nonce only matches after it.
+            transfer(1, 0x30, 1),
+            transfer(3, 0x10, 0),
+        ];
+        let accounts = [1, 2, 3, 0x10, 0x20, 0x30, 0xc0].map(Address::with_last_byte);
+        assert_eq!(assert_sequential(base, txs, &accounts), [2, 3]);
+    }
+
+    #[test]
+    fn fee_recipient_reads_are_reexecuted() {
+        let mut base = InMemoryDB::default();
+        for caller in 1..=2 {
+            base.insert_account_info(
+                Address::with_last_byte(caller),
+                AccountInfo::from_balance(U256::from(1_000_000)),
+            );
+        }
+        // COINBASE BALANCE PUSH1 0 SSTORE STOP
+        let reader = Address::with_last_byte(0x40);
+        base.insert_account_info(
+            reader,
+            AccountInfo::from_bytecode(Bytecode::new_raw(bytes!("413160005500"))),
+        );
+        // The second transaction reads the fee paid by the first one.
+        let txs = vec![transfer(1, 0x10, 0), transfer(2, 0x40, 0)];
+
+        let accounts = [1, 2, 0x10, 0x40, 0xc0].map(Address::with_last_byte);
+        assert_eq!(assert_sequential(base, txs, &accounts), [1]);
+    }
+}
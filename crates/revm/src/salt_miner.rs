@@ -0,0 +1,202 @@
+//! Parallel search of `CREATE2` salts giving addresses with a wanted pattern.
+use crate::{
+    interpreter::create2_address,
+    primitives::{keccak256, Address, B256, U256},
+};
+use core::{
+    num::NonZeroUsize,
+    ops::Range,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use std::{thread, vec::Vec};
+
+/// Salt found by a [`SaltMiner`] and the address of the contract deployed with it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SaltMatch {
+    /// The salt.
+    pub salt: U256,
+    /// Address of the contract deployed with `CREATE2` and the salt.
+    pub address: Address,
+}
+
+/// Searches `CREATE2` salts in parallel for contract addresses matching a predicate.
+///
+/// Addresses are derived with [`create2_address`], the derivation the EVM uses when executing
+/// `CREATE2`, so a found salt deploys the contract at the matched address, e.g. with
+/// [`Deployer::deploy_create2`](crate::Deployer::deploy_create2).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SaltMiner {
+    /// Account executing `CREATE2`.
+    pub deployer: Address,
+    /// Hash of the init code of the contract.
+    pub init_code_hash: B256,
+    /// Number of threads searching.
+    pub threads: NonZeroUsize,
+}
+
+impl SaltMiner {
+    /// Creates a miner searching on all available cores.
+    pub fn new(deployer: Address, init_code_hash: B256) -> Self {
+        Self {
+            deployer,
+            init_code_hash,
+            threads: thread::available_parallelism().unwrap_or(NonZeroUsize::MIN),
+        }
+    }
+
+    /// Creates a miner for the init code.
+    pub fn from_init_code(deployer: Address, init_code: &[u8]) -> Self {
+        Self::new(deployer, keccak256(init_code))
+    }
+
+    /// Sets the number of threads searching.
+    pub fn with_threads(mut self, threads: NonZeroUsize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Returns the address of the contract deployed with the salt.
+    #[inline]
+    pub fn address(&self, salt: U256) -> Address {
+        create2_address(self.deployer, salt, self.init_code_hash)
+    }
+
+    /// Returns the first `limit` salts in `salts` whose address matches `predicate`, in order.
+    ///
+    /// The threads check interleaved salts and stop once no smaller match can be found.
+    pub fn find<F>(&self, salts: Range<u64>, limit: usize, predicate: F) -> Vec<SaltMatch>
+    where
+        F: Fn(&Address) -> bool + Sync,
+    {
+        if limit == 0 {
+            return Vec::new();
+        }
+        let threads = self.threads.get() as u64;
+        // Salts after this one are not needed, as `limit` smaller matches were found.
+        let bound = AtomicU64::new(u64::MAX);
+        let mut matches = thread::scope(|scope| {
+            let workers = (0..threads)
+                .map(|offset| {
+                    let (bound, predicate, salts) = (&bound, &predicate, salts.clone());
+                    scope.spawn(move || {
+                        let mut matches = Vec::new();
+                        let start = salts.start.saturating_add(offset);
+                        for salt in (start..salts.end).step_by(threads as usize) {
+                            if salt > bound.load(Ordering::Relaxed) {
+                                break;
+                            }
+                            let salt = U256::from(salt);
+                            let address = self.address(salt);
+                            if predicate(&address) {
+                                matches.push(SaltMatch { salt, address });
+                                if matches.len() == limit {
+                                    bound.fetch_min(salt.to(), Ordering::Relaxed);
+                                    break;
+                                }
+                            }
+                        }
+                        matches
+                    })
+                })
+                .collect::<Vec<_>>();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("salt miner panicked"))
+                .collect::<Vec<_>>()
+        });
+        matches.sort_unstable_by_key(|found| found.salt);
+        matches.truncate(limit);
+        matches
+    }
+
+    /// Returns the salt in `salts` whose address has the highest `score`, the smallest salt
+    /// on ties. Returns `None` if `salts` is empty.
+    pub fn best<F>(&self, salts: Range<u64>, score: F) -> Option<SaltMatch>
+    where
+        F: Fn(&Address) -> u64 + Sync,
+    {
+        let threads = self.threads.get() as u64;
+        thread::scope(|scope| {
+            let workers = (0..threads)
+                .map(|offset| {
+                    let (score, salts) = (&score, salts.clone());
+                    scope.spawn(move || {
+                        let start = salts.start.saturating_add(offset);
+                        (start..salts.end)
+                            .step_by(threads as usize)
+                            .map(|salt| {
+                                let salt = U256::from(salt);
+                                let address = self.address(salt);
+                                (score(&address), SaltMatch { salt, address })
+                            })
+                            .max_by(|(a, a_match), (b, b_match)| {
+                                a.cmp(b).then(b_match.salt.cmp(&a_match.salt))
+                            })
+                    })
+                })
+                .collect::<Vec<_>>();
+            workers
+                .into_iter()
+                .filter_map(|worker| worker.join().expect("salt miner panicked"))
+                .max_by(|(a, a_match), (b, b_match)| a.cmp(b).then(b_match.salt.cmp(&a_match.salt)))
+                .map(|(_, found)| found)
+        })
+    }
+}
+
+/// Returns a predicate matching addresses starting with `prefix`.
+pub fn starts_with(prefix: &[u8]) -> impl Fn(&Address) -> bool + Sync + '_ {
+    move |address| address.starts_with(prefix)
+}
+
+/// Returns a predicate matching addresses ending with `suffix`.
+pub fn ends_with(suffix: &[u8]) -> impl Fn(&Address) -> bool + Sync + '_ {
+    move |address| address.ends_with(suffix)
+}
+
+/// Scores addresses by their number of leading zero bytes.
+pub fn leading_zero_bytes(address: &Address) -> u64 {
+    address.iter().take_while(|byte| **byte == 0).count() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::InMemoryDB, primitives::bytes, Deployer, Evm};
+
+    #[test]
+    fn mine_and_deploy() {
+        let deployer = Deployer::new(Address::with_last_byte(0xde));
+        // Deploys `STOP`: PUSH1 0 PUSH1 0 MSTORE8 PUSH1 1 PUSH1 0 RETURN
+        let init_code = bytes!("600060005360016000f3");
+        let miner = SaltMiner::from_init_code(deployer.address, &init_code)
+            .with_threads(NonZeroUsize::new(4).unwrap());
+
+        let matches = miner.find(0..10_000, 3, starts_with(&[0]));
+        assert_eq!(matches.len(), 3);
+        assert!(matches.windows(2).all(|w| w[0].salt < w[1].salt));
+        // Same result as a sequential search.
+        let sequential = (0..10_000u64)
+            .map(|salt| miner.address(U256::from(salt)))
+            .enumerate()
+            .filter(|(_, address)| address[0] == 0)
+            .map(|(salt, _)| U256::from(salt))
+            .take(3)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            matches.iter().map(|found| found.salt).collect::<Vec<_>>(),
+            sequential
+        );
+
+        let best = miner.best(0..10_000, leading_zero_bytes).unwrap();
+        assert!(leading_zero_bytes(&best.address) >= 1);
+        assert!(miner.best(5..5, leading_zero_bytes).is_none());
+
+        let mut evm = Evm::builder().with_db(InMemoryDB::default()).build();
+        let found = matches[0];
+        let address = deployer
+            .deploy_create2(&mut evm, found.salt, init_code)
+            .unwrap();
+        assert_eq!(address, found.address);
+    }
+}
@@ -0,0 +1,237 @@
+//! Parallel simulation of independent transactions on top of a shared base state.
+use crate::{
+    db::{CacheDB, StateOverride, StateOverrideDb},
+    primitives::{EVMError, EVMResult, TxEnv},
+    DatabaseRef, Evm,
+};
+use std::{
+    sync::{mpsc, Arc, Mutex},
+    thread::{self, JoinHandle},
+    vec::Vec,
+};
+
+/// Database of a simulation, the changes of the simulation on top of its overrides of the
+/// shared base state.
+pub type SimulationDB<DB> = CacheDB<StateOverrideDb<Arc<DB>>>;
+
+/// Transaction executed by a [`SimulationPool`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Simulation {
+    /// The transaction.
+    pub tx: TxEnv,
+    /// Changes to the base state only seen by this transaction.
+    pub overrides: StateOverride,
+}
+
+impl Simulation {
+    /// Creates a simulation of the transaction on the unchanged base state.
+    pub fn new(tx: TxEnv) -> Self {
+        Self {
+            tx,
+            overrides: StateOverride::default(),
+        }
+    }
+
+    /// Executes the simulation on a fresh database over the base state.
+    fn run<DB: DatabaseRef>(
+        self,
+        evm: &mut Evm<'_, (), SimulationDB<DB>>,
+        base: &Arc<DB>,
+    ) -> EVMResult<DB::Error> {
+        let db = StateOverrideDb::new(base.clone(), self.overrides)
+            .map_err(|e| EVMError::Custom(e.to_string()))?;
+        evm.context.evm.inner.db = CacheDB::new(db);
+        *evm.tx_mut() = self.tx;
+        evm.transact()
+    }
+}
+
+/// A simulation waiting for a worker, with the channel of its result.
+struct Job<DB: DatabaseRef> {
+    simulation: Simulation,
+    result: mpsc::Sender<EVMResult<DB::Error>>,
+}
+
+/// Executes independent transactions in parallel on top of an immutable base state.
+///
+/// Each worker thread builds its own [`Evm`] once, so the handler, the instruction tables and
+/// the environment are reused between simulations. Every
+/// simulation runs on a new [`SimulationDB`] over the shared base state, its changes are
+/// returned and never committed.
+///
+/// Dropping the pool finishes the submitted simulations and joins the workers.
+pub struct SimulationPool<DB: DatabaseRef> {
+    base: Arc<DB>,
+    jobs: Option<mpsc::Sender<Job<DB>>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl<DB> SimulationPool<DB>
+where
+    DB: DatabaseRef + Send + Sync + 'static,
+    DB::Error: Send + 'static,
+{
+    /// Creates a pool of `threads` workers executing with the default mainnet [`Evm`].
+    pub fn new(base: Arc<DB>, threads: usize) -> Self {
+        Self::with_evm(base, threads, |db| Evm::builder().with_db(db).build())
+    }
+
+    /// Creates a pool of `threads` workers executing with the [`Evm`] built by `build_evm`.
+    ///
+    /// `build_evm` is called once on every worker thread, and sets the environment and the
+    /// handler registers shared by all simulations. Only the transaction and the database of the
+    /// EVM are replaced by the simulations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threads` is zero.
+    pub fn with_evm<F>(base: Arc<DB>, threads: usize, build_evm: F) -> Self
+    where
+        F: Fn(SimulationDB<DB>) -> Evm<'static, (), SimulationDB<DB>> + Send + Sync + 'static,
+    {
+        assert!(threads > 0, "simulation pool needs at least one thread");
+        let (jobs, receiver) = mpsc::channel::<Job<DB>>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let build_evm = Arc::new(build_evm);
+        let workers = (0..threads)
+            .map(|_| {
+                let receiver = receiver.clone();
+                let build_evm = build_evm.clone();
+                let base = base.clone();
+                thread::spawn(move || {
+                    let db = StateOverrideDb::new(base.clone(), StateOverride::default())
+                        .expect("no overrides to validate");
+                    let mut evm = build_evm(CacheDB::new(db));
+                    loop {
+                        // The lock is released before the job is run.
+                        let Ok(job) = receiver
+                            .lock()
+                            .map_err(drop)
+                            .and_then(|jobs| jobs.recv().map_err(drop))
+                        else {
+                            return;
+                        };
+                        let result = job.simulation.run(&mut evm, &base);
+                        // The submitter may not wait for the result anymore.
+                        let _ = job.result.send(result);
+                    }
+                })
+            })
+            .collect();
+        Self {
+            base,
+            jobs: Some(jobs),
+            workers,
+        }
+    }
+
+    /// Returns the base state.
+    pub fn base(&self) -> &Arc<DB> {
+        &self.base
+    }
+
+    /// Returns the number of worker threads.
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Submits the simulation and returns the channel its result is sent to.
+    ///
+    /// The channel is closed without a result if the worker panicked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if all the workers panicked.
+    pub fn submit(&self, simulation: Simulation) -> mpsc::Receiver<EVMResult<DB::Error>> {
+        let (result, receiver) = mpsc::channel();
+        self.jobs
+            .as_ref()
+            .expect("jobs are only closed on drop")
+            .send(Job { simulation, result })
+            .expect("simulation workers panicked");
+        receiver
+    }
+
+    /// Executes the simulations in parallel and returns their results in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a worker panicked while executing one of the simulations.
+    pub fn simulate_all(
+        &self,
+        simulations: impl IntoIterator<Item = Simulation>,
+    ) -> Vec<EVMResult<DB::Error>> {
+        let receivers = simulations
+            .into_iter()
+            .map(|simulation| self.submit(simulation))
+            .collect::<Vec<_>>();
+        receivers
+            .into_iter()
+            .map(|receiver| receiver.recv().expect("simulation worker panicked"))
+            .collect()
+    }
+}
+
+impl<DB: DatabaseRef> Drop for SimulationPool<DB> {
+    fn drop(&mut self) {
+        // Closing the channel stops the workers once the queue is empty.
+        drop(self.jobs.take());
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{AccountOverride, InMemoryDB},
+        primitives::{AccountInfo, Address, InvalidTransaction, TxKind, U256},
+    };
+
+    #[test]
+    fn independent_simulations() {
+        let to = Address::with_last_byte(0x10);
+        let mut base = InMemoryDB::default();
+        for caller in 1..=4 {
+            base.insert_account_info(
+                Address::with_last_byte(caller),
+                AccountInfo::from_balance(U256::from(100)),
+            );
+        }
+        let pool = SimulationPool::new(Arc::new(base), 2);
+        assert_eq!(pool.threads(), 2);
+
+        let transfer = |caller: u8, value: u64| TxEnv {
+            caller: Address::with_last_byte(caller),
+            transact_to: TxKind::Call(to),
+            value: U256::from(value),
+            ..Default::default()
+        };
+        let mut simulations = (1..=4)
+            .map(|caller| Simulation::new(transfer(caller, caller as u64)))
+            .collect::<Vec<_>>();
+        // Without funds in the overrides, the transfer fails.
+        simulations[3].overrides.insert(
+            Address::with_last_byte(4),
+            AccountOverride {
+                balance: Some(U256::ZERO),
+                ..Default::default()
+            },
+        );
+
+        let results = pool.simulate_all(simulations);
+        for (caller, result) in results.iter().take(3).enumerate() {
+            // Transfers are not committed, each one sees the base state.
+            let state = &result.as_ref().unwrap().state;
+            assert_eq!(state[&to].info.balance, U256::from(caller + 1));
+        }
+        assert!(matches!(
+            results[3],
+            Err(EVMError::Transaction(
+                InvalidTransaction::LackOfFundForMaxFee { .. }
+            ))
+        ));
+    }
+}
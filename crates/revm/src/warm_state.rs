@@ -0,0 +1,108 @@
+//! EIP-2929 warm set shared between transactions.
+use crate::primitives::{AccountStatus, Address, EvmState, HashMap, HashSet, U256};
+
+/// Accounts and storage slots that are considered warm at the start of a transaction.
+///
+/// Used by [`Evm::transact_with_shared_warm_state`](crate::Evm::transact_with_shared_warm_state)
+/// to measure the "optimistic" gas of a transaction, as if the state it accesses was already
+/// accessed earlier in the same transaction.
+///
+/// # Consensus
+///
+/// This deviates from consensus: per [EIP-2929] every transaction starts with only the
+/// precompiles, the caller, the destination, the access list and, from Shanghai, the coinbase
+/// warm. Pre-seeded accesses are charged the warm cost instead of the cold one, so the gas used
+/// is lower and executions that depend on the remaining gas can take a different path. Use the
+/// results only next to the ones of an ordinary transaction.
+///
+/// [EIP-2929]: https://eips.ethereum.org/EIPS/eip-2929
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WarmState {
+    /// Warm accounts and their warm storage slots.
+    pub accounts: HashMap<Address, HashSet<U256>>,
+}
+
+impl WarmState {
+    /// Creates an empty warm state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if no account is warm.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Marks the account as warm.
+    pub fn insert_account(&mut self, address: Address) {
+        self.accounts.entry(address).or_default();
+    }
+
+    /// Marks the storage slot, and with it its account, as warm.
+    pub fn insert_storage(&mut self, address: Address, key: U256) {
+        self.accounts.entry(address).or_default().insert(key);
+    }
+
+    /// Returns `true` if the account is warm.
+    pub fn contains_account(&self, address: &Address) -> bool {
+        self.accounts.contains_key(address)
+    }
+
+    /// Returns `true` if the storage slot is warm.
+    pub fn contains_storage(&self, address: &Address, key: &U256) -> bool {
+        self.accounts
+            .get(address)
+            .is_some_and(|slots| slots.contains(key))
+    }
+
+    /// Marks the accounts and storage slots that are warm at the end of a transaction as warm.
+    ///
+    /// Accesses that were reverted are cold again and not included.
+    pub fn extend_from_state(&mut self, state: &EvmState) {
+        for (address, account) in state {
+            if account.status.contains(AccountStatus::Cold) {
+                continue;
+            }
+            let slots = self.accounts.entry(*address).or_default();
+            slots.extend(
+                account
+                    .storage
+                    .iter()
+                    .filter(|(_, slot)| !slot.is_cold)
+                    .map(|(key, _)| *key),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{Account, AccountInfo, EvmStorageSlot};
+
+    #[test]
+    fn extend_from_state() {
+        let mut account = Account::from(AccountInfo::default());
+        account
+            .storage
+            .insert(U256::from(1), EvmStorageSlot::new(U256::ZERO));
+        let mut cold_slot = EvmStorageSlot::new(U256::ZERO);
+        cold_slot.mark_cold();
+        account.storage.insert(U256::from(2), cold_slot);
+        let mut cold_account = Account::from(AccountInfo::default());
+        cold_account.mark_cold();
+
+        let mut state = EvmState::default();
+        state.insert(Address::with_last_byte(1), account);
+        state.insert(Address::with_last_byte(2), cold_account);
+
+        let mut warm = WarmState::new();
+        warm.insert_account(Address::with_last_byte(3));
+        warm.extend_from_state(&state);
+        assert!(warm.contains_account(&Address::with_last_byte(1)));
+        assert!(!warm.contains_account(&Address::with_last_byte(2)));
+        assert!(warm.contains_account(&Address::with_last_byte(3)));
+        assert!(warm.contains_storage(&Address::with_last_byte(1), &U256::from(1)));
+        assert!(!warm.contains_storage(&Address::with_last_byte(1), &U256::from(2)));
+    }
+}
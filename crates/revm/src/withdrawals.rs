@@ -0,0 +1,89 @@
+//! EIP-4895 withdrawals, processed after the transactions of a block.
+use crate::{
+    db::{Database, DatabaseCommit},
+    primitives::{hash_map::Entry, Account, Address, HashMap, Withdrawal, U256},
+};
+
+/// Wei in one gwei, the unit of withdrawal amounts.
+const GWEI_TO_WEI: u128 = 1_000_000_000;
+
+/// Returns the balance increments in wei of the withdrawals, skipping zero amounts.
+///
+/// The increments can be applied with [State::increment_balances](crate::State::increment_balances).
+pub fn withdrawal_increments(
+    withdrawals: &[Withdrawal],
+) -> impl Iterator<Item = (Address, u128)> + '_ {
+    withdrawals
+        .iter()
+        .filter(|withdrawal| withdrawal.amount != 0)
+        .map(|withdrawal| (withdrawal.address, withdrawal.amount as u128 * GWEI_TO_WEI))
+}
+
+/// Credits the withdrawals of a block to the database.
+///
+/// Withdrawals are not transactions: they don't use gas, and they are applied even to accounts
+/// with code without executing it. Call it after the last transaction of a Shanghai or later
+/// block. For [State](crate::State), prefer [State::apply_withdrawals](crate::State::apply_withdrawals)
+/// which records the changes as transitions.
+pub fn apply_withdrawals<DB: Database + DatabaseCommit>(
+    db: &mut DB,
+    withdrawals: &[Withdrawal],
+) -> Result<(), DB::Error> {
+    let mut changes: HashMap<Address, Account> = HashMap::default();
+    for (address, amount) in withdrawal_increments(withdrawals) {
+        let account = match changes.entry(address) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let account = match db.basic(address)? {
+                    Some(info) => Account::from(info),
+                    None => Account::new_not_existing(),
+                };
+                entry.insert(account)
+            }
+        };
+        account.mark_touch();
+        account.info.balance = account.info.balance.saturating_add(U256::from(amount));
+    }
+    db.commit(changes);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{CacheDB, EmptyDB},
+        primitives::AccountInfo,
+        DatabaseRef,
+    };
+
+    #[test]
+    fn credits_withdrawals() {
+        let (a, b) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(a, AccountInfo::from_balance(U256::from(5)));
+
+        let withdrawal = |index, address, amount| Withdrawal {
+            index,
+            validator_index: 0,
+            address,
+            amount,
+        };
+        apply_withdrawals(
+            &mut db,
+            &[
+                withdrawal(0, a, 1),
+                withdrawal(1, b, 0),
+                withdrawal(2, a, 2),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            db.basic_ref(a).unwrap().unwrap().balance,
+            U256::from(5 + 3 * GWEI_TO_WEI)
+        );
+        // Zero withdrawals don't touch the account.
+        assert!(db.basic_ref(b).unwrap().is_none());
+    }
+}